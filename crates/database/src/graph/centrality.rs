@@ -0,0 +1,99 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Betweenness centrality of the nodes of a directed, unweighted graph given as a list of
+/// `(source, target)` edges, computed with Brandes' algorithm in O(V*E). Self-loops and
+/// duplicate edges are ignored, nodes without edges are not part of the result.
+pub fn betweenness_centrality(edges: &[(u32, u32)]) -> HashMap<u32, f64> {
+    let mut ids = Vec::new();
+    let mut indexes: HashMap<u32, usize> = HashMap::new();
+    for &(source, target) in edges {
+        for id in [source, target] {
+            indexes.entry(id).or_insert_with(|| {
+                ids.push(id);
+                ids.len() - 1
+            });
+        }
+    }
+
+    let node_count = ids.len();
+    let mut adjacency = vec![Vec::new(); node_count];
+    for &(source, target) in edges {
+        if source != target {
+            adjacency[indexes[&source]].push(indexes[&target]);
+        }
+    }
+    for targets in &mut adjacency {
+        targets.sort_unstable();
+        targets.dedup();
+    }
+
+    let mut centrality = vec![0.0; node_count];
+    for start in 0..node_count {
+        let mut stack = Vec::with_capacity(node_count);
+        let mut predecessors = vec![Vec::new(); node_count];
+        let mut path_counts = vec![0.0_f64; node_count];
+        let mut distances = vec![usize::MAX; node_count];
+        path_counts[start] = 1.0;
+        distances[start] = 0;
+
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            stack.push(node);
+            for &next in &adjacency[node] {
+                if distances[next] == usize::MAX {
+                    distances[next] = distances[node] + 1;
+                    queue.push_back(next);
+                }
+                if distances[next] == distances[node] + 1 {
+                    path_counts[next] += path_counts[node];
+                    predecessors[next].push(node);
+                }
+            }
+        }
+
+        let mut dependencies = vec![0.0; node_count];
+        while let Some(node) = stack.pop() {
+            for &predecessor in &predecessors[node] {
+                dependencies[predecessor] +=
+                    path_counts[predecessor] / path_counts[node] * (1.0 + dependencies[node]);
+            }
+            if node != start {
+                centrality[node] += dependencies[node];
+            }
+        }
+    }
+
+    ids.into_iter().zip(centrality).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betweenness_of_a_chain() {
+        let scores = betweenness_centrality(&[(1, 2), (2, 3)]);
+
+        assert_eq!(scores[&1], 0.0);
+        assert_eq!(scores[&2], 1.0);
+        assert_eq!(scores[&3], 0.0);
+    }
+
+    #[test]
+    fn test_betweenness_splits_between_shortest_paths() {
+        // 1 reaches 4 through either 2 or 3
+        let scores = betweenness_centrality(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 4)]);
+
+        assert_eq!(scores[&2], 0.5);
+        assert_eq!(scores[&3], 0.5);
+        assert_eq!(scores[&1], 0.0);
+        assert_eq!(scores[&4], 0.0);
+    }
+
+    #[test]
+    fn test_betweenness_of_a_cycle() {
+        let scores = betweenness_centrality(&[(1, 2), (2, 3), (3, 1)]);
+
+        assert!(scores.values().all(|score| *score == 1.0));
+    }
+}
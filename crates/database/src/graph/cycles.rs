@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+/// Cycles of a directed graph given as a list of `(source, target)` edges: the strongly
+/// connected components with more than one node, found with Tarjan's algorithm, and the
+/// nodes with an edge to themselves. The nodes of every cycle are sorted, and the cycles
+/// are ordered by size, largest first, then by their smallest node.
+pub fn find_cycles(edges: &[(u32, u32)]) -> Vec<Vec<u32>> {
+    let mut ids = Vec::new();
+    let mut indexes: HashMap<u32, usize> = HashMap::new();
+    for &(source, target) in edges {
+        for id in [source, target] {
+            indexes.entry(id).or_insert_with(|| {
+                ids.push(id);
+                ids.len() - 1
+            });
+        }
+    }
+
+    let node_count = ids.len();
+    let mut adjacency = vec![Vec::new(); node_count];
+    let mut self_loops = vec![false; node_count];
+    for &(source, target) in edges {
+        if source == target {
+            self_loops[indexes[&source]] = true;
+        } else {
+            adjacency[indexes[&source]].push(indexes[&target]);
+        }
+    }
+
+    // Iterative Tarjan, so deep call chains cannot overflow the stack
+    let mut order = vec![usize::MAX; node_count];
+    let mut low_links = vec![0; node_count];
+    let mut on_stack = vec![false; node_count];
+    let mut stack = Vec::new();
+    let mut next_order = 0;
+    let mut cycles = Vec::new();
+
+    for root in 0..node_count {
+        if order[root] != usize::MAX {
+            continue;
+        }
+
+        let mut work = vec![(root, 0)];
+        while let Some((node, next_edge)) = work.pop() {
+            if next_edge == 0 {
+                order[node] = next_order;
+                low_links[node] = next_order;
+                next_order += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if let Some(&target) = adjacency[node].get(next_edge) {
+                work.push((node, next_edge + 1));
+                if order[target] == usize::MAX {
+                    work.push((target, 0));
+                } else if on_stack[target] {
+                    low_links[node] = low_links[node].min(order[target]);
+                }
+                continue;
+            }
+
+            if let Some(&(parent, _)) = work.last() {
+                low_links[parent] = low_links[parent].min(low_links[node]);
+            }
+
+            if low_links[node] == order[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(ids[member]);
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 || self_loops[node] {
+                    component.sort_unstable();
+                    cycles.push(component);
+                }
+            }
+        }
+    }
+
+    cycles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycles_ignores_acyclic_graph() {
+        assert!(find_cycles(&[(1, 2), (2, 3), (1, 3)]).is_empty());
+    }
+
+    #[test]
+    fn test_find_cycles_reports_components_and_self_loops() {
+        let edges = [
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 4),
+            (4, 5),
+            (5, 4),
+            (6, 6),
+            (6, 7),
+        ];
+
+        assert_eq!(
+            find_cycles(&edges),
+            vec![vec![1, 2, 3], vec![4, 5], vec![6]]
+        );
+    }
+
+    #[test]
+    fn test_find_cycles_handles_long_chains() {
+        let mut edges = (0..100_000).map(|id| (id, id + 1)).collect::<Vec<_>>();
+        edges.push((100_000, 0));
+
+        let cycles = find_cycles(&edges);
+
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 100_001);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+/// Language independent category of a definition, derived from its language specific
+/// `definition_type` so clients can treat e.g. a Ruby `SingletonMethod` and a C#
+/// `InstanceMethod` the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionCategory {
+    Module,
+    Class,
+    Interface,
+    Enum,
+    EnumMember,
+    Function,
+    Method,
+    Field,
+    Variable,
+    Other,
+}
+
+impl DefinitionCategory {
+    /// Categorize a `definition_type` as stored in the graph (e.g. `Class`, `InstanceMethod`)
+    pub fn from_definition_type(definition_type: &str) -> Self {
+        let normalized: String = definition_type
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        match normalized.as_str() {
+            "module" | "package" | "namespace" => Self::Module,
+            "interface" | "trait" | "annotationdeclaration" | "annotationclass" => Self::Interface,
+            "enum" => Self::Enum,
+            "enumconstant" | "enumentry" | "variant" => Self::EnumMember,
+            "field" | "property" | "event" => Self::Field,
+            "localvariable" | "parameter" => Self::Variable,
+            "struct" | "union" | "record" | "object" | "companionobject" | "anonymoustype" => {
+                Self::Class
+            }
+            "constructor" | "finalizer" | "operator" | "indexer" => Self::Method,
+            "lambda" | "proc" | "delegate" => Self::Function,
+            other if other.ends_with("method") => Self::Method,
+            other if other.contains("function") => Self::Function,
+            other if other.ends_with("class") || other.contains("classexpression") => Self::Class,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Enum => "enum",
+            Self::EnumMember => "enum_member",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Field => "field",
+            Self::Variable => "variable",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for DefinitionCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definition_category_from_definition_type() {
+        let cases = [
+            ("Class", DefinitionCategory::Class),
+            ("DataClass", DefinitionCategory::Class),
+            ("NamedClassExpression", DefinitionCategory::Class),
+            ("Struct", DefinitionCategory::Class),
+            ("Interface", DefinitionCategory::Interface),
+            ("Trait", DefinitionCategory::Interface),
+            ("Module", DefinitionCategory::Module),
+            ("Package", DefinitionCategory::Module),
+            ("Enum", DefinitionCategory::Enum),
+            ("EnumConstant", DefinitionCategory::EnumMember),
+            ("Method", DefinitionCategory::Method),
+            ("SingletonMethod", DefinitionCategory::Method),
+            ("InstanceMethod", DefinitionCategory::Method),
+            ("Constructor", DefinitionCategory::Method),
+            ("Function", DefinitionCategory::Function),
+            ("NamedArrowFunction", DefinitionCategory::Function),
+            ("AssociatedFunction", DefinitionCategory::Function),
+            ("Lambda", DefinitionCategory::Function),
+            ("Field", DefinitionCategory::Field),
+            ("Property", DefinitionCategory::Field),
+            ("LocalVariable", DefinitionCategory::Variable),
+            ("Impl", DefinitionCategory::Other),
+            ("", DefinitionCategory::Other),
+        ];
+
+        for (definition_type, expected) in cases {
+            assert_eq!(
+                DefinitionCategory::from_definition_type(definition_type),
+                expected,
+                "{definition_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_definition_category_serializes_as_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&DefinitionCategory::EnumMember).unwrap(),
+            "\"enum_member\""
+        );
+        assert_eq!(DefinitionCategory::EnumMember.to_string(), "enum_member");
+    }
+}
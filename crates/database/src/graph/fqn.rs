@@ -0,0 +1,95 @@
+/// Separators used when the language of a definition is unknown
+const ALL_SEPARATORS: &[&str] = &["::", "#", "."];
+
+/// Separators between the parts of the fully qualified names stored for a language, e.g.
+/// `::` and `#` in the Ruby `Billing::Invoice#total`. `language` is the language of a
+/// `FileNode`, e.g. `Ruby` or `CSharp`. All known separators are returned for other values.
+pub fn fqn_separators(language: &str) -> &'static [&'static str] {
+    match language.to_ascii_lowercase().as_str() {
+        "ruby" => &["::", "#"],
+        "typescript" | "rust" => &["::"],
+        "python" | "java" | "kotlin" | "csharp" => &["."],
+        _ => ALL_SEPARATORS,
+    }
+}
+
+/// Split a fully qualified name into its parts, e.g. `["Billing", "Invoice", "total"]`, so
+/// clients can render it without knowing the separators of each language
+pub fn split_fqn(fqn: &str, language: &str) -> Vec<String> {
+    let separators = fqn_separators(language);
+    let mut parts = Vec::new();
+    let mut part_start = 0;
+    let mut index = 0;
+
+    while index < fqn.len() {
+        match separators
+            .iter()
+            .find(|separator| fqn[index..].starts_with(*separator))
+        {
+            Some(separator) => {
+                parts.push(&fqn[part_start..index]);
+                index += separator.len();
+                part_start = index;
+            }
+            None => index += fqn[index..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    parts.push(&fqn[part_start..]);
+
+    parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fqn_by_language() {
+        let cases = [
+            (
+                "Billing::Invoice#total",
+                "Ruby",
+                vec!["Billing", "Invoice", "total"],
+            ),
+            (
+                "app.billing.Invoice.total",
+                "Python",
+                vec!["app", "billing", "Invoice", "total"],
+            ),
+            (
+                "com.example.Invoice.total",
+                "Java",
+                vec!["com", "example", "Invoice", "total"],
+            ),
+            (
+                "Billing.Invoice.Total",
+                "CSharp",
+                vec!["Billing", "Invoice", "Total"],
+            ),
+            (
+                "NotificationService::#notify",
+                "TypeScript",
+                vec!["NotificationService", "#notify"],
+            ),
+            (
+                "billing::Invoice::total",
+                "Rust",
+                vec!["billing", "Invoice", "total"],
+            ),
+            (
+                "Invoice::total.amount",
+                "",
+                vec!["Invoice", "total", "amount"],
+            ),
+            ("", "Ruby", vec![]),
+        ];
+
+        for (fqn, language, expected) in cases {
+            assert_eq!(split_fqn(fqn, language), expected, "{fqn} ({language})");
+        }
+    }
+}
@@ -1,3 +1,11 @@
+pub mod centrality;
+pub mod cycles;
+pub mod definition_category;
+pub mod fqn;
 pub mod relationship;
 
+pub use centrality::*;
+pub use cycles::*;
+pub use definition_category::*;
+pub use fqn::*;
 pub use relationship::*;
@@ -103,6 +103,16 @@ pub enum RelationshipType {
     AmbiguouslyCalls,
     #[strum(serialize = "PROPERTY_REFERENCE")]
     PropertyReference,
+    /// Use of a definition that doesn't invoke it, e.g. a type annotation or an annotation
+    #[strum(serialize = "REFERENCES")]
+    References,
+    /// Construction of an instance, e.g. `new User()` or `User.new`, targeting the class or
+    /// its constructor
+    #[strum(serialize = "INSTANTIATES")]
+    Instantiates,
+    /// Method overriding a method of one of the super types of its class
+    #[strum(serialize = "OVERRIDES")]
+    Overrides,
     // Imported symbol relationships
     #[strum(serialize = "IMPORTED_SYMBOL_TO_IMPORTED_SYMBOL")]
     ImportedSymbolToImportedSymbol,
@@ -127,6 +137,12 @@ impl RelationshipType {
     pub fn all_types() -> Vec<RelationshipType> {
         RelationshipType::iter().collect()
     }
+
+    /// The relationship type stored as `name`, ignoring case, e.g. `instantiates`
+    pub fn parse(name: &str) -> Option<RelationshipType> {
+        RelationshipType::iter()
+            .find(|relationship_type| relationship_type.as_str().eq_ignore_ascii_case(name))
+    }
 }
 
 #[cfg(test)]
@@ -141,4 +157,17 @@ mod tests {
         let contains_dir_contains_file = mapping.contains(&RelationshipType::DirContainsFile);
         assert!(contains_dir_contains_file);
     }
+
+    #[test]
+    fn test_relationship_type_parse() {
+        assert_eq!(
+            RelationshipType::parse("INSTANTIATES"),
+            Some(RelationshipType::Instantiates)
+        );
+        assert_eq!(
+            RelationshipType::parse("instantiates"),
+            Some(RelationshipType::Instantiates)
+        );
+        assert_eq!(RelationshipType::parse("CONSTRUCTS"), None);
+    }
 }
@@ -1,10 +1,21 @@
-use crate::kuzu::types::{DatabaseError, DatabaseStats, KuzuQueryResult};
+use crate::kuzu::types::{DatabaseError, DatabaseStats, KuzuQueryResult, QueryNoop};
+use crate::querying::query_builder::QueryBuilder;
+use crate::schema::types::{NodeTable, RelationshipTable};
 
 use anyhow::Error;
-use kuzu::{Connection, Database};
+use arrow::array::{
+    Array, ArrayRef, Float64Array, Int32Array, Int64Array, StringArray, UInt8Array, UInt32Array,
+};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use kuzu::{Connection, Database, LogicalType};
 use serde_json::Map;
+use std::time::Duration;
 use tracing::{debug, error, info};
 
+/// Rows sent to Kuzu per statement when inserting in-memory batches
+const INSERT_CHUNK_ROWS: usize = 10_000;
+
 pub struct KuzuConnection<'a> {
     connection: Connection<'a>,
 }
@@ -40,6 +51,19 @@ impl<'a> KuzuConnection<'a> {
         })
     }
 
+    /// Kuzu's physical plan of the query, the query itself isn't run
+    pub fn explain(
+        &self,
+        query: &str,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<String, Error> {
+        let kuzu_params = extract_kuzu_params(&params);
+        let mut prepared = self.connection.prepare(&format!("EXPLAIN {query}"))?;
+
+        let result = self.connection.execute(&mut prepared, kuzu_params)?;
+        Ok(result.to_string())
+    }
+
     pub fn query(&self, query: &str) -> Result<kuzu::QueryResult<'_>, DatabaseError> {
         self.connection
             .query(query)
@@ -49,6 +73,17 @@ impl<'a> KuzuConnection<'a> {
             })
     }
 
+    /// Interrupts the queries of this connection running longer than `timeout`
+    pub fn set_query_timeout(&self, timeout: Duration) {
+        self.connection
+            .set_query_timeout(timeout.as_millis().try_into().unwrap_or(u64::MAX));
+    }
+
+    /// Prepare a statement, to run it several times with [`Self::execute`]
+    pub fn prepare(&self, query: &str) -> Result<kuzu::PreparedStatement, DatabaseError> {
+        self.connection.prepare(query).map_err(DatabaseError::Kuzu)
+    }
+
     /// Execute a prepared statement with parameters
     pub fn execute(
         &self,
@@ -79,34 +114,37 @@ impl<'a> KuzuConnection<'a> {
         Ok(())
     }
 
-    fn start_transaction(&self) -> Result<(), DatabaseError> {
-        let mut prepared = self
-            .connection
-            .prepare("BEGIN TRANSACTION;")
-            .expect("Failed to prepare begin transaction");
-        self.connection
-            .execute(&mut prepared, vec![])
-            .expect("Failed to begin transaction");
+    pub(crate) fn start_transaction(&self) -> Result<(), DatabaseError> {
+        let mut prepared = self.connection.prepare("BEGIN TRANSACTION;")?;
+        self.connection.execute(&mut prepared, vec![])?;
         Ok(())
     }
 
-    fn commit_transaction(&self) -> Result<(), DatabaseError> {
-        let mut prepared = self
-            .connection
-            .prepare("COMMIT;")
-            .expect("Failed to prepare commit transaction");
-        self.connection
-            .execute(&mut prepared, vec![])
-            .expect("Failed to commit transaction");
+    pub(crate) fn commit_transaction(&self) -> Result<(), DatabaseError> {
+        let mut prepared = self.connection.prepare("COMMIT;")?;
+        self.connection.execute(&mut prepared, vec![])?;
         Ok(())
     }
 
+    pub(crate) fn rollback_transaction(&self) -> Result<(), DatabaseError> {
+        let mut prepared = self.connection.prepare("ROLLBACK;")?;
+        self.connection.execute(&mut prepared, vec![])?;
+        Ok(())
+    }
+
+    /// Runs `f` in a transaction. If `f` fails, the transaction is rolled back and the
+    /// error is returned.
     pub fn transaction(
         &mut self,
         f: impl FnOnce(&mut KuzuConnection) -> Result<(), DatabaseError>,
     ) -> Result<(), DatabaseError> {
         self.start_transaction()?;
-        f(self)?;
+        if let Err(e) = f(self) {
+            if let Err(rollback_error) = self.rollback_transaction() {
+                error!("Failed to roll back transaction: {rollback_error}");
+            }
+            return Err(e);
+        }
         self.commit_transaction()?;
         Ok(())
     }
@@ -199,6 +237,63 @@ impl<'a> KuzuConnection<'a> {
         Ok(())
     }
 
+    /// Insert the rows of an in-memory batch into a node table, without going through a
+    /// Parquet file
+    pub fn insert_nodes_from_batch(
+        &self,
+        table: &NodeTable,
+        batch: &RecordBatch,
+    ) -> Result<(), DatabaseError> {
+        let (_, query) = QueryBuilder::new().create_nodes_from_rows(table);
+        info!("Inserting {} rows into {}", batch.num_rows(), table.name);
+        self.insert_rows(&query, batch)
+    }
+
+    /// Insert the rows of an in-memory batch into a relationship table with specific FROM/TO
+    /// types, without going through a Parquet file
+    pub fn insert_relationships_from_batch(
+        &self,
+        table: &RelationshipTable,
+        from_table: &NodeTable,
+        to_table: &NodeTable,
+        batch: &RecordBatch,
+    ) -> Result<(), DatabaseError> {
+        let (noop, query) =
+            QueryBuilder::new().create_relationships_from_rows(table, from_table, to_table);
+        if noop == QueryNoop::Yes {
+            return Err(DatabaseError::InitializationFailed(format!(
+                "{} and {} must have a primary key",
+                from_table.name, to_table.name
+            )));
+        }
+        info!(
+            "Inserting {} rows into {} ({} -> {})",
+            batch.num_rows(),
+            table.name,
+            from_table.name,
+            to_table.name
+        );
+        self.insert_rows(&query, batch)
+    }
+
+    // Runs an UNWIND query over the batch, in chunks to keep the parameter size bounded
+    fn insert_rows(&self, query: &str, batch: &RecordBatch) -> Result<(), DatabaseError> {
+        let mut prepared = self.connection.prepare(query)?;
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let length = INSERT_CHUNK_ROWS.min(batch.num_rows() - offset);
+            let rows = record_batch_to_kuzu_rows(&batch.slice(offset, length))?;
+            self.connection
+                .execute(&mut prepared, vec![("rows", rows)])
+                .map_err(|e| DatabaseError::QueryExecutionError {
+                    query: query.to_string(),
+                    error: e,
+                })?;
+            offset += length;
+        }
+        Ok(())
+    }
+
     pub fn table_exists(&self, table_name: &str) -> Result<bool, DatabaseError> {
         let query = "CALL SHOW_TABLES() RETURN *";
         let result = self.connection.query(query)?;
@@ -270,6 +365,95 @@ impl<'a> KuzuConnection<'a> {
     }
 }
 
+/// Converts a batch into a Kuzu list with one struct per row, keyed by column name
+fn record_batch_to_kuzu_rows(batch: &RecordBatch) -> Result<kuzu::Value, DatabaseError> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        fields.push((field.name().clone(), arrow_to_kuzu_type(field.data_type())?));
+    }
+
+    let rows = (0..batch.num_rows())
+        .map(|row| {
+            let values = batch
+                .columns()
+                .iter()
+                .zip(&fields)
+                .map(|(column, (name, logical_type))| {
+                    (name.clone(), arrow_to_kuzu_value(column, row, logical_type))
+                })
+                .collect();
+            kuzu::Value::Struct(values)
+        })
+        .collect();
+
+    Ok(kuzu::Value::List(LogicalType::Struct { fields }, rows))
+}
+
+fn arrow_to_kuzu_type(data_type: &DataType) -> Result<LogicalType, DatabaseError> {
+    match data_type {
+        DataType::Utf8 => Ok(LogicalType::String),
+        DataType::UInt32 => Ok(LogicalType::UInt32),
+        DataType::UInt8 => Ok(LogicalType::UInt8),
+        DataType::Int32 => Ok(LogicalType::Int32),
+        DataType::Int64 => Ok(LogicalType::Int64),
+        DataType::Float64 => Ok(LogicalType::Double),
+        other => Err(DatabaseError::UnsupportedColumnType(other.to_string())),
+    }
+}
+
+// The column types were checked by `arrow_to_kuzu_type`
+fn arrow_to_kuzu_value(column: &ArrayRef, row: usize, logical_type: &LogicalType) -> kuzu::Value {
+    if column.is_null(row) {
+        return kuzu::Value::Null(logical_type.clone());
+    }
+    match logical_type {
+        LogicalType::String => kuzu::Value::String(
+            column
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .value(row)
+                .to_string(),
+        ),
+        LogicalType::UInt32 => kuzu::Value::UInt32(
+            column
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap()
+                .value(row),
+        ),
+        LogicalType::UInt8 => kuzu::Value::UInt8(
+            column
+                .as_any()
+                .downcast_ref::<UInt8Array>()
+                .unwrap()
+                .value(row),
+        ),
+        LogicalType::Int32 => kuzu::Value::Int32(
+            column
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap()
+                .value(row),
+        ),
+        LogicalType::Double => kuzu::Value::Double(
+            column
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap()
+                .value(row),
+        ),
+        _ => kuzu::Value::Int64(
+            column
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap()
+                .value(row),
+        ),
+    }
+}
+
 fn extract_kuzu_params(
     json_params: &serde_json::Map<String, serde_json::Value>,
 ) -> Vec<(&str, kuzu::Value)> {
@@ -564,4 +748,39 @@ mod tests {
             std::fs::remove_dir_all(temp_dir).unwrap();
         }
     }
+
+    mod transaction_test {
+        use crate::kuzu::types::DatabaseError;
+        use crate::kuzu::{connection::KuzuConnection, database::KuzuDatabase};
+
+        #[test]
+        fn test_failed_transaction_is_rolled_back() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let binding = temp_dir.path().join("test.db");
+            let database = KuzuDatabase::new()
+                .force_new_database(binding.to_str().unwrap(), None)
+                .unwrap();
+            let mut connection = KuzuConnection::new(&database).unwrap();
+            connection
+                .execute_ddl("CREATE NODE TABLE User (name STRING, PRIMARY KEY (name))")
+                .unwrap();
+
+            let result = connection.transaction(|conn| {
+                conn.execute_ddl("CREATE (u:User {name: 'Alice'});")?;
+                Err(DatabaseError::ConnectionClosed)
+            });
+            assert!(result.is_err());
+
+            connection
+                .transaction(|conn| conn.execute_ddl("CREATE (u:User {name: 'Jane'});"))
+                .unwrap();
+
+            let names: Vec<_> = connection
+                .query("MATCH (u:User) RETURN u.name")
+                .unwrap()
+                .map(|row| row[0].to_string())
+                .collect();
+            assert_eq!(names, vec!["Jane".to_string()]);
+        }
+    }
 }
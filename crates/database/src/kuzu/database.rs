@@ -1,11 +1,43 @@
 use crate::kuzu::config::DatabaseConfig;
+use crate::kuzu::connection::KuzuConnection;
+use crate::kuzu::types::DatabaseError;
 use kuzu::{Database, SystemConfig};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::path::Path;
+use std::sync::{Arc, Mutex, Weak};
 use tracing::{error, info};
 
+/// Outcome of [`KuzuDatabase::check_integrity`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseIntegrity {
+    /// Every table of the database could be read
+    Healthy { table_count: usize },
+    /// Nothing was indexed at the path
+    Missing,
+    /// Another process, e.g. `gkg server`, holds the database, so it couldn't be checked
+    Locked { reason: String },
+    /// Written by another storage version of Kuzu
+    Incompatible { reason: String },
+    /// The database can't be opened or one of its tables can't be read
+    Corrupt { reason: String },
+}
+
+impl DatabaseIntegrity {
+    /// Whether the database has to be rebuilt by reindexing the project
+    pub fn needs_reindex(&self) -> bool {
+        matches!(self, Self::Incompatible { .. } | Self::Corrupt { .. })
+    }
+}
+
+/// Told when a database was written or dropped from the [`KuzuDatabase`] cache, e.g. to drop
+/// what was read from it
+pub trait DatabaseChangeListener: Send + Sync {
+    fn database_changed(&self, database_path: &Path);
+}
+
 pub struct KuzuDatabase {
     databases: Mutex<HashMap<String, Arc<Database>>>,
+    change_listeners: Mutex<Vec<Weak<dyn DatabaseChangeListener>>>,
 }
 
 impl Default for KuzuDatabase {
@@ -18,6 +50,26 @@ impl KuzuDatabase {
     pub fn new() -> Self {
         Self {
             databases: Mutex::new(HashMap::new()),
+            change_listeners: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Calls `listener` whenever a database changes, until the listener is dropped
+    pub fn add_change_listener(&self, listener: Weak<dyn DatabaseChangeListener>) {
+        self.change_listeners.lock().unwrap().push(listener);
+    }
+
+    /// Tells the listeners that the database at `database_path` changed. Writers call it once
+    /// they're done, and it's called when a database is dropped from the cache. The listeners
+    /// run before it returns.
+    pub fn notify_changed(&self, database_path: &str) {
+        let listeners: Vec<Arc<dyn DatabaseChangeListener>> = {
+            let mut listeners = self.change_listeners.lock().unwrap();
+            listeners.retain(|listener| listener.strong_count() > 0);
+            listeners.iter().filter_map(Weak::upgrade).collect()
+        };
+        for listener in listeners {
+            listener.database_changed(Path::new(database_path));
         }
     }
 
@@ -27,8 +79,8 @@ impl KuzuDatabase {
     }
 
     pub fn drop_database(&self, database_path: &str) {
-        let mut databases_guard = self.databases.lock().unwrap();
-        databases_guard.remove(database_path);
+        self.databases.lock().unwrap().remove(database_path);
+        self.notify_changed(database_path);
     }
 
     pub fn get_or_create_database(
@@ -36,14 +88,49 @@ impl KuzuDatabase {
         database_path: &str,
         config: Option<DatabaseConfig>,
     ) -> Option<Arc<Database>> {
+        match self.try_get_or_create_database(database_path, config) {
+            Ok(database) => Some(database),
+            Err(e) => {
+                error!(
+                    "KuzuDatabase::get_or_create_database - Failed to create database error: {e}"
+                );
+                None
+            }
+        }
+    }
+
+    /// Opens the database of an indexed project. Unlike [`Self::try_get_or_create_database`],
+    /// nothing is created when there is no database at `database_path`: that fails with
+    /// [`DatabaseError::NotIndexed`], so callers can ask for the project to be indexed.
+    pub fn get_indexed_database(
+        &self,
+        database_path: &str,
+        config: Option<DatabaseConfig>,
+    ) -> Result<Arc<Database>, DatabaseError> {
+        if !std::path::Path::new(database_path).exists() {
+            return Err(DatabaseError::NotIndexed {
+                database_path: database_path.to_string(),
+            });
+        }
+        self.try_get_or_create_database(database_path, config)
+    }
+
+    /// Like [`Self::get_or_create_database`], but tells why the database couldn't be opened.
+    /// A database written by another storage version of Kuzu, e.g. before a Kuzu upgrade,
+    /// fails with [`DatabaseError::IncompatibleStorageVersion`] so callers can ask for a
+    /// reindex.
+    pub fn try_get_or_create_database(
+        &self,
+        database_path: &str,
+        config: Option<DatabaseConfig>,
+    ) -> Result<Arc<Database>, DatabaseError> {
         let mut databases_guard = self.databases.lock().unwrap();
 
-        if databases_guard.contains_key(database_path) {
+        if let Some(database) = databases_guard.get(database_path) {
             info!(
-                "KuzuDatabase::get_or_create_database -Found existing arc(database): {:?}",
-                databases_guard.get(database_path).unwrap()
+                "KuzuDatabase::get_or_create_database -Found existing arc(database): {database:?}"
             );
-            return Some(databases_guard.get(database_path).unwrap().clone());
+            return Ok(Arc::clone(database));
         }
 
         let already_exists = std::path::Path::new(database_path).exists();
@@ -56,21 +143,13 @@ impl KuzuDatabase {
             Database::new(database_path, system_config)
         } else {
             Database::new(database_path, SystemConfig::default())
-        };
-
-        if database.is_err() {
-            error!(
-                "KuzuDatabase::get_or_create_database - Failed to create database error: {:?}",
-                database.err()
-            );
-            return None;
-        } else {
-            info!("KuzuDatabase::get_or_create_database - Database created at: {database_path}");
         }
+        .map_err(|e| open_error(database_path, e))?;
+        info!("KuzuDatabase::get_or_create_database - Database created at: {database_path}");
 
-        let database_arc = Arc::new(database.unwrap());
+        let database_arc = Arc::new(database);
         databases_guard.insert(database_path.to_string(), database_arc.clone());
-        Some(database_arc)
+        Ok(database_arc)
     }
 
     pub fn force_new_database(
@@ -114,4 +193,354 @@ impl KuzuDatabase {
         }
         self.get_or_create_database(database_path, config)
     }
+
+    /// Replaces the database at `database_path` with the one built at `staging_path`. The
+    /// staging database must no longer be in use outside of this cache. The change listeners
+    /// are told before the files are replaced, so pooled connections to either database are
+    /// closed, or closed as soon as they're returned when they're in use.
+    pub fn replace_database(&self, staging_path: &str, database_path: &str) -> std::io::Result<()> {
+        self.drop_database(staging_path);
+        self.drop_database(database_path);
+
+        remove_database_files(database_path)?;
+        std::fs::rename(staging_path, database_path)?;
+        let staging_wal_path = wal_path(staging_path);
+        if std::path::Path::new(&staging_wal_path).exists() {
+            std::fs::rename(staging_wal_path, wal_path(database_path))?;
+        }
+
+        info!("KuzuDatabase::replace_database - Replaced {database_path} with {staging_path}");
+        Ok(())
+    }
+
+    /// Registers a new in-memory database under `database_path`, replacing any cached one.
+    /// Nothing is written to `database_path`; the database lives as long as it's cached.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn create_in_memory_database(&self, database_path: &str) -> Option<Arc<Database>> {
+        let database = match Database::new(":memory:", SystemConfig::default()) {
+            Ok(database) => Arc::new(database),
+            Err(e) => {
+                error!(
+                    "KuzuDatabase::create_in_memory_database - Failed to create database error: {e:?}"
+                );
+                return None;
+            }
+        };
+        self.databases
+            .lock()
+            .unwrap()
+            .insert(database_path.to_string(), Arc::clone(&database));
+        Some(database)
+    }
+
+    /// Checks that the database at `database_path` can be read: opens it read-only, lists its
+    /// tables and reads a row of each. The database must not be cached or open in another
+    /// process.
+    pub fn check_integrity(database_path: &str) -> DatabaseIntegrity {
+        if !std::path::Path::new(database_path).exists() {
+            return DatabaseIntegrity::Missing;
+        }
+
+        let config = DatabaseConfig::new(database_path).read_only();
+        let database = match Database::new(database_path, config.fmt_kuzu_database_config()) {
+            Ok(database) => database,
+            Err(e) => {
+                return match open_error(database_path, e) {
+                    DatabaseError::IncompatibleStorageVersion { reason, .. } => {
+                        DatabaseIntegrity::Incompatible { reason }
+                    }
+                    e if e.to_string().to_lowercase().contains("lock") => {
+                        DatabaseIntegrity::Locked {
+                            reason: e.to_string(),
+                        }
+                    }
+                    e => DatabaseIntegrity::Corrupt {
+                        reason: e.to_string(),
+                    },
+                };
+            }
+        };
+
+        match KuzuConnection::new(&database)
+            .map_err(|e| e.to_string())
+            .and_then(|connection| sample_tables(&connection))
+        {
+            Ok(table_count) => DatabaseIntegrity::Healthy { table_count },
+            Err(reason) => DatabaseIntegrity::Corrupt { reason },
+        }
+    }
+
+    /// Rebuilds the database to reclaim the space left behind by deleted nodes and
+    /// relationships, since Kuzu has no vacuum. The graph is exported, imported into a new
+    /// database and the new database replaces the old one. The database must not be in use
+    /// outside of this cache.
+    pub fn compact(&self, database_path: &str) -> Result<(), DatabaseError> {
+        let export_path = format!("{database_path}.export");
+        let staging_path = format!("{database_path}.compact");
+        remove_database_files(&export_path)?;
+
+        {
+            let database = self.try_get_or_create_database(database_path, None)?;
+            let connection = KuzuConnection::new(&database)
+                .map_err(|e| DatabaseError::InitializationFailed(e.to_string()))?;
+            connection.execute_ddl(&format!("EXPORT DATABASE '{}'", escape_path(&export_path)))?;
+        }
+        self.drop_database(database_path);
+
+        let imported = self.import_into_staging(&export_path, &staging_path);
+        remove_database_files(&export_path)?;
+        if let Err(e) = imported {
+            self.remove_database(&staging_path)?;
+            return Err(e);
+        }
+
+        self.replace_database(&staging_path, database_path)?;
+        info!("KuzuDatabase::compact - Compacted {database_path}");
+        Ok(())
+    }
+
+    fn import_into_staging(
+        &self,
+        export_path: &str,
+        staging_path: &str,
+    ) -> Result<(), DatabaseError> {
+        let staging = self.force_new_database(staging_path, None).ok_or_else(|| {
+            DatabaseError::InitializationFailed(format!("Failed to create database {staging_path}"))
+        })?;
+        let connection = KuzuConnection::new(&staging)
+            .map_err(|e| DatabaseError::InitializationFailed(e.to_string()))?;
+        connection.execute_ddl(&format!("IMPORT DATABASE '{}'", escape_path(export_path)))
+    }
+
+    /// Drops the database from the cache and renames its files so the project can be
+    /// reindexed, keeping them for inspection. Returns the new path of the database.
+    pub fn move_aside(&self, database_path: &str) -> std::io::Result<String> {
+        self.drop_database(database_path);
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let moved_path = format!("{database_path}.corrupt-{timestamp}");
+        std::fs::rename(database_path, &moved_path)?;
+        if std::path::Path::new(&wal_path(database_path)).exists() {
+            std::fs::rename(wal_path(database_path), wal_path(&moved_path))?;
+        }
+
+        info!("KuzuDatabase::move_aside - Moved {database_path} to {moved_path}");
+        Ok(moved_path)
+    }
+
+    /// Drops the database from the cache and deletes its files.
+    pub fn remove_database(&self, database_path: &str) -> std::io::Result<()> {
+        self.drop_database(database_path);
+        remove_database_files(database_path)
+    }
+}
+
+/// Classifies an error opening a database. Kuzu refuses to read files written with another
+/// storage version and only says so in the message of the error.
+pub(crate) fn open_error(database_path: &str, error: kuzu::Error) -> DatabaseError {
+    let reason = error.to_string();
+    let lowercase_reason = reason.to_lowercase();
+    if lowercase_reason.contains("different version")
+        || lowercase_reason.contains("storage version")
+    {
+        return DatabaseError::IncompatibleStorageVersion {
+            database_path: database_path.to_string(),
+            reason,
+        };
+    }
+    DatabaseError::Kuzu(error)
+}
+
+/// Reads a row of every table, returns how many tables there are
+fn sample_tables(connection: &KuzuConnection) -> Result<usize, String> {
+    let tables: Vec<(String, String)> = connection
+        .query("CALL SHOW_TABLES() RETURN name, type")
+        .map_err(|e| format!("Failed to list the tables: {e}"))?
+        .filter_map(|row| match (row.first(), row.get(1)) {
+            (Some(kuzu::Value::String(name)), Some(kuzu::Value::String(table_type))) => {
+                Some((name.clone(), table_type.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    if tables.is_empty() {
+        return Err("The database has no tables".to_string());
+    }
+
+    for (name, table_type) in &tables {
+        let query = if table_type.eq_ignore_ascii_case("REL") {
+            format!("MATCH ()-[r:{name}]->() RETURN r LIMIT 1")
+        } else {
+            format!("MATCH (n:{name}) RETURN n LIMIT 1")
+        };
+        connection
+            .query(&query)
+            .map_err(|e| format!("Failed to read table {name}: {e}"))?
+            .for_each(drop);
+    }
+    Ok(tables.len())
+}
+
+/// Escapes a path for a single-quoted string literal of a query
+fn escape_path(path: &str) -> String {
+    path.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn wal_path(database_path: &str) -> String {
+    format!("{database_path}.wal")
+}
+
+fn remove_database_files(database_path: &str) -> std::io::Result<()> {
+    for path in [database_path.to_string(), wal_path(database_path)] {
+        let path = std::path::Path::new(&path);
+        if !path.exists() {
+            continue;
+        }
+        if path.is_dir() {
+            std::fs::remove_dir_all(path)?;
+        } else {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_of_another_storage_version_is_incompatible() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        // A database header with the magic bytes of Kuzu and a storage version that no
+        // release uses
+        let mut header = b"KUZU".to_vec();
+        header.extend_from_slice(&1u64.to_le_bytes());
+        header.resize(4096, 0);
+        std::fs::write(&database_path, header).unwrap();
+
+        let database = KuzuDatabase::new();
+        let error = database
+            .try_get_or_create_database(&database_path.to_string_lossy(), None)
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error,
+            DatabaseError::IncompatibleStorageVersion { .. }
+        ));
+        assert!(error.to_string().contains("gkg index"));
+        assert!(
+            database
+                .get_or_create_database(&database_path.to_string_lossy(), None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_indexed_database_is_not_created() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database_path = database_path.to_string_lossy();
+        let database = KuzuDatabase::new();
+
+        let error = database
+            .get_indexed_database(&database_path, None)
+            .err()
+            .unwrap();
+        assert!(matches!(error, DatabaseError::NotIndexed { .. }));
+        assert!(!std::path::Path::new(database_path.as_ref()).exists());
+
+        database
+            .get_or_create_database(&database_path, None)
+            .unwrap();
+        assert!(database.get_indexed_database(&database_path, None).is_ok());
+    }
+
+    #[test]
+    fn test_compact_keeps_the_graph() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database_path = database_path.to_string_lossy();
+        let database = KuzuDatabase::new();
+        {
+            let writable = database
+                .get_or_create_database(&database_path, None)
+                .unwrap();
+            let connection = KuzuConnection::new(&writable).unwrap();
+            for query in [
+                "CREATE NODE TABLE Person(id INT64, name STRING, PRIMARY KEY(id))",
+                "CREATE REL TABLE Knows(FROM Person TO Person)",
+                "UNWIND range(1, 1000) AS i CREATE (:Person {id: i, name: concat('person ', CAST(i AS STRING))})",
+                "MATCH (a:Person {id: 1}), (b:Person {id: 2}) CREATE (a)-[:Knows]->(b)",
+                "MATCH (p:Person) WHERE p.id > 2 DELETE p",
+            ] {
+                connection.execute_ddl(query).unwrap();
+            }
+        }
+
+        database.compact(&database_path).unwrap();
+
+        let compacted = database
+            .get_or_create_database(&database_path, None)
+            .unwrap();
+        let connection = KuzuConnection::new(&compacted).unwrap();
+        let mut result = connection
+            .query("MATCH (a:Person)-[:Knows]->(b:Person) RETURN a.name, b.name")
+            .unwrap();
+        let row = result.next().unwrap();
+        assert_eq!(row[0], kuzu::Value::String("person 1".to_string()));
+        assert_eq!(row[1], kuzu::Value::String("person 2".to_string()));
+        let mut result = connection
+            .query("MATCH (p:Person) RETURN count(p)")
+            .unwrap();
+        assert_eq!(result.next().unwrap()[0], kuzu::Value::Int64(2));
+        assert!(!std::path::Path::new(&format!("{database_path}.export")).exists());
+        assert!(!std::path::Path::new(&format!("{database_path}.compact")).exists());
+    }
+
+    #[test]
+    fn test_check_integrity_and_move_aside() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database_path = database_path.to_string_lossy();
+        assert_eq!(
+            KuzuDatabase::check_integrity(&database_path),
+            DatabaseIntegrity::Missing
+        );
+
+        {
+            let database = KuzuDatabase::new();
+            let writable = database
+                .get_or_create_database(&database_path, None)
+                .unwrap();
+            let connection = KuzuConnection::new(&writable).unwrap();
+            for query in [
+                "CREATE NODE TABLE Person(name STRING, PRIMARY KEY(name))",
+                "CREATE REL TABLE Knows(FROM Person TO Person)",
+                "CREATE (:Person {name: 'Ada'})",
+            ] {
+                connection.execute_ddl(query).unwrap();
+            }
+        }
+        assert_eq!(
+            KuzuDatabase::check_integrity(&database_path),
+            DatabaseIntegrity::Healthy { table_count: 2 }
+        );
+
+        let corrupt_path = temp_dir.path().join("corrupt.kz");
+        std::fs::write(&corrupt_path, vec![0xAB; 8192]).unwrap();
+        let corrupt_path = corrupt_path.to_string_lossy();
+        let integrity = KuzuDatabase::check_integrity(&corrupt_path);
+        assert!(integrity.needs_reindex(), "{integrity:?}");
+
+        let moved_path = KuzuDatabase::new().move_aside(&corrupt_path).unwrap();
+        assert!(!std::path::Path::new(corrupt_path.as_ref()).exists());
+        assert!(std::path::Path::new(&moved_path).exists());
+        assert!(moved_path.contains(".corrupt-"));
+    }
 }
@@ -1,11 +1,19 @@
-use crate::graph::RelationshipType;
-use crate::kuzu::types::{FromKuzuNode, KuzuNodeType, QueryNoop, QuoteEscape};
-use crate::kuzu::types::{NodeCounts, RelationshipCounts};
+use crate::graph::{RelationshipType, betweenness_centrality, find_cycles};
+use crate::kuzu::types::{
+    DefinitionNodeFromKuzu, FromKuzuNode, KuzuNodeType, QueryNoop, QuoteEscape,
+};
+use crate::kuzu::types::{DeletedSubgraphCounts, NodeCounts, RelationshipCounts};
 use crate::kuzu::{connection::KuzuConnection, types::DatabaseError};
 use crate::querying::query_builder::QueryBuilder;
 use anyhow::Error;
-use kuzu::Database;
+use kuzu::{Database, LogicalType};
 use std::collections::HashMap;
+use tracing::error;
+
+/// Scores written to Kuzu per statement by [`NodeDatabaseService::compute_call_centrality`]
+const CENTRALITY_CHUNK_ROWS: usize = 10_000;
+/// Deepest override chain followed by [`NodeDatabaseService::find_implementations`]
+const MAX_OVERRIDE_DEPTH: usize = 30;
 
 pub struct NodeDatabaseService<'a> {
     database: &'a Database,
@@ -37,13 +45,26 @@ impl<'a> NodeDatabaseService<'a> {
         &mut self,
         f: impl FnOnce(&mut NodeDatabaseService) -> Result<(), DatabaseError>,
     ) -> Result<(), DatabaseError> {
-        if self.transaction_conn.is_none() {
-            return Err(DatabaseError::Kuzu(kuzu::Error::FailedQuery(
-                "No transaction connection available".to_string(),
-            )));
+        match self.transaction_conn {
+            Some(ref conn) => conn.start_transaction()?,
+            None => {
+                return Err(DatabaseError::Kuzu(kuzu::Error::FailedQuery(
+                    "No transaction connection available".to_string(),
+                )));
+            }
+        }
+
+        let result = f(self);
+        let conn = self.transaction_conn.as_ref().unwrap();
+        match result {
+            Ok(()) => conn.commit_transaction(),
+            Err(e) => {
+                if let Err(rollback_error) = conn.rollback_transaction() {
+                    error!("Failed to roll back transaction: {rollback_error}");
+                }
+                Err(e)
+            }
         }
-        f(self)?;
-        Ok(())
     }
 
     // HELPERS
@@ -114,6 +135,114 @@ impl<'a> NodeDatabaseService<'a> {
         }
     }
 
+    /// Delete nodes from a table whose column starts with any of the prefixes
+    pub fn delete_by_prefix(
+        &self,
+        node_type: KuzuNodeType,
+        column: &str,
+        prefixes: &[String],
+    ) -> Result<(), DatabaseError> {
+        match self
+            .query_builder
+            .delete_by_prefix(node_type, column, prefixes)
+        {
+            (QueryNoop::No, query) => {
+                self.query_builder.log_query(&query);
+                match self.transaction_conn {
+                    Some(ref conn) => conn.execute_ddl(&query)?,
+                    None => self.get_connection().execute_ddl(&query)?,
+                }
+                Ok(())
+            }
+            (QueryNoop::Yes, _) => Ok(()),
+        }
+    }
+
+    /// Remove everything that originated in a file: its `FileNode`, the definitions and
+    /// imported symbols located in it, and every relationship touching them. Definitions
+    /// that other files also define are kept. Runs in a single transaction.
+    pub fn delete_file_subgraph(
+        &self,
+        file_path: &str,
+    ) -> Result<DeletedSubgraphCounts, DatabaseError> {
+        let mut counts = DeletedSubgraphCounts::default();
+        let mut connection = self.get_connection();
+        let file_paths = [file_path.to_string()];
+
+        connection.transaction(|conn| {
+            let node_ids = vec![
+                (
+                    KuzuNodeType::FileNode,
+                    self.query_ids(
+                        conn,
+                        self.query_builder
+                            .get_ids_by(KuzuNodeType::FileNode, "path", &file_paths),
+                    )?,
+                ),
+                (
+                    KuzuNodeType::DefinitionNode,
+                    self.query_ids(
+                        conn,
+                        self.query_builder.get_file_owned_definition_ids(file_path),
+                    )?,
+                ),
+                (
+                    KuzuNodeType::ImportedSymbolNode,
+                    self.query_ids(
+                        conn,
+                        self.query_builder.get_ids_by(
+                            KuzuNodeType::ImportedSymbolNode,
+                            "file_path",
+                            &file_paths,
+                        ),
+                    )?,
+                ),
+            ];
+
+            for (_, query) in self.query_builder.count_incident_relationships(&node_ids) {
+                self.query_builder.log_query(&query);
+                let result = conn.query(&query)?;
+                counts.relationship_count += self.get_scalar_query_result(result).unwrap_or(0);
+            }
+
+            for (node_type, ids) in node_ids {
+                let count = ids.len() as u32;
+                match node_type {
+                    KuzuNodeType::FileNode => counts.file_count = count,
+                    KuzuNodeType::DefinitionNode => counts.definition_count = count,
+                    KuzuNodeType::ImportedSymbolNode => counts.imported_symbol_count = count,
+                    KuzuNodeType::DirectoryNode => {}
+                }
+                if let (QueryNoop::No, query) = self.query_builder.delete_by(node_type, "id", &ids)
+                {
+                    self.query_builder.log_query(&query);
+                    conn.execute_ddl(&query)?;
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(counts)
+    }
+
+    fn query_ids(
+        &self,
+        conn: &KuzuConnection,
+        (noop, query): (QueryNoop, String),
+    ) -> Result<Vec<u32>, DatabaseError> {
+        if let QueryNoop::Yes = noop {
+            return Ok(Vec::new());
+        }
+        self.query_builder.log_query(&query);
+        let result = conn.query(&query)?;
+        Ok(result
+            .filter_map(|row| match row.first() {
+                Some(kuzu::Value::UInt32(id)) => Some(*id),
+                _ => None,
+            })
+            .collect())
+    }
+
     pub fn get_by<T: std::fmt::Display + QuoteEscape, R: FromKuzuNode>(
         &self,
         node_type: KuzuNodeType,
@@ -345,12 +474,22 @@ impl<'a> NodeDatabaseService<'a> {
 
     /// Find all methods that call a specific target method
     pub fn find_calls_to_method(&self, target_fqn: &str) -> Result<Vec<String>, DatabaseError> {
+        self.find_relationships_to_definition(target_fqn, RelationshipType::Calls)
+    }
+
+    /// Find all definitions with a `relationship_type` relationship to a specific target
+    /// definition, e.g. the ones referencing a type with [`RelationshipType::References`]
+    pub fn find_relationships_to_definition(
+        &self,
+        target_fqn: &str,
+        relationship_type: RelationshipType,
+    ) -> Result<Vec<String>, DatabaseError> {
         let query = format!(
             "MATCH (source:DefinitionNode)-[r:DEFINITION_RELATIONSHIPS]->(target:DefinitionNode) 
              WHERE target.fqn = '{}' AND r.type = '{}' 
              RETURN source.fqn",
             target_fqn,
-            RelationshipType::Calls.as_str()
+            relationship_type.as_str()
         );
 
         let conn = self.get_connection();
@@ -370,6 +509,21 @@ impl<'a> NodeDatabaseService<'a> {
         &self,
         import_path: &str,
         import_name: &str,
+    ) -> Result<Vec<String>, DatabaseError> {
+        self.find_relationships_to_imported_symbol(
+            import_path,
+            import_name,
+            RelationshipType::Calls,
+        )
+    }
+
+    /// Find all definitions with a `relationship_type` relationship to a specific imported
+    /// symbol
+    pub fn find_relationships_to_imported_symbol(
+        &self,
+        import_path: &str,
+        import_name: &str,
+        relationship_type: RelationshipType,
     ) -> Result<Vec<String>, DatabaseError> {
         let query = format!(
             "MATCH (source:DefinitionNode)-[r:DEFINITION_RELATIONSHIPS]->(target:ImportedSymbolNode) 
@@ -377,7 +531,7 @@ impl<'a> NodeDatabaseService<'a> {
              RETURN source.fqn",
             import_path,
             import_name,
-            RelationshipType::Calls.as_str()
+            relationship_type.as_str()
         );
 
         let conn = self.get_connection();
@@ -469,4 +623,580 @@ impl<'a> NodeDatabaseService<'a> {
 
         Ok(call_relationships)
     }
+
+    /// Stamp the files imported without an index time with `indexed_at`, in milliseconds since
+    /// the Unix epoch. The time is left out of the written graph data so that indexing the same
+    /// source twice writes the same files, and files kept by an incremental reindex keep theirs.
+    pub fn record_index_time(&self, indexed_at: i64) -> Result<(), DatabaseError> {
+        let query = format!(
+            "MATCH (file:FileNode)
+             WHERE file.indexed_at = 0 OR file.indexed_at IS NULL
+             SET file.indexed_at = {indexed_at}"
+        );
+        self.query_builder.log_query(&query);
+        match self.transaction_conn {
+            Some(ref conn) => conn.execute_ddl(&query),
+            None => self.get_connection().execute_ddl(&query),
+        }
+    }
+
+    /// Store the number of incoming and outgoing `CALLS` relationships of every definition
+    /// in `call_degree`. With `include_betweenness`, the betweenness centrality of every
+    /// definition in the call graph is stored in `call_betweenness` too. Betweenness takes
+    /// O(V*E), so it is only computed on demand.
+    pub fn compute_call_centrality(&self, include_betweenness: bool) -> Result<(), DatabaseError> {
+        let owned_connection;
+        let connection = match self.transaction_conn {
+            Some(ref conn) => conn,
+            None => {
+                owned_connection = self.get_connection();
+                &owned_connection
+            }
+        };
+
+        let degree_query = format!(
+            "MATCH (d:DefinitionNode)
+             OPTIONAL MATCH (d)-[r:DEFINITION_RELATIONSHIPS]-(:DefinitionNode)
+             WHERE r.type = '{}'
+             WITH d, COUNT(r) AS degree
+             SET d.call_degree = CAST(degree AS INT32)",
+            RelationshipType::Calls.as_str()
+        );
+        self.query_builder.log_query(&degree_query);
+        connection.execute_ddl(&degree_query)?;
+
+        if !include_betweenness {
+            return Ok(());
+        }
+
+        let edges = self.get_relationship_edges(connection, RelationshipType::Calls)?;
+
+        // Definitions that lost all their calls keep no stale score
+        connection.execute_ddl("MATCH (d:DefinitionNode) SET d.call_betweenness = 0.0")?;
+
+        let scores = betweenness_centrality(&edges);
+        let score_type = LogicalType::Struct {
+            fields: vec![
+                ("id".to_string(), LogicalType::UInt32),
+                ("score".to_string(), LogicalType::Double),
+            ],
+        };
+        let scores = scores
+            .into_iter()
+            .map(|(id, score)| {
+                kuzu::Value::Struct(vec![
+                    ("id".to_string(), kuzu::Value::UInt32(id)),
+                    ("score".to_string(), kuzu::Value::Double(score)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let mut statement = connection.prepare(
+            "UNWIND $scores AS s
+             MATCH (d:DefinitionNode) WHERE d.id = s.id
+             SET d.call_betweenness = s.score",
+        )?;
+        for chunk in scores.chunks(CENTRALITY_CHUNK_ROWS) {
+            connection.execute(
+                &mut statement,
+                vec![(
+                    "scores",
+                    kuzu::Value::List(score_type.clone(), chunk.to_vec()),
+                )],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the recursive call chains: groups of definitions that can reach each other
+    /// through `CALLS` relationships, and definitions that call themselves. At most
+    /// `max_cycles` cycles are returned, the largest first.
+    pub fn find_call_cycles(
+        &self,
+        max_cycles: usize,
+    ) -> Result<Vec<Vec<DefinitionNodeFromKuzu>>, DatabaseError> {
+        let edges = match self.transaction_conn {
+            Some(ref conn) => self.get_relationship_edges(conn, RelationshipType::Calls)?,
+            None => self.get_relationship_edges(&self.get_connection(), RelationshipType::Calls)?,
+        };
+
+        let mut cycles = find_cycles(&edges);
+        cycles.truncate(max_cycles);
+
+        let ids = cycles.iter().flatten().copied().collect::<Vec<_>>();
+        let mut definitions = self
+            .get_by::<u32, DefinitionNodeFromKuzu>(KuzuNodeType::DefinitionNode, "id", &ids)?
+            .into_iter()
+            .map(|definition| (definition.id, definition))
+            .collect::<HashMap<_, _>>();
+
+        Ok(cycles
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .iter()
+                    .filter_map(|id| definitions.remove(id))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Find the implementations of the method `method_fqn`: the definitions that override it,
+    /// directly or through other overrides, nearest first. Interface methods are implemented
+    /// through [`RelationshipType::Overrides`] relationships as well.
+    pub fn find_implementations(
+        &self,
+        method_fqn: &str,
+    ) -> Result<Vec<DefinitionNodeFromKuzu>, DatabaseError> {
+        let ids = match self.transaction_conn {
+            Some(ref conn) => self.get_overrider_ids(conn, method_fqn)?,
+            None => self.get_overrider_ids(&self.get_connection(), method_fqn)?,
+        };
+
+        let mut definitions = self
+            .get_by::<u32, DefinitionNodeFromKuzu>(KuzuNodeType::DefinitionNode, "id", &ids)?
+            .into_iter()
+            .map(|definition| (definition.id, definition))
+            .collect::<HashMap<_, _>>();
+        Ok(ids.iter().filter_map(|id| definitions.remove(id)).collect())
+    }
+
+    /// The ids of the definitions reaching `method_fqn` through overrides, traversed from the
+    /// method, nearest first
+    fn get_overrider_ids(
+        &self,
+        connection: &KuzuConnection,
+        method_fqn: &str,
+    ) -> Result<Vec<u32>, DatabaseError> {
+        let query = format!(
+            "MATCH (method:DefinitionNode)<-[r:DEFINITION_RELATIONSHIPS*1..{MAX_OVERRIDE_DEPTH} (e, n | WHERE e.type = '{}')]-(overrider:DefinitionNode)
+             WHERE method.fqn = $fqn AND overrider.fqn <> $fqn
+             RETURN overrider.id, MIN(length(r)) AS distance, overrider.fqn AS fqn
+             ORDER BY distance, fqn",
+            RelationshipType::Overrides.as_str()
+        );
+        self.query_builder.log_query(&query);
+
+        let mut statement = connection.prepare(&query)?;
+        let result = connection.execute(
+            &mut statement,
+            vec![("fqn", kuzu::Value::String(method_fqn.to_string()))],
+        )?;
+        Ok(result
+            .filter_map(|row| match row.first() {
+                Some(kuzu::Value::UInt32(id)) => Some(*id),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Find the definitions whose primary file was indexed after `since`, in milliseconds
+    /// since the Unix epoch, ordered by file and line. Files that were left untouched by an
+    /// incremental reindex keep their earlier index time.
+    pub fn find_definitions_modified_since(
+        &self,
+        since: i64,
+    ) -> Result<Vec<DefinitionNodeFromKuzu>, DatabaseError> {
+        let file_paths = match self.transaction_conn {
+            Some(ref conn) => self.get_files_indexed_since(conn, since)?,
+            None => self.get_files_indexed_since(&self.get_connection(), since)?,
+        };
+
+        let mut definitions = self.get_by::<String, DefinitionNodeFromKuzu>(
+            KuzuNodeType::DefinitionNode,
+            "primary_file_path",
+            &file_paths,
+        )?;
+        definitions.sort_by(|a, b| {
+            (&a.primary_file_path, a.start_line, &a.fqn).cmp(&(
+                &b.primary_file_path,
+                b.start_line,
+                &b.fqn,
+            ))
+        });
+        Ok(definitions)
+    }
+
+    fn get_files_indexed_since(
+        &self,
+        connection: &KuzuConnection,
+        since: i64,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let query = format!(
+            "MATCH (file:FileNode)
+             WHERE file.indexed_at > {since}
+             RETURN file.path"
+        );
+        self.query_builder.log_query(&query);
+
+        let mut file_paths = Vec::new();
+        for row in connection.query(&query)? {
+            if let Some(kuzu::Value::String(path)) = row.first() {
+                file_paths.push(path.clone());
+            }
+        }
+        Ok(file_paths)
+    }
+
+    fn get_relationship_edges(
+        &self,
+        connection: &KuzuConnection,
+        relationship_type: RelationshipType,
+    ) -> Result<Vec<(u32, u32)>, DatabaseError> {
+        let query = format!(
+            "MATCH (source:DefinitionNode)-[r:DEFINITION_RELATIONSHIPS]->(target:DefinitionNode)
+             WHERE r.type = '{}'
+             RETURN source.id, target.id",
+            relationship_type.as_str()
+        );
+        self.query_builder.log_query(&query);
+
+        let mut edges = Vec::new();
+        for row in connection.query(&query)? {
+            if let (Some(kuzu::Value::UInt32(source)), Some(kuzu::Value::UInt32(target))) =
+                (row.first(), row.get(1))
+            {
+                edges.push((*source, *target));
+            }
+        }
+        Ok(edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kuzu::database::KuzuDatabase;
+    use crate::kuzu::types::FileNodeFromKuzu;
+    use crate::schema::manager::SchemaManager;
+
+    #[test]
+    fn test_delete_file_subgraph_keeps_shared_definitions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:FileNode {id: 1, path: 'a.rb'})",
+            "CREATE (:FileNode {id: 2, path: 'b.rb'})",
+            "CREATE (:DefinitionNode {id: 1, fqn: 'A', primary_file_path: 'a.rb'})",
+            "CREATE (:DefinitionNode {id: 2, fqn: 'Shared', primary_file_path: 'a.rb'})",
+            "CREATE (:DefinitionNode {id: 3, fqn: 'B', primary_file_path: 'b.rb'})",
+            "CREATE (:ImportedSymbolNode {id: 1, file_path: 'a.rb'})",
+            "MATCH (f:FileNode {id: 1}), (d:DefinitionNode {id: 1}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'DEFINES'}]->(d)",
+            "MATCH (f:FileNode {id: 1}), (d:DefinitionNode {id: 2}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'DEFINES'}]->(d)",
+            "MATCH (f:FileNode {id: 2}), (d:DefinitionNode {id: 2}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'DEFINES'}]->(d)",
+            "MATCH (f:FileNode {id: 2}), (d:DefinitionNode {id: 3}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'DEFINES'}]->(d)",
+            "MATCH (f:FileNode {id: 1}), (i:ImportedSymbolNode {id: 1}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'IMPORTS'}]->(i)",
+            "MATCH (a:DefinitionNode {id: 3}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (i:ImportedSymbolNode {id: 1}), (d:DefinitionNode {id: 3}) CREATE (i)-[:IMPORTED_SYMBOL_RELATIONSHIPS {type: 'IMPORTS'}]->(d)",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        let counts = service.delete_file_subgraph("a.rb").unwrap();
+
+        assert_eq!(
+            counts,
+            DeletedSubgraphCounts {
+                file_count: 1,
+                definition_count: 1,
+                imported_symbol_count: 1,
+                relationship_count: 5,
+            }
+        );
+
+        let files: Vec<FileNodeFromKuzu> = service.get_all(KuzuNodeType::FileNode).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "b.rb");
+
+        let mut definitions: Vec<String> = service
+            .get_all::<DefinitionNodeFromKuzu>(KuzuNodeType::DefinitionNode)
+            .unwrap()
+            .into_iter()
+            .map(|definition| definition.fqn)
+            .collect();
+        definitions.sort();
+        assert_eq!(definitions, vec!["B".to_string(), "Shared".to_string()]);
+        assert_eq!(
+            service
+                .get_relationship_counts()
+                .unwrap()
+                .file_relationships,
+            2
+        );
+    }
+
+    #[test]
+    fn test_delete_file_subgraph_with_a_quote_in_the_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            r"CREATE (:FileNode {id: 1, path: 'it\'s.rb'})",
+            r"CREATE (:DefinitionNode {id: 1, fqn: 'A', primary_file_path: 'it\'s.rb'})",
+            "MATCH (f:FileNode {id: 1}), (d:DefinitionNode {id: 1}) CREATE (f)-[:FILE_RELATIONSHIPS {type: 'DEFINES'}]->(d)",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        let counts = service.delete_file_subgraph("it's.rb").unwrap();
+
+        assert_eq!(counts.file_count, 1);
+        assert_eq!(counts.definition_count, 1);
+        assert!(
+            service
+                .get_all::<FileNodeFromKuzu>(KuzuNodeType::FileNode)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_delete_by_prefix_with_a_quote_in_the_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            r"CREATE (:FileNode {id: 1, path: 'app/it\'s/a.rb'})",
+            r"CREATE (:FileNode {id: 2, path: 'app/its/b.rb'})",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        service
+            .delete_by_prefix(KuzuNodeType::FileNode, "path", &["app/it's/".to_string()])
+            .unwrap();
+
+        let files: Vec<FileNodeFromKuzu> = service.get_all(KuzuNodeType::FileNode).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "app/its/b.rb");
+    }
+
+    #[test]
+    fn test_compute_call_centrality() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:DefinitionNode {id: 1, fqn: 'A'})",
+            "CREATE (:DefinitionNode {id: 2, fqn: 'B'})",
+            "CREATE (:DefinitionNode {id: 3, fqn: 'C'})",
+            "CREATE (:DefinitionNode {id: 4, fqn: 'Unused'})",
+            "MATCH (a:DefinitionNode {id: 1}), (b:DefinitionNode {id: 2}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 2}), (b:DefinitionNode {id: 3}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 1}), (b:DefinitionNode {id: 4}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CLASS_TO_METHOD'}]->(b)",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        service.compute_call_centrality(true).unwrap();
+
+        let mut scores = Vec::new();
+        for row in connection
+            .query(
+                "MATCH (d:DefinitionNode) RETURN d.fqn, d.call_degree, d.call_betweenness ORDER BY d.fqn",
+            )
+            .unwrap()
+        {
+            if let (
+                Some(kuzu::Value::String(fqn)),
+                Some(kuzu::Value::Int32(degree)),
+                Some(kuzu::Value::Double(betweenness)),
+            ) = (row.first(), row.get(1), row.get(2))
+            {
+                scores.push((fqn.clone(), *degree, *betweenness));
+            }
+        }
+
+        assert_eq!(
+            scores,
+            vec![
+                ("A".to_string(), 1, 0.0),
+                ("B".to_string(), 2, 1.0),
+                ("C".to_string(), 1, 0.0),
+                ("Unused".to_string(), 0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_call_cycles() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:DefinitionNode {id: 1, fqn: 'Even'})",
+            "CREATE (:DefinitionNode {id: 2, fqn: 'Odd'})",
+            "CREATE (:DefinitionNode {id: 3, fqn: 'Factorial'})",
+            "CREATE (:DefinitionNode {id: 4, fqn: 'Main'})",
+            "MATCH (a:DefinitionNode {id: 1}), (b:DefinitionNode {id: 2}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 2}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 3}), (b:DefinitionNode {id: 3}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 4}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 4}), (b:DefinitionNode {id: 4}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CLASS_TO_METHOD'}]->(b)",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        let fqns = |cycles: Vec<Vec<DefinitionNodeFromKuzu>>| {
+            cycles
+                .into_iter()
+                .map(|cycle| cycle.into_iter().map(|d| d.fqn).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            fqns(service.find_call_cycles(10).unwrap()),
+            vec![
+                vec!["Even".to_string(), "Odd".to_string()],
+                vec!["Factorial".to_string()],
+            ]
+        );
+        assert_eq!(
+            fqns(service.find_call_cycles(1).unwrap()),
+            vec![vec!["Even".to_string(), "Odd".to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_find_implementations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:DefinitionNode {id: 1, fqn: 'Shape.area'})",
+            "CREATE (:DefinitionNode {id: 2, fqn: 'Polygon.area'})",
+            "CREATE (:DefinitionNode {id: 3, fqn: 'Square.area'})",
+            "CREATE (:DefinitionNode {id: 4, fqn: 'Circle.area'})",
+            "CREATE (:DefinitionNode {id: 5, fqn: 'Report.area'})",
+            "MATCH (a:DefinitionNode {id: 2}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'OVERRIDES'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 3}), (b:DefinitionNode {id: 2}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'OVERRIDES'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 4}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'OVERRIDES'}]->(b)",
+            "MATCH (a:DefinitionNode {id: 5}), (b:DefinitionNode {id: 1}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS'}]->(b)",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        let fqns = |fqn: &str| {
+            service
+                .find_implementations(fqn)
+                .unwrap()
+                .into_iter()
+                .map(|definition| definition.fqn)
+                .collect::<Vec<_>>()
+        };
+
+        let mut implementations = fqns("Shape.area");
+        implementations[..2].sort();
+        assert_eq!(
+            implementations,
+            vec!["Circle.area", "Polygon.area", "Square.area"]
+        );
+        assert_eq!(fqns("Polygon.area"), vec!["Square.area"]);
+        assert!(fqns("Square.area").is_empty());
+        assert!(fqns("Unknown.area").is_empty());
+    }
+
+    #[test]
+    fn test_find_definitions_modified_since() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:FileNode {id: 1, path: 'app/billing.rb', indexed_at: 1000})",
+            "CREATE (:FileNode {id: 2, path: 'app/invoice.rb', indexed_at: 3000})",
+            "CREATE (:FileNode {id: 3, path: 'app/refund.rb', indexed_at: 2001})",
+            "CREATE (:DefinitionNode {id: 1, fqn: 'Billing', primary_file_path: 'app/billing.rb', start_line: 0})",
+            "CREATE (:DefinitionNode {id: 2, fqn: 'Invoice#total', primary_file_path: 'app/invoice.rb', start_line: 4})",
+            "CREATE (:DefinitionNode {id: 3, fqn: 'Invoice', primary_file_path: 'app/invoice.rb', start_line: 0})",
+            "CREATE (:DefinitionNode {id: 4, fqn: 'Refund', primary_file_path: 'app/refund.rb', start_line: 0})",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        let fqns = |since: i64| {
+            service
+                .find_definitions_modified_since(since)
+                .unwrap()
+                .into_iter()
+                .map(|definition| definition.fqn)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(fqns(2000), vec!["Invoice", "Invoice#total", "Refund"]);
+        assert_eq!(fqns(0).len(), 4);
+        assert!(fqns(3000).is_empty());
+    }
+
+    #[test]
+    fn test_record_index_time_keeps_earlier_times() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let database = KuzuDatabase::new()
+            .force_new_database(database_path.to_str().unwrap(), None)
+            .unwrap();
+        SchemaManager::new(&database).initialize_schema().unwrap();
+
+        let connection = KuzuConnection::new(&database).unwrap();
+        for statement in [
+            "CREATE (:FileNode {id: 1, path: 'app/billing.rb', indexed_at: 1000})",
+            "CREATE (:FileNode {id: 2, path: 'app/invoice.rb', indexed_at: 0})",
+            "CREATE (:FileNode {id: 3, path: 'app/refund.rb'})",
+        ] {
+            connection.execute_ddl(statement).unwrap();
+        }
+
+        let service = NodeDatabaseService::new(&database);
+        service.record_index_time(5000).unwrap();
+
+        let index_times = connection
+            .query("MATCH (file:FileNode) RETURN file.path, file.indexed_at ORDER BY file.path")
+            .unwrap()
+            .map(|row| row[1].to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(index_times, vec!["1000", "5000", "5000"]);
+    }
 }
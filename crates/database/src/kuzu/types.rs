@@ -33,6 +33,21 @@ pub enum DatabaseError {
     ConnectionClosed,
     #[error("Prepared statement error: {0}")]
     PreparedStatementError(String),
+    #[error("Import failed and was rolled back, the database was left unchanged: {0}")]
+    ImportRolledBack(String),
+    #[error("Unsupported column type for import: {0}")]
+    UnsupportedColumnType(String),
+    #[error(
+        "The database at {database_path} was written by an incompatible version of Kuzu ({reason}). Reindex the project with `gkg index` to rebuild it."
+    )]
+    IncompatibleStorageVersion {
+        database_path: String,
+        reason: String,
+    },
+    #[error(
+        "No database at {database_path}, the project has not been indexed yet. Index it with `gkg index`."
+    )]
+    NotIndexed { database_path: String },
 }
 
 #[derive(Debug, Clone)]
@@ -327,6 +342,14 @@ pub struct FileNodeFromKuzu {
     pub repository_name: String,
     pub extension: String,
     pub name: String,
+    /// SHA-256 of the file content when it was indexed, empty for older graphs
+    pub content_hash: String,
+    /// When the file was last indexed, in milliseconds since the Unix epoch, 0 for older
+    /// graphs
+    pub indexed_at: i64,
+    /// Length of the byte order mark the file starts with, which its byte offsets don't
+    /// count
+    pub content_offset: i64,
 }
 
 impl FileNodeFromKuzu {
@@ -339,6 +362,9 @@ impl FileNodeFromKuzu {
             repository_name: String::new(),
             extension: String::new(),
             name: String::new(),
+            content_hash: String::new(),
+            indexed_at: 0,
+            content_offset: 0,
         }
     }
 
@@ -352,8 +378,18 @@ impl FileNodeFromKuzu {
                             node.id = *i
                         }
                     }
+                    "indexed_at" => {
+                        if let Value::Int64(i) = prop_value {
+                            node.indexed_at = *i
+                        }
+                    }
+                    "content_offset" => {
+                        if let Value::Int64(i) = prop_value {
+                            node.content_offset = *i
+                        }
+                    }
                     "path" | "absolute_path" | "language" | "repository_name" | "extension"
-                    | "name" => {
+                    | "name" | "content_hash" => {
                         if let Value::String(s) = prop_value {
                             match prop_name.as_str() {
                                 "path" => node.path = s.to_string(),
@@ -362,6 +398,7 @@ impl FileNodeFromKuzu {
                                 "repository_name" => node.repository_name = s.to_string(),
                                 "extension" => node.extension = s.to_string(),
                                 "name" => node.name = s.to_string(),
+                                "content_hash" => node.content_hash = s.to_string(),
                                 _ => (),
                             }
                         }
@@ -461,6 +498,12 @@ impl std::fmt::Display for DirectoryNodeFromKuzu {
     }
 }
 
+/// Escapes a value for a single-quoted string literal of a query, so quotes and backslashes in
+/// paths neither break the query nor end the literal early
+pub fn escape_string_literal(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
 /// Trait to determine if a value needs to be quoted in SQL
 pub trait QuoteEscape {
     fn needs_quotes(&self) -> bool;
@@ -539,6 +582,15 @@ pub struct NodeCounts {
     pub imported_symbol_count: u32,
 }
 
+/// Nodes and relationships removed by `NodeDatabaseService::delete_file_subgraph`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeletedSubgraphCounts {
+    pub file_count: u32,
+    pub definition_count: u32,
+    pub imported_symbol_count: u32,
+    pub relationship_count: u64,
+}
+
 /// Relationship counts structure
 #[derive(Debug, Clone)]
 pub struct RelationshipCounts {
@@ -0,0 +1,337 @@
+use crate::kuzu::database::DatabaseChangeListener;
+use serde_json::Map;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_QUERY_CACHE_CAPACITY: usize = 256;
+pub const DEFAULT_QUERY_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Settings of the query result cache of
+/// [`DatabaseQueryingService`](crate::querying::DatabaseQueryingService)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryCacheConfig {
+    /// Number of query results kept, the least recently used one is dropped beyond it
+    pub capacity: usize,
+    /// How long a result is served from the cache after it was fetched
+    pub ttl: Duration,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUERY_CACHE_CAPACITY,
+            ttl: DEFAULT_QUERY_CACHE_TTL,
+        }
+    }
+}
+
+/// The rows of a query, shared by the results served from the cache
+#[derive(Debug)]
+pub struct CachedQueryResult {
+    pub column_names: Vec<String>,
+    pub rows: Vec<Vec<kuzu::Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    database_path: PathBuf,
+    query: String,
+    /// Parameters serialized to JSON with sorted keys, so the order they were set in doesn't
+    /// matter
+    params: String,
+}
+
+impl CacheKey {
+    fn new(database_path: &Path, query: &str, params: &Map<String, serde_json::Value>) -> Self {
+        Self {
+            database_path: database_path.to_path_buf(),
+            query: query.to_string(),
+            params: serde_json::to_string(&params.iter().collect::<BTreeMap<_, _>>())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+struct CacheEntry {
+    result: Arc<CachedQueryResult>,
+    fetched_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Incremented on every access, orders the entries by recency
+    clock: u64,
+    /// Incremented on every invalidation
+    generation: u64,
+}
+
+/// LRU cache of query results keyed by database, query and parameters. Eviction scans the
+/// entries, which is cheap for the few hundred results the cache is meant to hold.
+pub struct QueryCache {
+    config: QueryCacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl QueryCache {
+    pub fn new(config: QueryCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn get(
+        &self,
+        database_path: &Path,
+        query: &str,
+        params: &Map<String, serde_json::Value>,
+    ) -> Option<Arc<CachedQueryResult>> {
+        let key = CacheKey::new(database_path, query, params);
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+
+        let entry = state.entries.get_mut(&key)?;
+        if entry.fetched_at.elapsed() > self.config.ttl {
+            state.entries.remove(&key);
+            return None;
+        }
+        entry.last_used = clock;
+        Some(Arc::clone(&entry.result))
+    }
+
+    /// The current generation, to be read before fetching a result that will be inserted
+    pub fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Caches a result fetched during `generation`. The result is dropped if the cache was
+    /// invalidated since, as it may have been read from the database before it changed.
+    pub fn insert(
+        &self,
+        database_path: &Path,
+        query: &str,
+        params: &Map<String, serde_json::Value>,
+        result: Arc<CachedQueryResult>,
+        generation: u64,
+    ) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        let key = CacheKey::new(database_path, query, params);
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+            return;
+        }
+        state.clock += 1;
+        let clock = state.clock;
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.config.capacity {
+            let least_recently_used = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                state.entries.remove(&least_recently_used);
+            }
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                result,
+                fetched_at: Instant::now(),
+                last_used: clock,
+            },
+        );
+    }
+
+    /// Drops the results of one database, returns how many were dropped
+    pub fn invalidate_database(&self, database_path: &Path) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        let before = state.entries.len();
+        state
+            .entries
+            .retain(|key, _| key.database_path != database_path);
+        before - state.entries.len()
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl DatabaseChangeListener for QueryCache {
+    fn database_changed(&self, database_path: &Path) {
+        self.invalidate_database(database_path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(value: &str) -> Arc<CachedQueryResult> {
+        Arc::new(CachedQueryResult {
+            column_names: vec!["name".to_string()],
+            rows: vec![vec![kuzu::Value::String(value.to_string())]],
+        })
+    }
+
+    fn params(limit: i64) -> Map<String, serde_json::Value> {
+        let mut params = Map::new();
+        params.insert("limit".to_string(), limit.into());
+        params
+    }
+
+    #[test]
+    fn test_results_are_keyed_by_database_query_and_params() {
+        let cache = QueryCache::new(QueryCacheConfig::default());
+        let database = Path::new("/data/a.kz");
+        cache.insert(
+            database,
+            "MATCH (n) RETURN n",
+            &params(1),
+            result("a"),
+            cache.generation(),
+        );
+
+        assert!(
+            cache
+                .get(database, "MATCH (n) RETURN n", &params(1))
+                .is_some()
+        );
+        assert!(
+            cache
+                .get(database, "MATCH (n) RETURN n", &params(2))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(database, "MATCH (m) RETURN m", &params(1))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(Path::new("/data/b.kz"), "MATCH (n) RETURN n", &params(1))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_least_recently_used_result_is_evicted() {
+        let cache = QueryCache::new(QueryCacheConfig {
+            capacity: 2,
+            ..Default::default()
+        });
+        let database = Path::new("/data/a.kz");
+        cache.insert(
+            database,
+            "first",
+            &params(1),
+            result("first"),
+            cache.generation(),
+        );
+        cache.insert(
+            database,
+            "second",
+            &params(1),
+            result("second"),
+            cache.generation(),
+        );
+        // Reading the first result makes the second one the least recently used
+        assert!(cache.get(database, "first", &params(1)).is_some());
+        cache.insert(
+            database,
+            "third",
+            &params(1),
+            result("third"),
+            cache.generation(),
+        );
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(database, "first", &params(1)).is_some());
+        assert!(cache.get(database, "second", &params(1)).is_none());
+        assert!(cache.get(database, "third", &params(1)).is_some());
+    }
+
+    #[test]
+    fn test_expired_results_are_not_served() {
+        let cache = QueryCache::new(QueryCacheConfig {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        let database = Path::new("/data/a.kz");
+        cache.insert(
+            database,
+            "query",
+            &params(1),
+            result("a"),
+            cache.generation(),
+        );
+        std::thread::sleep(Duration::from_millis(5));
+
+        assert!(cache.get(database, "query", &params(1)).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_results_fetched_before_an_invalidation_are_not_cached() {
+        let cache = QueryCache::new(QueryCacheConfig::default());
+        let database = Path::new("/data/a.kz");
+        let generation = cache.generation();
+        cache.invalidate_database(database);
+        cache.insert(database, "query", &params(1), result("a"), generation);
+
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_invalidate_database_only_drops_its_results() {
+        let cache = QueryCache::new(QueryCacheConfig::default());
+        cache.insert(
+            Path::new("/data/a.kz"),
+            "query",
+            &params(1),
+            result("a"),
+            cache.generation(),
+        );
+        cache.insert(
+            Path::new("/data/a.kz"),
+            "query",
+            &params(2),
+            result("a"),
+            cache.generation(),
+        );
+        cache.insert(
+            Path::new("/data/b.kz"),
+            "query",
+            &params(1),
+            result("b"),
+            cache.generation(),
+        );
+
+        assert_eq!(cache.invalidate_database(Path::new("/data/a.kz")), 2);
+        assert_eq!(cache.len(), 1);
+        assert!(
+            cache
+                .get(Path::new("/data/b.kz"), "query", &params(1))
+                .is_some()
+        );
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
 use crate::querying::mappers::{
-    INT_MAPPER, QueryResultMapper, RELATIONSHIP_TYPE_MAPPER, STRING_MAPPER,
+    FLOAT_MAPPER, INT_MAPPER, QueryResultMapper, RELATIONSHIP_TYPE_MAPPER, STRING_MAPPER,
 };
 
 pub struct QueryLibrary;
 
+const SEARCH_NODES_ORDER: &str = "node_type, name, fqn, path, start_line, id";
+const SEARCH_NODES_BY_CALL_DEGREE_ORDER: &str =
+    "call_degree DESC, node_type, name, fqn, path, start_line, id";
+
 #[derive(Debug, Clone)]
 pub struct Query {
     pub query: String,
@@ -63,7 +67,7 @@ impl QueryLibrary {
                 MATCH (f:FileNode)-[:FILE_RELATIONSHIPS]->(imp:ImportedSymbolNode)
                 WHERE toLower(imp.import_path) IN $paths_lc{name_clause}{alias_clause}
                 OPTIONAL MATCH (imp)<-[r:DEFINITION_RELATIONSHIPS]-(src:DefinitionNode)
-                WHERE r IS NULL OR r.type IN [$calls_type_id, $ambiguous_calls_type_id]
+                WHERE r IS NULL OR r.type IN $reference_type_ids
                 RETURN
                   f.path AS file_path,
                   imp.import_path AS import_path,
@@ -89,17 +93,10 @@ impl QueryLibrary {
                 },
             ),
             (
-                "calls_type_id",
-                QueryParameter {
-                    name: "calls_type_id",
-                    definition: QueryParameterDefinition::Int(None),
-                },
-            ),
-            (
-                "ambiguous_calls_type_id",
+                "reference_type_ids",
                 QueryParameter {
-                    name: "ambiguous_calls_type_id",
-                    definition: QueryParameterDefinition::Int(None),
+                    name: "reference_type_ids",
+                    definition: QueryParameterDefinition::Array(None),
                 },
             ),
             (
@@ -267,9 +264,12 @@ impl QueryLibrary {
         let source_return = Self::get_node_neighbors_return_values(config.source_type, "source");
         let target_return = Self::get_node_neighbors_return_values(config.target_type, "target");
 
+        let source_language = Self::get_node_language_match(config.source_type, "source");
+        let target_language = Self::get_node_language_match(config.target_type, "target");
+
         format!(
             r#"
-            MATCH (source:{source_type})-[r:{relationship_name}]-(target:{target_type})
+            MATCH (source:{source_type})-[r:{relationship_name}]-(target:{target_type}){source_language}{target_language}
             RETURN 
                 {source_return}
                 {target_return}
@@ -421,7 +421,7 @@ impl QueryLibrary {
                     n.definition_type as definition_type,
                     n.primary_file_path as file_path,
                     n.start_line as line_number
-                ORDER BY n.fqn
+                ORDER BY n.fqn, n.primary_file_path, n.start_line
                 LIMIT $limit
             "#
             .to_string(),
@@ -548,6 +548,8 @@ impl QueryLibrary {
             ("source_import_type", STRING_MAPPER),
             ("source_import_path", STRING_MAPPER),
             ("source_import_alias", STRING_MAPPER),
+            ("source_call_degree", INT_MAPPER),
+            ("source_call_betweenness", FLOAT_MAPPER),
             ("target_id", STRING_MAPPER),
             ("target_type", STRING_MAPPER),
             ("target_name", STRING_MAPPER),
@@ -565,6 +567,8 @@ impl QueryLibrary {
             ("target_import_type", STRING_MAPPER),
             ("target_import_path", STRING_MAPPER),
             ("target_import_alias", STRING_MAPPER),
+            ("target_call_degree", INT_MAPPER),
+            ("target_call_betweenness", FLOAT_MAPPER),
             ("relationship_name", STRING_MAPPER),
             ("relationship_id", STRING_MAPPER),
             ("relationship_type", STRING_MAPPER),
@@ -573,6 +577,17 @@ impl QueryLibrary {
         ])
     }
 
+    /// Matches the primary file of a definition bound to `alias` as `{alias}_file`, for the
+    /// language of the definition to be returned with it
+    fn get_node_language_match(node_type: &str, alias: &str) -> String {
+        match node_type {
+            "DefinitionNode" => format!(
+                "\n            OPTIONAL MATCH ({alias}_file:FileNode) WHERE {alias}_file.path = {alias}.primary_file_path"
+            ),
+            _ => String::new(),
+        }
+    }
+
     fn get_node_neighbors_return_values(node_type: &str, alias: &str) -> String {
         match node_type {
             "DirectoryNode" => format!(
@@ -594,6 +609,8 @@ impl QueryLibrary {
                 '' as {alias}_import_type,
                 '' as {alias}_import_path,
                 '' as {alias}_import_alias,
+                CAST(0 AS INT64) as {alias}_call_degree,
+                CAST(0.0 AS DOUBLE) as {alias}_call_betweenness,
             "#
             ),
             "FileNode" => format!(
@@ -615,6 +632,8 @@ impl QueryLibrary {
                     '' as {alias}_import_type,
                     '' as {alias}_import_path,
                     '' as {alias}_import_alias,
+                    CAST(0 AS INT64) as {alias}_call_degree,
+                    CAST(0.0 AS DOUBLE) as {alias}_call_betweenness,
                 "#
             ),
             "DefinitionNode" => format!(
@@ -627,7 +646,7 @@ impl QueryLibrary {
                     '' as {alias}_repository_name,
                     {alias}.fqn as {alias}_fqn,
                     {alias}.definition_type as {alias}_definition_type,
-                    '' as {alias}_language,
+                    coalesce({alias}_file.language, '') as {alias}_language,
                     '' as {alias}_extension,
                     CAST({alias}.start_line AS INT64) as {alias}_start_line,
                     {alias}.primary_start_byte as {alias}_primary_start_byte,
@@ -636,6 +655,8 @@ impl QueryLibrary {
                     '' as {alias}_import_type,
                     '' as {alias}_import_path,
                     '' as {alias}_import_alias,
+                    CAST({alias}.call_degree AS INT64) as {alias}_call_degree,
+                    coalesce({alias}.call_betweenness, 0.0) as {alias}_call_betweenness,
                 "#
             ),
             "ImportedSymbolNode" => format!(
@@ -657,6 +678,8 @@ impl QueryLibrary {
                     {alias}.import_type as {alias}_import_type,
                     {alias}.import_path as {alias}_import_path,
                     {alias}.alias as {alias}_import_alias,
+                    CAST(0 AS INT64) as {alias}_call_degree,
+                    CAST(0.0 AS DOUBLE) as {alias}_call_betweenness,
                 "#
             ),
             _ => "".to_string(),
@@ -697,6 +720,16 @@ impl QueryLibrary {
 
     /// Builds a neighbor query section with the appropriate WHERE clause for node filtering
     pub fn build_neighbor_query_section(config: &RelationshipConfig, node_type: &str) -> String {
+        Self::build_filtered_neighbor_query_section(config, node_type, false)
+    }
+
+    /// Same as [`Self::build_neighbor_query_section`], keeping only the relationships whose
+    /// type is `$relationship_type` when `by_relationship_type` is set
+    fn build_filtered_neighbor_query_section(
+        config: &RelationshipConfig,
+        node_type: &str,
+        by_relationship_type: bool,
+    ) -> String {
         let source_return = Self::get_node_neighbors_return_values(config.source_type, "source");
         let target_return = Self::get_node_neighbors_return_values(config.target_type, "target");
 
@@ -726,10 +759,18 @@ impl QueryLibrary {
             }
             _ => "",
         };
+        let where_clause = if by_relationship_type && !where_clause.is_empty() {
+            format!("{where_clause} AND r.type = $relationship_type")
+        } else {
+            where_clause.to_string()
+        };
+
+        let source_language = Self::get_node_language_match(config.source_type, "source");
+        let target_language = Self::get_node_language_match(config.target_type, "target");
 
         format!(
             r#"
-            MATCH (source:{source_type})-[r:{relationship_name}]-(target:{target_type}) {where_clause}
+            MATCH (source:{source_type})-[r:{relationship_name}]-(target:{target_type}) {where_clause}{source_language}{target_language}
             RETURN 
                 {source_return}
                 {target_return}
@@ -749,6 +790,16 @@ impl QueryLibrary {
     }
 
     pub fn get_node_neighbors_query(node_type: &str) -> Option<Query> {
+        Self::build_node_neighbors_query(node_type, false)
+    }
+
+    /// Same as [`Self::get_node_neighbors_query`], only following the relationships whose
+    /// type is `$relationship_type`, e.g. `INSTANTIATES`
+    pub fn get_node_neighbors_by_relationship_type_query(node_type: &str) -> Option<Query> {
+        Self::build_node_neighbors_query(node_type, true)
+    }
+
+    fn build_node_neighbors_query(node_type: &str, by_relationship_type: bool) -> Option<Query> {
         let relationship_configs = Self::get_neighbor_relationship_configs(node_type);
 
         if relationship_configs.is_empty() {
@@ -757,39 +808,97 @@ impl QueryLibrary {
 
         let query_sections: Vec<String> = relationship_configs
             .iter()
-            .map(|config| Self::build_neighbor_query_section(config, node_type))
+            .map(|config| {
+                Self::build_filtered_neighbor_query_section(config, node_type, by_relationship_type)
+            })
             .collect();
 
         let query = format!("{} LIMIT $limit", query_sections.join("\nUNION\n"));
 
+        let mut parameters = HashMap::from([
+            (
+                "node_id",
+                QueryParameter {
+                    name: "node_id",
+                    definition: QueryParameterDefinition::String(None),
+                },
+            ),
+            (
+                "limit",
+                QueryParameter {
+                    name: "limit",
+                    definition: QueryParameterDefinition::Int(Some(100)),
+                },
+            ),
+        ]);
+        if by_relationship_type {
+            parameters.insert(
+                "relationship_type",
+                QueryParameter {
+                    name: "relationship_type",
+                    definition: QueryParameterDefinition::String(None),
+                },
+            );
+        }
+
         Some(Query {
             query,
-            parameters: HashMap::from([
-                (
-                    "node_id",
-                    QueryParameter {
-                        name: "node_id",
-                        definition: QueryParameterDefinition::String(None),
-                    },
-                ),
-                (
-                    "limit",
-                    QueryParameter {
-                        name: "limit",
-                        definition: QueryParameterDefinition::Int(Some(100)),
-                    },
-                ),
-            ]),
+            parameters,
             result: Self::get_graph_result_mappers(),
         })
     }
 
     pub fn get_search_nodes_query() -> Query {
-        Query {
-            query: r#"
+        Self::build_search_nodes_query(SEARCH_NODES_ORDER, false, false)
+    }
+
+    /// Same matches as [`Self::get_search_nodes_query`], the definitions with the most
+    /// incoming and outgoing calls first
+    pub fn get_search_nodes_by_call_degree_query() -> Query {
+        Self::build_search_nodes_query(SEARCH_NODES_BY_CALL_DEGREE_ORDER, false, false)
+    }
+
+    /// Same as [`Self::get_search_nodes_query`], with `$search_term` matched as a regular
+    /// expression, case sensitively, anywhere in the names, paths and fully qualified names
+    pub fn get_regex_search_nodes_query() -> Query {
+        Self::build_search_nodes_query(SEARCH_NODES_ORDER, true, false)
+    }
+
+    /// Same matches as [`Self::get_regex_search_nodes_query`], ordered like
+    /// [`Self::get_search_nodes_by_call_degree_query`]
+    pub fn get_regex_search_nodes_by_call_degree_query() -> Query {
+        Self::build_search_nodes_query(SEARCH_NODES_BY_CALL_DEGREE_ORDER, true, false)
+    }
+
+    /// The search queries above, only matching the definitions with an incoming or outgoing
+    /// relationship to another definition whose type is `$relationship_type`, e.g. the
+    /// classes that are instantiated somewhere
+    pub fn get_search_nodes_by_relationship_type_query(
+        sort_by_call_degree: bool,
+        regex: bool,
+    ) -> Query {
+        let order_by = if sort_by_call_degree {
+            SEARCH_NODES_BY_CALL_DEGREE_ORDER
+        } else {
+            SEARCH_NODES_ORDER
+        };
+        Self::build_search_nodes_query(order_by, regex, true)
+    }
+
+    fn build_search_nodes_query(order_by: &str, regex: bool, by_relationship_type: bool) -> Query {
+        let matches = |property: &str| {
+            if regex {
+                format!("regexp_matches({property}, $search_term)")
+            } else {
+                format!("toLower({property}) CONTAINS toLower($search_term)")
+            }
+        };
+
+        let directories = format!(
+            r#"
                 MATCH (d:DirectoryNode)
-                WHERE toLower(d.name) CONTAINS toLower($search_term) 
-                   OR toLower(d.path) CONTAINS toLower($search_term)
+                WHERE {d_name} 
+                   OR {d_path}
                 RETURN 
                     d.id as id,
                     'DirectoryNode' as node_type,
@@ -807,11 +916,17 @@ impl QueryLibrary {
                     CAST(0 AS INT64) as total_locations,
                     '' as import_type,
                     '' as import_path,
-                    '' as import_alias
-                UNION
+                    '' as import_alias,
+                    CAST(0 AS INT64) as call_degree,
+                    CAST(0.0 AS DOUBLE) as call_betweenness"#,
+            d_name = matches("d.name"),
+            d_path = matches("d.path"),
+        );
+        let files = format!(
+            r#"
                 MATCH (f:FileNode)
-                WHERE toLower(f.name) CONTAINS toLower($search_term)
-                   OR toLower(f.path) CONTAINS toLower($search_term)
+                WHERE {f_name}
+                   OR {f_path}
                 RETURN 
                     f.id as id,
                     'FileNode' as node_type,
@@ -829,11 +944,23 @@ impl QueryLibrary {
                     CAST(0 AS INT64) as total_locations,
                     '' as import_type,
                     '' as import_path,
-                    '' as import_alias
-                UNION
+                    '' as import_alias,
+                    CAST(0 AS INT64) as call_degree,
+                    CAST(0.0 AS DOUBLE) as call_betweenness"#,
+            f_name = matches("f.name"),
+            f_path = matches("f.path"),
+        );
+        let relationship_filter = if by_relationship_type {
+            "\n                  AND EXISTS { MATCH (def)-[r:DEFINITION_RELATIONSHIPS]-(:DefinitionNode) WHERE r.type = $relationship_type }"
+        } else {
+            ""
+        };
+        let definitions = format!(
+            r#"
                 MATCH (def:DefinitionNode)
-                WHERE toLower(def.name) CONTAINS toLower($search_term)
-                   OR toLower(def.fqn) CONTAINS toLower($search_term)
+                WHERE ({def_name}
+                   OR {def_fqn}){relationship_filter}
+                OPTIONAL MATCH (file:FileNode) WHERE file.path = def.primary_file_path
                 RETURN 
                     def.id as id,
                     'DefinitionNode' as node_type,
@@ -843,7 +970,7 @@ impl QueryLibrary {
                     '' as repository_name,
                     def.fqn as fqn,
                     def.definition_type as definition_type,
-                    '' as language,
+                    coalesce(file.language, '') as language,
                     '' as extension,
                     CAST(def.start_line AS INT64) as start_line,
                     def.primary_start_byte as primary_start_byte,
@@ -851,12 +978,18 @@ impl QueryLibrary {
                     CAST(def.total_locations AS INT64) as total_locations,
                     '' as import_type,
                     '' as import_path,
-                    '' as import_alias
-                UNION
+                    '' as import_alias,
+                    CAST(def.call_degree AS INT64) as call_degree,
+                    coalesce(def.call_betweenness, 0.0) as call_betweenness"#,
+            def_name = matches("def.name"),
+            def_fqn = matches("def.fqn"),
+        );
+        let imported_symbols = format!(
+            r#"
                 MATCH (imp:ImportedSymbolNode)
-                WHERE toLower(imp.name) CONTAINS toLower($search_term)
-                   OR toLower(imp.import_path) CONTAINS toLower($search_term)
-                   OR toLower(imp.alias) CONTAINS toLower($search_term)
+                WHERE {imp_name}
+                   OR {imp_import_path}
+                   OR {imp_alias}
                 RETURN 
                     imp.id as id,
                     'ImportedSymbolNode' as node_type,
@@ -874,27 +1007,53 @@ impl QueryLibrary {
                     CAST(0 AS INT64) as total_locations,
                     imp.import_type as import_type,
                     imp.import_path as import_path,
-                    imp.alias as import_alias
-                ORDER BY node_type, name
-                LIMIT $limit
-            "#
-            .to_string(),
-            parameters: HashMap::from([
-                (
-                    "search_term",
-                    QueryParameter {
-                        name: "search_term",
-                        definition: QueryParameterDefinition::String(None),
-                    },
-                ),
-                (
-                    "limit",
-                    QueryParameter {
-                        name: "limit",
-                        definition: QueryParameterDefinition::Int(Some(100)),
-                    },
-                ),
-            ]),
+                    imp.alias as import_alias,
+                    CAST(0 AS INT64) as call_degree,
+                    CAST(0.0 AS DOUBLE) as call_betweenness"#,
+            imp_name = matches("imp.name"),
+            imp_import_path = matches("imp.import_path"),
+            imp_alias = matches("imp.alias"),
+        );
+
+        // Only definitions have relationships to other definitions
+        let sections = if by_relationship_type {
+            vec![definitions]
+        } else {
+            vec![directories, files, definitions, imported_symbols]
+        };
+
+        let mut parameters = HashMap::from([
+            (
+                "search_term",
+                QueryParameter {
+                    name: "search_term",
+                    definition: QueryParameterDefinition::String(None),
+                },
+            ),
+            (
+                "limit",
+                QueryParameter {
+                    name: "limit",
+                    definition: QueryParameterDefinition::Int(Some(100)),
+                },
+            ),
+        ]);
+        if by_relationship_type {
+            parameters.insert(
+                "relationship_type",
+                QueryParameter {
+                    name: "relationship_type",
+                    definition: QueryParameterDefinition::String(None),
+                },
+            );
+        }
+
+        Query {
+            query: format!(
+                "{}\n                ORDER BY {order_by}\n                LIMIT $limit\n",
+                sections.join("\n                UNION")
+            ),
+            parameters,
             result: HashMap::from([
                 ("id", STRING_MAPPER),
                 ("node_type", STRING_MAPPER),
@@ -913,6 +1072,8 @@ impl QueryLibrary {
                 ("import_type", STRING_MAPPER),
                 ("import_path", STRING_MAPPER),
                 ("import_alias", STRING_MAPPER),
+                ("call_degree", INT_MAPPER),
+                ("call_betweenness", FLOAT_MAPPER),
             ]),
         }
     }
@@ -929,7 +1090,7 @@ impl QueryLibrary {
                     d.primary_file_path as file_path,
                     d.start_line as start_line,
                     d.end_line as end_line
-                ORDER BY d.name
+                ORDER BY d.name, d.fqn, d.primary_file_path, d.start_line
                 SKIP $skip
                 LIMIT $limit
             "#
@@ -968,6 +1129,143 @@ impl QueryLibrary {
         }
     }
 
+    /// Definitions in files under `$path_prefix`, ordered by file so a page can be grouped
+    /// per file.
+    pub fn get_definitions_by_file_prefix_query() -> Query {
+        Query {
+            query: r#"
+                MATCH (d:DefinitionNode)
+                WHERE starts_with(d.primary_file_path, $path_prefix)
+                OPTIONAL MATCH (file:FileNode) WHERE file.path = d.primary_file_path
+                RETURN
+                    d.id as id,
+                    d.name as name,
+                    d.fqn as fqn,
+                    d.definition_type as definition_type,
+                    d.primary_file_path as file_path,
+                    d.start_line as start_line,
+                    d.end_line as end_line,
+                    coalesce(file.language, '') as language
+                ORDER BY d.primary_file_path, d.start_line, d.fqn
+                SKIP $skip
+                LIMIT $limit
+            "#
+            .to_string(),
+            parameters: HashMap::from([
+                (
+                    "path_prefix",
+                    QueryParameter {
+                        name: "path_prefix",
+                        definition: QueryParameterDefinition::String(None),
+                    },
+                ),
+                (
+                    "limit",
+                    QueryParameter {
+                        name: "limit",
+                        definition: QueryParameterDefinition::Int(Some(100)),
+                    },
+                ),
+                (
+                    "skip",
+                    QueryParameter {
+                        name: "skip",
+                        definition: QueryParameterDefinition::Int(Some(0)),
+                    },
+                ),
+            ]),
+            result: HashMap::from([
+                ("id", STRING_MAPPER),
+                ("name", STRING_MAPPER),
+                ("fqn", STRING_MAPPER),
+                ("definition_type", STRING_MAPPER),
+                ("file_path", STRING_MAPPER),
+                ("start_line", INT_MAPPER),
+                ("end_line", INT_MAPPER),
+                ("language", STRING_MAPPER),
+            ]),
+        }
+    }
+
+    /// Number of definitions in the files under each directory, nested directories
+    /// included. Directories more than 30 levels deep below a directory are not counted
+    /// for it, which is Kuzu's default limit for variable length relationships.
+    pub fn get_directory_definition_counts_query() -> Query {
+        Query {
+            query: r#"
+                MATCH (dir:DirectoryNode)
+                OPTIONAL MATCH (dir)-[:DIRECTORY_RELATIONSHIPS*1..30]->(f:FileNode)-[:FILE_RELATIONSHIPS]->(d:DefinitionNode)
+                RETURN
+                    dir.path as directory_path,
+                    COUNT(d) as definition_count
+                ORDER BY directory_path
+            "#
+            .to_string(),
+            parameters: HashMap::new(),
+            result: HashMap::from([
+                ("directory_path", STRING_MAPPER),
+                ("definition_count", INT_MAPPER),
+            ]),
+        }
+    }
+
+    /// Looks up a definition by its exact FQN. An empty `$file_path` matches any file,
+    /// otherwise only definitions in that file are considered.
+    pub fn get_definition_id_by_fqn_query() -> Query {
+        Query {
+            query: r#"
+                MATCH (d:DefinitionNode)
+                WHERE
+                    d.fqn = $fqn
+                    AND ($file_path = '' OR d.primary_file_path = $file_path)
+                RETURN
+                    d.id as id
+                ORDER BY d.primary_file_path, d.start_line
+                LIMIT 1
+            "#
+            .to_string(),
+            parameters: HashMap::from([
+                (
+                    "fqn",
+                    QueryParameter {
+                        name: "fqn",
+                        definition: QueryParameterDefinition::String(None),
+                    },
+                ),
+                (
+                    "file_path",
+                    QueryParameter {
+                        name: "file_path",
+                        definition: QueryParameterDefinition::String(Some(String::new())),
+                    },
+                ),
+            ]),
+            result: HashMap::from([("id", STRING_MAPPER)]),
+        }
+    }
+
+    /// Looks up a file by its path, relative to the project root or absolute.
+    pub fn get_file_id_by_path_query() -> Query {
+        Query {
+            query: r#"
+                MATCH (f:FileNode)
+                WHERE f.path = $file_path OR f.absolute_path = $file_path
+                RETURN
+                    f.id as id
+                LIMIT 1
+            "#
+            .to_string(),
+            parameters: HashMap::from([(
+                "file_path",
+                QueryParameter {
+                    name: "file_path",
+                    definition: QueryParameterDefinition::String(None),
+                },
+            )]),
+            result: HashMap::from([("id", STRING_MAPPER)]),
+        }
+    }
+
     pub fn get_definitions_by_fqn_or_name_query() -> Query {
         Query {
             query: r#"
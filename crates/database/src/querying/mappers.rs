@@ -11,6 +11,9 @@ pub const INT_MAPPER: QueryResultMapper = |row: &dyn QueryResultRow, index: usiz
     Ok(serde_json::Value::Number(row.get_int_value(index)?.into()))
 };
 
+pub const FLOAT_MAPPER: QueryResultMapper =
+    |row: &dyn QueryResultRow, index: usize| Ok(serde_json::json!(row.get_float_value(index)?));
+
 pub const RELATIONSHIP_TYPE_MAPPER: QueryResultMapper = |row: &dyn QueryResultRow, index: usize| {
     let value: String = row.get_string_value(index)?;
     Ok(serde_json::Value::String(value))
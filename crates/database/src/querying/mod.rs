@@ -1,9 +1,15 @@
+pub mod cache;
 pub mod library;
 pub mod mappers;
+pub mod pool;
 pub mod query_builder;
+pub mod read_only;
 pub mod service;
 pub mod types;
 
+pub use cache::QueryCacheConfig;
 pub use library::*;
+pub use pool::ConnectionPoolConfig;
+pub use read_only::ReadOnlyGraph;
 pub use service::DatabaseQueryingService;
 pub use types::*;
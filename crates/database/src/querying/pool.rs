@@ -0,0 +1,321 @@
+use crate::kuzu::connection::KuzuConnection;
+use crate::kuzu::database::DatabaseChangeListener;
+use anyhow::{Error, Result};
+use kuzu::Database;
+use self_cell::self_cell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_CONNECTION_POOL_SIZE: usize = 8;
+pub const DEFAULT_CONNECTION_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Settings of the connection pool of
+/// [`DatabaseQueryingService`](crate::querying::DatabaseQueryingService)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionPoolConfig {
+    /// Number of idle connections kept per database. More connections are opened when more
+    /// queries run at once, and closed when they are returned to a full pool.
+    pub max_size: usize,
+    /// How long a connection stays idle in the pool before it's closed
+    pub idle_timeout: Duration,
+}
+
+impl Default for ConnectionPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_CONNECTION_POOL_SIZE,
+            idle_timeout: DEFAULT_CONNECTION_IDLE_TIMEOUT,
+        }
+    }
+}
+
+/// Read-only and read-write handles of a database are separate Kuzu databases, so their
+/// connections are pooled apart
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PoolKey {
+    database_path: PathBuf,
+    read_only: bool,
+}
+
+self_cell!(
+    /// A connection holding on to the database it borrows
+    struct OwnedConnection {
+        owner: Arc<Database>,
+
+        #[not_covariant]
+        dependent: KuzuConnection,
+    }
+);
+
+struct IdleConnection {
+    connection: OwnedConnection,
+    idle_since: Instant,
+}
+
+impl IdleConnection {
+    fn open(database: &Arc<Database>) -> Result<Self> {
+        let connection = OwnedConnection::try_new(Arc::clone(database), |database| {
+            KuzuConnection::new(database)
+        })?;
+        Ok(Self {
+            connection,
+            idle_since: Instant::now(),
+        })
+    }
+
+    fn database(&self) -> &Arc<Database> {
+        self.connection.borrow_owner()
+    }
+}
+
+/// Connections to the databases, reused across queries instead of opening one per query
+pub struct ConnectionPool {
+    config: ConnectionPoolConfig,
+    idle: Mutex<HashMap<PoolKey, Vec<IdleConnection>>>,
+    /// Bumped whenever connections are evicted, so connections that were in use at the time
+    /// are closed when they're returned instead of going back to the pool
+    generation: AtomicU64,
+}
+
+impl ConnectionPool {
+    pub fn new(config: ConnectionPoolConfig) -> Self {
+        Self {
+            config,
+            idle: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes an idle connection to `database`, or opens one when there is none. The
+    /// connection goes back to the pool when the returned handle is dropped, including when
+    /// its query failed.
+    pub fn get(
+        &self,
+        database_path: &Path,
+        read_only: bool,
+        database: &Arc<Database>,
+    ) -> Result<PooledConnection<'_>> {
+        let key = PoolKey {
+            database_path: database_path.to_path_buf(),
+            read_only,
+        };
+
+        let idle = {
+            let mut idle = self.idle.lock().unwrap();
+            self.evict_expired(&mut idle);
+            idle.get_mut(&key).and_then(|connections| {
+                // Connections to a database that was replaced since, e.g. by a reindex, are
+                // dropped
+                connections.retain(|connection| Arc::ptr_eq(connection.database(), database));
+                connections.pop()
+            })
+        };
+        let connection = match idle {
+            Some(connection) => connection,
+            None => IdleConnection::open(database).map_err(|e| {
+                Error::msg(format!(
+                    "Failed to create connection to database: {database_path:?}: {e}"
+                ))
+            })?,
+        };
+
+        Ok(PooledConnection {
+            pool: self,
+            key,
+            generation: self.generation.load(Ordering::Acquire),
+            connection: Some(connection),
+        })
+    }
+
+    /// Closes the idle connections to a database, returns how many were closed. The
+    /// connections in use are closed once they're returned.
+    pub fn evict_database(&self, database_path: &Path) -> usize {
+        let mut idle = self.idle.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        let mut evicted = 0;
+        idle.retain(|key, connections| {
+            if key.database_path == database_path {
+                evicted += connections.len();
+                return false;
+            }
+            true
+        });
+        evicted
+    }
+
+    /// Number of idle connections to a database
+    pub fn idle_count(&self, database_path: &Path, read_only: bool) -> usize {
+        let key = PoolKey {
+            database_path: database_path.to_path_buf(),
+            read_only,
+        };
+        self.idle
+            .lock()
+            .unwrap()
+            .get(&key)
+            .map_or(0, |connections| connections.len())
+    }
+
+    fn evict_expired(&self, idle: &mut HashMap<PoolKey, Vec<IdleConnection>>) {
+        let idle_timeout = self.config.idle_timeout;
+        for connections in idle.values_mut() {
+            connections.retain(|connection| connection.idle_since.elapsed() < idle_timeout);
+        }
+        idle.retain(|_, connections| !connections.is_empty());
+    }
+
+    fn put_back(&self, key: PoolKey, generation: u64, mut connection: IdleConnection) {
+        let mut idle = self.idle.lock().unwrap();
+        self.evict_expired(&mut idle);
+        // The database may have been replaced while the connection was in use, e.g. by a
+        // reindex, and the connection would keep the old one open
+        if generation != self.generation.load(Ordering::Acquire) {
+            return;
+        }
+        let connections = idle.entry(key).or_default();
+        if connections.len() < self.config.max_size {
+            connection.idle_since = Instant::now();
+            connections.push(connection);
+        }
+    }
+}
+
+impl DatabaseChangeListener for ConnectionPool {
+    fn database_changed(&self, database_path: &Path) {
+        self.evict_database(database_path);
+    }
+}
+
+/// A connection taken from a [`ConnectionPool`], returned to it on drop
+pub struct PooledConnection<'p> {
+    pool: &'p ConnectionPool,
+    key: PoolKey,
+    generation: u64,
+    connection: Option<IdleConnection>,
+}
+
+impl PooledConnection<'_> {
+    /// Runs `f` with the connection
+    pub fn with_connection<T>(&self, f: impl FnOnce(&KuzuConnection<'_>) -> T) -> T {
+        self.connection
+            .as_ref()
+            .unwrap()
+            .connection
+            .with_dependent(|_, connection| f(connection))
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool
+                .put_back(self.key.clone(), self.generation, connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kuzu::database::KuzuDatabase;
+    use serde_json::Map;
+
+    fn create_database(database: &KuzuDatabase, database_path: &Path) -> Arc<Database> {
+        let database = database
+            .force_new_database(&database_path.to_string_lossy(), None)
+            .unwrap();
+        KuzuConnection::new(&database)
+            .unwrap()
+            .execute_ddl("CREATE NODE TABLE Person(name STRING, PRIMARY KEY(name))")
+            .unwrap();
+        database
+    }
+
+    #[test]
+    fn test_connections_are_reused_and_returned_on_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let kuzu_database = KuzuDatabase::new();
+        let database = create_database(&kuzu_database, &database_path);
+        let pool = ConnectionPool::new(ConnectionPoolConfig {
+            max_size: 1,
+            ..Default::default()
+        });
+
+        {
+            let first = pool.get(&database_path, false, &database).unwrap();
+            let second = pool.get(&database_path, false, &database).unwrap();
+            first
+                .with_connection(|connection| {
+                    connection.generic_query("MATCH (p:Person) RETURN p.name", Map::new())
+                })
+                .unwrap();
+            assert!(
+                second
+                    .with_connection(|connection| {
+                        connection.generic_query("MATCH (p:Missing)", Map::new())
+                    })
+                    .is_err()
+            );
+        }
+        // One of the two connections was closed, the pool only keeps one
+        assert_eq!(pool.idle_count(&database_path, false), 1);
+        assert_eq!(pool.idle_count(&database_path, true), 0);
+
+        let connection = pool.get(&database_path, false, &database).unwrap();
+        assert_eq!(pool.idle_count(&database_path, false), 0);
+        drop(connection);
+        assert_eq!(pool.evict_database(&database_path), 1);
+        assert_eq!(pool.idle_count(&database_path, false), 0);
+    }
+
+    #[test]
+    fn test_idle_and_replaced_connections_are_closed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let kuzu_database = KuzuDatabase::new();
+        let database = create_database(&kuzu_database, &database_path);
+
+        let pool = ConnectionPool::new(ConnectionPoolConfig {
+            idle_timeout: Duration::ZERO,
+            ..Default::default()
+        });
+        drop(pool.get(&database_path, false, &database).unwrap());
+        pool.get(&database_path, false, &database).unwrap();
+        assert_eq!(pool.idle_count(&database_path, false), 1);
+        drop(pool.get(&database_path, true, &database).unwrap());
+        // Expired connections are closed before a connection is handed out or returned
+        assert_eq!(pool.idle_count(&database_path, false), 0);
+
+        let pool = ConnectionPool::new(ConnectionPoolConfig::default());
+        drop(pool.get(&database_path, false, &database).unwrap());
+        let replaced = create_database(&kuzu_database, &temp_dir.path().join("replaced.kz"));
+        let connection = pool.get(&database_path, false, &replaced).unwrap();
+        assert!(Arc::ptr_eq(
+            connection.connection.as_ref().unwrap().database(),
+            &replaced
+        ));
+        drop(connection);
+        assert_eq!(pool.idle_count(&database_path, false), 1);
+    }
+
+    #[test]
+    fn test_connections_in_use_are_closed_after_an_eviction() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        let kuzu_database = KuzuDatabase::new();
+        let database = create_database(&kuzu_database, &database_path);
+        let pool = ConnectionPool::new(ConnectionPoolConfig::default());
+
+        let connection = pool.get(&database_path, false, &database).unwrap();
+        pool.database_changed(&database_path);
+        drop(connection);
+        assert_eq!(pool.idle_count(&database_path, false), 0);
+
+        drop(pool.get(&database_path, false, &database).unwrap());
+        assert_eq!(pool.idle_count(&database_path, false), 1);
+    }
+}
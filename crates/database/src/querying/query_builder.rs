@@ -1,6 +1,6 @@
 use crate::graph::RelationshipType;
 use crate::kuzu::types::{
-    FromKuzuNode, KuzuNodeType, QueryGeneratorResult, QueryNoop, QuoteEscape,
+    FromKuzuNode, KuzuNodeType, QueryGeneratorResult, QueryNoop, QuoteEscape, escape_string_literal,
 };
 use crate::schema::types::{NodeTable, RelationshipTable};
 use tracing::info;
@@ -21,7 +21,7 @@ impl QueryBuilder {
             .iter()
             .map(|val| {
                 if val.needs_quotes() {
-                    format!("'{val}'")
+                    format!("'{}'", escape_string_literal(&val.to_string()))
                 } else {
                     format!("{val}")
                 }
@@ -111,6 +111,30 @@ impl QueryBuilder {
         )
     }
 
+    /// Delete nodes whose column starts with any of the prefixes
+    pub fn delete_by_prefix(
+        &self,
+        node_type: KuzuNodeType,
+        column: &str,
+        prefixes: &[String],
+    ) -> QueryGeneratorResult {
+        if prefixes.is_empty() {
+            return (QueryNoop::Yes, String::new());
+        }
+        let conditions = prefixes
+            .iter()
+            .map(|prefix| format!("n.{column} STARTS WITH '{}'", escape_string_literal(prefix)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        (
+            QueryNoop::No,
+            format!(
+                "MATCH (n:{}) WHERE {conditions} DETACH DELETE n",
+                node_type.as_str(),
+            ),
+        )
+    }
+
     pub fn get_by<T: std::fmt::Display + QuoteEscape, R: FromKuzuNode>(
         &self,
         node_type: KuzuNodeType,
@@ -130,6 +154,86 @@ impl QueryBuilder {
         )
     }
 
+    pub fn get_ids_by<T: std::fmt::Display + QuoteEscape>(
+        &self,
+        node_type: KuzuNodeType,
+        column: &str,
+        values: &[T],
+    ) -> QueryGeneratorResult {
+        if values.is_empty() {
+            return (QueryNoop::Yes, String::new());
+        }
+        let values_str = self.build_values_str(values);
+        (
+            QueryNoop::No,
+            format!(
+                "MATCH (n:{}) WHERE n.{column} IN [{values_str}] RETURN n.id",
+                node_type.as_str(),
+            ),
+        )
+    }
+
+    /// Definitions whose primary location is the file and that no other file defines
+    pub fn get_file_owned_definition_ids(&self, file_path: &str) -> QueryGeneratorResult {
+        let file_path = escape_string_literal(file_path);
+        (
+            QueryNoop::No,
+            format!(
+                "MATCH (n:{definition}) WHERE n.primary_file_path = '{file_path}' \
+                 AND NOT EXISTS {{ MATCH (f:{file})-[:FILE_RELATIONSHIPS]->(n) WHERE f.path <> '{file_path}' }} \
+                 RETURN n.id",
+                definition = KuzuNodeType::DefinitionNode.as_str(),
+                file = KuzuNodeType::FileNode.as_str(),
+            ),
+        )
+    }
+
+    /// Count the relationships touching any of the nodes, each relationship once
+    pub fn count_incident_relationships(
+        &self,
+        node_ids: &[(KuzuNodeType, Vec<u32>)],
+    ) -> Vec<QueryGeneratorResult> {
+        let node_ids = node_ids
+            .iter()
+            .filter(|(_, ids)| !ids.is_empty())
+            .collect::<Vec<_>>();
+        let is_in_set = |variable: &str| {
+            node_ids
+                .iter()
+                .map(|(node_type, ids)| {
+                    format!(
+                        "(label({variable}) = '{}' AND {variable}.id IN [{}])",
+                        node_type.as_str(),
+                        self.build_values_str(ids)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ")
+        };
+
+        let mut queries = Vec::new();
+        for (node_type, ids) in &node_ids {
+            let ids_str = self.build_values_str(ids);
+            // Outgoing relationships, plus incoming ones that don't start in the set
+            queries.push((
+                QueryNoop::No,
+                format!(
+                    "MATCH (n:{})-[r]->(m) WHERE n.id IN [{ids_str}] RETURN COUNT(r)",
+                    node_type.as_str(),
+                ),
+            ));
+            queries.push((
+                QueryNoop::No,
+                format!(
+                    "MATCH (m)-[r]->(n:{}) WHERE n.id IN [{ids_str}] AND NOT ({}) RETURN COUNT(r)",
+                    node_type.as_str(),
+                    is_in_set("m"),
+                ),
+            ));
+        }
+        queries
+    }
+
     pub fn agg_node_by<R: FromKuzuNode>(
         &self,
         agg_func: &str,
@@ -297,4 +401,87 @@ impl QueryBuilder {
 
         (QueryNoop::No, query)
     }
+
+    /// Creates one node per element of the `$rows` parameter, a list of structs keyed by
+    /// column name.
+    pub fn create_nodes_from_rows(&self, table: &NodeTable) -> QueryGeneratorResult {
+        let properties = table
+            .columns
+            .iter()
+            .map(|column| format!("{0}: row.{0}", column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (
+            QueryNoop::No,
+            format!(
+                "UNWIND $rows AS row CREATE (n:{} {{{properties}}})",
+                table.name
+            ),
+        )
+    }
+
+    /// Creates one relationship per element of the `$rows` parameter. Each row holds the
+    /// `source_id` and `target_id` of the nodes to connect and the relationship columns.
+    pub fn create_relationships_from_rows(
+        &self,
+        table: &RelationshipTable,
+        from_table: &NodeTable,
+        to_table: &NodeTable,
+    ) -> QueryGeneratorResult {
+        let (Some(from_key), Some(to_key)) =
+            (from_table.get_primary_key(), to_table.get_primary_key())
+        else {
+            return (QueryNoop::Yes, String::new());
+        };
+
+        let properties = table
+            .columns
+            .iter()
+            .map(|column| format!("{0}: row.{0}", column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (
+            QueryNoop::No,
+            format!(
+                "UNWIND $rows AS row \
+                 MATCH (source:{} {{{from_key}: row.source_id}}), (target:{} {{{to_key}: row.target_id}}) \
+                 CREATE (source)-[:{} {{{properties}}}]->(target)",
+                from_table.name, to_table.name, table.name
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_by_prefix_escapes_the_prefixes() {
+        let (_, query) = QueryBuilder::new().delete_by_prefix(
+            KuzuNodeType::FileNode,
+            "path",
+            &["app/it's/".to_string(), "lib\\".to_string()],
+        );
+        assert_eq!(
+            query,
+            "MATCH (n:FileNode) WHERE n.path STARTS WITH 'app/it\\'s/' \
+             OR n.path STARTS WITH 'lib\\\\' DETACH DELETE n"
+        );
+    }
+
+    #[test]
+    fn test_file_paths_are_escaped() {
+        let builder = QueryBuilder::new();
+        let (_, query) = builder.get_file_owned_definition_ids("app/it's.rb");
+        assert!(query.contains("n.primary_file_path = 'app/it\\'s.rb'"));
+        assert!(query.contains("f.path <> 'app/it\\'s.rb'"));
+
+        let (_, query) =
+            builder.get_ids_by(KuzuNodeType::FileNode, "path", &["app/it's.rb".to_string()]);
+        assert_eq!(
+            query,
+            "MATCH (n:FileNode) WHERE n.path IN ['app/it\\'s.rb'] RETURN n.id"
+        );
+    }
 }
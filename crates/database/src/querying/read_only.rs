@@ -0,0 +1,136 @@
+use crate::{
+    kuzu::{config::DatabaseConfig, database::open_error},
+    querying::{
+        cache::CachedQueryResult,
+        pool::{ConnectionPool, ConnectionPoolConfig},
+        service::DatabaseQueryResult,
+        types::QueryResult,
+    },
+};
+use anyhow::{Error, Result};
+use kuzu::Database;
+use serde_json::Map;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A graph database opened read-only, for tools that query an index built by `gkg` without
+/// going through the server. Queries that write to the graph are rejected by the database.
+///
+/// The database is opened outside of [`KuzuDatabase`](crate::kuzu::database::KuzuDatabase),
+/// so it can't be opened while a writer such as `gkg index` or `gkg server` holds it.
+pub struct ReadOnlyGraph {
+    database: Arc<Database>,
+    database_path: PathBuf,
+    pool: ConnectionPool,
+}
+
+impl ReadOnlyGraph {
+    /// Opens the database of an indexed project. Fails when nothing was indexed at
+    /// `database_path`, since a read-only database can't be created.
+    pub fn open(database_path: &Path) -> Result<Self> {
+        if !database_path.exists() {
+            return Err(Error::msg(format!(
+                "No graph database at {}, the project must be indexed before it can be queried",
+                database_path.display()
+            )));
+        }
+
+        let config = DatabaseConfig::new(database_path.to_string_lossy()).read_only();
+        let database = Database::new(database_path, config.fmt_kuzu_database_config())
+            .map_err(|e| open_error(&database_path.to_string_lossy(), e))?;
+
+        Ok(Self {
+            database: Arc::new(database),
+            database_path: database_path.to_path_buf(),
+            pool: ConnectionPool::new(ConnectionPoolConfig::default()),
+        })
+    }
+
+    pub fn database_path(&self) -> &Path {
+        &self.database_path
+    }
+
+    /// Runs a Cypher query, with `$name` parameters bound from `params`. Connections are
+    /// reused across queries.
+    pub fn execute_query(
+        &self,
+        query: &str,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<Box<dyn QueryResult>> {
+        let connection = self.pool.get(&self.database_path, true, &self.database)?;
+        let result =
+            connection.with_connection(|connection| connection.generic_query(query, params))?;
+
+        Ok(Box::new(DatabaseQueryResult {
+            result: Arc::new(CachedQueryResult {
+                column_names: result.column_names,
+                rows: result.result,
+            }),
+            current_index: 0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kuzu::connection::KuzuConnection;
+    use crate::kuzu::database::KuzuDatabase;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_only_graph_runs_parameterized_queries() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("graph.db");
+        {
+            let database = KuzuDatabase::new();
+            let writable = database
+                .get_or_create_database(&database_path.to_string_lossy(), None)
+                .unwrap();
+            let connection = KuzuConnection::new(&writable).unwrap();
+            connection
+                .execute_ddl("CREATE NODE TABLE Person(name STRING, age INT64, PRIMARY KEY(name))")
+                .unwrap();
+            connection
+                .execute_ddl(
+                    "CREATE (:Person {name: 'Ada', age: 36}), (:Person {name: 'Alan', age: 41})",
+                )
+                .unwrap();
+        }
+
+        let graph = ReadOnlyGraph::open(&database_path).unwrap();
+        let mut params = Map::new();
+        params.insert("min_age".to_string(), json!(40));
+        let mut result = graph
+            .execute_query(
+                "MATCH (p:Person) WHERE p.age >= $min_age RETURN p.name, p.age",
+                params,
+            )
+            .unwrap();
+
+        assert_eq!(result.get_column_names(), &vec!["p.name", "p.age"]);
+        let row = result.next().unwrap();
+        assert_eq!(row.get_string_value(0).unwrap(), "Alan");
+        assert_eq!(row.get_int_value(1).unwrap(), 41);
+        assert!(result.next().is_none());
+
+        assert!(
+            graph
+                .execute_query("CREATE (:Person {name: 'Grace', age: 45})", Map::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_read_only_graph_requires_an_indexed_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let error = ReadOnlyGraph::open(&temp_dir.path().join("missing.db"))
+            .err()
+            .unwrap();
+
+        assert!(error.to_string().contains("must be indexed"));
+    }
+}
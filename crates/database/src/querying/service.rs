@@ -1,28 +1,35 @@
 use crate::{
     kuzu::{connection::KuzuConnection, database::KuzuDatabase},
-    querying::types::{QueryResult, QueryResultRow, QueryingService},
+    querying::{
+        cache::{CachedQueryResult, QueryCache, QueryCacheConfig},
+        pool::{ConnectionPool, ConnectionPoolConfig},
+        types::{QueryResult, QueryResultRow, QueryingService},
+    },
 };
 use anyhow::{Error, Result};
 use serde_json::Map;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    time::Duration,
+};
 
-struct DatabaseQueryResult {
-    column_names: Vec<String>,
-    result: Vec<Vec<kuzu::Value>>,
-    current_index: usize,
+pub(crate) struct DatabaseQueryResult {
+    pub(crate) result: Arc<CachedQueryResult>,
+    pub(crate) current_index: usize,
 }
 
 impl QueryResult for DatabaseQueryResult {
     fn get_column_names(&self) -> &Vec<String> {
-        &self.column_names
+        &self.result.column_names
     }
 
     fn next(&mut self) -> Option<Box<dyn QueryResultRow>> {
-        if self.current_index >= self.result.len() {
+        if self.current_index >= self.result.rows.len() {
             return None;
         }
 
-        let row = self.result[self.current_index].clone();
+        let row = self.result.rows[self.current_index].clone();
         self.current_index += 1;
 
         Some(Box::new(DatabaseQueryResultRow { row }))
@@ -51,6 +58,17 @@ impl QueryResultRow for DatabaseQueryResultRow {
         }
     }
 
+    fn get_float_value(&self, index: usize) -> Result<f64, Error> {
+        match &self.row[index] {
+            kuzu::Value::Double(value) => Ok(*value),
+            kuzu::Value::Float(value) => Ok((*value).into()),
+            _ => Err(Error::msg(format!(
+                "Expected index {} to be a floating point value, got: {:?}",
+                index, self.row[index]
+            ))),
+        }
+    }
+
     fn get_uint_value(&self, index: usize) -> Result<u64, Error> {
         match &self.row[index] {
             kuzu::Value::UInt64(value) => Ok(*value),
@@ -70,47 +88,169 @@ impl QueryResultRow for DatabaseQueryResultRow {
 }
 pub struct DatabaseQueryingService {
     database: Arc<KuzuDatabase>,
+    cache: Option<Arc<QueryCache>>,
+    pool: Option<Arc<ConnectionPool>>,
+    query_timeout: Option<Duration>,
 }
 
 /// This service should only be used for uncontrolled query execution (e.g., MCP, Playground, API endpoints).
 /// For controlled query execution with strict typing for arguments and return types, a proper service should be created instead.
 impl DatabaseQueryingService {
     pub fn new(database: Arc<KuzuDatabase>) -> Self {
-        Self { database }
+        Self {
+            database,
+            cache: None,
+            pool: None,
+            query_timeout: None,
+        }
     }
-}
 
-impl QueryingService for DatabaseQueryingService {
-    fn execute_query(
+    /// Serves repeated queries from an LRU cache of their results. The results of a database
+    /// are dropped when they expire, and as soon as the database is written through the
+    /// shared [`KuzuDatabase`], see [`KuzuDatabase::notify_changed`].
+    pub fn with_cache(mut self, config: QueryCacheConfig) -> Self {
+        let cache = Arc::new(QueryCache::new(config));
+        let listener: Weak<QueryCache> = Arc::downgrade(&cache);
+        self.database.add_change_listener(listener);
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Reuses connections to the databases across queries instead of opening one per query.
+    /// The connections to a database are closed once it changes.
+    pub fn with_connection_pool(mut self, config: ConnectionPoolConfig) -> Self {
+        let pool = Arc::new(ConnectionPool::new(config));
+        let listener: Weak<ConnectionPool> = Arc::downgrade(&pool);
+        self.database.add_change_listener(listener);
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Interrupts the queries running longer than `timeout`, which then fail
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    /// Drops the cached results of a database, returns how many were dropped. The idle
+    /// connections to the database are closed too.
+    pub fn invalidate_database(&self, database_path: &Path) -> usize {
+        if let Some(pool) = &self.pool {
+            pool.evict_database(database_path);
+        }
+        self.cache
+            .as_ref()
+            .map_or(0, |cache| cache.invalidate_database(database_path))
+    }
+
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    fn fetch(
         &self,
-        database_path: PathBuf,
-        query: String,
+        database_path: &Path,
+        query: &str,
         params: Map<String, serde_json::Value>,
-    ) -> Result<Box<dyn QueryResult>, Error> {
+    ) -> Result<CachedQueryResult, Error> {
+        let result = self.with_connection(database_path, |connection| {
+            connection.generic_query(query, params)
+        })?;
+        Ok(CachedQueryResult {
+            column_names: result.column_names,
+            rows: result.result,
+        })
+    }
+
+    /// Runs `f` with a connection to the database, taken from the pool when there is one.
+    /// Fails with [`DatabaseError::NotIndexed`](crate::kuzu::types::DatabaseError::NotIndexed)
+    /// when the project has no database yet.
+    fn with_connection<T>(
+        &self,
+        database_path: &Path,
+        f: impl FnOnce(&KuzuConnection<'_>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
         let database = self
             .database
-            .get_or_create_database(database_path.to_str().unwrap(), None);
-        if database.is_none() {
-            return Err(Error::msg(format!(
-                "Database not found for path: {database_path:?}"
-            )));
+            .get_indexed_database(database_path.to_str().unwrap(), None)?;
+
+        if let Some(pool) = &self.pool {
+            let connection = pool.get(database_path, false, &database)?;
+            return connection.with_connection(|connection| {
+                if let Some(timeout) = self.query_timeout {
+                    connection.set_query_timeout(timeout);
+                }
+                f(connection)
+            });
         }
 
-        let database = database.unwrap();
-        let connection = KuzuConnection::new(&database);
-        if connection.is_err() {
-            return Err(Error::msg(format!(
+        let connection = KuzuConnection::new(&database).map_err(|_| {
+            Error::msg(format!(
                 "Failed to create connection to database: {database_path:?}"
-            )));
+            ))
+        })?;
+        if let Some(timeout) = self.query_timeout {
+            connection.set_query_timeout(timeout);
         }
+        f(&connection)
+    }
+}
 
-        let connection = connection.unwrap();
+impl QueryingService for DatabaseQueryingService {
+    fn execute_query(
+        &self,
+        database_path: PathBuf,
+        query: String,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<Box<dyn QueryResult>, Error> {
+        let Some(cache) = &self.cache else {
+            return self.execute_query_uncached(database_path, query, params);
+        };
 
-        let result = connection.generic_query(query.as_str(), params)?;
+        let result = match cache.get(&database_path, &query, &params) {
+            Some(result) => result,
+            None => {
+                let generation = cache.generation();
+                let result = Arc::new(self.fetch(&database_path, &query, params.clone())?);
+                cache.insert(
+                    &database_path,
+                    &query,
+                    &params,
+                    Arc::clone(&result),
+                    generation,
+                );
+                result
+            }
+        };
         Ok(Box::new(DatabaseQueryResult {
-            column_names: result.column_names,
-            result: result.result,
+            result,
             current_index: 0,
         }))
     }
+
+    fn execute_query_uncached(
+        &self,
+        database_path: PathBuf,
+        query: String,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<Box<dyn QueryResult>, Error> {
+        let result = self.fetch(&database_path, &query, params)?;
+        Ok(Box::new(DatabaseQueryResult {
+            result: Arc::new(result),
+            current_index: 0,
+        }))
+    }
+
+    fn explain_query(
+        &self,
+        database_path: PathBuf,
+        query: String,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<String, Error> {
+        self.with_connection(&database_path, |connection| {
+            connection.explain(query.as_str(), params)
+        })
+    }
 }
@@ -10,6 +10,25 @@ pub trait QueryingService: Send + Sync {
         query: String,
         params: Map<String, serde_json::Value>,
     ) -> Result<Box<dyn QueryResult>, Error>;
+
+    /// Runs the query against the database even when the service caches results, for reads that
+    /// must see the latest index
+    fn execute_query_uncached(
+        &self,
+        database_path: PathBuf,
+        query: String,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<Box<dyn QueryResult>, Error> {
+        self.execute_query(database_path, query, params)
+    }
+
+    /// The plan of the query, without fetching its results
+    fn explain_query(
+        &self,
+        database_path: PathBuf,
+        query: String,
+        params: Map<String, serde_json::Value>,
+    ) -> Result<String, Error>;
 }
 
 pub trait QueryResult: Send + Sync {
@@ -42,6 +61,7 @@ pub trait QueryResultRow: Send + Sync {
     fn get_string_value(&self, index: usize) -> Result<String, Error>;
     fn get_int_value(&self, index: usize) -> Result<i64, Error>;
     fn get_uint_value(&self, index: usize) -> Result<u64, Error>;
+    fn get_float_value(&self, index: usize) -> Result<f64, Error>;
     fn count(&self) -> usize;
 }
 
@@ -24,6 +24,10 @@ pub static FILE_TABLE: NodeTable = NodeTable {
         ColumnDefinition::new("repository_name"),
         ColumnDefinition::new("extension"),
         ColumnDefinition::new("name"),
+        ColumnDefinition::new("content_hash"),
+        // Stamped once the graph is imported, see `NodeDatabaseService::record_index_time`
+        ColumnDefinition::new("indexed_at").int64(),
+        ColumnDefinition::new("content_offset").int64(),
     ],
 };
 
@@ -43,6 +47,9 @@ pub static DEFINITION_TABLE: NodeTable = NodeTable {
         ColumnDefinition::new("start_col").int32(),
         ColumnDefinition::new("end_col").int32(),
         ColumnDefinition::new("total_locations").int32(),
+        // Computed once the graph is imported, see `NodeDatabaseService::compute_call_centrality`
+        ColumnDefinition::new("call_degree").int32(),
+        ColumnDefinition::new("call_betweenness").double(),
     ],
 };
 
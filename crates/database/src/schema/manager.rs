@@ -3,11 +3,16 @@ use crate::kuzu::types::DatabaseError;
 use crate::kuzu::types::QueryNoop;
 use crate::querying::query_builder::QueryBuilder;
 use crate::schema::init::{NODE_TABLES, RELATIONSHIP_TABLES};
-use crate::schema::types::{NodeTable, RelationshipTable, SchemaStats};
+use crate::schema::types::{GraphRecordBatches, NodeTable, RelationshipTable, SchemaStats};
 use dunce;
 use kuzu::Database;
 use tracing::{info, warn};
 
+/// Version of the graph schema created by [`SchemaManager`]. Bump it whenever a node or
+/// relationship table, or one of their columns, changes, so that projects indexed with an
+/// older schema are flagged for reindexing.
+pub const SCHEMA_VERSION: u32 = 4;
+
 /// Manages database schema creation and operations
 pub struct SchemaManager<'a> {
     database: &'a Database,
@@ -124,10 +129,13 @@ impl<'a> SchemaManager<'a> {
         Ok(())
     }
 
-    /// Import graph data from Parquet files
+    /// Import graph data from Parquet files. The import runs in a single transaction, so
+    /// a failure rolls back to the data present before the import. Relationship tables that
+    /// fail to load are reported and skipped rather than failing the import.
     pub fn import_graph_data(&self, parquet_dir: &str) -> Result<(), DatabaseError> {
         self._init_import_graph_data(parquet_dir)?;
-        self.import_nodes_and_relationships(parquet_dir, None)?;
+        self.import_nodes_and_relationships(parquet_dir, None)
+            .map_err(|e| DatabaseError::ImportRolledBack(e.to_string()))?;
         info!("Successfully imported graph data from Parquet files");
         Ok(())
     }
@@ -144,6 +152,46 @@ impl<'a> SchemaManager<'a> {
         Ok(())
     }
 
+    /// Import graph data from in-memory batches instead of Parquet files. Like
+    /// [`Self::import_graph_data`], a failure rolls back the whole import.
+    pub fn import_graph_batches(&self, batches: &GraphRecordBatches) -> Result<(), DatabaseError> {
+        self.get_connection()
+            .transaction(|conn| Self::insert_graph_batches(conn, batches))
+            .map_err(|e| DatabaseError::ImportRolledBack(e.to_string()))?;
+        info!("Successfully imported graph data from memory");
+        Ok(())
+    }
+
+    // Import in-memory graph data with an existing connection, used for re-indexing
+    pub fn import_graph_batches_with_existing_connection(
+        &self,
+        batches: &GraphRecordBatches,
+        existing_connection: &mut KuzuConnection,
+    ) -> Result<(), DatabaseError> {
+        Self::insert_graph_batches(existing_connection, batches)?;
+        info!("Successfully imported graph data from memory");
+        Ok(())
+    }
+
+    fn insert_graph_batches(
+        transaction_conn: &KuzuConnection,
+        batches: &GraphRecordBatches,
+    ) -> Result<(), DatabaseError> {
+        // Relationships match on node ids, so every node has to exist first
+        for (table, batch) in &batches.nodes {
+            transaction_conn.insert_nodes_from_batch(table, batch)?;
+        }
+        for relationships in &batches.relationships {
+            transaction_conn.insert_relationships_from_batch(
+                relationships.table,
+                relationships.from_table,
+                relationships.to_table,
+                &relationships.batch,
+            )?;
+        }
+        Ok(())
+    }
+
     // Import nodes and relationships in a single transaction
     fn import_nodes_and_relationships(
         &self,
@@ -155,11 +203,8 @@ impl<'a> SchemaManager<'a> {
             self.import_relationships(connection, parquet_dir)?;
         } else {
             self.get_connection().transaction(|conn| {
-                self.import_nodes(conn, parquet_dir)
-                    .expect("Failed to import nodes");
+                self.import_nodes(conn, parquet_dir)?;
                 self.import_relationships(conn, parquet_dir)
-                    .expect("Failed to import relationships");
-                Ok(())
             })?;
         }
         Ok(())
@@ -209,6 +254,7 @@ impl<'a> SchemaManager<'a> {
         transaction_conn: &KuzuConnection,
         parquet_dir: &str,
     ) -> Result<(), DatabaseError> {
+        let mut failed_tables = Vec::new();
         for table in RELATIONSHIP_TABLES.iter() {
             for (from, to) in table.from_to_pairs {
                 let filename = from.relationship_filename(to);
@@ -235,10 +281,14 @@ impl<'a> SchemaManager<'a> {
                             "Successfully imported {} ({} -> {})",
                             table.name, from.name, to.name
                         ),
-                        Err(e) => warn!(
-                            "Failed to import {} ({} -> {}): {}",
-                            table.name, from.name, to.name, e
-                        ),
+                        Err(e) => {
+                            warn!(
+                                "Failed to import {} ({} -> {}), continuing without it: {}",
+                                table.name, from.name, to.name, e
+                            );
+                            failed_tables
+                                .push(format!("{} ({} -> {})", table.name, from.name, to.name));
+                        }
                     }
                 } else {
                     warn!(
@@ -250,7 +300,15 @@ impl<'a> SchemaManager<'a> {
             }
         }
 
-        info!("Successfully imported all available consolidated relationship data");
+        if failed_tables.is_empty() {
+            info!("Successfully imported all available consolidated relationship data");
+        } else {
+            warn!(
+                "Imported the consolidated relationship data except for {} tables that failed to load: {}",
+                failed_tables.len(),
+                failed_tables.join(", ")
+            );
+        }
         Ok(())
     }
 
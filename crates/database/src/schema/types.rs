@@ -1,5 +1,5 @@
 use arrow::{
-    array::{Array, Int32Array, Int64Array, StringArray, UInt8Array, UInt32Array},
+    array::{Array, Float64Array, Int32Array, Int64Array, StringArray, UInt8Array, UInt32Array},
     datatypes::{DataType, Field, Schema},
     record_batch::RecordBatch,
 };
@@ -25,6 +25,12 @@ pub trait NodeFieldAccess {
         None
     }
 
+    /// Extract an f64 field value
+    fn get_f64_field(&self, field_name: &str) -> Option<f64> {
+        let _ = field_name;
+        None
+    }
+
     /// Extract a u32 field value
     fn get_u32_field(&self, field_name: &str) -> Option<u32> {
         let _ = field_name;
@@ -109,6 +115,13 @@ where
                         .collect();
                     Arc::new(Int64Array::from(values))
                 }
+                KuzuDataType::Double => {
+                    let values: Vec<f64> = nodes
+                        .iter()
+                        .map(|node| node.get_f64_field(column.name).unwrap_or(0.0))
+                        .collect();
+                    Arc::new(Float64Array::from(values))
+                }
                 KuzuDataType::UInt8 => {
                     let values: Vec<u8> = nodes
                         .iter()
@@ -216,6 +229,22 @@ where
     // Uses the default implementation
 }
 
+/// Graph data converted to Arrow batches, imported into Kuzu without writing Parquet files
+#[derive(Debug, Default)]
+pub struct GraphRecordBatches {
+    pub nodes: Vec<(&'static NodeTable, RecordBatch)>,
+    pub relationships: Vec<RelationshipRecordBatch>,
+}
+
+/// The relationships of one FROM/TO pair of a relationship table
+#[derive(Debug)]
+pub struct RelationshipRecordBatch {
+    pub table: &'static RelationshipTable,
+    pub from_table: &'static NodeTable,
+    pub to_table: &'static NodeTable,
+    pub batch: RecordBatch,
+}
+
 /// Represents a Kuzu node table definition
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct NodeTable {
@@ -0,0 +1,94 @@
+//! A tiny graph of a Ruby project, written straight into an in-memory database so tests can
+//! query a populated graph without parsing and indexing a repository. It contains:
+//!
+//! - `main.rb`, defining the `Checkout` class and its `run` method, which calls
+//!   `Billing::charge` and requires `app/billing`
+//! - `app/billing.rb`, defining the `Billing` class and its `charge` and `refund` methods
+
+use crate::kuzu::{connection::KuzuConnection, database::KuzuDatabase};
+use crate::schema::manager::SchemaManager;
+use anyhow::{Result, anyhow};
+use kuzu::Database;
+use std::sync::Arc;
+
+pub const FIXTURE_FILE_COUNT: u32 = 2;
+pub const FIXTURE_DEFINITION_COUNT: u32 = 5;
+pub const FIXTURE_IMPORTED_SYMBOL_COUNT: u32 = 1;
+
+/// `{project}` is replaced by the project path, `{repository}` by its directory name
+const FIXTURE_GRAPH_STATEMENTS: &[&str] = &[
+    "CREATE (:DirectoryNode {id: 1, path: 'app', absolute_path: '{project}/app', repository_name: '{repository}', name: 'app'})",
+    "CREATE (:FileNode {id: 1, path: 'main.rb', absolute_path: '{project}/main.rb', language: 'ruby', repository_name: '{repository}', extension: 'rb', name: 'main.rb'})",
+    "CREATE (:FileNode {id: 2, path: 'app/billing.rb', absolute_path: '{project}/app/billing.rb', language: 'ruby', repository_name: '{repository}', extension: 'rb', name: 'billing.rb'})",
+    "CREATE (:DefinitionNode {id: 1, fqn: 'Checkout', name: 'Checkout', definition_type: 'Class', primary_file_path: 'main.rb', primary_start_byte: 36, primary_end_byte: 103, start_line: 3, end_line: 7, start_col: 0, end_col: 3, total_locations: 1, call_degree: 0, call_betweenness: 0.0})",
+    "CREATE (:DefinitionNode {id: 2, fqn: 'Checkout::run', name: 'run', definition_type: 'Method', primary_file_path: 'main.rb', primary_start_byte: 53, primary_end_byte: 99, start_line: 4, end_line: 6, start_col: 2, end_col: 5, total_locations: 1, call_degree: 1, call_betweenness: 0.0})",
+    "CREATE (:DefinitionNode {id: 3, fqn: 'Billing', name: 'Billing', definition_type: 'Class', primary_file_path: 'app/billing.rb', primary_start_byte: 0, primary_end_byte: 84, start_line: 1, end_line: 9, start_col: 0, end_col: 3, total_locations: 1, call_degree: 0, call_betweenness: 0.0})",
+    "CREATE (:DefinitionNode {id: 4, fqn: 'Billing::charge', name: 'charge', definition_type: 'Method', primary_file_path: 'app/billing.rb', primary_start_byte: 16, primary_end_byte: 45, start_line: 2, end_line: 4, start_col: 2, end_col: 5, total_locations: 1, call_degree: 1, call_betweenness: 0.0})",
+    "CREATE (:DefinitionNode {id: 5, fqn: 'Billing::refund', name: 'refund', definition_type: 'Method', primary_file_path: 'app/billing.rb', primary_start_byte: 49, primary_end_byte: 80, start_line: 6, end_line: 8, start_col: 2, end_col: 5, total_locations: 1, call_degree: 0, call_betweenness: 0.0})",
+    "CREATE (:ImportedSymbolNode {id: 1, import_type: 'RequireRelative', import_path: 'app/billing', name: 'billing', alias: '', file_path: 'main.rb', start_byte: 0, end_byte: 34, start_line: 1, end_line: 1, start_col: 0, end_col: 34})",
+    "MATCH (a:DirectoryNode {id: 1}), (b:FileNode {id: 2}) CREATE (a)-[:DIRECTORY_RELATIONSHIPS {type: 'DIR_CONTAINS_FILE'}]->(b)",
+    "MATCH (a:FileNode {id: 1}), (b:DefinitionNode {id: 1}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_DEFINES'}]->(b)",
+    "MATCH (a:FileNode {id: 1}), (b:DefinitionNode {id: 2}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_DEFINES'}]->(b)",
+    "MATCH (a:FileNode {id: 2}), (b:DefinitionNode {id: 3}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_DEFINES'}]->(b)",
+    "MATCH (a:FileNode {id: 2}), (b:DefinitionNode {id: 4}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_DEFINES'}]->(b)",
+    "MATCH (a:FileNode {id: 2}), (b:DefinitionNode {id: 5}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_DEFINES'}]->(b)",
+    "MATCH (a:FileNode {id: 1}), (b:ImportedSymbolNode {id: 1}) CREATE (a)-[:FILE_RELATIONSHIPS {type: 'FILE_IMPORTS'}]->(b)",
+    "MATCH (a:DefinitionNode {id: 1}), (b:DefinitionNode {id: 2}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CLASS_TO_METHOD'}]->(b)",
+    "MATCH (a:DefinitionNode {id: 3}), (b:DefinitionNode {id: 4}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CLASS_TO_METHOD'}]->(b)",
+    "MATCH (a:DefinitionNode {id: 3}), (b:DefinitionNode {id: 5}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CLASS_TO_METHOD'}]->(b)",
+    "MATCH (a:DefinitionNode {id: 2}), (b:DefinitionNode {id: 4}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {type: 'CALLS', source_start_byte: 71, source_end_byte: 91, source_start_line: 5, source_end_line: 5, source_start_col: 4, source_end_col: 24}]->(b)",
+    "MATCH (a:ImportedSymbolNode {id: 1}), (b:FileNode {id: 2}) CREATE (a)-[:IMPORTED_SYMBOL_RELATIONSHIPS {type: 'IMPORTED_SYMBOL_TO_FILE'}]->(b)",
+];
+
+/// Registers an in-memory database under `database_path` holding the fixture graph, so
+/// anything opening `database_path` through `database` queries the fixture. `project_path`
+/// prefixes the absolute paths of the graph.
+pub fn load_fixture_graph(
+    database: &KuzuDatabase,
+    database_path: &str,
+    project_path: &str,
+) -> Result<Arc<Database>> {
+    let fixture_database = database
+        .create_in_memory_database(database_path)
+        .ok_or_else(|| anyhow!("Failed to create an in-memory database for {database_path}"))?;
+    SchemaManager::new(&fixture_database).initialize_schema()?;
+
+    let repository_name = std::path::Path::new(project_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("unknown");
+    let connection = KuzuConnection::new(&fixture_database)?;
+    for statement in FIXTURE_GRAPH_STATEMENTS {
+        let statement = statement
+            .replace("{project}", &project_path.replace('\'', "\\'"))
+            .replace("{repository}", &repository_name.replace('\'', "\\'"));
+        connection.execute_ddl(&statement)?;
+    }
+    Ok(fixture_database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kuzu::service::NodeDatabaseService;
+
+    #[test]
+    fn test_fixture_graph_is_queryable() {
+        let database = KuzuDatabase::new();
+        let fixture_database =
+            load_fixture_graph(&database, "/data/fixture.kz", "/work/fixture-repo").unwrap();
+
+        let counts = NodeDatabaseService::new(&fixture_database)
+            .get_node_counts()
+            .unwrap();
+        assert_eq!(counts.file_count, FIXTURE_FILE_COUNT);
+        assert_eq!(counts.definition_count, FIXTURE_DEFINITION_COUNT);
+        assert_eq!(counts.imported_symbol_count, FIXTURE_IMPORTED_SYMBOL_COUNT);
+
+        // Later lookups of the path get the fixture instead of opening a database on disk
+        let reopened = database
+            .get_or_create_database("/data/fixture.kz", None)
+            .unwrap();
+        assert!(Arc::ptr_eq(&reopened, &fixture_database));
+    }
+}
@@ -1,4 +1,9 @@
-use std::{path::PathBuf, sync::RwLock};
+pub mod fixture_graph;
+
+use std::{
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+};
 
 use crate::{querying::QueryResult, querying::QueryResultRow, querying::QueryingService};
 use anyhow::{Error, anyhow};
@@ -7,6 +12,36 @@ use serde_json::{Map, Value};
 type ReturnData = Vec<Vec<String>>;
 type ColumnNames = Vec<String>;
 
+/// Selects the queries a registered result is returned for
+#[derive(Debug, Clone)]
+pub enum QueryMatcher {
+    Exact(String),
+    Contains(String),
+}
+
+impl QueryMatcher {
+    pub fn matches(&self, query: &str) -> bool {
+        match self {
+            QueryMatcher::Exact(expected) => query == expected,
+            QueryMatcher::Contains(fragment) => query.contains(fragment.as_str()),
+        }
+    }
+}
+
+struct QueryMapping {
+    matcher: QueryMatcher,
+    column_names: ColumnNames,
+    data: ReturnData,
+}
+
+/// A call to [`MockQueryingService::execute_query`], in the order it was made
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedQuery {
+    pub project_path: PathBuf,
+    pub query: String,
+    pub params: Map<String, Value>,
+}
+
 pub struct MockQueryingService {
     pub should_fail: bool,
     pub expected_project_path: Option<String>,
@@ -14,6 +49,8 @@ pub struct MockQueryingService {
     pub expected_params: Option<Map<String, Value>>,
     pub return_data: RwLock<Vec<ReturnData>>,
     pub column_names: RwLock<Vec<ColumnNames>>,
+    query_mappings: Vec<QueryMapping>,
+    recorded_queries: Option<Mutex<Vec<RecordedQuery>>>,
 }
 
 impl Default for MockQueryingService {
@@ -31,6 +68,8 @@ impl MockQueryingService {
             expected_params: None,
             return_data: RwLock::new(vec![vec![vec!["test_value".to_string()]]]),
             column_names: RwLock::new(vec![vec!["test_column".to_string()]]),
+            query_mappings: Vec::new(),
+            recorded_queries: None,
         }
     }
 
@@ -56,6 +95,36 @@ impl MockQueryingService {
         self.return_data.write().unwrap().push(data);
         self
     }
+
+    /// Returns `data` for every query matched by `matcher`. Once a mapping is registered, the
+    /// first matching mapping answers each query and a query matching none of them panics.
+    pub fn with_query_result(
+        mut self,
+        matcher: QueryMatcher,
+        column_names: Vec<String>,
+        data: Vec<Vec<String>>,
+    ) -> Self {
+        self.query_mappings.push(QueryMapping {
+            matcher,
+            column_names,
+            data,
+        });
+        self
+    }
+
+    /// Records the executed queries, to be read with [`Self::recorded_queries`]
+    pub fn with_call_recording(mut self) -> Self {
+        self.recorded_queries = Some(Mutex::new(Vec::new()));
+        self
+    }
+
+    /// The executed queries in call order, empty unless recording was enabled
+    pub fn recorded_queries(&self) -> Vec<RecordedQuery> {
+        self.recorded_queries
+            .as_ref()
+            .map(|recorded_queries| recorded_queries.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
 }
 
 impl QueryingService for MockQueryingService {
@@ -84,11 +153,43 @@ impl QueryingService for MockQueryingService {
             assert_eq!(&params, expected_params, "Parameters mismatch");
         }
 
+        if let Some(recorded_queries) = &self.recorded_queries {
+            recorded_queries.lock().unwrap().push(RecordedQuery {
+                project_path,
+                query: query.clone(),
+                params,
+            });
+        }
+
+        if !self.query_mappings.is_empty() {
+            let mapping = self
+                .query_mappings
+                .iter()
+                .find(|mapping| mapping.matcher.matches(&query))
+                .unwrap_or_else(|| panic!("No result registered for query: {query}"));
+            return Ok(Box::new(MockQueryResult::new(
+                mapping.column_names.clone(),
+                mapping.data.clone(),
+            )));
+        }
+
         Ok(Box::new(MockQueryResult::new(
             self.column_names.write().unwrap().pop().unwrap(),
             self.return_data.write().unwrap().pop().unwrap(),
         )))
     }
+
+    fn explain_query(
+        &self,
+        _project_path: PathBuf,
+        query: String,
+        _params: Map<String, Value>,
+    ) -> Result<String, Error> {
+        if self.should_fail {
+            return Err(anyhow!("Mock query service failure"));
+        }
+        Ok(format!("Mock plan of: {query}"))
+    }
 }
 
 pub struct MockQueryResultRow {
@@ -117,6 +218,13 @@ impl QueryResultRow for MockQueryResultRow {
             .ok_or_else(|| anyhow!("Index {} out of bounds", index))
     }
 
+    fn get_float_value(&self, index: usize) -> Result<f64, Error> {
+        self.values
+            .get(index)
+            .and_then(|value| value.parse::<f64>().ok())
+            .ok_or_else(|| anyhow!("Index {} out of bounds", index))
+    }
+
     fn count(&self) -> usize {
         self.values.len()
     }
@@ -150,3 +258,78 @@ impl QueryResult for MockQueryResult {
         self.rows.next()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_value(result: &mut Box<dyn QueryResult>) -> String {
+        result.next().unwrap().get_string_value(0).unwrap()
+    }
+
+    #[test]
+    fn test_each_query_gets_its_registered_result() {
+        let service = MockQueryingService::new()
+            .with_query_result(
+                QueryMatcher::Contains("DefinitionNode".to_string()),
+                vec!["name".to_string()],
+                vec![vec!["Billing".to_string()]],
+            )
+            .with_query_result(
+                QueryMatcher::Exact("MATCH (f:FileNode) RETURN f.path".to_string()),
+                vec!["path".to_string()],
+                vec![vec!["app/billing.rb".to_string()]],
+            )
+            .with_call_recording();
+
+        let mut params = Map::new();
+        params.insert("limit".to_string(), 10.into());
+        let mut definitions = service
+            .execute_query(
+                PathBuf::from("/data/a.kz"),
+                "MATCH (d:DefinitionNode) RETURN d.name LIMIT $limit".to_string(),
+                params.clone(),
+            )
+            .unwrap();
+        let mut files = service
+            .execute_query(
+                PathBuf::from("/data/a.kz"),
+                "MATCH (f:FileNode) RETURN f.path".to_string(),
+                Map::new(),
+            )
+            .unwrap();
+
+        assert_eq!(first_value(&mut definitions), "Billing");
+        assert_eq!(first_value(&mut files), "app/billing.rb");
+        assert_eq!(
+            service.recorded_queries(),
+            vec![
+                RecordedQuery {
+                    project_path: PathBuf::from("/data/a.kz"),
+                    query: "MATCH (d:DefinitionNode) RETURN d.name LIMIT $limit".to_string(),
+                    params,
+                },
+                RecordedQuery {
+                    project_path: PathBuf::from("/data/a.kz"),
+                    query: "MATCH (f:FileNode) RETURN f.path".to_string(),
+                    params: Map::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No result registered for query")]
+    fn test_unmatched_query_panics() {
+        let service = MockQueryingService::new().with_query_result(
+            QueryMatcher::Exact("MATCH (f:FileNode) RETURN f".to_string()),
+            vec!["f".to_string()],
+            Vec::new(),
+        );
+        let _ = service.execute_query(
+            PathBuf::from("/data/a.kz"),
+            "MATCH (d:DefinitionNode) RETURN d".to_string(),
+            Map::new(),
+        );
+    }
+}
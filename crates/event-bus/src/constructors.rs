@@ -0,0 +1,211 @@
+//! Constructors for [`GkgEvent`] that wrap the payload in the right variants and stamp it
+//! with the current time, so call sites can't send e.g. a `Started` payload as `Completed`.
+
+use crate::types::project_info::to_ts_project_info;
+use crate::types::workspace_folder::to_ts_workspace_folder_info;
+use crate::{
+    GkgEvent, GraphEvent, GraphUpdated, ProjectIndexingCompleted, ProjectIndexingEvent,
+    ProjectIndexingFailed, ProjectIndexingStarted, ProjectReindexingCompleted,
+    ProjectReindexingEvent, ProjectReindexingFailed, ProjectReindexingStarted, WatcherEvent,
+    WatcherLost, WatcherRecovered, WorkspaceIndexingCompleted, WorkspaceIndexingEvent,
+    WorkspaceIndexingFailed, WorkspaceIndexingStarted, WorkspaceReindexingCompleted,
+    WorkspaceReindexingEvent, WorkspaceReindexingFailed, WorkspaceReindexingStarted,
+};
+use chrono::Utc;
+use std::path::Path;
+use workspace_manager::{ProjectInfo, WorkspaceFolderInfo};
+
+fn project_paths(projects: &[ProjectInfo]) -> Vec<String> {
+    projects.iter().map(|p| p.project_path.clone()).collect()
+}
+
+impl GkgEvent {
+    pub fn workspace_indexing_started(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        projects_to_process: &[ProjectInfo],
+    ) -> Self {
+        GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Started(WorkspaceIndexingStarted {
+            workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+            projects_to_process: project_paths(projects_to_process),
+            started_at: Utc::now(),
+        }))
+    }
+
+    pub fn workspace_indexing_completed(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        projects_indexed: &[ProjectInfo],
+    ) -> Self {
+        GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Completed(
+            WorkspaceIndexingCompleted {
+                workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+                projects_indexed: project_paths(projects_indexed),
+                completed_at: Utc::now(),
+            },
+        ))
+    }
+
+    pub fn workspace_indexing_failed(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        projects_indexed: &[ProjectInfo],
+        error: String,
+    ) -> Self {
+        GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Failed(WorkspaceIndexingFailed {
+            workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+            projects_indexed: project_paths(projects_indexed),
+            error,
+            failed_at: Utc::now(),
+        }))
+    }
+
+    pub fn project_indexing_started(project_info: &ProjectInfo) -> Self {
+        GkgEvent::ProjectIndexing(ProjectIndexingEvent::Started(ProjectIndexingStarted {
+            project_info: to_ts_project_info(project_info),
+            started_at: Utc::now(),
+        }))
+    }
+
+    pub fn project_indexing_completed(project_info: &ProjectInfo) -> Self {
+        GkgEvent::ProjectIndexing(ProjectIndexingEvent::Completed(ProjectIndexingCompleted {
+            project_info: to_ts_project_info(project_info),
+            completed_at: Utc::now(),
+        }))
+    }
+
+    pub fn project_indexing_failed(project_info: &ProjectInfo, error: String) -> Self {
+        GkgEvent::ProjectIndexing(ProjectIndexingEvent::Failed(ProjectIndexingFailed {
+            project_info: to_ts_project_info(project_info),
+            error,
+            failed_at: Utc::now(),
+        }))
+    }
+
+    pub fn workspace_reindexing_started(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        projects_to_process: &[ProjectInfo],
+    ) -> Self {
+        GkgEvent::WorkspaceReindexing(WorkspaceReindexingEvent::Started(
+            WorkspaceReindexingStarted {
+                workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+                projects_to_process: project_paths(projects_to_process),
+                started_at: Utc::now(),
+            },
+        ))
+    }
+
+    pub fn workspace_reindexing_completed(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        projects_indexed: &[ProjectInfo],
+    ) -> Self {
+        GkgEvent::WorkspaceReindexing(WorkspaceReindexingEvent::Completed(
+            WorkspaceReindexingCompleted {
+                workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+                projects_indexed: project_paths(projects_indexed),
+                completed_at: Utc::now(),
+            },
+        ))
+    }
+
+    pub fn workspace_reindexing_failed(
+        workspace_folder_info: &WorkspaceFolderInfo,
+        error: String,
+    ) -> Self {
+        GkgEvent::WorkspaceReindexing(WorkspaceReindexingEvent::Failed(
+            WorkspaceReindexingFailed {
+                workspace_folder_info: to_ts_workspace_folder_info(workspace_folder_info),
+                error,
+                failed_at: Utc::now(),
+            },
+        ))
+    }
+
+    pub fn project_reindexing_started(project_info: &ProjectInfo) -> Self {
+        GkgEvent::ProjectReindexing(ProjectReindexingEvent::Started(ProjectReindexingStarted {
+            project_info: to_ts_project_info(project_info),
+            started_at: Utc::now(),
+        }))
+    }
+
+    pub fn project_reindexing_completed(project_info: &ProjectInfo) -> Self {
+        GkgEvent::ProjectReindexing(ProjectReindexingEvent::Completed(
+            ProjectReindexingCompleted {
+                project_info: to_ts_project_info(project_info),
+                completed_at: Utc::now(),
+            },
+        ))
+    }
+
+    pub fn project_reindexing_failed(project_info: &ProjectInfo, error: String) -> Self {
+        GkgEvent::ProjectReindexing(ProjectReindexingEvent::Failed(ProjectReindexingFailed {
+            project_info: to_ts_project_info(project_info),
+            error,
+            failed_at: Utc::now(),
+        }))
+    }
+
+    pub fn watcher_lost(workspace_folder_path: &Path, project_path: &Path, error: String) -> Self {
+        GkgEvent::Watcher(WatcherEvent::Lost(WatcherLost {
+            workspace_folder_path: workspace_folder_path.to_string_lossy().into_owned(),
+            project_path: project_path.to_string_lossy().into_owned(),
+            error,
+            lost_at: Utc::now(),
+        }))
+    }
+
+    pub fn watcher_recovered(workspace_folder_path: &Path, project_path: &Path) -> Self {
+        GkgEvent::Watcher(WatcherEvent::Recovered(WatcherRecovered {
+            workspace_folder_path: workspace_folder_path.to_string_lossy().into_owned(),
+            project_path: project_path.to_string_lossy().into_owned(),
+            recovered_at: Utc::now(),
+        }))
+    }
+
+    pub fn graph_updated(project_info: &ProjectInfo) -> Self {
+        GkgEvent::Graph(GraphEvent::Updated(GraphUpdated {
+            workspace_folder_path: project_info.workspace_folder_path.clone(),
+            project_path: project_info.project_path.clone(),
+            database_path: project_info.database_path.to_string_lossy().into_owned(),
+            updated_at: Utc::now(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workspace_manager::Status;
+
+    #[test]
+    fn test_constructors_wrap_payload_in_matching_variant() {
+        let workspace_folder_info = WorkspaceFolderInfo {
+            workspace_folder_path: "/workspace".to_string(),
+            data_directory_name: "workspace".to_string(),
+            status: Status::Indexing,
+            last_indexed_at: None,
+            project_count: 0,
+            gitalisk_workspace: None,
+        };
+        let before = Utc::now();
+
+        let event = GkgEvent::workspace_reindexing_failed(&workspace_folder_info, "boom".into());
+        let GkgEvent::WorkspaceReindexing(WorkspaceReindexingEvent::Failed(failed)) = &event else {
+            panic!("Expected WorkspaceReindexingFailed, got: {event:?}");
+        };
+        assert_eq!(
+            failed.workspace_folder_info.workspace_folder_path,
+            "/workspace"
+        );
+        assert_eq!(failed.error, "boom");
+        assert!(event.timestamp() >= before);
+
+        let event = GkgEvent::watcher_lost(
+            Path::new("/workspace"),
+            Path::new("/workspace/project"),
+            "watch limit reached".into(),
+        );
+        let GkgEvent::Watcher(WatcherEvent::Lost(lost)) = &event else {
+            panic!("Expected WatcherLost, got: {event:?}");
+        };
+        assert_eq!(lost.project_path, "/workspace/project");
+        assert!(event.timestamp() >= before);
+    }
+}
@@ -0,0 +1,221 @@
+//! Writes the events of an [`EventBus`] to a file, one JSON object per line, so a run can be
+//! captured and inspected offline without verbose logs.
+
+use crate::{EventBus, GkgEvent};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// An event log being written in the background, see [`EventLog::start`]
+pub struct EventLog {
+    stop: oneshot::Sender<()>,
+    writer: JoinHandle<io::Result<usize>>,
+}
+
+impl EventLog {
+    /// Creates the file at `path`, replacing any previous log, and writes each [`GkgEvent`]
+    /// sent on `event_bus` from now on to it. Must be called from a Tokio runtime.
+    pub fn start(event_bus: &EventBus, path: &Path) -> io::Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Failed to create event log {}: {e}", path.display()),
+            )
+        })?;
+        let receiver = event_bus.subscribe();
+        let (stop, stopped) = oneshot::channel();
+        let writer = tokio::spawn(write_events(
+            receiver,
+            tokio::fs::File::from_std(file),
+            stopped,
+        ));
+        Ok(Self { stop, writer })
+    }
+
+    /// Starts a log at `path` when one is asked for, see [`Self::start`]
+    pub fn start_optional(event_bus: &EventBus, path: Option<&Path>) -> io::Result<Option<Self>> {
+        path.map(|path| Self::start(event_bus, path)).transpose()
+    }
+
+    /// Writes the events sent so far and closes the file, returns how many events were written
+    pub async fn finish(self) -> io::Result<usize> {
+        let _ = self.stop.send(());
+        self.writer.await.map_err(io::Error::other)?
+    }
+}
+
+/// Reads the events of a log written by [`EventLog`], in the order they were sent. Blank
+/// lines are skipped.
+pub fn read_event_log(path: &Path) -> io::Result<Vec<GkgEvent>> {
+    let file = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    for (index, line) in file.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid event on line {}: {e}", index + 1),
+            )
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+async fn write_events(
+    mut receiver: Receiver<GkgEvent>,
+    mut file: tokio::fs::File,
+    mut stopped: oneshot::Receiver<()>,
+) -> io::Result<usize> {
+    let mut written = 0;
+    let mut batch = Vec::new();
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    append_event(&mut batch, &event)?;
+                    written += 1 + append_sent_events(&mut receiver, &mut batch)?;
+                    write_batch(&mut file, &mut batch).await?;
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event log fell behind, {skipped} events were not written");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = &mut stopped => {
+                // Events sent before the log was stopped are still written
+                written += append_sent_events(&mut receiver, &mut batch)?;
+                break;
+            }
+        }
+    }
+    write_batch(&mut file, &mut batch).await?;
+    Ok(written)
+}
+
+/// Appends the events that were already sent to `batch` without waiting for more, returns how
+/// many were appended
+fn append_sent_events(receiver: &mut Receiver<GkgEvent>, batch: &mut Vec<u8>) -> io::Result<usize> {
+    let mut appended = 0;
+    loop {
+        match receiver.try_recv() {
+            Ok(event) => {
+                append_event(batch, &event)?;
+                appended += 1;
+            }
+            Err(TryRecvError::Lagged(skipped)) => {
+                tracing::warn!("Event log fell behind, {skipped} events were not written");
+            }
+            Err(TryRecvError::Empty | TryRecvError::Closed) => return Ok(appended),
+        }
+    }
+}
+
+fn append_event(batch: &mut Vec<u8>, event: &GkgEvent) -> io::Result<()> {
+    serde_json::to_writer(&mut *batch, event)?;
+    batch.push(b'\n');
+    Ok(())
+}
+
+/// Each batch is flushed so that the log is complete up to the last batch if the process
+/// crashes. The file is written off the runtime's worker threads.
+async fn write_batch(file: &mut tokio::fs::File, batch: &mut Vec<u8>) -> io::Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    file.write_all(batch).await?;
+    file.flush().await?;
+    batch.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::workspace_folder::TSWorkspaceFolderInfo;
+    use crate::{WorkspaceIndexingEvent, WorkspaceIndexingStarted};
+    use chrono::Utc;
+
+    fn test_event(path: &str) -> GkgEvent {
+        GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Started(WorkspaceIndexingStarted {
+            workspace_folder_info: TSWorkspaceFolderInfo {
+                workspace_folder_path: path.to_string(),
+                ..Default::default()
+            },
+            projects_to_process: vec![],
+            started_at: Utc::now(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_event_log_writes_json_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+        let event_bus = EventBus::new();
+        event_bus.send(&test_event("/before"));
+
+        let event_log = EventLog::start(&event_bus, &path).unwrap();
+        event_bus.send(&test_event("/a"));
+        event_bus.send(&test_event("/b"));
+        assert_eq!(event_log.finish().await.unwrap(), 2);
+        event_bus.send(&test_event("/after"));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "WorkspaceIndexing");
+        assert_eq!(lines[0]["payload"]["status"], "Started");
+        assert_eq!(
+            lines[1]["payload"]["workspace_folder_info"]["workspace_folder_path"],
+            "/b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_log_is_started_when_asked_for() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let event_bus = EventBus::new();
+        assert!(
+            EventLog::start_optional(&event_bus, None)
+                .unwrap()
+                .is_none()
+        );
+
+        let missing = temp_dir.path().join("missing").join("events.jsonl");
+        let error = EventLog::start_optional(&event_bus, Some(&missing))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains(&missing.display().to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_read_event_log_returns_the_written_events() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+        let event_bus = EventBus::new();
+        let event_log = EventLog::start(&event_bus, &path).unwrap();
+        let sent = test_event("/a");
+        event_bus.send(&sent);
+        event_log.finish().await.unwrap();
+
+        let events = read_event_log(&path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].timestamp(), sent.timestamp());
+
+        std::fs::write(&path, "\n{\"type\": \"Unknown\"}\n").unwrap();
+        let error = read_event_log(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("line 2"));
+    }
+}
@@ -34,14 +34,21 @@
 //! clients to react to *what the system has accomplished* with complete state information.
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast::{self, Sender};
 use ts_rs::TS;
 
 use crate::types::{project_info::TSProjectInfo, workspace_folder::TSWorkspaceFolderInfo};
+mod constructors;
+pub mod event_log;
 pub mod types;
 
-#[derive(Clone, Debug, Serialize, TS)]
+pub use event_log::{EventLog, read_event_log};
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 #[serde(tag = "type", content = "payload")]
 pub enum GkgEvent {
@@ -49,9 +56,11 @@ pub enum GkgEvent {
     ProjectIndexing(ProjectIndexingEvent),
     ProjectReindexing(ProjectReindexingEvent),
     WorkspaceReindexing(WorkspaceReindexingEvent),
+    Watcher(WatcherEvent),
+    Graph(GraphEvent),
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 #[serde(tag = "status")]
 pub enum WorkspaceIndexingEvent {
@@ -60,7 +69,7 @@ pub enum WorkspaceIndexingEvent {
     Failed(WorkspaceIndexingFailed),
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceIndexingStarted {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -68,7 +77,7 @@ pub struct WorkspaceIndexingStarted {
     pub started_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceIndexingCompleted {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -76,7 +85,7 @@ pub struct WorkspaceIndexingCompleted {
     pub completed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceIndexingFailed {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -85,7 +94,7 @@ pub struct WorkspaceIndexingFailed {
     pub failed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 #[serde(tag = "status")]
 pub enum ProjectIndexingEvent {
@@ -94,21 +103,21 @@ pub enum ProjectIndexingEvent {
     Failed(ProjectIndexingFailed),
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectIndexingStarted {
     pub project_info: TSProjectInfo,
     pub started_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectIndexingCompleted {
     pub project_info: TSProjectInfo,
     pub completed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectIndexingFailed {
     pub project_info: TSProjectInfo,
@@ -116,7 +125,7 @@ pub struct ProjectIndexingFailed {
     pub failed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 #[serde(tag = "status")]
 pub enum WorkspaceReindexingEvent {
@@ -125,7 +134,7 @@ pub enum WorkspaceReindexingEvent {
     Failed(WorkspaceReindexingFailed),
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceReindexingStarted {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -133,7 +142,7 @@ pub struct WorkspaceReindexingStarted {
     pub started_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceReindexingCompleted {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -141,7 +150,7 @@ pub struct WorkspaceReindexingCompleted {
     pub completed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct WorkspaceReindexingFailed {
     pub workspace_folder_info: TSWorkspaceFolderInfo,
@@ -149,7 +158,7 @@ pub struct WorkspaceReindexingFailed {
     pub failed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 #[serde(tag = "status")]
 pub enum ProjectReindexingEvent {
@@ -158,21 +167,21 @@ pub enum ProjectReindexingEvent {
     Failed(ProjectReindexingFailed),
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectReindexingStarted {
     pub project_info: TSProjectInfo,
     pub started_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectReindexingCompleted {
     pub project_info: TSProjectInfo,
     pub completed_at: DateTime<Utc>,
 }
 
-#[derive(Clone, Debug, Serialize, TS)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
 pub struct ProjectReindexingFailed {
     pub project_info: TSProjectInfo,
@@ -180,18 +189,194 @@ pub struct ProjectReindexingFailed {
     pub failed_at: DateTime<Utc>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
+#[serde(tag = "status")]
+pub enum WatcherEvent {
+    Lost(WatcherLost),
+    Recovered(WatcherRecovered),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
+pub struct WatcherLost {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+    pub error: String,
+    pub lost_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
+pub struct WatcherRecovered {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+    pub recovered_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
+#[serde(tag = "status")]
+pub enum GraphEvent {
+    Updated(GraphUpdated),
+}
+
+/// Sent once an index run wrote to a project's database, whether or not it succeeded.
+/// Anything derived from the database before, such as cached query results, is out of date.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../../packages/gkg/src/events.ts")]
+pub struct GraphUpdated {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+    pub database_path: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GkgEvent {
+    /// When the event happened, as recorded in its payload
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            GkgEvent::WorkspaceIndexing(event) => match event {
+                WorkspaceIndexingEvent::Started(started) => started.started_at,
+                WorkspaceIndexingEvent::Completed(completed) => completed.completed_at,
+                WorkspaceIndexingEvent::Failed(failed) => failed.failed_at,
+            },
+            GkgEvent::ProjectIndexing(event) => match event {
+                ProjectIndexingEvent::Started(started) => started.started_at,
+                ProjectIndexingEvent::Completed(completed) => completed.completed_at,
+                ProjectIndexingEvent::Failed(failed) => failed.failed_at,
+            },
+            GkgEvent::ProjectReindexing(event) => match event {
+                ProjectReindexingEvent::Started(started) => started.started_at,
+                ProjectReindexingEvent::Completed(completed) => completed.completed_at,
+                ProjectReindexingEvent::Failed(failed) => failed.failed_at,
+            },
+            GkgEvent::WorkspaceReindexing(event) => match event {
+                WorkspaceReindexingEvent::Started(started) => started.started_at,
+                WorkspaceReindexingEvent::Completed(completed) => completed.completed_at,
+                WorkspaceReindexingEvent::Failed(failed) => failed.failed_at,
+            },
+            GkgEvent::Watcher(event) => match event {
+                WatcherEvent::Lost(lost) => lost.lost_at,
+                WatcherEvent::Recovered(recovered) => recovered.recovered_at,
+            },
+            GkgEvent::Graph(GraphEvent::Updated(updated)) => updated.updated_at,
+        }
+    }
+}
+
+const CHANNEL_CAPACITY: usize = 1024;
+const REPLAY_BUFFER_CAPACITY: usize = 1024;
+
+/// A [`GkgEvent`] tagged with the monotonically increasing id it was broadcast with.
+/// Ids start at 1, so a client that has seen nothing can resume from 0.
+#[derive(Clone, Debug)]
+pub struct SequencedEvent {
+    pub id: u64,
+    pub event: GkgEvent,
+}
+
+#[derive(Debug, Default)]
+struct ReplayBuffer {
+    last_id: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+/// Number of events sent on an [`EventBus`] since it was created, per [`GkgEvent`] variant
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct EventBusStats {
+    pub workspace_indexing: u64,
+    pub project_indexing: u64,
+    pub project_reindexing: u64,
+    pub workspace_reindexing: u64,
+    pub watcher: u64,
+    pub graph: u64,
+}
+
+impl EventBusStats {
+    pub fn total(&self) -> u64 {
+        self.workspace_indexing
+            + self.project_indexing
+            + self.project_reindexing
+            + self.workspace_reindexing
+            + self.watcher
+            + self.graph
+    }
+}
+
+#[derive(Debug, Default)]
+struct EventCounters {
+    workspace_indexing: AtomicU64,
+    project_indexing: AtomicU64,
+    project_reindexing: AtomicU64,
+    workspace_reindexing: AtomicU64,
+    watcher: AtomicU64,
+    graph: AtomicU64,
+}
+
+impl EventCounters {
+    fn record(&self, event: &GkgEvent) {
+        let counter = match event {
+            GkgEvent::WorkspaceIndexing(_) => &self.workspace_indexing,
+            GkgEvent::ProjectIndexing(_) => &self.project_indexing,
+            GkgEvent::ProjectReindexing(_) => &self.project_reindexing,
+            GkgEvent::WorkspaceReindexing(_) => &self.workspace_reindexing,
+            GkgEvent::Watcher(_) => &self.watcher,
+            GkgEvent::Graph(_) => &self.graph,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> EventBusStats {
+        EventBusStats {
+            workspace_indexing: self.workspace_indexing.load(Ordering::Relaxed),
+            project_indexing: self.project_indexing.load(Ordering::Relaxed),
+            project_reindexing: self.project_reindexing.load(Ordering::Relaxed),
+            workspace_reindexing: self.workspace_reindexing.load(Ordering::Relaxed),
+            watcher: self.watcher.load(Ordering::Relaxed),
+            graph: self.graph.load(Ordering::Relaxed),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EventBus {
     sender: Sender<GkgEvent>,
+    sequenced_sender: Sender<SequencedEvent>,
+    replay_buffer: Arc<Mutex<ReplayBuffer>>,
+    counters: Arc<EventCounters>,
 }
 
 impl EventBus {
     pub fn new() -> Self {
-        let (sender, _) = broadcast::channel(1024);
-        Self { sender }
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let (sequenced_sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            sequenced_sender,
+            replay_buffer: Arc::new(Mutex::new(ReplayBuffer::default())),
+            counters: Arc::new(EventCounters::default()),
+        }
     }
 
     pub fn send(&self, event: &GkgEvent) {
+        self.counters.record(event);
+        {
+            // Assign the id and publish while holding the lock so that ids, the replay buffer
+            // and the sequenced channel all observe the same order.
+            let mut buffer = self.replay_buffer.lock().unwrap();
+            buffer.last_id += 1;
+            let sequenced = SequencedEvent {
+                id: buffer.last_id,
+                event: event.clone(),
+            };
+            if buffer.events.len() == REPLAY_BUFFER_CAPACITY {
+                buffer.events.pop_front();
+            }
+            buffer.events.push_back(sequenced.clone());
+            let _ = self.sequenced_sender.send(sequenced);
+        }
+
         if self.sender.send(event.clone()).is_err() {
             // This can happen if there are no receivers.
             // In our case, this is fine, we can just ignore the error for now.
@@ -199,9 +384,44 @@ impl EventBus {
         }
     }
 
+    /// Number of subscribers to the sequenced events, such as clients of the events stream
+    pub fn sequenced_subscriber_count(&self) -> usize {
+        self.sequenced_sender.receiver_count()
+    }
+
     pub fn subscribe(&self) -> broadcast::Receiver<GkgEvent> {
         self.sender.subscribe()
     }
+
+    /// Subscribe to events together with their sequence ids.
+    pub fn subscribe_sequenced(&self) -> broadcast::Receiver<SequencedEvent> {
+        self.sequenced_sender.subscribe()
+    }
+
+    /// Number of events sent so far, per kind of event. Events sent while nobody listened are
+    /// counted too.
+    pub fn stats(&self) -> EventBusStats {
+        self.counters.snapshot()
+    }
+
+    /// The id of the last event sent, 0 before the first one. Ids start again at 1 when the
+    /// server restarts.
+    pub fn last_id(&self) -> u64 {
+        self.replay_buffer.lock().unwrap().last_id
+    }
+
+    /// Returns the buffered events sent after `last_id`, oldest first.
+    /// Only the most recent events are retained, so a client that fell too far behind
+    /// receives whatever is still buffered.
+    pub fn replay_since(&self, last_id: u64) -> Vec<SequencedEvent> {
+        let buffer = self.replay_buffer.lock().unwrap();
+        buffer
+            .events
+            .iter()
+            .filter(|sequenced| sequenced.id > last_id)
+            .cloned()
+            .collect()
+    }
 }
 
 impl Default for EventBus {
@@ -209,3 +429,80 @@ impl Default for EventBus {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::workspace_folder::TSWorkspaceFolderInfo;
+
+    fn test_event(path: &str) -> GkgEvent {
+        GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Started(WorkspaceIndexingStarted {
+            workspace_folder_info: TSWorkspaceFolderInfo {
+                workspace_folder_path: path.to_string(),
+                ..Default::default()
+            },
+            projects_to_process: vec![],
+            started_at: Utc::now(),
+        }))
+    }
+
+    #[test]
+    fn test_replay_since_returns_events_after_id() {
+        let event_bus = EventBus::new();
+        assert_eq!(event_bus.last_id(), 0);
+        event_bus.send(&test_event("/a"));
+        event_bus.send(&test_event("/b"));
+        event_bus.send(&test_event("/c"));
+
+        assert_eq!(event_bus.last_id(), 3);
+        let ids: Vec<u64> = event_bus.replay_since(1).iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(event_bus.replay_since(0).len(), 3);
+        assert!(event_bus.replay_since(3).is_empty());
+    }
+
+    #[test]
+    fn test_replay_buffer_is_bounded() {
+        let event_bus = EventBus::new();
+        for _ in 0..REPLAY_BUFFER_CAPACITY + 10 {
+            event_bus.send(&test_event("/a"));
+        }
+
+        let replayed = event_bus.replay_since(0);
+        assert_eq!(replayed.len(), REPLAY_BUFFER_CAPACITY);
+        assert_eq!(replayed.first().unwrap().id, 11);
+    }
+
+    #[test]
+    fn test_stats_count_events_per_variant() {
+        let event_bus = EventBus::new();
+        assert_eq!(event_bus.stats(), EventBusStats::default());
+
+        event_bus.send(&test_event("/a"));
+        event_bus.send(&test_event("/b"));
+        event_bus
+            .clone()
+            .send(&GkgEvent::Graph(GraphEvent::Updated(GraphUpdated {
+                workspace_folder_path: "/a".to_string(),
+                project_path: "/a/project".to_string(),
+                database_path: "/data/database.kz".to_string(),
+                updated_at: Utc::now(),
+            })));
+
+        let stats = event_bus.stats();
+        assert_eq!(stats.workspace_indexing, 2);
+        assert_eq!(stats.graph, 1);
+        assert_eq!(stats.project_indexing, 0);
+        assert_eq!(stats.total(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_subscribers_receive_ids() {
+        let event_bus = EventBus::new();
+        let mut receiver = event_bus.subscribe_sequenced();
+        event_bus.send(&test_event("/a"));
+
+        let received = receiver.recv().await.unwrap();
+        assert_eq!(received.id, 1);
+    }
+}
@@ -1,5 +1,18 @@
+use crate::commands::list::{ListFormat, ListSortBy};
+use crate::exit_code::ExitCode;
 use clap::{Args, Parser, Subcommand};
+use database::querying::{
+    ConnectionPoolConfig, QueryCacheConfig, cache::DEFAULT_QUERY_CACHE_TTL,
+    pool::DEFAULT_CONNECTION_IDLE_TIMEOUT,
+};
+use http_server_desktop::config::{DEFAULT_MAX_REQUEST_BODY_BYTES, ServerConfig};
+use http_server_desktop::queue::SchedulingPolicy;
+use indexer::execution::config::ExtensionOverride;
+use indexer::project::io::InvalidUtf8Policy;
+use indexer::writer::{DEFAULT_PARQUET_BATCH_SIZE, ParquetCompression};
+use logging::LogFormat;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -16,8 +29,19 @@ pub struct GkgCli {
 }
 
 impl GkgCli {
-    pub fn parse_args() -> Self {
-        Self::parse()
+    /// The parsed arguments, or the exit code to return once the help, the version or the
+    /// error is printed. Invalid arguments give [`ExitCode::UsageError`], instead of clap's exit
+    /// code 2 that would be mistaken for a partial failure.
+    pub fn parse_args() -> Result<Self, ExitCode> {
+        Self::try_parse().map_err(|e| {
+            let _ = e.print();
+            if e.use_stderr() {
+                ExitCode::UsageError
+            } else {
+                // --help and --version
+                ExitCode::Success
+            }
+        })
     }
 }
 
@@ -31,9 +55,9 @@ const DEV_TOOLS_ENABLED: bool = true;
 pub enum Commands {
     /// Index repositories in a workspace
     Index {
-        /// Directory to scan for repositories
-        #[arg(default_value = ".")]
-        workspace_path: PathBuf,
+        /// Directories to scan for repositories, each indexed as its own workspace folder
+        #[arg(default_value = ".", num_args = 1..)]
+        workspace_paths: Vec<PathBuf>,
 
         /// Number of worker threads (0 means auto-detect based on CPU cores)
         #[arg(short, long, default_value_t = 0)]
@@ -43,17 +67,135 @@ pub enum Commands {
         #[arg(short, long)]
         verbose: bool,
 
+        /// Only print warnings and errors, to stderr, e.g. when running in scripts
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+
+        /// Format of the logs: text, or json for one JSON object per line (no progress bar)
+        #[arg(long, value_name = "FORMAT", default_value_t = LogFormat::default())]
+        log_format: LogFormat,
+
         /// Output statistics. Optionally specify a file path to save to.
         #[arg(long, value_name = "FILE", num_args = 0..=1, require_equals = true)]
         stats: Option<Option<PathBuf>>,
+
+        /// Directory for the intermediate Parquet files (default: next to the database in ~/.gkg)
+        #[arg(long, value_name = "DIR")]
+        parquet_dir: Option<PathBuf>,
+
+        /// Import the graph straight into the database without writing Parquet files
+        #[arg(long, conflicts_with = "parquet_dir")]
+        no_parquet: bool,
+
+        /// Compression of the Parquet files: none, snappy, zstd or zstd:<level>
+        #[arg(long, value_name = "CODEC", default_value_t = ParquetCompression::default(), conflicts_with = "no_parquet")]
+        parquet_compression: ParquetCompression,
+
+        /// Rows written to Parquet at a time, lower values reduce peak memory on large repositories
+        #[arg(long, value_name = "ROWS", default_value_t = DEFAULT_PARQUET_BATCH_SIZE, conflicts_with = "no_parquet")]
+        parquet_batch_size: usize,
+
+        /// Memory, in MiB, that files being parsed may use at once (default: a quarter of system memory)
+        #[arg(long, value_name = "MIB")]
+        parse_memory_budget: Option<usize>,
+
+        /// Also compute the betweenness centrality of definitions in the call graph (slow on large repositories)
+        #[arg(long)]
+        call_betweenness: bool,
+
+        /// Parse files with a custom extension as a supported language, e.g. rbx=ruby (repeatable)
+        #[arg(long = "map-extension", value_name = "EXT=LANGUAGE")]
+        extension_overrides: Vec<ExtensionOverride>,
+
+        /// Skip files matching a gitignore style pattern relative to the project root, e.g. dist/ (repeatable)
+        #[arg(long = "exclude", value_name = "GLOB")]
+        exclude_patterns: Vec<String>,
+
+        /// Link TypeScript imports to the definitions of sibling projects publishing the imported package
+        #[arg(long)]
+        cross_project: bool,
+
+        /// Build only the structural graph, without resolving references such as calls, which is much faster
+        #[arg(long)]
+        no_references: bool,
+
+        /// Keep only public definitions, e.g. to document a project's API. Languages whose visibility isn't known keep all their definitions
+        #[arg(long)]
+        public_only: bool,
+
+        /// Keep a snapshot of each project's definitions for the last N index runs, to diff them later
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        snapshot_history: usize,
+
+        /// Also index the files below symlinked directories, following each real directory once
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Files that are not valid UTF-8: skip, or lossy to replace the invalid bytes and parse the rest
+        #[arg(long, value_name = "POLICY", default_value_t = InvalidUtf8Policy::default())]
+        invalid_utf8: InvalidUtf8Policy,
+
+        /// Abandon a project, marking it as failed, when indexing it takes longer than this (unbounded when omitted)
+        #[arg(long, value_name = "SECONDS")]
+        project_timeout: Option<u64>,
+
+        /// Index the commit a git ref (SHA, branch or tag) points to, read from git without checking it out
+        #[arg(long = "ref", value_name = "REF")]
+        git_ref: Option<String>,
+
+        /// Write every event of the run to this file as JSON lines, e.g. to attach to a bug report
+        #[arg(long, value_name = "FILE")]
+        event_log: Option<PathBuf>,
+
+        /// Stop at the first workspace folder that fails to index, or has a project that does, instead of indexing the others
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Write a Chrome trace of the indexing phases to this file, to open in Perfetto or chrome://tracing
+        #[arg(long, value_name = "FILE")]
+        profile: Option<PathBuf>,
     },
     /// Manage the gkg server
     Server {
         #[command(subcommand)]
         action: Option<ServerCommands>,
     },
+    /// Manage the gkg entry in the MCP configuration
+    Mcp {
+        #[command(subcommand)]
+        action: McpCommands,
+    },
     /// Remove all indexed data
-    Clean,
+    Clean {
+        /// Remove the data without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Set the tags used to group an indexed project, e.g. by team or service area
+    Tag {
+        /// Path of the project to tag
+        project_path: PathBuf,
+        /// Tags replacing the current ones (clears the tags when omitted)
+        tags: Vec<String>,
+    },
+    /// Report whether the indexed projects are up to date with their source
+    Status {
+        /// Only report the projects of this workspace folder
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+    },
+    /// List the definitions added, removed, moved or changed between two definition snapshots
+    Diff {
+        /// Path of the indexed project
+        project_path: PathBuf,
+        /// Earlier snapshot, by id or commit prefix
+        from: String,
+        /// Later snapshot, by id or commit prefix
+        to: String,
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Developer tools (enabled for debug builds or with --features dev-tools in release builds)
     #[command(hide = !DEV_TOOLS_ENABLED, name="devtools")]
     DevTools {
@@ -72,6 +214,34 @@ pub enum DevToolsCommands {
         /// Query string or file path containing the query
         #[arg(value_name = "QUERY_OR_FILE")]
         query_or_file: String,
+        /// Print the query and Kuzu's plan for it instead of running it
+        #[arg(long, default_value_t = false)]
+        explain: bool,
+    },
+    /// Check that the databases of the indexed projects can be read. Unreadable databases are
+    /// moved aside and their projects flagged for reindex
+    Check {
+        /// Only check this project
+        #[arg(long)]
+        project: Option<PathBuf>,
+    },
+    /// Rebuild the databases of the indexed projects to reclaim the space left by reindexing
+    Compact {
+        /// Only compact this project
+        #[arg(long)]
+        project: Option<PathBuf>,
+    },
+    /// Serve the events of a log recorded with --event-log to the frontend, to debug its
+    /// handling of events without reindexing
+    ReplayEvents {
+        /// Event log to replay
+        path: PathBuf,
+        /// Port to serve the events on (default: the server's preferred port when it's free)
+        #[arg(long)]
+        port: Option<u16>,
+        /// How many times faster than recorded the events are replayed
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
     },
     /// List all indexed repositories
     List {
@@ -84,6 +254,40 @@ pub enum DevToolsCommands {
         /// Don't print headers
         #[arg(long, default_value_t = false)]
         header: bool,
+        /// Output format, `json` prints the listings as JSON arrays and ignores --header
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+        /// Sort by path or status (ascending), or by last-indexed or project-count (descending)
+        #[arg(long, value_enum, default_value_t = ListSortBy::Path)]
+        sort_by: ListSortBy,
+        /// Only list the entries with this status, e.g. error or stale
+        #[arg(long, value_name = "STATUS")]
+        filter_status: Option<workspace_manager::Status>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// Add or update the gkg server in the MCP configuration without starting it
+    Register {
+        /// Path to MCP configuration file (default: ~/.gitlab/duo/mcp.json)
+        #[arg(long)]
+        path: Option<PathBuf>,
+        /// Port of the gkg server (default: the port of the running server)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+    /// Remove the gkg server from the MCP configuration
+    Unregister {
+        /// Path to MCP configuration file (default: ~/.gitlab/duo/mcp.json)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Check the MCP configuration and the server it points to
+    Doctor {
+        /// Path to MCP configuration file (default: ~/.gitlab/duo/mcp.json)
+        #[arg(long)]
+        path: Option<PathBuf>,
     },
 }
 
@@ -92,7 +296,16 @@ pub enum ServerCommands {
     /// Start the gkg server
     Start(ServerStartArgs),
     /// Stop the running gkg server
-    Stop,
+    Stop(ServerStopArgs),
+    /// Show the running gkg server and its indexing jobs
+    Status,
+}
+
+#[derive(Args, Debug)]
+pub struct ServerStopArgs {
+    /// Kill the server if it hasn't shut down gracefully before the timeout
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
 }
 
 #[derive(Args, Debug)]
@@ -117,7 +330,101 @@ pub struct ServerStartArgs {
     #[arg(long)]
     pub mcp_configuration_path: Option<PathBuf>,
 
+    /// Only let MCP tools access projects inside this workspace folder or project path
+    /// (repeatable; all registered projects when omitted)
+    #[arg(long = "allow-project", value_name = "PATH")]
+    pub allowed_projects: Vec<PathBuf>,
+
     /// Enable verbose logging
     #[arg(long)]
     pub verbose: bool,
+
+    /// Seconds of inactivity before a keepalive comment is sent on the events stream
+    #[arg(long, default_value_t = 15)]
+    pub sse_keep_alive_secs: u64,
+
+    /// Maximum accepted request body size in bytes for the API endpoints
+    #[arg(long, default_value_t = DEFAULT_MAX_REQUEST_BODY_BYTES)]
+    pub max_request_body_bytes: usize,
+
+    /// Maximum number of workspaces indexed at the same time (unlimited when omitted)
+    #[arg(long)]
+    pub max_concurrent_indexing_jobs: Option<usize>,
+
+    /// Give free indexing slots to the smallest waiting workspace first instead of in
+    /// arrival order (requires --max-concurrent-indexing-jobs)
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "max_concurrent_indexing_jobs"
+    )]
+    pub fair_scheduling: bool,
+
+    /// Directory for the intermediate Parquet files (default: next to the database in ~/.gkg)
+    #[arg(long, value_name = "DIR")]
+    pub parquet_dir: Option<PathBuf>,
+
+    /// Reindex, on startup, the projects indexed with an older graph schema
+    #[arg(long, default_value_t = false)]
+    pub reindex_outdated: bool,
+
+    /// Cache the results of up to this many MCP tool queries (no cache when omitted)
+    #[arg(long, value_name = "ENTRIES")]
+    pub query_cache_size: Option<usize>,
+
+    /// Seconds a cached query result is served before the query runs again
+    /// (requires --query-cache-size)
+    #[arg(
+        long,
+        default_value_t = DEFAULT_QUERY_CACHE_TTL.as_secs(),
+        requires = "query_cache_size"
+    )]
+    pub query_cache_ttl_secs: u64,
+
+    /// Keep up to this many idle connections per database for the MCP tool queries instead of
+    /// opening one per query (no pooling when omitted)
+    #[arg(long, value_name = "CONNECTIONS")]
+    pub connection_pool_size: Option<usize>,
+
+    /// Seconds a pooled connection stays idle before it's closed
+    /// (requires --connection-pool-size)
+    #[arg(
+        long,
+        default_value_t = DEFAULT_CONNECTION_IDLE_TIMEOUT.as_secs(),
+        requires = "connection_pool_size"
+    )]
+    pub connection_idle_secs: u64,
+
+    /// Write every event to this file as JSON lines until the server stops, e.g. to attach to
+    /// a bug report
+    #[arg(long, value_name = "FILE")]
+    pub event_log: Option<PathBuf>,
+}
+
+impl ServerStartArgs {
+    pub fn server_config(&self) -> ServerConfig {
+        ServerConfig {
+            sse_keep_alive_interval: Duration::from_secs(self.sse_keep_alive_secs),
+            max_request_body_bytes: self.max_request_body_bytes,
+            max_concurrent_indexing_jobs: self.max_concurrent_indexing_jobs,
+            scheduling_policy: if self.fair_scheduling {
+                SchedulingPolicy::ShortestJobFirst
+            } else {
+                SchedulingPolicy::Fifo
+            },
+            reindex_outdated_projects: self.reindex_outdated,
+            query_cache: self.query_cache_size.map(|capacity| QueryCacheConfig {
+                capacity,
+                ttl: Duration::from_secs(self.query_cache_ttl_secs),
+            }),
+            connection_pool: self
+                .connection_pool_size
+                .map(|max_size| ConnectionPoolConfig {
+                    max_size,
+                    idle_timeout: Duration::from_secs(self.connection_idle_secs),
+                }),
+            event_log: self.event_log.clone(),
+            ..Default::default()
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use database::kuzu::database::{DatabaseIntegrity, KuzuDatabase};
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use tracing::error;
+use workspace_manager::{ProjectInfo, WorkspaceManager};
+
+use crate::utils::is_server_running;
+
+/// Checks the databases of the indexed projects. A database that can't be read is moved aside
+/// and its project is flagged as outdated, so the next `gkg index` rebuilds it.
+pub fn run(
+    workspace_manager: Arc<WorkspaceManager>,
+    database: Arc<KuzuDatabase>,
+    project: Option<PathBuf>,
+) -> Result<()> {
+    if let Some(port) = is_server_running()? {
+        error!("Error: gkg server is running on port {port}. Stop it before checking databases.");
+        process::exit(1);
+    }
+
+    let mut projects = match project {
+        Some(project_path) => {
+            let project_path = dunce::canonicalize(&project_path)?;
+            let project = workspace_manager
+                .get_project_for_path(&project_path.to_string_lossy())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Project not found: {}. Index it with `gkg index` first.",
+                        project_path.display()
+                    )
+                })?;
+            vec![project]
+        }
+        None => workspace_manager.list_all_projects(),
+    };
+    projects.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+    let mut unhealthy = 0;
+    for project in &projects {
+        let database_path = project.database_path.to_string_lossy();
+        let integrity = KuzuDatabase::check_integrity(&database_path);
+        let (label, details) = match &integrity {
+            DatabaseIntegrity::Healthy { table_count } => ("ok", format!("{table_count} tables")),
+            DatabaseIntegrity::Missing => ("not indexed", String::new()),
+            DatabaseIntegrity::Locked { reason } => ("locked", reason.clone()),
+            DatabaseIntegrity::Incompatible { reason } => (
+                "incompatible",
+                format!(
+                    "{reason}. {}",
+                    recover(&workspace_manager, &database, project)
+                ),
+            ),
+            DatabaseIntegrity::Corrupt { reason } => (
+                "corrupt",
+                format!(
+                    "{reason}. {}",
+                    recover(&workspace_manager, &database, project)
+                ),
+            ),
+        };
+        if !matches!(
+            integrity,
+            DatabaseIntegrity::Healthy { .. } | DatabaseIntegrity::Missing
+        ) {
+            unhealthy += 1;
+        }
+
+        // We're printing to stdout, so we don't need to use tracing
+        let line = format!("{label:<14}{}  {details}", project.project_path);
+        println!("{}", line.trim_end());
+    }
+
+    if projects.is_empty() {
+        println!("No indexed projects. Index a workspace with `gkg index` first.");
+        return Ok(());
+    }
+    println!();
+    println!("{} project(s), {unhealthy} with problems", projects.len());
+
+    if unhealthy > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Moves the database aside and flags the project for reindex, describes the outcome
+fn recover(
+    workspace_manager: &WorkspaceManager,
+    database: &KuzuDatabase,
+    project: &ProjectInfo,
+) -> String {
+    let moved_path = match database.move_aside(&project.database_path.to_string_lossy()) {
+        Ok(moved_path) => moved_path,
+        Err(e) => return format!("Failed to move the database aside: {e}"),
+    };
+    if let Err(e) = workspace_manager
+        .mark_project_outdated(&project.workspace_folder_path, &project.project_path)
+    {
+        return format!("Moved the database to {moved_path}, but failed to flag the project: {e}");
+    }
+    format!("Moved the database to {moved_path}, reindex the project with `gkg index`")
+}
@@ -1,18 +1,57 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use std::io::{self, BufRead, Write};
 use std::process;
 use std::sync::Arc;
 use tracing::{error, info};
 
 use crate::utils::is_server_running;
 use workspace_manager::WorkspaceManager;
+use workspace_manager::data_directory::format_bytes;
 
-pub fn run(workspace_manager: Arc<WorkspaceManager>) -> Result<()> {
+/// Asks on the terminal whether to go ahead. Only an answer starting with `y` confirms.
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer)? == 0 {
+        bail!("Can't ask for confirmation without input, pass --yes to clean anyway");
+    }
+    Ok(answer.trim().to_ascii_lowercase().starts_with('y'))
+}
+
+pub fn run(workspace_manager: Arc<WorkspaceManager>, yes: bool) -> Result<()> {
     if let Some(port) = is_server_running()? {
         error!("Error: gkg server is running on port {port}. Stop it before running clean.");
         process::exit(1);
     }
 
+    let workspace_folders = workspace_manager.list_workspace_folders();
+    let disk_usage = workspace_manager.total_disk_usage()?;
+    if workspace_folders.is_empty() && disk_usage == 0 {
+        info!("Nothing to clean");
+        return Ok(());
+    }
+
+    // We're printing to stdout, so we don't need to use tracing
+    println!(
+        "This removes the indexed data of {} workspace folders, freeing {}:",
+        workspace_folders.len(),
+        format_bytes(disk_usage)
+    );
+    for workspace_folder in &workspace_folders {
+        println!(
+            "  - {} ({} projects)",
+            workspace_folder.workspace_folder_path, workspace_folder.project_count
+        );
+    }
+
+    if !yes && !confirm("Remove it? [y/N] ")? {
+        println!("Clean cancelled, nothing was removed");
+        return Ok(());
+    }
+
     workspace_manager.clean()?;
-    info!("Clean completed");
+    info!("Clean completed, freed {}", format_bytes(disk_usage));
     Ok(())
 }
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use database::kuzu::database::KuzuDatabase;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use tracing::error;
+use workspace_manager::{WorkspaceManager, format_bytes};
+
+use crate::utils::is_server_running;
+
+/// Compacts the databases of the indexed projects and reports the space reclaimed
+pub fn run(
+    workspace_manager: Arc<WorkspaceManager>,
+    database: Arc<KuzuDatabase>,
+    project: Option<PathBuf>,
+) -> Result<()> {
+    if let Some(port) = is_server_running()? {
+        error!("Error: gkg server is running on port {port}. Stop it before compacting databases.");
+        process::exit(1);
+    }
+
+    let mut projects = match project {
+        Some(project_path) => {
+            let project_path = dunce::canonicalize(&project_path)?;
+            let project = workspace_manager
+                .get_project_for_path(&project_path.to_string_lossy())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Project not found: {}. Index it with `gkg index` first.",
+                        project_path.display()
+                    )
+                })?;
+            vec![project]
+        }
+        None => workspace_manager.list_all_projects(),
+    };
+    projects.retain(|project| project.database_path.exists());
+    projects.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+    if projects.is_empty() {
+        println!("No indexed projects. Index a workspace with `gkg index` first.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    let (mut total_before, mut total_after) = (0, 0);
+    for project in &projects {
+        let before = project.database_size_bytes().unwrap_or_default();
+        // We're printing to stdout, so we don't need to use tracing
+        match database.compact(&project.database_path.to_string_lossy()) {
+            Ok(()) => {
+                let after = project.database_size_bytes().unwrap_or_default();
+                total_before += before;
+                total_after += after;
+                println!(
+                    "{}  {} -> {}",
+                    project.project_path,
+                    format_bytes(before),
+                    format_bytes(after)
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{}  failed: {e}", project.project_path);
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{} -> {}, reclaimed {}",
+        format_bytes(total_before),
+        format_bytes(total_after),
+        format_bytes(total_before.saturating_sub(total_after))
+    );
+
+    if failed > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use indexer::snapshots::{DefinitionDiff, SnapshotDefinition, diff_index};
+use std::path::PathBuf;
+use std::sync::Arc;
+use workspace_manager::WorkspaceManager;
+
+/// `path:line`, with the 0-based line of the database shown 1-based
+fn location(definition: &SnapshotDefinition) -> String {
+    format!("{}:{}", definition.file_path, definition.start_line + 1)
+}
+
+fn print_diff(diff: &DefinitionDiff) {
+    // We're printing to stdout, so we don't need to use tracing
+    for definition in &diff.added {
+        println!("+ {}  {}", definition.fqn, location(definition));
+    }
+    for definition in &diff.removed {
+        println!("- {}  {}", definition.fqn, location(definition));
+    }
+    for change in &diff.moved {
+        println!(
+            "> {}  {} -> {}",
+            change.after.fqn,
+            location(&change.before),
+            location(&change.after)
+        );
+    }
+    for change in &diff.changed {
+        println!("~ {}  {}", change.after.fqn, location(&change.after));
+    }
+
+    if diff.is_empty() {
+        println!("No definitions changed");
+    } else {
+        println!();
+        println!(
+            "{} added, {} removed, {} moved, {} changed",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.moved.len(),
+            diff.changed.len()
+        );
+    }
+}
+
+pub fn run(
+    workspace_manager: Arc<WorkspaceManager>,
+    project_path: PathBuf,
+    from: String,
+    to: String,
+    json: bool,
+) -> Result<()> {
+    let project_path = dunce::canonicalize(&project_path)?;
+    let diff = diff_index(
+        &workspace_manager,
+        &project_path.to_string_lossy(),
+        &from,
+        &to,
+    )?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print_diff(&diff);
+    }
+    Ok(())
+}
@@ -1,104 +1,183 @@
 use anyhow::Result;
-use indexer::execution::config::IndexingConfigBuilder;
+use indexer::execution::config::{
+    ExtensionOverride, IndexingConfigBuilder, extension_override_map,
+};
 use indexer::execution::executor::IndexingExecutor;
+use indexer::project::io::InvalidUtf8Policy;
 use indexer::stats::WorkspaceStatistics;
-use std::path::PathBuf;
-use std::process;
+use indexer::writer::ParquetCompression;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
+use crate::exit_code::ExitCode;
+use crate::progress_bar::ProgressBar;
 use crate::utils::is_server_running;
 use database::kuzu::database::KuzuDatabase;
-use event_bus::EventBus;
+use event_bus::{EventBus, EventLog};
 use workspace_manager::WorkspaceManager;
 
-fn handle_statistics_output(
-    workspace_stats: &WorkspaceStatistics,
-    stats_output: Option<Option<PathBuf>>,
-) {
-    if let Some(stats_path_option) = stats_output {
-        // Save to file if path provided
-        if let Some(stats_path) = stats_path_option {
-            match workspace_stats.export_to_file(&stats_path) {
-                Ok(_) => {
-                    info!("Statistics saved to: {}", stats_path.display());
-                }
-                Err(e) => {
-                    error!("Failed to save statistics: {e}");
-                }
-            }
+/// Saves the statistics of the indexed workspace folders as an array, even when a single
+/// folder was indexed, so the file has the same shape whatever the number of paths
+fn save_statistics(workspace_stats: &[&WorkspaceStatistics], stats_path: &Path) {
+    match WorkspaceStatistics::export_all_to_file(workspace_stats, stats_path) {
+        Ok(_) => {
+            info!("Statistics saved to: {}", stats_path.display());
         }
+        Err(e) => {
+            error!("Failed to save statistics: {e}");
+        }
+    }
+}
 
-        // Display summary and top breakdowns when user requested stats
-        info!("Indexing Summary:");
-        info!("  - Total Projects: {}", workspace_stats.total_projects);
-        info!("  - Total Files: {}", workspace_stats.total_files);
-        info!(
-            "  - Total Definitions: {}",
-            workspace_stats.total_definitions
-        );
-        info!(
-            "  - Total Imported Symbols: {}",
-            workspace_stats.total_imported_symbols
-        );
+/// Totals of all the workspace folders indexed by one command
+fn print_combined_summary(
+    indexed: &[&WorkspaceStatistics],
+    failed: &[&PathBuf],
+    duration: Duration,
+) {
+    info!(
+        "Indexed {} of {} workspace folders in {:.2} seconds",
+        indexed.len(),
+        indexed.len() + failed.len(),
+        duration.as_secs_f64()
+    );
+    info!(
+        "  - Total Projects: {}",
+        indexed.iter().map(|s| s.total_projects).sum::<usize>()
+    );
+    info!(
+        "  - Total Files: {}",
+        indexed.iter().map(|s| s.total_files).sum::<usize>()
+    );
+    info!(
+        "  - Total Definitions: {}",
+        indexed.iter().map(|s| s.total_definitions).sum::<usize>()
+    );
+    info!(
+        "  - Total Imported Symbols: {}",
+        indexed
+            .iter()
+            .map(|s| s.total_imported_symbols)
+            .sum::<usize>()
+    );
+    for path in failed {
+        error!("  - Failed: {}", path.display());
+    }
+}
+
+fn print_statistics(workspace_stats: &WorkspaceStatistics) {
+    info!(
+        "Indexing Summary for {}:",
+        workspace_stats.metadata.workspace_path
+    );
+    info!("  - Total Projects: {}", workspace_stats.total_projects);
+    info!("  - Total Files: {}", workspace_stats.total_files);
+    if workspace_stats.total_excluded_files > 0 {
         info!(
-            "  - Total Definition Relationships: {}",
-            workspace_stats.total_definition_relationships
+            "  - Excluded Files: {}",
+            workspace_stats.total_excluded_files
         );
+    }
+    info!(
+        "  - Total Definitions: {}",
+        workspace_stats.total_definitions
+    );
+    info!(
+        "  - Total Imported Symbols: {}",
+        workspace_stats.total_imported_symbols
+    );
+    info!(
+        "  - Total Definition Relationships: {}",
+        workspace_stats.total_definition_relationships
+    );
+    info!(
+        "  - Total Imported Symbol Relationships: {}",
+        workspace_stats.total_imported_symbol_relationships
+    );
+    if !workspace_stats.metadata.references_resolved {
+        info!("  - References: not resolved (--no-references), the graph has no calls");
+    }
+    if workspace_stats.total_cross_project_links > 0 {
         info!(
-            "  - Total Imported Symbol Relationships: {}",
-            workspace_stats.total_imported_symbol_relationships
+            "  - Cross-Project Links: {}",
+            workspace_stats.total_cross_project_links
         );
+    }
 
-        if !workspace_stats.projects.is_empty() {
-            info!("Project Timing:");
-            for project in &workspace_stats.projects {
-                info!(
-                    "  - {}: {:.2}s ({} files, {} definitions, {} imported symbols, {} def relationships, {} imp relationships)",
-                    project.project_name,
-                    project.indexing_duration_seconds,
-                    project.total_files,
-                    project.total_definitions,
-                    project.total_imported_symbols,
-                    project.total_definition_relationships,
-                    project.total_imported_symbol_relationships
-                );
-            }
+    if !workspace_stats.projects.is_empty() {
+        info!("Project Timing:");
+        for project in &workspace_stats.projects {
+            info!(
+                "  - {}: {:.2}s ({} files, {} definitions, {} imported symbols, {} def relationships, {} imp relationships)",
+                project.project_name,
+                project.indexing_duration_seconds,
+                project.total_files,
+                project.total_definitions,
+                project.total_imported_symbols,
+                project.total_definition_relationships,
+                project.total_imported_symbol_relationships
+            );
         }
+    }
 
-        if !workspace_stats.total_languages.is_empty() {
-            info!("Language Breakdown:");
-            let mut languages: Vec<(&String, &indexer::stats::LanguageSummary)> =
-                workspace_stats.total_languages.iter().collect();
-            languages.sort_by(|a, b| b.1.file_count.cmp(&a.1.file_count));
+    if !workspace_stats.total_languages.is_empty() {
+        info!("Language Breakdown:");
+        let mut languages: Vec<(&String, &indexer::stats::LanguageSummary)> =
+            workspace_stats.total_languages.iter().collect();
+        languages.sort_by(|a, b| b.1.file_count.cmp(&a.1.file_count));
 
-            for (language, summary) in languages.iter().take(10) {
-                info!(
-                    "  - {}: {} files, {} definitions",
-                    language, summary.file_count, summary.definitions_count
-                );
-            }
+        for (language, summary) in languages.iter().take(10) {
+            info!(
+                "  - {}: {} files, {} definitions",
+                language, summary.file_count, summary.definitions_count
+            );
+        }
 
-            if languages.len() > 10 {
-                info!("  ... and {} more languages", languages.len() - 10);
-            }
+        if languages.len() > 10 {
+            info!("  ... and {} more languages", languages.len() - 10);
         }
     }
 }
 
+pub struct IndexArgs {
+    pub workspace_paths: Vec<PathBuf>,
+    pub threads: usize,
+    pub stats_output: Option<Option<PathBuf>>,
+    pub write_parquet: bool,
+    pub parquet_compression: ParquetCompression,
+    pub parquet_batch_size: usize,
+    /// In bytes, the default budget when `None`
+    pub parse_memory_budget: Option<usize>,
+    pub compute_call_betweenness: bool,
+    pub extension_overrides: Vec<ExtensionOverride>,
+    pub exclude_patterns: Vec<String>,
+    pub cross_project_resolution: bool,
+    pub resolve_references: bool,
+    pub public_only: bool,
+    pub definition_snapshot_history: usize,
+    pub follow_symlinks: bool,
+    pub invalid_utf8: InvalidUtf8Policy,
+    pub project_timeout: Option<Duration>,
+    pub git_ref: Option<String>,
+    pub event_log: Option<PathBuf>,
+    pub fail_fast: bool,
+}
+
 pub async fn run(
-    workspace_path: PathBuf,
-    threads: usize,
-    stats_output: Option<Option<PathBuf>>,
+    args: IndexArgs,
+    progress_bar: Option<ProgressBar>,
     workspace_manager: Arc<WorkspaceManager>,
     event_bus: Arc<EventBus>,
     database: Arc<KuzuDatabase>,
-) -> Result<()> {
+) -> Result<ExitCode> {
     if let Some(port) = is_server_running()? {
         error!(
             "Error: gkg server is running on port {port}. Please stop it to run indexing from the CLI."
         );
-        process::exit(1);
+        return Ok(ExitCode::Error);
     }
 
     // Subscribe to events; CLI frontend consumer is currently disabled.
@@ -106,35 +185,130 @@ pub async fn run(
     // TODO: implement CLI frontend consumer
     tokio::spawn(async move { while (rx.recv().await).is_ok() {} });
 
-    let config = IndexingConfigBuilder::build(threads);
+    let event_log = EventLog::start_optional(&event_bus, args.event_log.as_deref())?;
+
+    let mut config = IndexingConfigBuilder::build(args.threads);
+    config.write_parquet = args.write_parquet;
+    config.parquet_compression = args.parquet_compression;
+    config.parquet_batch_size = args.parquet_batch_size;
+    if let Some(parse_memory_budget) = args.parse_memory_budget {
+        config.parse_memory_budget = parse_memory_budget;
+    }
+    config.compute_call_betweenness = args.compute_call_betweenness;
+    config.extension_overrides = extension_override_map(args.extension_overrides);
+    config.exclude_patterns = args.exclude_patterns;
+    config.cross_project_resolution = args.cross_project_resolution;
+    config.resolve_references = args.resolve_references;
+    config.public_only = args.public_only;
+    config.definition_snapshot_history = args.definition_snapshot_history;
+    config.follow_symlinks = args.follow_symlinks;
+    config.invalid_utf8 = args.invalid_utf8;
+    config.project_timeout = args.project_timeout;
     let mut executor = IndexingExecutor::new(
         database.clone(),
         workspace_manager.clone(),
-        event_bus,
+        Arc::clone(&event_bus),
         config,
-    );
+    )
+    .with_git_ref(args.git_ref);
+    if let Some(progress_bar) = &progress_bar {
+        executor = executor.with_progress_sink(Arc::new(progress_bar.clone()));
+    }
 
-    let canonical_workspace_path = workspace_path.canonicalize()?;
     let start_time = std::time::Instant::now();
-
-    match executor
-        .execute_workspace_indexing(canonical_workspace_path.clone(), None)
-        .await
-    {
-        Ok(workspace_stats) => {
-            let indexing_duration = start_time.elapsed();
-            info!(
-                "✅ Workspace indexing completed in {:.2} seconds",
-                indexing_duration.as_secs_f64()
+    let mut results = Vec::with_capacity(args.workspace_paths.len());
+    let mut indexed_paths = HashSet::new();
+    for workspace_path in args.workspace_paths {
+        let canonical_workspace_path = match workspace_path.canonicalize() {
+            Ok(path) => path,
+            Err(e) => {
+                let e = anyhow::anyhow!("Failed to resolve {}: {e}", workspace_path.display());
+                error!("❌ Indexing failed: {e}");
+                results.push((workspace_path, Err(e)));
+                if args.fail_fast {
+                    break;
+                }
+                continue;
+            }
+        };
+        if !indexed_paths.insert(canonical_workspace_path.clone()) {
+            warn!(
+                "Skipping {}, it was already indexed",
+                workspace_path.display()
             );
+            continue;
+        }
 
-            handle_statistics_output(&workspace_stats, stats_output);
+        let workspace_start_time = std::time::Instant::now();
+        let result = executor
+            .execute_workspace_indexing(canonical_workspace_path.clone(), None)
+            .await;
+        match &result {
+            Ok(workspace_stats) => {
+                info!(
+                    "✅ Workspace indexing of {} completed in {:.2} seconds",
+                    canonical_workspace_path.display(),
+                    workspace_start_time.elapsed().as_secs_f64()
+                );
+                if args.stats_output.is_some() {
+                    print_statistics(workspace_stats);
+                }
+            }
+            Err(e) => error!(
+                "❌ Indexing {} failed: {e}",
+                canonical_workspace_path.display()
+            ),
         }
-        Err(e) => {
-            error!("❌ Indexing failed: {e}");
-            process::exit(1);
+        // A folder whose projects failed to index fails too, though its other projects were
+        let failed = !result
+            .as_ref()
+            .is_ok_and(|workspace_stats| workspace_stats.failed_projects.is_empty());
+        results.push((canonical_workspace_path, result));
+        if failed && args.fail_fast {
+            break;
         }
     }
 
-    Ok(())
+    if let Some(progress_bar) = &progress_bar {
+        progress_bar.finish();
+    }
+
+    let event_stats = event_bus.stats();
+    debug!(
+        "Sent {} events during indexing: {event_stats:?}",
+        event_stats.total()
+    );
+
+    if let Some(event_log) = event_log {
+        match event_log.finish().await {
+            Ok(written) => info!("Wrote {written} events to the event log"),
+            Err(e) => error!("Failed to write the event log: {e}"),
+        }
+    }
+
+    let indexed: Vec<&WorkspaceStatistics> = results
+        .iter()
+        .filter_map(|(_, result)| result.as_ref().ok())
+        .collect();
+    let failed: Vec<&PathBuf> = results
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(path, _)| path)
+        .collect();
+    if results.len() > 1 {
+        print_combined_summary(&indexed, &failed, start_time.elapsed());
+    }
+    if let Some(Some(stats_path)) = &args.stats_output
+        && !indexed.is_empty()
+    {
+        save_statistics(&indexed, stats_path);
+    }
+
+    let indexed_projects = indexed.iter().map(|s| s.total_projects).sum();
+    let failed_projects = failed.len()
+        + indexed
+            .iter()
+            .map(|s| s.failed_projects.len())
+            .sum::<usize>();
+    Ok(ExitCode::from_indexing(indexed_projects, failed_projects))
 }
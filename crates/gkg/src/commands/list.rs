@@ -1,37 +1,227 @@
 use anyhow::Result;
 use std::sync::Arc;
-use workspace_manager::WorkspaceManager;
+use workspace_manager::{Status, WorkspaceManager};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    Table,
+    Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListSortBy {
+    Path,
+    Status,
+    LastIndexed,
+    ProjectCount,
+}
 
 pub struct ListArgs {
     pub projects: bool,
     pub workspace_folders: bool,
     pub header: bool,
+    pub format: ListFormat,
+    pub sort_by: ListSortBy,
+    pub filter_status: Option<Status>,
+}
+
+/// Path, status, last indexing time and project count of a listed entry
+#[cfg(any(debug_assertions, feature = "dev-tools"))]
+type SortFields<'a> = (
+    &'a str,
+    &'a Status,
+    Option<chrono::DateTime<chrono::Utc>>,
+    Option<usize>,
+);
+
+/// Keeps the entries with the status to filter on, sorted by `sort_by`. Entries are first
+/// sorted by path, which breaks the ties of the other keys. Projects have no project count and
+/// stay sorted by path.
+#[cfg(any(debug_assertions, feature = "dev-tools"))]
+fn sort_and_filter<T>(
+    entries: &mut Vec<T>,
+    args: &ListArgs,
+    fields: impl Fn(&T) -> SortFields<'_>,
+) {
+    if let Some(filter_status) = &args.filter_status {
+        entries.retain(|entry| fields(entry).1 == filter_status);
+    }
+    entries.sort_by(|a, b| fields(a).0.cmp(fields(b).0));
+    match args.sort_by {
+        ListSortBy::Path => {}
+        ListSortBy::Status => {
+            entries.sort_by_cached_key(|entry| fields(entry).1.to_string());
+        }
+        ListSortBy::LastIndexed => {
+            entries.sort_by_key(|entry| std::cmp::Reverse(fields(entry).2));
+        }
+        ListSortBy::ProjectCount => {
+            entries.sort_by_key(|entry| std::cmp::Reverse(fields(entry).3));
+        }
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "dev-tools"))]
+mod json {
+    use chrono::{DateTime, Utc};
+    use serde::Serialize;
+    use workspace_manager::{ProjectInfo, Status, WorkspaceFolderInfo};
+
+    #[derive(Serialize)]
+    pub struct ListedWorkspaceFolder {
+        pub workspace_folder_path: String,
+        pub data_directory_name: String,
+        pub status: Status,
+        pub last_indexed_at: Option<DateTime<Utc>>,
+        pub project_count: usize,
+    }
+
+    impl From<WorkspaceFolderInfo> for ListedWorkspaceFolder {
+        fn from(workspace_folder: WorkspaceFolderInfo) -> Self {
+            Self {
+                workspace_folder_path: workspace_folder.workspace_folder_path,
+                data_directory_name: workspace_folder.data_directory_name,
+                status: workspace_folder.status,
+                last_indexed_at: workspace_folder.last_indexed_at,
+                project_count: workspace_folder.project_count,
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    pub struct ListedProject {
+        pub project_path: String,
+        pub workspace_folder_path: String,
+        pub project_hash: String,
+        pub status: Status,
+        pub last_indexed_at: Option<DateTime<Utc>>,
+        pub error_message: Option<String>,
+        pub schema_version: Option<u32>,
+        pub tags: Vec<String>,
+        pub indexed_commit: Option<String>,
+        pub database_path: String,
+        pub database_size_bytes: Option<u64>,
+        pub parquet_directory: String,
+    }
+
+    impl From<ProjectInfo> for ListedProject {
+        fn from(project: ProjectInfo) -> Self {
+            let database_size_bytes = project.database_size_bytes();
+            Self {
+                project_path: project.project_path,
+                workspace_folder_path: project.workspace_folder_path,
+                project_hash: project.project_hash,
+                status: project.status,
+                last_indexed_at: project.last_indexed_at,
+                error_message: project.error_message,
+                schema_version: project.schema_version,
+                tags: project.tags,
+                indexed_commit: project.indexed_commit,
+                database_path: project.database_path.to_string_lossy().into_owned(),
+                database_size_bytes,
+                parquet_directory: project.parquet_directory.to_string_lossy().into_owned(),
+            }
+        }
+    }
+
+    /// Only the listings that were asked for are included
+    #[derive(Serialize)]
+    pub struct Listing {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub workspace_folders: Option<Vec<ListedWorkspaceFolder>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub projects: Option<Vec<ListedProject>>,
+    }
 }
 
 #[cfg(any(debug_assertions, feature = "dev-tools"))]
 pub fn run(workspace_manager: Arc<WorkspaceManager>, args: ListArgs) -> Result<()> {
+    workspace_manager.flag_outdated_projects(database::schema::manager::SCHEMA_VERSION)?;
+    workspace_manager.flag_stale_projects()?;
+
+    let mut workspace_folders = if args.workspace_folders {
+        workspace_manager.list_workspace_folders()
+    } else {
+        Vec::new()
+    };
+    sort_and_filter(&mut workspace_folders, &args, |workspace_folder| {
+        (
+            &workspace_folder.workspace_folder_path,
+            &workspace_folder.status,
+            workspace_folder.last_indexed_at,
+            Some(workspace_folder.project_count),
+        )
+    });
+    let mut projects = if args.projects {
+        workspace_manager.list_all_projects()
+    } else {
+        Vec::new()
+    };
+    sort_and_filter(&mut projects, &args, |project| {
+        (
+            &project.project_path,
+            &project.status,
+            project.last_indexed_at,
+            None,
+        )
+    });
+
+    if args.format == ListFormat::Json {
+        let listing = json::Listing {
+            workspace_folders: args
+                .workspace_folders
+                .then(|| workspace_folders.into_iter().map(Into::into).collect()),
+            projects: args
+                .projects
+                .then(|| projects.into_iter().map(Into::into).collect()),
+        };
+        println!("{}", serde_json::to_string_pretty(&listing)?);
+        return Ok(());
+    }
+
     if args.workspace_folders {
-        let workspace_folders = workspace_manager.list_workspace_folders();
         if args.header {
             println!("Workspace folders:");
         }
         for workspace_folder in workspace_folders {
             // We're printing to stdout, so we don't need to use tracing
-            println!("{}", workspace_folder.workspace_folder_path);
+            println!(
+                "{}{}",
+                workspace_folder.workspace_folder_path,
+                status_marker(&workspace_folder.status)
+            );
         }
     }
     if args.projects {
-        let projects = workspace_manager.list_all_projects();
         if args.header {
             println!("Projects:");
         }
         for project in projects {
-            println!("{}", project.project_path);
+            let size = project
+                .database_size_bytes()
+                .map(|size| format!(" ({})", workspace_manager::format_bytes(size)))
+                .unwrap_or_default();
+            println!(
+                "{}{size}{}",
+                project.project_path,
+                status_marker(&project.status)
+            );
         }
     }
     Ok(())
 }
 
+#[cfg(any(debug_assertions, feature = "dev-tools"))]
+fn status_marker(status: &Status) -> &'static str {
+    match status {
+        Status::Outdated => {
+            " (outdated: indexed with an older graph schema, run `gkg index` again)"
+        }
+        Status::Stale => " (stale: changed since indexed, run `gkg index` again)",
+        _ => "",
+    }
+}
+
 #[cfg(not(any(debug_assertions, feature = "dev-tools")))]
 pub fn run(_workspace_manager: Arc<WorkspaceManager>, _args: ListArgs) -> Result<()> {
     anyhow::bail!("List command is not available. Use --features dev-tools to enable.")
@@ -0,0 +1,116 @@
+use anyhow::{Result, bail};
+use mcp::MCP_NAME;
+use mcp::duo_configuration::{
+    McpConfigCheck, add_local_http_server_to_mcp_config, default_duo_mcp_config_path,
+    diagnose_mcp_config, local_http_server_port, remove_local_http_server_from_mcp_config,
+};
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::utils::is_server_running;
+
+/// Writes the gkg entry into the MCP configuration without starting a server. The port
+/// defaults to the one of the running server.
+pub fn register(path: Option<PathBuf>, port: Option<u16>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => default_duo_mcp_config_path()?,
+    };
+    let port = match port {
+        Some(port) => port,
+        None => match is_server_running()? {
+            Some(port) => port,
+            None => bail!("No gkg server is running. Start one or pass the port with --port."),
+        },
+    };
+
+    add_local_http_server_to_mcp_config(path.clone(), port)?;
+    println!(
+        "Registered {MCP_NAME} (http://localhost:{port}/mcp/sse) in {}",
+        path.display()
+    );
+    Ok(())
+}
+
+pub fn unregister(path: Option<PathBuf>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => default_duo_mcp_config_path()?,
+    };
+
+    if remove_local_http_server_from_mcp_config(path.clone())? {
+        println!("Removed {MCP_NAME} from {}", path.display());
+    } else {
+        println!("{MCP_NAME} is not registered in {}", path.display());
+    }
+    Ok(())
+}
+
+const PORT_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Validates the MCP configuration and the server it points to, printing a checklist.
+pub fn doctor(path: Option<PathBuf>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => default_duo_mcp_config_path()?,
+    };
+
+    let diagnostics = diagnose_mcp_config(path);
+    let mut checks = diagnostics.checks;
+    let registered_port = diagnostics
+        .server_url
+        .as_deref()
+        .and_then(local_http_server_port);
+
+    let running_port = is_server_running()?;
+    match running_port {
+        Some(port) => checks.push(McpConfigCheck::pass(format!(
+            "gkg server is running on port {port}"
+        ))),
+        None => checks.push(McpConfigCheck::fail(
+            "gkg server is running",
+            "Start it with `gkg server start`.",
+        )),
+    }
+
+    if let (Some(url), Some(running_port)) = (&diagnostics.server_url, running_port) {
+        let description = format!("Registered URL {url} points to the running server");
+        if registered_port == Some(running_port) {
+            checks.push(McpConfigCheck::pass(description));
+        } else {
+            checks.push(McpConfigCheck::fail(
+                description,
+                "Run `gkg mcp register` to update the port.",
+            ));
+        }
+    }
+
+    if let Some(port) = running_port.or(registered_port) {
+        let description = format!("Port {port} is reachable");
+        let address = SocketAddr::from(([127, 0, 0, 1], port));
+        if TcpStream::connect_timeout(&address, PORT_CHECK_TIMEOUT).is_ok() {
+            checks.push(McpConfigCheck::pass(description));
+        } else {
+            checks.push(McpConfigCheck::fail(
+                description,
+                "Make sure the server is running and no firewall blocks local connections.",
+            ));
+        }
+    }
+
+    for check in &checks {
+        let mark = if check.passed { "[ok]  " } else { "[fail]" };
+        println!("{mark} {}", check.description);
+        if let Some(hint) = &check.hint {
+            println!("       {hint}");
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    if failed > 0 {
+        bail!("{failed} check(s) failed");
+    }
+    println!("All checks passed");
+    Ok(())
+}
@@ -1,5 +1,12 @@
+pub mod check;
 pub mod clean;
+pub mod compact;
+pub mod diff;
 pub mod index;
 pub mod list;
+pub mod mcp;
 pub mod query;
+pub mod replay_events;
 pub mod server;
+pub mod status;
+pub mod tag;
@@ -1,5 +1,8 @@
 use anyhow::Result;
-use database::kuzu::{config::DatabaseConfig, connection::KuzuConnection, database::KuzuDatabase};
+use database::kuzu::{
+    config::DatabaseConfig, connection::KuzuConnection, database::KuzuDatabase,
+    types::DatabaseError,
+};
 use std::sync::Arc;
 use tracing::info;
 use workspace_manager::WorkspaceManager;
@@ -7,6 +10,7 @@ use workspace_manager::WorkspaceManager;
 pub struct QueryArgs {
     pub project: String,
     pub query_or_file: String,
+    pub explain: bool,
 }
 
 #[cfg(any(debug_assertions, feature = "dev-tools"))]
@@ -28,9 +32,17 @@ pub fn run(
 
     // Get the database struct, so we can create a connection to it
     let config = DatabaseConfig::default().read_only();
-    let database = database
-        .get_or_create_database(db_path, Some(config))
-        .ok_or_else(|| anyhow::anyhow!("Failed to create database"))?;
+    let database = match database.try_get_or_create_database(db_path, Some(config)) {
+        Ok(database) => database,
+        Err(e @ DatabaseError::IncompatibleStorageVersion { .. }) => {
+            workspace_manager.mark_project_outdated(
+                &project_info.workspace_folder_path,
+                &project_info.project_path,
+            )?;
+            return Err(e.into());
+        }
+        Err(e) => anyhow::bail!("Failed to create database: {e}"),
+    };
 
     // Read the query from the file if provided
     let query = if std::path::Path::new(&args.query_or_file).exists() {
@@ -48,6 +60,13 @@ pub fn run(
 
     // Create a connection to the database and execute the query
     match KuzuConnection::new(&database) {
+        Ok(connection) if args.explain => {
+            let plan = connection
+                .explain(&query, serde_json::Map::new())
+                .map_err(|e| anyhow::anyhow!("Failed to explain query: {:?}", e))?;
+            // We're printing to stdout, so we don't need to use tracing
+            println!("{}\n\n{plan}", query.trim());
+        }
         Ok(connection) => {
             info!("Connection created successfully");
             match connection.query(&query) {
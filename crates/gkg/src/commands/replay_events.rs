@@ -0,0 +1,61 @@
+use anyhow::{Result, anyhow};
+use event_bus::{EventBus, read_event_log};
+use http_server_desktop::config::DEFAULT_SSE_KEEP_ALIVE_INTERVAL;
+use http_server_desktop::replay::{replay_events, serve_replay};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Serves the events of a log recorded with `--event-log` on the events endpoint, to debug the
+/// frontend's handling of events without reindexing. The replay starts once a client listens.
+pub async fn run(path: PathBuf, port: Option<u16>, speed: f64) -> Result<()> {
+    if !speed.is_finite() || speed <= 0.0 {
+        anyhow::bail!("--speed must be a positive number, got {speed}");
+    }
+
+    let events = read_event_log(&path)
+        .map_err(|e| anyhow!("Failed to read event log {}: {e}", path.display()))?;
+    if events.is_empty() {
+        // We're printing to stdout, so we don't need to use tracing
+        println!("No events in {}", path.display());
+        return Ok(());
+    }
+
+    let port = match port {
+        Some(port) => port,
+        None => http_server_desktop::find_unused_port()?,
+    };
+    let event_bus = Arc::new(EventBus::new());
+    let server = tokio::spawn(serve_replay(
+        port,
+        Arc::clone(&event_bus),
+        DEFAULT_SSE_KEEP_ALIVE_INTERVAL,
+    ));
+
+    println!(
+        "Replaying {} events from {} on http://localhost:{port}/api/events",
+        events.len(),
+        path.display()
+    );
+    println!("Waiting for a client to connect...");
+    while event_bus.sequenced_subscriber_count() == 0 {
+        if server.is_finished() {
+            return server.await?;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let total = events.len();
+    let mut sent = 0;
+    replay_events(&event_bus, &events, speed, |event| {
+        sent += 1;
+        println!(
+            "[{sent}/{total}] {}",
+            serde_json::to_string(event).unwrap_or_default()
+        );
+    })
+    .await;
+
+    println!("Replay finished, press Ctrl+C to stop");
+    server.await?
+}
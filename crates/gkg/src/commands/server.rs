@@ -6,25 +6,31 @@ use std::env;
 use std::io::Write;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
+use std::process;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
-use std::{fs, process};
-use tracing::info;
+use tracing::{info, warn};
 
 #[cfg(unix)]
-use nix::sys::signal::{Signal::SIGTERM, kill};
+use nix::sys::signal::{
+    Signal::{SIGKILL, SIGTERM},
+    kill,
+};
 #[cfg(unix)]
 use nix::unistd::Pid;
 
-#[cfg(windows)]
-use std::time::Duration;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
 
 use crate::utils::{
-    ServerInfo, ServerLockInfo, get_lock_file_path, get_single_instance, is_server_running,
-    read_lock_info, remove_lock_file, write_lock_info,
+    ServerInfo, ServerLockInfo, get_single_instance, is_server_running, read_lock_info,
+    remove_lock_file, remove_lock_file_if_owned, write_lock_info,
 };
 use database::kuzu::database::KuzuDatabase;
 use event_bus::EventBus;
+use http_server_desktop::config::ServerConfig;
+use http_server_desktop::endpoints::job_list::JobListSuccessResponse;
+use http_server_desktop::queue::JobInfo;
 use workspace_manager::WorkspaceManager;
 
 pub fn print_server_info(port: u16) -> Result<()> {
@@ -41,6 +47,8 @@ pub async fn start(
     detached: bool,
     port_override: Option<u16>,
     mcp_configuration_path: Option<std::path::PathBuf>,
+    allowed_projects: Vec<std::path::PathBuf>,
+    server_config: ServerConfig,
     database: Arc<KuzuDatabase>,
     workspace_manager: Arc<WorkspaceManager>,
     event_bus: Arc<EventBus>,
@@ -81,8 +89,48 @@ pub async fn start(
             if enable_reindexing {
                 args.push("--enable-reindexing".to_string());
             }
+            for path in &allowed_projects {
+                args.push("--allow-project".to_string());
+                args.push(path.display().to_string());
+            }
             args.push("--port".to_string());
             args.push(port.to_string());
+            args.push("--sse-keep-alive-secs".to_string());
+            args.push(server_config.sse_keep_alive_interval.as_secs().to_string());
+            args.push("--max-request-body-bytes".to_string());
+            args.push(server_config.max_request_body_bytes.to_string());
+            if let Some(max_jobs) = server_config.max_concurrent_indexing_jobs {
+                args.push("--max-concurrent-indexing-jobs".to_string());
+                args.push(max_jobs.to_string());
+            }
+            if server_config.scheduling_policy
+                == http_server_desktop::queue::SchedulingPolicy::ShortestJobFirst
+            {
+                args.push("--fair-scheduling".to_string());
+            }
+            if server_config.reindex_outdated_projects {
+                args.push("--reindex-outdated".to_string());
+            }
+            if let Some(query_cache) = server_config.query_cache {
+                args.push("--query-cache-size".to_string());
+                args.push(query_cache.capacity.to_string());
+                args.push("--query-cache-ttl-secs".to_string());
+                args.push(query_cache.ttl.as_secs().to_string());
+            }
+            if let Some(connection_pool) = server_config.connection_pool {
+                args.push("--connection-pool-size".to_string());
+                args.push(connection_pool.max_size.to_string());
+                args.push("--connection-idle-secs".to_string());
+                args.push(connection_pool.idle_timeout.as_secs().to_string());
+            }
+            if let Some(event_log) = &server_config.event_log {
+                args.push("--event-log".to_string());
+                args.push(std::path::absolute(event_log)?.display().to_string());
+            }
+            if let Some(parquet_root) = workspace_manager.parquet_root() {
+                args.push("--parquet-dir".to_string());
+                args.push(parquet_root.display().to_string());
+            }
 
             let mut cmd = Command::new(current_exe);
             cmd.args(args)
@@ -129,26 +177,39 @@ pub async fn start(
             add_local_http_server_to_mcp_config(mcp_config_path, port)?;
         }
 
-        let mcp_configuration = match mcp_configuration_path {
-            Some(path) => Arc::new(mcp::configuration::read_mcp_configuration(path)),
-            None => Arc::new(get_or_create_mcp_configuration(workspace_manager.clone())),
+        let mut mcp_configuration = match mcp_configuration_path {
+            Some(path) => mcp::configuration::read_mcp_configuration(path),
+            None => get_or_create_mcp_configuration(workspace_manager.clone()),
         };
+        for path in allowed_projects {
+            let path = path.canonicalize().unwrap_or(path);
+            mcp_configuration
+                .allowed_projects
+                .insert(path.to_string_lossy().to_string());
+        }
+        let mcp_configuration = Arc::new(mcp_configuration);
 
-        let l_file = get_lock_file_path()?;
+        let pid = process::id();
         ctrlc::set_handler(move || {
-            let _ = fs::remove_file(&l_file);
+            let _ = remove_lock_file_if_owned(pid);
             process::exit(0);
         })?;
 
-        http_server_desktop::run(
+        let result = http_server_desktop::run(
             port,
             enable_reindexing,
             Arc::clone(&database),
             Arc::clone(&workspace_manager),
             Arc::clone(&event_bus),
             Arc::clone(&mcp_configuration),
+            server_config,
         )
-        .await
+        .await;
+
+        // The server stopped on its own (e.g. SIGTERM); drop its entry so clients don't
+        // try to reach it
+        let _ = remove_lock_file_if_owned(pid);
+        result
     } else if let Some(port) = is_server_running()? {
         if let Some(mcp_config_path) = register_duo_mcp {
             add_local_http_server_to_mcp_config(mcp_config_path, port)?;
@@ -164,38 +225,169 @@ pub async fn start(
     }
 }
 
-pub async fn stop() -> Result<()> {
-    if let Some(info) = read_lock_info()? {
-        // Try graceful stop via SIGTERM on Unix, else remove lock if process gone
-        #[cfg(unix)]
+/// How long `stop` waits for the server to shut down after asking it to.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long `stop --force` waits for the server to disappear after killing it.
+const FORCE_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub async fn stop(force: bool) -> Result<()> {
+    let Some(info) = read_lock_info()? else {
+        bail!("No running server found");
+    };
+
+    // Try graceful stop via SIGTERM on Unix, escalating to SIGKILL with --force
+    #[cfg(unix)]
+    {
+        if let Some(pid) = info.pid
+            && kill(Pid::from_raw(pid as i32), None).is_ok()
         {
-            if let Some(pid) = info.pid
-                && kill(Pid::from_raw(pid as i32), None).is_ok()
-            {
-                let _ = kill(Pid::from_raw(pid as i32), SIGTERM);
+            let _ = kill(Pid::from_raw(pid as i32), SIGTERM);
+
+            if !wait_for_shutdown(&info, STOP_TIMEOUT).await {
+                if !force {
+                    bail!(
+                        "gkg server (pid {pid}) did not stop within {}s; run `gkg server stop --force` to kill it",
+                        STOP_TIMEOUT.as_secs()
+                    );
+                }
+
+                warn!(
+                    "gkg server (pid {}) did not stop within {}s, killing it",
+                    pid,
+                    STOP_TIMEOUT.as_secs()
+                );
+                let _ = kill(Pid::from_raw(pid as i32), SIGKILL);
+                // A killed server can't clean up after itself
+                let _ = remove_lock_file_if_owned(pid);
+
+                if !wait_for_shutdown(&info, FORCE_STOP_TIMEOUT).await {
+                    bail!("gkg server (pid {pid}) could not be killed");
+                }
             }
         }
+    }
 
-        // Best effort to stop the server on windows, on windows server cannot run in detached mode
-        // so it is up to the caller to stop the server via ^C or taskkill
-        // TODO: rework windows handling to use windows-service approach
-        #[cfg(windows)]
-        {
-            if let Some(pid) = info.pid {
-                // Temporary behavior: forceful termination only
-                let _ = Command::new("taskkill")
-                    .args(["/PID", &pid.to_string(), "/T", "/F"])
-                    .status();
+    // Best effort to stop the server on windows, on windows server cannot run in detached mode
+    // so it is up to the caller to stop the server via ^C or taskkill
+    // TODO: rework windows handling to use windows-service approach
+    #[cfg(windows)]
+    {
+        let _ = force;
+        if let Some(pid) = info.pid {
+            // Temporary behavior: forceful termination only
+            let _ = Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .status();
+            if !wait_for_shutdown(&info, STOP_TIMEOUT).await {
+                bail!("gkg server (pid {pid}) did not stop");
             }
         }
+    }
 
-        let _ = remove_lock_file();
-        info!("Server stopped");
-        println!(
-            "{}",
-            serde_json::to_string(&ServerInfo { port: info.port })?
+    // Clears the entry of a server that died without removing it
+    let _ = remove_lock_file();
+    info!("Server stopped");
+    println!(
+        "{}",
+        serde_json::to_string(&ServerInfo { port: info.port })?
+    );
+    Ok(())
+}
+
+/// Polls until the server has removed its lock file entry (or its process is gone) and
+/// its port no longer accepts connections. Returns `false` if `timeout` elapses first.
+async fn wait_for_shutdown(info: &ServerLockInfo, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !lock_points_to_live_server(info) && !is_port_open(info.port) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(STOP_POLL_INTERVAL).await;
+    }
+}
+
+fn lock_points_to_live_server(info: &ServerLockInfo) -> bool {
+    let Ok(Some(current)) = read_lock_info() else {
+        return false;
+    };
+    if current != *info {
+        return false;
+    }
+
+    #[cfg(unix)]
+    if let Some(pid) = info.pid {
+        return kill(Pid::from_raw(pid as i32), None).is_ok();
+    }
+
+    true
+}
+
+fn is_port_open(port: u16) -> bool {
+    TcpStream::connect_timeout(
+        &std::net::SocketAddr::from(([127, 0, 0, 1], port)),
+        STOP_POLL_INTERVAL,
+    )
+    .is_ok()
+}
+
+pub async fn status() -> Result<()> {
+    let Some(port) = is_server_running()? else {
+        println!("No gkg server is running");
+        return Ok(());
+    };
+    let pid = read_lock_info()?.and_then(|info| info.pid);
+
+    let url = format!("http://127.0.0.1:{port}/api/jobs");
+    let response = match reqwest::get(&url).await {
+        Ok(response) => response,
+        Err(e) if e.is_connect() || e.is_timeout() => {
+            println!("No gkg server is responding on port {port}");
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !response.status().is_success() {
+        bail!(
+            "gkg server on port {port} returned {} for {url}",
+            response.status()
         );
+    }
+    let body: JobListSuccessResponse = serde_json::from_str(&response.text().await?)?;
+
+    match pid {
+        Some(pid) => println!("gkg server is running on port {port} (pid {pid})"),
+        None => println!("gkg server is running on port {port}"),
+    }
+    if body.jobs.is_empty() {
+        println!("No jobs");
         return Ok(());
     }
-    bail!("No running server found");
+
+    println!();
+    println!(
+        "{:<10} {:<40} {:<25} WORKSPACE",
+        "STATUS", "TYPE", "CREATED"
+    );
+    for job in &body.jobs {
+        println!("{}", format_job_row(job));
+    }
+    Ok(())
+}
+
+fn format_job_row(job: &JobInfo) -> String {
+    let mut row = format!(
+        "{:<10} {:<40} {:<25} {}",
+        format!("{:?}", job.status),
+        job.job.job_type(),
+        job.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        job.job.workspace_path()
+    );
+    if let Some(error) = &job.error {
+        row.push_str(&format!(" ({error})"));
+    }
+    row
 }
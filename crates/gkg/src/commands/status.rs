@@ -0,0 +1,137 @@
+use anyhow::Result;
+use database::schema::manager::SCHEMA_VERSION;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use workspace_manager::{ProjectInfo, Status, WorkspaceManager, git_state};
+
+/// How a project's graph compares to its current source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    Fresh,
+    Stale,
+    Outdated,
+    NeverIndexed,
+    Indexing,
+    Failed,
+}
+
+impl Freshness {
+    const ALL: [Freshness; 6] = [
+        Freshness::Fresh,
+        Freshness::Stale,
+        Freshness::Outdated,
+        Freshness::NeverIndexed,
+        Freshness::Indexing,
+        Freshness::Failed,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Freshness::Fresh => "fresh",
+            Freshness::Stale => "stale",
+            Freshness::Outdated => "outdated",
+            Freshness::NeverIndexed => "never indexed",
+            Freshness::Indexing => "indexing",
+            Freshness::Failed => "failed",
+        }
+    }
+}
+
+/// Only reads the manifest and the git checkouts, so the server may be running
+fn freshness(project: &ProjectInfo) -> Freshness {
+    match project.status {
+        Status::Error => Freshness::Failed,
+        Status::Indexing | Status::Reindexing => Freshness::Indexing,
+        Status::Outdated => Freshness::Outdated,
+        Status::Stale => Freshness::Stale,
+        Status::Pending => Freshness::NeverIndexed,
+        Status::Indexed => match project.last_indexed_at {
+            None => Freshness::NeverIndexed,
+            Some(_) if project.schema_version != Some(SCHEMA_VERSION) => Freshness::Outdated,
+            Some(indexed_at) => {
+                if git_state::has_changed_since_indexing(
+                    Path::new(&project.project_path),
+                    project.indexed_commit.as_deref(),
+                    indexed_at,
+                ) {
+                    Freshness::Stale
+                } else {
+                    Freshness::Fresh
+                }
+            }
+        },
+    }
+}
+
+fn details(project: &ProjectInfo, freshness: Freshness) -> String {
+    if freshness == Freshness::Failed {
+        return project.error_message.clone().unwrap_or_default();
+    }
+    let Some(indexed_at) = project.last_indexed_at else {
+        return String::new();
+    };
+    let indexed_at = indexed_at.format("%Y-%m-%d %H:%M UTC");
+    match &project.indexed_commit {
+        Some(commit) => format!("indexed {indexed_at} at {}", &commit[..commit.len().min(8)]),
+        None => format!("indexed {indexed_at}"),
+    }
+}
+
+pub fn run(workspace_manager: Arc<WorkspaceManager>, workspace: Option<PathBuf>) -> Result<()> {
+    let workspace_folders = match workspace {
+        Some(workspace) => {
+            let workspace = dunce::canonicalize(&workspace)?;
+            let workspace_folder = workspace_manager
+                .get_workspace_folder_info(&workspace.to_string_lossy())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Workspace folder not found: {}. Index it with `gkg index` first.",
+                        workspace.display()
+                    )
+                })?;
+            vec![workspace_folder]
+        }
+        None => workspace_manager.list_workspace_folders(),
+    };
+
+    let mut counts = [0usize; Freshness::ALL.len()];
+    for workspace_folder in &workspace_folders {
+        let mut projects =
+            workspace_manager.list_projects_in_workspace(&workspace_folder.workspace_folder_path);
+        projects.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+        // We're printing to stdout, so we don't need to use tracing
+        println!("{}", workspace_folder.workspace_folder_path);
+        for project in &projects {
+            let project_freshness = freshness(project);
+            counts[project_freshness as usize] += 1;
+            let line = format!(
+                "  {:<14}{}  {}",
+                project_freshness.label(),
+                project.project_path,
+                details(project, project_freshness)
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+
+    let total: usize = counts.iter().sum();
+    let summary: Vec<String> = Freshness::ALL
+        .iter()
+        .filter(|freshness| counts[**freshness as usize] > 0)
+        .map(|freshness| format!("{} {}", counts[*freshness as usize], freshness.label()))
+        .collect();
+    if total == 0 {
+        println!("No indexed projects. Index a workspace with `gkg index` first.");
+        return Ok(());
+    }
+    println!();
+    println!("{total} project(s): {}", summary.join(", "));
+
+    // Only a graph that fell behind its source is an error, the other states are reported
+    if counts[Freshness::Stale as usize] > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
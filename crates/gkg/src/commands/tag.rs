@@ -0,0 +1,46 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::utils::is_server_running;
+use workspace_manager::WorkspaceManager;
+
+pub fn run(
+    workspace_manager: Arc<WorkspaceManager>,
+    project_path: PathBuf,
+    tags: Vec<String>,
+) -> Result<()> {
+    if let Some(port) = is_server_running()? {
+        error!("Error: gkg server is running on port {port}. Stop it before tagging projects.");
+        process::exit(1);
+    }
+
+    let project_path = dunce::canonicalize(&project_path)?;
+    let project = workspace_manager
+        .get_project_for_path(&project_path.to_string_lossy())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Project not found: {}. Index it with `gkg index` first.",
+                project_path.display()
+            )
+        })?;
+
+    let project = workspace_manager.set_project_tags(
+        &project.workspace_folder_path,
+        &project.project_path,
+        tags,
+    )?;
+
+    if project.tags.is_empty() {
+        info!("Cleared the tags of {}", project.project_path);
+    } else {
+        info!(
+            "Tagged {} with: {}",
+            project.project_path,
+            project.tags.join(", ")
+        );
+    }
+    Ok(())
+}
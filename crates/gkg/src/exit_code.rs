@@ -0,0 +1,33 @@
+//! The exit codes of `gkg`, documented with `gkg index`. They're stable, CI pipelines tell
+//! a partial failure from a total one with them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    /// The command couldn't run, e.g. because the server is running
+    Error = 1,
+    /// Some of the projects failed to index, the others were indexed
+    PartialFailure = 2,
+    /// Nothing was indexed because every project or workspace folder failed
+    TotalFailure = 3,
+    /// The arguments are invalid
+    UsageError = 4,
+}
+
+impl ExitCode {
+    /// The exit code of an index run from its count of indexed and failed projects. A
+    /// workspace folder that couldn't be indexed at all counts as a failed project.
+    pub fn from_indexing(indexed: usize, failed: usize) -> Self {
+        match (indexed, failed) {
+            (_, 0) => ExitCode::Success,
+            (0, _) => ExitCode::TotalFailure,
+            _ => ExitCode::PartialFailure,
+        }
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(exit_code: ExitCode) -> Self {
+        std::process::ExitCode::from(exit_code as u8)
+    }
+}
@@ -3,33 +3,53 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 mod cli;
 mod commands;
+mod exit_code;
+mod progress_bar;
 mod utils;
 
-use crate::commands::{clean, index, list, query, server};
-use cli::{Commands, DevToolsCommands, GkgCli, ServerCommands, ServerStartArgs};
+use crate::commands::{
+    check, clean, compact, diff, index, list, mcp, query, replay_events, server, status, tag,
+};
+use cli::{Commands, DevToolsCommands, GkgCli, McpCommands, ServerCommands, ServerStartArgs};
 use database::kuzu::database::KuzuDatabase;
+use database::querying::cache::DEFAULT_QUERY_CACHE_TTL;
+use database::querying::pool::DEFAULT_CONNECTION_IDLE_TIMEOUT;
 use event_bus::EventBus;
-use logging::LogMode;
+use exit_code::ExitCode;
+use http_server_desktop::config::{
+    DEFAULT_MAX_REQUEST_BODY_BYTES, DEFAULT_SSE_KEEP_ALIVE_INTERVAL,
+};
+use logging::{LogFormat, LogMode, Verbosity};
+use progress_bar::ProgressBar;
 use std::sync::Arc;
-use workspace_manager::WorkspaceManager;
+use std::time::Duration;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use workspace_manager::{DataDirectory, WorkspaceManager};
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cli = GkgCli::parse_args();
+async fn main() -> anyhow::Result<std::process::ExitCode> {
+    let cli = match GkgCli::parse_args() {
+        Ok(cli) => cli,
+        Err(exit_code) => return Ok(exit_code.into()),
+    };
 
-    let verbose = match &cli.command {
-        Commands::Index { verbose, .. } => *verbose,
+    let verbosity = match &cli.command {
+        Commands::Index { verbose, quiet, .. } => Verbosity::from_flags(*verbose, *quiet),
         Commands::Server {
             action: Some(ServerCommands::Start(args)),
             ..
-        } => args.verbose,
+        } => Verbosity::from_verbose(args.verbose),
         Commands::Server {
-            action: Some(ServerCommands::Stop),
+            action: Some(ServerCommands::Stop(_) | ServerCommands::Status),
             ..
-        } => false,
-        Commands::Server { action: None, .. } => false,
-        Commands::Clean => false,
-        Commands::DevTools { .. } => false,
+        } => Verbosity::Normal,
+        Commands::Server { action: None, .. } => Verbosity::Normal,
+        Commands::Mcp { .. } => Verbosity::Normal,
+        Commands::Clean { .. } => Verbosity::Normal,
+        Commands::Tag { .. } => Verbosity::Normal,
+        Commands::Status { .. } => Verbosity::Normal,
+        Commands::Diff { .. } => Verbosity::Normal,
+        Commands::DevTools { .. } => Verbosity::Normal,
     };
 
     let mode = match &cli.command {
@@ -42,51 +62,142 @@ async fn main() -> anyhow::Result<()> {
                     LogMode::ServerForeground
                 }
             }
-            Some(ServerCommands::Stop) => LogMode::ServerForeground,
+            Some(ServerCommands::Stop(_) | ServerCommands::Status) => LogMode::ServerForeground,
             None => LogMode::ServerForeground, // Default to start command
         },
-        Commands::Clean => LogMode::Cli,
+        Commands::Mcp { .. } => LogMode::Cli,
+        Commands::Clean { .. } => LogMode::Cli,
+        Commands::Tag { .. } => LogMode::Cli,
+        Commands::Status { .. } => LogMode::Cli,
+        Commands::Diff { .. } => LogMode::Cli,
         Commands::DevTools { .. } => LogMode::Cli,
     };
 
-    let _guard = logging::init(mode, verbose)?;
+    let profile = match &cli.command {
+        Commands::Index { profile, .. } => profile.clone(),
+        _ => None,
+    };
+
+    let log_format = match &cli.command {
+        Commands::Index { log_format, .. } => *log_format,
+        _ => LogFormat::Text,
+    };
+
+    // JSON logs are read by tools, which the progress bars would get in the way of
+    let progress_bar = match &cli.command {
+        Commands::Index {
+            quiet: false,
+            log_format: LogFormat::Text,
+            ..
+        } => ProgressBar::for_terminal(),
+        _ => None,
+    };
+
+    let _guard = match &progress_bar {
+        Some(progress_bar) => logging::init_cli_with_writer(
+            verbosity,
+            log_format,
+            profile.as_deref(),
+            BoxMakeWriter::new(progress_bar.clone()),
+        )?,
+        None => logging::init(mode, verbosity, log_format, profile.as_deref())?,
+    };
+
+    let parquet_dir = match &cli.command {
+        Commands::Index { parquet_dir, .. } => parquet_dir.clone(),
+        Commands::Server {
+            action: Some(ServerCommands::Start(args)),
+        } => args.parquet_dir.clone(),
+        _ => None,
+    };
 
-    let workspace_manager = Arc::new(WorkspaceManager::new_system_default()?);
+    let mut data_directory = DataDirectory::new_system_default()?;
+    if let Some(parquet_dir) = parquet_dir {
+        data_directory = data_directory.with_parquet_root(std::path::absolute(parquet_dir)?);
+    }
+    let workspace_manager = Arc::new(WorkspaceManager::new_with_data_directory(data_directory)?);
     let event_bus = Arc::new(EventBus::new());
     let database = Arc::new(KuzuDatabase::new());
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Index {
-            workspace_path,
+            workspace_paths,
             threads,
             verbose: _,
+            quiet: _,
+            log_format: _,
             stats,
+            parquet_dir: _,
+            no_parquet,
+            parquet_compression,
+            parquet_batch_size,
+            parse_memory_budget,
+            call_betweenness,
+            extension_overrides,
+            exclude_patterns,
+            cross_project,
+            no_references,
+            public_only,
+            snapshot_history,
+            follow_symlinks,
+            invalid_utf8,
+            project_timeout,
+            git_ref,
+            event_log,
+            fail_fast,
+            profile: _,
         } => {
-            index::run(
-                workspace_path,
-                threads,
-                stats,
+            use crate::commands::index::IndexArgs;
+            let exit_code = index::run(
+                IndexArgs {
+                    workspace_paths,
+                    threads,
+                    stats_output: stats,
+                    write_parquet: !no_parquet,
+                    parquet_compression,
+                    parquet_batch_size,
+                    parse_memory_budget: parse_memory_budget.map(|mib| mib * 1024 * 1024),
+                    compute_call_betweenness: call_betweenness,
+                    extension_overrides,
+                    exclude_patterns,
+                    cross_project_resolution: cross_project,
+                    resolve_references: !no_references,
+                    public_only,
+                    definition_snapshot_history: snapshot_history,
+                    follow_symlinks,
+                    invalid_utf8,
+                    project_timeout: project_timeout.map(Duration::from_secs),
+                    git_ref,
+                    event_log,
+                    fail_fast,
+                },
+                progress_bar,
                 Arc::clone(&workspace_manager),
                 Arc::clone(&event_bus),
                 Arc::clone(&database),
             )
-            .await
+            .await?;
+            return Ok(exit_code.into());
         }
         Commands::Server { action } => match action {
             Some(ServerCommands::Start(args)) => {
+                let server_config = args.server_config();
                 server::start(
                     args.register_mcp,
                     args.enable_reindexing,
                     args.detached,
                     args.port,
                     args.mcp_configuration_path,
+                    args.allowed_projects,
+                    server_config,
                     Arc::clone(&database),
                     Arc::clone(&workspace_manager),
                     Arc::clone(&event_bus),
                 )
                 .await
             }
-            Some(ServerCommands::Stop) => server::stop().await,
+            Some(ServerCommands::Stop(args)) => server::stop(args.force).await,
+            Some(ServerCommands::Status) => server::status().await,
             None => {
                 // Default behavior: start with default arguments
                 // FIXME: This is a temporary fix to allow the server to start with default arguments
@@ -96,14 +207,29 @@ async fn main() -> anyhow::Result<()> {
                     detached: false,
                     port: None,
                     mcp_configuration_path: None,
+                    allowed_projects: Vec::new(),
                     verbose: false,
+                    sse_keep_alive_secs: DEFAULT_SSE_KEEP_ALIVE_INTERVAL.as_secs(),
+                    max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+                    max_concurrent_indexing_jobs: None,
+                    fair_scheduling: false,
+                    parquet_dir: None,
+                    reindex_outdated: false,
+                    query_cache_size: None,
+                    query_cache_ttl_secs: DEFAULT_QUERY_CACHE_TTL.as_secs(),
+                    connection_pool_size: None,
+                    connection_idle_secs: DEFAULT_CONNECTION_IDLE_TIMEOUT.as_secs(),
+                    event_log: None,
                 };
+                let server_config = args.server_config();
                 server::start(
                     args.register_mcp,
                     args.enable_reindexing,
                     args.detached,
                     args.port,
                     args.mcp_configuration_path,
+                    args.allowed_projects,
+                    server_config,
                     Arc::clone(&database),
                     Arc::clone(&workspace_manager),
                     Arc::clone(&event_bus),
@@ -111,11 +237,27 @@ async fn main() -> anyhow::Result<()> {
                 .await
             }
         },
-        Commands::Clean => clean::run(Arc::clone(&workspace_manager)),
+        Commands::Mcp { action } => match action {
+            McpCommands::Register { path, port } => mcp::register(path, port),
+            McpCommands::Unregister { path } => mcp::unregister(path),
+            McpCommands::Doctor { path } => mcp::doctor(path),
+        },
+        Commands::Clean { yes } => clean::run(Arc::clone(&workspace_manager), yes),
+        Commands::Tag { project_path, tags } => {
+            tag::run(Arc::clone(&workspace_manager), project_path, tags)
+        }
+        Commands::Status { workspace } => status::run(Arc::clone(&workspace_manager), workspace),
+        Commands::Diff {
+            project_path,
+            from,
+            to,
+            json,
+        } => diff::run(Arc::clone(&workspace_manager), project_path, from, to, json),
         Commands::DevTools { command } => match command {
             DevToolsCommands::Query {
                 project,
                 query_or_file,
+                explain,
             } => {
                 use crate::commands::query::QueryArgs;
                 query::run(
@@ -124,13 +266,30 @@ async fn main() -> anyhow::Result<()> {
                     QueryArgs {
                         project,
                         query_or_file,
+                        explain,
                     },
                 )
             }
+            DevToolsCommands::Check { project } => check::run(
+                Arc::clone(&workspace_manager),
+                Arc::clone(&database),
+                project,
+            ),
+            DevToolsCommands::Compact { project } => compact::run(
+                Arc::clone(&workspace_manager),
+                Arc::clone(&database),
+                project,
+            ),
+            DevToolsCommands::ReplayEvents { path, port, speed } => {
+                replay_events::run(path, port, speed).await
+            }
             DevToolsCommands::List {
                 projects,
                 workspace_folders,
                 header,
+                format,
+                sort_by,
+                filter_status,
             } => {
                 use crate::commands::list::ListArgs;
                 list::run(
@@ -139,9 +298,13 @@ async fn main() -> anyhow::Result<()> {
                         projects,
                         workspace_folders,
                         header,
+                        format,
+                        sort_by,
+                        filter_status,
                     },
                 )
             }
         },
-    }
+    };
+    result.map(|()| ExitCode::Success.into())
 }
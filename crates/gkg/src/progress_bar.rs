@@ -0,0 +1,264 @@
+//! The progress bars of `gkg index`, drawn with indicatif at the bottom of the terminal while
+//! the logs scroll above them: one for the project being indexed and one for the workspace
+//! folder. They're only shown when STDOUT is a terminal, so piped output stays clean.
+
+use indexer::progress::{IndexingPhase, IndexingProgressSummary, ProgressSink};
+use indicatif::{MultiProgress, ProgressDrawTarget, ProgressStyle};
+use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing_subscriber::fmt::MakeWriter;
+
+const PROJECT_TEMPLATE: &str = "{prefix:30!} {msg:9} [{bar:30}] {pos}/{len} files";
+const OVERALL_TEMPLATE: &str = "[{pos}/{len}] projects, {msg}";
+
+/// Shows the progress reported to it as a [`ProgressSink`]. The console logs must be written
+/// through it, as a [`MakeWriter`], so they're printed above the bars instead of over them.
+#[derive(Clone)]
+pub struct ProgressBar {
+    bars: MultiProgress,
+    /// The files parsed of the project being indexed, with its phase
+    project: indicatif::ProgressBar,
+    /// The projects indexed of the workspace folder, with the files parsed in total
+    overall: indicatif::ProgressBar,
+    /// Files of the projects indexed so far
+    completed_files: Arc<AtomicUsize>,
+}
+
+impl ProgressBar {
+    /// Progress bars when STDOUT is a terminal
+    pub fn for_terminal() -> Option<Self> {
+        io::stdout()
+            .is_terminal()
+            .then(|| Self::new(ProgressDrawTarget::stdout()))
+    }
+
+    fn new(target: ProgressDrawTarget) -> Self {
+        let bars = MultiProgress::with_draw_target(target);
+        let project = bars.add(
+            indicatif::ProgressBar::new(0).with_style(
+                ProgressStyle::with_template(PROJECT_TEMPLATE)
+                    .expect("valid progress bar template")
+                    .progress_chars("#-"),
+            ),
+        );
+        let overall = bars.add(indicatif::ProgressBar::new(0).with_style(
+            ProgressStyle::with_template(OVERALL_TEMPLATE).expect("valid progress bar template"),
+        ));
+        Self {
+            bars,
+            project,
+            overall,
+            completed_files: Arc::default(),
+        }
+    }
+
+    /// Removes the bars, e.g. before printing the summary of the run
+    pub fn finish(&self) {
+        self.project.finish_and_clear();
+        self.overall.finish_and_clear();
+    }
+
+    fn set_total_files(&self, parsed_files: usize) {
+        let total = self.completed_files.load(Ordering::Relaxed) + parsed_files;
+        self.overall.set_message(format!("{total} files in total"));
+    }
+}
+
+fn phase_label(phase: IndexingPhase) -> &'static str {
+    match phase {
+        IndexingPhase::Parsing => "Parsing",
+        IndexingPhase::Analyzing => "Analyzing",
+        IndexingPhase::Writing => "Writing",
+        IndexingPhase::Loading => "Loading",
+    }
+}
+
+impl ProgressSink for ProgressBar {
+    fn on_project_started(&self, project_path: &str, number: usize, total: usize) {
+        let project_name = project_path
+            .rsplit(['/', '\\'])
+            .find(|segment| !segment.is_empty())
+            .unwrap_or(project_path)
+            .to_string();
+        self.project.reset();
+        self.project.set_length(0);
+        self.project.set_prefix(project_name);
+        self.project
+            .set_message(phase_label(IndexingPhase::Parsing));
+        self.overall.set_length(total as u64);
+        self.overall.set_position(number.saturating_sub(1) as u64);
+        self.set_total_files(0);
+    }
+
+    fn on_file_parsed(&self, _file_path: &str, completed: usize, total: usize) {
+        self.project.set_length(total as u64);
+        self.project.set_position(completed as u64);
+        self.set_total_files(completed);
+    }
+
+    fn on_phase_changed(&self, phase: IndexingPhase) {
+        self.project.set_message(phase_label(phase));
+    }
+
+    fn on_complete(&self, summary: &IndexingProgressSummary) {
+        self.completed_files.fetch_add(
+            summary.processed_files + summary.skipped_files + summary.errored_files,
+            Ordering::Relaxed,
+        );
+        self.overall.inc(1);
+        self.set_total_files(0);
+    }
+}
+
+/// Writes a log line to STDOUT with the bars hidden, they're drawn again below it
+pub struct ProgressBarWriter<'a> {
+    bars: &'a MultiProgress,
+}
+
+impl Write for ProgressBarWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bars.suspend(|| io::stdout().lock().write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for ProgressBar {
+    type Writer = ProgressBarWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ProgressBarWriter { bars: &self.bars }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indicatif::TermLike;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// A terminal keeping the rows drawn on it
+    #[derive(Debug, Clone, Default)]
+    struct Screen(Arc<Mutex<ScreenState>>);
+
+    #[derive(Debug, Default)]
+    struct ScreenState {
+        rows: Vec<String>,
+        cursor: usize,
+    }
+
+    impl ScreenState {
+        fn row(&mut self) -> &mut String {
+            if self.rows.len() <= self.cursor {
+                self.rows.resize(self.cursor + 1, String::new());
+            }
+            &mut self.rows[self.cursor]
+        }
+    }
+
+    impl Screen {
+        fn rows(&self) -> Vec<String> {
+            let state = self.0.lock().unwrap();
+            state
+                .rows
+                .iter()
+                .map(|row| row.trim_end().to_string())
+                .collect()
+        }
+    }
+
+    impl TermLike for Screen {
+        fn width(&self) -> u16 {
+            120
+        }
+
+        fn move_cursor_up(&self, n: usize) -> io::Result<()> {
+            let mut state = self.0.lock().unwrap();
+            state.cursor = state.cursor.saturating_sub(n);
+            Ok(())
+        }
+
+        fn move_cursor_down(&self, n: usize) -> io::Result<()> {
+            self.0.lock().unwrap().cursor += n;
+            Ok(())
+        }
+
+        fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_line(&self, s: &str) -> io::Result<()> {
+            let mut state = self.0.lock().unwrap();
+            state.row().push_str(s);
+            state.cursor += 1;
+            Ok(())
+        }
+
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .row()
+                .push_str(s.trim_start_matches('\r'));
+            Ok(())
+        }
+
+        fn clear_line(&self) -> io::Result<()> {
+            self.0.lock().unwrap().row().clear();
+            Ok(())
+        }
+
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bars_show_the_project_and_the_workspace_folder() {
+        let screen = Screen::default();
+        let progress_bar =
+            ProgressBar::new(ProgressDrawTarget::term_like(Box::new(screen.clone())));
+
+        progress_bar.on_project_started("/work/api", 1, 2);
+        progress_bar.on_file_parsed("main.rb", 4, 4);
+        progress_bar.on_complete(&IndexingProgressSummary {
+            repository_path: "/work/api".to_string(),
+            processed_files: 4,
+            skipped_files: 0,
+            errored_files: 0,
+            elapsed: Duration::ZERO,
+        });
+        progress_bar.on_project_started("/work/web/", 2, 2);
+        progress_bar.on_file_parsed("app.ts", 3, 8);
+        progress_bar.on_phase_changed(IndexingPhase::Analyzing);
+
+        let rows = screen.rows();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("web "));
+        assert!(rows[0].contains("Analyzing"));
+        assert!(rows[0].ends_with("3/8 files"));
+        assert_eq!(rows[1], "[1/2] projects, 7 files in total");
+    }
+
+    #[test]
+    fn test_finish_clears_the_bars() {
+        let screen = Screen::default();
+        let progress_bar =
+            ProgressBar::new(ProgressDrawTarget::term_like(Box::new(screen.clone())));
+
+        progress_bar.on_project_started("/work/api", 1, 1);
+        progress_bar.on_phase_changed(IndexingPhase::Writing);
+        progress_bar.finish();
+
+        assert!(screen.rows().iter().all(String::is_empty));
+    }
+}
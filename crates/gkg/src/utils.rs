@@ -74,6 +74,17 @@ pub fn remove_lock_file() -> Result<()> {
     Ok(())
 }
 
+/// Removes the lock file only if it still describes the server with `pid`, so a server
+/// shutting down never deletes the entry of another instance that started in the meantime.
+pub fn remove_lock_file_if_owned(pid: u32) -> Result<()> {
+    if let Some(info) = read_lock_info()?
+        && info.pid == Some(pid)
+    {
+        remove_lock_file()?;
+    }
+    Ok(())
+}
+
 pub fn is_server_running() -> Result<Option<u16>> {
     let Some(lock) = read_lock_info()? else {
         return Ok(None);
@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use tempfile::TempDir;
+use testing::repository::TestRepository;
+
+fn gkg(home: &TempDir) -> Command {
+    let mut command = Command::cargo_bin("gkg").expect("cargo bin gkg");
+    command.env("HOME", home.path());
+    command
+}
+
+#[test]
+fn clean_asks_for_confirmation_before_removing_data() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    TestRepository::with_files(
+        workspace.path(),
+        &[("main.rb", "class Main\n  def run; end\nend\n")],
+    );
+    let workspace_folders_dir = temp_home.path().join(".gkg").join("gkg_workspace_folders");
+
+    gkg(&temp_home)
+        .args(["index", workspace.path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    let assert = gkg(&temp_home)
+        .arg("clean")
+        .write_stdin("n\n")
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    assert!(stdout.contains("This removes the indexed data of 1 workspace folders"));
+    assert!(stdout.contains("Clean cancelled"));
+    assert!(
+        std::fs::read_dir(&workspace_folders_dir)
+            .unwrap()
+            .next()
+            .is_some()
+    );
+
+    // Without input there's no one to confirm
+    gkg(&temp_home)
+        .arg("clean")
+        .write_stdin("")
+        .assert()
+        .failure();
+
+    gkg(&temp_home).args(["clean", "--yes"]).assert().success();
+    assert!(!workspace_folders_dir.exists());
+}
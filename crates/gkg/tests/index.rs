@@ -0,0 +1,188 @@
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+use testing::repository::TestRepository;
+
+fn create_repo(path: &Path) -> String {
+    TestRepository::with_files(path, &[("main.rb", "class Main\n  def run; end\nend\n")]);
+    path.to_str().unwrap().to_string()
+}
+
+/// The workspace paths of the reports in a `--stats` file
+fn stats_workspace_paths(stats_path: &Path) -> Vec<String> {
+    let stats: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(stats_path).unwrap()).unwrap();
+    stats
+        .as_array()
+        .expect("the stats file holds an array")
+        .iter()
+        .map(|report| {
+            report["metadata"]["workspace_path"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+fn gkg_index(home: &TempDir, args: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("gkg").expect("cargo bin gkg");
+    command
+        .arg("index")
+        .args(args)
+        .env("HOME", home.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    command
+}
+
+#[test]
+fn index_exits_with_usage_error_on_invalid_arguments() {
+    let temp_home = TempDir::new().expect("temp home");
+
+    gkg_index(&temp_home, &["--no-such-option"])
+        .assert()
+        .code(4);
+    gkg_index(&temp_home, &["--quiet", "--verbose"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn index_exit_code_tells_partial_from_total_failure() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let repo = create_repo(&workspace.path().join("repo"));
+    let repo_arg = repo.as_str();
+    let missing_path = workspace.path().join("missing");
+    let missing_arg = missing_path.to_str().unwrap();
+
+    gkg_index(&temp_home, &[repo_arg]).assert().code(0);
+    gkg_index(&temp_home, &[repo_arg, missing_arg])
+        .assert()
+        .code(2);
+    gkg_index(&temp_home, &[missing_arg]).assert().code(3);
+}
+
+#[test]
+fn index_fail_fast_stops_at_the_first_failure() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let first = create_repo(&workspace.path().join("first"));
+    let second = create_repo(&workspace.path().join("second"));
+    let missing = workspace.path().join("missing");
+    let stats_path = workspace.path().join("stats.json");
+    let stats_arg = format!("--stats={}", stats_path.display());
+
+    gkg_index(
+        &temp_home,
+        &[
+            &first,
+            missing.to_str().unwrap(),
+            &second,
+            "--fail-fast",
+            &stats_arg,
+        ],
+    )
+    .assert()
+    .code(2);
+    let indexed = stats_workspace_paths(&stats_path);
+    assert_eq!(indexed.len(), 1);
+    assert!(indexed[0].ends_with("first"));
+}
+
+#[test]
+fn index_skips_workspace_folders_given_twice() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let repo = create_repo(&workspace.path().join("repo"));
+    let stats_path = workspace.path().join("stats.json");
+    let stats_arg = format!("--stats={}", stats_path.display());
+
+    gkg_index(&temp_home, &[&repo, &format!("{repo}/"), &stats_arg])
+        .assert()
+        .code(0);
+    assert_eq!(stats_workspace_paths(&stats_path).len(), 1);
+}
+
+#[test]
+fn index_prints_a_combined_summary_of_several_workspace_folders() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let first = create_repo(&workspace.path().join("first"));
+    let second = create_repo(&workspace.path().join("second"));
+    let missing = workspace.path().join("missing");
+
+    let output = gkg_index(&temp_home, &[&first, &second, missing.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Indexed 2 of 3 workspace folders"));
+    assert!(stdout.contains("Total Projects: 2"));
+}
+
+#[test]
+fn index_quiet_json_logs_only_errors_to_stderr() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let repo = create_repo(&workspace.path().join("repo"));
+    let missing = workspace.path().join("missing");
+
+    let output = gkg_index(
+        &temp_home,
+        &[
+            &repo,
+            missing.to_str().unwrap(),
+            "--quiet",
+            "--log-format=json",
+        ],
+    )
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("a JSON log line"))
+        .collect();
+    assert!(!events.is_empty());
+    assert!(events.iter().all(|event| event["level"] != "INFO"));
+}
+
+#[test]
+fn index_fail_fast_stops_at_a_folder_with_failed_projects() {
+    let temp_home = TempDir::new().expect("temp home");
+    let workspace = TempDir::new().expect("temp workspace");
+    let first = create_repo(&workspace.path().join("first"));
+    let second = create_repo(&workspace.path().join("second"));
+    let stats_path = workspace.path().join("stats.json");
+    let stats_arg = format!("--stats={}", stats_path.display());
+
+    // Every project times out, so the first folder is indexed with a failed project
+    gkg_index(
+        &temp_home,
+        &[
+            &first,
+            &second,
+            "--project-timeout=0",
+            "--fail-fast",
+            &stats_arg,
+        ],
+    )
+    .assert()
+    .code(3);
+    let indexed = stats_workspace_paths(&stats_path);
+    assert_eq!(indexed.len(), 1);
+    assert!(indexed[0].ends_with("first"));
+}
+
+#[test]
+fn index_help_exits_successfully() {
+    let temp_home = TempDir::new().expect("temp home");
+
+    gkg_index(&temp_home, &["--help"]).assert().code(0);
+}
@@ -0,0 +1,74 @@
+use assert_cmd::prelude::*;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+fn gkg_mcp(home: &TempDir, args: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("gkg").expect("cargo bin gkg");
+    command
+        .arg("mcp")
+        .args(args)
+        .env("HOME", home.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    command
+}
+
+#[test]
+fn mcp_register_and_unregister_without_server() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mcp_config_path = temp_home.path().join("duo").join("mcp.json");
+    let path_arg = mcp_config_path.to_str().unwrap();
+
+    gkg_mcp(
+        &temp_home,
+        &["register", "--path", path_arg, "--port", "27495"],
+    )
+    .assert()
+    .success();
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&mcp_config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["mcpServers"]["knowledge-graph"]["url"],
+        "http://localhost:27495/mcp/sse"
+    );
+
+    gkg_mcp(&temp_home, &["unregister", "--path", path_arg])
+        .assert()
+        .success();
+
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&mcp_config_path).unwrap()).unwrap();
+    assert!(config["mcpServers"]["knowledge-graph"].is_null());
+}
+
+#[test]
+fn mcp_register_without_server_or_port_fails() {
+    let temp_home = TempDir::new().expect("temp home");
+
+    gkg_mcp(&temp_home, &["register"]).assert().failure();
+    assert!(!temp_home.path().join(".gitlab").exists());
+}
+
+#[test]
+fn mcp_doctor_reports_failed_checks() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mcp_config_path = temp_home.path().join("duo").join("mcp.json");
+    let path_arg = mcp_config_path.to_str().unwrap();
+
+    gkg_mcp(
+        &temp_home,
+        &["register", "--path", path_arg, "--port", "27495"],
+    )
+    .assert()
+    .success();
+
+    let assert = gkg_mcp(&temp_home, &["doctor", "--path", path_arg])
+        .assert()
+        .failure();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    assert!(stdout.contains("[ok]   Configuration file is valid JSON"));
+    assert!(stdout.contains("[fail] gkg server is running"));
+    assert!(stdout.contains("gkg server start"));
+}
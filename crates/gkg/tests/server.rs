@@ -259,3 +259,64 @@ fn server_start_detached_and_stop() {
     );
     assert!(!lock_path.exists());
 }
+
+#[test]
+#[serial]
+fn server_removes_lockfile_on_sigterm() {
+    let temp_home = TempDir::new().expect("temp home");
+    let home_path = temp_home.path().to_path_buf();
+
+    let mut cmd = Command::cargo_bin("gkg").expect("cargo bin gkg");
+    cmd.arg("server")
+        .arg("start")
+        .env("HOME", &home_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    let mut child = cmd.spawn().expect("spawn gkg server start");
+    let child_stdout = child.stdout.take().expect("capture stdout");
+    let info = read_server_info_line(child_stdout);
+
+    assert!(
+        wait_for_port(info.port, Duration::from_secs(3)),
+        "server did not start listening in time"
+    );
+
+    // Lock file records the server's pid
+    let lock_path = lock_file_path(&home_path);
+    let contents = std::fs::read_to_string(&lock_path).expect("read lock file");
+    let obj: serde_json::Value = serde_json::from_str(contents.trim()).expect("json lock");
+    assert_eq!(
+        obj.get("pid").and_then(|v| v.as_u64()),
+        Some(child.id() as u64)
+    );
+
+    nix::sys::signal::kill(
+        nix::unistd::Pid::from_raw(child.id() as i32),
+        nix::sys::signal::Signal::SIGTERM,
+    )
+    .expect("send SIGTERM");
+    let _ = child.wait();
+
+    assert!(!lock_path.exists());
+}
+
+#[test]
+#[serial]
+fn server_status_without_server() {
+    let temp_home = TempDir::new().expect("temp home");
+
+    let assert = Command::cargo_bin("gkg")
+        .expect("cargo bin gkg")
+        .arg("server")
+        .arg("status")
+        .env("HOME", temp_home.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .assert()
+        .success();
+
+    let output = assert.get_output();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No gkg server is running"));
+}
@@ -19,6 +19,8 @@ pub(crate) struct Claims {
     pub(crate) iss: String,
     pub(crate) iat: i64,
     pub(crate) exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sub: Option<String>,
 }
 
 impl Claims {
@@ -28,6 +30,7 @@ impl Claims {
             iss: issuer,
             iat: now.timestamp(),
             exp: (now + ttl).timestamp(),
+            sub: None,
         }
     }
 }
@@ -83,8 +86,8 @@ impl Auth {
 }
 
 #[derive(Debug, Serialize)]
-struct ErrorResponse {
-    error: String,
+pub(crate) struct ErrorResponse {
+    pub(crate) error: String,
 }
 
 async fn jwt_auth_middleware(
@@ -4,6 +4,16 @@ pub mod metrics;
 pub mod webserver;
 
 use axum::Router;
+use database::querying::QueryingService;
+use std::sync::Arc;
+use workspace_manager::WorkspaceManager;
+
+/// Shared state for routes that read already-indexed data.
+#[derive(Clone)]
+pub struct AppState {
+    pub workspace_manager: Arc<WorkspaceManager>,
+    pub querying_service: Arc<dyn QueryingService>,
+}
 
 /// List of endpoints that are explicitly allowed without authentication.
 /// All other endpoints require JWT authentication by default (secure by default).
@@ -14,7 +24,9 @@ pub fn is_public_endpoint(path: &str) -> bool {
     PUBLIC_ENDPOINTS.contains(&path)
 }
 
-pub fn get_routes(mode: String) -> Router {
+/// Routes are split by role: `webserver` serves read-only graph queries and `indexer` serves
+/// indexing/write endpoints. Routes of the other role are never registered, so they 404.
+pub fn get_routes(mode: String, state: AppState) -> Router {
     // routes from all endpoints should be merged here
     let router = Router::new()
         // Public endpoints available in all modes
@@ -22,7 +34,7 @@ pub fn get_routes(mode: String) -> Router {
         .merge(metrics::get_routes())
         .merge(match mode.as_str() {
             "indexer" => indexer::get_routes(),
-            "webserver" => webserver::get_routes(),
+            "webserver" => webserver::get_routes(state),
             _ => {
                 println!("unknown mode {mode}");
                 Router::new()
@@ -35,16 +47,51 @@ pub fn get_routes(mode: String) -> Router {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::build_app_state;
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use tempfile::TempDir;
 
     #[test]
     fn routes_are_not_empty() {
-        let app = get_routes("indexer".to_string());
+        let temp_dir = TempDir::new().unwrap();
+
+        let app = get_routes("indexer".to_string(), build_app_state(temp_dir.path()));
         assert!(app.has_routes(), "no routes are defined");
 
-        let app = get_routes("webserver".to_string());
+        let app = get_routes("webserver".to_string(), build_app_state(temp_dir.path()));
         assert!(app.has_routes(), "no routes are defined");
     }
 
+    #[tokio::test]
+    async fn test_routes_of_other_mode_return_404() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let indexer = TestServer::new(get_routes(
+            "indexer".to_string(),
+            build_app_state(temp_dir.path()),
+        ))
+        .unwrap();
+        indexer
+            .get("/webserver/v1/search")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        indexer
+            .post("/webserver/v1/tool")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let webserver = TestServer::new(get_routes(
+            "webserver".to_string(),
+            build_app_state(temp_dir.path()),
+        ))
+        .unwrap();
+        webserver
+            .post("/indexer/v1/index")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
     #[test]
     fn test_public_endpoint_detection() {
         assert!(is_public_endpoint("/health"));
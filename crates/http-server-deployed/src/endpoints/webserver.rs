@@ -1,7 +1,62 @@
-use axum::{http::StatusCode, routing::post, Router};
+use crate::authentication::ErrorResponse;
+use crate::endpoints::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use database::graph::RelationshipType;
+use database::querying::{Query as LibraryQuery, QueryLibrary};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::error;
 
-pub fn get_routes() -> Router {
-    let routes = Router::new().route("/tool", post(handle_tool));
+const DEFAULT_LIMIT: i64 = 100;
+
+#[derive(Deserialize, Debug)]
+struct SearchQuery {
+    workspace_folder_path: String,
+    project_path: String,
+    search_term: String,
+    limit: Option<i64>,
+    /// Only matches definitions with a relationship of this type, e.g. `INSTANTIATES`
+    relationship_type: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NeighborsQuery {
+    workspace_folder_path: String,
+    project_path: String,
+    node_id: String,
+    node_type: String,
+    limit: Option<i64>,
+    /// Only follows the relationships of this type, e.g. `INSTANTIATES`
+    relationship_type: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DefinitionsQuery {
+    workspace_folder_path: String,
+    project_path: String,
+    file_path: String,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct QueryResponse {
+    results: Value,
+}
+
+/// Read-only query routes over already-indexed projects.
+pub fn get_routes(state: AppState) -> Router {
+    let routes = Router::new()
+        .route("/tool", post(handle_tool))
+        .route("/search", get(handle_search))
+        .route("/neighbors", get(handle_neighbors))
+        .route("/definitions", get(handle_definitions))
+        .with_state(state);
 
     // Nest under /webserver for plug-and-play experience with the helm chart https://gitlab.com/gitlab-org/cloud-native/charts/gitlab-zoekt
     Router::new().nest("/webserver/v1", routes)
@@ -11,18 +66,274 @@ async fn handle_tool() -> (StatusCode, String) {
     (StatusCode::NOT_IMPLEMENTED, "Not implemented".to_string())
 }
 
+async fn handle_search(
+    State(state): State<AppState>,
+    Query(params): Query<SearchQuery>,
+) -> Response {
+    let search_term = params.search_term.trim();
+    if search_term.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Search term must not be empty");
+    }
+
+    let relationship_type = match params
+        .relationship_type
+        .as_deref()
+        .map(parse_relationship_type)
+    {
+        Some(Err(response)) => return response,
+        Some(Ok(relationship_type)) => Some(relationship_type),
+        None => None,
+    };
+
+    let mut query_params = Map::new();
+    query_params.insert(
+        "search_term".to_string(),
+        Value::String(search_term.to_string()),
+    );
+    query_params.insert(
+        "limit".to_string(),
+        Value::Number(params.limit.unwrap_or(DEFAULT_LIMIT).into()),
+    );
+    let query = match relationship_type {
+        Some(relationship_type) => {
+            query_params.insert(
+                "relationship_type".to_string(),
+                Value::String(relationship_type.as_string()),
+            );
+            QueryLibrary::get_search_nodes_by_relationship_type_query(false, false)
+        }
+        None => QueryLibrary::get_search_nodes_query(),
+    };
+
+    run_query(
+        &state,
+        &params.workspace_folder_path,
+        &params.project_path,
+        query,
+        query_params,
+    )
+}
+
+async fn handle_neighbors(
+    State(state): State<AppState>,
+    Query(params): Query<NeighborsQuery>,
+) -> Response {
+    let relationship_type = match params
+        .relationship_type
+        .as_deref()
+        .map(parse_relationship_type)
+    {
+        Some(Err(response)) => return response,
+        Some(Ok(relationship_type)) => Some(relationship_type),
+        None => None,
+    };
+
+    let query = if relationship_type.is_some() {
+        QueryLibrary::get_node_neighbors_by_relationship_type_query(&params.node_type)
+    } else {
+        QueryLibrary::get_node_neighbors_query(&params.node_type)
+    };
+    let Some(query) = query else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Unsupported node type: {}", params.node_type),
+        );
+    };
+
+    let mut query_params = Map::new();
+    query_params.insert("node_id".to_string(), Value::String(params.node_id));
+    query_params.insert(
+        "limit".to_string(),
+        Value::Number(params.limit.unwrap_or(DEFAULT_LIMIT).into()),
+    );
+    if let Some(relationship_type) = relationship_type {
+        query_params.insert(
+            "relationship_type".to_string(),
+            Value::String(relationship_type.as_string()),
+        );
+    }
+
+    run_query(
+        &state,
+        &params.workspace_folder_path,
+        &params.project_path,
+        query,
+        query_params,
+    )
+}
+
+async fn handle_definitions(
+    State(state): State<AppState>,
+    Query(params): Query<DefinitionsQuery>,
+) -> Response {
+    if params.name.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Name must not be empty");
+    }
+
+    let mut query_params = Map::new();
+    query_params.insert("file_path".to_string(), Value::String(params.file_path));
+    query_params.insert(
+        "name_or_fqn".to_string(),
+        Value::String(params.name.trim().to_string()),
+    );
+
+    run_query(
+        &state,
+        &params.workspace_folder_path,
+        &params.project_path,
+        QueryLibrary::get_definitions_by_fqn_or_name_query(),
+        query_params,
+    )
+}
+
+fn run_query(
+    state: &AppState,
+    workspace_folder_path: &str,
+    project_path: &str,
+    query: LibraryQuery,
+    query_params: Map<String, Value>,
+) -> Response {
+    let Some(project_info) = state
+        .workspace_manager
+        .get_project_info(workspace_folder_path, project_path)
+    else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "Project is not registered in the workspace folder",
+        );
+    };
+
+    let mut query_result = match state.querying_service.execute_query(
+        project_info.database_path,
+        query.query,
+        query_params,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to execute query: {}", e);
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed to execute query: {e}"),
+            );
+        }
+    };
+
+    match query_result.to_json(&query.result) {
+        Ok(results) => (StatusCode::OK, Json(QueryResponse { results })).into_response(),
+        Err(e) => {
+            error!("Failed to process query results: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed to process query results: {e}"),
+            )
+        }
+    }
+}
+
+fn parse_relationship_type(name: &str) -> Result<RelationshipType, Response> {
+    RelationshipType::parse(name).ok_or_else(|| {
+        error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("Unsupported relationship type: {name}"),
+        )
+    })
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.to_string(),
+        }),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::build_app_state;
     use axum_test::TestServer;
+    use tempfile::TempDir;
+
+    fn create_test_server(temp_dir: &TempDir) -> TestServer {
+        TestServer::new(get_routes(build_app_state(temp_dir.path()))).unwrap()
+    }
 
     #[tokio::test]
     async fn tool_route_returns_200_ok() {
-        let app = get_routes();
-        let server = TestServer::new(app).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_server(&temp_dir);
 
         let response = server.post("/webserver/v1/tool").await;
 
         response.assert_status(StatusCode::NOT_IMPLEMENTED);
     }
+
+    #[tokio::test]
+    async fn search_returns_404_for_unknown_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_server(&temp_dir);
+
+        let response = server
+            .get("/webserver/v1/search")
+            .add_query_param("workspace_folder_path", "/missing")
+            .add_query_param("project_path", "/missing/project")
+            .add_query_param("search_term", "main")
+            .await;
+
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn search_rejects_empty_search_term() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_server(&temp_dir);
+
+        let response = server
+            .get("/webserver/v1/search")
+            .add_query_param("workspace_folder_path", "/workspace")
+            .add_query_param("project_path", "/workspace/project")
+            .add_query_param("search_term", "  ")
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn neighbors_rejects_unsupported_node_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_server(&temp_dir);
+
+        let response = server
+            .get("/webserver/v1/neighbors")
+            .add_query_param("workspace_folder_path", "/workspace")
+            .add_query_param("project_path", "/workspace/project")
+            .add_query_param("node_id", "1")
+            .add_query_param("node_type", "UnknownNode")
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = response.json();
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("Unsupported node type"));
+    }
+
+    #[tokio::test]
+    async fn definitions_returns_404_for_unknown_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_server(&temp_dir);
+
+        let response = server
+            .get("/webserver/v1/definitions")
+            .add_query_param("workspace_folder_path", "/missing")
+            .add_query_param("project_path", "/missing/project")
+            .add_query_param("file_path", "src/main.rs")
+            .add_query_param("name", "main")
+            .await;
+
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
 }
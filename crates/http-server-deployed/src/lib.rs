@@ -1,6 +1,7 @@
 pub mod authentication;
 pub mod endpoints;
 pub mod metrics;
+pub mod rate_limit;
 
 #[cfg(any(test, feature = "test-helpers"))]
 pub mod test_helpers;
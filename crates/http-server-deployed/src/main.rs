@@ -1,14 +1,21 @@
+use http_server_deployed::endpoints::AppState;
+use http_server_deployed::rate_limit::{self, RateLimitConfig, RateLimiter};
 use http_server_deployed::{authentication, endpoints, metrics};
 
 use axum::{middleware, Router};
+use axum_server::tls_rustls::RustlsConfig;
 use clap::Parser;
-use logging::{init, LogMode};
+use database::kuzu::database::KuzuDatabase;
+use database::querying::DatabaseQueryingService;
+use logging::{init, LogFormat, LogMode, Verbosity};
 use std::error::Error;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::net::{TcpListener, UnixListener};
 use tokio::signal;
 use tracing::{error, info};
-use workspace_manager::DataDirectory;
+use workspace_manager::{DataDirectory, WorkspaceManager};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -28,11 +35,28 @@ struct Args {
     // Data directory for persistent storage (required)
     #[arg(long)]
     data_dir: PathBuf,
+    // Sustained requests per second allowed for each client (JWT subject or IP)
+    #[arg(long, default_value_t = 10)]
+    rate_limit_rps: u32,
+    // Number of requests a client may burst above the sustained rate
+    #[arg(long, default_value_t = 20)]
+    rate_limit_burst: u32,
+    // PEM certificate chain to serve HTTPS on the bind address (requires --tls-key)
+    #[arg(long, requires = "tls_key", conflicts_with = "socket")]
+    tls_cert: Option<PathBuf>,
+    // PEM private key matching --tls-cert (requires --tls-cert)
+    #[arg(long, requires = "tls_cert", conflicts_with = "socket")]
+    tls_key: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    init(LogMode::ServerDeployed, false)?;
+    init(
+        LogMode::ServerDeployed,
+        Verbosity::Normal,
+        LogFormat::Text,
+        None,
+    )?;
 
     let args = Args::parse();
 
@@ -50,6 +74,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
         data_directory.root_path.display()
     );
 
+    let workspace_manager = match WorkspaceManager::new_with_directory(data_directory.root_path) {
+        Ok(workspace_manager) => Arc::new(workspace_manager),
+        Err(e) => {
+            error!("Failed to initialize workspace manager: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    let state = AppState {
+        workspace_manager,
+        querying_service: Arc::new(DatabaseQueryingService::new(Arc::new(KuzuDatabase::new()))),
+    };
+
     // Initialize JWT authentication
     let auth = match authentication::Auth::new(&args.secret_path) {
         Ok(auth) => auth,
@@ -59,10 +96,32 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
+    // Load TLS material up front so a bad cert/key fails before we start listening
+    let tls_config = match (&args.tls_cert, &args.tls_key) {
+        (Some(cert), Some(key)) => match load_tls_config(cert, key).await {
+            Ok(config) => Some(config),
+            Err(e) => {
+                error!("Failed to load TLS certificate and key: {}", e);
+                return Err(e);
+            }
+        },
+        _ => None,
+    };
+
+    let rate_limiter = RateLimiter::new(RateLimitConfig {
+        requests_per_second: args.rate_limit_rps,
+        burst: args.rate_limit_burst,
+    });
+
     // Create routes and apply middleware layers
-    let app = endpoints::get_routes(args.mode.clone())
+    let app = endpoints::get_routes(args.mode.clone(), state)
         // Apply metrics middleware first (before auth) to track all requests
         .layer(middleware::from_fn(metrics::request_metrics_middleware))
+        // Rate limit after auth so authenticated clients are keyed by their JWT subject
+        .layer(middleware::from_fn_with_state(
+            rate_limiter,
+            rate_limit::rate_limit_middleware,
+        ))
         // Then apply JWT authentication
         .layer(middleware::from_fn_with_state(
             auth,
@@ -71,6 +130,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     if let Some(socket) = args.socket {
         serve_unix_socket(socket, app).await;
+    } else if let Some(tls_config) = tls_config {
+        serve_tls_socket(args.bind, app, tls_config).await?;
     } else {
         serve_tcp_socket(args.bind, app).await;
     }
@@ -91,7 +152,41 @@ async fn serve_unix_socket(socket: String, app: Router) {
 async fn serve_tcp_socket(bind: String, app: Router) {
     let listener = TcpListener::bind(bind.clone()).await.unwrap();
     info!("HTTP server listening on {}", bind);
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+async fn load_tls_config(cert: &Path, key: &Path) -> Result<RustlsConfig, Box<dyn Error>> {
+    // Only the ring provider is compiled in; installing fails harmlessly if already set
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    RustlsConfig::from_pem_file(cert, key).await.map_err(|e| {
+        format!(
+            "Failed to read TLS files {} and {}: {e}",
+            cert.display(),
+            key.display()
+        )
+        .into()
+    })
+}
+
+async fn serve_tls_socket(
+    bind: String,
+    app: Router,
+    tls_config: RustlsConfig,
+) -> Result<(), Box<dyn Error>> {
+    let addr: SocketAddr = bind
+        .parse()
+        .map_err(|e| format!("Invalid bind address {bind} for TLS: {e}"))?;
+    info!("HTTPS server listening on {}", bind);
+    axum_server::bind_rustls(addr, tls_config)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await?;
+    Ok(())
 }
 
 async fn shutdown_signal(path: String) {
@@ -0,0 +1,217 @@
+use crate::authentication::{Claims, ErrorResponse};
+use crate::endpoints;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header::RETRY_AFTER, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Once this many clients are tracked, buckets that have refilled completely are dropped
+/// so the map doesn't grow without bound.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Key used when neither a JWT subject nor a peer address is available (e.g. unix sockets).
+const ANONYMOUS_CLIENT_KEY: &str = "anonymous";
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    pub requests_per_second: u32,
+    pub burst: u32,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-client token bucket rate limiter. Each client starts with `burst` tokens which are
+/// refilled at `requests_per_second`; a request consumes one token.
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<Mutex<HashMap<String, TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Takes a token for `client_key`, or returns how long the client has to wait for one.
+    pub fn check(&self, client_key: &str) -> Result<(), Duration> {
+        self.check_at(client_key, Instant::now())
+    }
+
+    fn check_at(&self, client_key: &str, now: Instant) -> Result<(), Duration> {
+        let rate = f64::from(self.config.requests_per_second);
+        let capacity = f64::from(self.config.burst.max(1));
+
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * rate
+                    < capacity
+            });
+        }
+
+        let bucket = buckets
+            .entry(client_key.to_string())
+            .or_insert_with(|| TokenBucket {
+                tokens: capacity,
+                last_refill: now,
+            });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if rate > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// Identifies the caller by JWT subject when authenticated, falling back to the peer IP.
+fn client_key(request: &Request) -> String {
+    if let Some(sub) = request
+        .extensions()
+        .get::<Claims>()
+        .and_then(|claims| claims.sub.as_ref())
+    {
+        return format!("sub:{sub}");
+    }
+
+    if let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        return format!("ip:{}", addr.ip());
+    }
+
+    ANONYMOUS_CLIENT_KEY.to_string()
+}
+
+pub async fn rate_limit_middleware(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Health checks and metrics scraping must never be throttled
+    if endpoints::is_public_endpoint(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let key = client_key(&request);
+    match limiter.check(&key) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            warn!("Rate limit exceeded for client {}", key);
+            // Retry-After is expressed in whole seconds, so round up
+            let retry_after_secs = wait.as_secs_f64().ceil().min(u32::MAX as f64).max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, retry_after_secs.to_string())],
+                Json(ErrorResponse {
+                    error: "Rate limit exceeded".to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::build_app_state;
+    use axum::{body::Body, middleware};
+    use axum_test::TestServer;
+
+    fn limiter(requests_per_second: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_refills() {
+        let limiter = limiter(2, 3);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at("client", start).is_ok());
+        }
+
+        let wait = limiter.check_at("client", start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        assert!(limiter
+            .check_at("client", start + Duration::from_millis(500))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_buckets_are_per_client() {
+        let limiter = limiter(1, 1);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("a", now).is_ok());
+        assert!(limiter.check_at("a", now).is_err());
+        assert!(limiter.check_at("b", now).is_ok());
+    }
+
+    #[test]
+    fn test_client_key_prefers_jwt_subject() {
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+
+        let mut request = Request::new(Body::empty());
+        assert_eq!(client_key(&request), ANONYMOUS_CLIENT_KEY);
+
+        request.extensions_mut().insert(ConnectInfo(addr));
+        assert_eq!(client_key(&request), "ip:10.0.0.1");
+
+        let mut claims = Claims::new("gitlab".to_string(), chrono::Duration::hours(1));
+        claims.sub = Some("user-1".to_string());
+        request.extensions_mut().insert(claims);
+        assert_eq!(client_key(&request), "sub:user-1");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_returns_429_and_exempts_public_endpoints() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let app =
+            endpoints::get_routes("webserver".to_string(), build_app_state(temp_dir.path())).layer(
+                middleware::from_fn_with_state(limiter(1, 1), rate_limit_middleware),
+            );
+        let server = TestServer::new(app).unwrap();
+
+        server
+            .post("/webserver/v1/tool")
+            .await
+            .assert_status(StatusCode::NOT_IMPLEMENTED);
+
+        let response = server.post("/webserver/v1/tool").await;
+        response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        for _ in 0..3 {
+            server.get("/health").await.assert_status_ok();
+            server.get("/metrics").await.assert_status_ok();
+        }
+    }
+}
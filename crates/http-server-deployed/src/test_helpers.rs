@@ -1,6 +1,11 @@
 use crate::authentication::{Auth, Claims};
+use crate::endpoints::AppState;
 use chrono::Duration;
+use database::testing::MockQueryingService;
 use jsonwebtoken::{encode, EncodingKey, Header};
+use std::path::Path;
+use std::sync::Arc;
+use workspace_manager::WorkspaceManager;
 
 impl Auth {
     pub fn generate_jwt(&self, ttl: Duration) -> Result<String, jsonwebtoken::errors::Error> {
@@ -11,3 +16,20 @@ impl Auth {
         encode(&header, &claims, &encoding_key)
     }
 }
+
+/// Builds an `AppState` backed by an empty workspace in `data_dir` and a mock querying service.
+pub fn build_app_state(data_dir: &Path) -> AppState {
+    build_app_state_with_querying_service(data_dir, MockQueryingService::new())
+}
+
+pub fn build_app_state_with_querying_service(
+    data_dir: &Path,
+    querying_service: MockQueryingService,
+) -> AppState {
+    AppState {
+        workspace_manager: Arc::new(
+            WorkspaceManager::new_with_directory(data_dir.to_path_buf()).unwrap(),
+        ),
+        querying_service: Arc::new(querying_service),
+    }
+}
@@ -3,11 +3,10 @@ use chrono::Duration;
 use http_server_deployed::authentication::Auth;
 use http_server_deployed::endpoints;
 use std::io::Write;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 
-// Import test_helpers to make generate_jwt method available on Auth
-#[allow(unused_imports)]
-use http_server_deployed::test_helpers;
+// test_helpers also makes the generate_jwt method available on Auth
+use http_server_deployed::test_helpers::build_app_state;
 
 fn create_secret_file() -> NamedTempFile {
     let mut temp_file = NamedTempFile::new().expect("create temp secret file");
@@ -21,9 +20,10 @@ fn create_secret_file() -> NamedTempFile {
 async fn test_public_endpoints_accessible_without_auth() {
     let secret_file = create_secret_file();
     let auth = Auth::new(secret_file.path().to_str().unwrap()).unwrap();
+    let data_dir = TempDir::new().unwrap();
 
-    let app =
-        endpoints::get_routes("webserver".to_string()).layer(axum::middleware::from_fn_with_state(
+    let app = endpoints::get_routes("webserver".to_string(), build_app_state(data_dir.path()))
+        .layer(axum::middleware::from_fn_with_state(
             auth,
             http_server_deployed::authentication::jwt_middleware_for_all,
         ));
@@ -43,9 +43,10 @@ async fn test_public_endpoints_accessible_without_auth() {
 async fn test_protected_endpoints_require_auth() {
     let secret_file = create_secret_file();
     let auth = Auth::new(secret_file.path().to_str().unwrap()).unwrap();
+    let data_dir = TempDir::new().unwrap();
 
-    let app =
-        endpoints::get_routes("webserver".to_string()).layer(axum::middleware::from_fn_with_state(
+    let app = endpoints::get_routes("webserver".to_string(), build_app_state(data_dir.path()))
+        .layer(axum::middleware::from_fn_with_state(
             auth,
             http_server_deployed::authentication::jwt_middleware_for_all,
         ));
@@ -61,12 +62,13 @@ async fn test_protected_endpoints_require_auth() {
 async fn test_protected_endpoints_work_with_valid_jwt() {
     let secret_file = create_secret_file();
     let auth = Auth::new(secret_file.path().to_str().unwrap()).unwrap();
+    let data_dir = TempDir::new().unwrap();
 
     // Generate a valid JWT
     let token = auth.generate_jwt(Duration::hours(1)).unwrap();
 
-    let app =
-        endpoints::get_routes("webserver".to_string()).layer(axum::middleware::from_fn_with_state(
+    let app = endpoints::get_routes("webserver".to_string(), build_app_state(data_dir.path()))
+        .layer(axum::middleware::from_fn_with_state(
             auth,
             http_server_deployed::authentication::jwt_middleware_for_all,
         ));
@@ -90,9 +92,10 @@ async fn test_protected_endpoints_work_with_valid_jwt() {
 async fn test_protected_endpoints_reject_invalid_jwt() {
     let secret_file = create_secret_file();
     let auth = Auth::new(secret_file.path().to_str().unwrap()).unwrap();
+    let data_dir = TempDir::new().unwrap();
 
-    let app =
-        endpoints::get_routes("webserver".to_string()).layer(axum::middleware::from_fn_with_state(
+    let app = endpoints::get_routes("webserver".to_string(), build_app_state(data_dir.path()))
+        .layer(axum::middleware::from_fn_with_state(
             auth,
             http_server_deployed::authentication::jwt_middleware_for_all,
         ));
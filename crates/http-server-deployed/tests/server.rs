@@ -200,3 +200,56 @@ fn server_rejects_file_as_data_dir() {
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("DataDirectoryCreationFailed"));
 }
+
+#[test]
+#[serial]
+fn server_requires_tls_key_with_tls_cert() {
+    let data_dir = TempDir::new().expect("temp data dir");
+    let secret_file = create_secret_file();
+
+    let mut cmd =
+        Command::cargo_bin("http-server-deployed").expect("cargo bin http-server-deployed");
+    cmd.arg("-b")
+        .arg("127.0.0.1:8837")
+        .arg("--secret-path")
+        .arg(secret_file.path())
+        .arg("--data-dir")
+        .arg(data_dir.path())
+        .arg("--tls-cert")
+        .arg("/tmp/cert.pem")
+        .stderr(Stdio::piped());
+
+    let output = cmd.output().expect("run command");
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--tls-key"));
+}
+
+#[test]
+#[serial]
+fn server_rejects_unreadable_tls_files() {
+    let temp_home = TempDir::new().expect("temp home");
+    let data_dir = TempDir::new().expect("temp data dir");
+    let secret_file = create_secret_file();
+
+    let mut cmd =
+        Command::cargo_bin("http-server-deployed").expect("cargo bin http-server-deployed");
+    cmd.arg("-b")
+        .arg("127.0.0.1:8838")
+        .arg("--secret-path")
+        .arg(secret_file.path())
+        .arg("--data-dir")
+        .arg(data_dir.path())
+        .arg("--tls-cert")
+        .arg(temp_home.path().join("missing-cert.pem"))
+        .arg("--tls-key")
+        .arg(temp_home.path().join("missing-key.pem"))
+        .stderr(Stdio::piped());
+
+    let output = cmd.output().expect("run command");
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read TLS files"));
+}
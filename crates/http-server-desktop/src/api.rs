@@ -1,10 +1,13 @@
 use crate::endpoints::{
     events::EventsEndpointDef,
     graph::{
+        graph_definitions::GraphDefinitionsEndpointDef,
+        graph_directory_counts::GraphDirectoryCountsEndpointDef,
         graph_initial::GraphInitialEndpointDef, graph_neighbors::GraphNeighborsEndpointDef,
         graph_search::GraphSearchEndpointDef, graph_stats::GraphStatsEndpointDef,
     },
     info::InfoEndpointDef,
+    job_list::JobListEndpointDef,
     workspace_delete::WorkspaceDeleteEndpointDef,
     workspace_index::WorkspaceIndexEndpointDef,
     workspace_list::WorkspaceListEndpointDef,
@@ -22,8 +25,11 @@ pub struct ApiContract {
     pub workspace_delete: WorkspaceDeleteEndpointDef,
     pub index: WorkspaceIndexEndpointDef,
     pub events: EventsEndpointDef,
+    pub job_list: JobListEndpointDef,
     pub graph_initial: GraphInitialEndpointDef,
     pub graph_neighbors: GraphNeighborsEndpointDef,
     pub graph_search: GraphSearchEndpointDef,
     pub graph_stats: GraphStatsEndpointDef,
+    pub graph_definitions: GraphDefinitionsEndpointDef,
+    pub graph_directory_counts: GraphDirectoryCountsEndpointDef,
 }
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use database::kuzu::database::KuzuDatabase;
 use event_bus::EventBus;
-use http_server_desktop::{find_unused_port, run};
-use logging::{LogMode, init};
+use http_server_desktop::{config::ServerConfig, find_unused_port, run};
+use logging::{LogFormat, LogMode, Verbosity, init};
 use std::env;
 use std::sync::Arc;
 use tracing::info;
@@ -10,7 +10,7 @@ use workspace_manager::WorkspaceManager;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    init(LogMode::Cli, true).unwrap();
+    init(LogMode::Cli, Verbosity::Verbose, LogFormat::Text, None).unwrap();
 
     let port = env::var("DEV_PORT")
         .ok()
@@ -48,6 +48,7 @@ async fn main() -> Result<()> {
         workspace_manager,
         event_bus,
         mcp_configuration,
+        ServerConfig::default(),
     )
     .await
 }
@@ -0,0 +1,67 @@
+use crate::queue::SchedulingPolicy;
+use database::querying::{ConnectionPoolConfig, QueryCacheConfig};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default interval between SSE keepalive comments on an idle `/api/events` stream.
+pub const DEFAULT_SSE_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Default maximum size of a request body accepted by the API routes (1 MiB).
+pub const DEFAULT_MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
+
+/// Tunable options for the desktop HTTP server.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// How long the events stream may stay silent before a `: keepalive` comment is sent,
+    /// so idle proxies don't drop the connection.
+    pub sse_keep_alive_interval: Duration,
+    /// Requests with a larger body are rejected with `413 Payload Too Large` before being
+    /// fully buffered.
+    pub max_request_body_bytes: usize,
+    /// Per-route body limits keyed by endpoint path (e.g. `WorkspaceIndexEndpoint::PATH`),
+    /// taking precedence over `max_request_body_bytes`.
+    pub body_limit_overrides: HashMap<String, usize>,
+    /// Upper bound on how many workspaces are indexed at the same time; further jobs wait
+    /// in their workspace queue. `None` means no limit.
+    pub max_concurrent_indexing_jobs: Option<usize>,
+    /// Order in which waiting workspaces get a free indexing slot. Only matters when
+    /// `max_concurrent_indexing_jobs` is set.
+    pub scheduling_policy: SchedulingPolicy,
+    /// Queue a full reindex, on startup, of the projects indexed with an older graph schema.
+    /// When false they are only flagged as outdated.
+    pub reindex_outdated_projects: bool,
+    /// Cache the results of the queries run by the MCP tools. `None` disables the cache, so
+    /// every query reads the latest index.
+    pub query_cache: Option<QueryCacheConfig>,
+    /// Reuse the connections to the databases across the queries of the MCP tools. `None`
+    /// opens a connection per query.
+    pub connection_pool: Option<ConnectionPoolConfig>,
+    /// Write every event to this file as JSON lines while the server runs
+    pub event_log: Option<PathBuf>,
+}
+
+impl ServerConfig {
+    pub fn body_limit_for(&self, path: &str) -> usize {
+        self.body_limit_overrides
+            .get(path)
+            .copied()
+            .unwrap_or(self.max_request_body_bytes)
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            sse_keep_alive_interval: DEFAULT_SSE_KEEP_ALIVE_INTERVAL,
+            max_request_body_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            body_limit_overrides: HashMap::new(),
+            max_concurrent_indexing_jobs: None,
+            scheduling_policy: SchedulingPolicy::default(),
+            reindex_outdated_projects: false,
+            query_cache: None,
+            connection_pool: None,
+            event_log: None,
+        }
+    }
+}
@@ -2,8 +2,10 @@ use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::define_endpoint;
 use axum::extract::State;
+use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use chrono::Utc;
+use event_bus::{EventBus, SequencedEvent};
 use futures_util::stream::Stream;
 use futures_util::{StreamExt, stream};
 use serde::Serialize;
@@ -11,6 +13,7 @@ use serde_json::json;
 use std::convert::Infallible;
 use std::time::Duration;
 use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use ts_rs::TS;
 
 #[derive(Serialize, TS, Default)]
@@ -39,12 +42,88 @@ define_endpoint! {
     export_to = "../../../packages/gkg/src/api.ts"
 }
 
+/// Header sent by reconnecting SSE clients with the id of the last event they received.
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+fn to_sse_event(sequenced: &SequencedEvent) -> Option<Event> {
+    match serde_json::to_string(&sequenced.event) {
+        Ok(json) => Some(
+            Event::default()
+                .event("gkg-event")
+                .id(sequenced.id.to_string())
+                .data(json),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to serialize event: {}", e);
+            None
+        }
+    }
+}
+
+/// Tells the client that it missed events, because it fell behind the event bus or reconnected
+/// after its last event left the replay buffer. The client should refetch its state instead
+/// of relying on the events it received.
+fn resync_event(missed: u64) -> Event {
+    let data = json!({
+        "type": "events-missed",
+        "timestamp": Utc::now().to_rfc3339(),
+        "missed": missed,
+    });
+    Event::default().event("gkg-resync").data(data.to_string())
+}
+
+/// Tells the client that its `Last-Event-ID` is ahead of the event bus, so it comes from before
+/// a server restart and the events it missed since can't be told apart. The client should
+/// refetch its state.
+fn reset_resync_event() -> Event {
+    let data = json!({
+        "type": "events-reset",
+        "timestamp": Utc::now().to_rfc3339(),
+    });
+    Event::default().event("gkg-resync").data(data.to_string())
+}
+
 /// Handler for the events endpoint
-/// Returns a Server-Sent Events (SSE) stream of all system events
+/// Returns a Server-Sent Events (SSE) stream of all system events.
+/// Every event carries its event bus id; a client reconnecting with `Last-Event-ID` first
+/// receives the buffered events it missed, then the live stream.
+/// A `gkg-resync` event is sent when the client missed events that can't be delivered anymore,
+/// or when its `Last-Event-ID` is from before a server restart.
+/// A `: keepalive` comment is sent whenever the stream has been idle for `keep_alive_interval`;
+/// the keepalive timer is dropped together with the stream once the client disconnects.
 pub async fn events_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    keep_alive_interval: Duration,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let receiver = state.event_bus.subscribe();
+    event_stream(&state.event_bus, &headers, keep_alive_interval)
+}
+
+/// The SSE stream of the events sent on `event_bus`, see [`events_handler`]
+pub fn event_stream(
+    event_bus: &EventBus,
+    headers: &HeaderMap,
+    keep_alive_interval: Duration,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>> + use<>> {
+    // Subscribe before reading the replay buffer so no event falls between the two.
+    let receiver = event_bus.subscribe_sequenced();
+
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    // Ids start again at 1 after a restart, an id past the last one sent is from before it
+    let stale_last_event_id = last_event_id.is_some_and(|id| id > event_bus.last_id());
+    let last_event_id = last_event_id.filter(|_| !stale_last_event_id);
+
+    let replayed = last_event_id
+        .map(|id| event_bus.replay_since(id))
+        .unwrap_or_default();
+    let replayed_up_to = replayed
+        .last()
+        .map(|sequenced| sequenced.id)
+        .or(last_event_id)
+        .unwrap_or(0);
 
     // Create initial connection event
     let connection_event = json!({
@@ -59,47 +138,69 @@ pub async fn events_handler(
             .data(connection_event.to_string()))
     });
 
-    let event_stream = BroadcastStream::new(receiver).filter_map(|result| async move {
+    // The events right after `Last-Event-ID` were already dropped from the replay buffer
+    let missed_on_reconnect = match (last_event_id, replayed.first()) {
+        (Some(last_event_id), Some(first)) => first.id - last_event_id - 1,
+        _ => 0,
+    };
+    let resync_on_reconnect = if stale_last_event_id {
+        Some(Ok(reset_resync_event()))
+    } else {
+        (missed_on_reconnect > 0).then(|| Ok(resync_event(missed_on_reconnect)))
+    };
+
+    let replay_stream = stream::iter(
+        resync_on_reconnect
+            .into_iter()
+            .chain(replayed.iter().filter_map(to_sse_event).map(Ok))
+            .collect::<Vec<_>>(),
+    );
+
+    let event_stream = BroadcastStream::new(receiver).filter_map(move |result| async move {
         match result {
-            Ok(event) => {
-                // Serialize the event to JSON
-                match serde_json::to_string(&event) {
-                    Ok(json) => Some(Ok(Event::default().event("gkg-event").data(json))),
-                    Err(e) => {
-                        tracing::error!("Failed to serialize event: {}", e);
-                        None
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Event stream error: {}", e);
-                None
+            // Events already delivered through the replay are skipped.
+            Ok(sequenced) if sequenced.id <= replayed_up_to => None,
+            Ok(sequenced) => to_sse_event(&sequenced).map(Ok),
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                tracing::warn!("Event stream fell behind, {missed} events were dropped");
+                Some(Ok(resync_event(missed)))
             }
         }
     });
 
-    let combined_stream = initial_event.chain(event_stream);
+    let combined_stream = initial_event.chain(replay_stream).chain(event_stream);
 
-    Sse::new(combined_stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(30)))
+    Sse::new(combined_stream).keep_alive(
+        KeepAlive::new()
+            .interval(keep_alive_interval)
+            .text("keepalive"),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::AppState;
+    use crate::config::DEFAULT_SSE_KEEP_ALIVE_INTERVAL;
+    use axum::body::Body;
+    use axum::http::Request;
     use axum::{Router, routing::get};
     use axum_test::TestServer;
     use chrono::Utc;
     use database::kuzu::database::KuzuDatabase;
     use event_bus::types::workspace_folder::to_ts_workspace_folder_info;
-    use event_bus::{EventBus, GkgEvent, WorkspaceIndexingEvent, WorkspaceIndexingStarted};
+    use event_bus::{
+        EventBus, GkgEvent, GraphEvent, GraphUpdated, WorkspaceIndexingEvent,
+        WorkspaceIndexingStarted,
+    };
     use std::sync::Arc;
     use std::time::Duration;
     use tempfile::TempDir;
+    use tower::ServiceExt;
     use workspace_manager::WorkspaceManager;
     use workspace_manager::{Status, WorkspaceFolderInfo};
 
-    async fn create_test_app() -> (TestServer, Arc<EventBus>, TempDir) {
+    fn create_test_router(keep_alive_interval: Duration) -> (Router, Arc<EventBus>, TempDir) {
         let temp_data_dir = TempDir::new().unwrap();
         let workspace_manager = Arc::new(
             WorkspaceManager::new_with_directory(temp_data_dir.path().to_path_buf()).unwrap(),
@@ -113,16 +214,33 @@ mod tests {
             Arc::clone(&database),
         ));
 
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
+
         let state = AppState {
             database: Arc::clone(&database),
             workspace_manager,
             event_bus: Arc::clone(&event_bus),
             job_dispatcher,
+            watcher,
         };
 
         let app = Router::new()
-            .route("/events", get(events_handler))
+            .route(
+                "/events",
+                get(move |state: State<AppState>, headers: HeaderMap| {
+                    events_handler(state, headers, keep_alive_interval)
+                }),
+            )
             .with_state(state);
+        (app, event_bus, temp_data_dir)
+    }
+
+    async fn create_test_app() -> (TestServer, Arc<EventBus>, TempDir) {
+        let (app, event_bus, temp_data_dir) = create_test_router(DEFAULT_SSE_KEEP_ALIVE_INTERVAL);
         (TestServer::new(app).unwrap(), event_bus, temp_data_dir)
     }
 
@@ -199,4 +317,146 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_events_endpoint_sends_keepalive_when_idle() {
+        let (app, _event_bus, _temp_dir) = create_test_router(Duration::from_millis(20));
+
+        let response = app
+            .oneshot(Request::get("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+
+        let connection_chunk = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&connection_chunk).contains("gkg-connection"));
+
+        let keepalive_chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("keepalive should be sent while the stream is idle")
+            .unwrap()
+            .unwrap();
+        assert_eq!(&keepalive_chunk[..], b": keepalive\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_events_endpoint_replays_after_last_event_id() {
+        let (app, event_bus, _temp_dir) = create_test_router(DEFAULT_SSE_KEEP_ALIVE_INTERVAL);
+
+        let test_event = GkgEvent::WorkspaceIndexing(WorkspaceIndexingEvent::Started(
+            WorkspaceIndexingStarted {
+                workspace_folder_info: to_ts_workspace_folder_info(&WorkspaceFolderInfo {
+                    workspace_folder_path: "/test/workspace".to_string(),
+                    data_directory_name: "test".to_string(),
+                    status: Status::Indexing,
+                    last_indexed_at: Some(Utc::now()),
+                    project_count: 2,
+                    gitalisk_workspace: None,
+                }),
+                projects_to_process: vec![],
+                started_at: Utc::now(),
+            },
+        ));
+        event_bus.send(&test_event);
+        event_bus.send(&test_event);
+
+        let response = app
+            .oneshot(
+                Request::get("/events")
+                    .header("Last-Event-ID", "1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+
+        let connection_chunk = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&connection_chunk).contains("gkg-connection"));
+
+        let replayed_chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("missed event should be replayed")
+            .unwrap()
+            .unwrap();
+        let replayed = String::from_utf8_lossy(&replayed_chunk);
+        assert!(replayed.contains("event: gkg-event"));
+        assert!(replayed.contains("id: 2"));
+    }
+
+    #[tokio::test]
+    async fn test_events_endpoint_resyncs_a_last_event_id_from_before_a_restart() {
+        let (app, event_bus, _temp_dir) = create_test_router(DEFAULT_SSE_KEEP_ALIVE_INTERVAL);
+
+        let response = app
+            .oneshot(
+                Request::get("/events")
+                    .header("Last-Event-ID", "500")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let connection_chunk = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&connection_chunk).contains("gkg-connection"));
+
+        let resync_chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("resync event should be sent")
+            .unwrap()
+            .unwrap();
+        let resync = String::from_utf8_lossy(&resync_chunk);
+        assert!(resync.contains("event: gkg-resync"));
+        assert!(resync.contains("events-reset"));
+
+        // Live events are delivered though their ids are below the client's
+        event_bus.send(&GkgEvent::Graph(GraphEvent::Updated(GraphUpdated {
+            workspace_folder_path: "/test/workspace".to_string(),
+            project_path: "/test/workspace/project".to_string(),
+            database_path: "/data/database.kz".to_string(),
+            updated_at: Utc::now(),
+        })));
+        let live_chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("live event should be sent")
+            .unwrap()
+            .unwrap();
+        let live = String::from_utf8_lossy(&live_chunk);
+        assert!(live.contains("event: gkg-event"));
+        assert!(live.contains("id: 1"));
+    }
+
+    #[tokio::test]
+    async fn test_events_endpoint_sends_resync_when_lagging() {
+        let (app, event_bus, _temp_dir) = create_test_router(DEFAULT_SSE_KEEP_ALIVE_INTERVAL);
+
+        let response = app
+            .oneshot(Request::get("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let connection_chunk = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&connection_chunk).contains("gkg-connection"));
+
+        // The stream isn't read while the events are sent, so its receiver overflows
+        let test_event = GkgEvent::Graph(GraphEvent::Updated(GraphUpdated {
+            workspace_folder_path: "/test/workspace".to_string(),
+            project_path: "/test/workspace/project".to_string(),
+            database_path: "/data/database.kz".to_string(),
+            updated_at: Utc::now(),
+        }));
+        for _ in 0..2000 {
+            event_bus.send(&test_event);
+        }
+
+        let resync_chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("resync event should be sent")
+            .unwrap()
+            .unwrap();
+        let resync = String::from_utf8_lossy(&resync_chunk);
+        assert!(resync.contains("event: gkg-resync"));
+        assert!(resync.contains("events-missed"));
+    }
 }
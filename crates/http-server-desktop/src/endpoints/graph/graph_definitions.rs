@@ -0,0 +1,446 @@
+use super::shared::{create_error_response, query_error_status};
+use crate::AppState;
+use crate::contract::{EmptyRequest, EndpointConfigTypes};
+use crate::decode_url_param;
+use crate::define_endpoint;
+use crate::endpoints::shared::ApiError;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use database::graph::{DefinitionCategory, split_fqn};
+use database::querying::{
+    QueryLibrary, QueryResult, QueryingService, service::DatabaseQueryingService,
+};
+use event_bus::types::project_info::{TSProjectInfo, to_ts_project_info};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+use ts_rs::TS;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Deserialize, Serialize, TS, Default, Clone, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDefinitionsPathRequest {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+}
+
+#[derive(Deserialize, Serialize, TS, Default, Clone, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDefinitionsQueryRequest {
+    /// Directory relative to the project root, e.g. `src/auth`. Empty for the whole project.
+    pub path_prefix: Option<String>,
+    /// Page number, starting from 1
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug, Clone)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDefinitionSummary {
+    pub id: String,
+    pub name: String,
+    pub fqn: String,
+    /// Parts of `fqn` split by the separators of the definition's language
+    pub fqn_parts: Vec<String>,
+    pub definition_type: String,
+    pub definition_category: String,
+    pub start_line: i32,
+    pub end_line: i32,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug, Clone)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphFileDefinitions {
+    pub path: String,
+    pub definitions: Vec<GraphDefinitionSummary>,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDefinitionsSuccessResponse {
+    pub files: Vec<GraphFileDefinitions>,
+    pub page: u32,
+    pub page_size: u32,
+    /// Whether a next page has more definitions under the prefix
+    pub has_more: bool,
+    pub project_info: TSProjectInfo,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDefinitionsResponses {
+    #[serde(rename = "200")]
+    pub ok: Option<GraphDefinitionsSuccessResponse>,
+    #[serde(rename = "404")]
+    pub not_found: Option<ApiError>,
+    #[serde(rename = "400")]
+    pub bad_request: Option<ApiError>,
+    #[serde(rename = "500")]
+    pub internal_server_error: Option<ApiError>,
+}
+
+pub struct GraphDefinitionsEndpointConfig;
+
+impl EndpointConfigTypes for GraphDefinitionsEndpointConfig {
+    type PathRequest = GraphDefinitionsPathRequest;
+    type BodyRequest = EmptyRequest;
+    type QueryRequest = GraphDefinitionsQueryRequest;
+    type Response = GraphDefinitionsSuccessResponse;
+}
+
+define_endpoint! {
+    GraphDefinitionsEndpoint,
+    GraphDefinitionsEndpointDef,
+    Get,
+    "/graph/definitions/{workspace_folder_path}/{project_path}",
+    ts_path_type = "\"/api/graph/definitions/{workspace_folder_path}/{project_path}\"",
+    config = GraphDefinitionsEndpointConfig,
+    export_to = "../../../packages/gkg/src/api.ts"
+}
+
+impl GraphDefinitionsEndpoint {
+    pub fn create_success_response(
+        files: Vec<GraphFileDefinitions>,
+        page: u32,
+        page_size: u32,
+        has_more: bool,
+        project_info: TSProjectInfo,
+    ) -> GraphDefinitionsSuccessResponse {
+        GraphDefinitionsSuccessResponse {
+            files,
+            page,
+            page_size,
+            has_more,
+            project_info,
+        }
+    }
+
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        create_error_response(code, message)
+    }
+}
+
+/// Turns a directory into the prefix of the file paths below it, so `src/auth` doesn't
+/// also match `src/authentication/`.
+fn normalize_path_prefix(path_prefix: &str) -> String {
+    let trimmed = path_prefix
+        .trim()
+        .trim_start_matches("./")
+        .trim_start_matches('/');
+
+    if trimmed.is_empty() || trimmed.ends_with('/') {
+        trimmed.to_string()
+    } else {
+        format!("{trimmed}/")
+    }
+}
+
+pub async fn graph_definitions_handler(
+    State(state): State<AppState>,
+    Path(path_params): Path<GraphDefinitionsPathRequest>,
+    Query(query_params): Query<GraphDefinitionsQueryRequest>,
+) -> impl IntoResponse {
+    let input_project_path = decode_url_param!(
+        &path_params.project_path,
+        "project_path",
+        GraphDefinitionsEndpoint::create_error_response
+    );
+    let input_workspace_folder_path = decode_url_param!(
+        &path_params.workspace_folder_path,
+        "workspace_folder_path",
+        GraphDefinitionsEndpoint::create_error_response
+    );
+
+    if input_project_path.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphDefinitionsEndpoint::create_error_response(
+                "empty_project_path",
+                "Project path must not be empty",
+            )),
+        )
+            .into_response();
+    }
+
+    let page = query_params.page.unwrap_or(1);
+    if page == 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphDefinitionsEndpoint::create_error_response(
+                "invalid_page",
+                "Page numbers start from 1",
+            )),
+        )
+            .into_response();
+    }
+    let page_size = query_params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let path_prefix = normalize_path_prefix(query_params.path_prefix.as_deref().unwrap_or(""));
+
+    info!(
+        "Received definitions request {workspace_folder_path} {project_path} path_prefix=\"{path_prefix}\" page={page} page_size={page_size}",
+        workspace_folder_path = input_workspace_folder_path,
+        project_path = input_project_path,
+    );
+
+    let project_info = match state
+        .workspace_manager
+        .get_project_info(&input_workspace_folder_path, &input_project_path)
+    {
+        Some(info) => info,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(GraphDefinitionsEndpoint::create_error_response(
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let query = QueryLibrary::get_definitions_by_file_prefix_query();
+
+    // Fetch one extra row to know whether there is a next page
+    let mut query_params = serde_json::Map::new();
+    query_params.insert(
+        "path_prefix".to_string(),
+        serde_json::Value::String(path_prefix),
+    );
+    query_params.insert(
+        "skip".to_string(),
+        serde_json::Value::Number(((page - 1) as u64 * page_size as u64).into()),
+    );
+    query_params.insert(
+        "limit".to_string(),
+        serde_json::Value::Number((page_size as u64 + 1).into()),
+    );
+
+    let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
+    let mut query_result = match query_service.execute_query(
+        project_info.database_path.clone(),
+        query.query,
+        query_params,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to execute definitions query: {}", e);
+            let (status, code) = query_error_status(&e);
+            return (
+                status,
+                Json(GraphDefinitionsEndpoint::create_error_response(
+                    code,
+                    format!("Failed to execute definitions query: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let (files, has_more) = match group_definitions_by_file(&mut query_result, page_size) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to convert query result to definitions: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GraphDefinitionsEndpoint::create_error_response(
+                    "query_result_processing_failed",
+                    format!("Failed to process definitions: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(GraphDefinitionsEndpoint::create_success_response(
+            files,
+            page,
+            page_size,
+            has_more,
+            to_ts_project_info(&project_info),
+        )),
+    )
+        .into_response()
+}
+
+/// Groups rows ordered by file path into one entry per file. Returns whether there were
+/// more rows than fit on the page.
+fn group_definitions_by_file(
+    query_result: &mut Box<dyn QueryResult>,
+    page_size: u32,
+) -> Result<(Vec<GraphFileDefinitions>, bool), Box<dyn std::error::Error>> {
+    let mut files: Vec<GraphFileDefinitions> = Vec::new();
+    let mut count = 0;
+
+    while let Some(row) = query_result.next() {
+        if count == page_size {
+            return Ok((files, true));
+        }
+        count += 1;
+
+        let definition_type = row.get_string_value(3)?;
+        let file_path = row.get_string_value(4)?;
+        let fqn = row.get_string_value(2)?;
+        let language = row.get_string_value(7)?;
+        let definition = GraphDefinitionSummary {
+            id: row.get_string_value(0)?,
+            name: row.get_string_value(1)?,
+            fqn_parts: split_fqn(&fqn, &language),
+            fqn,
+            definition_category: DefinitionCategory::from_definition_type(&definition_type)
+                .to_string(),
+            definition_type,
+            start_line: row.get_int_value(5)? as i32,
+            end_line: row.get_int_value(6)? as i32,
+        };
+
+        match files.last_mut() {
+            Some(file) if file.path == file_path => file.definitions.push(definition),
+            _ => files.push(GraphFileDefinitions {
+                path: file_path,
+                definitions: vec![definition],
+            }),
+        }
+    }
+
+    Ok((files, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{build_app_state, index_data};
+    use testing::repository::TestRepository;
+
+    use super::*;
+    use axum::{Router, routing::get};
+    use axum_test::TestServer;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    async fn create_test_app_with_indexed_data() -> (TestServer, String, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+
+        let workspace_folder = temp_dir.path().join("test_workspace");
+        std::fs::create_dir_all(&workspace_folder).unwrap();
+
+        let _repository =
+            TestRepository::new(&workspace_folder.join("test-repo"), Some("test-repo"));
+
+        let (app_state, temp_dir) =
+            build_app_state(temp_dir, vec![workspace_folder], None).unwrap();
+
+        let workspace_folder_path = app_state.workspace_manager.list_workspace_folders()[0]
+            .workspace_folder_path
+            .clone();
+        index_data(&app_state, vec![PathBuf::from(&workspace_folder_path)]).await;
+
+        let project_path = app_state
+            .workspace_manager
+            .list_projects_in_workspace(&workspace_folder_path)[0]
+            .project_path
+            .clone();
+
+        let app = Router::new()
+            .route(
+                "/graph/definitions/{workspace_folder_path}/{project_path}",
+                get(graph_definitions_handler),
+            )
+            .with_state(app_state);
+
+        let base_url = format!(
+            "/graph/definitions/{}/{}",
+            urlencoding::encode(&workspace_folder_path),
+            urlencoding::encode(&project_path)
+        );
+
+        (TestServer::new(app).unwrap(), base_url, temp_dir)
+    }
+
+    #[test]
+    fn test_normalize_path_prefix() {
+        assert_eq!(normalize_path_prefix(""), "");
+        assert_eq!(normalize_path_prefix("  "), "");
+        assert_eq!(normalize_path_prefix("lib"), "lib/");
+        assert_eq!(normalize_path_prefix("./lib/"), "lib/");
+        assert_eq!(normalize_path_prefix("/app/models"), "app/models/");
+    }
+
+    #[tokio::test]
+    async fn test_graph_definitions_by_path_prefix() {
+        let (server, base_url, _temp_dir) = create_test_app_with_indexed_data().await;
+
+        let response = server
+            .get(&format!("{base_url}?path_prefix=app/models"))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphDefinitionsSuccessResponse>();
+
+        assert!(
+            !body.files.is_empty(),
+            "Expected definitions under app/models"
+        );
+        assert!(!body.has_more);
+        for file in &body.files {
+            assert!(
+                file.path.starts_with("app/models/"),
+                "Unexpected file {}",
+                file.path
+            );
+            assert!(!file.definitions.is_empty());
+            for definition in &file.definitions {
+                assert_eq!(
+                    definition.fqn_parts.concat(),
+                    definition.fqn.replace([':', '#'], ""),
+                    "Unexpected parts of {}",
+                    definition.fqn
+                );
+            }
+        }
+
+        let mut paths: Vec<_> = body.files.iter().map(|file| file.path.clone()).collect();
+        paths.dedup();
+        assert_eq!(paths.len(), body.files.len(), "Files should not repeat");
+    }
+
+    #[tokio::test]
+    async fn test_graph_definitions_pagination() {
+        let (server, base_url, _temp_dir) = create_test_app_with_indexed_data().await;
+
+        let response = server.get(&format!("{base_url}?page_size=2")).await;
+        response.assert_status(StatusCode::OK);
+        let first_page = response.json::<GraphDefinitionsSuccessResponse>();
+        let first_ids: Vec<_> = first_page
+            .files
+            .iter()
+            .flat_map(|file| file.definitions.iter().map(|d| d.id.clone()))
+            .collect();
+        assert_eq!(first_ids.len(), 2);
+        assert!(first_page.has_more);
+
+        let response = server.get(&format!("{base_url}?page=2&page_size=2")).await;
+        response.assert_status(StatusCode::OK);
+        let second_page = response.json::<GraphDefinitionsSuccessResponse>();
+        assert_eq!(second_page.page, 2);
+        for file in &second_page.files {
+            for definition in &file.definitions {
+                assert!(!first_ids.contains(&definition.id));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graph_definitions_rejects_page_zero() {
+        let (server, base_url, _temp_dir) = create_test_app_with_indexed_data().await;
+
+        let response = server.get(&format!("{base_url}?page=0")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,277 @@
+use super::shared::{create_error_response, query_error_status};
+use crate::AppState;
+use crate::contract::{EmptyRequest, EndpointConfigTypes};
+use crate::decode_url_param;
+use crate::define_endpoint;
+use crate::endpoints::shared::ApiError;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use database::querying::{
+    QueryLibrary, QueryResult, QueryingService, service::DatabaseQueryingService,
+};
+use event_bus::types::project_info::{TSProjectInfo, to_ts_project_info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{error, info};
+use ts_rs::TS;
+
+#[derive(Deserialize, Serialize, TS, Default, Clone, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDirectoryCountsPathRequest {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDirectoryCountsSuccessResponse {
+    /// Directory path relative to the project root -> number of definitions in the files
+    /// under it, nested directories included
+    pub definition_counts: BTreeMap<String, u32>,
+    pub project_info: TSProjectInfo,
+}
+
+#[derive(Serialize, Deserialize, TS, Default, Debug)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphDirectoryCountsResponses {
+    #[serde(rename = "200")]
+    pub ok: Option<GraphDirectoryCountsSuccessResponse>,
+    #[serde(rename = "404")]
+    pub not_found: Option<ApiError>,
+    #[serde(rename = "400")]
+    pub bad_request: Option<ApiError>,
+    #[serde(rename = "500")]
+    pub internal_server_error: Option<ApiError>,
+}
+
+pub struct GraphDirectoryCountsEndpointConfig;
+
+impl EndpointConfigTypes for GraphDirectoryCountsEndpointConfig {
+    type PathRequest = GraphDirectoryCountsPathRequest;
+    type BodyRequest = EmptyRequest;
+    type QueryRequest = EmptyRequest;
+    type Response = GraphDirectoryCountsSuccessResponse;
+}
+
+define_endpoint! {
+    GraphDirectoryCountsEndpoint,
+    GraphDirectoryCountsEndpointDef,
+    Get,
+    "/graph/directory-counts/{workspace_folder_path}/{project_path}",
+    ts_path_type = "\"/api/graph/directory-counts/{workspace_folder_path}/{project_path}\"",
+    config = GraphDirectoryCountsEndpointConfig,
+    export_to = "../../../packages/gkg/src/api.ts"
+}
+
+impl GraphDirectoryCountsEndpoint {
+    pub fn create_success_response(
+        definition_counts: BTreeMap<String, u32>,
+        project_info: TSProjectInfo,
+    ) -> GraphDirectoryCountsSuccessResponse {
+        GraphDirectoryCountsSuccessResponse {
+            definition_counts,
+            project_info,
+        }
+    }
+
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        create_error_response(code, message)
+    }
+}
+
+pub async fn graph_directory_counts_handler(
+    State(state): State<AppState>,
+    Path(path_params): Path<GraphDirectoryCountsPathRequest>,
+) -> impl IntoResponse {
+    let input_project_path = decode_url_param!(
+        &path_params.project_path,
+        "project_path",
+        GraphDirectoryCountsEndpoint::create_error_response
+    );
+    let input_workspace_folder_path = decode_url_param!(
+        &path_params.workspace_folder_path,
+        "workspace_folder_path",
+        GraphDirectoryCountsEndpoint::create_error_response
+    );
+
+    if input_project_path.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphDirectoryCountsEndpoint::create_error_response(
+                "empty_project_path",
+                "Project path must not be empty",
+            )),
+        )
+            .into_response();
+    }
+
+    info!(
+        "Received directory counts request {workspace_folder_path} {project_path}",
+        workspace_folder_path = input_workspace_folder_path,
+        project_path = input_project_path,
+    );
+
+    let project_info = match state
+        .workspace_manager
+        .get_project_info(&input_workspace_folder_path, &input_project_path)
+    {
+        Some(info) => info,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(GraphDirectoryCountsEndpoint::create_error_response(
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let query = QueryLibrary::get_directory_definition_counts_query();
+    let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
+    let mut query_result = match query_service.execute_query(
+        project_info.database_path.clone(),
+        query.query,
+        serde_json::Map::new(),
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to execute directory counts query: {}", e);
+            let (status, code) = query_error_status(&e);
+            return (
+                status,
+                Json(GraphDirectoryCountsEndpoint::create_error_response(
+                    code,
+                    format!("Failed to execute directory counts query: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let definition_counts = match collect_definition_counts(&mut query_result) {
+        Ok(counts) => counts,
+        Err(e) => {
+            error!("Failed to convert query result to directory counts: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GraphDirectoryCountsEndpoint::create_error_response(
+                    "query_result_processing_failed",
+                    format!("Failed to process directory counts: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(GraphDirectoryCountsEndpoint::create_success_response(
+            definition_counts,
+            to_ts_project_info(&project_info),
+        )),
+    )
+        .into_response()
+}
+
+fn collect_definition_counts(
+    query_result: &mut Box<dyn QueryResult>,
+) -> Result<BTreeMap<String, u32>, Box<dyn std::error::Error>> {
+    let mut counts = BTreeMap::new();
+
+    while let Some(row) = query_result.next() {
+        let directory_path = row.get_string_value(0)?;
+        let definition_count = row.get_int_value(1)?;
+        counts.insert(directory_path, definition_count as u32);
+    }
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::graph::graph_definitions::{
+        GraphDefinitionsSuccessResponse, graph_definitions_handler,
+    };
+    use crate::testing::{build_app_state, index_data};
+    use testing::repository::TestRepository;
+
+    use super::*;
+    use axum::{Router, routing::get};
+    use axum_test::TestServer;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_graph_directory_counts_include_nested_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder = temp_dir.path().join("test_workspace");
+        std::fs::create_dir_all(&workspace_folder).unwrap();
+        let _repository =
+            TestRepository::new(&workspace_folder.join("test-repo"), Some("test-repo"));
+
+        let (app_state, _temp_dir) =
+            build_app_state(temp_dir, vec![workspace_folder], None).unwrap();
+        let workspace_folder_path = app_state.workspace_manager.list_workspace_folders()[0]
+            .workspace_folder_path
+            .clone();
+        index_data(&app_state, vec![PathBuf::from(&workspace_folder_path)]).await;
+        let project_path = app_state
+            .workspace_manager
+            .list_projects_in_workspace(&workspace_folder_path)[0]
+            .project_path
+            .clone();
+
+        let app = Router::new()
+            .route(
+                "/graph/directory-counts/{workspace_folder_path}/{project_path}",
+                get(graph_directory_counts_handler),
+            )
+            .route(
+                "/graph/definitions/{workspace_folder_path}/{project_path}",
+                get(graph_definitions_handler),
+            )
+            .with_state(app_state);
+        let server = TestServer::new(app).unwrap();
+        let encoded_paths = format!(
+            "{}/{}",
+            urlencoding::encode(&workspace_folder_path),
+            urlencoding::encode(&project_path)
+        );
+
+        let response = server
+            .get(&format!("/graph/directory-counts/{encoded_paths}"))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let counts = response
+            .json::<GraphDirectoryCountsSuccessResponse>()
+            .definition_counts;
+
+        let count_definitions_under = |prefix: &'static str| {
+            let server = &server;
+            let encoded_paths = &encoded_paths;
+            async move {
+                server
+                    .get(&format!(
+                        "/graph/definitions/{encoded_paths}?path_prefix={prefix}&page_size=500"
+                    ))
+                    .await
+                    .json::<GraphDefinitionsSuccessResponse>()
+                    .files
+                    .iter()
+                    .map(|file| file.definitions.len() as u32)
+                    .sum::<u32>()
+            }
+        };
+
+        let app_count = counts["app"];
+        let models_count = counts["app/models"];
+        assert!(models_count > 0);
+        assert!(app_count >= models_count);
+        assert_eq!(app_count, count_definitions_under("app").await);
+        assert_eq!(models_count, count_definitions_under("app/models").await);
+    }
+}
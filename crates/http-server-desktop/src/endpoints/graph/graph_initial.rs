@@ -1,24 +1,29 @@
 use super::shared::{
-    GraphRelationship, TypedGraphNode, create_error_response, create_typed_node, extract_node_data,
+    GraphRelationship, NodeData, TypedGraphNode, create_error_response, create_typed_node,
+    extract_node_data, query_error_status,
 };
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::decode_url_param;
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use database::querying::mappers::RELATIONSHIP_TYPE_MAPPER;
 use database::querying::{
-    QueryLibrary, QueryResult, QueryResultRow, QueryingService, service::DatabaseQueryingService,
+    QueryLibrary, QueryResult, QueryingService, service::DatabaseQueryingService,
 };
 use event_bus::types::project_info::{TSProjectInfo, to_ts_project_info};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{error, info};
 use ts_rs::TS;
 use urlencoding;
+use workspace_manager::ProjectInfo;
 
 #[derive(Deserialize, Serialize, TS, Default, Clone, Debug)]
 #[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
@@ -34,6 +39,15 @@ pub struct GraphInitialQueryRequest {
     pub file_limit: Option<i32>,
     pub definition_limit: Option<i32>,
     pub imported_symbol_limit: Option<i32>,
+    /// FQN of a definition to center the initial graph on
+    pub focus_fqn: Option<String>,
+    /// File to center the initial graph on, relative to the project root or absolute.
+    /// Combined with `focus_fqn` it disambiguates definitions sharing the same FQN.
+    pub focus_file: Option<String>,
+    /// Maximum number of nodes in the response, defaults to 1000
+    pub max_nodes: Option<u32>,
+    /// Maximum number of relationships in the response, defaults to 2000
+    pub max_edges: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, TS, Default, Debug)]
@@ -41,6 +55,12 @@ pub struct GraphInitialQueryRequest {
 pub struct GraphInitialSuccessResponse {
     pub nodes: Vec<TypedGraphNode>,
     pub relationships: Vec<GraphRelationship>,
+    /// Id of the node the graph is centered on, when a focus was requested and found
+    pub focus_node_id: Option<String>,
+    /// Whether the requested focus node was not found and the default view was returned
+    pub focus_fallback: bool,
+    /// Whether nodes or relationships were left out to stay within `max_nodes` and `max_edges`
+    pub truncated: bool,
     pub project_info: TSProjectInfo,
 }
 
@@ -50,11 +70,11 @@ pub struct GraphInitialResponses {
     #[serde(rename = "200")]
     pub ok: Option<GraphInitialSuccessResponse>,
     #[serde(rename = "404")]
-    pub not_found: Option<StatusResponse>,
+    pub not_found: Option<ApiError>,
     #[serde(rename = "400")]
-    pub bad_request: Option<StatusResponse>,
+    pub bad_request: Option<ApiError>,
     #[serde(rename = "500")]
-    pub internal_server_error: Option<StatusResponse>,
+    pub internal_server_error: Option<ApiError>,
 }
 
 pub struct GraphInitialEndpointConfig;
@@ -80,22 +100,33 @@ impl GraphInitialEndpoint {
     pub fn create_success_response(
         nodes: Vec<TypedGraphNode>,
         relationships: Vec<GraphRelationship>,
+        focus_node_id: Option<String>,
+        focus_fallback: bool,
+        truncated: bool,
         project_info: TSProjectInfo,
     ) -> GraphInitialSuccessResponse {
         GraphInitialSuccessResponse {
             nodes,
             relationships,
+            focus_node_id,
+            focus_fallback,
+            truncated,
             project_info,
         }
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        create_error_response(status)
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        create_error_response(code, message)
     }
 }
 
+const FOCUS_NEIGHBOR_LIMIT: i32 = 100;
+const DEFAULT_MAX_NODES: u32 = 1000;
+const DEFAULT_MAX_EDGES: u32 = 2000;
+
 /// Handler for the graph initial endpoint
-/// Fetches the initial graph structure for a project including top-level directories, files, and definitions
+/// Fetches the initial graph structure for a project including top-level directories, files, and definitions.
+/// When `focus_fqn` or `focus_file` is given, the neighborhood of that node is returned instead.
 pub async fn graph_initial_handler(
     State(state): State<AppState>,
     Path(path_params): Path<GraphInitialPathRequest>,
@@ -112,16 +143,33 @@ pub async fn graph_initial_handler(
         GraphInitialEndpoint::create_error_response
     );
 
-    let directory_limit = query_params.directory_limit.unwrap_or(100);
-    let file_limit = query_params.file_limit.unwrap_or(200);
-    let definition_limit = query_params.definition_limit.unwrap_or(500);
-    let imported_symbol_limit = query_params.imported_symbol_limit.unwrap_or(50);
+    let max_nodes = query_params.max_nodes.unwrap_or(DEFAULT_MAX_NODES) as usize;
+    let max_edges = query_params.max_edges.unwrap_or(DEFAULT_MAX_EDGES) as usize;
+    // Each row is a relationship, so no query section needs more than `max_edges` rows. The
+    // extra row tells whether a section was cut short.
+    let row_limit = i32::try_from(max_edges + 1).unwrap_or(i32::MAX);
+    let directory_limit = query_params.directory_limit.unwrap_or(100).min(row_limit);
+    let file_limit = query_params.file_limit.unwrap_or(200).min(row_limit);
+    let definition_limit = query_params.definition_limit.unwrap_or(500).min(row_limit);
+    let imported_symbol_limit = query_params
+        .imported_symbol_limit
+        .unwrap_or(50)
+        .min(row_limit);
+    let focus_fqn = query_params
+        .focus_fqn
+        .clone()
+        .filter(|fqn| !fqn.trim().is_empty());
+    let focus_file = query_params
+        .focus_file
+        .clone()
+        .filter(|file| !file.trim().is_empty());
 
     if input_project_path.trim().is_empty() {
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphInitialEndpoint::create_error_response(
-                "empty_project_path".to_string(),
+                "empty_project_path",
+                "Project path must not be empty",
             )),
         )
             .into_response();
@@ -143,13 +191,53 @@ pub async fn graph_initial_handler(
             return (
                 StatusCode::NOT_FOUND,
                 Json(GraphInitialEndpoint::create_error_response(
-                    "project_not_found".to_string(),
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
                 )),
             )
                 .into_response();
         }
     };
 
+    let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
+
+    let focus_node = if focus_fqn.is_some() || focus_file.is_some() {
+        match find_focus_node(
+            &query_service,
+            project_info.database_path.clone(),
+            focus_fqn.as_deref(),
+            focus_file.as_deref(),
+        ) {
+            Ok(node) => node,
+            Err(e) => {
+                error!("Failed to look up focus node: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(GraphInitialEndpoint::create_error_response(
+                        "query_execution_failed",
+                        format!("Failed to look up focus node: {e}"),
+                    )),
+                )
+                    .into_response();
+            }
+        }
+    } else {
+        None
+    };
+    let focus_fallback = (focus_fqn.is_some() || focus_file.is_some()) && focus_node.is_none();
+
+    if let Some((node_type, node_id)) = &focus_node {
+        return focused_graph_response(
+            &query_service,
+            &project_info,
+            node_type,
+            node_id,
+            FOCUS_NEIGHBOR_LIMIT.min(row_limit),
+            max_nodes,
+            max_edges,
+        );
+    }
+
     let query = QueryLibrary::get_initial_project_graph_query();
 
     let mut query_params = serde_json::Map::new();
@@ -170,8 +258,6 @@ pub async fn graph_initial_handler(
         serde_json::Value::Number(imported_symbol_limit.into()),
     );
 
-    let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
-
     info!(
         "Executing initial graph query for project {} and workspace folder {}, query params: {:?}",
         project_info.project_path, input_workspace_folder_path, query_params
@@ -184,25 +270,28 @@ pub async fn graph_initial_handler(
         Ok(result) => result,
         Err(e) => {
             error!("Failed to execute initial graph query: {}", e);
+            let (status, code) = query_error_status(&e);
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphInitialEndpoint::create_error_response(format!(
-                    "Failed to execute graph query: {e}"
-                ))),
+                status,
+                Json(GraphInitialEndpoint::create_error_response(
+                    code,
+                    format!("Failed to execute graph query: {e}"),
+                )),
             )
                 .into_response();
         }
     };
 
-    let graph_data = match convert_query_result_to_graph(&mut query_result) {
+    let graph_data = match convert_query_result_to_graph(&mut query_result, max_nodes, max_edges) {
         Ok(data) => data,
         Err(e) => {
             error!("Failed to convert query result to graph: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphInitialEndpoint::create_error_response(format!(
-                    "Failed to process graph data: {e}"
-                ))),
+                Json(GraphInitialEndpoint::create_error_response(
+                    "query_result_processing_failed",
+                    format!("Failed to process graph data: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -211,84 +300,230 @@ pub async fn graph_initial_handler(
     (
         StatusCode::OK,
         Json(GraphInitialEndpoint::create_success_response(
-            graph_data.0,
-            graph_data.1,
+            graph_data.nodes,
+            graph_data.relationships,
+            None,
+            focus_fallback,
+            graph_data.truncated,
             to_ts_project_info(&project_info),
         )),
     )
         .into_response()
 }
 
-fn convert_query_result_to_graph(
-    query_result: &mut Box<dyn QueryResult>,
-) -> Result<(Vec<TypedGraphNode>, Vec<GraphRelationship>), Box<dyn std::error::Error>> {
-    let mut nodes = Vec::new();
-    let mut relationships = Vec::new();
-    let mut node_ids = std::collections::HashSet::new();
-    let mut relationship_ids = std::collections::HashSet::new();
+/// Resolves the focus parameters to a `(node_type, node_id)` pair. A definition is looked up
+/// when `focus_fqn` is set, optionally narrowed down to `focus_file`, otherwise the file itself.
+fn find_focus_node(
+    query_service: &DatabaseQueryingService,
+    database_path: PathBuf,
+    focus_fqn: Option<&str>,
+    focus_file: Option<&str>,
+) -> Result<Option<(&'static str, String)>, Box<dyn std::error::Error>> {
+    let mut params = serde_json::Map::new();
+    params.insert(
+        "file_path".to_string(),
+        serde_json::Value::String(focus_file.unwrap_or_default().to_string()),
+    );
 
-    let mut all_rows = Vec::new();
-    while let Some(row) = query_result.next() {
-        let order_priority = row.get_int_value(37)?;
-        all_rows.push((row, order_priority));
-    }
+    let (node_type, query) = match focus_fqn {
+        Some(fqn) => {
+            params.insert(
+                "fqn".to_string(),
+                serde_json::Value::String(fqn.to_string()),
+            );
+            (
+                "DefinitionNode",
+                QueryLibrary::get_definition_id_by_fqn_query(),
+            )
+        }
+        None => ("FileNode", QueryLibrary::get_file_id_by_path_query()),
+    };
 
-    all_rows.sort_by_key(|(_, priority)| *priority);
+    let mut result = query_service.execute_query(database_path, query.query, params)?;
 
-    for (row, _) in all_rows {
-        process_graph_row(
-            row,
-            &mut nodes,
-            &mut relationships,
-            &mut node_ids,
-            &mut relationship_ids,
-        )?;
+    match result.next() {
+        Some(row) => Ok(Some((node_type, row.get_string_value(0)?))),
+        None => Ok(None),
     }
+}
+
+fn focused_graph_response(
+    query_service: &DatabaseQueryingService,
+    project_info: &ProjectInfo,
+    node_type: &str,
+    node_id: &str,
+    limit: i32,
+    max_nodes: usize,
+    max_edges: usize,
+) -> axum::response::Response {
+    let Some(query) = QueryLibrary::get_node_neighbors_query(node_type) else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(GraphInitialEndpoint::create_error_response(
+                "invalid_node_type",
+                format!("No neighbors query for node type {node_type}"),
+            )),
+        )
+            .into_response();
+    };
+
+    let mut query_params = serde_json::Map::new();
+    query_params.insert(
+        "node_id".to_string(),
+        serde_json::Value::String(node_id.to_string()),
+    );
+    query_params.insert("limit".to_string(), serde_json::Value::Number(limit.into()));
+
+    let mut query_result = match query_service.execute_query(
+        project_info.database_path.clone(),
+        query.query,
+        query_params,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Failed to execute focused graph query: {}", e);
+            let (status, code) = query_error_status(&e);
+            return (
+                status,
+                Json(GraphInitialEndpoint::create_error_response(
+                    code,
+                    format!("Failed to execute graph query: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let graph_data = match convert_query_result_to_graph(&mut query_result, max_nodes, max_edges) {
+        Ok(data) => data,
+        Err(e) => {
+            error!("Failed to convert query result to graph: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GraphInitialEndpoint::create_error_response(
+                    "query_result_processing_failed",
+                    format!("Failed to process graph data: {e}"),
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(GraphInitialEndpoint::create_success_response(
+            graph_data.nodes,
+            graph_data.relationships,
+            Some(format!("{node_type}_{node_id}")),
+            false,
+            graph_data.truncated,
+            to_ts_project_info(project_info),
+        )),
+    )
+        .into_response()
+}
+
+struct GraphRow {
+    source: NodeData,
+    target: NodeData,
+    relationship_name: String,
+    relationship_id: String,
+    relationship_type: String,
+    order_priority: i64,
+}
 
-    Ok((nodes, relationships))
+struct GraphData {
+    nodes: Vec<TypedGraphNode>,
+    relationships: Vec<GraphRelationship>,
+    truncated: bool,
 }
 
-fn process_graph_row(
-    row: Box<dyn QueryResultRow>,
-    nodes: &mut Vec<TypedGraphNode>,
-    relationships: &mut Vec<GraphRelationship>,
-    node_ids: &mut std::collections::HashSet<String>,
-    relationship_ids: &mut std::collections::HashSet<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let source_data = extract_node_data(&*row, 0)?;
-    let target_data = extract_node_data(&*row, 17)?;
-
-    let relationship_name = row.get_string_value(34)?;
-    let relationship_id = row.get_string_value(35)?;
-    let relationship_type = RELATIONSHIP_TYPE_MAPPER(&*row, 36)?;
-
-    let source_id = source_data.id.clone();
-    let target_id = target_data.id.clone();
-
-    if node_ids.insert(source_id.clone()) {
-        nodes.push(create_typed_node(source_data)?);
+/// Builds the graph from the query rows, keeping at most `max_nodes` nodes and `max_edges`
+/// relationships. Rows are taken by `order_priority` first, so the directory and file structure
+/// comes before definitions and imports, then by the degree of their nodes within the result.
+fn convert_query_result_to_graph(
+    query_result: &mut Box<dyn QueryResult>,
+    max_nodes: usize,
+    max_edges: usize,
+) -> Result<GraphData, Box<dyn std::error::Error>> {
+    let mut rows = Vec::new();
+    while let Some(row) = query_result.next() {
+        rows.push(GraphRow {
+            source: extract_node_data(&*row, 0)?,
+            target: extract_node_data(&*row, 19)?,
+            relationship_name: row.get_string_value(38)?,
+            relationship_id: row.get_string_value(39)?,
+            relationship_type: RELATIONSHIP_TYPE_MAPPER(&*row, 40)?.to_string(),
+            order_priority: row.get_int_value(41)?,
+        });
     }
 
-    if node_ids.insert(target_id.clone()) {
-        nodes.push(create_typed_node(target_data)?);
+    let mut degrees: HashMap<String, usize> = HashMap::new();
+    for row in &rows {
+        *degrees.entry(row.source.id.clone()).or_default() += 1;
+        *degrees.entry(row.target.id.clone()).or_default() += 1;
     }
+    rows.sort_by_key(|row| {
+        (
+            row.order_priority,
+            Reverse(degrees[&row.source.id] + degrees[&row.target.id]),
+        )
+    });
+
+    let mut nodes = Vec::new();
+    let mut relationships = Vec::new();
+    let mut node_ids = HashSet::new();
+    let mut relationship_ids = HashSet::new();
+    let mut truncated = false;
+
+    for row in rows {
+        if relationship_ids.contains(&row.relationship_id) {
+            continue;
+        }
+
+        let mut new_nodes = HashSet::new();
+        for id in [&row.source.id, &row.target.id] {
+            if !node_ids.contains(id) {
+                new_nodes.insert(id.clone());
+            }
+        }
+        if relationships.len() >= max_edges || nodes.len() + new_nodes.len() > max_nodes {
+            truncated = true;
+            continue;
+        }
+
+        let source_id = row.source.id.clone();
+        let target_id = row.target.id.clone();
+
+        if node_ids.insert(source_id.clone()) {
+            nodes.push(create_typed_node(row.source)?);
+        }
+
+        if node_ids.insert(target_id.clone()) {
+            nodes.push(create_typed_node(row.target)?);
+        }
 
-    if relationship_ids.insert(relationship_id.clone()) {
+        relationship_ids.insert(row.relationship_id.clone());
         relationships.push(GraphRelationship {
-            id: relationship_id,
+            id: row.relationship_id,
             source: source_id,
             target: target_id,
-            relationship_name,
-            relationship_type: relationship_type.to_string(),
+            relationship_name: row.relationship_name,
+            relationship_type: row.relationship_type,
         });
     }
 
-    Ok(())
+    Ok(GraphData {
+        nodes,
+        relationships,
+        truncated,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::testing::{build_app_state, index_data};
+    use database::graph::DefinitionCategory;
     use testing::repository::TestRepository;
 
     use super::*;
@@ -340,8 +575,8 @@ mod tests {
         let response = server.get("/graph/initial/placeholder_workspace/%20").await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_project_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_project_path");
     }
 
     #[tokio::test]
@@ -464,6 +699,11 @@ mod tests {
                 !properties.definition_type.is_empty(),
                 "DefinitionNode definition_type should not be empty"
             );
+            assert_eq!(
+                properties.definition_category,
+                DefinitionCategory::from_definition_type(&properties.definition_type).to_string(),
+                "DefinitionNode definition_category should match its definition_type"
+            );
             assert!(
                 properties.start_line > 0,
                 "DefinitionNode start_line should be positive"
@@ -560,4 +800,137 @@ mod tests {
             "Should have at least one Definition to Definition relationship"
         );
     }
+
+    async fn get_initial_graph(
+        server: &TestServer,
+        app_state: &AppState,
+        query_string: &str,
+    ) -> GraphInitialSuccessResponse {
+        let workspace_folder_path = app_state.workspace_manager.list_workspace_folders()[0]
+            .workspace_folder_path
+            .clone();
+        let project_path = app_state
+            .workspace_manager
+            .list_projects_in_workspace(&workspace_folder_path)[0]
+            .project_path
+            .clone();
+
+        let response = server
+            .get(&format!(
+                "/graph/initial/{}/{}?{query_string}",
+                urlencoding::encode(&workspace_folder_path),
+                urlencoding::encode(&project_path)
+            ))
+            .await;
+        response.assert_status(StatusCode::OK);
+        response.json::<GraphInitialSuccessResponse>()
+    }
+
+    #[tokio::test]
+    async fn test_graph_initial_focus_fqn() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let body = get_initial_graph(&server, &app_state, "focus_fqn=UserModel").await;
+
+        assert!(!body.focus_fallback);
+        let focus_node_id = body.focus_node_id.expect("focus node should be found");
+        let focus_node = body
+            .nodes
+            .iter()
+            .find(|node| match node {
+                TypedGraphNode::DefinitionNode { id, .. } => *id == focus_node_id,
+                _ => false,
+            })
+            .expect("focus node should be part of the graph");
+        if let TypedGraphNode::DefinitionNode { properties, .. } = focus_node {
+            assert_eq!(properties.fqn, "UserModel");
+        }
+        assert!(
+            body.relationships
+                .iter()
+                .all(|r| r.source == focus_node_id || r.target == focus_node_id),
+            "Every relationship should touch the focus node"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_graph_initial_focus_file() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let body = get_initial_graph(
+            &server,
+            &app_state,
+            &format!(
+                "focus_file={}",
+                urlencoding::encode("app/models/user_model.rb")
+            ),
+        )
+        .await;
+
+        assert!(!body.focus_fallback);
+        let focus_node_id = body.focus_node_id.expect("focus node should be found");
+        assert!(focus_node_id.starts_with("FileNode_"));
+        assert!(body.nodes.iter().any(|node| matches!(
+            node,
+            TypedGraphNode::DefinitionNode { properties, .. } if properties.fqn == "UserModel"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_graph_initial_focus_not_found_falls_back() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let body = get_initial_graph(&server, &app_state, "focus_fqn=DoesNotExist").await;
+        let default_body = get_initial_graph(&server, &app_state, "").await;
+
+        assert!(body.focus_fallback);
+        assert!(body.focus_node_id.is_none());
+        assert!(!default_body.focus_fallback);
+        assert_eq!(body.nodes.len(), default_body.nodes.len());
+        assert_eq!(body.relationships.len(), default_body.relationships.len());
+    }
+
+    #[tokio::test]
+    async fn test_graph_initial_respects_max_nodes_and_edges() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let full = get_initial_graph(&server, &app_state, "").await;
+        assert!(!full.truncated);
+        assert!(full.nodes.len() > 5);
+
+        let capped = get_initial_graph(&server, &app_state, "max_nodes=5&max_edges=3").await;
+        assert!(capped.truncated);
+        assert!(!capped.nodes.is_empty());
+        assert!(capped.nodes.len() <= 5);
+        assert!(capped.relationships.len() <= 3);
+
+        let node_ids: std::collections::HashSet<_> = capped
+            .nodes
+            .iter()
+            .map(|node| match node {
+                TypedGraphNode::DirectoryNode { id, .. }
+                | TypedGraphNode::FileNode { id, .. }
+                | TypedGraphNode::DefinitionNode { id, .. }
+                | TypedGraphNode::ImportedSymbolNode { id, .. } => id.clone(),
+            })
+            .collect();
+        assert!(
+            capped
+                .relationships
+                .iter()
+                .all(|r| node_ids.contains(&r.source) && node_ids.contains(&r.target)),
+            "Relationships should only reference returned nodes"
+        );
+        assert!(
+            capped
+                .nodes
+                .iter()
+                .all(|node| !matches!(node, TypedGraphNode::ImportedSymbolNode { .. })),
+            "Directory and file structure should be kept before imports"
+        );
+    }
 }
@@ -1,14 +1,16 @@
 use super::shared::{
-    GraphRelationship, TypedGraphNode, create_error_response, create_typed_node, extract_node_data,
+    ExplainMode, GraphRelationship, QueryExplanation, TypedGraphNode, create_error_response,
+    create_typed_node, explain_query, extract_node_data, query_error_status,
 };
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::decode_url_param;
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
+use database::graph::RelationshipType;
 use database::querying::mappers::RELATIONSHIP_TYPE_MAPPER;
 use database::querying::{
     QueryLibrary, QueryResult, QueryResultRow, QueryingService, service::DatabaseQueryingService,
@@ -33,6 +35,11 @@ pub struct GraphNeighborsPathRequest {
 #[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
 pub struct GraphNeighborsQueryRequest {
     pub limit: Option<i32>,
+    /// Only follows the relationships of this type, e.g. `INSTANTIATES`
+    pub relationship_type: Option<String>,
+    /// `true` returns the query and its plan alongside the neighbors, `only` returns them
+    /// without fetching the neighbors
+    pub explain: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, TS, Default, Debug)]
@@ -41,6 +48,9 @@ pub struct GraphNeighborsSuccessResponse {
     pub nodes: Vec<TypedGraphNode>,
     pub relationships: Vec<GraphRelationship>,
     pub project_info: TSProjectInfo,
+    /// The query and its plan, only filled when `explain` is requested
+    #[serde(default)]
+    pub explain: Option<QueryExplanation>,
 }
 
 #[derive(Serialize, Deserialize, TS, Default, Debug)]
@@ -49,11 +59,11 @@ pub struct GraphNeighborsResponses {
     #[serde(rename = "200")]
     pub ok: Option<GraphNeighborsSuccessResponse>,
     #[serde(rename = "404")]
-    pub not_found: Option<StatusResponse>,
+    pub not_found: Option<ApiError>,
     #[serde(rename = "400")]
-    pub bad_request: Option<StatusResponse>,
+    pub bad_request: Option<ApiError>,
     #[serde(rename = "500")]
-    pub internal_server_error: Option<StatusResponse>,
+    pub internal_server_error: Option<ApiError>,
 }
 
 pub struct GraphNeighborsEndpointConfig;
@@ -85,11 +95,12 @@ impl GraphNeighborsEndpoint {
             nodes,
             relationships,
             project_info,
+            explain: None,
         }
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        create_error_response(status)
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        create_error_response(code, message)
     }
 }
 
@@ -120,12 +131,26 @@ pub async fn graph_neighbors_handler(
     );
 
     let limit = query_params.limit.unwrap_or(100);
+    let Some(explain_mode) = ExplainMode::parse(query_params.explain.as_deref()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphNeighborsEndpoint::create_error_response(
+                "invalid_explain",
+                format!(
+                    "Unsupported explain value: {}",
+                    query_params.explain.unwrap_or_default()
+                ),
+            )),
+        )
+            .into_response();
+    };
 
     if input_project_path.trim().is_empty() {
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphNeighborsEndpoint::create_error_response(
-                "empty_project_path".to_string(),
+                "empty_project_path",
+                "Project path must not be empty",
             )),
         )
             .into_response();
@@ -135,7 +160,8 @@ pub async fn graph_neighbors_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphNeighborsEndpoint::create_error_response(
-                "empty_node_id".to_string(),
+                "empty_node_id",
+                "Node id must not be empty",
             )),
         )
             .into_response();
@@ -145,7 +171,8 @@ pub async fn graph_neighbors_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphNeighborsEndpoint::create_error_response(
-                "empty_node_type".to_string(),
+                "empty_node_type",
+                "Node type must not be empty",
             )),
         )
             .into_response();
@@ -160,20 +187,43 @@ pub async fn graph_neighbors_handler(
             return (
                 StatusCode::NOT_FOUND,
                 Json(GraphNeighborsEndpoint::create_error_response(
-                    "project_not_found".to_string(),
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
                 )),
             )
                 .into_response();
         }
     };
 
-    let query = QueryLibrary::get_node_neighbors_query(input_node_type.as_str());
+    let relationship_type = match query_params.relationship_type.as_deref() {
+        None => None,
+        Some(name) => match RelationshipType::parse(name) {
+            Some(relationship_type) => Some(relationship_type),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(GraphNeighborsEndpoint::create_error_response(
+                        "invalid_relationship_type",
+                        format!("Unsupported relationship_type value: {name}"),
+                    )),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let query = if relationship_type.is_some() {
+        QueryLibrary::get_node_neighbors_by_relationship_type_query(input_node_type.as_str())
+    } else {
+        QueryLibrary::get_node_neighbors_query(input_node_type.as_str())
+    };
 
     if query.is_none() {
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphNeighborsEndpoint::create_error_response(
-                "invalid_node_type".to_string(),
+                "invalid_node_type",
+                "Node type is not a known graph node type",
             )),
         )
             .into_response();
@@ -186,9 +236,53 @@ pub async fn graph_neighbors_handler(
         serde_json::Value::String(input_node_id.clone()),
     );
     query_params.insert("limit".to_string(), serde_json::Value::Number(limit.into()));
+    if let Some(relationship_type) = relationship_type {
+        query_params.insert(
+            "relationship_type".to_string(),
+            serde_json::Value::String(relationship_type.as_string()),
+        );
+    }
 
     let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
 
+    let explanation = if explain_mode == ExplainMode::Off {
+        None
+    } else {
+        match explain_query(
+            &query_service,
+            project_info.database_path.clone(),
+            &query.query,
+            &query_params,
+        ) {
+            Ok(explanation) => Some(explanation),
+            Err(e) => {
+                error!("Failed to explain neighbors query: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(GraphNeighborsEndpoint::create_error_response(
+                        "query_explain_failed",
+                        format!("Failed to explain graph query: {e}"),
+                    )),
+                )
+                    .into_response();
+            }
+        }
+    };
+    if explain_mode == ExplainMode::Only {
+        return (
+            StatusCode::OK,
+            Json(GraphNeighborsSuccessResponse {
+                explain: explanation,
+                ..GraphNeighborsEndpoint::create_success_response(
+                    Vec::new(),
+                    Vec::new(),
+                    to_ts_project_info(&project_info),
+                )
+            }),
+        )
+            .into_response();
+    }
+
     let mut query_result = match query_service.execute_query(
         project_info.database_path.clone(),
         query.query.clone(),
@@ -197,11 +291,13 @@ pub async fn graph_neighbors_handler(
         Ok(result) => result,
         Err(e) => {
             error!("Failed to execute neighbors query: {}", e);
+            let (status, code) = query_error_status(&e);
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphNeighborsEndpoint::create_error_response(format!(
-                    "Failed to execute graph query: {e}"
-                ))),
+                status,
+                Json(GraphNeighborsEndpoint::create_error_response(
+                    code,
+                    format!("Failed to execute graph query: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -213,9 +309,10 @@ pub async fn graph_neighbors_handler(
             error!("Failed to convert query result to graph: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphNeighborsEndpoint::create_error_response(format!(
-                    "Failed to process graph data: {e}"
-                ))),
+                Json(GraphNeighborsEndpoint::create_error_response(
+                    "query_result_processing_failed",
+                    format!("Failed to process graph data: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -223,11 +320,14 @@ pub async fn graph_neighbors_handler(
 
     (
         StatusCode::OK,
-        Json(GraphNeighborsEndpoint::create_success_response(
-            graph_data.0,
-            graph_data.1,
-            to_ts_project_info(&project_info),
-        )),
+        Json(GraphNeighborsSuccessResponse {
+            explain: explanation,
+            ..GraphNeighborsEndpoint::create_success_response(
+                graph_data.0,
+                graph_data.1,
+                to_ts_project_info(&project_info),
+            )
+        }),
     )
         .into_response()
 }
@@ -266,11 +366,11 @@ fn process_neighbors_row(
     relationship_ids: &mut std::collections::HashSet<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let source_data = extract_node_data(&*row, 0)?;
-    let target_data = extract_node_data(&*row, 17)?;
+    let target_data = extract_node_data(&*row, 19)?;
 
-    let relationship_name = row.get_string_value(34)?;
-    let relationship_id = row.get_string_value(35)?;
-    let relationship_type = RELATIONSHIP_TYPE_MAPPER(&*row, 36)?;
+    let relationship_name = row.get_string_value(38)?;
+    let relationship_id = row.get_string_value(39)?;
+    let relationship_type = RELATIONSHIP_TYPE_MAPPER(&*row, 40)?;
 
     let source_id = source_data.id.clone();
     let target_id = target_data.id.clone();
@@ -372,8 +472,8 @@ mod tests {
             .await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_project_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_project_path");
     }
 
     #[tokio::test]
@@ -386,8 +486,8 @@ mod tests {
             .await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_node_id");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_node_id");
     }
 
     #[tokio::test]
@@ -400,8 +500,8 @@ mod tests {
             .await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_node_type");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_node_type");
     }
 
     #[tokio::test]
@@ -734,6 +834,58 @@ mod tests {
         response.assert_status(StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn test_graph_neighbors_filtered_by_relationship_type() {
+        use database::querying::{QueryingService, service::DatabaseQueryingService};
+
+        let (app, workspace_folder_path, project_path, app_state) = setup_test_environment().await;
+        let server = TestServer::new(app).unwrap();
+
+        let project_info = app_state
+            .workspace_manager
+            .get_project_info(&workspace_folder_path, &project_path)
+            .expect("Should have project info");
+        // e.g. `token = Token.new(user_id)` in lib/authentication/tokens.rb
+        let query_service = DatabaseQueryingService::new(Arc::clone(&app_state.database));
+        let mut result = query_service
+            .execute_query(
+                project_info.database_path.clone(),
+                "MATCH (:DefinitionNode)-[r:DEFINITION_RELATIONSHIPS]->(class:DefinitionNode) \
+                 WHERE r.type = 'INSTANTIATES' RETURN class.id LIMIT 1"
+                    .to_string(),
+                serde_json::Map::new(),
+            )
+            .expect("Should execute query");
+        let class_id = result
+            .next()
+            .expect("Should have an instantiated class")
+            .get_string_value(0)
+            .unwrap();
+
+        let encoded_workspace = urlencoding::encode(&workspace_folder_path);
+        let encoded_project = urlencoding::encode(&project_path);
+        let encoded_node_id = urlencoding::encode(&class_id);
+        let uri = |relationship_type: &str| {
+            format!(
+                "/graph/neighbors/{encoded_workspace}/{encoded_project}/DefinitionNode/{encoded_node_id}?relationship_type={relationship_type}"
+            )
+        };
+
+        let response = server.get(&uri("instantiates")).await;
+        response.assert_status(StatusCode::OK);
+        let response_json = response.json::<GraphNeighborsSuccessResponse>();
+        assert!(!response_json.relationships.is_empty());
+        assert!(
+            response_json
+                .relationships
+                .iter()
+                .all(|relationship| relationship.relationship_type == "INSTANTIATES")
+        );
+
+        let response = server.get(&uri("CONSTRUCTS")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_directory_node_finds_directory_and_file_neighbors() {
         let (app, workspace_folder_path, project_path, _app_state) = setup_test_environment().await;
@@ -995,10 +1147,10 @@ mod tests {
 
         let mut imported_symbol_id: Option<String> = None;
         while let Some(row) = initial_result.next() {
-            // In the initial graph query, target_type sits at index 18
-            if let Ok(target_type) = row.get_string_value(18)
+            // In the initial graph query, target_type sits at index 20
+            if let Ok(target_type) = row.get_string_value(20)
                 && target_type == "ImportedSymbolNode"
-                && let Ok(id) = row.get_string_value(17)
+                && let Ok(id) = row.get_string_value(19)
             {
                 imported_symbol_id = Some(id);
                 break;
@@ -1,21 +1,43 @@
-use super::shared::{TypedGraphNode, create_error_response, create_typed_node, extract_node_data};
+use super::shared::{
+    ExplainMode, QueryExplanation, TypedGraphNode, create_error_response, create_typed_node,
+    explain_query, extract_node_data, query_error_status,
+};
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::decode_url_param;
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
+use database::graph::RelationshipType;
+use database::kuzu::database::KuzuDatabase;
 use database::querying::{
     QueryLibrary, QueryResult, QueryingService, service::DatabaseQueryingService,
 };
 use event_bus::types::project_info::{TSProjectInfo, to_ts_project_info};
+use futures_util::{StreamExt, stream};
+use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{error, info, warn};
 use ts_rs::TS;
 use urlencoding;
+use workspace_manager::ProjectInfo;
+
+/// Number of project databases searched at the same time for the wider scopes
+const SEARCH_FAN_OUT: usize = 4;
+const DEFAULT_SEARCH_LIMIT: i32 = 100;
+/// Upper bound of `limit`, which caps the results across all the searched projects
+const MAX_SEARCH_LIMIT: i32 = 500;
+/// Upper bound, in bytes, of the compiled `regex` search term, which rejects oversized
+/// patterns before they reach the database
+const MAX_REGEX_SIZE: usize = 1 << 20;
+/// The `regex` search term is matched by Kuzu, whose search is interrupted after this long
+const REGEX_SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Deserialize, Serialize, TS, Default, Clone, Debug)]
 #[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
@@ -29,13 +51,89 @@ pub struct GraphSearchPathRequest {
 pub struct GraphSearchQueryRequest {
     pub search_term: String,
     pub limit: Option<i32>,
+    /// `call_degree` lists the most called definitions first. Defaults to sorting by name.
+    pub sort_by: Option<String>,
+    /// `true` matches `search_term` as a case sensitive regular expression, e.g.
+    /// `^Foo::.*_test$`, instead of a case insensitive substring
+    pub regex: Option<bool>,
+    /// `project` searches the requested project only, `workspace` every project of its
+    /// workspace folder and `all` every registered project. Defaults to `project`.
+    pub scope: Option<String>,
+    /// Only matches the definitions with a relationship of this type to another definition,
+    /// e.g. `INSTANTIATES` for the classes that are constructed somewhere
+    pub relationship_type: Option<String>,
+    /// `true` returns the query and its plan alongside the matches, `only` returns them
+    /// without searching. The plan is the one of the requested project.
+    pub explain: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct GraphSearchHit {
+    pub project_path: String,
+    pub node: TypedGraphNode,
 }
 
 #[derive(Serialize, Deserialize, TS, Default, Debug)]
 #[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
 pub struct GraphSearchSuccessResponse {
+    /// Matches in the requested project
     pub nodes: Vec<TypedGraphNode>,
     pub project_info: TSProjectInfo,
+    /// Matches in every searched project, only filled for the `workspace` and `all` scopes
+    #[serde(default)]
+    pub hits: Vec<GraphSearchHit>,
+    /// Whether more matches were found than `limit` across the searched projects
+    #[serde(default)]
+    pub truncated: bool,
+    /// The query and its plan, only filled when `explain` is requested
+    #[serde(default)]
+    pub explain: Option<QueryExplanation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchScope {
+    Project,
+    Workspace,
+    All,
+}
+
+impl SearchScope {
+    fn parse(scope: Option<&str>) -> Option<Self> {
+        match scope {
+            None | Some("project") => Some(Self::Project),
+            Some("workspace") => Some(Self::Workspace),
+            Some("all") => Some(Self::All),
+            Some(_) => None,
+        }
+    }
+}
+
+enum ProjectSearchError {
+    Query(anyhow::Error),
+    Processing(String),
+}
+
+impl ProjectSearchError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Query(e) => query_error_status(e).0,
+            Self::Processing(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn into_api_error(self) -> ApiError {
+        match self {
+            Self::Query(e) => GraphSearchEndpoint::create_error_response(
+                query_error_status(&e).1,
+                format!("Failed to execute search query: {e}"),
+            ),
+            Self::Processing(e) => GraphSearchEndpoint::create_error_response(
+                "query_result_processing_failed",
+                format!("Failed to process search results: {e}"),
+            ),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, TS, Default, Debug)]
@@ -44,11 +142,11 @@ pub struct GraphSearchResponses {
     #[serde(rename = "200")]
     pub ok: Option<GraphSearchSuccessResponse>,
     #[serde(rename = "404")]
-    pub not_found: Option<StatusResponse>,
+    pub not_found: Option<ApiError>,
     #[serde(rename = "400")]
-    pub bad_request: Option<StatusResponse>,
+    pub bad_request: Option<ApiError>,
     #[serde(rename = "500")]
-    pub internal_server_error: Option<StatusResponse>,
+    pub internal_server_error: Option<ApiError>,
 }
 
 pub struct GraphSearchEndpointConfig;
@@ -78,11 +176,14 @@ impl GraphSearchEndpoint {
         GraphSearchSuccessResponse {
             nodes,
             project_info,
+            hits: Vec::new(),
+            truncated: false,
+            explain: None,
         }
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        create_error_response(status)
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        create_error_response(code, message)
     }
 }
 
@@ -103,13 +204,17 @@ pub async fn graph_search_handler(
     );
 
     let search_term = query_params.search_term.trim();
-    let limit = query_params.limit.unwrap_or(100);
+    let limit = query_params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
 
     if input_project_path.trim().is_empty() {
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphSearchEndpoint::create_error_response(
-                "empty_project_path".to_string(),
+                "empty_project_path",
+                "Project path must not be empty",
             )),
         )
             .into_response();
@@ -119,7 +224,8 @@ pub async fn graph_search_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphSearchEndpoint::create_error_response(
-                "empty_search_term".to_string(),
+                "empty_search_term",
+                "Search term must not be empty",
             )),
         )
             .into_response();
@@ -142,14 +248,97 @@ pub async fn graph_search_handler(
             return (
                 StatusCode::NOT_FOUND,
                 Json(GraphSearchEndpoint::create_error_response(
-                    "project_not_found".to_string(),
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
                 )),
             )
                 .into_response();
         }
     };
 
-    let query = QueryLibrary::get_search_nodes_query();
+    let Some(scope) = SearchScope::parse(query_params.scope.as_deref()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphSearchEndpoint::create_error_response(
+                "invalid_scope",
+                format!(
+                    "Unsupported scope value: {}",
+                    query_params.scope.unwrap_or_default()
+                ),
+            )),
+        )
+            .into_response();
+    };
+
+    let Some(explain_mode) = ExplainMode::parse(query_params.explain.as_deref()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphSearchEndpoint::create_error_response(
+                "invalid_explain",
+                format!(
+                    "Unsupported explain value: {}",
+                    query_params.explain.unwrap_or_default()
+                ),
+            )),
+        )
+            .into_response();
+    };
+
+    let regex = query_params.regex.unwrap_or(false);
+    if regex
+        && let Err(e) = RegexBuilder::new(search_term)
+            .size_limit(MAX_REGEX_SIZE)
+            .dfa_size_limit(MAX_REGEX_SIZE)
+            .build()
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GraphSearchEndpoint::create_error_response(
+                "invalid_regex",
+                format!("Invalid regular expression: {e}"),
+            )),
+        )
+            .into_response();
+    }
+
+    let relationship_type = match query_params.relationship_type.as_deref() {
+        None => None,
+        Some(name) => match RelationshipType::parse(name) {
+            Some(relationship_type) => Some(relationship_type),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(GraphSearchEndpoint::create_error_response(
+                        "invalid_relationship_type",
+                        format!("Unsupported relationship_type value: {name}"),
+                    )),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let query_timeout = regex.then_some(REGEX_SEARCH_TIMEOUT);
+    let sort_by_call_degree = query_params.sort_by.as_deref() == Some("call_degree");
+    let query = match (query_params.sort_by.as_deref(), regex) {
+        (None | Some("call_degree"), _) if relationship_type.is_some() => {
+            QueryLibrary::get_search_nodes_by_relationship_type_query(sort_by_call_degree, regex)
+        }
+        (None, false) => QueryLibrary::get_search_nodes_query(),
+        (None, true) => QueryLibrary::get_regex_search_nodes_query(),
+        (Some("call_degree"), false) => QueryLibrary::get_search_nodes_by_call_degree_query(),
+        (Some("call_degree"), true) => QueryLibrary::get_regex_search_nodes_by_call_degree_query(),
+        (Some(other), _) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(GraphSearchEndpoint::create_error_response(
+                    "invalid_sort_by",
+                    format!("Unsupported sort_by value: {other}"),
+                )),
+            )
+                .into_response();
+        }
+    };
 
     let mut query_params = serde_json::Map::new();
     query_params.insert(
@@ -157,55 +346,229 @@ pub async fn graph_search_handler(
         serde_json::Value::String(search_term.to_string()),
     );
     query_params.insert("limit".to_string(), serde_json::Value::Number(limit.into()));
+    if let Some(relationship_type) = relationship_type {
+        query_params.insert(
+            "relationship_type".to_string(),
+            serde_json::Value::String(relationship_type.as_string()),
+        );
+    }
+
+    let explanation = if explain_mode == ExplainMode::Off {
+        None
+    } else {
+        let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
+        match explain_query(
+            &query_service,
+            project_info.database_path.clone(),
+            &query.query,
+            &query_params,
+        ) {
+            Ok(explanation) => Some(explanation),
+            Err(e) => {
+                error!("Failed to explain search query: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(GraphSearchEndpoint::create_error_response(
+                        "query_explain_failed",
+                        format!("Failed to explain search query: {e}"),
+                    )),
+                )
+                    .into_response();
+            }
+        }
+    };
+    if explain_mode == ExplainMode::Only {
+        return (
+            StatusCode::OK,
+            Json(GraphSearchSuccessResponse {
+                explain: explanation,
+                ..GraphSearchEndpoint::create_success_response(
+                    Vec::new(),
+                    to_ts_project_info(&project_info),
+                )
+            }),
+        )
+            .into_response();
+    }
+
+    if scope != SearchScope::Project {
+        let projects = match scope {
+            SearchScope::Workspace => state
+                .workspace_manager
+                .list_projects_in_workspace(&input_workspace_folder_path),
+            _ => state.workspace_manager.list_all_projects(),
+        };
+        info!(
+            "Executing search query across {} projects, search_term=\"{}\", limit={}",
+            projects.len(),
+            search_term,
+            limit
+        );
+
+        let (hits, truncated) = search_projects(
+            &state.database,
+            projects,
+            query.query,
+            query_params,
+            limit as usize,
+            sort_by_call_degree,
+            query_timeout,
+        )
+        .await;
+        let nodes = hits
+            .iter()
+            .filter(|hit| hit.project_path == project_info.project_path)
+            .map(|hit| hit.node.clone())
+            .collect();
 
-    let query_service = DatabaseQueryingService::new(Arc::clone(&state.database));
+        return (
+            StatusCode::OK,
+            Json(GraphSearchSuccessResponse {
+                nodes,
+                project_info: to_ts_project_info(&project_info),
+                hits,
+                truncated,
+                explain: explanation,
+            }),
+        )
+            .into_response();
+    }
 
     info!(
         "Executing search query for project {} and workspace folder {}, search_term=\"{}\", limit={}",
         project_info.project_path, input_workspace_folder_path, search_term, limit
     );
-    let mut query_result = match query_service.execute_query(
+    let nodes = match search_project(
+        Arc::clone(&state.database),
         project_info.database_path.clone(),
         query.query,
         query_params,
+        query_timeout,
     ) {
-        Ok(result) => result,
-        Err(e) => {
-            error!("Failed to execute search query: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphSearchEndpoint::create_error_response(format!(
-                    "Failed to execute search query: {e}"
-                ))),
-            )
-                .into_response();
-        }
-    };
-
-    let nodes = match convert_query_result_to_nodes(&mut query_result) {
         Ok(nodes) => nodes,
         Err(e) => {
-            error!("Failed to convert query result to nodes: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphSearchEndpoint::create_error_response(format!(
-                    "Failed to process search results: {e}"
-                ))),
-            )
-                .into_response();
+            let status = e.status();
+            let error = e.into_api_error();
+            error!("{}", error.message);
+            return (status, Json(error)).into_response();
         }
     };
 
     (
         StatusCode::OK,
-        Json(GraphSearchEndpoint::create_success_response(
-            nodes,
-            to_ts_project_info(&project_info),
-        )),
+        Json(GraphSearchSuccessResponse {
+            explain: explanation,
+            ..GraphSearchEndpoint::create_success_response(nodes, to_ts_project_info(&project_info))
+        }),
     )
         .into_response()
 }
 
+fn search_project(
+    database: Arc<KuzuDatabase>,
+    database_path: PathBuf,
+    query: String,
+    query_params: serde_json::Map<String, serde_json::Value>,
+    query_timeout: Option<Duration>,
+) -> Result<Vec<TypedGraphNode>, ProjectSearchError> {
+    let mut query_service = DatabaseQueryingService::new(database);
+    if let Some(timeout) = query_timeout {
+        query_service = query_service.with_query_timeout(timeout);
+    }
+    let mut query_result = query_service
+        .execute_query(database_path, query, query_params)
+        .map_err(ProjectSearchError::Query)?;
+
+    convert_query_result_to_nodes(&mut query_result)
+        .map_err(|e| ProjectSearchError::Processing(e.to_string()))
+}
+
+/// Searches the databases of `projects`, at most [`SEARCH_FAN_OUT`] at a time, and merges
+/// the matches in the order of the search query. Projects without a database or whose
+/// search fails are skipped. Returns at most `limit` matches and whether more were found.
+async fn search_projects(
+    database: &Arc<KuzuDatabase>,
+    projects: Vec<ProjectInfo>,
+    query: String,
+    query_params: serde_json::Map<String, serde_json::Value>,
+    limit: usize,
+    sort_by_call_degree: bool,
+    query_timeout: Option<Duration>,
+) -> (Vec<GraphSearchHit>, bool) {
+    let searches = projects
+        .into_iter()
+        .filter(|project| project.database_path.exists())
+        .map(|project| {
+            let database = Arc::clone(database);
+            let query = query.clone();
+            let query_params = query_params.clone();
+            async move {
+                let database_path = project.database_path.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    search_project(database, database_path, query, query_params, query_timeout)
+                })
+                .await;
+                (project.project_path, result)
+            }
+        });
+
+    let results: Vec<_> = stream::iter(searches)
+        .buffer_unordered(SEARCH_FAN_OUT)
+        .collect()
+        .await;
+
+    let mut hits = Vec::new();
+    for (project_path, result) in results {
+        match result {
+            Ok(Ok(nodes)) => hits.extend(nodes.into_iter().map(|node| GraphSearchHit {
+                project_path: project_path.clone(),
+                node,
+            })),
+            Ok(Err(e)) => warn!(
+                "Skipping project {project_path} in search: {}",
+                e.into_api_error().message
+            ),
+            Err(e) => warn!("Skipping project {project_path} in search: {e}"),
+        }
+    }
+
+    // Each project's matches are already ordered, the stable sort keeps that order on ties
+    hits.sort_by(|a, b| compare_hits(a, b, sort_by_call_degree));
+    let truncated = hits.len() > limit;
+    hits.truncate(limit);
+
+    (hits, truncated)
+}
+
+fn compare_hits(a: &GraphSearchHit, b: &GraphSearchHit, sort_by_call_degree: bool) -> Ordering {
+    let (a_type, a_label, a_call_degree) = node_sort_key(&a.node);
+    let (b_type, b_label, b_call_degree) = node_sort_key(&b.node);
+
+    let by_call_degree = if sort_by_call_degree {
+        b_call_degree.cmp(&a_call_degree)
+    } else {
+        Ordering::Equal
+    };
+
+    by_call_degree
+        .then_with(|| a_type.cmp(b_type))
+        .then_with(|| a_label.cmp(b_label))
+        .then_with(|| a.project_path.cmp(&b.project_path))
+}
+
+fn node_sort_key(node: &TypedGraphNode) -> (&'static str, &str, i32) {
+    match node {
+        TypedGraphNode::DirectoryNode { label, .. } => ("DirectoryNode", label.as_str(), 0),
+        TypedGraphNode::FileNode { label, .. } => ("FileNode", label.as_str(), 0),
+        TypedGraphNode::DefinitionNode {
+            label, properties, ..
+        } => ("DefinitionNode", label.as_str(), properties.call_degree),
+        TypedGraphNode::ImportedSymbolNode { label, .. } => {
+            ("ImportedSymbolNode", label.as_str(), 0)
+        }
+    }
+}
+
 fn convert_query_result_to_nodes(
     query_result: &mut Box<dyn QueryResult>,
 ) -> Result<Vec<TypedGraphNode>, Box<dyn std::error::Error>> {
@@ -221,7 +584,7 @@ fn convert_query_result_to_nodes(
 
 #[cfg(test)]
 mod tests {
-    use crate::testing::{build_app_state, index_data};
+    use crate::testing::{build_app_state, index_data, load_fixture_graphs};
     use testing::repository::TestRepository;
 
     use super::*;
@@ -231,13 +594,20 @@ mod tests {
     use tempfile::TempDir;
 
     async fn create_test_app_with_indexed_data() -> (Router, AppState, TempDir) {
+        create_test_app_with_repositories(&["test-repo"]).await
+    }
+
+    async fn create_test_app_with_repositories(
+        repository_names: &[&str],
+    ) -> (Router, AppState, TempDir) {
         let temp_dir = TempDir::new().unwrap();
 
         let workspace_folder = temp_dir.path().join("test_workspace");
         std::fs::create_dir_all(&workspace_folder).unwrap();
 
-        let _repository =
-            TestRepository::new(&workspace_folder.join("test-repo"), Some("test-repo"));
+        for repository_name in repository_names {
+            TestRepository::new(&workspace_folder.join(repository_name), Some("test-repo"));
+        }
 
         let (app_state, temp_dir) =
             build_app_state(temp_dir, vec![workspace_folder], None).unwrap();
@@ -275,8 +645,8 @@ mod tests {
             .await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_project_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_project_path");
     }
 
     #[tokio::test]
@@ -289,8 +659,8 @@ mod tests {
             .await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_search_term");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_search_term");
     }
 
     #[tokio::test]
@@ -413,6 +783,43 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_graph_search_order_is_stable() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let projects = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+        let project_path = &projects[0].project_path;
+
+        let encoded_project_path = urlencoding::encode(project_path);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+
+        let url_string = format!(
+            "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=e&limit=50"
+        );
+
+        let first = server.get(&url_string).await;
+        first.assert_status(StatusCode::OK);
+        let first_nodes =
+            serde_json::to_value(first.json::<GraphSearchSuccessResponse>().nodes).unwrap();
+        assert!(
+            first_nodes.as_array().is_some_and(|nodes| nodes.len() > 1),
+            "Search term should match several nodes"
+        );
+
+        for _ in 0..3 {
+            let response = server.get(&url_string).await;
+            response.assert_status(StatusCode::OK);
+            let nodes =
+                serde_json::to_value(response.json::<GraphSearchSuccessResponse>().nodes).unwrap();
+            assert_eq!(nodes, first_nodes);
+        }
+    }
+
     #[tokio::test]
     async fn test_graph_search_case_insensitive() {
         let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
@@ -470,4 +877,295 @@ mod tests {
             assert!(body.nodes.len() <= 2, "Should respect limit parameter");
         }
     }
+
+    #[tokio::test]
+    async fn test_graph_search_sort_by_call_degree() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let projects = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+        let project_path = &projects[0].project_path;
+
+        let encoded_project_path = urlencoding::encode(project_path);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+
+        let response = server
+            .get(&format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=e&limit=50&sort_by=call_degree"
+            ))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+
+        let degrees = body
+            .nodes
+            .iter()
+            .map(|node| match node {
+                TypedGraphNode::DefinitionNode { properties, .. } => properties.call_degree,
+                _ => 0,
+            })
+            .collect::<Vec<_>>();
+        assert!(degrees.iter().any(|degree| *degree > 0));
+        assert!(degrees.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        let response = server
+            .get(&format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=e&sort_by=size"
+            ))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_sort_by");
+    }
+
+    #[tokio::test]
+    async fn test_graph_search_regex() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let projects = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+        let project_path = &projects[0].project_path;
+
+        let encoded_project_path = urlencoding::encode(project_path);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+        let encoded_pattern = urlencoding::encode("^find_by_.*e$");
+
+        let response = server
+            .get(&format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term={encoded_pattern}&regex=true"
+            ))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+
+        let definition_names = body
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                TypedGraphNode::DefinitionNode { label, .. } => Some(label.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(definition_names.contains(&"find_by_username"));
+        assert!(!definition_names.contains(&"find_by_email"));
+        for name in definition_names {
+            assert!(
+                name.starts_with("find_by_") && name.ends_with('e'),
+                "Unexpected match {name}"
+            );
+        }
+
+        let encoded_pattern = urlencoding::encode("find_(");
+        let response = server
+            .get(&format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term={encoded_pattern}&regex=true"
+            ))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_regex");
+        assert!(body.message.contains("unclosed group"), "{}", body.message);
+    }
+
+    #[tokio::test]
+    async fn test_graph_search_by_relationship_type() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let projects = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+        let project_path = &projects[0].project_path;
+
+        let encoded_project_path = urlencoding::encode(project_path);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+        let url = |relationship_type: &str| {
+            format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=token&relationship_type={relationship_type}"
+            )
+        };
+
+        // `Token.new(user_id)` in lib/authentication/tokens.rb, the file itself isn't matched
+        let response = server.get(&url("INSTANTIATES")).await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        assert!(
+            body.nodes
+                .iter()
+                .all(|node| node.node_type() == "DefinitionNode")
+        );
+        assert!(body.nodes.iter().any(|node| node.label() == "Token"));
+
+        let response = server.get(&url("CONSTRUCTS")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_relationship_type");
+    }
+
+    #[tokio::test]
+    async fn test_graph_search_on_fixture_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder = temp_dir.path().join("test_workspace");
+        TestRepository::new_minimal(&workspace_folder.join("fixture-repo"));
+        let (app_state, _temp_dir) =
+            build_app_state(temp_dir, vec![workspace_folder], None).unwrap();
+        load_fixture_graphs(&app_state);
+        let project = app_state.workspace_manager.list_all_projects().remove(0);
+        let app = Router::new()
+            .route(
+                "/graph/search/{workspace_folder_path}/{project_path}",
+                get(graph_search_handler),
+            )
+            .with_state(app_state.clone());
+        let server = TestServer::new(app).unwrap();
+
+        let response = server
+            .get(&format!(
+                "/graph/search/{}/{}?search_term=charge",
+                urlencoding::encode(&project.workspace_folder_path),
+                urlencoding::encode(&project.project_path)
+            ))
+            .await;
+
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        let fqns: Vec<&str> = body
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                TypedGraphNode::DefinitionNode { properties, .. } => Some(properties.fqn.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fqns, vec!["Billing::charge"]);
+    }
+
+    #[tokio::test]
+    async fn test_graph_search_explain() {
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let projects = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+        let project_path = &projects[0].project_path;
+
+        let encoded_project_path = urlencoding::encode(project_path);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+        let url = |explain: &str| {
+            format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=main&explain={explain}"
+            )
+        };
+
+        let response = server.get(&url("only")).await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        assert!(body.nodes.is_empty());
+        let explanation = body.explain.expect("Should return the query plan");
+        assert_eq!(
+            explanation.query,
+            QueryLibrary::get_search_nodes_query().query
+        );
+        assert!(explanation.params.contains("\"search_term\":\"main\""));
+        assert!(!explanation.plan.is_empty());
+
+        let response = server.get(&url("true")).await;
+        response.assert_status(StatusCode::OK);
+        assert!(
+            response
+                .json::<GraphSearchSuccessResponse>()
+                .explain
+                .is_some()
+        );
+
+        let response = server.get(&url("false")).await;
+        response.assert_status(StatusCode::OK);
+        assert!(
+            response
+                .json::<GraphSearchSuccessResponse>()
+                .explain
+                .is_none()
+        );
+
+        let response = server.get(&url("verbose")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_explain");
+    }
+
+    #[tokio::test]
+    async fn test_graph_search_across_workspace_projects() {
+        let (app, app_state, _temp_dir) =
+            create_test_app_with_repositories(&["service-a", "service-b"]).await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspaces = app_state.workspace_manager.list_workspace_folders();
+        let workspace_folder_path = &workspaces[0].workspace_folder_path;
+        let mut project_paths = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path)
+            .into_iter()
+            .map(|project| project.project_path)
+            .collect::<Vec<_>>();
+        project_paths.sort();
+        assert_eq!(project_paths.len(), 2);
+
+        let encoded_project_path = urlencoding::encode(&project_paths[0]);
+        let encoded_workspace_folder_path = urlencoding::encode(workspace_folder_path);
+        let url = |query: &str| {
+            format!(
+                "/graph/search/{encoded_workspace_folder_path}/{encoded_project_path}?search_term=main&{query}"
+            )
+        };
+
+        let response = server.get(&url("scope=project")).await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        assert!(!body.nodes.is_empty());
+        assert!(body.hits.is_empty());
+
+        let response = server.get(&url("scope=workspace&limit=500")).await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        let mut hit_projects = body
+            .hits
+            .iter()
+            .map(|hit| hit.project_path.clone())
+            .collect::<Vec<_>>();
+        hit_projects.sort();
+        hit_projects.dedup();
+        assert_eq!(hit_projects, project_paths);
+        assert_eq!(
+            body.nodes.len(),
+            body.hits
+                .iter()
+                .filter(|hit| hit.project_path == project_paths[0])
+                .count()
+        );
+        assert!(!body.truncated);
+
+        let response = server.get(&url("scope=all&limit=1")).await;
+        response.assert_status(StatusCode::OK);
+        let body = response.json::<GraphSearchSuccessResponse>();
+        assert_eq!(body.hits.len(), 1);
+        assert!(body.truncated);
+
+        let response = server.get(&url("scope=galaxy")).await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_scope");
+    }
 }
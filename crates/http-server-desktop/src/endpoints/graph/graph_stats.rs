@@ -6,6 +6,7 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::DatabaseError;
 use event_bus::types::project_info::TSProjectInfo;
 use event_bus::types::project_info::to_ts_project_info;
 use serde::{Deserialize, Serialize};
@@ -52,11 +53,11 @@ pub struct GraphStatsResponses {
     #[serde(rename = "200")]
     pub ok: Option<GraphStatsSuccessResponse>,
     #[serde(rename = "404")]
-    pub not_found: Option<crate::endpoints::shared::StatusResponse>,
+    pub not_found: Option<crate::endpoints::shared::ApiError>,
     #[serde(rename = "400")]
-    pub bad_request: Option<crate::endpoints::shared::StatusResponse>,
+    pub bad_request: Option<crate::endpoints::shared::ApiError>,
     #[serde(rename = "500")]
-    pub internal_server_error: Option<crate::endpoints::shared::StatusResponse>,
+    pub internal_server_error: Option<crate::endpoints::shared::ApiError>,
 }
 
 pub struct GraphStatsEndpointConfig;
@@ -95,8 +96,11 @@ impl GraphStatsEndpoint {
         }
     }
 
-    pub fn create_error_response(status: String) -> crate::endpoints::shared::StatusResponse {
-        create_error_response(status)
+    pub fn create_error_response(
+        code: &str,
+        message: impl Into<String>,
+    ) -> crate::endpoints::shared::ApiError {
+        create_error_response(code, message)
     }
 }
 
@@ -119,7 +123,8 @@ pub async fn graph_stats_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(GraphStatsEndpoint::create_error_response(
-                "empty_project_path".to_string(),
+                "empty_project_path",
+                "Project path must not be empty",
             )),
         )
             .into_response();
@@ -140,32 +145,65 @@ pub async fn graph_stats_handler(
             return (
                 StatusCode::NOT_FOUND,
                 Json(GraphStatsEndpoint::create_error_response(
-                    "project_not_found".to_string(),
+                    "project_not_found",
+                    "Project is not registered in the workspace folder",
                 )),
             )
                 .into_response();
         }
     };
 
-    let database = state
+    let database = match state
         .database
-        .get_or_create_database(project_info.database_path.to_str().unwrap(), None);
-    if database.is_none() {
-        error!(
-            "Failed to get database for project {} at {}",
-            project_info.project_path,
-            project_info.database_path.display()
-        );
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(GraphStatsEndpoint::create_error_response(
-                "database_not_found".to_string(),
-            )),
-        )
-            .into_response();
-    }
-
-    let database = database.unwrap();
+        .get_indexed_database(project_info.database_path.to_str().unwrap(), None)
+    {
+        Ok(database) => database,
+        Err(e @ DatabaseError::NotIndexed { .. }) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(GraphStatsEndpoint::create_error_response(
+                    "not_indexed",
+                    e.to_string(),
+                )),
+            )
+                .into_response();
+        }
+        Err(e @ DatabaseError::IncompatibleStorageVersion { .. }) => {
+            error!("{e}");
+            if let Err(e) = state.workspace_manager.mark_project_outdated(
+                &project_info.workspace_folder_path,
+                &project_info.project_path,
+            ) {
+                error!(
+                    "Failed to mark project {} as outdated: {e}",
+                    project_info.project_path
+                );
+            }
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GraphStatsEndpoint::create_error_response(
+                    "incompatible_storage_version",
+                    e.to_string(),
+                )),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!(
+                "Failed to get database for project {} at {}: {e}",
+                project_info.project_path,
+                project_info.database_path.display()
+            );
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GraphStatsEndpoint::create_error_response(
+                    "database_not_found",
+                    "Project database does not exist, index the project first",
+                )),
+            )
+                .into_response();
+        }
+    };
     let node_service = NodeDatabaseService::new(&database);
 
     let node_counts = match node_service.get_node_counts() {
@@ -174,9 +212,10 @@ pub async fn graph_stats_handler(
             error!("Failed to get node counts: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphStatsEndpoint::create_error_response(format!(
-                    "failed_to_get_node_counts: {e}"
-                ))),
+                Json(GraphStatsEndpoint::create_error_response(
+                    "failed_to_get_node_counts",
+                    format!("Failed to get node counts: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -188,9 +227,10 @@ pub async fn graph_stats_handler(
             error!("Failed to get relationship counts: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(GraphStatsEndpoint::create_error_response(format!(
-                    "failed_to_get_relationship_counts: {e}"
-                ))),
+                Json(GraphStatsEndpoint::create_error_response(
+                    "failed_to_get_relationship_counts",
+                    format!("Failed to get relationship counts: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -273,15 +313,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_graph_stats_empty_project_path() {
-        use crate::endpoints::shared::StatusResponse;
+        use crate::endpoints::shared::ApiError;
         let (app, _app_state, _temp_dir) = create_test_app_with_indexed_data().await;
         let server = TestServer::new(app).unwrap();
 
         let response = server.get("/graph/stats/workspace/%20").await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_project_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_project_path");
     }
 
     #[tokio::test]
@@ -335,4 +375,34 @@ mod tests {
                 + body.relationship_counts.definition_relationships
         );
     }
+
+    #[tokio::test]
+    async fn test_graph_stats_of_a_project_without_database() {
+        use crate::endpoints::shared::ApiError;
+        let (app, app_state, _temp_dir) = create_test_app_with_indexed_data().await;
+        let server = TestServer::new(app).unwrap();
+
+        let workspace_folder_path =
+            &app_state.workspace_manager.list_workspace_folders()[0].workspace_folder_path;
+        let project = app_state
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path)
+            .remove(0);
+        app_state
+            .database
+            .remove_database(&project.database_path.to_string_lossy())
+            .unwrap();
+
+        let url_string = format!(
+            "/graph/stats/{}/{}",
+            urlencoding::encode(workspace_folder_path),
+            urlencoding::encode(&project.project_path)
+        );
+        let response = server.get(&url_string).await;
+
+        response.assert_status(StatusCode::NOT_FOUND);
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "not_indexed");
+        assert!(!project.database_path.exists());
+    }
 }
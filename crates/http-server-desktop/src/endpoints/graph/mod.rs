@@ -1,3 +1,5 @@
+pub mod graph_definitions;
+pub mod graph_directory_counts;
 pub mod graph_initial;
 pub mod graph_neighbors;
 pub mod graph_search;
@@ -1,6 +1,10 @@
-use crate::endpoints::shared::StatusResponse;
-use database::querying::QueryResultRow;
+use crate::endpoints::shared::ApiError;
+use axum::http::StatusCode;
+use database::graph::{DefinitionCategory, split_fqn};
+use database::kuzu::types::DatabaseError;
+use database::querying::{QueryResultRow, QueryingService};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use ts_rs::TS;
 
 #[derive(Serialize, Deserialize, TS, Debug, Clone)]
@@ -26,11 +30,20 @@ pub struct FileNodeProperties {
 pub struct DefinitionNodeProperties {
     pub path: String,
     pub fqn: String,
+    /// Parts of `fqn` split by the separators of the definition's language, e.g.
+    /// `["Billing", "Invoice", "total"]` for the Ruby `Billing::Invoice#total`
+    pub fqn_parts: Vec<String>,
     pub definition_type: String,
+    /// Language independent category of `definition_type`, e.g. `class` or `method`
+    pub definition_category: String,
     pub start_line: i32,
     pub primary_start_byte: i64,
     pub primary_end_byte: i64,
     pub total_locations: i32,
+    /// Number of calls into and out of the definition
+    pub call_degree: i32,
+    /// Betweenness centrality in the call graph, 0 unless computed with `--call-betweenness`
+    pub call_betweenness: f64,
 }
 
 #[derive(Serialize, Deserialize, TS, Debug, Clone)]
@@ -85,6 +98,52 @@ pub struct GraphRelationship {
     pub relationship_type: String,
 }
 
+/// The query run by an endpoint, returned when it's called with `explain`
+#[derive(Serialize, Deserialize, TS, Default, Debug, Clone)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct QueryExplanation {
+    pub query: String,
+    /// Parameters bound to the query, as a JSON object
+    pub params: String,
+    /// Kuzu's physical plan of the query
+    pub plan: String,
+}
+
+/// Value of the `explain` query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainMode {
+    Off,
+    /// The plan is returned alongside the results
+    WithResults,
+    /// Only the plan is returned, the results aren't fetched
+    Only,
+}
+
+impl ExplainMode {
+    pub fn parse(explain: Option<&str>) -> Option<Self> {
+        match explain {
+            None | Some("false") => Some(Self::Off),
+            Some("true") => Some(Self::WithResults),
+            Some("only") => Some(Self::Only),
+            Some(_) => None,
+        }
+    }
+}
+
+pub fn explain_query(
+    query_service: &dyn QueryingService,
+    database_path: PathBuf,
+    query: &str,
+    params: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<QueryExplanation> {
+    let plan = query_service.explain_query(database_path, query.to_string(), params.clone())?;
+    Ok(QueryExplanation {
+        query: query.to_string(),
+        params: serde_json::to_string(params)?,
+        plan,
+    })
+}
+
 #[derive(Debug)]
 pub struct NodeData {
     pub id: String,
@@ -105,6 +164,8 @@ pub struct NodeData {
     pub import_type: String,
     pub import_path: String,
     pub import_alias: String,
+    pub call_degree: i64,
+    pub call_betweenness: f64,
 }
 
 pub fn extract_node_data(
@@ -132,6 +193,8 @@ pub fn extract_node_data(
         import_type: row.get_string_value(start_index + 14)?,
         import_path: row.get_string_value(start_index + 15)?,
         import_alias: row.get_string_value(start_index + 16)?,
+        call_degree: row.get_int_value(start_index + 17)?,
+        call_betweenness: row.get_float_value(start_index + 18)?,
     })
 }
 
@@ -164,13 +227,20 @@ pub fn create_typed_node(data: NodeData) -> Result<TypedGraphNode, Box<dyn std::
             node_id: data.node_id,
             label: data.name,
             properties: DefinitionNodeProperties {
+                fqn_parts: split_fqn(&data.fqn, &data.language),
                 path: data.path,
                 fqn: data.fqn,
+                definition_category: DefinitionCategory::from_definition_type(
+                    &data.definition_type,
+                )
+                .to_string(),
                 definition_type: data.definition_type,
                 start_line: data.start_line as i32,
                 primary_start_byte: data.primary_start_byte,
                 primary_end_byte: data.primary_end_byte,
                 total_locations: data.total_locations as i32,
+                call_degree: data.call_degree as i32,
+                call_betweenness: data.call_betweenness,
             },
         },
         "ImportedSymbolNode" => TypedGraphNode::ImportedSymbolNode {
@@ -211,7 +281,8 @@ macro_rules! decode_url_param {
                 return (
                     StatusCode::BAD_REQUEST,
                     Json($error_handler(
-                        concat!("invalid_", $param_name, "_encoding").to_string(),
+                        concat!("invalid_", $param_name, "_encoding"),
+                        concat!($param_name, " is not valid percent-encoded UTF-8"),
                     )),
                 )
                     .into_response();
@@ -220,6 +291,15 @@ macro_rules! decode_url_param {
     };
 }
 
-pub fn create_error_response(status: String) -> StatusResponse {
-    StatusResponse { status }
+pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::new(code, message)
+}
+
+/// Status and error code of a failed graph query. A project that is registered but has no
+/// database yet is reported as `not_indexed`, so clients can prompt to index it.
+pub fn query_error_status(error: &anyhow::Error) -> (StatusCode, &'static str) {
+    match error.downcast_ref::<DatabaseError>() {
+        Some(DatabaseError::NotIndexed { .. }) => (StatusCode::NOT_FOUND, "not_indexed"),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "query_execution_failed"),
+    }
 }
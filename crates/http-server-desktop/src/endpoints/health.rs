@@ -1,10 +1,62 @@
+use crate::watcher::WatcherHealth;
+use axum::Json;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use workspace_manager::WorkspaceManager;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HealthResponse {
+    /// `ok`, or `degraded` while some file watches are lost and being re-established
+    pub status: String,
+    pub watcher_degraded: bool,
+    pub lost_watches: Vec<String>,
+    /// Registered projects without a database yet, which must be indexed before they can be
+    /// queried
+    #[serde(default)]
+    pub not_indexed_projects: Vec<NotIndexedProject>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NotIndexedProject {
+    pub workspace_folder_path: String,
+    pub project_path: String,
+}
 
 /// Handler for the health check endpoint
-/// Returns a simple 200 OK status indicating the service is running
-pub async fn health_handler() -> impl IntoResponse {
-    StatusCode::OK
+/// Returns 200 OK while the service is running, along with the file watcher's health and the
+/// projects that still need to be indexed
+pub async fn health_handler(
+    watcher_health: Arc<WatcherHealth>,
+    workspace_manager: Arc<WorkspaceManager>,
+) -> impl IntoResponse {
+    let lost_watches: Vec<String> = watcher_health
+        .lost_watches()
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let watcher_degraded = !lost_watches.is_empty();
+
+    let mut not_indexed_projects: Vec<NotIndexedProject> = workspace_manager
+        .list_not_indexed_projects()
+        .into_iter()
+        .map(|(workspace_folder_path, project_path)| NotIndexedProject {
+            workspace_folder_path,
+            project_path,
+        })
+        .collect();
+    not_indexed_projects.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+    (
+        StatusCode::OK,
+        Json(HealthResponse {
+            status: if watcher_degraded { "degraded" } else { "ok" }.to_string(),
+            watcher_degraded,
+            lost_watches,
+            not_indexed_projects,
+        }),
+    )
 }
 
 #[cfg(test)]
@@ -12,24 +64,98 @@ mod tests {
     use super::*;
     use axum::{Router, routing::get};
     use axum_test::TestServer;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
 
-    async fn create_test_app() -> TestServer {
-        let app = Router::new().route("/health", get(health_handler));
+    async fn create_test_app(
+        watcher_health: Arc<WatcherHealth>,
+        workspace_manager: Arc<WorkspaceManager>,
+    ) -> TestServer {
+        let app = Router::new().route(
+            "/health",
+            get(move || health_handler(watcher_health.clone(), workspace_manager.clone())),
+        );
         TestServer::new(app).unwrap()
     }
 
+    fn create_workspace_manager(temp_dir: &TempDir) -> Arc<WorkspaceManager> {
+        Arc::new(WorkspaceManager::new_with_directory(temp_dir.path().join("data")).unwrap())
+    }
+
     #[tokio::test]
     async fn test_health_check() {
-        let server = create_test_app().await;
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_app(
+            Arc::new(WatcherHealth::default()),
+            create_workspace_manager(&temp_dir),
+        )
+        .await;
+
+        let response = server.get("/health").await;
+
+        response.assert_status_ok();
+        let body: HealthResponse = response.json();
+        assert_eq!(body.status, "ok");
+        assert!(!body.watcher_degraded);
+        assert!(body.not_indexed_projects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_degraded_watcher() {
+        let temp_dir = TempDir::new().unwrap();
+        let watcher_health = Arc::new(WatcherHealth::default());
+        watcher_health.mark_lost(std::path::Path::new("/workspace/project"));
+        let server = create_test_app(watcher_health, create_workspace_manager(&temp_dir)).await;
+
+        let response = server.get("/health").await;
+
+        response.assert_status_ok();
+        let body: HealthResponse = response.json();
+        assert_eq!(body.status, "degraded");
+        assert!(body.watcher_degraded);
+        assert_eq!(body.lost_watches, vec!["/workspace/project".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_not_indexed_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder = temp_dir.path().join("workspace");
+        std::fs::create_dir_all(&workspace_folder).unwrap();
+        TestRepository::new(&workspace_folder.join("test-repo"), Some("test-repo"));
+        let workspace_manager = create_workspace_manager(&temp_dir);
+        workspace_manager
+            .register_workspace_folder(&workspace_folder)
+            .unwrap();
+        let project = workspace_manager.list_all_projects().remove(0);
+        let server = create_test_app(
+            Arc::new(WatcherHealth::default()),
+            Arc::clone(&workspace_manager),
+        )
+        .await;
 
         let response = server.get("/health").await;
 
         response.assert_status_ok();
+        let body: HealthResponse = response.json();
+        // A project without a database doesn't make the server unhealthy
+        assert_eq!(body.status, "ok");
+        assert_eq!(
+            body.not_indexed_projects,
+            vec![NotIndexedProject {
+                workspace_folder_path: project.workspace_folder_path,
+                project_path: project.project_path,
+            }]
+        );
     }
 
     #[tokio::test]
     async fn test_health_check_performance() {
-        let server = create_test_app().await;
+        let temp_dir = TempDir::new().unwrap();
+        let server = create_test_app(
+            Arc::new(WatcherHealth::default()),
+            create_workspace_manager(&temp_dir),
+        )
+        .await;
 
         let start_time = std::time::Instant::now();
         let response = server.get("/health").await;
@@ -0,0 +1,100 @@
+use crate::AppState;
+use crate::contract::{EmptyRequest, EndpointConfigTypes};
+use crate::define_endpoint;
+use crate::queue::job::JobInfo;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Serialize, Deserialize, TS, Default)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct JobListResponses {
+    #[serde(rename = "200")]
+    pub ok: JobListSuccessResponse,
+}
+
+#[derive(Serialize, Deserialize, TS, Default)]
+#[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
+pub struct JobListSuccessResponse {
+    pub jobs: Vec<JobInfo>,
+}
+
+pub struct JobListEndpointConfig;
+
+impl EndpointConfigTypes for JobListEndpointConfig {
+    type PathRequest = EmptyRequest;
+    type BodyRequest = EmptyRequest;
+    type QueryRequest = EmptyRequest;
+    type Response = JobListResponses;
+}
+
+define_endpoint! {
+    JobListEndpoint,
+    JobListEndpointDef,
+    Get,
+    "/jobs",
+    ts_path_type = "\"/api/jobs\"",
+    config = JobListEndpointConfig,
+    export_to = "../../../packages/gkg/src/api.ts"
+}
+
+impl JobListEndpoint {
+    pub fn create_success_response(jobs: Vec<JobInfo>) -> JobListSuccessResponse {
+        JobListSuccessResponse { jobs }
+    }
+}
+
+/// Handler for the job list endpoint
+/// Returns the queued and running jobs along with the most recently finished ones
+pub async fn job_list_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let jobs = state.job_dispatcher.job_store.jobs();
+
+    (
+        StatusCode::OK,
+        Json(JobListEndpoint::create_success_response(jobs)),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::job::{Job, JobPriority, JobStatus};
+    use crate::testing::build_app_state;
+    use axum::{Router, routing::get};
+    use axum_test::TestServer;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_job_list_returns_tracked_jobs() {
+        let (state, _temp_dir) = build_app_state(TempDir::new().unwrap(), vec![], None).unwrap();
+        state.job_dispatcher.job_store.record(&JobInfo {
+            id: "job-1".to_string(),
+            job: Job::IndexWorkspaceFolder {
+                workspace_folder_path: "/workspace".to_string(),
+                priority: JobPriority::Normal,
+            },
+            created_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            completed_at: None,
+            status: JobStatus::Running,
+            error: None,
+        });
+
+        let app = Router::new()
+            .route(JobListEndpoint::PATH, get(job_list_handler))
+            .with_state(state);
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.get("/jobs").await;
+
+        response.assert_status_ok();
+        let body: JobListSuccessResponse = response.json();
+        assert_eq!(body.jobs.len(), 1);
+        assert_eq!(body.jobs[0].id, "job-1");
+        assert_eq!(body.jobs[0].status, JobStatus::Running);
+    }
+}
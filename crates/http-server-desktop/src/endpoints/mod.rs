@@ -2,6 +2,7 @@ pub mod events;
 pub mod graph;
 pub mod health;
 pub mod info;
+pub mod job_list;
 pub mod shared;
 pub mod workspace_delete;
 pub mod workspace_index;
@@ -1,8 +1,30 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use ts_rs::TS;
 
-#[derive(Serialize, Deserialize, TS, Default, Debug)]
+/// Error body returned by every API endpoint on failure.
+/// `code` is a stable, machine-readable identifier (e.g. `workspace_not_found`) that clients
+/// can branch on, while `message` is human-readable and may change between releases.
+#[derive(Serialize, Deserialize, TS, Default, Debug, Clone, PartialEq)]
 #[ts(export, export_to = "../../../packages/gkg/src/api.ts")]
-pub struct StatusResponse {
-    pub status: String,
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+    #[ts(type = "unknown")]
+    pub details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
 }
@@ -1,11 +1,12 @@
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use ts_rs::TS;
 
 #[derive(Deserialize, Serialize, TS, Default, Clone)]
@@ -27,11 +28,11 @@ pub struct WorkspaceDeleteResponses {
     #[serde(rename = "200")]
     pub ok: WorkspaceDeleteSuccessResponse,
     #[serde(rename = "400")]
-    pub bad_request: StatusResponse,
+    pub bad_request: ApiError,
     #[serde(rename = "404")]
-    pub not_found: StatusResponse,
+    pub not_found: ApiError,
     #[serde(rename = "500")]
-    pub internal_server_error: StatusResponse,
+    pub internal_server_error: ApiError,
 }
 
 pub struct WorkspaceDeleteEndpointConfig;
@@ -64,8 +65,8 @@ impl WorkspaceDeleteEndpoint {
         }
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        StatusResponse { status }
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        ApiError::new(code, message)
     }
 }
 
@@ -80,7 +81,8 @@ pub async fn delete_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(WorkspaceDeleteEndpoint::create_error_response(
-                "empty_workspace_path".to_string(),
+                "empty_workspace_path",
+                "Workspace folder path must not be empty",
             )),
         )
             .into_response();
@@ -94,9 +96,15 @@ pub async fn delete_handler(
     if workspace_info.is_none() {
         return (
             StatusCode::NOT_FOUND,
-            Json(WorkspaceDeleteEndpoint::create_error_response(
-                "workspace_not_found".to_string(),
-            )),
+            Json(
+                WorkspaceDeleteEndpoint::create_error_response(
+                    "workspace_not_found",
+                    "Workspace folder is not registered",
+                )
+                .with_details(serde_json::json!({
+                    "workspace_folder_path": payload.workspace_folder_path,
+                })),
+            ),
         )
             .into_response();
     }
@@ -120,21 +128,29 @@ pub async fn delete_handler(
         .workspace_manager
         .remove_workspace_folder(&payload.workspace_folder_path)
     {
-        Ok(removed) => (
-            StatusCode::OK,
-            Json(WorkspaceDeleteEndpoint::create_success_response(
-                payload.workspace_folder_path,
-                removed,
-            )),
-        )
-            .into_response(),
+        Ok(removed) => {
+            state
+                .watcher
+                .unwatch_folder(Path::new(&payload.workspace_folder_path))
+                .await;
+
+            (
+                StatusCode::OK,
+                Json(WorkspaceDeleteEndpoint::create_success_response(
+                    payload.workspace_folder_path,
+                    removed,
+                )),
+            )
+                .into_response()
+        }
         Err(e) => {
             tracing::error!("Failed to remove workspace folder: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(WorkspaceDeleteEndpoint::create_error_response(format!(
-                    "Failed to remove workspace: {e}"
-                ))),
+                Json(WorkspaceDeleteEndpoint::create_error_response(
+                    "workspace_removal_failed",
+                    format!("Failed to remove workspace: {e}"),
+                )),
             )
                 .into_response()
         }
@@ -188,11 +204,17 @@ mod tests {
             event_bus.clone(),
             database.clone(),
         ));
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
         let state = crate::AppState {
             workspace_manager,
             event_bus,
             job_dispatcher,
             database,
+            watcher,
         };
         let app = Router::new()
             .route("/workspace/delete", delete(delete_handler))
@@ -227,11 +249,17 @@ mod tests {
             event_bus.clone(),
             database.clone(),
         ));
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
         let state = crate::AppState {
             workspace_manager: workspace_manager.clone(),
             event_bus,
             job_dispatcher,
             database: database.clone(),
+            watcher,
         };
         let app = Router::new()
             .route("/workspace/delete", delete(delete_handler))
@@ -275,8 +303,8 @@ mod tests {
         let response = server.delete("/workspace/delete").json(&request_body).await;
 
         response.assert_status(StatusCode::NOT_FOUND);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "workspace_not_found");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "workspace_not_found");
     }
 
     #[tokio::test]
@@ -290,8 +318,8 @@ mod tests {
         let response = server.delete("/workspace/delete").json(&request_body).await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_workspace_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_workspace_path");
     }
 
     #[tokio::test]
@@ -305,8 +333,8 @@ mod tests {
         let response = server.delete("/workspace/delete").json(&request_body).await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "empty_workspace_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "empty_workspace_path");
     }
 
     #[tokio::test]
@@ -361,8 +389,8 @@ mod tests {
         // Second deletion should return not found
         let response = server.delete("/workspace/delete").json(&request_body).await;
         response.assert_status(StatusCode::NOT_FOUND);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "workspace_not_found");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "workspace_not_found");
     }
 
     #[tokio::test]
@@ -1,7 +1,7 @@
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
@@ -13,7 +13,7 @@ use event_bus::{
 use indexer::execution::{config::IndexingConfigBuilder, executor::IndexingExecutor};
 use num_cpus;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info};
 use ts_rs::TS;
@@ -32,9 +32,9 @@ pub struct WorkspaceIndexResponses {
     #[serde(rename = "200")]
     pub ok: TSWorkspaceFolderInfo,
     #[serde(rename = "400")]
-    pub bad_request: StatusResponse,
+    pub bad_request: ApiError,
     #[serde(rename = "500")]
-    pub internal_server_error: StatusResponse,
+    pub internal_server_error: ApiError,
 }
 
 pub struct WorkspaceIndexEndpointConfig;
@@ -61,8 +61,8 @@ impl WorkspaceIndexEndpoint {
         to_ts_workspace_folder_info(workspace_info)
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        StatusResponse { status }
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        ApiError::new(code, message)
     }
 }
 
@@ -75,9 +75,15 @@ pub async fn index_handler(
     if !workspace_folder_path.exists() {
         return (
             StatusCode::BAD_REQUEST,
-            Json(WorkspaceIndexEndpoint::create_error_response(
-                "invalid_workspace_path".to_string(),
-            )),
+            Json(
+                WorkspaceIndexEndpoint::create_error_response(
+                    "invalid_workspace_path",
+                    "Workspace folder path does not exist",
+                )
+                .with_details(serde_json::json!({
+                    "workspace_folder_path": payload.workspace_folder_path,
+                })),
+            ),
         )
             .into_response();
     }
@@ -90,9 +96,10 @@ pub async fn index_handler(
         Err(e) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(WorkspaceIndexEndpoint::create_error_response(format!(
-                    "Failed to get or register workspace: {e}"
-                ))),
+                Json(WorkspaceIndexEndpoint::create_error_response(
+                    "workspace_registration_failed",
+                    format!("Failed to get or register workspace: {e}"),
+                )),
             )
                 .into_response();
         }
@@ -102,7 +109,8 @@ pub async fn index_handler(
         return (
             StatusCode::BAD_REQUEST,
             Json(WorkspaceIndexEndpoint::create_error_response(
-                "no_projects_found_in_workspace".to_string(),
+                "no_projects_found_in_workspace",
+                "No projects were found in the workspace folder",
             )),
         )
             .into_response();
@@ -118,13 +126,19 @@ pub async fn index_handler(
         error!("Failed to dispatch indexing job: {}", e);
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(WorkspaceIndexEndpoint::create_error_response(format!(
-                "Failed to schedule indexing job: {e}"
-            ))),
+            Json(WorkspaceIndexEndpoint::create_error_response(
+                "job_dispatch_failed",
+                format!("Failed to schedule indexing job: {e}"),
+            )),
         )
             .into_response();
     }
 
+    state
+        .watcher
+        .watch_folder(Path::new(&workspace_info.workspace_folder_path))
+        .await;
+
     (
         StatusCode::OK,
         Json(WorkspaceIndexEndpoint::create_success_response(
@@ -227,11 +241,18 @@ mod tests {
             Arc::clone(&database),
         ));
 
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
+
         let state = crate::AppState {
             database: Arc::clone(&database),
             workspace_manager,
             event_bus,
             job_dispatcher,
+            watcher,
         };
         let app = Router::new()
             .route("/workspace/index", post(index_handler))
@@ -250,8 +271,12 @@ mod tests {
         let response = server.post("/workspace/index").json(&request_body).await;
 
         response.assert_status(StatusCode::BAD_REQUEST);
-        let body: StatusResponse = response.json();
-        assert_eq!(body.status, "invalid_workspace_path");
+        let body: ApiError = response.json();
+        assert_eq!(body.code, "invalid_workspace_path");
+        assert_eq!(
+            body.details.unwrap()["workspace_folder_path"],
+            "/nonexistent/path"
+        );
     }
 
     #[tokio::test]
@@ -267,8 +292,8 @@ mod tests {
 
         let status = response.status_code();
         if status == StatusCode::BAD_REQUEST {
-            let body: StatusResponse = response.json();
-            assert_eq!(body.status, "no_projects_found_in_workspace");
+            let body: ApiError = response.json();
+            assert_eq!(body.code, "no_projects_found_in_workspace");
         } else {
             panic!("Expected BAD_REQUEST but got: {status}");
         }
@@ -1,7 +1,7 @@
 use crate::AppState;
 use crate::contract::{EmptyRequest, EndpointConfigTypes};
 use crate::define_endpoint;
-use crate::endpoints::shared::StatusResponse;
+use crate::endpoints::shared::ApiError;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
@@ -18,7 +18,7 @@ pub struct WorkspaceListResponses {
     #[serde(rename = "200")]
     pub ok: WorkspaceListSuccessResponse,
     #[serde(rename = "500")]
-    pub internal_server_error: StatusResponse,
+    pub internal_server_error: ApiError,
 }
 
 #[derive(Serialize, Deserialize, TS, Default)]
@@ -60,8 +60,8 @@ impl WorkspaceListEndpoint {
         WorkspaceListSuccessResponse { workspaces }
     }
 
-    pub fn create_error_response(status: String) -> StatusResponse {
-        StatusResponse { status }
+    pub fn create_error_response(code: &str, message: impl Into<String>) -> ApiError {
+        ApiError::new(code, message)
     }
 }
 
@@ -137,11 +137,18 @@ mod tests {
         ));
         let database = Arc::new(KuzuDatabase::new());
 
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
+
         let state = AppState {
             database,
             workspace_manager,
             event_bus,
             job_dispatcher,
+            watcher,
         };
 
         let app = Router::new()
@@ -179,11 +186,17 @@ mod tests {
             database.clone(),
         ));
         let database = Arc::new(KuzuDatabase::new());
+        let watcher = Arc::new(crate::watcher::Watcher::new(
+            workspace_manager.clone(),
+            job_dispatcher.clone(),
+            None,
+        ));
         let state = AppState {
             database,
             workspace_manager: Arc::clone(&workspace_manager),
             event_bus,
             job_dispatcher,
+            watcher,
         };
         let app = Router::new()
             .route("/workspace/list", get(workspace_list_handler))
@@ -1,17 +1,24 @@
 pub mod api;
+pub mod config;
 pub mod contract;
 pub mod endpoints;
 pub mod queue;
+pub mod replay;
 pub mod watcher;
 
 #[cfg(test)]
 pub mod testing;
 
 use crate::{
+    config::ServerConfig,
     contract::EndpointContract,
     endpoints::{
         events::{EventsEndpoint, events_handler},
         graph::{
+            graph_definitions::{GraphDefinitionsEndpoint, graph_definitions_handler},
+            graph_directory_counts::{
+                GraphDirectoryCountsEndpoint, graph_directory_counts_handler,
+            },
             graph_initial::{GraphInitialEndpoint, graph_initial_handler},
             graph_neighbors::{GraphNeighborsEndpoint, graph_neighbors_handler},
             graph_search::{GraphSearchEndpoint, graph_search_handler},
@@ -19,24 +26,32 @@ use crate::{
         },
         health::health_handler,
         info::{InfoEndpoint, info_handler},
+        job_list::{JobListEndpoint, job_list_handler},
         workspace_delete::{WorkspaceDeleteEndpoint, delete_handler},
         workspace_index::{WorkspaceIndexEndpoint, index_handler},
         workspace_list::{WorkspaceListEndpoint, workspace_list_handler},
     },
-    queue::dispatch::JobDispatcher,
+    queue::{
+        dispatch::JobDispatcher,
+        job::{Job, JobPriority},
+        store::JobStore,
+    },
     watcher::Watcher,
 };
 
 use anyhow::Result;
-use axum::http::HeaderValue;
+use axum::http::{HeaderMap, HeaderValue};
 use axum::{
     Router,
+    extract::{DefaultBodyLimit, State},
     routing::{delete, get, post},
 };
 use axum_embed::ServeEmbed;
 use database::querying::service::DatabaseQueryingService;
+use database::schema::manager::SCHEMA_VERSION;
 use database::{kuzu::database::KuzuDatabase, querying::QueryingService};
-use event_bus::EventBus;
+use event_bus::{EventBus, EventLog};
+use mcp::indexing_jobs::IndexingJobQueue;
 use mcp::{configuration::McpConfiguration, http::mcp_http_service, sse::mcp_sse_router};
 use rust_embed::Embed;
 use std::net::{SocketAddr, TcpListener};
@@ -44,7 +59,7 @@ use std::sync::Arc;
 use tokio::signal;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use workspace_manager::WorkspaceManager;
 
 #[derive(Clone)]
@@ -53,6 +68,7 @@ pub struct AppState {
     pub workspace_manager: Arc<WorkspaceManager>,
     pub event_bus: Arc<EventBus>,
     pub job_dispatcher: Arc<JobDispatcher>,
+    pub watcher: Arc<Watcher>,
 }
 
 #[cfg(feature = "no-frontend")]
@@ -74,27 +90,48 @@ pub async fn run(
     workspace_manager: Arc<WorkspaceManager>,
     event_bus: Arc<EventBus>,
     mcp_configuration: Arc<McpConfiguration>,
+    config: ServerConfig,
 ) -> Result<()> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    let cors_layer = CorsLayer::new().allow_origin(tower_http::cors::AllowOrigin::predicate(
-        |origin: &HeaderValue, _| {
-            if let Ok(origin_str) = origin.to_str()
-                && let Ok(uri) = origin_str.parse::<http::Uri>()
-            {
-                return uri.host() == Some("localhost");
-            }
-            false
-        },
-    ));
+    let cors_layer = localhost_cors_layer();
 
-    let job_dispatcher = Arc::new(JobDispatcher::new(
-        workspace_manager.clone(),
-        event_bus.clone(),
-        Arc::clone(&database),
-    ));
+    // Started first, so the log also has the events of the jobs restored below
+    let event_log = EventLog::start_optional(&event_bus, config.event_log.as_deref())?;
 
-    let query_service: Arc<dyn QueryingService> =
-        Arc::new(DatabaseQueryingService::new(Arc::clone(&database)));
+    let job_store = Arc::new(JobStore::open(workspace_manager.job_queue_path()));
+    let job_dispatcher = Arc::new(
+        JobDispatcher::with_scheduling(
+            workspace_manager.clone(),
+            event_bus.clone(),
+            Arc::clone(&database),
+            config.max_concurrent_indexing_jobs,
+            config.scheduling_policy,
+        )
+        .with_job_store(job_store),
+    );
+    match job_dispatcher.restore_persisted_jobs().await {
+        Ok(0) => {}
+        Ok(restored) => info!("Restored {} unfinished jobs from a previous run", restored),
+        Err(e) => error!("Failed to restore persisted jobs: {}", e),
+    }
+    handle_outdated_projects(&workspace_manager, &job_dispatcher, &config).await;
+    match workspace_manager.flag_stale_projects() {
+        Ok(stale_projects) if !stale_projects.is_empty() => info!(
+            "{} project(s) changed since they were last indexed",
+            stale_projects.len()
+        ),
+        Ok(_) => {}
+        Err(e) => error!("Failed to check projects for changes since indexing: {e}"),
+    }
+
+    let mut database_query_service = DatabaseQueryingService::new(Arc::clone(&database));
+    if let Some(query_cache) = config.query_cache {
+        database_query_service = database_query_service.with_cache(query_cache);
+    }
+    if let Some(connection_pool) = config.connection_pool {
+        database_query_service = database_query_service.with_connection_pool(connection_pool);
+    }
+    let query_service: Arc<dyn QueryingService> = Arc::new(database_query_service);
 
     let watcher = Arc::new(Watcher::new(
         workspace_manager.clone(),
@@ -105,11 +142,14 @@ pub async fn run(
         watcher.start().await;
     }
 
+    let mcp_job_queue: Arc<dyn IndexingJobQueue> = job_dispatcher.clone();
+
     let state = AppState {
         database: Arc::clone(&database),
         workspace_manager: workspace_manager.clone(),
         event_bus: Arc::clone(&event_bus),
         job_dispatcher,
+        watcher: Arc::clone(&watcher),
     };
 
     let serve_assets = ServeEmbed::<Assets>::new();
@@ -120,6 +160,7 @@ pub async fn run(
         Arc::clone(&database),
         Arc::clone(&event_bus),
         Arc::clone(&mcp_configuration),
+        Arc::clone(&mcp_job_queue),
     );
     let (mcp_sse_router, mcp_sse_cancellation_token) = mcp_sse_router(
         addr,
@@ -128,28 +169,20 @@ pub async fn run(
         Arc::clone(&database),
         Arc::clone(&event_bus),
         Arc::clone(&mcp_configuration),
+        Arc::clone(&mcp_job_queue),
     );
 
-    let api_router = Router::new()
+    let api_router = api_router(state, port, &config);
+
+    let app = Router::new()
         .route(
-            InfoEndpoint::PATH,
+            "/health",
             get({
-                let shared_port = port;
-                move || info_handler(shared_port)
+                let watcher_health = Arc::clone(&watcher.health);
+                let workspace_manager = Arc::clone(&workspace_manager);
+                move || health_handler(Arc::clone(&watcher_health), Arc::clone(&workspace_manager))
             }),
         )
-        .route(WorkspaceIndexEndpoint::PATH, post(index_handler))
-        .route(WorkspaceDeleteEndpoint::PATH, delete(delete_handler))
-        .route(EventsEndpoint::PATH, get(events_handler))
-        .route(WorkspaceListEndpoint::PATH, get(workspace_list_handler))
-        .route(GraphInitialEndpoint::PATH, get(graph_initial_handler))
-        .route(GraphNeighborsEndpoint::PATH, get(graph_neighbors_handler))
-        .route(GraphSearchEndpoint::PATH, get(graph_search_handler))
-        .route(GraphStatsEndpoint::PATH, get(graph_stats_handler))
-        .with_state(state);
-
-    let app = Router::new()
-        .route("/health", get(health_handler))
         .nest("/api", api_router)
         .nest_service("/mcp", mcp_http_router)
         .nest_service("/mcp/sse", mcp_sse_router)
@@ -168,12 +201,124 @@ pub async fn run(
     // Cancel MCP SSE server
     mcp_sse_cancellation_token.cancel();
 
+    if let Some(event_log) = event_log {
+        match event_log.finish().await {
+            Ok(written) => info!("Wrote {written} events to the event log"),
+            Err(e) => error!("Failed to write the event log: {e}"),
+        }
+    }
+
     // Log shutdown completion
     info!("HTTP server shut down gracefully");
 
     result.map_err(Into::into)
 }
 
+/// Only accepts cross-origin requests from localhost, e.g. the frontend's dev server
+fn localhost_cors_layer() -> CorsLayer {
+    CorsLayer::new().allow_origin(tower_http::cors::AllowOrigin::predicate(
+        |origin: &HeaderValue, _| {
+            if let Ok(origin_str) = origin.to_str()
+                && let Ok(uri) = origin_str.parse::<http::Uri>()
+            {
+                return uri.host() == Some("localhost");
+            }
+            false
+        },
+    ))
+}
+
+/// Flags the projects indexed with an older graph schema, and queues a full reindex of them
+/// when `config.reindex_outdated_projects` is set
+async fn handle_outdated_projects(
+    workspace_manager: &WorkspaceManager,
+    job_dispatcher: &JobDispatcher,
+    config: &ServerConfig,
+) {
+    let outdated_projects = match workspace_manager.flag_outdated_projects(SCHEMA_VERSION) {
+        Ok(outdated_projects) => outdated_projects,
+        Err(e) => {
+            error!("Failed to check projects for an outdated graph schema: {e}");
+            return;
+        }
+    };
+    if outdated_projects.is_empty() {
+        return;
+    }
+
+    if !config.reindex_outdated_projects {
+        warn!(
+            "{} project(s) were indexed with an older graph schema and may return wrong results until reindexed. Run `gkg index` or start the server with --reindex-outdated",
+            outdated_projects.len()
+        );
+        return;
+    }
+
+    for (workspace_folder_path, project_folder_path) in outdated_projects {
+        info!("Queueing a reindex of outdated project: {project_folder_path}");
+        if let Err(e) = job_dispatcher
+            .dispatch(Job::IndexProjectFolder {
+                workspace_folder_path,
+                project_folder_path,
+                priority: JobPriority::Low,
+            })
+            .await
+        {
+            error!("Failed to queue the reindex of an outdated project: {e}");
+        }
+    }
+}
+
+/// Builds the `/api` router. Every route shares the default request-body limit from `config`,
+/// and routes that accept a body may raise or lower it through `config.body_limit_overrides`.
+pub fn api_router(state: AppState, port: u16, config: &ServerConfig) -> Router {
+    Router::new()
+        .route(
+            InfoEndpoint::PATH,
+            get({
+                let shared_port = port;
+                move || info_handler(shared_port)
+            }),
+        )
+        .route(
+            WorkspaceIndexEndpoint::PATH,
+            post(index_handler).layer(DefaultBodyLimit::max(
+                config.body_limit_for(WorkspaceIndexEndpoint::PATH),
+            )),
+        )
+        .route(
+            WorkspaceDeleteEndpoint::PATH,
+            delete(delete_handler).layer(DefaultBodyLimit::max(
+                config.body_limit_for(WorkspaceDeleteEndpoint::PATH),
+            )),
+        )
+        .route(
+            EventsEndpoint::PATH,
+            get({
+                let keep_alive_interval = config.sse_keep_alive_interval;
+                move |state: State<AppState>, headers: HeaderMap| {
+                    events_handler(state, headers, keep_alive_interval)
+                }
+            }),
+        )
+        .route(WorkspaceListEndpoint::PATH, get(workspace_list_handler))
+        .route(JobListEndpoint::PATH, get(job_list_handler))
+        .route(GraphInitialEndpoint::PATH, get(graph_initial_handler))
+        .route(GraphNeighborsEndpoint::PATH, get(graph_neighbors_handler))
+        .route(GraphSearchEndpoint::PATH, get(graph_search_handler))
+        .route(GraphStatsEndpoint::PATH, get(graph_stats_handler))
+        .route(
+            GraphDefinitionsEndpoint::PATH,
+            get(graph_definitions_handler),
+        )
+        .route(
+            GraphDirectoryCountsEndpoint::PATH,
+            get(graph_directory_counts_handler),
+        )
+        .layer(DefaultBodyLimit::max(config.max_request_body_bytes))
+        .with_state(state)
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -224,3 +369,61 @@ pub fn find_unused_port() -> Result<u16> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoints::workspace_index::WorkspaceIndexBodyRequest;
+    use crate::testing::build_app_state;
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use tempfile::TempDir;
+
+    fn oversized_index_request() -> WorkspaceIndexBodyRequest {
+        WorkspaceIndexBodyRequest {
+            workspace_folder_path: "a".repeat(1024),
+        }
+    }
+
+    fn create_test_server(config: &ServerConfig) -> (TestServer, TempDir) {
+        let (state, temp_dir) = build_app_state(TempDir::new().unwrap(), vec![], None).unwrap();
+        let app = api_router(state, 0, config);
+        (TestServer::new(app).unwrap(), temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let config = ServerConfig {
+            max_request_body_bytes: 256,
+            ..Default::default()
+        };
+        let (server, _temp_dir) = create_test_server(&config);
+
+        let response = server
+            .post(WorkspaceIndexEndpoint::PATH)
+            .json(&oversized_index_request())
+            .await;
+
+        response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_override_applies_per_route() {
+        let mut config = ServerConfig {
+            max_request_body_bytes: 256,
+            ..Default::default()
+        };
+        config
+            .body_limit_overrides
+            .insert(WorkspaceIndexEndpoint::PATH.to_string(), 64 * 1024);
+        let (server, _temp_dir) = create_test_server(&config);
+
+        let response = server
+            .post(WorkspaceIndexEndpoint::PATH)
+            .json(&oversized_index_request())
+            .await;
+
+        // The body is accepted and reaches the handler, which rejects the bogus path.
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
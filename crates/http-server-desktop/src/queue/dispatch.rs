@@ -9,6 +9,7 @@ use chrono::Utc;
 use dashmap::DashMap;
 use database::kuzu::database::KuzuDatabase;
 use event_bus::EventBus;
+use mcp::indexing_jobs::{IndexingJobQueue, IndexingJobState, IndexingJobStatus};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -18,6 +19,8 @@ use workspace_manager::WorkspaceManager;
 
 use crate::queue::{
     job::{Job, JobInfo, JobPriority, JobStatus},
+    scheduler::{IndexingScheduler, SchedulingPolicy},
+    store::JobStore,
     worker::{WorkerMessage, WorkspaceWorker},
 };
 
@@ -31,6 +34,12 @@ pub struct JobDispatcher {
     pub event_bus: Arc<EventBus>,
     pub database: Arc<KuzuDatabase>,
     pub worker_cancellation_tokens: Arc<DashMap<String, CancellationToken>>,
+    /// Shared by all workers; a worker holds a slot while it processes a job, which caps
+    /// how many workspaces are indexed at the same time.
+    pub scheduler: Arc<IndexingScheduler>,
+    /// Tracks job statuses; when backed by a file, unfinished jobs are resumed after a
+    /// restart.
+    pub job_store: Arc<JobStore>,
 }
 
 impl JobDispatcher {
@@ -41,13 +50,67 @@ impl JobDispatcher {
         event_bus: Arc<EventBus>,
         database: Arc<KuzuDatabase>,
     ) -> Self {
+        Self::with_scheduling(
+            workspace_manager,
+            event_bus,
+            database,
+            None,
+            SchedulingPolicy::default(),
+        )
+    }
+
+    /// Like [`JobDispatcher::new`], but at most `max_concurrent_jobs` workspaces are
+    /// processed at once; jobs for other workspaces wait in their queues until a slot
+    /// frees up and `policy` decides which of them goes next. `None` leaves the number of
+    /// concurrent workspaces unbounded.
+    pub fn with_scheduling(
+        workspace_manager: Arc<WorkspaceManager>,
+        event_bus: Arc<EventBus>,
+        database: Arc<KuzuDatabase>,
+        max_concurrent_jobs: Option<usize>,
+        policy: SchedulingPolicy,
+    ) -> Self {
+        let scheduler = match max_concurrent_jobs {
+            Some(max_concurrent_jobs) => IndexingScheduler::new(max_concurrent_jobs, policy),
+            None => IndexingScheduler::unbounded(),
+        };
+
         Self {
             workspace_queues: Arc::new(DashMap::new()),
             workspace_manager,
             event_bus,
             database,
             worker_cancellation_tokens: Arc::new(DashMap::new()),
+            scheduler: Arc::new(scheduler),
+            job_store: Arc::new(JobStore::in_memory()),
+        }
+    }
+
+    /// Tracks jobs in `job_store` instead of the default in-memory store.
+    pub fn with_job_store(mut self, job_store: Arc<JobStore>) -> Self {
+        self.job_store = job_store;
+        self
+    }
+
+    /// Re-dispatches the jobs a previous run of the server left unfinished, keeping their
+    /// IDs. Jobs that were running when the server stopped start over from the beginning.
+    ///
+    /// Returns the number of restored jobs.
+    pub async fn restore_persisted_jobs(&self) -> Result<usize> {
+        let jobs = self.job_store.unfinished_jobs();
+        for mut job_info in jobs.iter().cloned() {
+            info!(
+                "Restoring persisted job {} ({}) for workspace {}",
+                job_info.id,
+                job_info.job.job_type(),
+                job_info.job.workspace_path()
+            );
+            job_info.status = JobStatus::Pending;
+            job_info.started_at = None;
+            self.enqueue(job_info).await?;
         }
+
+        Ok(jobs.len())
     }
 
     /// Dispatches a job to the appropriate workspace queue.
@@ -89,12 +152,7 @@ impl JobDispatcher {
                 .await?;
         }
 
-        let sender = self.get_or_create_workspace_queue(&workspace_path).await?;
-
-        sender
-            .send(WorkerMessage::Job(job_info))
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send job to workspace queue: {}", e))?;
+        self.enqueue(job_info).await?;
 
         info!(
             "Successfully dispatched job {} for workspace {}",
@@ -103,6 +161,25 @@ impl JobDispatcher {
         Ok(job_id)
     }
 
+    async fn enqueue(&self, job_info: JobInfo) -> Result<()> {
+        let sender = self
+            .get_or_create_workspace_queue(job_info.job.workspace_path())
+            .await?;
+
+        self.job_store.record(&job_info);
+
+        let job_id = job_info.id.clone();
+        if let Err(e) = sender.send(WorkerMessage::Job(job_info)).await {
+            self.job_store.remove(&job_id);
+            return Err(anyhow::anyhow!(
+                "Failed to send job to workspace queue: {}",
+                e
+            ));
+        }
+
+        Ok(())
+    }
+
     /// If a queue already exists for the workspace, returns the existing sender.
     /// Otherwise, creates a new mpsc channel, spawns a WorkspaceWorker to process jobs,
     /// and sets up automatic cleanup when the worker shuts down.
@@ -128,6 +205,8 @@ impl JobDispatcher {
             Arc::clone(&self.workspace_manager),
             Arc::clone(&self.event_bus),
             Arc::clone(&self.database),
+            Arc::clone(&self.scheduler),
+            Arc::clone(&self.job_store),
             cancellation_token.clone(),
         );
 
@@ -155,6 +234,14 @@ impl JobDispatcher {
         workspace_path: &str,
         job_type: &str,
     ) -> Result<()> {
+        let cancelled_count = self.job_store.cancel_pending(workspace_path, job_type);
+        if cancelled_count > 0 {
+            info!(
+                "Cancelled {} pending {} jobs for workspace {}",
+                cancelled_count, job_type, workspace_path
+            );
+        }
+
         if let Some(sender_entry) = self.workspace_queues.get(workspace_path) {
             let sender = sender_entry.clone();
             if let Err(e) = sender
@@ -176,6 +263,43 @@ impl JobDispatcher {
     }
 }
 
+/// Lets the `index_project` MCP tool run project indexing through the job queue and the
+/// `get_index_status` tool poll the resulting jobs.
+#[async_trait::async_trait]
+impl IndexingJobQueue for JobDispatcher {
+    async fn dispatch_project_indexing(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+    ) -> Result<String> {
+        self.dispatch(Job::IndexProjectFolder {
+            workspace_folder_path: workspace_folder_path.to_string(),
+            project_folder_path: project_path.to_string(),
+            priority: JobPriority::Normal,
+        })
+        .await
+    }
+
+    fn indexing_job_status(&self, job_id: &str) -> Option<IndexingJobStatus> {
+        let job_info = self.job_store.get(job_id)?;
+        let state = match job_info.status {
+            JobStatus::Pending => IndexingJobState::Pending,
+            JobStatus::Running => IndexingJobState::Running,
+            JobStatus::Completed => IndexingJobState::Completed,
+            JobStatus::Failed => IndexingJobState::Failed,
+            JobStatus::Cancelled => IndexingJobState::Cancelled,
+        };
+
+        Some(IndexingJobStatus {
+            job_id: job_info.id,
+            state,
+            started_at: job_info.started_at.map(|time| time.to_rfc3339()),
+            completed_at: job_info.completed_at.map(|time| time.to_rfc3339()),
+            error: job_info.error,
+        })
+    }
+}
+
 impl Drop for JobDispatcher {
     /// The Drop implementation cancels all active workers and clears internal state.
     /// While this is a synchronous operation (Drop trait cannot be async), the
@@ -234,6 +358,38 @@ mod tests {
         assert_eq!(dispatcher.workspace_queues.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_scheduling_sizes_indexing_slots() {
+        let (workspace_manager, event_bus, database, _temp_dir) = create_test_setup();
+
+        let dispatcher = JobDispatcher::with_scheduling(
+            Arc::clone(&workspace_manager),
+            Arc::clone(&event_bus),
+            Arc::clone(&database),
+            Some(2),
+            SchedulingPolicy::ShortestJobFirst,
+        );
+        assert_eq!(dispatcher.scheduler.available_slots(), 2);
+        assert_eq!(
+            dispatcher.scheduler.policy(),
+            SchedulingPolicy::ShortestJobFirst
+        );
+
+        // A limit of zero would never run anything, so at least one slot is kept
+        let dispatcher = JobDispatcher::with_scheduling(
+            Arc::clone(&workspace_manager),
+            Arc::clone(&event_bus),
+            Arc::clone(&database),
+            Some(0),
+            SchedulingPolicy::Fifo,
+        );
+        assert_eq!(dispatcher.scheduler.available_slots(), 1);
+
+        let dispatcher = JobDispatcher::new(workspace_manager, event_bus, database);
+        assert_eq!(dispatcher.scheduler.available_slots(), usize::MAX);
+        assert_eq!(dispatcher.scheduler.policy(), SchedulingPolicy::Fifo);
+    }
+
     #[tokio::test]
     async fn test_dispatch_creates_worker() {
         let (workspace_manager, event_bus, database, _temp_dir) = create_test_setup();
@@ -400,6 +556,35 @@ mod tests {
         assert!(!sender.is_closed());
     }
 
+    #[tokio::test]
+    async fn test_restore_persisted_jobs() {
+        let (workspace_manager, event_bus, database, temp_dir) = create_test_setup();
+        let job_store_path = temp_dir.path().join("gkg_job_queue.json");
+
+        let interrupted_job = JobInfo {
+            id: "interrupted-job".to_string(),
+            job: Job::IndexWorkspaceFolder {
+                workspace_folder_path: "/test/workspace".to_string(),
+                priority: JobPriority::Normal,
+            },
+            created_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            completed_at: None,
+            status: JobStatus::Running,
+            error: None,
+        };
+        JobStore::open(&job_store_path).record(&interrupted_job);
+
+        let dispatcher = JobDispatcher::new(workspace_manager, event_bus, database)
+            .with_job_store(Arc::new(JobStore::open(&job_store_path)));
+
+        let restored = dispatcher.restore_persisted_jobs().await.unwrap();
+
+        assert_eq!(restored, 1);
+        assert_eq!(dispatcher.workspace_queues.len(), 1);
+        assert!(dispatcher.workspace_queues.contains_key("/test/workspace"));
+    }
+
     #[tokio::test]
     async fn test_drop_trait_automatic_shutdown() {
         let (workspace_manager, event_bus, database, _temp_dir) = create_test_setup();
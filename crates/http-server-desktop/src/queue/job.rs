@@ -42,6 +42,13 @@ pub enum Job {
         project_changes: Vec<PathBuf>,
         priority: JobPriority,
     },
+
+    /// This job triggers a full indexing of a single project within a workspace folder.
+    IndexProjectFolder {
+        workspace_folder_path: String,
+        project_folder_path: String,
+        priority: JobPriority,
+    },
 }
 
 #[derive(PartialEq, Eq, Hash)]
@@ -49,6 +56,7 @@ pub enum JobType {
     IndexWorkspaceFolder,
     ReindexWorkspaceFolderWithWatchedFiles,
     ReindexProjectFolderWithWatchedFiles,
+    IndexProjectFolder,
 }
 
 impl JobType {
@@ -59,6 +67,7 @@ impl JobType {
                 "ReindexWorkspaceFolderWithWatchedFiles"
             }
             JobType::ReindexProjectFolderWithWatchedFiles => "ReindexProjectFolderWithWatchedFiles",
+            JobType::IndexProjectFolder => "IndexProjectFolder",
         }
     }
 }
@@ -78,6 +87,10 @@ impl Job {
                 workspace_folder_path,
                 ..
             } => workspace_folder_path,
+            Job::IndexProjectFolder {
+                workspace_folder_path,
+                ..
+            } => workspace_folder_path,
         }
     }
 
@@ -86,6 +99,7 @@ impl Job {
             Job::IndexWorkspaceFolder { priority, .. } => priority.clone(),
             Job::ReindexWorkspaceFolderWithWatchedFiles { priority, .. } => priority.clone(),
             Job::ReindexProjectFolderWithWatchedFiles { priority, .. } => priority.clone(),
+            Job::IndexProjectFolder { priority, .. } => priority.clone(),
         }
     }
 
@@ -98,6 +112,7 @@ impl Job {
             Job::ReindexProjectFolderWithWatchedFiles { .. } => {
                 JobType::ReindexProjectFolderWithWatchedFiles.as_str()
             }
+            Job::IndexProjectFolder { .. } => JobType::IndexProjectFolder.as_str(),
         }
     }
 
@@ -110,6 +125,7 @@ impl Job {
             Job::ReindexProjectFolderWithWatchedFiles { .. } => {
                 JobType::ReindexProjectFolderWithWatchedFiles
             }
+            Job::IndexProjectFolder { .. } => JobType::IndexProjectFolder,
         }
     }
 }
@@ -25,6 +25,8 @@
 //!
 //! - **[`job`]**: Defines job types, priorities, and metadata structures
 //! - **[`dispatch`]**: Central dispatching and queue management logic  
+//! - **[`scheduler`]**: Bounds and orders concurrent indexing across workspaces
+//! - **[`store`]**: Persists unfinished jobs so they are resumed after a restart
 //! - **[`worker`]**: Per-workspace job processing workers
 //!
 //! ## Usage Example
@@ -50,13 +52,35 @@
 //! - **High**: User-triggered operations that should preempt existing work
 //!
 //! High-priority jobs will cancel any existing worker for the same workspace.
+//!
+//! ## Concurrency Limit
+//!
+//! Workers share an [`IndexingScheduler`] owned by the dispatcher and hold a slot while
+//! running a job, so [`JobDispatcher::with_scheduling`] can cap how many workspaces are
+//! indexed at once. Jobs of the same workspace are still processed one after another.
+//!
+//! When workspaces wait for a slot, the [`SchedulingPolicy`] picks the next one: FIFO by
+//! default, or shortest-job-first based on a dry-run file count so small workspaces don't
+//! wait behind a large one.
+//!
+//! ## Persistence
+//!
+//! The dispatcher records every job in a [`JobStore`], which also backs the job status
+//! endpoint. When the store is opened on a file in the data directory, jobs are written
+//! there when dispatched and removed once they finish. On startup,
+//! [`JobDispatcher::restore_persisted_jobs`] re-dispatches whatever a previous run left
+//! queued or running.
 
 pub mod dispatch;
 pub mod job;
+pub mod scheduler;
+pub mod store;
 pub mod worker;
 
 pub use dispatch::JobDispatcher;
 pub use job::{Job, JobInfo, JobPriority, JobStatus};
+pub use scheduler::{IndexingScheduler, SchedulingPolicy};
+pub use store::JobStore;
 pub use worker::WorkspaceWorker;
 
 #[cfg(test)]
@@ -0,0 +1,366 @@
+//! Indexing slot scheduling.
+//!
+//! The IndexingScheduler bounds how many workspaces are indexed at the same time. Workers
+//! request a slot before running a job and hold it until the job finishes. When all slots
+//! are taken, waiting workers are granted the next free slot according to the configured
+//! [`SchedulingPolicy`].
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Number of times a waiter may be passed over by cheaper jobs before it is served next
+/// regardless of its estimated cost. Prevents large workspaces from starving.
+const MAX_PASSED_OVER: u32 = 8;
+
+/// Decides which waiting worker receives a slot once one frees up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchedulingPolicy {
+    /// Slots are granted in the order workers started waiting.
+    #[default]
+    Fifo,
+    /// Slots are granted to the job with the smallest estimated cost (number of files to
+    /// process), so small workspaces are not stuck behind a large one.
+    ShortestJobFirst,
+}
+
+/// A waiter's place in line. A worker keeps its ticket while it waits for the same job, even
+/// if it has to re-issue `acquire`, so the times it was passed over keep counting.
+#[derive(Clone)]
+pub struct Ticket {
+    number: u64,
+    passed_over: Arc<AtomicU32>,
+}
+
+impl Ticket {
+    fn passed_over(&self) -> u32 {
+        self.passed_over.load(Ordering::Relaxed)
+    }
+}
+
+struct Waiter {
+    ticket: Ticket,
+    estimated_cost: u64,
+    sender: oneshot::Sender<()>,
+}
+
+struct SchedulerState {
+    available: usize,
+    waiters: Vec<Waiter>,
+}
+
+pub struct IndexingScheduler {
+    policy: SchedulingPolicy,
+    state: Mutex<SchedulerState>,
+    next_ticket: AtomicU64,
+}
+
+impl IndexingScheduler {
+    pub fn new(max_concurrent_jobs: usize, policy: SchedulingPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(SchedulerState {
+                available: max_concurrent_jobs.max(1),
+                waiters: Vec::new(),
+            }),
+            next_ticket: AtomicU64::new(0),
+        }
+    }
+
+    /// A scheduler that never makes a worker wait.
+    pub fn unbounded() -> Self {
+        Self::new(usize::MAX, SchedulingPolicy::Fifo)
+    }
+
+    pub fn policy(&self) -> SchedulingPolicy {
+        self.policy
+    }
+
+    pub fn available_slots(&self) -> usize {
+        self.state.lock().unwrap().available
+    }
+
+    /// Returns a ticket identifying a waiter's place in line.
+    pub fn next_ticket(&self) -> Ticket {
+        Ticket {
+            number: self.next_ticket.fetch_add(1, Ordering::Relaxed),
+            passed_over: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Waits for a free slot. The returned guard gives the slot back when dropped.
+    ///
+    /// Cancel safe: dropping the future before it resolves removes the waiter, and a slot
+    /// granted in the meantime is handed on to the next waiter.
+    pub async fn acquire(self: &Arc<Self>, ticket: &Ticket, estimated_cost: u64) -> IndexingSlot {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.available > 0 {
+                state.available -= 1;
+                return IndexingSlot {
+                    scheduler: Arc::clone(self),
+                };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push(Waiter {
+                ticket: ticket.clone(),
+                estimated_cost,
+                sender,
+            });
+            receiver
+        };
+
+        let mut pending = PendingSlot {
+            scheduler: Arc::clone(self),
+            ticket: ticket.number,
+            receiver: Some(receiver),
+        };
+        // The sender is only ever consumed by a grant while the waiter is registered
+        let _ = pending.receiver.as_mut().unwrap().await;
+        pending.receiver = None;
+
+        IndexingSlot {
+            scheduler: Arc::clone(self),
+        }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        while let Some(index) = self.next_waiter(&state.waiters) {
+            let waiter = state.waiters.swap_remove(index);
+            for other in state.waiters.iter() {
+                if other.ticket.number < waiter.ticket.number {
+                    other.ticket.passed_over.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            if waiter.sender.send(()).is_ok() {
+                return;
+            }
+        }
+        state.available = state.available.saturating_add(1);
+    }
+
+    fn next_waiter(&self, waiters: &[Waiter]) -> Option<usize> {
+        let by_ticket = |candidates: &mut dyn Iterator<Item = (usize, &Waiter)>| {
+            candidates
+                .min_by_key(|(_, waiter)| waiter.ticket.number)
+                .map(|(index, _)| index)
+        };
+
+        match self.policy {
+            SchedulingPolicy::Fifo => by_ticket(&mut waiters.iter().enumerate()),
+            SchedulingPolicy::ShortestJobFirst => by_ticket(
+                &mut waiters
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, waiter)| waiter.ticket.passed_over() >= MAX_PASSED_OVER),
+            )
+            .or_else(|| {
+                waiters
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, waiter)| (waiter.estimated_cost, waiter.ticket.number))
+                    .map(|(index, _)| index)
+            }),
+        }
+    }
+}
+
+/// A held indexing slot, returned to the scheduler on drop.
+pub struct IndexingSlot {
+    scheduler: Arc<IndexingScheduler>,
+}
+
+impl Drop for IndexingSlot {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
+
+struct PendingSlot {
+    scheduler: Arc<IndexingScheduler>,
+    ticket: u64,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for PendingSlot {
+    fn drop(&mut self) {
+        let Some(mut receiver) = self.receiver.take() else {
+            return;
+        };
+
+        self.scheduler
+            .state
+            .lock()
+            .unwrap()
+            .waiters
+            .retain(|waiter| waiter.ticket.number != self.ticket);
+
+        // A slot may have been granted after the future was last polled
+        if receiver.try_recv().is_ok() {
+            self.scheduler.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    async fn wait_in_line(
+        scheduler: &Arc<IndexingScheduler>,
+        estimated_cost: u64,
+    ) -> tokio::task::JoinHandle<IndexingSlot> {
+        let scheduler = Arc::clone(scheduler);
+        let ticket = scheduler.next_ticket();
+        let handle = tokio::spawn(async move { scheduler.acquire(&ticket, estimated_cost).await });
+        // Let the task register as a waiter before the next one does
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        handle
+    }
+
+    #[tokio::test]
+    async fn test_fifo_grants_slots_in_arrival_order() {
+        let scheduler = Arc::new(IndexingScheduler::new(1, SchedulingPolicy::Fifo));
+        let held = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+
+        let large = wait_in_line(&scheduler, 10_000).await;
+        let small = wait_in_line(&scheduler, 10).await;
+
+        drop(held);
+        let large_slot = timeout(Duration::from_secs(1), large)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!small.is_finished());
+
+        drop(large_slot);
+        timeout(Duration::from_secs(1), small)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shortest_job_first_prefers_small_jobs() {
+        let scheduler = Arc::new(IndexingScheduler::new(
+            1,
+            SchedulingPolicy::ShortestJobFirst,
+        ));
+        let held = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+
+        let large = wait_in_line(&scheduler, 10_000).await;
+        let small = wait_in_line(&scheduler, 10).await;
+
+        drop(held);
+        let small_slot = timeout(Duration::from_secs(1), small)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!large.is_finished());
+
+        drop(small_slot);
+        let _large_slot = timeout(Duration::from_secs(1), large)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(scheduler.available_slots(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shortest_job_first_does_not_starve_large_jobs() {
+        let scheduler = Arc::new(IndexingScheduler::new(
+            1,
+            SchedulingPolicy::ShortestJobFirst,
+        ));
+        let mut held = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+        let large = wait_in_line(&scheduler, 10_000).await;
+
+        for _ in 0..MAX_PASSED_OVER {
+            let small = wait_in_line(&scheduler, 10).await;
+            drop(held);
+            held = timeout(Duration::from_secs(1), small)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let small = wait_in_line(&scheduler, 10).await;
+        drop(held);
+        let large_slot = timeout(Duration::from_secs(1), large)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!small.is_finished());
+
+        drop(large_slot);
+        timeout(Duration::from_secs(1), small)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reissued_acquire_keeps_passed_over_count() {
+        let scheduler = Arc::new(IndexingScheduler::new(
+            1,
+            SchedulingPolicy::ShortestJobFirst,
+        ));
+        let mut held = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+        let large_ticket = scheduler.next_ticket();
+        let wait_with_large_ticket = || {
+            let scheduler = Arc::clone(&scheduler);
+            let ticket = large_ticket.clone();
+            tokio::spawn(async move { scheduler.acquire(&ticket, 10_000).await })
+        };
+
+        for _ in 0..MAX_PASSED_OVER {
+            // The worker of the large job re-issues `acquire`, e.g. after handling a message
+            let large = wait_with_large_ticket();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let small = wait_in_line(&scheduler, 10).await;
+            drop(held);
+            held = timeout(Duration::from_secs(1), small)
+                .await
+                .unwrap()
+                .unwrap();
+            large.abort();
+            assert!(large.await.is_err());
+        }
+
+        let large = wait_with_large_ticket();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let small = wait_in_line(&scheduler, 10).await;
+        drop(held);
+        let large_slot = timeout(Duration::from_secs(1), large)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!small.is_finished());
+
+        drop(large_slot);
+        timeout(Duration::from_secs(1), small)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_slot() {
+        let scheduler = Arc::new(IndexingScheduler::new(1, SchedulingPolicy::Fifo));
+        let held = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+
+        let waiting = timeout(
+            Duration::from_millis(20),
+            scheduler.acquire(&scheduler.next_ticket(), 0),
+        )
+        .await;
+        assert!(waiting.is_err());
+
+        drop(held);
+        assert_eq!(scheduler.available_slots(), 1);
+    }
+}
@@ -0,0 +1,352 @@
+//! Tracking and persistence of jobs.
+//!
+//! The JobStore keeps the status of every job that hasn't finished yet, plus the most
+//! recently finished ones so clients can see what the server has been doing. When opened
+//! with a path, unfinished jobs are also mirrored into a JSON file in the data directory:
+//! they are written when dispatched or when they start running and dropped once they
+//! complete, fail or are cancelled, so after a restart the file holds exactly the work
+//! that still has to be done.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::queue::job::{JobInfo, JobStatus};
+
+/// Number of finished jobs kept in memory for status reporting.
+const MAX_FINISHED_JOBS: usize = 100;
+
+pub struct JobStore {
+    path: Option<PathBuf>,
+    jobs: Mutex<Vec<JobInfo>>,
+    finished_jobs: Mutex<VecDeque<JobInfo>>,
+}
+
+impl JobStore {
+    /// A store that only tracks jobs in memory.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            jobs: Mutex::new(Vec::new()),
+            finished_jobs: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Loads the jobs left over from a previous run. A missing or unreadable file starts
+    /// an empty store instead of preventing the server from starting.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let jobs = match Self::load(&path) {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                warn!(
+                    "Ignoring unreadable job queue file {}: {}",
+                    path.display(),
+                    e
+                );
+                Vec::new()
+            }
+        };
+
+        let store = Self {
+            path: Some(path),
+            jobs: Mutex::new(jobs),
+            finished_jobs: Mutex::new(VecDeque::new()),
+        };
+        store.prune_finished();
+        store
+    }
+
+    fn load(path: &Path) -> Result<Vec<JobInfo>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let jobs: Vec<JobInfo> = serde_json::from_str(&content)?;
+        info!(
+            "Loaded {} persisted jobs from {}",
+            jobs.len(),
+            path.display()
+        );
+        Ok(jobs)
+    }
+
+    /// Jobs that were queued or running when the store was last written, oldest first.
+    pub fn unfinished_jobs(&self) -> Vec<JobInfo> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    /// Unfinished jobs followed by the most recently finished ones, oldest first.
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        let mut jobs = self.unfinished_jobs();
+        jobs.extend(self.finished_jobs.lock().unwrap().iter().cloned());
+        jobs.sort_by_key(|job| job.created_at);
+        jobs
+    }
+
+    /// Looks up a job that is still unfinished or among the most recently finished ones.
+    pub fn get(&self, job_id: &str) -> Option<JobInfo> {
+        if let Some(job) = self
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|job| job.id == job_id)
+        {
+            return Some(job.clone());
+        }
+
+        self.finished_jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|job| job.id == job_id)
+            .cloned()
+    }
+
+    /// Inserts or updates a job. Jobs in a final state move to the finished jobs and are
+    /// no longer persisted.
+    pub fn record(&self, job_info: &JobInfo) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|job| job.id != job_info.id);
+
+        if is_finished(&job_info.status) {
+            self.push_finished(job_info.clone());
+        } else {
+            jobs.push(job_info.clone());
+        }
+        self.persist(&jobs);
+    }
+
+    /// Marks the pending jobs of `job_type` for a workspace as cancelled right away, so they
+    /// aren't resumed after a restart even if the worker never gets to drop them.
+    ///
+    /// Returns the number of cancelled jobs.
+    pub fn cancel_pending(&self, workspace_path: &str, job_type: &str) -> usize {
+        let mut jobs = self.jobs.lock().unwrap();
+        let (cancelled, remaining): (Vec<JobInfo>, Vec<JobInfo>) =
+            jobs.drain(..).partition(|job| {
+                job.status == JobStatus::Pending
+                    && job.job.workspace_path() == workspace_path
+                    && job.job.job_type() == job_type
+            });
+        *jobs = remaining;
+
+        let cancelled_count = cancelled.len();
+        for mut job_info in cancelled {
+            job_info.status = JobStatus::Cancelled;
+            job_info.completed_at = Some(Utc::now());
+            self.push_finished(job_info);
+        }
+        if cancelled_count > 0 {
+            self.persist(&jobs);
+        }
+        cancelled_count
+    }
+
+    fn push_finished(&self, job_info: JobInfo) {
+        let mut finished_jobs = self.finished_jobs.lock().unwrap();
+        finished_jobs.retain(|job| job.id != job_info.id);
+        if finished_jobs.len() == MAX_FINISHED_JOBS {
+            finished_jobs.pop_front();
+        }
+        finished_jobs.push_back(job_info);
+    }
+
+    pub fn remove(&self, job_id: &str) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let original_count = jobs.len();
+        jobs.retain(|job| job.id != job_id);
+        if jobs.len() != original_count {
+            self.persist(&jobs);
+        }
+    }
+
+    fn prune_finished(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|job| !is_finished(&job.status));
+        self.persist(&jobs);
+    }
+
+    /// Failing to persist only loses the jobs on a restart, so it is logged rather than
+    /// failing the dispatch.
+    fn persist(&self, jobs: &[JobInfo]) {
+        if let Err(e) = self.write(jobs) {
+            warn!("Failed to persist job queue: {}", e);
+        }
+    }
+
+    fn write(&self, jobs: &[JobInfo]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if jobs.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(jobs)?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, path)?;
+
+        debug!("Persisted {} jobs to {}", jobs.len(), path.display());
+        Ok(())
+    }
+}
+
+fn is_finished(status: &JobStatus) -> bool {
+    matches!(
+        status,
+        JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::job::{Job, JobPriority};
+    use tempfile::TempDir;
+
+    fn job_info(id: &str, status: JobStatus) -> JobInfo {
+        JobInfo {
+            id: id.to_string(),
+            job: Job::IndexWorkspaceFolder {
+                workspace_folder_path: format!("/workspace/{id}"),
+                priority: JobPriority::Normal,
+            },
+            created_at: Utc::now(),
+            started_at: None,
+            completed_at: None,
+            status,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_unfinished_jobs_survive_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gkg_job_queue.json");
+
+        let store = JobStore::open(&path);
+        store.record(&job_info("a", JobStatus::Pending));
+        store.record(&job_info("b", JobStatus::Pending));
+        store.record(&job_info("a", JobStatus::Running));
+        store.remove("b");
+
+        let reopened = JobStore::open(&path);
+        let jobs = reopened.unfinished_jobs();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, "a");
+        assert_eq!(jobs[0].status, JobStatus::Running);
+    }
+
+    #[test]
+    fn test_finished_jobs_are_pruned() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gkg_job_queue.json");
+
+        let store = JobStore::open(&path);
+        store.record(&job_info("a", JobStatus::Pending));
+        store.record(&job_info("a", JobStatus::Completed));
+        assert!(store.unfinished_jobs().is_empty());
+        assert!(!path.exists());
+
+        // Entries already in a final state are dropped on load
+        let stale = vec![
+            job_info("done", JobStatus::Completed),
+            job_info("cancelled", JobStatus::Cancelled),
+            job_info("queued", JobStatus::Pending),
+        ];
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+
+        let jobs = JobStore::open(&path).unfinished_jobs();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, "queued");
+    }
+
+    #[test]
+    fn test_recently_finished_jobs_are_listed() {
+        let store = JobStore::in_memory();
+        store.record(&job_info("queued", JobStatus::Pending));
+        for i in 0..MAX_FINISHED_JOBS + 1 {
+            store.record(&job_info(&format!("done-{i}"), JobStatus::Completed));
+        }
+
+        let jobs = store.jobs();
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+        assert_eq!(jobs[0].id, "queued");
+        assert!(!jobs.iter().any(|job| job.id == "done-0"));
+        assert_eq!(store.unfinished_jobs().len(), 1);
+    }
+
+    #[test]
+    fn test_get_finds_unfinished_and_finished_jobs() {
+        let store = JobStore::in_memory();
+        store.record(&job_info("queued", JobStatus::Pending));
+        store.record(&job_info("done", JobStatus::Running));
+        store.record(&job_info("done", JobStatus::Failed));
+
+        assert_eq!(store.get("queued").unwrap().status, JobStatus::Pending);
+        assert_eq!(store.get("done").unwrap().status, JobStatus::Failed);
+        assert!(store.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_cancelled_jobs_are_not_resumed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gkg_job_queue.json");
+
+        let store = JobStore::open(&path);
+        store.record(&job_info("a", JobStatus::Pending));
+        store.record(&job_info("b", JobStatus::Pending));
+        store.record(&job_info("running", JobStatus::Running));
+
+        assert_eq!(
+            store.cancel_pending("/workspace/a", "IndexWorkspaceFolder"),
+            1
+        );
+        assert_eq!(
+            store.cancel_pending("/workspace/running", "IndexWorkspaceFolder"),
+            0
+        );
+        assert_eq!(store.get("a").unwrap().status, JobStatus::Cancelled);
+
+        // The worker records the cancellation too, which must not list the job twice
+        store.record(&store.get("a").unwrap());
+        assert_eq!(store.jobs().iter().filter(|job| job.id == "a").count(), 1);
+
+        let mut ids: Vec<String> = JobStore::open(&path)
+            .unfinished_jobs()
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["b", "running"]);
+    }
+
+    #[test]
+    fn test_corrupt_file_starts_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gkg_job_queue.json");
+        fs::write(&path, "not json").unwrap();
+
+        let store = JobStore::open(&path);
+        assert!(store.unfinished_jobs().is_empty());
+
+        store.record(&job_info("a", JobStatus::Pending));
+        assert_eq!(JobStore::open(&path).unfinished_jobs().len(), 1);
+    }
+}
@@ -3,6 +3,7 @@ use chrono::Utc;
 use database::kuzu::database::KuzuDatabase;
 use event_bus::EventBus;
 use indexer::execution::{config::IndexingConfigBuilder, executor::IndexingExecutor};
+use indexer::project::source::PathFileSource;
 use num_cpus;
 use std::collections::VecDeque;
 use std::path::PathBuf;
@@ -15,6 +16,8 @@ use tracing::{debug, error, info, warn};
 use workspace_manager::WorkspaceManager;
 
 use crate::queue::job::{Job, JobInfo, JobStatus};
+use crate::queue::scheduler::{IndexingScheduler, SchedulingPolicy, Ticket};
+use crate::queue::store::JobStore;
 
 /// Message types that can be sent to a workspace worker
 #[derive(Debug, Clone)]
@@ -29,6 +32,13 @@ pub enum WorkerMessage {
 /// This helps conserve system resources when workspaces are not actively being processed.
 const WORKER_TIMEOUT_SECS: u64 = 60;
 
+/// The place in line a worker holds while its next job waits for an indexing slot.
+struct SlotRequest {
+    job_id: String,
+    ticket: Ticket,
+    estimated_cost: u64,
+}
+
 /// Each WorkspaceWorker is responsible for processing jobs sequentially for a single
 /// workspace. This ensures that operations on the same workspace are atomic and ordered,
 /// while allowing parallel processing across different workspaces.
@@ -38,8 +48,11 @@ pub struct WorkspaceWorker {
     workspace_manager: Arc<WorkspaceManager>,
     event_bus: Arc<EventBus>,
     database: Arc<KuzuDatabase>,
+    scheduler: Arc<IndexingScheduler>,
+    job_store: Arc<JobStore>,
     cancellation_token: CancellationToken,
     job_queue: VecDeque<JobInfo>,
+    slot_request: Option<SlotRequest>,
 }
 
 impl WorkspaceWorker {
@@ -49,6 +62,8 @@ impl WorkspaceWorker {
         workspace_manager: Arc<WorkspaceManager>,
         event_bus: Arc<EventBus>,
         database: Arc<KuzuDatabase>,
+        scheduler: Arc<IndexingScheduler>,
+        job_store: Arc<JobStore>,
         cancellation_token: CancellationToken,
     ) -> Self {
         Self {
@@ -57,8 +72,11 @@ impl WorkspaceWorker {
             workspace_manager,
             event_bus,
             database,
+            scheduler,
+            job_store,
             cancellation_token,
             job_queue: VecDeque::new(),
+            slot_request: None,
         }
     }
 
@@ -70,19 +88,38 @@ impl WorkspaceWorker {
     /// - No messages are received within the timeout period (auto-cleanup)
     ///
     /// Jobs are processed one at a time in FIFO order, with support for cancelling
-    /// specific job types while preserving others in the queue.
+    /// specific job types while preserving others in the queue. Before running a job the
+    /// worker takes a slot from the dispatcher's shared scheduler; while it waits for one,
+    /// incoming messages are still handled so cancellations apply to the waiting jobs.
     pub async fn run(mut self) {
         info!("Starting worker for workspace: {}", self.workspace_path);
 
         while !self.cancellation_token.is_cancelled() {
             // First, try to process any queued jobs
-            if let Some(mut job_info) = self.job_queue.pop_front() {
+            if let Some((ticket, estimated_cost)) = self.slot_request_for_next_job().await {
+                let scheduler = Arc::clone(&self.scheduler);
+                let _slot = tokio::select! {
+                    biased;
+                    _ = self.cancellation_token.cancelled() => break,
+                    Some(message) = self.receiver.recv() => {
+                        self.handle_message(message);
+                        continue;
+                    }
+                    slot = scheduler.acquire(&ticket, estimated_cost) => slot,
+                };
+                self.slot_request = None;
+
+                let Some(mut job_info) = self.job_queue.pop_front() else {
+                    continue;
+                };
+
                 info!(
                     "Processing queued job {} for workspace {}",
                     job_info.id, self.workspace_path
                 );
                 job_info.started_at = Some(Utc::now());
                 job_info.status = JobStatus::Running;
+                self.job_store.record(&job_info);
 
                 let result = self.process_job(&job_info.job).await;
 
@@ -105,6 +142,7 @@ impl WorkspaceWorker {
                         );
                     }
                 }
+                self.job_store.record(&job_info);
                 continue;
             }
 
@@ -115,31 +153,7 @@ impl WorkspaceWorker {
             )
             .await
             {
-                Ok(Some(message)) => match message {
-                    WorkerMessage::Job(job_info) => {
-                        self.job_queue.push_back(job_info);
-                    }
-                    WorkerMessage::CancelJobsOfType(job_type) => {
-                        let original_count = self.job_queue.len();
-                        self.job_queue.retain(|job_info| {
-                            let should_keep = job_info.job.job_type() != job_type;
-                            if !should_keep {
-                                warn!(
-                                    "Cancelling job {} ({}) for workspace {}",
-                                    job_info.id, job_type, self.workspace_path
-                                );
-                            }
-                            should_keep
-                        });
-                        let cancelled_count = original_count - self.job_queue.len();
-                        if cancelled_count > 0 {
-                            info!(
-                                "Cancelled {} {} jobs for workspace {}",
-                                cancelled_count, job_type, self.workspace_path
-                            );
-                        }
-                    }
-                },
+                Ok(Some(message)) => self.handle_message(message),
                 Ok(None) => {
                     debug!(
                         "Message channel closed for workspace {}",
@@ -160,6 +174,68 @@ impl WorkspaceWorker {
         info!("Worker for workspace {} shutting down", self.workspace_path);
     }
 
+    /// Returns the ticket and estimated cost the next queued job waits for a slot with,
+    /// keeping the same ticket while that job stays at the front of the queue.
+    async fn slot_request_for_next_job(&mut self) -> Option<(Ticket, u64)> {
+        let job_info = self.job_queue.front()?;
+
+        if let Some(request) = &self.slot_request
+            && request.job_id == job_info.id
+        {
+            return Some((request.ticket.clone(), request.estimated_cost));
+        }
+
+        let estimated_cost = match self.scheduler.policy() {
+            SchedulingPolicy::Fifo => 0,
+            SchedulingPolicy::ShortestJobFirst => estimate_job_cost(&job_info.job).await,
+        };
+        let request = SlotRequest {
+            job_id: job_info.id.clone(),
+            ticket: self.scheduler.next_ticket(),
+            estimated_cost,
+        };
+        debug!(
+            "Job {} for workspace {} waits for an indexing slot (estimated cost {})",
+            request.job_id, self.workspace_path, request.estimated_cost
+        );
+
+        let slot_request = (request.ticket.clone(), request.estimated_cost);
+        self.slot_request = Some(request);
+        Some(slot_request)
+    }
+
+    fn handle_message(&mut self, message: WorkerMessage) {
+        match message {
+            WorkerMessage::Job(job_info) => {
+                self.job_queue.push_back(job_info);
+            }
+            WorkerMessage::CancelJobsOfType(job_type) => {
+                let original_count = self.job_queue.len();
+                self.job_queue.retain(|job_info| {
+                    let should_keep = job_info.job.job_type() != job_type;
+                    if !should_keep {
+                        warn!(
+                            "Cancelling job {} ({}) for workspace {}",
+                            job_info.id, job_type, self.workspace_path
+                        );
+                        let mut cancelled_job = job_info.clone();
+                        cancelled_job.status = JobStatus::Cancelled;
+                        cancelled_job.completed_at = Some(Utc::now());
+                        self.job_store.record(&cancelled_job);
+                    }
+                    should_keep
+                });
+                let cancelled_count = original_count - self.job_queue.len();
+                if cancelled_count > 0 {
+                    info!(
+                        "Cancelled {} {} jobs for workspace {}",
+                        cancelled_count, job_type, self.workspace_path
+                    );
+                }
+            }
+        }
+    }
+
     async fn process_job(&self, job: &Job) -> Result<()> {
         match job {
             Job::IndexWorkspaceFolder {
@@ -190,6 +266,14 @@ impl WorkspaceWorker {
                 )
                 .await
             }
+            Job::IndexProjectFolder {
+                workspace_folder_path,
+                project_folder_path,
+                ..
+            } => {
+                self.process_index_project_job(workspace_folder_path, project_folder_path)
+                    .await
+            }
         }
     }
 
@@ -353,6 +437,96 @@ impl WorkspaceWorker {
             }
         }
     }
+
+    /// Processes an IndexProjectFolder job by running full indexing of a single project,
+    /// e.g. when requested through the `index_project` MCP tool.
+    async fn process_index_project_job(
+        &self,
+        workspace_folder_path: &str,
+        project_folder_path: &str,
+    ) -> Result<()> {
+        let workspace_path_copy = workspace_folder_path.to_string();
+        let project_path_copy = project_folder_path.to_string();
+        let threads = num_cpus::get();
+        let config = IndexingConfigBuilder::build(threads);
+        let mut executor = IndexingExecutor::new(
+            Arc::clone(&self.database),
+            Arc::clone(&self.workspace_manager),
+            Arc::clone(&self.event_bus),
+            config,
+        );
+
+        let cancellation_token = CancellationToken::new();
+        let result = tokio::task::spawn(async move {
+            executor
+                .execute_project_indexing(
+                    &workspace_path_copy,
+                    &project_path_copy,
+                    Some(cancellation_token),
+                )
+                .await
+        })
+        .await;
+
+        match result {
+            Ok(Ok(_stats)) => {
+                info!(
+                    "Indexing completed successfully for project '{}' in workspace '{}'",
+                    project_folder_path, workspace_folder_path
+                );
+                Ok(())
+            }
+            Ok(Err(e)) => {
+                error!(
+                    "Indexing failed for project '{}' in workspace '{}': {}",
+                    project_folder_path, workspace_folder_path, e
+                );
+                Err(e)
+            }
+            Err(e) => {
+                error!(
+                    "Indexing task panicked for project '{}' in workspace '{}': {}",
+                    project_folder_path, workspace_folder_path, e
+                );
+                Err(anyhow::anyhow!("Indexing task panicked: {}", e))
+            }
+        }
+    }
+}
+
+/// Estimates a job's size as the number of files it will process, for shortest-job-first
+/// scheduling. Full indexing runs a dry-run of file discovery without parsing anything.
+async fn estimate_job_cost(job: &Job) -> u64 {
+    match job {
+        Job::IndexWorkspaceFolder {
+            workspace_folder_path,
+            ..
+        } => {
+            let workspace_path = PathBuf::from(workspace_folder_path);
+            tokio::task::spawn_blocking(move || {
+                PathFileSource::from_path(workspace_path).files.len() as u64
+            })
+            .await
+            .unwrap_or(u64::MAX)
+        }
+        Job::ReindexWorkspaceFolderWithWatchedFiles {
+            workspace_changes, ..
+        } => workspace_changes.len() as u64,
+        Job::ReindexProjectFolderWithWatchedFiles {
+            project_changes, ..
+        } => project_changes.len() as u64,
+        Job::IndexProjectFolder {
+            project_folder_path,
+            ..
+        } => {
+            let project_path = PathBuf::from(project_folder_path);
+            tokio::task::spawn_blocking(move || {
+                PathFileSource::from_path(project_path).files.len() as u64
+            })
+            .await
+            .unwrap_or(u64::MAX)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -392,6 +566,8 @@ mod tests {
             workspace_manager,
             event_bus,
             database,
+            Arc::new(IndexingScheduler::unbounded()),
+            Arc::new(JobStore::in_memory()),
             cancellation_token,
         );
 
@@ -410,6 +586,8 @@ mod tests {
             workspace_manager,
             event_bus,
             database,
+            Arc::new(IndexingScheduler::unbounded()),
+            Arc::new(JobStore::in_memory()),
             cancellation_token.clone(),
         );
 
@@ -419,6 +597,55 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_worker_waiting_for_slot_stops_on_cancellation() {
+        let (workspace_manager, event_bus, database, _temp_dir) = create_test_setup();
+        let (sender, receiver) = mpsc::channel::<WorkerMessage>(100);
+        let cancellation_token = CancellationToken::new();
+        let scheduler = Arc::new(IndexingScheduler::new(1, SchedulingPolicy::Fifo));
+
+        // Another workspace holds the only indexing slot
+        let _held_slot = scheduler.acquire(&scheduler.next_ticket(), 0).await;
+
+        let worker = WorkspaceWorker::new(
+            "/test/workspace".to_string(),
+            receiver,
+            workspace_manager,
+            event_bus,
+            database,
+            Arc::clone(&scheduler),
+            Arc::new(JobStore::in_memory()),
+            cancellation_token.clone(),
+        );
+
+        sender
+            .send(WorkerMessage::Job(JobInfo {
+                id: "job1".to_string(),
+                job: Job::IndexWorkspaceFolder {
+                    workspace_folder_path: "/test/workspace".to_string(),
+                    priority: JobPriority::Normal,
+                },
+                created_at: Utc::now(),
+                started_at: None,
+                completed_at: None,
+                status: JobStatus::Pending,
+                error: None,
+            }))
+            .await
+            .unwrap();
+
+        let worker_handle = tokio::spawn(worker.run());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The job is queued behind the held slot, so the worker is still waiting
+        assert!(!worker_handle.is_finished());
+        assert_eq!(scheduler.available_slots(), 0);
+
+        cancellation_token.cancel();
+        let result = timeout(Duration::from_millis(500), worker_handle).await;
+        assert!(result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_worker_timeout_behavior() {
         let (workspace_manager, event_bus, database, _temp_dir) = create_test_setup();
@@ -431,6 +658,8 @@ mod tests {
             workspace_manager,
             event_bus,
             database,
+            Arc::new(IndexingScheduler::unbounded()),
+            Arc::new(JobStore::in_memory()),
             cancellation_token,
         );
 
@@ -446,6 +675,28 @@ mod tests {
         drop(worker_future); // Prevent the test from hanging
     }
 
+    #[tokio::test]
+    async fn test_estimate_job_cost_counts_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(temp_dir.path().join("lib.rs"), "pub fn lib() {}").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "not source").unwrap();
+
+        let full_index = Job::IndexWorkspaceFolder {
+            workspace_folder_path: temp_dir.path().to_string_lossy().to_string(),
+            priority: JobPriority::Normal,
+        };
+        assert_eq!(estimate_job_cost(&full_index).await, 2);
+
+        let reindex = Job::ReindexProjectFolderWithWatchedFiles {
+            workspace_folder_path: "/test/workspace".to_string(),
+            project_folder_path: "/test/workspace/project".to_string(),
+            project_changes: vec![PathBuf::from("/test/workspace/project/main.rs")],
+            priority: JobPriority::Low,
+        };
+        assert_eq!(estimate_job_cost(&reindex).await, 1);
+    }
+
     #[tokio::test]
     async fn test_job_processing_dispatch() {
         let job = Job::IndexWorkspaceFolder {
@@ -501,6 +752,8 @@ mod tests {
             workspace_manager,
             event_bus,
             database,
+            Arc::new(IndexingScheduler::unbounded()),
+            Arc::new(JobStore::in_memory()),
             cancellation_token,
         );
 
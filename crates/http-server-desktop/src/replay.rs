@@ -0,0 +1,152 @@
+//! Replays an event log recorded with `--event-log` through the events endpoint, to reproduce
+//! the state of the frontend without indexing anything.
+
+use crate::contract::EndpointContract;
+use crate::endpoints::events::{EventsEndpoint, event_stream};
+use crate::{Assets, localhost_cors_layer, shutdown_signal};
+use anyhow::Result;
+use axum::Router;
+use axum::http::HeaderMap;
+use axum::routing::get;
+use axum_embed::ServeEmbed;
+use chrono::{DateTime, Utc};
+use event_bus::{EventBus, GkgEvent};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longer gaps between recorded events are shortened, so the idle periods of a server's log
+/// don't stall the replay
+pub const MAX_REPLAY_DELAY: Duration = Duration::from_secs(10);
+
+/// Serves the events endpoint on its own at `/api/events`, along with the frontend. The other
+/// API endpoints aren't available.
+pub fn replay_router(event_bus: Arc<EventBus>, keep_alive_interval: Duration) -> Router {
+    let api_router = Router::new().route(
+        EventsEndpoint::PATH,
+        get(move |headers: HeaderMap| {
+            let event_bus = Arc::clone(&event_bus);
+            async move { event_stream(&event_bus, &headers, keep_alive_interval) }
+        }),
+    );
+
+    Router::new()
+        .nest("/api", api_router)
+        .fallback_service(ServeEmbed::<Assets>::new())
+        .layer(localhost_cors_layer())
+}
+
+/// Serves [`replay_router`] on `port` until the process is asked to stop, e.g. with Ctrl+C
+pub async fn serve_replay(
+    port: u16,
+    event_bus: Arc<EventBus>,
+    keep_alive_interval: Duration,
+) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+    axum::serve(listener, replay_router(event_bus, keep_alive_interval))
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+    Ok(())
+}
+
+/// How long to wait between events recorded at `previous` and `next` when replaying `speed`
+/// times faster than recorded. `speed` must be positive.
+pub fn replay_delay(previous: DateTime<Utc>, next: DateTime<Utc>, speed: f64) -> Duration {
+    let recorded = (next - previous).to_std().unwrap_or_default();
+    recorded.div_f64(speed).min(MAX_REPLAY_DELAY)
+}
+
+/// Sends `events` on `event_bus`, spaced as they were recorded and `speed` times faster.
+/// `on_sent` is called after each event is sent.
+pub async fn replay_events(
+    event_bus: &EventBus,
+    events: &[GkgEvent],
+    speed: f64,
+    mut on_sent: impl FnMut(&GkgEvent),
+) {
+    let mut previous: Option<DateTime<Utc>> = None;
+    for event in events {
+        let timestamp = event.timestamp();
+        if let Some(previous) = previous {
+            tokio::time::sleep(replay_delay(previous, timestamp, speed)).await;
+        }
+        // Events aren't always recorded in the order of their timestamps
+        previous = Some(previous.map_or(timestamp, |previous| previous.max(timestamp)));
+
+        event_bus.send(event);
+        on_sent(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_SSE_KEEP_ALIVE_INTERVAL;
+    use axum::body::Body;
+    use axum::http::Request;
+    use event_bus::{GraphEvent, GraphUpdated};
+    use futures_util::StreamExt;
+    use tower::ServiceExt;
+
+    fn graph_updated(project_path: &str, updated_at: DateTime<Utc>) -> GkgEvent {
+        GkgEvent::Graph(GraphEvent::Updated(GraphUpdated {
+            workspace_folder_path: "/workspace".to_string(),
+            project_path: project_path.to_string(),
+            database_path: format!("{project_path}/database.kz"),
+            updated_at,
+        }))
+    }
+
+    #[test]
+    fn test_replay_delay() {
+        let start = Utc::now();
+
+        assert_eq!(
+            replay_delay(start, start + chrono::Duration::seconds(4), 2.0),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            replay_delay(start, start - chrono::Duration::seconds(4), 1.0),
+            Duration::ZERO
+        );
+        assert_eq!(
+            replay_delay(start, start + chrono::Duration::hours(1), 1.0),
+            MAX_REPLAY_DELAY
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replayed_events_are_streamed() {
+        let event_bus = Arc::new(EventBus::new());
+        let app = replay_router(Arc::clone(&event_bus), DEFAULT_SSE_KEEP_ALIVE_INTERVAL);
+
+        let response = app
+            .oneshot(Request::get("/api/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let connection_chunk = body.next().await.unwrap().unwrap();
+        assert!(String::from_utf8_lossy(&connection_chunk).contains("gkg-connection"));
+        assert_eq!(event_bus.sequenced_subscriber_count(), 1);
+
+        let start = Utc::now();
+        let events = vec![
+            graph_updated("/workspace/a", start),
+            graph_updated("/workspace/b", start + chrono::Duration::milliseconds(200)),
+        ];
+        let mut sent = 0;
+        replay_events(&event_bus, &events, 4.0, |_| sent += 1).await;
+        assert_eq!(sent, 2);
+
+        for project_path in ["/workspace/a", "/workspace/b"] {
+            let chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let chunk = String::from_utf8_lossy(&chunk);
+            assert!(chunk.contains("event: gkg-event"));
+            assert!(chunk.contains(project_path));
+        }
+    }
+}
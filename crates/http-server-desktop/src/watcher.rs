@@ -1,16 +1,20 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info};
+use tokio_util::task::AbortOnDropHandle;
+use tracing::{debug, error, info, warn};
 
+use event_bus::{EventBus, GkgEvent};
 use ignore::WalkBuilder;
 use ignore_files::{IgnoreFilesFromOriginArgs, IgnoreFilter};
 use watchexec::WatchedPath;
-use watchexec::Watchexec;
+use watchexec::error::RuntimeError;
+use watchexec::{ErrorHook, Watchexec};
 use watchexec_events::Event;
 use watchexec_filterer_ignore::IgnoreFilterer;
 // use watchexec_events::{Priority};
@@ -26,6 +30,8 @@ const DEBOUNCE_DURATION: Duration = Duration::from_millis(3000);
 const MAX_EVENTS_PER_DEBOUNCE_WINDOW: usize = 8192;
 const EXCLUDED_SUBDIRECTORIES: &[&str] = &[".git", ".idea", ".vscode", ".cache"];
 const PERIODIC_REINDEX_INTERVAL: Duration = Duration::from_secs(600); // 10 minutes
+const WATCHER_RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const WATCHER_RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 #[derive(Default, Clone, Copy)]
 pub struct WatcherConfig {
@@ -56,6 +62,52 @@ impl WatcherConfig {
     }
 }
 
+/// Tracks project watches that have failed and are waiting to be re-established,
+/// so the health endpoint can report a degraded watcher.
+#[derive(Debug, Default)]
+pub struct WatcherHealth {
+    lost_watches: Mutex<HashSet<PathBuf>>,
+}
+
+impl WatcherHealth {
+    pub fn is_degraded(&self) -> bool {
+        !self.lost_watches.lock().unwrap().is_empty()
+    }
+
+    pub fn lost_watches(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.lost_watches.lock().unwrap().iter().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    /// Returns true if the watch was healthy before this call.
+    pub(crate) fn mark_lost(&self, project_path: &Path) -> bool {
+        self.lost_watches
+            .lock()
+            .unwrap()
+            .insert(project_path.to_path_buf())
+    }
+
+    /// Returns true if the watch was lost before this call.
+    pub(crate) fn mark_recovered(&self, project_path: &Path) -> bool {
+        self.lost_watches.lock().unwrap().remove(project_path)
+    }
+}
+
+/// Everything a project watch task needs to (re-)create its watchexec instance.
+struct ProjectWatch {
+    workspace_path: PathBuf,
+    project_path: PathBuf,
+    workspace_manager: Arc<WorkspaceManager>,
+    watcher_config: WatcherConfig,
+    project_events: Arc<Mutex<HashMap<PathBuf, Vec<Vec<PathBuf>>>>>,
+    debounce_windows: Arc<Mutex<HashMap<PathBuf, Instant>>>,
+    event_sender: mpsc::Sender<(PathBuf, PathBuf, Vec<PathBuf>)>,
+    event_bus: Arc<EventBus>,
+    health: Arc<WatcherHealth>,
+    cancellation_token: CancellationToken,
+}
+
 pub struct Watcher {
     // Used to list all workspaces and their project folders/paths
     pub workspace_manager: Arc<WorkspaceManager>,
@@ -77,6 +129,14 @@ pub struct Watcher {
     cancellation_token: CancellationToken,
     // Watcher config
     watcher_config: WatcherConfig,
+    // Project watches that are currently lost and being re-established
+    pub health: Arc<WatcherHealth>,
+    // Workspace folders added through `watch_folder`, watched regardless of their indexing status
+    watched_workspace_folders: Arc<Mutex<HashSet<PathBuf>>>,
+    // Workspace folders removed through `unwatch_folder`, never watched until re-added
+    unwatched_workspace_folders: Arc<Mutex<HashSet<PathBuf>>>,
+    // Whether `start` has been called; folders are only recorded until then
+    started: AtomicBool,
 }
 
 impl Watcher {
@@ -87,6 +147,7 @@ impl Watcher {
     ) -> Self {
         let (tx, rx) = mpsc::channel(MAX_EVENTS_PER_DEBOUNCE_WINDOW);
         let job_dispatcher_clone = job_dispatcher.clone();
+        let workspace_manager_clone = workspace_manager.clone();
         let cancellation_token = CancellationToken::new();
         let watcher = Self {
             workspace_manager,
@@ -99,10 +160,20 @@ impl Watcher {
             runtime: tokio::runtime::Handle::current(),
             cancellation_token,
             watcher_config: watcher_config.unwrap_or_default(),
+            health: Arc::new(WatcherHealth::default()),
+            watched_workspace_folders: Arc::new(Mutex::new(HashSet::new())),
+            unwatched_workspace_folders: Arc::new(Mutex::new(HashSet::new())),
+            started: AtomicBool::new(false),
         };
 
         watcher.runtime.spawn(async move {
-            Self::process_events(rx, job_dispatcher_clone, watcher.watcher_config).await;
+            Self::process_events(
+                rx,
+                job_dispatcher_clone,
+                workspace_manager_clone,
+                watcher.watcher_config,
+            )
+            .await;
         });
 
         watcher
@@ -111,6 +182,7 @@ impl Watcher {
     async fn process_events(
         mut rx: mpsc::Receiver<(PathBuf, PathBuf, Vec<PathBuf>)>,
         job_dispatcher: Arc<JobDispatcher>,
+        workspace_manager: Arc<WorkspaceManager>,
         watcher_config: WatcherConfig,
     ) {
         while let Some((workspace_path, project_path, changed_paths)) = rx.recv().await {
@@ -123,6 +195,14 @@ impl Watcher {
             info!("changed paths in group: {}", changed_paths.len());
             info!("Changed paths: {changed_paths:?}");
 
+            // The graph lags behind the source until the re-indexing job completes
+            if let Err(e) = workspace_manager.mark_project_stale(
+                &workspace_path.to_string_lossy(),
+                &project_path.to_string_lossy(),
+            ) {
+                warn!("Failed to mark project {project_path:?} as stale: {e}");
+            }
+
             let job = if watcher_config.single_watcher {
                 info!(
                     "Single watcher mode, dispatching re-indexing job for workspace: {:?}",
@@ -153,6 +233,7 @@ impl Watcher {
     }
 
     pub async fn start(self: Arc<Self>) {
+        self.started.store(true, Ordering::SeqCst);
         info!(
             "Watcher is excluding the following (sub)directories: {:?}",
             EXCLUDED_SUBDIRECTORIES
@@ -172,6 +253,48 @@ impl Watcher {
         }
     }
 
+    /// Starts watching `workspace_folder_path` (e.g. right after it was registered) without
+    /// waiting for its projects to finish indexing.
+    pub async fn watch_folder(&self, workspace_folder_path: &Path) {
+        info!("Watching workspace folder: {:?}", workspace_folder_path);
+        self.unwatched_workspace_folders
+            .lock()
+            .unwrap()
+            .remove(workspace_folder_path);
+        self.watched_workspace_folders
+            .lock()
+            .unwrap()
+            .insert(workspace_folder_path.to_path_buf());
+
+        if self.started.load(Ordering::SeqCst) {
+            self.sync_project_watchers().await;
+        }
+    }
+
+    /// Stops watching `workspace_folder_path` and drops the OS watches of all its projects.
+    pub async fn unwatch_folder(&self, workspace_folder_path: &Path) {
+        info!("Unwatching workspace folder: {:?}", workspace_folder_path);
+        self.watched_workspace_folders
+            .lock()
+            .unwrap()
+            .remove(workspace_folder_path);
+        self.unwatched_workspace_folders
+            .lock()
+            .unwrap()
+            .insert(workspace_folder_path.to_path_buf());
+
+        let mut watched_project_folders = self.watched_project_folders.lock().unwrap();
+        let folders_to_remove: Vec<PathBuf> = watched_project_folders
+            .iter()
+            .filter(|folder| folder.starts_with(workspace_folder_path))
+            .cloned()
+            .collect();
+
+        for folder in folders_to_remove {
+            self.stop_project_watcher(&folder, &mut watched_project_folders);
+        }
+    }
+
     async fn stop_abandoned_project_watchers(&self, project_folder_paths: &[PathBuf]) {
         let mut watched_project_folders = self.watched_project_folders.lock().unwrap();
         let current_paths: HashSet<PathBuf> = project_folder_paths.iter().cloned().collect();
@@ -181,32 +304,58 @@ impl Watcher {
             .collect();
 
         for folder in folders_to_remove {
-            info!("Stopping project watcher for removed folder: {:?}", folder);
-            // Join on the abandoned watchers
-            if let Ok(mut handles) = self.watcher_handles.lock()
-                && let Some(handle) = handles.remove(&folder)
-            {
-                handle.abort();
-            }
+            self.stop_project_watcher(&folder, &mut watched_project_folders);
+        }
+    }
 
-            // Remove events, debounce windows, and watched folder
-            if let Ok(mut events) = self.project_events.lock() {
-                events.remove(&folder);
-            }
-            if let Ok(mut windows) = self.debounce_windows.lock() {
-                windows.remove(&folder);
-            }
-            watched_project_folders.remove(&folder);
+    fn stop_project_watcher(&self, folder: &Path, watched_project_folders: &mut HashSet<PathBuf>) {
+        info!("Stopping project watcher for removed folder: {:?}", folder);
+        // Aborting the task also aborts its watchexec instance, releasing the OS watch
+        if let Ok(mut handles) = self.watcher_handles.lock()
+            && let Some(handle) = handles.remove(folder)
+        {
+            handle.abort();
         }
+
+        // Remove events, debounce windows, and watched folder
+        if let Ok(mut events) = self.project_events.lock() {
+            events.remove(folder);
+        }
+        if let Ok(mut windows) = self.debounce_windows.lock() {
+            windows.remove(folder);
+        }
+        self.health.mark_recovered(folder);
+        watched_project_folders.remove(folder);
     }
 
-    async fn get_active_paths(watcher: Arc<Watcher>) -> Vec<(PathBuf, PathBuf)> {
+    fn should_watch(&self, workspace_folder_path: &str, status: &Status) -> bool {
+        let workspace_folder_path = Path::new(workspace_folder_path);
+        if self
+            .unwatched_workspace_folders
+            .lock()
+            .unwrap()
+            .contains(workspace_folder_path)
+        {
+            return false;
+        }
+
+        *status == Status::Indexed
+            || *status == Status::Reindexing
+            || *status == Status::Stale
+            || self
+                .watched_workspace_folders
+                .lock()
+                .unwrap()
+                .contains(workspace_folder_path)
+    }
+
+    async fn get_active_paths(watcher: &Watcher) -> Vec<(PathBuf, PathBuf)> {
         if watcher.watcher_config.single_watcher {
             watcher
                 .workspace_manager
                 .list_workspace_folders()
                 .iter()
-                .filter(|w| w.status == Status::Indexed || w.status == Status::Reindexing)
+                .filter(|w| watcher.should_watch(&w.workspace_folder_path, &w.status))
                 .map(|w| {
                     (
                         PathBuf::from(&w.workspace_folder_path),
@@ -219,7 +368,7 @@ impl Watcher {
                 .workspace_manager
                 .list_all_projects()
                 .iter()
-                .filter(|p| p.status == Status::Indexed || p.status == Status::Reindexing)
+                .filter(|p| watcher.should_watch(&p.workspace_folder_path, &p.status))
                 .map(|p: &workspace_manager::ProjectInfo| {
                     (
                         PathBuf::from(&p.workspace_folder_path),
@@ -230,52 +379,55 @@ impl Watcher {
         }
     }
 
-    async fn monitor_workspace_folders(watcher: Arc<Watcher>) {
-        loop {
-            if watcher.cancellation_token.is_cancelled() {
-                info!("Workspace folder monitoring shutting down");
-                break;
-            }
+    /// Starts watchers for newly active projects and stops the ones that are no longer active.
+    async fn sync_project_watchers(&self) {
+        // Only launch watchers for projects that are indexed, being reindexed, or explicitly watched
+        let active_project_paths = Self::get_active_paths(self).await;
 
-            // Only proceed with launching watchers if the underlying projects are indexed or being reindexed
-            let active_project_paths = Self::get_active_paths(watcher.clone()).await;
+        self.stop_abandoned_project_watchers(
+            &active_project_paths
+                .iter()
+                .map(|(_, p)| p.clone())
+                .collect::<Vec<_>>(),
+        )
+        .await;
 
-            watcher
-                .stop_abandoned_project_watchers(
-                    &active_project_paths
-                        .iter()
-                        .map(|(_, p)| p.clone())
-                        .collect::<Vec<_>>(),
-                )
-                .await;
+        let paths_needing_watchers = {
+            let mut watched_folders = self.watched_project_folders.lock().unwrap();
 
-            let paths_needing_watchers = {
-                let mut watched_folders = watcher.watched_project_folders.lock().unwrap();
+            // Find project folder paths that don't have watchers yet
+            let paths_needing_watchers: Vec<(PathBuf, PathBuf)> = active_project_paths
+                .into_iter()
+                .filter(|(_, project_path)| !watched_folders.contains(project_path))
+                .collect();
 
-                // Find project folder paths that don't have watchers yet
-                let paths_needing_watchers: Vec<(PathBuf, PathBuf)> = active_project_paths
-                    .into_iter()
-                    .filter(|(_, project_path)| !watched_folders.contains(project_path))
-                    .collect();
+            // Mark these paths as being watched (optimistically)
+            for (_, project_path) in &paths_needing_watchers {
+                watched_folders.insert(project_path.clone());
+            }
 
-                // Mark these paths as being watched (optimistically)
-                for (_, project_path) in &paths_needing_watchers {
-                    watched_folders.insert(project_path.clone());
-                }
+            paths_needing_watchers
+        };
 
-                paths_needing_watchers
-            };
+        for (workspace_folder_path, project_path) in &paths_needing_watchers {
+            info!(
+                "Starting new project watcher for: {:?} in workspace: {:?}",
+                project_path, workspace_folder_path
+            );
+            self.start_project_watcher(workspace_folder_path, project_path)
+                .await;
+        }
+    }
 
-            for (workspace_folder_path, project_path) in &paths_needing_watchers {
-                info!(
-                    "Starting new project watcher for: {:?} in workspace: {:?}",
-                    project_path, workspace_folder_path
-                );
-                watcher
-                    .start_project_watcher(workspace_folder_path, project_path)
-                    .await;
+    async fn monitor_workspace_folders(watcher: Arc<Watcher>) {
+        loop {
+            if watcher.cancellation_token.is_cancelled() {
+                info!("Workspace folder monitoring shutting down");
+                break;
             }
 
+            watcher.sync_project_watchers().await;
+
             // Use select! to allow cancellation during sleep
             tokio::select! {
                 _ = tokio::time::sleep(WATCHER_SPAWN_INTERVAL) => {},
@@ -291,8 +443,8 @@ impl Watcher {
         loop {
             tokio::select! {
                 _ = tokio::time::sleep(PERIODIC_REINDEX_INTERVAL) => {
-                    let projects = watcher.workspace_manager.list_all_projects();
-                    let project_paths: Vec<PathBuf> = projects.iter().map(|p| PathBuf::from(&p.project_path)).collect();
+                    let mut project_paths: Vec<PathBuf> = Vec::new();
+                    watcher.workspace_manager.for_each_project(|project_path, _| project_paths.push(PathBuf::from(project_path)));
                     watcher.stop_abandoned_project_watchers(&project_paths).await;
 
                     // After stopping all watchers, we can cancel all existing reindexing jobs
@@ -372,12 +524,14 @@ impl Watcher {
         let mut pathset: Vec<WatchedPath> = Vec::new();
 
         // Get all other project paths (excluding the current one)
-        let projects = workspace_manager.list_all_projects();
-        let other_project_paths: std::collections::HashSet<PathBuf> = projects
-            .iter()
-            .filter(|p| PathBuf::from(&p.project_path) != project_path)
-            .map(|p| PathBuf::from(&p.project_path))
-            .collect();
+        let mut other_project_paths: std::collections::HashSet<PathBuf> =
+            std::collections::HashSet::new();
+        workspace_manager.for_each_project(|project_path_str, _| {
+            let other_project_path = PathBuf::from(project_path_str);
+            if other_project_path != project_path {
+                other_project_paths.insert(other_project_path);
+            }
+        });
 
         // Watch the project path non-recursively (files directly in the project root)
         pathset.push(WatchedPath::non_recursive(project_path.to_path_buf()));
@@ -440,100 +594,185 @@ impl Watcher {
     }
 
     async fn start_project_watcher(&self, workspace_path: &Path, project_path: &Path) {
-        if let Ok(ignore_filterer) = Self::resolve_ignore_filter(project_path).await {
-            let project_path_clone = project_path.to_path_buf();
-            let workspace_path_clone = workspace_path.to_path_buf();
-            let events_map = self.project_events.clone();
-            let windows_map = self.debounce_windows.clone();
-            let event_sender = self.event_sender.clone();
-
-            let pathset = Self::compute_project_watcher_pathset(
-                &self.workspace_manager,
-                self.watcher_config,
-                workspace_path,
-                project_path,
-            );
-
-            debug!(
-                "computed pathset for project: {:?} in workspace: {:?} ->  {:?}",
-                project_path, workspace_path, pathset
-            );
-            debug!(
-                "Launching watcher for project: {:?} in workspace: {:?}",
-                project_path, workspace_path
-            );
+        let watch = ProjectWatch {
+            workspace_path: workspace_path.to_path_buf(),
+            project_path: project_path.to_path_buf(),
+            workspace_manager: self.workspace_manager.clone(),
+            watcher_config: self.watcher_config,
+            project_events: self.project_events.clone(),
+            debounce_windows: self.debounce_windows.clone(),
+            event_sender: self.event_sender.clone(),
+            event_bus: self.job_dispatcher.event_bus.clone(),
+            health: self.health.clone(),
+            cancellation_token: self.cancellation_token.clone(),
+        };
 
-            match Watchexec::new(move |action| {
-                debug!(
-                    "Received watchexec action with {} events",
-                    action.events.len()
-                );
+        let handle = self.runtime.spawn(Self::run_project_watcher(watch));
 
-                let current_time = Instant::now();
-                let mut windows = windows_map.lock().unwrap();
-                let mut events = events_map.lock().unwrap();
+        // Store the task handle so we can stop it later
+        let mut handles = self.watcher_handles.lock().unwrap();
+        handles.insert(project_path.to_path_buf(), handle);
+    }
 
-                // Get or create window start time for this project path
-                let window_start = windows
-                    .entry(project_path_clone.clone())
-                    .or_insert(current_time);
+    /// Keeps a project watch alive: whenever watchexec stops (e.g. the OS watch broke on a
+    /// network filesystem) it is re-created with exponential backoff.
+    async fn run_project_watcher(watch: ProjectWatch) {
+        let mut backoff = WATCHER_RECONNECT_INITIAL_BACKOFF;
 
-                // Get the current group of events for this project path
-                let project_events = events.entry(project_path_clone.clone()).or_default();
+        loop {
+            let error = match Self::create_project_watchexec(&watch).await {
+                Ok(wx) => {
+                    if watch.health.mark_recovered(&watch.project_path) {
+                        info!(
+                            "File watcher recovered for project: {:?}",
+                            watch.project_path
+                        );
+                        watch.event_bus.send(&GkgEvent::watcher_recovered(
+                            &watch.workspace_path,
+                            &watch.project_path,
+                        ));
+                    }
 
-                // Create first group if none exists
-                if project_events.is_empty() {
-                    project_events.push(Vec::new());
-                }
+                    let started_at = Instant::now();
+                    // Aborting this task (see stop_project_watcher) must also stop watchexec
+                    let result = AbortOnDropHandle::new(wx.main()).await;
 
-                // Add events to the current group
-                let current_group = project_events.last_mut().unwrap();
+                    // Only a watch that stayed up for a while counts as a successful reconnect
+                    if started_at.elapsed() >= WATCHER_RECONNECT_MAX_BACKOFF {
+                        backoff = WATCHER_RECONNECT_INITIAL_BACKOFF;
+                    }
 
-                for event in action.events.iter() {
-                    current_group.extend(Self::handle_file_event(event));
+                    match result {
+                        Ok(Ok(())) => "file watcher stopped unexpectedly".to_string(),
+                        Ok(Err(e)) => e.to_string(),
+                        Err(e) => e.to_string(),
+                    }
                 }
+                Err(e) => e,
+            };
 
-                // If we have events and debounce window elapsed, process them
-                if current_time.duration_since(*window_start) >= DEBOUNCE_DURATION {
-                    *window_start = current_time;
-                    let events_to_process = project_events.pop().unwrap();
-                    project_events.push(Vec::new());
-
-                    let ws_path = workspace_path_clone.clone();
-                    let proj_path = project_path_clone.clone();
-                    let sender = event_sender.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = sender.send((ws_path, proj_path, events_to_process)).await {
-                            error!("Failed to send events for processing: {}", e);
-                        }
-                    });
-                }
-                action
-            }) {
-                Ok(wx) => {
-                    wx.config.filterer(ignore_filterer);
-                    wx.config.pathset(pathset);
-                    wx.config.throttle(DEBOUNCE_DURATION);
+            if watch.cancellation_token.is_cancelled() {
+                break;
+            }
 
-                    let handle = self.runtime.spawn(async move {
-                        if let Err(e) = wx.main().await {
-                            error!("Error in file watcher: {}", e);
-                        }
-                    });
+            error!(
+                "File watcher for project {:?} failed: {}. Retrying in {:?}",
+                watch.project_path, error, backoff
+            );
+            if watch.health.mark_lost(&watch.project_path) {
+                watch.event_bus.send(&GkgEvent::watcher_lost(
+                    &watch.workspace_path,
+                    &watch.project_path,
+                    error,
+                ));
+            }
 
-                    // Store the task handle so we can stop it later
-                    let mut handles = self.watcher_handles.lock().unwrap();
-                    handles.insert(project_path.to_path_buf(), handle);
-                }
-                Err(e) => {
-                    error!("Failed to create file watcher: {}", e);
-                }
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {},
+                _ = watch.cancellation_token.cancelled() => break,
             }
-        } else {
-            error!("Failed to create ignore filter");
+            backoff = next_backoff(backoff);
         }
     }
 
+    async fn create_project_watchexec(watch: &ProjectWatch) -> Result<Arc<Watchexec>, String> {
+        let workspace_path = watch.workspace_path.as_path();
+        let project_path = watch.project_path.as_path();
+
+        let ignore_filterer = Self::resolve_ignore_filter(project_path)
+            .await
+            .map_err(|e| format!("Failed to create ignore filter: {e}"))?;
+
+        let project_path_clone = project_path.to_path_buf();
+        let workspace_path_clone = workspace_path.to_path_buf();
+        let events_map = watch.project_events.clone();
+        let windows_map = watch.debounce_windows.clone();
+        let event_sender = watch.event_sender.clone();
+
+        let pathset = Self::compute_project_watcher_pathset(
+            &watch.workspace_manager,
+            watch.watcher_config,
+            workspace_path,
+            project_path,
+        );
+
+        debug!(
+            "computed pathset for project: {:?} in workspace: {:?} ->  {:?}",
+            project_path, workspace_path, pathset
+        );
+        debug!(
+            "Launching watcher for project: {:?} in workspace: {:?}",
+            project_path, workspace_path
+        );
+
+        let wx = Watchexec::new(move |action| {
+            debug!(
+                "Received watchexec action with {} events",
+                action.events.len()
+            );
+
+            let current_time = Instant::now();
+            let mut windows = windows_map.lock().unwrap();
+            let mut events = events_map.lock().unwrap();
+
+            // Get or create window start time for this project path
+            let window_start = windows
+                .entry(project_path_clone.clone())
+                .or_insert(current_time);
+
+            // Get the current group of events for this project path
+            let project_events = events.entry(project_path_clone.clone()).or_default();
+
+            // Create first group if none exists
+            if project_events.is_empty() {
+                project_events.push(Vec::new());
+            }
+
+            // Add events to the current group
+            let current_group = project_events.last_mut().unwrap();
+
+            for event in action.events.iter() {
+                current_group.extend(Self::handle_file_event(event));
+            }
+
+            // If we have events and debounce window elapsed, process them
+            if current_time.duration_since(*window_start) >= DEBOUNCE_DURATION {
+                *window_start = current_time;
+                let events_to_process = project_events.pop().unwrap();
+                project_events.push(Vec::new());
+
+                let ws_path = workspace_path_clone.clone();
+                let proj_path = project_path_clone.clone();
+                let sender = event_sender.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = sender.send((ws_path, proj_path, events_to_process)).await {
+                        error!("Failed to send events for processing: {}", e);
+                    }
+                });
+            }
+            action
+        })
+        .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+
+        let error_project_path = project_path.to_path_buf();
+        wx.config.on_error(move |err: ErrorHook| {
+            error!(
+                "File watcher error for project {:?}: {}",
+                error_project_path, err.error
+            );
+            // Once the OS watch breaks no more events arrive, so stop watchexec and let
+            // run_project_watcher re-establish it.
+            if matches!(&*err.error, RuntimeError::FsWatcher { .. }) {
+                err.elevate();
+            }
+        });
+        wx.config.filterer(ignore_filterer);
+        wx.config.pathset(pathset);
+        wx.config.throttle(DEBOUNCE_DURATION);
+
+        Ok(wx)
+    }
+
     fn handle_file_event(event: &Event) -> HashSet<PathBuf> {
         // Check if this event has actual file paths (real file events)
         let event_paths: Vec<_> = event
@@ -566,6 +805,10 @@ impl Watcher {
     }
 }
 
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(WATCHER_RECONNECT_MAX_BACKOFF)
+}
+
 // NOTE: I implemented this because I'm not sure why server is not gracefully exiting
 impl Drop for Watcher {
     fn drop(&mut self) {
@@ -659,6 +902,83 @@ mod tests {
         assert!(watcher.project_events.lock().unwrap().is_empty());
         assert!(watcher.debounce_windows.lock().unwrap().is_empty());
         assert!(watcher.watcher_handles.lock().unwrap().is_empty());
+        assert!(!watcher.health.is_degraded());
+    }
+
+    #[test]
+    fn test_watcher_health_tracks_lost_watches() {
+        let health = WatcherHealth::default();
+        let project = PathBuf::from("/workspace/project");
+        assert!(!health.is_degraded());
+
+        assert!(health.mark_lost(&project));
+        assert!(!health.mark_lost(&project));
+        assert!(health.is_degraded());
+        assert_eq!(health.lost_watches(), vec![project.clone()]);
+
+        assert!(health.mark_recovered(&project));
+        assert!(!health.mark_recovered(&project));
+        assert!(!health.is_degraded());
+    }
+
+    #[test]
+    fn test_reconnect_backoff_is_capped() {
+        let mut backoff = WATCHER_RECONNECT_INITIAL_BACKOFF;
+        for _ in 0..10 {
+            backoff = next_backoff(backoff);
+        }
+        assert_eq!(backoff, WATCHER_RECONNECT_MAX_BACKOFF);
+        assert_eq!(
+            next_backoff(WATCHER_RECONNECT_INITIAL_BACKOFF),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_folder_overrides_indexing_status() {
+        let (workspace_manager, job_dispatcher, _temp_dir) = create_test_setup();
+        let watcher = Watcher::new(workspace_manager, job_dispatcher, None);
+        let workspace_folder = Path::new("/workspace");
+
+        assert!(!watcher.should_watch("/workspace", &Status::Pending));
+        assert!(watcher.should_watch("/workspace", &Status::Indexed));
+
+        watcher.watch_folder(workspace_folder).await;
+        assert!(watcher.should_watch("/workspace", &Status::Pending));
+
+        watcher.unwatch_folder(workspace_folder).await;
+        assert!(!watcher.should_watch("/workspace", &Status::Pending));
+        assert!(!watcher.should_watch("/workspace", &Status::Indexed));
+    }
+
+    #[tokio::test]
+    async fn test_unwatch_folder_stops_its_project_watchers() {
+        let (workspace_manager, job_dispatcher, _temp_dir) = create_test_setup();
+        let watcher = Watcher::new(workspace_manager, job_dispatcher, None);
+        let project = PathBuf::from("/workspace/project");
+        let other_project = PathBuf::from("/other/project");
+
+        for path in [&project, &other_project] {
+            watcher
+                .watched_project_folders
+                .lock()
+                .unwrap()
+                .insert(path.clone());
+            watcher
+                .watcher_handles
+                .lock()
+                .unwrap()
+                .insert(path.clone(), tokio::spawn(std::future::pending::<()>()));
+        }
+
+        watcher.unwatch_folder(Path::new("/workspace")).await;
+
+        let watched = watcher.watched_project_folders.lock().unwrap();
+        assert!(!watched.contains(&project));
+        assert!(watched.contains(&other_project));
+        let handles = watcher.watcher_handles.lock().unwrap();
+        assert!(!handles.contains_key(&project));
+        assert!(handles.contains_key(&other_project));
     }
 
     #[tokio::test]
@@ -149,6 +149,8 @@ impl FileSystemAnalyzer {
             repository_name: self.repository_name.clone(),
             extension,
             name,
+            content_hash: file_result.content_hash.clone(),
+            content_offset: file_result.encoding.bom_len() as i64,
         }
     }
 
@@ -5,19 +5,142 @@ use parser_core::{
     csharp::types::{CSharpDefinitionType, CSharpFqn, CSharpFqnPartType, CSharpImportType},
     imports::ImportedSymbolInfo,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    analysis::types::{
-        ConsolidatedRelationship, DefinitionNode, DefinitionType, FqnType, ImportIdentifier,
-        ImportType, ImportedSymbolLocation, ImportedSymbolNode,
+    analysis::{
+        languages::signatures::parameters_match,
+        types::{
+            ConsolidatedRelationship, DefinitionNode, DefinitionType, FqnType, ImportIdentifier,
+            ImportType, ImportedSymbolLocation, ImportedSymbolNode, RelationshipKind,
+        },
+    },
+    parsing::{
+        csharp_constructions::CSharpConstruction,
+        csharp_declarations::CSharpDeclaration,
+        processor::{FileProcessingResult, References},
     },
-    parsing::processor::FileProcessingResult,
 };
 
 #[derive(Default)]
 pub struct CSharpAnalyzer;
 
+/// The C# definitions of each file and by FQN, and the types by simple name
+pub struct CSharpDefinitionIndex<'a> {
+    definitions_by_file: HashMap<&'a str, Vec<&'a DefinitionNode>>,
+    definitions_by_fqn: HashMap<&'a str, Vec<&'a DefinitionNode>>,
+    types_by_name: HashMap<&'a str, Vec<&'a DefinitionNode>>,
+}
+
+fn is_interface(definition: &DefinitionNode) -> bool {
+    definition.definition_type == DefinitionType::CSharp(CSharpDefinitionType::Interface)
+}
+
+fn is_type(definition: &DefinitionNode) -> bool {
+    matches!(
+        definition.definition_type,
+        DefinitionType::CSharp(
+            CSharpDefinitionType::Class
+                | CSharpDefinitionType::Struct
+                | CSharpDefinitionType::Record
+                | CSharpDefinitionType::Interface
+        )
+    )
+}
+
+/// Whether `definition` is a member that can override, a method or a property
+fn is_member(definition: &DefinitionNode) -> bool {
+    matches!(
+        definition.definition_type,
+        DefinitionType::CSharp(
+            CSharpDefinitionType::InstanceMethod | CSharpDefinitionType::Property
+        )
+    )
+}
+
+impl<'a> CSharpDefinitionIndex<'a> {
+    pub fn new(definition_map: &'a HashMap<(String, String), (DefinitionNode, FqnType)>) -> Self {
+        let mut definitions_by_file: HashMap<&str, Vec<&DefinitionNode>> = HashMap::new();
+        let mut definitions_by_fqn: HashMap<&str, Vec<&DefinitionNode>> = HashMap::new();
+        let mut types_by_name: HashMap<&str, Vec<&DefinitionNode>> = HashMap::new();
+        for (definition, _) in definition_map.values() {
+            definitions_by_file
+                .entry(definition.file_path.as_str())
+                .or_default()
+                .push(definition);
+            definitions_by_fqn
+                .entry(definition.fqn.as_str())
+                .or_default()
+                .push(definition);
+            if is_type(definition) {
+                types_by_name
+                    .entry(definition.name.as_str())
+                    .or_default()
+                    .push(definition);
+            }
+        }
+        Self {
+            definitions_by_file,
+            definitions_by_fqn,
+            types_by_name,
+        }
+    }
+
+    /// The innermost definition of `file_path` containing `range`
+    fn enclosing_definition(&self, file_path: &str, range: &Range) -> Option<&'a DefinitionNode> {
+        self.definitions_by_file
+            .get(file_path)?
+            .iter()
+            .filter(|definition| {
+                definition.range.byte_offset.0 <= range.byte_offset.0
+                    && range.byte_offset.1 <= definition.range.byte_offset.1
+            })
+            .min_by_key(|definition| {
+                definition.range.byte_offset.1 - definition.range.byte_offset.0
+            })
+            .copied()
+    }
+
+    /// The type created by `construction`, which can't be an interface
+    fn created_type(
+        &self,
+        file_path: &str,
+        construction: &CSharpConstruction,
+    ) -> Option<&'a DefinitionNode> {
+        self.resolve_type(file_path, &construction.type_name)
+            .filter(|definition| !is_interface(definition))
+    }
+
+    /// The type named `type_name` in `file_path`. A qualified name has to match the end of the
+    /// fully qualified name, and a name shared by several types is only resolved to the one of
+    /// the same file.
+    fn resolve_type(&self, file_path: &str, type_name: &str) -> Option<&'a DefinitionNode> {
+        let simple_name = type_name.rsplit('.').next().unwrap_or(type_name);
+        let candidates: Vec<&DefinitionNode> = self
+            .types_by_name
+            .get(simple_name)?
+            .iter()
+            .filter(|definition| {
+                definition.fqn == type_name || definition.fqn.ends_with(&format!(".{type_name}"))
+            })
+            .copied()
+            .collect();
+
+        match candidates.as_slice() {
+            [definition] => Some(*definition),
+            _ => {
+                let mut in_file = candidates
+                    .iter()
+                    .filter(|definition| definition.file_path == file_path);
+                match (in_file.next(), in_file.next()) {
+                    (Some(definition), None) => Some(*definition),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
 impl CSharpAnalyzer {
     pub fn new() -> Self {
         Self
@@ -116,6 +239,194 @@ impl CSharpAnalyzer {
         }
     }
 
+    /// Creates an [`RelationshipType::Instantiates`] relationship for each object creation
+    /// whose type is a class, struct or record of the project
+    pub fn process_references(
+        &self,
+        references: &Option<References>,
+        relative_file_path: &str,
+        index: &CSharpDefinitionIndex,
+        relationships: &mut Vec<ConsolidatedRelationship>,
+    ) {
+        let Some(constructions) = references.as_ref().and_then(|r| r.iter_csharp()) else {
+            return;
+        };
+
+        for construction in constructions {
+            let Some(created_type) = index.created_type(relative_file_path, construction) else {
+                continue;
+            };
+
+            let Some(source) = index.enclosing_definition(relative_file_path, &construction.range)
+            else {
+                let mut relationship = ConsolidatedRelationship::file_to_definition(
+                    relative_file_path.to_string(),
+                    created_type.file_path.clone(),
+                );
+                relationship.relationship_type = RelationshipType::Instantiates;
+                relationship.source_range = ArcIntern::new(Range::empty());
+                relationship.target_range = ArcIntern::new(created_type.range);
+                relationships.push(relationship);
+                continue;
+            };
+
+            relationships.push(ConsolidatedRelationship {
+                source_path: Some(ArcIntern::new(relative_file_path.to_string())),
+                target_path: Some(ArcIntern::new(created_type.file_path.clone())),
+                kind: RelationshipKind::DefinitionToDefinition,
+                relationship_type: RelationshipType::Instantiates,
+                source_range: ArcIntern::new(construction.range),
+                target_range: ArcIntern::new(created_type.range),
+                source_definition_range: Some(ArcIntern::new(source.range)),
+                target_definition_range: Some(ArcIntern::new(created_type.range)),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Creates an [`RelationshipType::Overrides`] relationship from each method or property to
+    /// the member it implements, the nearest one along each base type taking the same parameter
+    /// types. A member of a class is only overridden by one declared `override`, while an
+    /// interface member is implemented by any member of a class or struct.
+    pub fn resolve_overrides(
+        &self,
+        file_references: &[(String, Option<References>)],
+        index: &CSharpDefinitionIndex,
+        relationships: &mut Vec<ConsolidatedRelationship>,
+    ) {
+        // Partial types and overloads have several declarations under the same FQN
+        let mut declarations: HashMap<&str, Vec<&CSharpDeclaration>> = HashMap::new();
+        for (_, references) in file_references {
+            let Some(file_declarations) = references
+                .as_ref()
+                .and_then(|references| references.iter_csharp_declarations())
+            else {
+                continue;
+            };
+            for declaration in file_declarations {
+                declarations
+                    .entry(declaration.fqn.as_str())
+                    .or_default()
+                    .push(declaration);
+            }
+        }
+
+        let mut linked = HashSet::new();
+        for (fqn, member_declarations) in &declarations {
+            let Some((owner_fqn, name)) = fqn.rsplit_once('.') else {
+                continue;
+            };
+            let Some(members) = index.definitions_by_fqn.get(fqn) else {
+                continue;
+            };
+            if !members.iter().all(|member| is_member(member)) {
+                continue;
+            }
+            let Some(owner) = index
+                .definitions_by_fqn
+                .get(owner_fqn)
+                .and_then(|owners| owners.iter().find(|owner| is_type(owner)))
+            else {
+                continue;
+            };
+            if is_interface(owner) {
+                continue;
+            }
+            let is_override = member_declarations.iter().any(|d| d.is_override);
+
+            let mut visited = HashSet::from([owner.fqn.as_str()]);
+            for overridden in self.find_overridden_members(
+                owner,
+                name,
+                member_declarations,
+                is_override,
+                &declarations,
+                index,
+                &mut visited,
+            ) {
+                for member in members {
+                    if !linked.insert((
+                        member.fqn.as_str(),
+                        member.file_path.as_str(),
+                        overridden.fqn.as_str(),
+                        overridden.file_path.as_str(),
+                    )) {
+                        continue;
+                    }
+                    let mut relationship = ConsolidatedRelationship::definition_to_definition(
+                        member.file_path.clone(),
+                        overridden.file_path.clone(),
+                    );
+                    relationship.relationship_type = RelationshipType::Overrides;
+                    relationship.source_range = ArcIntern::new(member.range);
+                    relationship.target_range = ArcIntern::new(overridden.range);
+                    relationships.push(relationship);
+                }
+            }
+        }
+    }
+
+    /// The members named `name` of the base types of `owner` taking the parameters of one of
+    /// `member_declarations`, the nearest one along each base type
+    #[allow(clippy::too_many_arguments)]
+    fn find_overridden_members<'a>(
+        &self,
+        owner: &DefinitionNode,
+        name: &str,
+        member_declarations: &[&CSharpDeclaration],
+        is_override: bool,
+        declarations: &HashMap<&str, Vec<&CSharpDeclaration>>,
+        index: &CSharpDefinitionIndex<'a>,
+        visited: &mut HashSet<&'a str>,
+    ) -> Vec<&'a DefinitionNode> {
+        let base_types = declarations
+            .get(owner.fqn.as_str())
+            .into_iter()
+            .flatten()
+            .flat_map(|declaration| &declaration.base_types);
+
+        let mut overridden = Vec::new();
+        for base_type_name in base_types {
+            let Some(base_type) = index.resolve_type(&owner.file_path, base_type_name) else {
+                continue;
+            };
+            if !visited.insert(base_type.fqn.as_str()) {
+                continue;
+            }
+
+            let member_fqn = format!("{}.{name}", base_type.fqn);
+            let takes_same_parameters = declarations.get(member_fqn.as_str()).is_some_and(|base| {
+                base.iter().any(|base| {
+                    member_declarations
+                        .iter()
+                        .any(|d| parameters_match(&d.parameter_types, &base.parameter_types))
+                })
+            });
+            let base_member = index
+                .definitions_by_fqn
+                .get(member_fqn.as_str())
+                .and_then(|members| members.first());
+
+            match base_member {
+                Some(base_member)
+                    if takes_same_parameters && (is_override || is_interface(base_type)) =>
+                {
+                    overridden.push(*base_member);
+                }
+                _ => overridden.extend(self.find_overridden_members(
+                    base_type,
+                    name,
+                    member_declarations,
+                    is_override,
+                    declarations,
+                    index,
+                    visited,
+                )),
+            }
+        }
+        overridden
+    }
+
     pub fn add_definition_relationships(
         &self,
         definition_map: &HashMap<(String, String), (DefinitionNode, FqnType)>,
@@ -338,3 +649,124 @@ impl CSharpAnalyzer {
             .join(".")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::AnalysisService;
+    use crate::parsing::processor::{FileProcessor, ProcessingResult};
+    use database::graph::RelationshipType;
+
+    #[test]
+    fn test_object_creations_instantiate_classes() {
+        let content = r#"namespace Shop
+{
+    public class Order
+    {
+        public Order(string id) {}
+    }
+
+    public class Orders
+    {
+        public Order Create(string id)
+        {
+            return new Order(id);
+        }
+
+        public Order CreateDefault() => new Shop.Order("default");
+    }
+}
+"#;
+        let ProcessingResult::Success(file_result) =
+            FileProcessor::new("/repo/Orders.cs".to_string(), content).process()
+        else {
+            panic!("Orders.cs should be processed");
+        };
+        let graph_data = AnalysisService::new("repo".to_string(), "/repo".to_string())
+            .analyze_results(vec![file_result])
+            .unwrap();
+
+        let order = graph_data
+            .definition_nodes
+            .iter()
+            .find(|definition| definition.fqn == "Shop.Order")
+            .expect("Order should be a definition");
+        let instantiations: Vec<_> = graph_data
+            .relationships
+            .iter()
+            .filter(|relationship| relationship.relationship_type == RelationshipType::Instantiates)
+            .collect();
+        assert_eq!(instantiations.len(), 2);
+        for instantiation in instantiations {
+            assert_eq!(*instantiation.target_range, order.range);
+            let (start, end) = instantiation.source_range.byte_offset;
+            assert!(content[start..end].ends_with("Order"));
+        }
+    }
+
+    #[test]
+    fn test_overrides_match_parameters() {
+        let content = r#"namespace Shop
+{
+    public interface IStore
+    {
+        void Save(string id);
+    }
+
+    public class Repository : IStore
+    {
+        public virtual void Save(string id) {}
+
+        public virtual void Load(string id) {}
+    }
+
+    public class Orders : Repository
+    {
+        public override void Save(string id) {}
+
+        public void Load(string id, bool cached) {}
+    }
+
+    public class Cache : IStore
+    {
+        public void Save(int id) {}
+    }
+}
+"#;
+        let ProcessingResult::Success(file_result) =
+            FileProcessor::new("/repo/Store.cs".to_string(), content).process()
+        else {
+            panic!("Store.cs should be processed");
+        };
+        let graph_data = AnalysisService::new("repo".to_string(), "/repo".to_string())
+            .analyze_results(vec![file_result])
+            .unwrap();
+
+        let fqn_at = |range| {
+            graph_data
+                .definition_nodes
+                .iter()
+                .find(|definition| definition.range == range)
+                .map(|definition| definition.fqn.as_str())
+                .unwrap()
+        };
+        let mut overrides: Vec<_> = graph_data
+            .relationships
+            .iter()
+            .filter(|relationship| relationship.relationship_type == RelationshipType::Overrides)
+            .map(|relationship| {
+                (
+                    fqn_at(*relationship.source_range),
+                    fqn_at(*relationship.target_range),
+                )
+            })
+            .collect();
+        overrides.sort();
+        assert_eq!(
+            overrides,
+            vec![
+                ("Shop.Orders.Save", "Shop.Repository.Save"),
+                ("Shop.Repository.Save", "Shop.IStore.Save"),
+            ]
+        );
+    }
+}
@@ -171,6 +171,10 @@ impl JavaAnalyzer {
                 relationships.push(relationship);
             }
         }
+
+        self.expression_resolver.resolve_overrides(relationships);
+        self.expression_resolver
+            .resolve_type_references(relationships);
     }
 
     fn get_parent_fqn_string(&self, fqn: &FqnType) -> Option<String> {
@@ -7,9 +7,12 @@ use parser_core::java::{
 
 use crate::{
     analysis::{
-        languages::java::{
-            java_file::{JavaClass, JavaFile},
-            utils::full_import_path,
+        languages::{
+            java::{
+                java_file::{JavaClass, JavaFile},
+                utils::full_import_path,
+            },
+            signatures::parameters_match,
         },
         types::{
             ConsolidatedRelationship, DefinitionNode, DefinitionType, ImportType,
@@ -20,12 +23,15 @@ use crate::{
 };
 
 use internment::ArcIntern;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
+/// Definitions and imported symbols an expression refers to, each with the type of the
+/// relationship from the referencing definition, e.g. `CALLS` for a method call and
+/// `REFERENCES` for an annotation
 #[derive(Default)]
 pub(crate) struct Resolutions {
-    definition_resolutions: Vec<DefinitionResolution>,
-    import_resolutions: Vec<ImportedSymbolNode>,
+    definition_resolutions: Vec<(DefinitionResolution, RelationshipType)>,
+    import_resolutions: Vec<(ImportedSymbolNode, RelationshipType)>,
 }
 
 pub(crate) enum ResolvedType {
@@ -94,7 +100,9 @@ impl ExpressionResolver {
                     debug!("Resolving Java expression {}.", reference.name);
                     self.resolve_expression(file_path, range, &expression, &mut resolutions);
 
-                    for resolved_definition in resolutions.definition_resolutions {
+                    for (resolved_definition, relationship_type) in
+                        resolutions.definition_resolutions
+                    {
                         let to_definition = self.definition_nodes.get(&resolved_definition.fqn);
 
                         if let Some(to_definition) = to_definition {
@@ -103,7 +111,7 @@ impl ExpressionResolver {
                                     from_definition.file_path.clone(),
                                     to_definition.file_path.clone(),
                                 );
-                            relationship.relationship_type = RelationshipType::Calls;
+                            relationship.relationship_type = relationship_type;
                             relationship.source_range = ArcIntern::new(reference.range);
                             relationship.target_range = ArcIntern::new(to_definition.range);
                             relationship.source_definition_range =
@@ -114,13 +122,13 @@ impl ExpressionResolver {
                         }
                     }
 
-                    for resolved_import in resolutions.import_resolutions {
+                    for (resolved_import, relationship_type) in resolutions.import_resolutions {
                         let mut relationship =
                             ConsolidatedRelationship::definition_to_imported_symbol(
                                 from_definition.file_path.clone(),
                                 resolved_import.location.file_path.clone(),
                             );
-                        relationship.relationship_type = RelationshipType::Calls;
+                        relationship.relationship_type = relationship_type;
                         relationship.source_range = ArcIntern::new(reference.range);
                         relationship.target_range =
                             ArcIntern::new(resolved_import.location.range());
@@ -135,6 +143,164 @@ impl ExpressionResolver {
         }
     }
 
+    /// Link the methods of every class to the super type methods they override. Methods are
+    /// matched by name and parameter types since their FQNs don't include the parameter types,
+    /// an overload with other parameters isn't an override.
+    pub fn resolve_overrides(&self, relationships: &mut Vec<ConsolidatedRelationship>) {
+        for file in self.files.values() {
+            for (method_fqn, method) in file.methods.iter() {
+                let Some((class_fqn, _)) = method_fqn.rsplit_once('.') else {
+                    continue;
+                };
+                let Some(class) = file.classes.get(class_fqn) else {
+                    continue;
+                };
+                // Constructors are named after their class and are never overridden
+                if method.name == class.name {
+                    continue;
+                }
+                let Some(from_definition) = self.definition_nodes.get(method_fqn) else {
+                    continue;
+                };
+
+                let signatures = file.method_signatures(method_fqn);
+                let mut visited = FxHashSet::default();
+                visited.insert(class.fqn.clone());
+                for overridden_fqn in self.find_overridden_methods(
+                    class,
+                    file,
+                    &method.name,
+                    &signatures,
+                    &mut visited,
+                ) {
+                    let Some(to_definition) = self.definition_nodes.get(&overridden_fqn) else {
+                        continue;
+                    };
+
+                    let mut relationship = ConsolidatedRelationship::definition_to_definition(
+                        from_definition.file_path.clone(),
+                        to_definition.file_path.clone(),
+                    );
+                    relationship.relationship_type = RelationshipType::Overrides;
+                    relationship.source_range = ArcIntern::new(from_definition.range);
+                    relationship.target_range = ArcIntern::new(to_definition.range);
+                    relationships.push(relationship);
+                }
+            }
+        }
+    }
+
+    /// FQNs of the methods named `name` that `class` inherits, taking the parameters of one of
+    /// `signatures`, the nearest one along each of its super types
+    fn find_overridden_methods(
+        &self,
+        class: &JavaClass,
+        file: &JavaFile,
+        name: &str,
+        signatures: &[Vec<&str>],
+        visited: &mut FxHashSet<String>,
+    ) -> Vec<String> {
+        let mut overridden = Vec::new();
+
+        for super_type in class.super_types.iter() {
+            let Some(ResolvedType::Definition(super_class)) =
+                self.resolve_type(file.file_path.as_str(), Some(&class.fqn), super_type)
+            else {
+                continue;
+            };
+            if !visited.insert(super_class.fqn.clone()) {
+                continue;
+            }
+
+            let Some(super_class_file) = self
+                .definition_nodes
+                .get(&super_class.fqn)
+                .and_then(|definition| self.files.get(&definition.file_path))
+            else {
+                continue;
+            };
+            let Some(super_class) = super_class_file.classes.get(&super_class.fqn) else {
+                continue;
+            };
+
+            let method_fqn = format!("{}.{}", super_class.fqn, name);
+            let overrides =
+                super_class_file
+                    .method_signatures(&method_fqn)
+                    .iter()
+                    .any(|super_signature| {
+                        signatures
+                            .iter()
+                            .any(|signature| parameters_match(signature, super_signature))
+                    });
+            if overrides {
+                overridden.push(method_fqn);
+            } else {
+                overridden.extend(self.find_overridden_methods(
+                    super_class,
+                    super_class_file,
+                    name,
+                    signatures,
+                    visited,
+                ));
+            }
+        }
+
+        overridden
+    }
+
+    /// Link the classes and methods to the types their fields and parameters are declared
+    /// with, including the type arguments, e.g. both `List` and `Foo` for `List<Foo>`
+    pub fn resolve_type_references(&self, relationships: &mut Vec<ConsolidatedRelationship>) {
+        for file in self.files.values() {
+            for usage in file.type_usages.iter() {
+                let Some(from_definition) = self.definition_nodes.get(&usage.owner_fqn) else {
+                    continue;
+                };
+
+                for type_name in type_names(&usage.type_name) {
+                    let mut relationship = match self.resolve_type(
+                        file.file_path.as_str(),
+                        Some(&usage.owner_fqn),
+                        type_name,
+                    ) {
+                        Some(ResolvedType::Definition(target)) => {
+                            let Some(to_definition) = self.definition_nodes.get(&target.fqn) else {
+                                continue;
+                            };
+                            let mut relationship =
+                                ConsolidatedRelationship::definition_to_definition(
+                                    from_definition.file_path.clone(),
+                                    to_definition.file_path.clone(),
+                                );
+                            relationship.target_range = ArcIntern::new(to_definition.range);
+                            relationship.target_definition_range =
+                                Some(ArcIntern::new(to_definition.range));
+                            relationship
+                        }
+                        Some(ResolvedType::Import(import)) => {
+                            let mut relationship =
+                                ConsolidatedRelationship::definition_to_imported_symbol(
+                                    from_definition.file_path.clone(),
+                                    import.location.file_path.clone(),
+                                );
+                            relationship.target_range = ArcIntern::new(import.location.range());
+                            relationship.target_definition_range =
+                                Some(ArcIntern::new(import.location.range()));
+                            relationship
+                        }
+                        None => continue,
+                    };
+                    relationship.relationship_type = RelationshipType::References;
+                    relationship.source_range = ArcIntern::new(usage.range);
+                    relationship.source_definition_range =
+                        Some(ArcIntern::new(from_definition.range));
+                    relationships.push(relationship);
+                }
+            }
+        }
+    }
+
     // Resolve an expression and returns the resolved type.
     pub fn resolve_expression(
         &self,
@@ -153,7 +319,9 @@ impl ExpressionResolver {
                 if let Some(ResolvedType::Definition(target)) = target {
                     return self.resolve_field_access(&target, member);
                 } else if let Some(ResolvedType::Import(import)) = target {
-                    resolutions.import_resolutions.push(import);
+                    resolutions
+                        .import_resolutions
+                        .push((import, RelationshipType::Calls));
                 }
 
                 None
@@ -164,7 +332,9 @@ impl ExpressionResolver {
                 if let Some(ResolvedType::Definition(target)) = target {
                     return self.resolve_method_call(&target, member, resolutions);
                 } else if let Some(ResolvedType::Import(import)) = target {
-                    resolutions.import_resolutions.push(import);
+                    resolutions
+                        .import_resolutions
+                        .push((import, RelationshipType::Calls));
                 }
 
                 None
@@ -178,7 +348,9 @@ impl ExpressionResolver {
                 if let Some(ResolvedType::Definition(target)) = target {
                     return self.resolve_method_call(&target, member, resolutions);
                 } else if let Some(ResolvedType::Import(import)) = target {
-                    resolutions.import_resolutions.push(import);
+                    resolutions
+                        .import_resolutions
+                        .push((import, RelationshipType::Calls));
                 }
 
                 None
@@ -186,12 +358,18 @@ impl ExpressionResolver {
             JavaExpression::Index { target } => {
                 self.resolve_expression(file_path, range, target, resolutions)
             }
-            JavaExpression::ObjectCreation { target } => {
-                self.resolve_constructor_call(file_path, &target.name, resolutions)
-            }
-            JavaExpression::ArrayCreation { target } => {
-                self.resolve_constructor_call(file_path, &target.name, resolutions)
-            }
+            JavaExpression::ObjectCreation { target } => self.resolve_constructor_call(
+                file_path,
+                &target.name,
+                RelationshipType::Instantiates,
+                resolutions,
+            ),
+            JavaExpression::ArrayCreation { target } => self.resolve_constructor_call(
+                file_path,
+                &target.name,
+                RelationshipType::Calls,
+                resolutions,
+            ),
             JavaExpression::ArrayItem { target } => {
                 self.resolve_expression(file_path, range, target, resolutions)
             }
@@ -204,11 +382,15 @@ impl ExpressionResolver {
                 if let Some(resolution) = self.resolve_type(file_path, None, name) {
                     match resolution {
                         ResolvedType::Definition(definition) => {
-                            resolutions.definition_resolutions.push(definition.clone());
+                            resolutions
+                                .definition_resolutions
+                                .push((definition.clone(), RelationshipType::References));
                             return Some(ResolvedType::Definition(definition));
                         }
                         ResolvedType::Import(import) => {
-                            resolutions.import_resolutions.push(import);
+                            resolutions
+                                .import_resolutions
+                                .push((import, RelationshipType::References));
                             return None;
                         }
                     }
@@ -247,12 +429,13 @@ impl ExpressionResolver {
         // Look for method in current class
         let method_fqn = format!("{}.{}", class.fqn, member);
         if let Some(method) = file.methods.get(&method_fqn) {
-            resolutions
-                .definition_resolutions
-                .push(DefinitionResolution {
+            resolutions.definition_resolutions.push((
+                DefinitionResolution {
                     name: method.name.clone(),
                     fqn: method_fqn,
-                });
+                },
+                RelationshipType::Calls,
+            ));
 
             if let Some(resolution) = self.resolve_type(
                 file.file_path.as_str(),
@@ -581,10 +764,14 @@ impl ExpressionResolver {
         None
     }
 
+    /// Resolve `new {type_name}(...)` to the constructor of the type, or the type itself when
+    /// it has no explicit constructor. Object creations are
+    /// [`RelationshipType::Instantiates`] relationships while array creations stay calls.
     pub fn resolve_constructor_call(
         &self,
         file_path: &str,
         type_name: &str,
+        relationship_type: RelationshipType,
         resolutions: &mut Resolutions,
     ) -> Option<ResolvedType> {
         debug!("Resolving Java constructor call {type_name} in file {file_path}.");
@@ -611,17 +798,19 @@ impl ExpressionResolver {
                 {
                     resolutions
                         .definition_resolutions
-                        .push(constructor_resolution.clone());
+                        .push((constructor_resolution.clone(), relationship_type));
                 } else {
                     resolutions
                         .definition_resolutions
-                        .push(class_resolution.clone());
+                        .push((class_resolution.clone(), relationship_type));
                 }
 
                 Some(ResolvedType::Definition(java_type))
             }
             Some(ResolvedType::Import(import)) => {
-                resolutions.import_resolutions.push(import);
+                resolutions
+                    .import_resolutions
+                    .push((import, relationship_type));
                 None
             }
             None => None,
@@ -830,3 +1019,11 @@ impl ExpressionResolver {
         }
     }
 }
+
+/// Names of the types in a declared type, e.g. `Map` and `Foo` for `Map<String, Foo[]>`.
+/// Primitive types are left in and simply don't resolve.
+fn type_names(declared_type: &str) -> impl Iterator<Item = &str> {
+    declared_type
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '.'))
+        .filter(|name| !name.is_empty() && !matches!(*name, "extends" | "super"))
+}
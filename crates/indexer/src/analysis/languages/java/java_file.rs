@@ -5,6 +5,7 @@ use parser_core::java::{
     },
 };
 
+use parser_core::utils::Range;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 
@@ -17,6 +18,16 @@ pub(crate) struct JavaBinding {
     pub init: Option<JavaExpression>,
 }
 
+/// A type named in the declaration of a field or a parameter
+#[derive(Debug, Clone)]
+pub(crate) struct JavaTypeUsage {
+    /// FQN of the class declaring the field, or of the method declaring the parameter
+    pub owner_fqn: String,
+    pub type_name: String,
+    /// Range of the field or parameter declaration
+    pub range: Range,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct JavaMethod {
     pub name: String,
@@ -46,10 +57,16 @@ pub(crate) struct JavaFile {
     pub enum_constants_by_enum: FxHashMap<String, FxHashSet<String>>,
     /// Method FQN -> Method
     pub methods: FxHashMap<String, JavaMethod>,
+    /// Method FQN -> byte ranges of its declarations, overloads share their FQN
+    pub method_declarations: FxHashMap<String, Vec<(u64, u64)>>,
+    /// Method FQN -> start offset and type of the parameters of all its declarations
+    pub method_parameters: FxHashMap<String, Vec<(u64, String)>>,
     /// FQN -> Scope
     pub scopes: FxHashMap<String, ScopeTree>,
     /// Scope FQN -> parent_scope
     pub scope_hierarchy: FxHashMap<String, String>,
+    /// Types named by the fields and parameters declared in the file
+    pub type_usages: Vec<JavaTypeUsage>,
 }
 
 impl JavaFile {
@@ -63,8 +80,11 @@ impl JavaFile {
             classes: FxHashMap::default(),
             enum_constants_by_enum: FxHashMap::default(),
             methods: FxHashMap::default(),
+            method_declarations: FxHashMap::default(),
+            method_parameters: FxHashMap::default(),
             scopes: FxHashMap::default(),
             scope_hierarchy: FxHashMap::default(),
+            type_usages: Vec::new(),
         }
     }
 
@@ -78,8 +98,11 @@ impl JavaFile {
             classes: FxHashMap::default(),
             enum_constants_by_enum: FxHashMap::default(),
             methods: FxHashMap::default(),
+            method_declarations: FxHashMap::default(),
+            method_parameters: FxHashMap::default(),
             scopes: FxHashMap::default(),
             scope_hierarchy: FxHashMap::default(),
+            type_usages: Vec::new(),
         }
     }
 
@@ -131,9 +154,44 @@ impl JavaFile {
             return_type: return_type.name.clone(),
         };
 
+        let method_fqn = java_fqn_to_string(&definition.fqn);
+        self.method_declarations
+            .entry(method_fqn.clone())
+            .or_default()
+            .push((
+                definition.range.byte_offset.0 as u64,
+                definition.range.byte_offset.1 as u64,
+            ));
+
         self.index_scope(definition.fqn.clone(), true);
-        self.methods
-            .insert(java_fqn_to_string(&definition.fqn), method);
+        self.methods.insert(method_fqn, method);
+    }
+
+    /// Parameter types of each declaration of the method, one list per overload
+    pub fn method_signatures(&self, method_fqn: &str) -> Vec<Vec<&str>> {
+        let Some(declarations) = self.method_declarations.get(method_fqn) else {
+            return Vec::new();
+        };
+        let parameters = self
+            .method_parameters
+            .get(method_fqn)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        declarations
+            .iter()
+            .map(|(start, end)| {
+                let mut declaration_parameters: Vec<&(u64, String)> = parameters
+                    .iter()
+                    .filter(|(offset, _)| start <= offset && offset <= end)
+                    .collect();
+                declaration_parameters.sort_by_key(|(offset, _)| *offset);
+                declaration_parameters
+                    .into_iter()
+                    .map(|(_, parameter_type)| parameter_type.as_str())
+                    .collect()
+            })
+            .collect()
     }
 
     pub fn index_enum_constant(&mut self, definition: &JavaDefinitionInfo) {
@@ -163,6 +221,37 @@ impl JavaFile {
             _ => return,
         };
 
+        if let Some(java_type) = &java_type
+            && !matches!(
+                definition.metadata,
+                Some(JavaDefinitionMetadata::LocalVariable { .. })
+            )
+            && definition.fqn.len() > 1
+        {
+            let owner_fqn = definition.fqn[..definition.fqn.len() - 1]
+                .iter()
+                .map(|part| part.node_name.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+            if matches!(
+                definition.metadata,
+                Some(JavaDefinitionMetadata::Parameter { .. })
+            ) {
+                self.method_parameters
+                    .entry(owner_fqn.clone())
+                    .or_default()
+                    .push((
+                        definition.range.byte_offset.0 as u64,
+                        java_type.name.clone(),
+                    ));
+            }
+            self.type_usages.push(JavaTypeUsage {
+                owner_fqn,
+                type_name: java_type.name.clone(),
+                range: definition.range,
+            });
+        }
+
         let binding = JavaBinding {
             range: (
                 definition.range.byte_offset.0 as u64,
@@ -65,8 +65,8 @@ pub async fn setup_java_reference_pipeline(database: &Arc<KuzuDatabase>) -> Java
 
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     let output_dir = local_repo.workspace_path.join("output");
@@ -104,6 +104,7 @@ mod integration_tests {
     use std::sync::Arc;
 
     use crate::analysis::languages::java::tests::setup_java_reference_pipeline;
+    use database::graph::RelationshipType;
     use database::kuzu::database::KuzuDatabase;
     use database::kuzu::service::NodeDatabaseService;
 
@@ -121,26 +122,36 @@ mod integration_tests {
         let node_database_service = NodeDatabaseService::new(&database_instance);
 
         // Main.main -> Traceable
-        let callers_to_traceable = node_database_service
-            .find_calls_to_method("com.example.app.Traceable")
+        let references_to_traceable = node_database_service
+            .find_relationships_to_definition(
+                "com.example.app.Traceable",
+                RelationshipType::References,
+            )
             .unwrap_or_default();
         assert!(
-            callers_to_traceable
+            references_to_traceable
                 .iter()
                 .any(|c| c.ends_with("com.example.app.Main.main")),
             "Main.main should have a Traceable annotation"
         );
+        let callers_to_traceable = node_database_service
+            .find_calls_to_method("com.example.app.Traceable")
+            .unwrap_or_default();
+        assert!(
+            callers_to_traceable.is_empty(),
+            "Annotations should not be calls"
+        );
 
         // Main.main -> new Foo()
-        let callers_to_foo = node_database_service
-            .find_calls_to_method("com.example.app.Foo")
+        let instantiators_of_foo = node_database_service
+            .find_relationships_to_definition("com.example.app.Foo", RelationshipType::Instantiates)
             .unwrap_or_default();
 
         assert!(
-            callers_to_foo
+            instantiators_of_foo
                 .iter()
                 .any(|c| c.ends_with("com.example.app.Main.Main")),
-            "Main.Main should call Foo"
+            "Main.Main should instantiate Foo"
         );
 
         // Main.main -> this.myParameter.bar()
@@ -221,14 +232,17 @@ mod integration_tests {
         );
 
         // Main.main -> Outer.Inner
-        let callers_to_outer_inner = node_database_service
-            .find_calls_to_method("com.example.util.Outer.Inner")
+        let instantiators_of_outer_inner = node_database_service
+            .find_relationships_to_definition(
+                "com.example.util.Outer.Inner",
+                RelationshipType::Instantiates,
+            )
             .unwrap_or_default();
         assert!(
-            callers_to_outer_inner
+            instantiators_of_outer_inner
                 .iter()
                 .any(|c| c.ends_with("com.example.app.Main.main")),
-            "Main.main should call Outer.Inner"
+            "Main.main should instantiate Outer.Inner"
         );
 
         // Main.main -> Outer.Inner.innerMethod
@@ -288,14 +302,18 @@ mod integration_tests {
         let node_database_service = NodeDatabaseService::new(&database_instance);
 
         // Main.main -> java.util.ArrayList
-        let callers_to_array_list = node_database_service
-            .find_calls_to_imported_symbol("java.util", "ArrayList")
+        let instantiators_of_array_list = node_database_service
+            .find_relationships_to_imported_symbol(
+                "java.util",
+                "ArrayList",
+                RelationshipType::Instantiates,
+            )
             .unwrap_or_default();
         assert!(
-            callers_to_array_list
+            instantiators_of_array_list
                 .iter()
                 .any(|c| c.ends_with("com.example.app.Main.main")),
-            "Main.main should call ArrayList"
+            "Main.main should instantiate ArrayList"
         );
 
         // Main.main -> java.util.List.of
@@ -311,7 +329,11 @@ mod integration_tests {
 
         // Traceable -> java.lang.annotation.Retention
         let callers_to_retention = node_database_service
-            .find_calls_to_imported_symbol("java.lang.annotation", "Retention")
+            .find_relationships_to_imported_symbol(
+                "java.lang.annotation",
+                "Retention",
+                RelationshipType::References,
+            )
             .unwrap_or_default();
 
         assert!(
@@ -323,7 +345,11 @@ mod integration_tests {
 
         // Traceable -> java.lang.annotation.Target
         let callers_to_target = node_database_service
-            .find_calls_to_imported_symbol("java.lang.annotation", "Target")
+            .find_relationships_to_imported_symbol(
+                "java.lang.annotation",
+                "Target",
+                RelationshipType::References,
+            )
             .unwrap_or_default();
         assert!(
             callers_to_target
@@ -357,6 +383,82 @@ mod integration_tests {
         );
     }
 
+    #[traced_test]
+    #[tokio::test]
+    async fn test_java_method_overrides() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_java_reference_pipeline(&database).await;
+
+        let database_instance = database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+        let node_database_service = NodeDatabaseService::new(&database_instance);
+
+        // ServerFilter.Filter.apply -> ServerFilter -> Filter.apply
+        let overrides_of_apply = node_database_service
+            .find_relationships_to_definition(
+                "com.example.filter.Filter.apply",
+                RelationshipType::Overrides,
+            )
+            .unwrap_or_default();
+        assert_eq!(
+            overrides_of_apply,
+            vec!["com.example.filter.ServerFilter.Filter.apply".to_string()],
+            "ServerFilter.Filter.apply should override Filter.apply"
+        );
+
+        // LengthMatcher.matches(String, int) only overloads Matcher.matches(String)
+        let overrides_of_matches = node_database_service
+            .find_relationships_to_definition(
+                "com.example.filter.Matcher.matches",
+                RelationshipType::Overrides,
+            )
+            .unwrap_or_default();
+        assert!(
+            overrides_of_matches.is_empty(),
+            "An overload shouldn't override, got {overrides_of_matches:?}"
+        );
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_java_field_and_parameter_type_references() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_java_reference_pipeline(&database).await;
+
+        let database_instance = database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+        let node_database_service = NodeDatabaseService::new(&database_instance);
+
+        // Main has a `Foo myParameter` field
+        let references_to_foo = node_database_service
+            .find_relationships_to_definition("com.example.app.Foo", RelationshipType::References)
+            .unwrap_or_default();
+        assert!(
+            references_to_foo.contains(&"com.example.app.Main".to_string()),
+            "Main should reference Foo through its field, got {references_to_foo:?}"
+        );
+
+        // Foo has an `Executor executor` field, FooFactory.configure an `Executor` parameter
+        let references_to_executor = node_database_service
+            .find_relationships_to_definition(
+                "com.example.app.Executor",
+                RelationshipType::References,
+            )
+            .unwrap_or_default();
+        assert!(references_to_executor.contains(&"com.example.app.Foo".to_string()));
+        assert!(
+            references_to_executor.contains(&"com.example.app.FooFactory.configure".to_string())
+        );
+
+        // Declaring a parameter doesn't call its type
+        let callers_to_executor = node_database_service
+            .find_calls_to_method("com.example.app.Executor")
+            .unwrap_or_default();
+        assert!(!callers_to_executor.contains(&"com.example.app.FooFactory.configure".to_string()));
+    }
+
     #[traced_test]
     #[tokio::test]
     async fn test_java_call_relationship_has_location() {
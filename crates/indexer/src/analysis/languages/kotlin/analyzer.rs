@@ -173,6 +173,8 @@ impl KotlinAnalyzer {
                 relationships.push(relationship);
             }
         }
+
+        self.expression_resolver.resolve_overrides(relationships);
     }
 
     fn get_parent_fqn_string(&self, fqn: &FqnType) -> Option<String> {
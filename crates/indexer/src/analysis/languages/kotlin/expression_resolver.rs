@@ -15,10 +15,15 @@ use std::collections::VecDeque;
 
 use crate::{
     analysis::{
-        languages::kotlin::{
-            kotlin_file::{KotlinBinding, KotlinFile},
-            types::{KotlinScopeTree, ScopeContext},
-            utils::{full_import_path, get_binary_operator_function, get_unary_operator_function},
+        languages::{
+            kotlin::{
+                kotlin_file::{KotlinBinding, KotlinFile},
+                types::{KotlinScopeTree, ScopeContext},
+                utils::{
+                    full_import_path, get_binary_operator_function, get_unary_operator_function,
+                },
+            },
+            signatures::parameters_match,
         },
         types::{
             ConsolidatedRelationship, DefinitionNode, DefinitionType, ImportType,
@@ -52,6 +57,8 @@ const STD_MEMBER_FUNCTIONS: [&str; 14] = [
 pub(crate) struct Resolutions {
     definition_resolutions: Vec<DefinitionResolution>,
     import_resolutions: Vec<ImportResolution>,
+    /// Constructors, or classes without one, invoked to create an instance
+    instantiations: Vec<DefinitionResolution>,
 }
 
 #[derive(Debug, Clone)]
@@ -127,7 +134,15 @@ impl KotlinExpressionResolver {
                     let mut resolutions = Resolutions::default();
                     self.resolve_expression(file_path, &expression, &mut resolutions);
 
-                    for resolved_definition in resolutions.definition_resolutions {
+                    let calls = resolutions
+                        .definition_resolutions
+                        .into_iter()
+                        .map(|resolution| (resolution, RelationshipType::Calls));
+                    let instantiations = resolutions
+                        .instantiations
+                        .into_iter()
+                        .map(|resolution| (resolution, RelationshipType::Instantiates));
+                    for (resolved_definition, relationship_type) in calls.chain(instantiations) {
                         let to_definition = self.definition_nodes.get(&resolved_definition.fqn);
 
                         if let Some(to_definition) = to_definition {
@@ -136,7 +151,7 @@ impl KotlinExpressionResolver {
                                     from_definition.file_path.clone(),
                                     to_definition.file_path.clone(),
                                 );
-                            relationship.relationship_type = RelationshipType::Calls;
+                            relationship.relationship_type = relationship_type;
                             relationship.source_range = ArcIntern::new(from_definition.range);
                             relationship.target_range = ArcIntern::new(to_definition.range);
                             relationships.push(relationship);
@@ -741,12 +756,10 @@ impl KotlinExpressionResolver {
                 // Lookup if there is a constructor for this class.
                 let potenrial_constructor_fqn = format!("{}.{}", class.fqn, "<init>");
                 if let Some(constructor) = self.definition_nodes.get(&potenrial_constructor_fqn) {
-                    resolutions
-                        .definition_resolutions
-                        .push(DefinitionResolution {
-                            name: constructor.name.clone(),
-                            fqn: constructor.fqn.clone(),
-                        });
+                    resolutions.instantiations.push(DefinitionResolution {
+                        name: constructor.name.clone(),
+                        fqn: constructor.fqn.clone(),
+                    });
 
                     return Some(ResolvedType::Definition(DefinitionResolution {
                         name: class.name.clone(),
@@ -755,12 +768,10 @@ impl KotlinExpressionResolver {
                 }
 
                 // Otherwise, resolve the definition class itself.
-                resolutions
-                    .definition_resolutions
-                    .push(DefinitionResolution {
-                        name: class.name.clone(),
-                        fqn: class.fqn.clone(),
-                    });
+                resolutions.instantiations.push(DefinitionResolution {
+                    name: class.name.clone(),
+                    fqn: class.fqn.clone(),
+                });
 
                 return Some(ResolvedType::Definition(DefinitionResolution {
                     name: class.name.clone(),
@@ -833,12 +844,10 @@ impl KotlinExpressionResolver {
         // First check if the member is child class of the type
         let potential_init_fqn = format!("{class_fqn}.{name}.<init>");
         if let Some(init) = file.functions.get(&potential_init_fqn) {
-            resolutions
-                .definition_resolutions
-                .push(DefinitionResolution {
-                    name: init.name.clone(),
-                    fqn: init.fqn.clone(),
-                });
+            resolutions.instantiations.push(DefinitionResolution {
+                name: init.name.clone(),
+                fqn: init.fqn.clone(),
+            });
 
             return Some(ResolvedType::Definition(DefinitionResolution {
                 name: init.name.clone(),
@@ -848,12 +857,10 @@ impl KotlinExpressionResolver {
 
         let potential_fqn = format!("{class_fqn}.{name}");
         if let Some(definition) = file.classes.get(&potential_fqn) {
-            resolutions
-                .definition_resolutions
-                .push(DefinitionResolution {
-                    name: definition.name.clone(),
-                    fqn: definition.fqn.clone(),
-                });
+            resolutions.instantiations.push(DefinitionResolution {
+                name: definition.name.clone(),
+                fqn: definition.fqn.clone(),
+            });
 
             return Some(ResolvedType::Definition(DefinitionResolution {
                 name: definition.name.clone(),
@@ -1460,12 +1467,10 @@ impl KotlinExpressionResolver {
                     let potential_constructor_fqn = format!("{}.{}", definition.fqn, "<init>");
                     if let Some(constructor) = self.definition_nodes.get(&potential_constructor_fqn)
                     {
-                        resolutions
-                            .definition_resolutions
-                            .push(DefinitionResolution {
-                                name: constructor.name.clone(),
-                                fqn: constructor.fqn.clone(),
-                            });
+                        resolutions.instantiations.push(DefinitionResolution {
+                            name: constructor.name.clone(),
+                            fqn: constructor.fqn.clone(),
+                        });
 
                         // Resolve to the definition class itself.
                         return Some(ResolvedType::Definition(DefinitionResolution {
@@ -1474,12 +1479,10 @@ impl KotlinExpressionResolver {
                         }));
                     }
 
-                    resolutions
-                        .definition_resolutions
-                        .push(DefinitionResolution {
-                            name: definition.name.clone(),
-                            fqn: definition.fqn.clone(),
-                        });
+                    resolutions.instantiations.push(DefinitionResolution {
+                        name: definition.name.clone(),
+                        fqn: definition.fqn.clone(),
+                    });
 
                     return Some(ResolvedType::Definition(DefinitionResolution {
                         name: definition.name.clone(),
@@ -1597,6 +1600,95 @@ impl KotlinExpressionResolver {
         None
     }
 
+    /// Link the member functions of every class to the super type functions they override.
+    /// Functions are matched by name and parameter types since their FQNs don't include the
+    /// parameter types, an overload with other parameters isn't an override.
+    pub fn resolve_overrides(&self, relationships: &mut Vec<ConsolidatedRelationship>) {
+        for file in self.files.values() {
+            for function in file.functions.values() {
+                if function.receiver_type.is_some() {
+                    continue;
+                }
+                let Some((class_fqn, _)) = function.fqn.rsplit_once('.') else {
+                    continue;
+                };
+                let Some(class) = file.classes.get(class_fqn) else {
+                    continue;
+                };
+                let Some(from_definition) = self.definition_nodes.get(&function.fqn) else {
+                    continue;
+                };
+                if !Self::is_function(from_definition) {
+                    continue;
+                }
+
+                let signatures = file.function_signatures(&function.fqn);
+                let super_types = class.super_class.iter().chain(&class.super_interfaces);
+                let mut overridden_fqns = FxHashSet::default();
+                for super_type in super_types {
+                    let Some(ResolvedType::Definition(super_class)) =
+                        self.resolve_type_reference(super_type, Some(&class.fqn), &file.file_path)
+                    else {
+                        continue;
+                    };
+
+                    // Nearest declaration with the same parameters along this super type
+                    let overridden = self
+                        .collect_ancestors_in_order(&super_class.fqn)
+                        .into_iter()
+                        .map(|ancestor_fqn| format!("{ancestor_fqn}.{}", function.name))
+                        .find_map(|fqn| {
+                            self.definition_nodes
+                                .get(&fqn)
+                                .filter(|definition| Self::is_function(definition))
+                                .filter(|definition| {
+                                    self.has_matching_signature(definition, &fqn, &signatures)
+                                })
+                        });
+                    let Some(to_definition) = overridden else {
+                        continue;
+                    };
+                    if !overridden_fqns.insert(to_definition.fqn.clone()) {
+                        continue;
+                    }
+
+                    let mut relationship = ConsolidatedRelationship::definition_to_definition(
+                        from_definition.file_path.clone(),
+                        to_definition.file_path.clone(),
+                    );
+                    relationship.relationship_type = RelationshipType::Overrides;
+                    relationship.source_range = ArcIntern::new(from_definition.range);
+                    relationship.target_range = ArcIntern::new(to_definition.range);
+                    relationships.push(relationship);
+                }
+            }
+        }
+    }
+
+    /// Whether a declaration of the function `fqn` takes the parameters of one of `signatures`
+    fn has_matching_signature(
+        &self,
+        definition: &DefinitionNode,
+        fqn: &str,
+        signatures: &[Vec<&str>],
+    ) -> bool {
+        let Some(file) = self.files.get(&definition.file_path) else {
+            return false;
+        };
+        file.function_signatures(fqn).iter().any(|super_signature| {
+            signatures
+                .iter()
+                .any(|signature| parameters_match(signature, super_signature))
+        })
+    }
+
+    fn is_function(definition: &DefinitionNode) -> bool {
+        matches!(
+            definition.definition_type,
+            DefinitionType::Kotlin(KotlinDefinitionType::Function)
+        )
+    }
+
     fn collect_ancestors_in_order(&self, start_fqn: &str) -> Vec<String> {
         let mut order: Vec<String> = Vec::new();
         let mut visited: FxHashSet<String> = FxHashSet::default();
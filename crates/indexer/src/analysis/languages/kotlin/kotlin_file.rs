@@ -70,6 +70,10 @@ pub(crate) struct KotlinFile {
     pub classes: FxHashMap<String, KotlinClass>,
     /// FQN -> functions
     pub functions: FxHashMap<String, KotlinFunction>,
+    /// Function FQN -> byte ranges of its declarations, overloads share their FQN
+    pub function_declarations: FxHashMap<String, Vec<(usize, usize)>>,
+    /// Function FQN -> start offset and type of the parameters of all its declarations
+    pub function_parameters: FxHashMap<String, Vec<(usize, String)>>,
     /// FQN -> constants
     pub constants: FxHashMap<String, KotlinBinding>,
     /// FQN -> enum FQN
@@ -90,6 +94,8 @@ impl KotlinFile {
             wildcard_imports: FxHashSet::default(),
             classes: FxHashMap::default(),
             functions: FxHashMap::default(),
+            function_declarations: FxHashMap::default(),
+            function_parameters: FxHashMap::default(),
             constants: FxHashMap::default(),
             enum_entries_by_enum: FxHashMap::default(),
             scopes: FxHashMap::default(),
@@ -106,6 +112,8 @@ impl KotlinFile {
             wildcard_imports: FxHashSet::default(),
             classes: FxHashMap::default(),
             functions: FxHashMap::default(),
+            function_declarations: FxHashMap::default(),
+            function_parameters: FxHashMap::default(),
             constants: FxHashMap::default(),
             enum_entries_by_enum: FxHashMap::default(),
             scopes: FxHashMap::default(),
@@ -204,11 +212,43 @@ impl KotlinFile {
             init: init.clone(),
         };
 
+        self.function_declarations
+            .entry(function.fqn.clone())
+            .or_default()
+            .push(definition.range.byte_offset);
+
         self.index_scope(definition.fqn.clone(), true);
         self.functions
             .insert(kotlin_fqn_to_string(&definition.fqn), function);
     }
 
+    /// Parameter types of each declaration of the function, one list per overload
+    pub fn function_signatures(&self, function_fqn: &str) -> Vec<Vec<&str>> {
+        let Some(declarations) = self.function_declarations.get(function_fqn) else {
+            return Vec::new();
+        };
+        let parameters = self
+            .function_parameters
+            .get(function_fqn)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        declarations
+            .iter()
+            .map(|(start, end)| {
+                let mut declaration_parameters: Vec<&(usize, String)> = parameters
+                    .iter()
+                    .filter(|(offset, _)| start <= offset && offset <= end)
+                    .collect();
+                declaration_parameters.sort_by_key(|(offset, _)| *offset);
+                declaration_parameters
+                    .into_iter()
+                    .map(|(_, parameter_type)| parameter_type.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
     fn index_binding(&mut self, definition: &KotlinDefinitionInfo) {
         let (receiver_type, binding_type, init, range) = match &definition.metadata {
             Some(KotlinDefinitionMetadata::Field {
@@ -224,6 +264,21 @@ impl KotlinFile {
             _ => return,
         };
 
+        if let Some(KotlinDefinitionMetadata::Parameter { parameter_type, .. }) =
+            &definition.metadata
+            && definition.fqn.len() > 1
+        {
+            let function_fqn = definition.fqn[..definition.fqn.len() - 1]
+                .iter()
+                .map(|part| part.node_name.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+            self.function_parameters
+                .entry(function_fqn)
+                .or_default()
+                .push((definition.range.byte_offset.0, parameter_type.clone()));
+        }
+
         let binding = KotlinBinding {
             range: (range.byte_offset.0, range.byte_offset.1),
             receiver_type: receiver_type.clone(),
@@ -67,8 +67,8 @@ pub async fn setup_kotlin_reference_pipeline(
 
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     let output_dir = local_repo.workspace_path.join("output");
@@ -106,6 +106,7 @@ mod integration_tests {
     use std::sync::Arc;
 
     use crate::analysis::languages::kotlin::tests::setup_kotlin_reference_pipeline;
+    use database::graph::RelationshipType;
     use database::kuzu::database::KuzuDatabase;
     use database::kuzu::service::NodeDatabaseService;
 
@@ -123,14 +124,14 @@ mod integration_tests {
         let node_database_service = NodeDatabaseService::new(&database_instance);
 
         // Main.main -> Foo() constructor
-        let callers_to_foo_constructor = node_database_service
-            .find_calls_to_method("com.example.foo.Foo")
+        let instantiators_of_foo = node_database_service
+            .find_relationships_to_definition("com.example.foo.Foo", RelationshipType::Instantiates)
             .unwrap_or_default();
         assert!(
-            callers_to_foo_constructor
+            instantiators_of_foo
                 .iter()
                 .any(|c| c.ends_with("com.example.main")),
-            "Main function should call Foo constructor"
+            "Main function should instantiate Foo"
         );
 
         // Main.main -> foo.foo() instance method
@@ -253,6 +254,48 @@ mod integration_tests {
         setup.cleanup();
     }
 
+    #[traced_test]
+    #[tokio::test]
+    async fn test_kotlin_function_overrides() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_kotlin_reference_pipeline(&database).await;
+
+        let database_instance = database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+        let node_database_service = NodeDatabaseService::new(&database_instance);
+
+        // User.getName and Admin.getName -> Person.getName
+        let mut overrides_of_get_name = node_database_service
+            .find_relationships_to_definition(
+                "com.example.entites.Person.getName",
+                RelationshipType::Overrides,
+            )
+            .unwrap_or_default();
+        overrides_of_get_name.sort();
+        assert_eq!(
+            overrides_of_get_name,
+            vec![
+                "com.example.entites.Admin.getName".to_string(),
+                "com.example.entites.User.getName".to_string(),
+            ]
+        );
+
+        // Admin.greet(String, Int) only overloads Person.greet(String)
+        let overrides_of_greet = node_database_service
+            .find_relationships_to_definition(
+                "com.example.entites.Person.greet",
+                RelationshipType::Overrides,
+            )
+            .unwrap_or_default();
+        assert!(
+            overrides_of_greet.is_empty(),
+            "An overload shouldn't override, got {overrides_of_greet:?}"
+        );
+
+        setup.cleanup();
+    }
+
     #[traced_test]
     #[tokio::test]
     async fn test_kotlin_type_inference_from_if_expression() {
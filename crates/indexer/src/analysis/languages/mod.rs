@@ -4,4 +4,5 @@ pub mod kotlin;
 pub mod python;
 pub mod ruby;
 pub mod rust;
+pub mod signatures;
 pub mod typescript;
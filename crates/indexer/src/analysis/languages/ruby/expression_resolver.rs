@@ -345,7 +345,7 @@ impl ExpressionResolver {
         let mut created_relationships: std::collections::HashSet<String> =
             std::collections::HashSet::new();
 
-        for symbol in symbols.iter() {
+        for (index, symbol) in symbols.iter().enumerate() {
             let resolution = self.resolve_single_symbol(symbol, context, current_type.as_deref());
 
             // Create call relationship if we found a definition
@@ -378,7 +378,12 @@ impl ExpressionResolver {
                                         calling_definition.file_path.clone(),
                                         definition.file_path.clone(),
                                     );
-                                call_relationship.relationship_type = RelationshipType::Calls;
+                                call_relationship.relationship_type =
+                                    if Self::is_instantiation(definition, symbols.get(index + 1)) {
+                                        RelationshipType::Instantiates
+                                    } else {
+                                        RelationshipType::Calls
+                                    };
                                 call_relationship.source_range = ArcIntern::new(symbol.range);
                                 call_relationship.target_range = ArcIntern::new(definition.range);
                                 call_relationship.source_definition_range =
@@ -418,6 +423,24 @@ impl ExpressionResolver {
             .unwrap_or(InferredType::Unknown)
     }
 
+    /// Whether `definition` is a class that `next_symbol` creates an instance of, as in `User.new`
+    fn is_instantiation(
+        definition: &DefinitionNode,
+        next_symbol: Option<&RubyExpressionSymbol>,
+    ) -> bool {
+        matches!(
+            definition.definition_type,
+            DefinitionType::Ruby(RubyDefinitionType::Class)
+        ) && next_symbol.is_some_and(|next_symbol| {
+            let name: &str = &next_symbol.name;
+            name == "new"
+                && matches!(
+                    next_symbol.symbol_type,
+                    RubySymbolType::MethodCall | RubySymbolType::SafeMethodCall
+                )
+        })
+    }
+
     /// Check if a method call is a framework method that should be filtered out
     /// TODO: find better ways to do this
     fn is_framework_method(&self, method_name: &str, receiver_type: Option<&str>) -> bool {
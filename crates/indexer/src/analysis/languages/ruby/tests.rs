@@ -58,9 +58,9 @@ async fn setup_ruby_reference_pipeline(database: &Arc<KuzuDatabase>) -> RubyRefe
 
     // Configure indexing for Ruby files with Ruby-specific settings
     let config = IndexingConfig {
-        worker_threads: 1, // Use single thread for deterministic testing
-        max_file_size: 5_000_000,
+        worker_threads: 1,        // Use single thread for deterministic testing
         respect_gitignore: false, // Don't use gitignore in tests
+        ..Default::default()
     };
 
     // Create output directory for this test
@@ -1017,3 +1017,33 @@ async fn test_ruby_method_resolution_accuracy() {
         "Profile::create_default must have callers"
     );
 }
+
+#[traced_test]
+#[tokio::test]
+async fn test_ruby_new_is_an_instantiation() {
+    let database = Arc::new(KuzuDatabase::new());
+    let setup = setup_ruby_reference_pipeline(&database).await;
+
+    let database_instance = database
+        .get_or_create_database(&setup.database_path, None)
+        .expect("Failed to create database");
+    let node_database_service = NodeDatabaseService::new(&database_instance);
+
+    // Session.new(user)
+    let instantiators_of_session = node_database_service
+        .find_relationships_to_definition("Session", RelationshipType::Instantiates)
+        .unwrap_or_default();
+    assert!(
+        instantiators_of_session.contains(&"AuthService::create_session".to_string()),
+        "AuthService::create_session should instantiate Session"
+    );
+    assert!(
+        !node_database_service
+            .find_calls_to_method("Session")
+            .unwrap_or_default()
+            .contains(&"AuthService::create_session".to_string()),
+        "Session.new should not be a call to Session"
+    );
+
+    database.drop_database(&setup.database_path);
+}
@@ -0,0 +1,71 @@
+//! Compares the parameter lists of method declarations, to tell an override from an overload
+//! sharing its name. The FQNs of the methods don't include their parameter types.
+
+/// Whether two declarations take the same parameters. Types are compared by their simple
+/// name without type arguments, and a type variable, e.g. `T` in `accept(T item)`, matches any
+/// type since it can be bound to a concrete type by the subclass.
+pub(crate) fn parameters_match<A: AsRef<str>, B: AsRef<str>>(a: &[A], b: &[B]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| types_match(a.as_ref(), b.as_ref()))
+}
+
+fn types_match(a: &str, b: &str) -> bool {
+    let (a, b) = (erase(a), erase(b));
+    a == b || is_type_variable(&a) || is_type_variable(&b)
+}
+
+/// `java.util.List<Foo>[]` as `List[]`, `String?` as `String` and `String...` as `String[]`
+fn erase(type_name: &str) -> String {
+    let mut erased = String::with_capacity(type_name.len());
+    let mut depth = 0usize;
+    for c in type_name.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            '?' => {}
+            c if depth == 0 && !c.is_whitespace() => erased.push(c),
+            _ => {}
+        }
+    }
+    let erased = erased.replace("...", "[]");
+    let suffix_start = erased.find('[').unwrap_or(erased.len());
+    let (name, suffix) = erased.split_at(suffix_start);
+    let simple_name = name.rsplit(['.', ':']).next().unwrap_or(name);
+    format!("{simple_name}{suffix}")
+}
+
+/// A single upper case letter, optionally followed by a digit, e.g. `T` or `T2`
+fn is_type_variable(type_name: &str) -> bool {
+    let mut chars = type_name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && match (chars.next(), chars.next()) {
+            (None, _) => true,
+            (Some(c), None) => c.is_ascii_digit(),
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters_match() {
+        assert!(parameters_match::<&str, &str>(&[], &[]));
+        assert!(parameters_match(&["String", "int"], &["String", "int"]));
+        assert!(parameters_match(&["java.util.List<Foo>"], &["List<Bar>"]));
+        assert!(parameters_match(&["String..."], &["String[]"]));
+        assert!(parameters_match(&["String?"], &["String"]));
+        assert!(parameters_match(&["T"], &["Order"]));
+    }
+
+    #[test]
+    fn test_overloads_do_not_match() {
+        assert!(!parameters_match(&["String"], &["String", "int"]));
+        assert!(!parameters_match(&["String"], &["int"]));
+        assert!(!parameters_match(&["String[]"], &["String"]));
+        assert!(!parameters_match(&["Order"], &["OrderId"]));
+    }
+}
@@ -1,9 +1,10 @@
 use crate::analysis::types::{
     ConsolidatedRelationship, DefinitionNode, DefinitionType, FqnType, ImportIdentifier,
-    ImportType, ImportedSymbolLocation, ImportedSymbolNode, RelationshipKind,
+    ImportType, ImportedSymbolLocation, ImportedSymbolNode, OptimizedFileTree, RelationshipKind,
 };
-use crate::parsing::processor::{FileProcessingResult, References};
-use database::graph::RelationshipType;
+use crate::parsing::processor::{FileProcessingResult, References, TypeScriptReference};
+use crate::parsing::reference_syntax::TypeScriptReferenceSyntax;
+use database::graph::{DefinitionCategory, RelationshipType};
 use internment::ArcIntern;
 use parser_core::typescript::{
     ast::typescript_fqn_to_string,
@@ -12,6 +13,10 @@ use parser_core::typescript::{
 };
 use parser_core::utils::Range;
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Extensions tried, in order, for an import path without one
+const MODULE_EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mts", "cts", "mjs", "cjs"];
 
 // Handles Python-specific analysis operations
 pub struct TypeScriptAnalyzer;
@@ -135,7 +140,11 @@ impl TypeScriptAnalyzer {
         if let Some(analyzer_references) = file_references {
             let iter_refs = analyzer_references.iter_typescript();
             if let Some(iter_refs) = iter_refs {
-                for reference in iter_refs {
+                for TypeScriptReference {
+                    info: reference,
+                    syntax,
+                } in iter_refs
+                {
                     let target_defn = match &reference.target {
                         TypeScriptReferenceTarget::Resolved(target) => target,
                         _ => continue,
@@ -144,13 +153,15 @@ impl TypeScriptAnalyzer {
                         TypeScriptTargetResolution::Definition(definition) => definition,
                         _ => continue,
                     };
+                    let relationship_type =
+                        Self::reference_relationship_type(*syntax, target_defn.definition_type);
 
                     let Some(scope) = &reference.scope else {
                         let mut relationship = ConsolidatedRelationship::file_to_definition(
                             relative_file_path.to_string(),
                             relative_file_path.to_string(),
                         );
-                        relationship.relationship_type = RelationshipType::Calls;
+                        relationship.relationship_type = relationship_type;
                         relationship.source_range = ArcIntern::new(Range::empty());
                         relationship.target_range = ArcIntern::new(target_defn.range);
                         relationships.push(relationship);
@@ -165,7 +176,7 @@ impl TypeScriptAnalyzer {
                         source_path: Some(ArcIntern::new(relative_file_path.to_string())),
                         target_path: Some(ArcIntern::new(relative_file_path.to_string())),
                         kind: RelationshipKind::DefinitionToDefinition,
-                        relationship_type: RelationshipType::Calls,
+                        relationship_type,
                         source_range: ArcIntern::new(reference.range), // Call site location for source_start_line etc
                         target_range: ArcIntern::new(target_defn.range),
                         source_definition_range: Some(ArcIntern::new(scope_range)), // Source definition range for ID lookup
@@ -179,6 +190,29 @@ impl TypeScriptAnalyzer {
         }
     }
 
+    /// References in type positions, and to interfaces and enums which can only be used as
+    /// types or values, never invoked, are [`RelationshipType::References`] rather than calls.
+    /// Only the class of a `new` expression is [`RelationshipType::Instantiates`], other uses of
+    /// a class, e.g. calling its static methods, stay calls.
+    fn reference_relationship_type(
+        syntax: TypeScriptReferenceSyntax,
+        definition_type: TypeScriptDefinitionType,
+    ) -> RelationshipType {
+        let category = DefinitionType::TypeScript(definition_type).category();
+        match syntax {
+            TypeScriptReferenceSyntax::Type => RelationshipType::References,
+            TypeScriptReferenceSyntax::New if category == DefinitionCategory::Class => {
+                RelationshipType::Instantiates
+            }
+            _ => match category {
+                DefinitionCategory::Interface | DefinitionCategory::Enum => {
+                    RelationshipType::References
+                }
+                _ => RelationshipType::Calls,
+            },
+        }
+    }
+
     /// Create definition-to-definition and definition-to-imported-symbol relationships using definitions map
     pub fn add_definition_relationships(
         &self,
@@ -230,6 +264,52 @@ impl TypeScriptAnalyzer {
         }
     }
 
+    /// Resolve relative imports (`./utils`, `../lib/index.js`) to the files they point to,
+    /// and named imports to the top-level definitions of those files. Package imports are
+    /// left unresolved.
+    pub fn resolve_imported_symbols(
+        &self,
+        imported_symbol_map: &HashMap<(String, String), Vec<ImportedSymbolNode>>,
+        definition_map: &HashMap<(String, String), (DefinitionNode, FqnType)>,
+        file_tree: &OptimizedFileTree,
+        imported_symbol_to_definitions: &mut HashMap<ImportedSymbolLocation, Vec<DefinitionNode>>,
+        imported_symbol_to_files: &mut HashMap<ImportedSymbolLocation, Vec<String>>,
+    ) {
+        for imported_symbol_node in imported_symbol_map.values().flatten() {
+            if !matches!(imported_symbol_node.import_type, ImportType::TypeScript(_)) {
+                continue;
+            }
+
+            let Some(target_file) = resolve_relative_import(
+                &imported_symbol_node.import_path,
+                &imported_symbol_node.location.file_path,
+                file_tree,
+            ) else {
+                continue;
+            };
+
+            let target_definition =
+                imported_symbol_node
+                    .identifier
+                    .as_ref()
+                    .and_then(|identifier| {
+                        definition_map.get(&(identifier.name.clone(), target_file.clone()))
+                    });
+
+            match target_definition {
+                Some((definition_node, _)) => imported_symbol_to_definitions
+                    .entry(imported_symbol_node.location.clone())
+                    .or_default()
+                    .push(definition_node.clone()),
+                // Default, namespace and side-effect imports point at the module itself
+                None => imported_symbol_to_files
+                    .entry(imported_symbol_node.location.clone())
+                    .or_default()
+                    .push(target_file),
+            }
+        }
+    }
+
     /// Create an imported symbol location from an imported symbol info
     fn create_imported_symbol_location(
         &self,
@@ -343,3 +423,104 @@ impl TypeScriptAnalyzer {
         }
     }
 }
+
+/// Resolve a relative import specifier to a file in `file_tree`, following the
+/// TypeScript/Node lookup order: the exact path, the path with a known extension, then an
+/// `index` file in the directory. `./foo.js` also matches `foo.ts`, as emitted by ESM projects.
+fn resolve_relative_import(
+    import_path: &str,
+    importing_file: &str,
+    file_tree: &OptimizedFileTree,
+) -> Option<String> {
+    if !(import_path.starts_with("./") || import_path.starts_with("../")) {
+        return None;
+    }
+
+    let importing_dir = Path::new(importing_file).parent().unwrap_or(Path::new(""));
+    let base = normalize_path(&importing_dir.join(import_path))?;
+    let base = base.to_string_lossy().to_lowercase();
+
+    let mut candidates = vec![base.clone()];
+    if let Some(stem) = base
+        .strip_suffix(".js")
+        .or_else(|| base.strip_suffix(".jsx"))
+    {
+        candidates.extend(["ts", "tsx"].iter().map(|ext| format!("{stem}.{ext}")));
+    }
+    candidates.extend(MODULE_EXTENSIONS.iter().map(|ext| format!("{base}.{ext}")));
+    candidates.extend(
+        MODULE_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{base}/index.{ext}")),
+    );
+
+    candidates
+        .iter()
+        .find_map(|candidate| file_tree.get_denormalized_file(candidate).cloned())
+}
+
+/// Collapse `.` and `..` components of a repository relative path. Returns `None` for paths
+/// that escape the repository.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(part) => normalized.push(part),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_tree(files: &[&str]) -> OptimizedFileTree {
+        let files: Vec<String> = files.iter().map(|file| file.to_string()).collect();
+        OptimizedFileTree::new(files.iter())
+    }
+
+    #[test]
+    fn test_resolve_relative_import() {
+        let tree = file_tree(&[
+            "src/app.ts",
+            "src/utils.ts",
+            "src/components/Button.tsx",
+            "src/lib/index.ts",
+            "src/legacy.js",
+        ]);
+
+        let resolve = |import_path: &str| resolve_relative_import(import_path, "src/app.ts", &tree);
+        assert_eq!(resolve("./utils"), Some("src/utils.ts".to_string()));
+        assert_eq!(resolve("./utils.js"), Some("src/utils.ts".to_string()));
+        assert_eq!(
+            resolve("./components/Button"),
+            Some("src/components/Button.tsx".to_string())
+        );
+        assert_eq!(resolve("./lib"), Some("src/lib/index.ts".to_string()));
+        assert_eq!(resolve("./legacy.js"), Some("src/legacy.js".to_string()));
+        assert_eq!(resolve("./missing"), None);
+        assert_eq!(resolve("react"), None);
+    }
+
+    #[test]
+    fn test_resolve_parent_relative_import() {
+        let tree = file_tree(&["src/utils.ts", "src/components/Button.tsx"]);
+
+        assert_eq!(
+            resolve_relative_import("../utils", "src/components/Button.tsx", &tree),
+            Some("src/utils.ts".to_string())
+        );
+        assert_eq!(
+            resolve_relative_import("../../../outside", "src/components/Button.tsx", &tree),
+            None
+        );
+    }
+}
@@ -1,6 +1,7 @@
 pub mod files;
 pub mod languages;
 pub mod types;
+pub mod visibility;
 
 use crate::analysis::types::{
     ConsolidatedRelationship, DefinitionNode, DirectoryNode, FileNode, FqnType, GraphData,
@@ -19,7 +20,7 @@ use std::{
 
 // Re-export the sub-module functionality
 pub use files::FileSystemAnalyzer;
-pub use languages::csharp::CSharpAnalyzer;
+pub use languages::csharp::{CSharpAnalyzer, CSharpDefinitionIndex};
 pub use languages::java::JavaAnalyzer;
 pub use languages::kotlin::KotlinAnalyzer;
 pub use languages::python::PythonAnalyzer;
@@ -39,6 +40,8 @@ pub struct AnalysisService {
     csharp_analyzer: CSharpAnalyzer,
     typescript_analyzer: TypeScriptAnalyzer,
     rust_analyzer: RustAnalyzer,
+    resolve_references: bool,
+    public_only: bool,
 }
 
 impl AnalysisService {
@@ -65,9 +68,25 @@ impl AnalysisService {
             csharp_analyzer,
             typescript_analyzer,
             rust_analyzer,
+            resolve_references: true,
+            public_only: false,
         }
     }
 
+    /// Skip the resolution of references, e.g. calls, when false, so that only the structural
+    /// graph is built
+    pub fn with_resolve_references(mut self, resolve_references: bool) -> Self {
+        self.resolve_references = resolve_references;
+        self
+    }
+
+    /// Remove the definitions that aren't visible outside of the project once the graph is
+    /// built, see [`visibility`]
+    pub fn with_public_only(mut self, public_only: bool) -> Self {
+        self.public_only = public_only;
+        self
+    }
+
     /// Analyze file processing results and transform them into graph data
     pub fn analyze_results(
         mut self,
@@ -91,8 +110,14 @@ impl AnalysisService {
         let mut created_directories = HashSet::new();
         let mut created_dir_relationships = HashSet::new();
 
+        if !self.resolve_references {
+            log::info!("Skipping reference resolution, only the structural graph is built");
+        }
+
         let results_by_language = self.group_results_by_language(file_results);
         for (language, results) in results_by_language {
+            let _span =
+                tracing::info_span!("analyze_language", ?language, files = results.len()).entered();
             let mut definition_map = HashMap::new(); // (fqn_str, file_path) -> (node, fqn)
             let mut imported_symbol_map = HashMap::new(); // (fqn_str, file_path) -> [node, ...]
             let mut imported_symbol_to_imported_symbols = HashMap::new();
@@ -134,7 +159,7 @@ impl AnalysisService {
                 &imported_symbol_map,
                 &mut relationships,
             );
-            if language == SupportedLanguage::Python {
+            if Self::uses_file_tree(language) {
                 let file_tree =
                     OptimizedFileTree::new(file_references.iter().map(|(path, _)| path));
 
@@ -149,6 +174,9 @@ impl AnalysisService {
                     &mut relationships,
                 );
             }
+            if !self.resolve_references {
+                continue;
+            }
             self.extract_reference_relationships(
                 language,
                 file_references,
@@ -161,26 +189,40 @@ impl AnalysisService {
             );
         }
 
+        let mut graph_data = GraphData {
+            directory_nodes,
+            file_nodes,
+            definition_nodes,
+            imported_symbol_nodes,
+            relationships,
+        };
+        if self.public_only {
+            let _span = tracing::info_span!("retain_public_definitions").entered();
+            visibility::retain_public_definitions(&mut graph_data);
+        }
+
         let analysis_time = start_time.elapsed();
         log::info!(
             "Analysis completed in {:?}: {} directories, {} files, {} definitions ({} total locations), {} imported symbols ({} total locations), {} total relationships",
             analysis_time,
-            directory_nodes.len(),
-            file_nodes.len(),
-            definition_nodes.len(),
-            definition_nodes.iter().map(|_d| 1).sum::<usize>(),
-            imported_symbol_nodes.len(),
-            imported_symbol_nodes.iter().map(|_i| 1).sum::<usize>(),
-            relationships.len()
+            graph_data.directory_nodes.len(),
+            graph_data.file_nodes.len(),
+            graph_data.definition_nodes.len(),
+            graph_data
+                .definition_nodes
+                .iter()
+                .map(|_d| 1)
+                .sum::<usize>(),
+            graph_data.imported_symbol_nodes.len(),
+            graph_data
+                .imported_symbol_nodes
+                .iter()
+                .map(|_i| 1)
+                .sum::<usize>(),
+            graph_data.relationships.len()
         );
 
-        Ok(GraphData {
-            directory_nodes,
-            file_nodes,
-            definition_nodes,
-            imported_symbol_nodes,
-            relationships,
-        })
+        Ok(graph_data)
     }
 
     fn group_results_by_language(
@@ -342,6 +384,14 @@ impl AnalysisService {
         }
     }
 
+    /// Whether imports of `language` are resolved to files through an [`OptimizedFileTree`]
+    fn uses_file_tree(language: SupportedLanguage) -> bool {
+        matches!(
+            language,
+            SupportedLanguage::Python | SupportedLanguage::TypeScript
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn extract_import_relationships(
         &mut self,
@@ -357,64 +407,71 @@ impl AnalysisService {
         imported_symbol_to_files: &mut HashMap<ImportedSymbolLocation, Vec<String>>,
         relationships: &mut Vec<ConsolidatedRelationship>,
     ) {
-        if language == SupportedLanguage::Python {
-            // Maps imported symbols to their sources (e.g. a definition, another imported symbol, etc.)
-            self.python_analyzer.resolve_imported_symbols(
+        // Maps imported symbols to their sources (e.g. a definition, another imported symbol, etc.)
+        match language {
+            SupportedLanguage::Python => self.python_analyzer.resolve_imported_symbols(
                 imported_symbol_map,
                 definition_map,
                 &file_tree,
                 imported_symbol_to_imported_symbols,
                 imported_symbol_to_definitions,
                 imported_symbol_to_files,
-            );
+            ),
+            SupportedLanguage::TypeScript => self.typescript_analyzer.resolve_imported_symbols(
+                imported_symbol_map,
+                definition_map,
+                &file_tree,
+                imported_symbol_to_definitions,
+                imported_symbol_to_files,
+            ),
+            _ => return,
+        }
 
-            // Create imported symbol -> imported symbol relationships
-            for (source_location, target_imported_symbols) in imported_symbol_to_imported_symbols {
-                for target_imported_symbol in target_imported_symbols {
-                    let source_range = source_location.range();
-                    let target_range = target_imported_symbol.location.range();
-                    let mut relationship = ConsolidatedRelationship::import_to_import(
-                        source_location.file_path.clone(),
-                        target_imported_symbol.location.file_path.clone(),
-                    );
-                    relationship.source_range = ArcIntern::new(source_range);
-                    relationship.target_range = ArcIntern::new(target_range);
-                    relationship.relationship_type =
-                        RelationshipType::ImportedSymbolToImportedSymbol;
-                    relationships.push(relationship);
-                }
+        // Create imported symbol -> imported symbol relationships
+        for (source_location, target_imported_symbols) in imported_symbol_to_imported_symbols {
+            for target_imported_symbol in target_imported_symbols {
+                let source_range = source_location.range();
+                let target_range = target_imported_symbol.location.range();
+                let mut relationship = ConsolidatedRelationship::import_to_import(
+                    source_location.file_path.clone(),
+                    target_imported_symbol.location.file_path.clone(),
+                );
+                relationship.source_range = ArcIntern::new(source_range);
+                relationship.target_range = ArcIntern::new(target_range);
+                relationship.relationship_type = RelationshipType::ImportedSymbolToImportedSymbol;
+                relationships.push(relationship);
             }
+        }
 
-            // Create imported symbol -> definition relationships
-            for (source_location, target_definitions) in imported_symbol_to_definitions {
-                for target_definition in target_definitions {
-                    let source_range = source_location.range();
-                    let target_range = target_definition.range;
-                    let mut relationship = ConsolidatedRelationship::import_to_definition(
-                        source_location.file_path.clone(),
-                        target_definition.file_path.clone(),
-                    );
-                    relationship.source_range = ArcIntern::new(source_range);
-                    relationship.target_range = ArcIntern::new(target_range);
-                    relationship.relationship_type = RelationshipType::ImportedSymbolToDefinition;
-                    relationships.push(relationship);
-                }
+        // Create imported symbol -> definition relationships
+        for (source_location, target_definitions) in imported_symbol_to_definitions {
+            for target_definition in target_definitions {
+                let source_range = source_location.range();
+                let target_range = target_definition.range;
+                let mut relationship = ConsolidatedRelationship::import_to_definition(
+                    source_location.file_path.clone(),
+                    target_definition.file_path.clone(),
+                );
+                relationship.source_range = ArcIntern::new(source_range);
+                relationship.target_range = ArcIntern::new(target_range);
+                relationship.relationship_type = RelationshipType::ImportedSymbolToDefinition;
+                relationships.push(relationship);
             }
+        }
 
-            // Create imported symbol -> file relationships
-            for (source_location, target_files) in imported_symbol_to_files {
-                for target_file in target_files {
-                    let source_range = source_location.range();
-                    let target_range = Range::new(Position::new(0, 0), Position::new(0, 0), (0, 0));
-                    let mut relationship = ConsolidatedRelationship::import_to_file(
-                        source_location.file_path.clone(),
-                        target_file.clone(),
-                    );
-                    relationship.source_range = ArcIntern::new(source_range);
-                    relationship.target_range = ArcIntern::new(target_range);
-                    relationship.relationship_type = RelationshipType::ImportedSymbolToFile;
-                    relationships.push(relationship);
-                }
+        // Create imported symbol -> file relationships
+        for (source_location, target_files) in imported_symbol_to_files {
+            for target_file in target_files {
+                let source_range = source_location.range();
+                let target_range = Range::new(Position::new(0, 0), Position::new(0, 0), (0, 0));
+                let mut relationship = ConsolidatedRelationship::import_to_file(
+                    source_location.file_path.clone(),
+                    target_file.clone(),
+                );
+                relationship.source_range = ArcIntern::new(source_range);
+                relationship.target_range = ArcIntern::new(target_range);
+                relationship.relationship_type = RelationshipType::ImportedSymbolToFile;
+                relationships.push(relationship);
             }
         }
     }
@@ -434,6 +491,12 @@ impl AnalysisService {
         imported_symbol_to_definitions: &HashMap<ImportedSymbolLocation, Vec<DefinitionNode>>,
         imported_symbol_to_files: &HashMap<ImportedSymbolLocation, Vec<String>>,
     ) {
+        let csharp_index = (language == SupportedLanguage::CSharp)
+            .then(|| CSharpDefinitionIndex::new(definition_map));
+        if let Some(csharp_index) = &csharp_index {
+            self.csharp_analyzer
+                .resolve_overrides(&file_references, csharp_index, relationships);
+        }
         for (relative_path, references) in file_references {
             match language {
                 SupportedLanguage::Python => {
@@ -478,6 +541,16 @@ impl AnalysisService {
                         relationships,
                     );
                 }
+                SupportedLanguage::CSharp => {
+                    if let Some(csharp_index) = &csharp_index {
+                        self.csharp_analyzer.process_references(
+                            &references,
+                            &relative_path,
+                            csharp_index,
+                            relationships,
+                        );
+                    }
+                }
                 _ => {}
             }
         }
@@ -5,7 +5,7 @@ use std::{
 
 use internment::ArcIntern;
 
-use database::graph::RelationshipType;
+use database::graph::{DefinitionCategory, RelationshipType};
 use database::schema::types::{NodeFieldAccess, NodeTable};
 use parser_core::{
     csharp::types::{CSharpDefinitionType, CSharpFqn, CSharpImportType},
@@ -183,53 +183,42 @@ pub fn rels_by_kind(
         .collect()
 }
 
+/// The relationship kind stored for a FROM/TO pair of a relationship table
+pub fn relationship_kind_for_pair(
+    from_table: &NodeTable,
+    to_table: &NodeTable,
+) -> Option<RelationshipKind> {
+    match (from_table.name, to_table.name) {
+        ("DirectoryNode", "DirectoryNode") => Some(RelationshipKind::DirectoryToDirectory),
+        ("DirectoryNode", "FileNode") => Some(RelationshipKind::DirectoryToFile),
+        ("FileNode", "DefinitionNode") => Some(RelationshipKind::FileToDefinition),
+        ("FileNode", "ImportedSymbolNode") => Some(RelationshipKind::FileToImportedSymbol),
+        ("DefinitionNode", "DefinitionNode") => Some(RelationshipKind::DefinitionToDefinition),
+        ("DefinitionNode", "ImportedSymbolNode") => {
+            Some(RelationshipKind::DefinitionToImportedSymbol)
+        }
+        ("ImportedSymbolNode", "ImportedSymbolNode") => {
+            Some(RelationshipKind::ImportedSymbolToImportedSymbol)
+        }
+        ("ImportedSymbolNode", "DefinitionNode") => {
+            Some(RelationshipKind::ImportedSymbolToDefinition)
+        }
+        ("ImportedSymbolNode", "FileNode") => Some(RelationshipKind::ImportedSymbolToFile),
+        _ => None,
+    }
+}
+
 pub fn get_relationships_for_pair(
     relationships: &[ConsolidatedRelationship],
     from_table: &NodeTable,
     to_table: &NodeTable,
 ) -> (Option<String>, Vec<ConsolidatedRelationship>) {
-    let filename = from_table.relationship_filename(to_table);
-    match (from_table.name, to_table.name) {
-        ("DirectoryNode", "DirectoryNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::DirectoryToDirectory),
-        ),
-        ("DirectoryNode", "FileNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::DirectoryToFile),
-        ),
-        ("FileNode", "DefinitionNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::FileToDefinition),
-        ),
-        ("FileNode", "ImportedSymbolNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::FileToImportedSymbol),
+    match relationship_kind_for_pair(from_table, to_table) {
+        Some(kind) => (
+            Some(from_table.relationship_filename(to_table)),
+            rels_by_kind(relationships, kind),
         ),
-        ("DefinitionNode", "DefinitionNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::DefinitionToDefinition),
-        ),
-        ("DefinitionNode", "ImportedSymbolNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::DefinitionToImportedSymbol),
-        ),
-        ("ImportedSymbolNode", "ImportedSymbolNode") => (
-            Some(filename),
-            rels_by_kind(
-                relationships,
-                RelationshipKind::ImportedSymbolToImportedSymbol,
-            ),
-        ),
-        ("ImportedSymbolNode", "DefinitionNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::ImportedSymbolToDefinition),
-        ),
-        ("ImportedSymbolNode", "FileNode") => (
-            Some(filename),
-            rels_by_kind(relationships, RelationshipKind::ImportedSymbolToFile),
-        ),
-        _ => (None, vec![]),
+        None => (None, vec![]),
     }
 }
 
@@ -340,6 +329,12 @@ pub struct FileNode {
     pub extension: String,
     /// File name (last component of path)
     pub name: String,
+    /// Hash of the file content when it was indexed
+    pub content_hash: String,
+    /// Length of the byte order mark the file starts with, which is stripped before parsing.
+    /// The byte offsets of the file's definitions and imports plus this are offsets in the
+    /// file, except for UTF-16 files whose offsets are in their content transcoded to UTF-8.
+    pub content_offset: i64,
 }
 
 /// Implementation of NodeFieldAccess for FileNode
@@ -352,6 +347,14 @@ impl NodeFieldAccess for FileNode {
             "repository_name" => Some(self.repository_name.clone()),
             "extension" => Some(self.extension.clone()),
             "name" => Some(self.name.clone()),
+            "content_hash" => Some(self.content_hash.clone()),
+            _ => None,
+        }
+    }
+
+    fn get_i64_field(&self, field_name: &str) -> Option<i64> {
+        match field_name {
+            "content_offset" => Some(self.content_offset),
             _ => None,
         }
     }
@@ -393,6 +396,11 @@ impl DefinitionType {
             DefinitionType::Unsupported() => "unsupported",
         }
     }
+
+    /// Language independent category of this definition type
+    pub fn category(&self) -> DefinitionCategory {
+        DefinitionCategory::from_definition_type(self.as_str())
+    }
 }
 
 /// Represents a language-specific FQN type
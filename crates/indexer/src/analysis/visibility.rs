@@ -0,0 +1,199 @@
+//! Trims the graph down to the definitions visible outside of their project, for public API
+//! extraction with `--public-only`.
+//!
+//! The parser doesn't report modifiers such as `pub`, `public` or `export` yet, so the visibility
+//! is only known where it follows from the names of definitions, i.e. Python's leading underscore
+//! convention. The definitions of other languages are all kept, with a warning.
+
+use crate::analysis::types::{
+    ConsolidatedRelationship, DefinitionType, GraphData, RelationshipKind,
+};
+use parser_core::utils::Range;
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+/// The visibility of a definition, or `None` when it isn't known for its language
+pub fn definition_visibility(definition_type: &DefinitionType, fqn: &str) -> Option<Visibility> {
+    match definition_type {
+        DefinitionType::Python(_) => Some(python_visibility(fqn)),
+        _ => None,
+    }
+}
+
+/// A Python definition is private when it, or one of the definitions enclosing it, is named with
+/// a leading underscore. Dunder names such as `__init__` are public.
+fn python_visibility(fqn: &str) -> Visibility {
+    let is_private = fqn.split('.').any(|segment| {
+        let is_dunder = segment.len() > 4 && segment.starts_with("__") && segment.ends_with("__");
+        segment.starts_with('_') && !is_dunder
+    });
+    if is_private {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+fn language_name(definition_type: &DefinitionType) -> &'static str {
+    match definition_type {
+        DefinitionType::Ruby(_) => "Ruby",
+        DefinitionType::Python(_) => "Python",
+        DefinitionType::Kotlin(_) => "Kotlin",
+        DefinitionType::Java(_) => "Java",
+        DefinitionType::CSharp(_) => "C#",
+        DefinitionType::TypeScript(_) => "TypeScript",
+        DefinitionType::Rust(_) => "Rust",
+        DefinitionType::Unsupported() => "unsupported",
+    }
+}
+
+/// The file and range a definition is looked up by, see `mutation::utils`
+type DefinitionKey = (String, Range);
+
+/// The definitions at either end of a relationship
+fn definition_endpoints(relationship: &ConsolidatedRelationship) -> Vec<DefinitionKey> {
+    let source = || {
+        relationship.source_path.as_ref().map(|path| {
+            let range = relationship
+                .source_definition_range
+                .as_ref()
+                .unwrap_or(&relationship.source_range);
+            (path.to_string(), **range)
+        })
+    };
+    let target = || {
+        relationship.target_path.as_ref().map(|path| {
+            let range = relationship
+                .target_definition_range
+                .as_ref()
+                .unwrap_or(&relationship.target_range);
+            (path.to_string(), **range)
+        })
+    };
+    match relationship.kind {
+        RelationshipKind::DefinitionToDefinition => source().into_iter().chain(target()).collect(),
+        RelationshipKind::DefinitionToImportedSymbol => source().into_iter().collect(),
+        RelationshipKind::FileToDefinition | RelationshipKind::ImportedSymbolToDefinition => {
+            target().into_iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Removes the private definitions from `graph_data`, along with their relationships, and
+/// returns how many were removed
+pub fn retain_public_definitions(graph_data: &mut GraphData) -> usize {
+    let mut unknown_languages = BTreeSet::new();
+    let mut private_definitions: HashSet<DefinitionKey> = HashSet::new();
+    graph_data.definition_nodes.retain(|definition| {
+        match definition_visibility(&definition.definition_type, &definition.fqn) {
+            Some(Visibility::Public) => true,
+            Some(Visibility::Private) => {
+                private_definitions.insert((definition.file_path.clone(), definition.range));
+                false
+            }
+            None => {
+                unknown_languages.insert(language_name(&definition.definition_type));
+                true
+            }
+        }
+    });
+
+    for language in unknown_languages {
+        log::warn!(
+            "The visibility of {language} definitions isn't known, all of them are kept with --public-only"
+        );
+    }
+
+    if !private_definitions.is_empty() {
+        graph_data.relationships.retain(|relationship| {
+            definition_endpoints(relationship)
+                .iter()
+                .all(|endpoint| !private_definitions.contains(endpoint))
+        });
+    }
+    log::info!(
+        "Removed {} private definitions with --public-only",
+        private_definitions.len()
+    );
+    private_definitions.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::types::DefinitionNode;
+    use database::graph::RelationshipType;
+    use internment::ArcIntern;
+    use parser_core::python::types::PythonDefinitionType;
+    use parser_core::utils::Position;
+
+    fn python_class(fqn: &str, line: usize) -> DefinitionNode {
+        DefinitionNode::new(
+            fqn.to_string(),
+            fqn.rsplit('.').next().unwrap().to_string(),
+            DefinitionType::Python(PythonDefinitionType::Class),
+            Range::new(
+                Position::new(line, 0),
+                Position::new(line, 10),
+                (line * 10, line * 10 + 10),
+            ),
+            "app/billing.py".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_python_visibility_follows_underscore_convention() {
+        assert_eq!(python_visibility("Invoice.total"), Visibility::Public);
+        assert_eq!(python_visibility("Invoice.__init__"), Visibility::Public);
+        assert_eq!(python_visibility("Invoice._round"), Visibility::Private);
+        assert_eq!(python_visibility("Invoice.__cache"), Visibility::Private);
+        assert_eq!(python_visibility("_Helper.run"), Visibility::Private);
+        assert_eq!(
+            python_visibility("app._internal.parse"),
+            Visibility::Private
+        );
+        assert_eq!(python_visibility("_"), Visibility::Private);
+    }
+
+    #[test]
+    fn test_retain_public_definitions_removes_private_definitions_and_their_relationships() {
+        let public = python_class("Invoice", 1);
+        let private = python_class("_Ledger", 2);
+        let file_defines = |definition: &DefinitionNode| {
+            let mut relationship = ConsolidatedRelationship::file_to_definition(
+                definition.file_path.clone(),
+                definition.file_path.clone(),
+            );
+            relationship.target_range = ArcIntern::new(definition.range);
+            relationship.relationship_type = RelationshipType::FileDefines;
+            relationship
+        };
+        let mut calls = ConsolidatedRelationship::definition_to_definition(
+            public.file_path.clone(),
+            private.file_path.clone(),
+        );
+        calls.source_definition_range = Some(ArcIntern::new(public.range));
+        calls.target_definition_range = Some(ArcIntern::new(private.range));
+        calls.relationship_type = RelationshipType::Calls;
+
+        let mut graph_data = GraphData {
+            directory_nodes: Vec::new(),
+            file_nodes: Vec::new(),
+            definition_nodes: vec![public.clone(), private.clone()],
+            imported_symbol_nodes: Vec::new(),
+            relationships: vec![file_defines(&public), file_defines(&private), calls],
+        };
+
+        assert_eq!(retain_public_definitions(&mut graph_data), 1);
+        assert_eq!(graph_data.definition_nodes.len(), 1);
+        assert_eq!(graph_data.definition_nodes[0].fqn, "Invoice");
+        assert_eq!(graph_data.relationships.len(), 1);
+        assert_eq!(*graph_data.relationships[0].target_range, public.range);
+    }
+}
@@ -1,4 +1,102 @@
 use crate::indexer::IndexingConfig;
+use parser_core::parser::SupportedLanguage;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Share of the system memory the parsing phase may use by default
+const PARSE_MEMORY_FRACTION: u64 = 4;
+/// Budget used when the amount of system memory cannot be determined
+const FALLBACK_PARSE_MEMORY_BUDGET: usize = 1024 * 1024 * 1024;
+
+/// Default memory budget for the parsing phase: a quarter of the total system memory,
+/// or 1 GiB when it cannot be determined.
+pub fn default_parse_memory_budget() -> usize {
+    system_memory_bytes()
+        .map(|total| (total / PARSE_MEMORY_FRACTION) as usize)
+        .unwrap_or(FALLBACK_PARSE_MEMORY_BUDGET)
+}
+
+#[cfg(target_os = "linux")]
+fn system_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_total(&meminfo)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_memory_bytes() -> Option<u64> {
+    None
+}
+
+/// Reads the `MemTotal` entry (in kB) of a `/proc/meminfo` listing
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|value| {
+            value
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<u64>()
+                .ok()
+        })
+        .map(|kib| kib * 1024)
+}
+
+/// Routes files with a custom extension to the analyzer of a supported language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionOverride {
+    /// Extension without the leading dot, e.g. `rbx`
+    pub extension: String,
+    pub language: SupportedLanguage,
+}
+
+/// Parses `<extension>=<language>`, e.g. `rbx=ruby` or `.cts=typescript`
+impl FromStr for ExtensionOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (extension, language) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid extension mapping '{s}', expected <EXT>=<LANGUAGE>"))?;
+        let extension = extension.trim().trim_start_matches('.');
+        if extension.is_empty() {
+            return Err(format!("Missing extension in mapping '{s}'"));
+        }
+
+        let language = match language.trim().to_ascii_lowercase().as_str() {
+            "ruby" => SupportedLanguage::Ruby,
+            "python" => SupportedLanguage::Python,
+            "kotlin" => SupportedLanguage::Kotlin,
+            "java" => SupportedLanguage::Java,
+            "csharp" | "c#" => SupportedLanguage::CSharp,
+            "typescript" => SupportedLanguage::TypeScript,
+            "rust" => SupportedLanguage::Rust,
+            other => {
+                return Err(format!(
+                    "Unknown language '{other}', expected ruby, python, kotlin, java, csharp, typescript or rust"
+                ));
+            }
+        };
+
+        Ok(Self {
+            extension: extension.to_string(),
+            language,
+        })
+    }
+}
+
+/// Collects extension overrides into the map used by [`IndexingConfig::extension_overrides`].
+/// Later entries win over earlier ones for the same extension.
+pub fn extension_override_map(
+    overrides: impl IntoIterator<Item = ExtensionOverride>,
+) -> HashMap<String, SupportedLanguage> {
+    overrides
+        .into_iter()
+        .map(|o| (o.extension, o.language))
+        .collect()
+}
 
 pub struct IndexingConfigBuilder;
 
@@ -7,8 +105,7 @@ impl IndexingConfigBuilder {
         let effective_threads = IndexingConfigBuilder::get_effective_threads(threads);
         IndexingConfig {
             worker_threads: effective_threads,
-            max_file_size: 5_000_000,
-            respect_gitignore: true,
+            ..Default::default()
         }
     }
 
@@ -32,5 +129,35 @@ mod tests {
         assert!(config.worker_threads > 0);
         assert_eq!(config.max_file_size, 5_000_000);
         assert!(config.respect_gitignore);
+        assert!(config.write_parquet);
+        assert!(config.parse_memory_budget > 0);
+    }
+
+    #[test]
+    fn test_parse_mem_total() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:         1234567 kB\n";
+        assert_eq!(parse_mem_total(meminfo), Some(16_318_480 * 1024));
+        assert_eq!(parse_mem_total("MemFree: 1 kB\n"), None);
+    }
+
+    #[test]
+    fn test_parse_extension_override() {
+        assert_eq!(
+            "rbx=ruby".parse::<ExtensionOverride>().unwrap(),
+            ExtensionOverride {
+                extension: "rbx".to_string(),
+                language: SupportedLanguage::Ruby,
+            }
+        );
+        assert_eq!(
+            ".cts=TypeScript".parse::<ExtensionOverride>().unwrap(),
+            ExtensionOverride {
+                extension: "cts".to_string(),
+                language: SupportedLanguage::TypeScript,
+            }
+        );
+        assert!("rbx".parse::<ExtensionOverride>().is_err());
+        assert!("=ruby".parse::<ExtensionOverride>().is_err());
+        assert!("foo=cobol".parse::<ExtensionOverride>().is_err());
     }
 }
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::{DefinitionNodeFromKuzu, ImportedSymbolNodeFromKuzu, KuzuNodeType};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use workspace_manager::ProjectInfo;
+
+/// Extensions of the files whose imports and definitions take part in the resolution
+const TYPESCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// An import of one project that resolves to a definition of another project of the same
+/// workspace folder. Lines are 0-based, like in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossProjectLink {
+    pub source_project_path: String,
+    pub source_file_path: String,
+    pub source_start_line: i32,
+    pub import_path: String,
+    pub imported_name: String,
+    pub target_project_path: String,
+    pub target_definition_id: u32,
+    pub target_file_path: String,
+    pub target_fqn: String,
+    pub target_start_line: i32,
+    pub target_end_line: i32,
+}
+
+/// The `name` of the `package.json` at the root of a project
+pub fn package_name(project_root: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(project_root.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    manifest
+        .get("name")
+        .and_then(|name| name.as_str())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// The package an import path points into: the package itself (`@acme/billing`) or one of
+/// its modules (`@acme/billing/invoices`)
+pub fn package_for_import<'a>(import_path: &str, package_names: &'a [String]) -> Option<&'a str> {
+    package_names
+        .iter()
+        .find(|name| {
+            import_path
+                .strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(String::as_str)
+}
+
+fn is_typescript_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| TYPESCRIPT_EXTENSIONS.contains(&extension))
+}
+
+/// Links the TypeScript imports of every project to the top-level definitions of the
+/// sibling project whose `package.json` name matches the import path. Projects without a
+/// database are skipped. Links are sorted by source project, file and line.
+pub fn resolve_cross_project_links(
+    database: &KuzuDatabase,
+    projects: &[ProjectInfo],
+) -> Result<Vec<CrossProjectLink>> {
+    // Package name -> project publishing it
+    let packages: HashMap<String, &ProjectInfo> = projects
+        .iter()
+        .filter_map(|project| {
+            package_name(Path::new(&project.project_path)).map(|name| (name, project))
+        })
+        .collect();
+    if packages.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Longest names first, so `@acme/billing-api` isn't taken for `@acme/billing`
+    let mut package_names: Vec<String> = packages.keys().cloned().collect();
+    package_names.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    // Imports to resolve, grouped by the project publishing the package
+    let mut imports_by_package: HashMap<&str, Vec<(&ProjectInfo, ImportedSymbolNodeFromKuzu)>> =
+        HashMap::new();
+    for project in projects {
+        if !project.database_path.exists() {
+            continue;
+        }
+        let Some(project_database) =
+            database.get_or_create_database(&project.database_path.to_string_lossy(), None)
+        else {
+            warn!(
+                "Skipping cross-project resolution of {}: failed to open its database",
+                project.project_path
+            );
+            continue;
+        };
+
+        let imported_symbols =
+            NodeDatabaseService::new(&project_database)
+                .get_all::<ImportedSymbolNodeFromKuzu>(KuzuNodeType::ImportedSymbolNode)?;
+        for imported_symbol in imported_symbols {
+            if imported_symbol.name.is_none() || !is_typescript_file(&imported_symbol.file_path) {
+                continue;
+            }
+            let Some(package) = package_for_import(&imported_symbol.import_path, &package_names)
+            else {
+                continue;
+            };
+            if packages[package].project_path == project.project_path {
+                continue;
+            }
+            imports_by_package
+                .entry(package)
+                .or_default()
+                .push((project, imported_symbol));
+        }
+    }
+
+    let mut links = Vec::new();
+    for (package, imports) in imports_by_package {
+        let target_project = packages[package];
+        if !target_project.database_path.exists() {
+            continue;
+        }
+        let Some(target_database) =
+            database.get_or_create_database(&target_project.database_path.to_string_lossy(), None)
+        else {
+            continue;
+        };
+
+        let mut names: Vec<String> = imports
+            .iter()
+            .filter_map(|(_, imported_symbol)| imported_symbol.name.clone())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+
+        // Only top-level definitions can be imported from a module
+        let mut definitions_by_name: HashMap<String, Vec<DefinitionNodeFromKuzu>> = HashMap::new();
+        for definition in NodeDatabaseService::new(&target_database)
+            .get_by::<String, DefinitionNodeFromKuzu>(
+                KuzuNodeType::DefinitionNode,
+                "name",
+                &names,
+            )?
+        {
+            if definition.fqn == definition.name
+                && is_typescript_file(&definition.primary_file_path)
+            {
+                definitions_by_name
+                    .entry(definition.name.clone())
+                    .or_default()
+                    .push(definition);
+            }
+        }
+
+        for (source_project, imported_symbol) in imports {
+            let Some(name) = &imported_symbol.name else {
+                continue;
+            };
+            for definition in definitions_by_name.get(name).into_iter().flatten() {
+                links.push(CrossProjectLink {
+                    source_project_path: source_project.project_path.clone(),
+                    source_file_path: imported_symbol.file_path.clone(),
+                    source_start_line: imported_symbol.start_line,
+                    import_path: imported_symbol.import_path.clone(),
+                    imported_name: name.clone(),
+                    target_project_path: target_project.project_path.clone(),
+                    target_definition_id: definition.id,
+                    target_file_path: definition.primary_file_path.clone(),
+                    target_fqn: definition.fqn.clone(),
+                    target_start_line: definition.start_line,
+                    target_end_line: definition.end_line,
+                });
+            }
+        }
+    }
+
+    links.sort_by(|a, b| {
+        (
+            &a.source_project_path,
+            &a.source_file_path,
+            a.source_start_line,
+        )
+            .cmp(&(
+                &b.source_project_path,
+                &b.source_file_path,
+                b.source_start_line,
+            ))
+            .then_with(|| a.target_file_path.cmp(&b.target_file_path))
+    });
+    info!("Resolved {} cross-project links", links.len());
+    Ok(links)
+}
+
+pub fn save_cross_project_links(path: &Path, links: &[CrossProjectLink]) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(links)?)?;
+    Ok(())
+}
+
+/// Reads the links saved by the last indexing, none when cross-project resolution didn't run
+pub fn load_cross_project_links(path: &Path) -> Result<Vec<CrossProjectLink>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_package_for_import_matches_package_and_modules() {
+        let package_names = vec!["@acme/billing-api".to_string(), "@acme/billing".to_string()];
+
+        assert_eq!(
+            package_for_import("@acme/billing", &package_names),
+            Some("@acme/billing")
+        );
+        assert_eq!(
+            package_for_import("@acme/billing/invoices", &package_names),
+            Some("@acme/billing")
+        );
+        assert_eq!(
+            package_for_import("@acme/billing-api", &package_names),
+            Some("@acme/billing-api")
+        );
+        assert_eq!(package_for_import("@acme/billings", &package_names), None);
+        assert_eq!(package_for_import("./billing", &package_names), None);
+    }
+
+    #[test]
+    fn test_package_name_reads_package_json() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(package_name(temp_dir.path()), None);
+
+        std::fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{ "name": "@acme/billing", "version": "1.0.0" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            package_name(temp_dir.path()),
+            Some("@acme/billing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cross_project_links_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cross_project_links.json");
+        assert!(load_cross_project_links(&path).unwrap().is_empty());
+
+        let links = vec![CrossProjectLink {
+            source_project_path: "/workspace/web".to_string(),
+            source_file_path: "src/checkout.ts".to_string(),
+            source_start_line: 0,
+            import_path: "@acme/billing".to_string(),
+            imported_name: "createInvoice".to_string(),
+            target_project_path: "/workspace/billing".to_string(),
+            target_definition_id: 7,
+            target_file_path: "src/invoices.ts".to_string(),
+            target_fqn: "createInvoice".to_string(),
+            target_start_line: 4,
+            target_end_line: 9,
+        }];
+        save_cross_project_links(&path, &links).unwrap();
+
+        assert_eq!(load_cross_project_links(&path).unwrap(), links);
+    }
+}
@@ -1,31 +1,30 @@
-use crate::indexer::{IndexingConfig, RepositoryIndexer};
+use crate::execution::cross_project::{resolve_cross_project_links, save_cross_project_links};
+use crate::indexer::{FatalIndexingError, IndexingConfig, RepositoryIndexer};
 use crate::parsing::changes::FileChanges;
+use crate::progress::{NoopProgressSink, ProgressSink};
+use crate::project::git_ref::GitCommit;
 use crate::project::source::GitaliskFileSource;
+use crate::snapshots::{DefinitionSnapshot, SnapshotStore};
 use crate::stats::{ProjectStatistics, WorkspaceStatistics, finalize_project_statistics};
 
 use anyhow::Result;
-use chrono::Utc;
 use database::kuzu::database::KuzuDatabase;
-use event_bus::types::project_info::to_ts_project_info;
-use event_bus::types::workspace_folder::to_ts_workspace_folder_info;
-use event_bus::{
-    EventBus, GkgEvent, ProjectIndexingCompleted, ProjectIndexingEvent, ProjectIndexingFailed,
-    ProjectIndexingStarted, ProjectReindexingCompleted, ProjectReindexingEvent,
-    ProjectReindexingFailed, ProjectReindexingStarted, WorkspaceIndexingCompleted,
-    WorkspaceIndexingEvent, WorkspaceIndexingStarted, WorkspaceReindexingCompleted,
-    WorkspaceReindexingEvent, WorkspaceReindexingStarted,
-};
+use database::schema::manager::SCHEMA_VERSION;
+use event_bus::{EventBus, GkgEvent};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
-use workspace_manager::{Status, WorkspaceManager};
+use tracing::{Instrument, error, info, info_span};
+use workspace_manager::{ProjectInfo, Status, WorkspaceManager};
 
 pub struct IndexingExecutor {
     database: Arc<KuzuDatabase>,
     event_bus: Arc<EventBus>,
     workspace_manager: Arc<WorkspaceManager>,
     config: IndexingConfig,
+    progress: Arc<dyn ProgressSink>,
+    /// Index the commit this ref resolves to instead of the working tree
+    git_ref: Option<String>,
 }
 
 impl IndexingExecutor {
@@ -40,9 +39,33 @@ impl IndexingExecutor {
             workspace_manager,
             event_bus,
             config,
+            progress: Arc::new(NoopProgressSink),
+            git_ref: None,
         }
     }
 
+    /// Report the progress of every project indexed by this executor to `progress`,
+    /// in addition to the events sent on the event bus
+    pub fn with_progress_sink(mut self, progress: Arc<dyn ProgressSink>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Index the commit `git_ref` resolves to in each project, read from the object database,
+    /// instead of the working tree. Reindexing is not affected.
+    pub fn with_git_ref(mut self, git_ref: Option<String>) -> Self {
+        self.git_ref = git_ref;
+        self
+    }
+
+    /// The commit to index in the project, `None` to index its working tree
+    fn resolve_git_ref(&self, project_path: &str) -> Result<Option<GitCommit>> {
+        let Some(git_ref) = &self.git_ref else {
+            return Ok(None);
+        };
+        Ok(Some(GitCommit::resolve(Path::new(project_path), git_ref)?))
+    }
+
     pub async fn execute_workspace_indexing(
         &mut self,
         workspace_folder_path: PathBuf,
@@ -62,12 +85,9 @@ impl IndexingExecutor {
             .list_projects_in_workspace(workspace_folder_path_str);
 
         if projects.is_empty() {
-            self.event_bus.send(&GkgEvent::WorkspaceIndexing(
-                WorkspaceIndexingEvent::Completed(WorkspaceIndexingCompleted {
-                    workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                    projects_indexed: projects.iter().map(|p| p.project_path.clone()).collect(),
-                    completed_at: Utc::now(),
-                }),
+            self.event_bus.send(&GkgEvent::workspace_indexing_completed(
+                &workspace_folder_info,
+                &projects,
             ));
 
             // Return empty statistics
@@ -77,24 +97,32 @@ impl IndexingExecutor {
                 indexing_duration,
             ));
         }
-        self.event_bus.send(&GkgEvent::WorkspaceIndexing(
-            WorkspaceIndexingEvent::Started(WorkspaceIndexingStarted {
-                workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                projects_to_process: projects.iter().map(|p| p.project_path.clone()).collect(),
-                started_at: Utc::now(),
-            }),
+
+        // Fail before indexing anything when a project doesn't have the commit
+        for project in &projects {
+            self.resolve_git_ref(&project.project_path)?;
+        }
+        self.event_bus.send(&GkgEvent::workspace_indexing_started(
+            &workspace_folder_info,
+            &projects,
         ));
 
         // Create statistics collector
         let indexing_duration = start_time.elapsed().as_secs_f64();
         let mut workspace_stats =
             WorkspaceStatistics::new(workspace_folder_path_str.clone(), indexing_duration);
+        workspace_stats.metadata.references_resolved = self.config.resolve_references;
 
-        for project_discovery in projects.iter() {
+        for (index, project_discovery) in projects.iter().enumerate() {
             self.check_cancellation(&cancellation_token, "during project iteration")?;
+            self.progress.on_project_started(
+                &project_discovery.project_path,
+                index + 1,
+                projects.len(),
+            );
 
             match self
-                .execute_project_indexing(
+                .index_project(
                     workspace_folder_path_str,
                     &project_discovery.project_path,
                     cancellation_token.clone(),
@@ -114,29 +142,30 @@ impl IndexingExecutor {
                         Status::Error,
                         Some(error_msg.clone()),
                     )?;
-                    self.event_bus
-                        .send(&GkgEvent::ProjectIndexing(ProjectIndexingEvent::Failed(
-                            ProjectIndexingFailed {
-                                project_info: to_ts_project_info(project_discovery),
-                                error: error_msg.clone(),
-                                failed_at: Utc::now(),
-                            },
-                        )));
+                    self.event_bus.send(&GkgEvent::project_indexing_failed(
+                        project_discovery,
+                        error_msg.clone(),
+                    ));
                     error!(
                         "  ❌ Failed to index repository '{}': {}",
                         &project_discovery.project_path, error_msg
                     );
+                    workspace_stats
+                        .failed_projects
+                        .push(project_discovery.project_path.clone());
                     continue;
                 }
             }
         }
 
-        self.event_bus.send(&GkgEvent::WorkspaceIndexing(
-            WorkspaceIndexingEvent::Completed(WorkspaceIndexingCompleted {
-                workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                projects_indexed: projects.iter().map(|p| p.project_path.clone()).collect(),
-                completed_at: Utc::now(),
-            }),
+        if self.config.cross_project_resolution {
+            workspace_stats.total_cross_project_links =
+                self.resolve_cross_project_links(workspace_folder_path_str);
+        }
+
+        self.event_bus.send(&GkgEvent::workspace_indexing_completed(
+            &workspace_folder_info,
+            &projects,
         ));
 
         // Update duration after all processing
@@ -163,21 +192,16 @@ impl IndexingExecutor {
             .list_projects_in_workspace(workspace_folder_path_str);
 
         if projects.is_empty() {
-            self.event_bus.send(&GkgEvent::WorkspaceReindexing(
-                WorkspaceReindexingEvent::Completed(WorkspaceReindexingCompleted {
-                    workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                    projects_indexed: projects.iter().map(|p| p.project_path.clone()).collect(),
-                    completed_at: Utc::now(),
-                }),
-            ));
+            self.event_bus
+                .send(&GkgEvent::workspace_reindexing_completed(
+                    &workspace_folder_info,
+                    &projects,
+                ));
             return Ok(());
         }
-        self.event_bus.send(&GkgEvent::WorkspaceReindexing(
-            WorkspaceReindexingEvent::Started(WorkspaceReindexingStarted {
-                workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                projects_to_process: projects.iter().map(|p| p.project_path.clone()).collect(),
-                started_at: Utc::now(),
-            }),
+        self.event_bus.send(&GkgEvent::workspace_reindexing_started(
+            &workspace_folder_info,
+            &projects,
         ));
 
         for project_discovery in projects.iter() {
@@ -192,7 +216,7 @@ impl IndexingExecutor {
                 .collect();
 
             match self
-                .execute_project_reindexing(
+                .reindex_project(
                     workspace_folder_path_str,
                     &project_discovery.project_path,
                     project_changes,
@@ -212,12 +236,9 @@ impl IndexingExecutor {
                         Status::Error,
                         Some(error_msg.clone()),
                     )?;
-                    self.event_bus.send(&GkgEvent::ProjectReindexing(
-                        ProjectReindexingEvent::Failed(ProjectReindexingFailed {
-                            project_info: to_ts_project_info(project_discovery),
-                            error: error_msg.clone(),
-                            failed_at: Utc::now(),
-                        }),
+                    self.event_bus.send(&GkgEvent::project_reindexing_failed(
+                        project_discovery,
+                        error_msg.clone(),
                     ));
                     error!(
                         "  ❌ Failed to re-index repository '{}': {}",
@@ -228,13 +249,13 @@ impl IndexingExecutor {
             }
         }
 
-        self.event_bus.send(&GkgEvent::WorkspaceReindexing(
-            WorkspaceReindexingEvent::Completed(WorkspaceReindexingCompleted {
-                workspace_folder_info: to_ts_workspace_folder_info(&workspace_folder_info),
-                projects_indexed: projects.iter().map(|p| p.project_path.clone()).collect(),
-                completed_at: Utc::now(),
-            }),
-        ));
+        self.refresh_cross_project_links(workspace_folder_path_str);
+
+        self.event_bus
+            .send(&GkgEvent::workspace_reindexing_completed(
+                &workspace_folder_info,
+                &projects,
+            ));
 
         Ok(())
     }
@@ -247,8 +268,41 @@ impl IndexingExecutor {
         workspace_folder_path: &str,
         project_path: &str,
         cancellation_token: Option<CancellationToken>,
+    ) -> Result<ProjectStatistics> {
+        let result = self
+            .index_project(workspace_folder_path, project_path, cancellation_token)
+            .await;
+        self.refresh_cross_project_links(workspace_folder_path);
+        result
+    }
+
+    pub async fn execute_project_reindexing(
+        &mut self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        project_changes: Vec<PathBuf>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<()> {
+        let result = self
+            .reindex_project(
+                workspace_folder_path,
+                project_path,
+                project_changes,
+                cancellation_token,
+            )
+            .await;
+        self.refresh_cross_project_links(workspace_folder_path);
+        result
+    }
+
+    async fn index_project(
+        &mut self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        cancellation_token: Option<CancellationToken>,
     ) -> Result<ProjectStatistics> {
         self.check_cancellation(&cancellation_token, "before starting")?;
+        let commit = self.resolve_git_ref(project_path)?;
 
         self.mark_project_status(workspace_folder_path, project_path, Status::Indexing, None)?;
 
@@ -258,12 +312,7 @@ impl IndexingExecutor {
             .ok_or_else(|| anyhow::anyhow!("Project not found"))?;
 
         self.event_bus
-            .send(&GkgEvent::ProjectIndexing(ProjectIndexingEvent::Started(
-                ProjectIndexingStarted {
-                    project_info: to_ts_project_info(&project_info),
-                    started_at: Utc::now(),
-                },
-            )));
+            .send(&GkgEvent::project_indexing_started(&project_info));
 
         let parquet_directory = project_info.parquet_directory.to_string_lossy();
         let database_path = project_info.database_path.to_string_lossy();
@@ -272,10 +321,15 @@ impl IndexingExecutor {
             .and_then(|name| name.to_str())
             .unwrap_or("unknown")
             .to_string();
-        let indexer = RepositoryIndexer::new(repo_name.clone(), project_info.project_path.clone());
-        let file_source = GitaliskFileSource::new(project_info.repository.clone());
+        let indexer = RepositoryIndexer::new(repo_name.clone(), project_info.project_path.clone())
+            .with_progress_sink(Arc::clone(&self.progress));
+        let indexed_commit = commit.as_ref().map(|commit| commit.id().to_string());
+        let mut file_source = GitaliskFileSource::new(project_info.repository.clone());
+        if let Some(commit) = commit {
+            file_source = file_source.at_commit(commit);
+        }
 
-        match indexer
+        let indexing = indexer
             .process_files_full_with_database(
                 &self.database,
                 file_source,
@@ -283,23 +337,45 @@ impl IndexingExecutor {
                 &parquet_directory,
                 &database_path,
             )
-            .await
-        {
+            .instrument(info_span!("index_project", project = %project_info.project_path));
+        // Parsing already handed to the CPU pool finishes in the background on timeout, but
+        // its results are dropped
+        let result = match self.config.project_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, indexing)
+                .await
+                .unwrap_or(Err(FatalIndexingError::TimedOut(timeout))),
+            None => indexing.await,
+        };
+        self.notify_graph_updated(&project_info);
+        match result {
             Ok(project_stats) => {
                 self.check_cancellation(&cancellation_token, "after re-indexing completed")?;
+                self.workspace_manager
+                    .update_project_schema_version(
+                        workspace_folder_path,
+                        project_path,
+                        SCHEMA_VERSION,
+                    )
+                    .map_err(|e| anyhow::anyhow!("Failed to record schema version: {}", e))?;
+                self.snapshot_definitions(&project_info);
                 self.mark_project_status(
                     workspace_folder_path,
                     project_path,
                     Status::Indexed,
                     None,
                 )?;
+                // Marking the project indexed records HEAD, not the commit the ref named
+                if let Some(indexed_commit) = indexed_commit {
+                    self.workspace_manager
+                        .set_project_indexed_commit(
+                            workspace_folder_path,
+                            project_path,
+                            indexed_commit,
+                        )
+                        .map_err(|e| anyhow::anyhow!("Failed to record indexed commit: {}", e))?;
+                }
                 self.event_bus
-                    .send(&GkgEvent::ProjectIndexing(ProjectIndexingEvent::Completed(
-                        ProjectIndexingCompleted {
-                            project_info: to_ts_project_info(&project_info),
-                            completed_at: Utc::now(),
-                        },
-                    )));
+                    .send(&GkgEvent::project_indexing_completed(&project_info));
                 // Use finalize_project_statistics to build ProjectStatistics from written data
                 let stats = finalize_project_statistics(
                     project_info.project_path.clone(),
@@ -313,6 +389,7 @@ impl IndexingExecutor {
                         .writer_result
                         .as_ref()
                         .expect("writer_result should exist"),
+                    project_stats.excluded_files,
                 );
                 Ok(stats)
             }
@@ -324,20 +401,16 @@ impl IndexingExecutor {
                     Status::Error,
                     Some(error_msg.clone()),
                 )?;
-                self.event_bus
-                    .send(&GkgEvent::ProjectIndexing(ProjectIndexingEvent::Failed(
-                        ProjectIndexingFailed {
-                            project_info: to_ts_project_info(&project_info),
-                            error: error_msg.clone(),
-                            failed_at: Utc::now(),
-                        },
-                    )));
+                self.event_bus.send(&GkgEvent::project_indexing_failed(
+                    &project_info,
+                    error_msg.clone(),
+                ));
                 Err(anyhow::anyhow!("Project re-indexing failed: {error_msg}"))
             }
         }
     }
 
-    pub async fn execute_project_reindexing(
+    async fn reindex_project(
         &mut self,
         workspace_folder_path: &str,
         project_path: &str,
@@ -346,6 +419,20 @@ impl IndexingExecutor {
     ) -> Result<()> {
         self.check_cancellation(&cancellation_token, "before starting")?;
 
+        // Changes can't be applied to a database built with another graph schema
+        if self
+            .workspace_manager
+            .get_project_info(workspace_folder_path, project_path)
+            .is_some_and(|project_info| project_info.schema_version != Some(SCHEMA_VERSION))
+        {
+            info!(
+                "Project {project_path} was indexed with another graph schema, indexing it fully"
+            );
+            self.index_project(workspace_folder_path, project_path, cancellation_token)
+                .await?;
+            return Ok(());
+        }
+
         self.mark_project_status(
             workspace_folder_path,
             project_path,
@@ -358,12 +445,8 @@ impl IndexingExecutor {
             .get_project_info(workspace_folder_path, project_path)
             .ok_or_else(|| anyhow::anyhow!("Project not found"))?;
 
-        self.event_bus.send(&GkgEvent::ProjectReindexing(
-            ProjectReindexingEvent::Started(ProjectReindexingStarted {
-                project_info: to_ts_project_info(&project_info),
-                started_at: Utc::now(),
-            }),
-        ));
+        self.event_bus
+            .send(&GkgEvent::project_reindexing_started(&project_info));
 
         let parquet_directory = project_info.parquet_directory.to_string_lossy();
         let database_path = project_info.database_path.to_string_lossy();
@@ -378,7 +461,8 @@ impl IndexingExecutor {
             .map(|path| path.to_string_lossy().to_string())
             .collect();
         let mut indexer =
-            RepositoryIndexer::new(repo_name.clone(), project_info.project_path.clone());
+            RepositoryIndexer::new(repo_name.clone(), project_info.project_path.clone())
+                .with_progress_sink(Arc::clone(&self.progress));
         let changes = FileChanges::from_watched_files(changes_as_strs);
 
         info!("Re-indexing project with changes: {:?}", changes);
@@ -397,7 +481,7 @@ impl IndexingExecutor {
         );
         info!("Re-indexing project with repo name: {:?}", repo_name);
 
-        match indexer
+        let result = indexer
             .reindex_repository(
                 &self.database,
                 changes,
@@ -405,22 +489,27 @@ impl IndexingExecutor {
                 &database_path,
                 &parquet_directory,
             )
-            .await
-        {
-            Ok(_) => {
+            .instrument(info_span!("reindex_project", project = %project_info.project_path))
+            .await;
+        self.notify_graph_updated(&project_info);
+        match result {
+            Ok(result) => {
+                if result.unchanged_files > 0 {
+                    info!(
+                        "Skipped {} changed files with unchanged content",
+                        result.unchanged_files
+                    );
+                }
                 self.check_cancellation(&cancellation_token, "after re-indexing completed")?;
+                self.snapshot_definitions(&project_info);
                 self.mark_project_status(
                     workspace_folder_path,
                     project_path,
                     Status::Indexed,
                     None,
                 )?;
-                self.event_bus.send(&GkgEvent::ProjectReindexing(
-                    ProjectReindexingEvent::Completed(ProjectReindexingCompleted {
-                        project_info: to_ts_project_info(&project_info),
-                        completed_at: Utc::now(),
-                    }),
-                ));
+                self.event_bus
+                    .send(&GkgEvent::project_reindexing_completed(&project_info));
                 Ok(())
             }
             Err(e) => {
@@ -431,18 +520,126 @@ impl IndexingExecutor {
                     Status::Error,
                     Some(error_msg.clone()),
                 )?;
-                self.event_bus.send(&GkgEvent::ProjectReindexing(
-                    ProjectReindexingEvent::Failed(ProjectReindexingFailed {
-                        project_info: to_ts_project_info(&project_info),
-                        error: error_msg.clone(),
-                        failed_at: Utc::now(),
-                    }),
+                self.event_bus.send(&GkgEvent::project_reindexing_failed(
+                    &project_info,
+                    error_msg.clone(),
                 ));
                 Err(anyhow::anyhow!("Project re-indexing failed: {error_msg}"))
             }
         }
     }
 
+    /// Re-index only the files that git reports as added, modified, deleted or untracked in
+    /// the project's working tree. Returns the absolute paths that were re-indexed.
+    pub async fn execute_project_incremental_reindexing(
+        &mut self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<Vec<PathBuf>> {
+        let project_info = self
+            .workspace_manager
+            .get_project_info(workspace_folder_path, project_path)
+            .ok_or_else(|| anyhow::anyhow!("Project not found"))?;
+
+        let git_status = project_info
+            .repository
+            .get_status()
+            .map_err(|e| anyhow::anyhow!("Failed to get git status: {e}"))?;
+        let mut project_changes: Vec<PathBuf> = git_status
+            .into_iter()
+            .map(|status| Path::new(&project_info.project_path).join(status.path))
+            .collect();
+        project_changes.sort();
+        project_changes.dedup();
+
+        self.execute_project_reindexing(
+            workspace_folder_path,
+            project_path,
+            project_changes.clone(),
+            cancellation_token,
+        )
+        .await?;
+
+        Ok(project_changes)
+    }
+
+    /// Tells the subscribers that the project's database changed. Sent after failed runs too,
+    /// since they may have written part of the graph. What was read from the database, like
+    /// cached query results, is dropped before this returns.
+    fn notify_graph_updated(&self, project_info: &ProjectInfo) {
+        self.database
+            .notify_changed(&project_info.database_path.to_string_lossy());
+        self.event_bus.send(&GkgEvent::graph_updated(project_info));
+    }
+
+    /// Records the project's definitions after an index run when snapshots are enabled.
+    /// Failures are logged and don't fail the indexing.
+    fn snapshot_definitions(&self, project_info: &ProjectInfo) {
+        if self.config.definition_snapshot_history == 0 {
+            return;
+        }
+        let Some(directory) = self.workspace_manager.project_snapshots_directory(
+            &project_info.workspace_folder_path,
+            &project_info.project_path,
+        ) else {
+            return;
+        };
+
+        let result = DefinitionSnapshot::take(
+            &self.database,
+            &project_info.database_path,
+            Path::new(&project_info.project_path),
+        )
+        .and_then(|snapshot| {
+            SnapshotStore::new(directory).save(&snapshot, self.config.definition_snapshot_history)
+        });
+        if let Err(e) = result {
+            error!(
+                "Failed to snapshot the definitions of {}: {e}",
+                project_info.project_path
+            );
+        }
+    }
+
+    /// Links the imports of the workspace folder's projects to the definitions of their
+    /// siblings and saves the links next to the project databases. Failures are logged and
+    /// don't fail the indexing. Returns the number of links.
+    fn resolve_cross_project_links(&self, workspace_folder_path: &str) -> usize {
+        let Some(links_path) = self
+            .workspace_manager
+            .cross_project_links_path(workspace_folder_path)
+        else {
+            return 0;
+        };
+        let projects = self
+            .workspace_manager
+            .list_projects_in_workspace(workspace_folder_path);
+
+        let result = resolve_cross_project_links(&self.database, &projects)
+            .and_then(|links| save_cross_project_links(&links_path, &links).map(|_| links.len()));
+        match result {
+            Ok(link_count) => link_count,
+            Err(e) => {
+                error!("Failed to resolve cross-project links of {workspace_folder_path}: {e}");
+                0
+            }
+        }
+    }
+
+    /// Resolves the cross-project links again after projects of the workspace folder were
+    /// indexed on their own, when enabled or when an earlier run saved links that would
+    /// otherwise go stale
+    fn refresh_cross_project_links(&self, workspace_folder_path: &str) {
+        let links_saved = self
+            .workspace_manager
+            .cross_project_links_path(workspace_folder_path)
+            .is_some_and(|links_path| links_path.exists());
+        if self.config.cross_project_resolution || links_saved {
+            self.resolve_cross_project_links(workspace_folder_path);
+        }
+    }
+
     pub fn mark_workspace_status(&self, workspace_folder_path: &str, status: Status) -> Result<()> {
         self.workspace_manager
             .update_workspace_folder_status(workspace_folder_path, Some(status))
@@ -487,11 +684,16 @@ mod tests {
     use super::*;
     use crate::execution::config::IndexingConfigBuilder;
     use database::kuzu::service::NodeDatabaseService;
-    use event_bus::{EventBus, GkgEvent, ProjectIndexingEvent, WorkspaceIndexingEvent};
+    use event_bus::{
+        EventBus, GkgEvent, ProjectIndexingEvent, ProjectReindexingEvent, WorkspaceIndexingEvent,
+        WorkspaceReindexingEvent,
+    };
     use kuzu::{Database, SystemConfig};
     use std::fs;
     use std::sync::Arc;
+    use std::time::Duration;
     use tempfile::TempDir;
+    use testing::repository::TestRepository;
     use tokio_util::sync::CancellationToken;
     use workspace_manager::Status;
 
@@ -676,6 +878,63 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_one_executor_indexes_several_workspace_folders() {
+        let (workspace_manager, temp_dir) = create_test_workspace_manager();
+        let mut execution = IndexingExecutor::new(
+            Arc::new(KuzuDatabase::new()),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            IndexingConfigBuilder::build(4),
+        );
+
+        for name in ["workspace_a", "workspace_b"] {
+            let workspace_path = temp_dir.path().join(name);
+            create_test_git_repo(&workspace_path.join("project"));
+
+            let workspace_stats = execution
+                .execute_workspace_indexing(workspace_path.canonicalize().unwrap(), None)
+                .await
+                .unwrap();
+            assert_eq!(workspace_stats.total_projects, 1);
+            assert!(workspace_stats.total_files > 0);
+        }
+
+        assert_eq!(workspace_manager.list_workspace_folders().len(), 2);
+        assert_eq!(workspace_manager.list_all_projects().len(), 2);
+    }
+
+    #[derive(Default)]
+    struct RecordingProjectsSink {
+        started: std::sync::Mutex<Vec<(usize, usize)>>,
+    }
+
+    impl ProgressSink for RecordingProjectsSink {
+        fn on_project_started(&self, _project_path: &str, number: usize, total: usize) {
+            self.started.lock().unwrap().push((number, total));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_sink_is_told_about_each_project() {
+        let (workspace_manager, _temp_dir, workspace_path) = create_test_workspace_with_projects(2);
+        let sink = Arc::new(RecordingProjectsSink::default());
+        let mut execution = IndexingExecutor::new(
+            Arc::new(KuzuDatabase::new()),
+            workspace_manager,
+            Arc::new(EventBus::new()),
+            IndexingConfigBuilder::build(4),
+        )
+        .with_progress_sink(sink.clone());
+
+        execution
+            .execute_workspace_indexing(workspace_path.canonicalize().unwrap(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(*sink.started.lock().unwrap(), vec![(1, 2), (2, 2)]);
+    }
+
     #[tokio::test]
     async fn test_run_workspace_indexing_with_projects_events() {
         let (workspace_manager, _temp_dir, workspace_path) = create_test_workspace_with_projects(2);
@@ -826,6 +1085,155 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_reindexing_outdated_schema_runs_full_indexing() {
+        let (workspace_manager, _temp_dir, workspace_path) = create_test_workspace_with_projects(1);
+        let event_bus = Arc::new(EventBus::new());
+        let database = Arc::new(KuzuDatabase::new());
+        let mut execution = IndexingExecutor::new(
+            database,
+            Arc::clone(&workspace_manager),
+            Arc::clone(&event_bus),
+            IndexingConfigBuilder::build(4),
+        );
+
+        let workspace_str = workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap()
+            .workspace_folder_path;
+        let project = workspace_manager.list_projects_in_workspace(&workspace_str)[0].clone();
+
+        execution
+            .execute_project_indexing(&workspace_str, &project.project_path, None)
+            .await
+            .unwrap();
+        let project_info = workspace_manager
+            .get_project_info(&workspace_str, &project.project_path)
+            .unwrap();
+        assert_eq!(project_info.schema_version, Some(SCHEMA_VERSION));
+
+        workspace_manager
+            .update_project_schema_version(
+                &workspace_str,
+                &project.project_path,
+                SCHEMA_VERSION - 1,
+            )
+            .unwrap();
+        assert_eq!(
+            workspace_manager
+                .flag_outdated_projects(SCHEMA_VERSION)
+                .unwrap(),
+            vec![(workspace_str.clone(), project.project_path.clone())]
+        );
+
+        let mut event_receiver = event_bus.subscribe();
+        execution
+            .execute_project_reindexing(&workspace_str, &project.project_path, Vec::new(), None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            event_receiver.try_recv().unwrap(),
+            GkgEvent::ProjectIndexing(ProjectIndexingEvent::Started(_))
+        ));
+        let project_info = workspace_manager
+            .get_project_info(&workspace_str, &project.project_path)
+            .unwrap();
+        assert_eq!(project_info.status, Status::Indexed);
+        assert_eq!(project_info.schema_version, Some(SCHEMA_VERSION));
+    }
+
+    #[tokio::test]
+    async fn test_indexing_a_git_ref_records_the_resolved_commit() {
+        let (workspace_manager, _temp_dir, workspace_path) = create_test_workspace_with_projects(1);
+        let project_path = workspace_path.join("test_project1");
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(&project_path)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let first_commit = git(&["rev-parse", "HEAD"]);
+        std::fs::write(project_path.join("later.rb"), "class Later; end\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-m", "Later"]);
+
+        let mut execution = IndexingExecutor::new(
+            Arc::new(KuzuDatabase::new()),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            IndexingConfigBuilder::build(4),
+        )
+        .with_git_ref(Some("HEAD~1".to_string()));
+
+        let workspace_str = workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap()
+            .workspace_folder_path;
+        let project = workspace_manager.list_projects_in_workspace(&workspace_str)[0].clone();
+        execution
+            .execute_project_indexing(&workspace_str, &project.project_path, None)
+            .await
+            .unwrap();
+
+        let project_info = workspace_manager
+            .get_project_info(&workspace_str, &project.project_path)
+            .unwrap();
+        assert_eq!(project_info.indexed_commit, Some(first_commit));
+    }
+
+    #[tokio::test]
+    async fn test_projects_exceeding_the_timeout_are_marked_failed() {
+        let (workspace_manager, _temp_dir, workspace_path) = create_test_workspace_with_projects(2);
+        let event_bus = Arc::new(EventBus::new());
+        let database = Arc::new(KuzuDatabase::new());
+        let mut config = IndexingConfigBuilder::build(4);
+        config.project_timeout = Some(Duration::from_nanos(1));
+        let mut execution = IndexingExecutor::new(
+            database,
+            Arc::clone(&workspace_manager),
+            Arc::clone(&event_bus),
+            config,
+        );
+        let mut event_receiver = event_bus.subscribe();
+
+        workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap();
+        let canonical_workspace_path = workspace_path.canonicalize().unwrap();
+        let workspace_str = canonical_workspace_path.to_string_lossy().to_string();
+
+        // The workspace carries on past the failed projects
+        let workspace_stats = execution
+            .execute_workspace_indexing(canonical_workspace_path, None)
+            .await
+            .unwrap();
+        assert_eq!(workspace_stats.total_projects, 0);
+        assert_eq!(workspace_stats.failed_projects.len(), 2);
+
+        let projects = workspace_manager.list_projects_in_workspace(&workspace_str);
+        assert_eq!(projects.len(), 2);
+        for project in projects {
+            assert_eq!(project.status, Status::Error);
+            assert!(
+                project
+                    .error_message
+                    .is_some_and(|error| error.contains("Timed out after 1ns"))
+            );
+        }
+
+        let mut failed_events = 0;
+        while let Ok(event) = event_receiver.try_recv() {
+            if let GkgEvent::ProjectIndexing(ProjectIndexingEvent::Failed(failed)) = event {
+                assert!(failed.error.contains("Timed out"));
+                failed_events += 1;
+            }
+        }
+        assert!(failed_events >= 2);
+    }
+
     #[tokio::test]
     async fn test_run_project_indexing_project_not_found() {
         let (workspace_manager, _temp_dir) = create_test_workspace_manager();
@@ -1355,4 +1763,291 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_workspace_indexing_links_imports_across_projects() {
+        let (workspace_manager, temp_dir) = create_test_workspace_manager();
+        let workspace_path = temp_dir.path().join("test_workspace");
+        TestRepository::with_files(
+            &workspace_path.join("billing"),
+            &[
+                ("package.json", r#"{ "name": "@acme/billing" }"#),
+                (
+                    "src/index.ts",
+                    "export function createInvoice(amount: number) {\n  return { amount };\n}\n",
+                ),
+            ],
+        );
+        TestRepository::with_files(
+            &workspace_path.join("web"),
+            &[
+                ("package.json", r#"{ "name": "@acme/web" }"#),
+                (
+                    "src/checkout.ts",
+                    "import { createInvoice } from '@acme/billing';\n\nexport function checkout() {\n  return createInvoice(42);\n}\n",
+                ),
+            ],
+        );
+
+        let mut config = IndexingConfigBuilder::build(2);
+        config.cross_project_resolution = true;
+        let mut executor = IndexingExecutor::new(
+            Arc::new(KuzuDatabase::new()),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            config,
+        );
+
+        let workspace_stats = executor
+            .execute_workspace_indexing(workspace_path.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(workspace_stats.total_cross_project_links, 1);
+
+        let workspace_folder_path = &workspace_manager.list_workspace_folders()[0];
+        let links_path = workspace_manager
+            .cross_project_links_path(&workspace_folder_path.workspace_folder_path)
+            .unwrap();
+        let links = crate::execution::cross_project::load_cross_project_links(&links_path).unwrap();
+
+        assert_eq!(links.len(), 1);
+        let link = &links[0];
+        assert!(link.source_project_path.ends_with("web"));
+        assert_eq!(link.source_file_path, "src/checkout.ts");
+        assert_eq!(link.import_path, "@acme/billing");
+        assert_eq!(link.imported_name, "createInvoice");
+        assert!(link.target_project_path.ends_with("billing"));
+        assert_eq!(link.target_file_path, "src/index.ts");
+        assert_eq!(link.target_fqn, "createInvoice");
+        assert_eq!(link.target_start_line, 0);
+    }
+
+    #[tokio::test]
+    async fn test_indexing_invalidates_cached_query_results() {
+        use database::querying::{DatabaseQueryingService, QueryCacheConfig, QueryingService};
+
+        let (workspace_manager, temp_dir) = create_test_workspace_manager();
+        let project_path = temp_dir.path().join("test_workspace").join("billing");
+        TestRepository::with_files(
+            &project_path,
+            &[("billing.rb", "class Billing\n  def charge; end\nend\n")],
+        );
+        let database = Arc::new(KuzuDatabase::new());
+        let mut executor = IndexingExecutor::new(
+            Arc::clone(&database),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            IndexingConfigBuilder::build(2),
+        );
+        executor
+            .execute_workspace_indexing(temp_dir.path().join("test_workspace"), None)
+            .await
+            .unwrap();
+        let project = workspace_manager.list_all_projects().remove(0);
+
+        let query_service = DatabaseQueryingService::new(Arc::clone(&database))
+            .with_cache(QueryCacheConfig::default());
+        let count_definitions = |name: &str| {
+            let mut params = serde_json::Map::new();
+            params.insert("name".to_string(), name.into());
+            let mut result = query_service
+                .execute_query(
+                    project.database_path.clone(),
+                    "MATCH (d:DefinitionNode) WHERE d.name = $name RETURN count(d)".to_string(),
+                    params,
+                )
+                .unwrap();
+            result.next().unwrap().get_int_value(0).unwrap()
+        };
+        assert_eq!(count_definitions("Refunds"), 0);
+
+        let refunds_path = Path::new(&project.project_path).join("refunds.rb");
+        fs::write(&refunds_path, "class Refunds\n  def issue; end\nend\n").unwrap();
+        executor
+            .execute_project_reindexing(
+                &project.workspace_folder_path,
+                &project.project_path,
+                vec![refunds_path.clone()],
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(count_definitions("Refunds"), 1);
+
+        fs::remove_file(&refunds_path).unwrap();
+        executor
+            .execute_project_indexing(&project.workspace_folder_path, &project.project_path, None)
+            .await
+            .unwrap();
+        assert_eq!(count_definitions("Refunds"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_project_reindexing_refreshes_cross_project_links() {
+        let (workspace_manager, temp_dir) = create_test_workspace_manager();
+        let workspace_path = temp_dir.path().join("test_workspace");
+        TestRepository::with_files(
+            &workspace_path.join("billing"),
+            &[
+                ("package.json", r#"{ "name": "@acme/billing" }"#),
+                (
+                    "src/index.ts",
+                    "export function createInvoice(amount: number) {\n  return { amount };\n}\n",
+                ),
+            ],
+        );
+        let web_path = workspace_path.join("web");
+        TestRepository::with_files(
+            &web_path,
+            &[
+                ("package.json", r#"{ "name": "@acme/web" }"#),
+                (
+                    "src/checkout.ts",
+                    "import { createInvoice } from '@acme/billing';\n\nexport function checkout() {\n  return createInvoice(42);\n}\n",
+                ),
+            ],
+        );
+
+        let database = Arc::new(KuzuDatabase::new());
+        let mut config = IndexingConfigBuilder::build(2);
+        config.cross_project_resolution = true;
+        IndexingExecutor::new(
+            Arc::clone(&database),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            config,
+        )
+        .execute_workspace_indexing(workspace_path.clone(), None)
+        .await
+        .unwrap();
+
+        let workspace_folder_path = workspace_manager.list_workspace_folders()[0]
+            .workspace_folder_path
+            .clone();
+        let links_path = workspace_manager
+            .cross_project_links_path(&workspace_folder_path)
+            .unwrap();
+        assert_eq!(
+            crate::execution::cross_project::load_cross_project_links(&links_path)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Reindexed like the server does, without enabling the resolution
+        fs::write(
+            web_path.join("src/checkout.ts"),
+            "export function checkout() {\n  return 42;\n}\n",
+        )
+        .unwrap();
+        let web_project_path = workspace_manager
+            .list_projects_in_workspace(&workspace_folder_path)
+            .into_iter()
+            .map(|project| project.project_path)
+            .find(|project_path| project_path.ends_with("web"))
+            .unwrap();
+        IndexingExecutor::new(
+            database,
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            IndexingConfigBuilder::build(2),
+        )
+        .execute_project_reindexing(
+            &workspace_folder_path,
+            &web_project_path,
+            vec![Path::new(&web_project_path).join("src/checkout.ts")],
+            None,
+        )
+        .await
+        .unwrap();
+
+        let links = crate::execution::cross_project::load_cross_project_links(&links_path).unwrap();
+        assert!(links.is_empty(), "The removed import must not stay linked");
+    }
+
+    #[tokio::test]
+    async fn test_project_indexing_keeps_definition_snapshots() {
+        let (workspace_manager, temp_dir) = create_test_workspace_manager();
+        let project_path = temp_dir.path().join("test_workspace").join("billing");
+        TestRepository::with_files(
+            &project_path,
+            &[("billing.rb", "class Billing\n  def charge\n  end\nend\n")],
+        );
+        let workspace_folder = workspace_manager
+            .register_workspace_folder(&temp_dir.path().join("test_workspace"))
+            .unwrap();
+        let project = workspace_manager
+            .list_projects_in_workspace(&workspace_folder.workspace_folder_path)
+            .remove(0);
+
+        let mut config = IndexingConfigBuilder::build(2);
+        config.definition_snapshot_history = 2;
+        let mut executor = IndexingExecutor::new(
+            Arc::new(KuzuDatabase::new()),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+            config,
+        );
+        let store = SnapshotStore::new(
+            workspace_manager
+                .project_snapshots_directory(
+                    &workspace_folder.workspace_folder_path,
+                    &project.project_path,
+                )
+                .unwrap(),
+        );
+
+        let workspace_folder_path = workspace_folder.workspace_folder_path.as_str();
+        executor
+            .execute_project_indexing(workspace_folder_path, &project.project_path, None)
+            .await
+            .unwrap();
+        fs::write(
+            project_path.join("billing.rb"),
+            "class Billing\n  def charge\n  end\n\n  def refund\n  end\nend\n",
+        )
+        .unwrap();
+        executor
+            .execute_project_indexing(workspace_folder_path, &project.project_path, None)
+            .await
+            .unwrap();
+
+        let snapshots = store.list().unwrap();
+        assert_eq!(snapshots.len(), 2);
+        let diff = crate::snapshots::diff_index(
+            &workspace_manager,
+            &project.project_path,
+            &snapshots[0],
+            &snapshots[1],
+        )
+        .unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert!(diff.added[0].fqn.ends_with("refund"));
+        assert!(diff.removed.is_empty());
+        // The class body grew
+        assert!(
+            diff.changed
+                .iter()
+                .any(|change| change.after.fqn == "Billing")
+        );
+
+        executor
+            .execute_project_indexing(workspace_folder_path, &project.project_path, None)
+            .await
+            .unwrap();
+
+        let pruned_snapshots = store.list().unwrap();
+        assert_eq!(pruned_snapshots.len(), 2);
+        assert!(!pruned_snapshots.contains(&snapshots[0]));
+        assert!(
+            crate::snapshots::diff_index(
+                &workspace_manager,
+                &project.project_path,
+                &pruned_snapshots[0],
+                &pruned_snapshots[1],
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
 }
@@ -0,0 +1,141 @@
+//! The memory budget of the parsing phase. A file reserves its estimated share when it's read
+//! and keeps it while its parsed result waits to be handed to the analysis, so the budget
+//! bounds the files in flight together with the results collected so far.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Usage {
+    /// Reserved by files being read or parsed
+    in_flight_kib: usize,
+    /// Reserved by parsed results waiting for the analysis
+    retained_kib: usize,
+}
+
+pub struct ParseMemoryBudget {
+    budget_kib: usize,
+    usage: Mutex<Usage>,
+    released: Notify,
+}
+
+impl ParseMemoryBudget {
+    pub fn new(budget_bytes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            budget_kib: (budget_bytes / 1024).max(1),
+            usage: Mutex::default(),
+            released: Notify::new(),
+        })
+    }
+
+    fn usage(&self) -> MutexGuard<'_, Usage> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits until `kib` of the budget is free. A file larger than the whole budget takes all
+    /// of it, and once the collected results fill the budget the files are parsed one at a
+    /// time, so parsing always makes progress.
+    pub async fn reserve(self: &Arc<Self>, kib: usize) -> MemoryReservation {
+        let kib = kib.clamp(1, self.budget_kib);
+        loop {
+            // Created before checking so a release in between isn't missed
+            let released = self.released.notified();
+            {
+                let mut usage = self.usage();
+                let used = usage.in_flight_kib + usage.retained_kib;
+                if usage.in_flight_kib == 0 || used + kib <= self.budget_kib {
+                    usage.in_flight_kib += kib;
+                    return MemoryReservation {
+                        budget: Arc::clone(self),
+                        kib,
+                        retained: false,
+                    };
+                }
+            }
+            released.await;
+        }
+    }
+
+    #[cfg(test)]
+    fn used_kib(&self) -> usize {
+        let usage = self.usage();
+        usage.in_flight_kib + usage.retained_kib
+    }
+}
+
+/// A share of the [`ParseMemoryBudget`], given back when dropped
+pub struct MemoryReservation {
+    budget: Arc<ParseMemoryBudget>,
+    kib: usize,
+    retained: bool,
+}
+
+impl MemoryReservation {
+    /// Keeps the reservation for a parsed result until it's handed to the analysis
+    pub fn retain(&mut self) {
+        if self.retained {
+            return;
+        }
+        let mut usage = self.budget.usage();
+        usage.in_flight_kib -= self.kib;
+        usage.retained_kib += self.kib;
+        self.retained = true;
+        drop(usage);
+        // The file is no longer in flight, which may let a waiting file go ahead
+        self.budget.released.notify_waiters();
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        let mut usage = self.budget.usage();
+        if self.retained {
+            usage.retained_kib -= self.kib;
+        } else {
+            usage.in_flight_kib -= self.kib;
+        }
+        drop(usage);
+        self.budget.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_retained_results_count_against_the_budget() {
+        let budget = ParseMemoryBudget::new(10 * 1024);
+        let mut first = budget.reserve(6).await;
+        first.retain();
+        let second = budget.reserve(4).await;
+
+        // 6 KiB retained and 4 KiB in flight, a third file has to wait
+        let waiting = tokio::time::timeout(Duration::from_millis(50), budget.reserve(4)).await;
+        assert!(waiting.is_err());
+
+        drop(second);
+        let third = budget.reserve(4).await;
+        assert_eq!(budget.used_kib(), 10);
+
+        drop(third);
+        drop(first);
+        assert_eq!(budget.used_kib(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_budget_of_results_still_lets_one_file_through() {
+        let budget = ParseMemoryBudget::new(1024);
+        let mut results = Vec::new();
+        for _ in 0..3 {
+            let mut reservation = budget.reserve(1).await;
+            reservation.retain();
+            results.push(reservation);
+        }
+        assert_eq!(budget.used_kib(), 3);
+
+        drop(results);
+        assert_eq!(budget.used_kib(), 0);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod config;
+pub mod cross_project;
 pub mod executor;
+pub mod memory_budget;
+pub mod thread_pools;
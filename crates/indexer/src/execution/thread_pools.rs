@@ -0,0 +1,74 @@
+//! The rayon thread pools the indexer runs CPU bound work on. Their threads are named after the
+//! phase they run, e.g. `gkg-parse-3`, so they can be told apart in profilers and thread dumps.
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub const PARSE_THREAD_PREFIX: &str = "gkg-parse";
+pub const ANALYZE_THREAD_PREFIX: &str = "gkg-analyze";
+
+/// The pools of a phase by thread count, so indexing runs with the same number of workers
+/// share their threads
+type Pools = OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>>;
+
+/// A pool of `threads` threads, or sized like rayon's global pool, i.e. `RAYON_NUM_THREADS`
+/// or one thread per CPU, when `threads` is 0
+fn pool(pools: &'static Pools, prefix: &'static str, threads: usize) -> Arc<ThreadPool> {
+    let mut pools = pools.get_or_init(Default::default).lock().unwrap();
+    let pool = pools.entry(threads).or_insert_with(|| {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(move |index| format!("{prefix}-{index}"))
+            .build()
+            .unwrap_or_else(|e| panic!("Failed to start the {prefix} threads: {e}"));
+        Arc::new(pool)
+    });
+    Arc::clone(pool)
+}
+
+/// Parses the files of a project on the indexer's `threads` workers
+pub fn parse_pool(threads: usize) -> Arc<ThreadPool> {
+    static POOLS: Pools = OnceLock::new();
+    pool(&POOLS, PARSE_THREAD_PREFIX, threads)
+}
+
+/// Runs the analysis of parsed files, including its parallel iterators, on `threads` threads
+pub fn analyze_pool(threads: usize) -> Arc<ThreadPool> {
+    static POOLS: Pools = OnceLock::new();
+    pool(&POOLS, ANALYZE_THREAD_PREFIX, threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current_thread_name() -> String {
+        std::thread::current()
+            .name()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    #[test]
+    fn test_pool_threads_are_named_after_their_phase() {
+        assert!(
+            parse_pool(2)
+                .install(current_thread_name)
+                .starts_with("gkg-parse-")
+        );
+        assert!(
+            analyze_pool(2)
+                .install(current_thread_name)
+                .starts_with("gkg-analyze-")
+        );
+    }
+
+    #[test]
+    fn test_pools_are_sized_by_thread_count() {
+        assert_eq!(parse_pool(3).current_num_threads(), 3);
+        assert_eq!(analyze_pool(1).current_num_threads(), 1);
+        assert!(Arc::ptr_eq(&parse_pool(3), &parse_pool(3)));
+        assert!(!Arc::ptr_eq(&parse_pool(3), &parse_pool(1)));
+    }
+}
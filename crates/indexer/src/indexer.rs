@@ -11,31 +11,51 @@
 // ██║   ██║██╔══██╗██╔══██║██╔═══╝ ██╔══██║
 // ╚██████╔╝██║  ██║██║  ██║██║     ██║  ██║
 //  ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚═╝     ╚═╝  ╚═╝
+use chrono::Utc;
 use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::{FileNodeFromKuzu, KuzuNodeType};
 use database::schema::manager::SchemaManager;
+use database::schema::types::GraphRecordBatches;
 use futures::stream::{self, StreamExt};
 use gitalisk_core::repository::gitalisk_repository::FileInfo;
-use log::{info, warn};
+use kuzu::Database;
+use log::{debug, info, warn};
+use parser_core::parser::SupportedLanguage;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
+use tokio_rayon::AsyncThreadPool;
+use tracing::{Instrument, info_span};
 
 // Simplified imports - file processing is now handled by the File module
 use crate::analysis::{AnalysisService, types::GraphData};
+use crate::execution::config::default_parse_memory_budget;
+use crate::execution::memory_budget::ParseMemoryBudget;
+use crate::execution::thread_pools::{PARSE_THREAD_PREFIX, analyze_pool, parse_pool};
 use crate::mutation::changes::KuzuChanges;
 use database::kuzu::config::DatabaseConfig;
 
-use crate::parsing::processor::FileProcessor;
-use crate::project::source::FileSource;
-use crate::writer::{WriterResult, WriterService};
+use crate::parsing::processor::{
+    DEFAULT_MAX_DEFINITIONS_PER_FILE, DEFAULT_MAX_REFERENCES_PER_FILE, FileProcessor,
+};
+use crate::progress::{IndexingPhase, IndexingProgressSummary, NoopProgressSink, ProgressSink};
+use crate::project::exclude::ExcludeMatcher;
+use crate::project::git_ref::GitCommit;
+use crate::project::source::{FileSource, PathFileSource};
+use crate::project::symlinks::files_below_symlinked_directories;
+use crate::writer::{DEFAULT_PARQUET_BATCH_SIZE, ParquetCompression, WriterResult, WriterService};
 
 use crate::mutation::utils::NodeIdGenerator;
 pub use crate::parsing::changes::{FileChanges, FileChangesPathType};
 pub use crate::parsing::processor::{
     ErroredFile, FileProcessingResult, ProcessingStage, ProcessingStats, SkippedFile,
 };
-use crate::project::io::{ProcessingError, read_text_file};
+use crate::project::io::{
+    InvalidUtf8Policy, ProcessingError, TextEncoding, content_hash, read_text_file,
+};
 use crate::project::source::ChangesFileSource;
 
 type ParseFilesResult = (
@@ -54,11 +74,55 @@ enum IndexingProcessingResult {
     Error(ErroredFile),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct IndexingConfig {
     pub worker_threads: usize,
     pub max_file_size: usize,
     pub respect_gitignore: bool,
+    /// Write the graph to Parquet files and import them into Kuzu. When false, the graph
+    /// is imported straight from memory and no Parquet file is written.
+    pub write_parquet: bool,
+    pub parquet_compression: ParquetCompression,
+    /// Rows written to Parquet per batch, bounds the memory used by the writer
+    pub parquet_batch_size: usize,
+    /// Approximate memory, in bytes, that files being parsed and the parsed results waiting
+    /// for the analysis may hold at once. Once it's used up, files are parsed one at a time.
+    pub parse_memory_budget: usize,
+    /// Compute the betweenness centrality of definitions in the call graph once the graph
+    /// is imported. It takes O(V*E), the call degree is always computed.
+    pub compute_call_betweenness: bool,
+    /// Extensions, without the leading dot, parsed as the given language. They supplement
+    /// and take precedence over the built-in language detection.
+    pub extension_overrides: HashMap<String, SupportedLanguage>,
+    /// Gitignore style patterns, relative to the project root, of files left out of the
+    /// index even when they are tracked by git. They apply after the project's `.gkgignore`.
+    pub exclude_patterns: Vec<String>,
+    /// Once a workspace folder is indexed, link the TypeScript imports of its projects to
+    /// the definitions of the sibling projects publishing the imported packages
+    pub cross_project_resolution: bool,
+    /// Number of definition snapshots kept per project, one taken after each index run so
+    /// they can be diffed. No snapshot is taken when 0.
+    pub definition_snapshot_history: usize,
+    /// Also index the files below symlinked directories. Each real directory is indexed
+    /// once, so symlink cycles are broken.
+    pub follow_symlinks: bool,
+    /// Whether files that are not valid UTF-8 are skipped or decoded lossily and parsed
+    pub invalid_utf8: InvalidUtf8Policy,
+    /// Files with more definitions than this are skipped, protecting the run from
+    /// pathological files such as machine-generated code
+    pub max_definitions_per_file: usize,
+    /// Files with more references than this are skipped
+    pub max_references_per_file: usize,
+    /// Time after which indexing a project is abandoned and the project marked as failed,
+    /// so a hung project doesn't stall the rest of the workspace. Unbounded when `None`.
+    pub project_timeout: Option<Duration>,
+    /// Resolve the references of each file, such as calls, to the definitions they point to.
+    /// When false, only the structural graph of directories, files, definitions and imports
+    /// is built, which is much faster.
+    pub resolve_references: bool,
+    /// Keep only the definitions visible outside of their project, e.g. to document its public
+    /// API. Definitions whose visibility isn't known for their language are kept.
+    pub public_only: bool,
 }
 
 impl Default for IndexingConfig {
@@ -67,8 +131,104 @@ impl Default for IndexingConfig {
             worker_threads: 0,
             max_file_size: 5_000_000,
             respect_gitignore: true,
+            write_parquet: true,
+            parquet_compression: ParquetCompression::default(),
+            parquet_batch_size: DEFAULT_PARQUET_BATCH_SIZE,
+            parse_memory_budget: default_parse_memory_budget(),
+            compute_call_betweenness: false,
+            extension_overrides: HashMap::new(),
+            exclude_patterns: Vec::new(),
+            cross_project_resolution: false,
+            definition_snapshot_history: 0,
+            follow_symlinks: false,
+            invalid_utf8: InvalidUtf8Policy::Skip,
+            max_definitions_per_file: DEFAULT_MAX_DEFINITIONS_PER_FILE,
+            max_references_per_file: DEFAULT_MAX_REFERENCES_PER_FILE,
+            project_timeout: None,
+            resolve_references: true,
+            public_only: false,
+        }
+    }
+}
+
+/// Logs how many of `files` are parsed with an extension override, so users can confirm
+/// that their mapping took effect
+fn log_extension_overrides(
+    files: &[FileInfo],
+    extension_overrides: &HashMap<String, SupportedLanguage>,
+) {
+    if extension_overrides.is_empty() {
+        return;
+    }
+
+    let mut overridden_files: HashMap<&str, usize> = HashMap::new();
+    for file_info in files {
+        if extension_overrides.contains_key(file_info.extension()) {
+            *overridden_files.entry(file_info.extension()).or_default() += 1;
         }
     }
+
+    for (extension, language) in extension_overrides {
+        info!(
+            "Parsing {} file(s) with extension .{extension} as {language:?} (extension override)",
+            overridden_files.get(extension.as_str()).unwrap_or(&0)
+        );
+    }
+}
+
+/// Rough ratio between the memory used while parsing a file and its size on disk
+const PARSE_MEMORY_FACTOR: u64 = 8;
+
+/// Estimated memory needed to read and parse a file, in KiB
+async fn estimated_parse_memory_kib(path: &Path) -> usize {
+    let file_len = tokio::fs::metadata(path)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    (file_len * PARSE_MEMORY_FACTOR).div_ceil(1024).max(1) as usize
+}
+
+/// Where the graph data imported into Kuzu comes from
+enum ImportSource<'a> {
+    Parquet(&'a str),
+    Memory(&'a GraphRecordBatches),
+}
+
+/// A file that was parsed and added to the graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFileReport {
+    pub file_path: String,
+    pub language: String,
+    pub file_size: u64,
+    pub definition_count: usize,
+    /// Whether invalid UTF-8 sequences were replaced before parsing
+    pub lossy_decoded: bool,
+}
+
+impl From<&FileProcessingResult> for IndexedFileReport {
+    fn from(file_result: &FileProcessingResult) -> Self {
+        Self {
+            file_path: file_result.file_path.clone(),
+            language: format!("{:?}", file_result.language),
+            file_size: file_result.file_size,
+            definition_count: file_result.definitions.count(),
+            lossy_decoded: file_result.lossy_decoded,
+        }
+    }
+}
+
+/// A file that failed to be read or parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErroredFileReport {
+    pub file_path: String,
+    pub error: String,
+}
+
+/// A file that was left out of the graph, e.g. because it is too large or not UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFileReport {
+    pub file_path: String,
+    pub reason: String,
 }
 
 pub struct RepositoryIndexingResult {
@@ -82,6 +242,32 @@ pub struct RepositoryIndexingResult {
     pub writer_result: Option<WriterResult>,
     pub database_path: Option<String>,
     pub database_loaded: bool,
+    /// Files left out because they match an exclude pattern
+    pub excluded_files: usize,
+    indexed_files: Vec<IndexedFileReport>,
+}
+
+impl RepositoryIndexingResult {
+    /// Files that were parsed successfully, with their language and definition count
+    pub fn successful_files(&self) -> &[IndexedFileReport] {
+        &self.indexed_files
+    }
+
+    /// Files that failed to be read or parsed, with the error
+    pub fn errored_files(&self) -> impl Iterator<Item = ErroredFileReport> + '_ {
+        self.errored_files.iter().map(|errored| ErroredFileReport {
+            file_path: errored.file_path.clone(),
+            error: errored.error_message.clone(),
+        })
+    }
+
+    /// Files that were skipped, with the reason
+    pub fn skipped_files(&self) -> impl Iterator<Item = SkippedFileReport> + '_ {
+        self.skipped_files.iter().map(|skipped| SkippedFileReport {
+            file_path: skipped.file_path.clone(),
+            reason: skipped.reason.clone(),
+        })
+    }
 }
 
 pub struct RepositoryReindexingResult {
@@ -95,6 +281,8 @@ pub struct RepositoryReindexingResult {
     pub writer_result: Option<WriterResult>,
     pub database_path: Option<String>,
     pub database_loaded: bool,
+    /// Changed files that were not reparsed because their content hash is unchanged
+    pub unchanged_files: usize,
 }
 
 #[derive(Debug)]
@@ -112,6 +300,8 @@ pub enum FatalIndexingError {
     FailedToWrite(AnalyzeAndWriteErrors),
     FailedToLoadDatabase(AnalyzeAndWriteErrors),
     FailedToSyncChanges(String),
+    /// The project took longer than [`IndexingConfig::project_timeout`]
+    TimedOut(Duration),
 }
 
 impl std::fmt::Display for FatalIndexingError {
@@ -129,6 +319,9 @@ impl std::fmt::Display for FatalIndexingError {
             FatalIndexingError::FailedToSyncChanges(msg) => {
                 write!(f, "Failed to sync changes: {msg}")
             }
+            FatalIndexingError::TimedOut(timeout) => {
+                write!(f, "Timed out after {timeout:?}")
+            }
         }
     }
 }
@@ -136,15 +329,26 @@ impl std::fmt::Display for FatalIndexingError {
 pub struct RepositoryIndexer {
     pub name: String,
     pub path: String,
+    progress: Arc<dyn ProgressSink>,
 }
 
 impl RepositoryIndexer {
     pub fn new(name: String, path: String) -> Self {
-        Self { name, path }
+        Self {
+            name,
+            path,
+            progress: Arc::new(NoopProgressSink),
+        }
     }
 
     pub fn with_name(name: String, path: String) -> Self {
-        Self { name, path }
+        Self::new(name, path)
+    }
+
+    /// Report parsing progress and phase changes to `progress`
+    pub fn with_progress_sink(mut self, progress: Arc<dyn ProgressSink>) -> Self {
+        self.progress = progress;
+        self
     }
 
     pub fn path(&self) -> &str {
@@ -163,22 +367,31 @@ impl RepositoryIndexer {
         let start_time = Instant::now();
         info!("Starting repository indexing for: {}", self.name);
 
-        let files = file_source
-            .get_files(config)
-            .map_err(|e| FatalIndexingError::FailedToGetFiles(e.to_string()))?;
+        let commit = file_source.commit().cloned();
+        if let Some(commit) = &commit {
+            info!("Indexing commit {} of: {}", commit.id(), self.name);
+        }
+        // Commits are read from git, which doesn't follow symlinks
+        let follow_symlinks = config.follow_symlinks && commit.is_none();
+        let (files, excluded_files) = info_span!("list_files")
+            .in_scope(|| self.get_files(file_source, config, follow_symlinks))?;
 
         let total_files = files.len();
 
-        let (file_results, skipped_files, errored_files, errors) =
-            self.parse_files(files, config).await?;
+        let (file_results, skipped_files, errored_files, errors) = self
+            .parse_files_at_commit(files, config, commit.as_ref())
+            .instrument(info_span!("parse", files = total_files))
+            .await?;
 
         let file_results_len = file_results.len();
+        let indexed_files = file_results.iter().map(IndexedFileReport::from).collect();
 
         let (graph_data, writer_result) = self.analyze_and_write_graph_data(
             database,
             file_results,
             output_directory,
             database_path,
+            config,
         )?;
 
         let skipped_files_len = skipped_files.len();
@@ -195,11 +408,21 @@ impl RepositoryIndexer {
             writer_result: None,
             database_path: None,
             database_loaded: false,
+            excluded_files,
+            indexed_files,
         };
 
         indexing_result.graph_data = Some(graph_data);
         indexing_result.writer_result = Some(writer_result);
 
+        self.progress.on_complete(&IndexingProgressSummary {
+            repository_path: self.path.clone(),
+            processed_files: file_results_len,
+            skipped_files: skipped_files_len,
+            errored_files: errored_files_len,
+            elapsed: indexing_result.total_processing_time,
+        });
+
         info!(
             "✅ Repository indexing completed for '{}' in {:?}",
             self.name, indexing_result.total_processing_time
@@ -219,6 +442,16 @@ impl RepositoryIndexer {
         &self,
         files: Vec<FileInfo>,
         config: &IndexingConfig,
+    ) -> Result<ParseFilesResult, FatalIndexingError> {
+        self.parse_files_at_commit(files, config, None).await
+    }
+
+    /// Parse `files` with their contents read from `commit`, or from disk when it's `None`
+    pub async fn parse_files_at_commit(
+        &self,
+        files: Vec<FileInfo>,
+        config: &IndexingConfig,
+        commit: Option<&GitCommit>,
     ) -> Result<ParseFilesResult, FatalIndexingError> {
         if files.is_empty() {
             return Ok((Vec::new(), Vec::new(), Vec::new(), Vec::new()));
@@ -226,6 +459,7 @@ impl RepositoryIndexer {
 
         let total_files = files.len();
         info!("Processing {total_files} files");
+        self.progress.on_phase_changed(IndexingPhase::Parsing);
 
         // Calculate optimal worker count
         let num_cores = num_cpus::get();
@@ -235,10 +469,14 @@ impl RepositoryIndexer {
             config.worker_threads
         };
 
-        info!("Using {worker_count} CPU workers (spawn_blocking)");
+        info!("Using {worker_count} CPU workers ({PARSE_THREAD_PREFIX} threads)");
         // FIXME: make this configurable in the future
         let io_concurrency = std::cmp::max(worker_count * 2, 8);
         let cpu_sem = Arc::new(Semaphore::new(worker_count));
+        let parse_pool = parse_pool(worker_count);
+        // Reserved from reading a file until its parsed result is handed to the analysis
+        let memory_budget = ParseMemoryBudget::new(config.parse_memory_budget);
+        let mut memory_reservations = Vec::with_capacity(total_files);
 
         // Collect results
         let mut file_results = Vec::with_capacity(total_files);
@@ -248,9 +486,15 @@ impl RepositoryIndexer {
 
         let repo_path = self.path.clone();
         let max_file_size = config.max_file_size;
+        let invalid_utf8 = config.invalid_utf8;
+        let max_definitions = config.max_definitions_per_file;
+        let max_references = config.max_references_per_file;
         let start_time = Instant::now();
         let mut last_progress = 0usize;
 
+        let extension_overrides = &config.extension_overrides;
+        log_extension_overrides(&files, extension_overrides);
+
         // Stage 1: async read -> Stage 2: CPU parse (bounded)
         // TODO: investigate splitting the pipeline into multiple modules
         // and turning the entire execution pipeline into a streaming model.
@@ -261,31 +505,80 @@ impl RepositoryIndexer {
             } else {
                 Path::new(&repo_path).join(&file_info.path)
             };
-            (file_info, full_path)
+            let language_override = extension_overrides.get(file_info.extension()).copied();
+            (file_info, full_path, language_override)
         }))
-        .map(move |(file_info, full_path)| async move {
-            let content_res = read_text_file(&full_path, max_file_size).await;
-            (file_info, content_res)
+        .map(move |(file_info, full_path, language_override)| {
+            let memory_budget = Arc::clone(&memory_budget);
+            let commit = commit.cloned();
+            async move {
+                // Block until enough of the memory budget is free. Files read from a commit
+                // are estimated from their working tree copy, if any.
+                let memory_reservation = memory_budget
+                    .reserve(estimated_parse_memory_kib(&full_path).await)
+                    .await;
+                let content_res = match commit {
+                    Some(commit) => {
+                        let relative_path = full_path
+                            .strip_prefix(commit.repository_path())
+                            .unwrap_or(&full_path)
+                            .to_string_lossy()
+                            .to_string();
+                        commit
+                            .read_text_file(&relative_path, max_file_size, invalid_utf8)
+                            .await
+                    }
+                    None => read_text_file(&full_path, max_file_size, invalid_utf8).await,
+                };
+                (
+                    file_info,
+                    content_res,
+                    memory_reservation,
+                    language_override,
+                )
+            }
         })
         .buffer_unordered(io_concurrency)
-        .map(|(file_info, content_res)| {
+        .map(|(file_info, content_res, memory_reservation, language)| {
             let cpu_sem = Arc::clone(&cpu_sem);
+            let parse_pool = Arc::clone(&parse_pool);
             async move {
-                match content_res {
-                    Ok(content) => {
+                let result = match content_res {
+                    Ok(decoded) => {
+                        let (encoding, lossy_decoded) = (decoded.encoding, decoded.lossy);
+                        let raw_content_hash = decoded.content_hash;
+                        if encoding != TextEncoding::Utf8 {
+                            debug!("Decoded {} from {encoding}", file_info.path.display());
+                        }
+                        if lossy_decoded {
+                            warn!(
+                                "Replaced invalid UTF-8 sequences in {} before parsing",
+                                file_info.path.display()
+                            );
+                        }
                         // Acquire CPU permit then parse in blocking pool
                         let _permit = cpu_sem.acquire_owned().await.expect("semaphore closed");
                         let file_path_for_error = file_info.path.to_string_lossy().to_string();
                         let fi_for_parse = file_info;
 
-                        let parse_res = tokio_rayon::spawn(move || {
-                            let processor = FileProcessor::from_file_info(fi_for_parse, &content);
-                            processor.process()
-                        })
-                        .await;
+                        let parse_res = parse_pool
+                            .spawn_async(move || {
+                                FileProcessor::from_file_info(fi_for_parse, &decoded.text)
+                                    .with_language_override(language)
+                                    .with_max_counts(max_definitions, max_references)
+                                    .process()
+                            })
+                            .await;
 
                         match parse_res {
-                            crate::parsing::processor::ProcessingResult::Success(file_result) => {
+                            crate::parsing::processor::ProcessingResult::Success(
+                                mut file_result,
+                            ) => {
+                                // Hash the bytes on disk, which is what unchanged files are
+                                // compared with, rather than the decoded text
+                                file_result.content_hash = raw_content_hash;
+                                file_result.encoding = encoding;
+                                file_result.lossy_decoded = lossy_decoded;
                                 IndexingProcessingResult::Success(file_result)
                             }
                             crate::parsing::processor::ProcessingResult::Skipped(skipped) => {
@@ -319,27 +612,36 @@ impl RepositoryIndexer {
                             })
                         }
                     },
-                }
+                };
+                (result, memory_reservation)
             }
         })
         .buffer_unordered(worker_count);
 
         tokio::pin!(pipeline);
-        while let Some(result) = pipeline.next().await {
+        while let Some((result, mut memory_reservation)) = pipeline.next().await {
+            let completed = file_results.len() + skipped_files.len() + errored_files.len() + 1;
             match result {
                 IndexingProcessingResult::Success(file_result) => {
+                    self.progress
+                        .on_file_parsed(&file_result.file_path, completed, total_files);
                     file_results.push(file_result);
+                    memory_reservation.retain();
+                    memory_reservations.push(memory_reservation);
                 }
                 IndexingProcessingResult::Skipped(skipped) => {
+                    self.progress
+                        .on_file_parsed(&skipped.file_path, completed, total_files);
                     skipped_files.push(skipped);
                 }
                 IndexingProcessingResult::Error(errored) => {
+                    self.progress
+                        .on_file_parsed(&errored.file_path, completed, total_files);
                     errors.push((errored.file_path.clone(), errored.error_message.clone()));
                     errored_files.push(errored);
                 }
             }
 
-            let completed = file_results.len() + skipped_files.len() + errored_files.len();
             let progress = (completed * 100) / total_files;
             if progress >= last_progress + 10 && progress <= 100 {
                 let elapsed = start_time.elapsed();
@@ -367,20 +669,59 @@ impl RepositoryIndexer {
             final_completed
         );
 
+        // The results are handed to the analysis, which takes over their memory
+        drop(memory_reservations);
         Ok((file_results, skipped_files, errored_files, errors))
     }
 
+    /// Enumerates the files of `file_source`, and with `follow_symlinks` the files below the
+    /// symlinked directories of the repository, and drops the ones matching the project's
+    /// `.gkgignore` or `config.exclude_patterns`, returning the files to index and the
+    /// excluded count
     fn get_files<F: FileSource>(
         &self,
         file_source: F,
         config: &IndexingConfig,
-    ) -> Result<Vec<FileInfo>, FatalIndexingError> {
-        file_source
+        follow_symlinks: bool,
+    ) -> Result<(Vec<FileInfo>, usize), FatalIndexingError> {
+        let mut files = file_source
             .get_files(config)
-            .map_err(|e| FatalIndexingError::FailedToGetFiles(e.to_string()))
+            .map_err(|e| FatalIndexingError::FailedToGetFiles(e.to_string()))?;
+
+        if follow_symlinks {
+            let symlinked_files =
+                files_below_symlinked_directories(Path::new(&self.path), config.respect_gitignore)
+                    .into_iter()
+                    .map(FileInfo::from_path)
+                    .collect();
+            let symlinked_files = PathFileSource::new(symlinked_files)
+                .get_files(config)
+                .map_err(|e| FatalIndexingError::FailedToGetFiles(e.to_string()))?;
+            info!(
+                "Found {} file(s) below symlinked directories in repository: {}",
+                symlinked_files.len(),
+                self.name
+            );
+            files.extend(symlinked_files);
+        }
+
+        let exclude_matcher = ExcludeMatcher::new(Path::new(&self.path), &config.exclude_patterns)
+            .map_err(|e| {
+                FatalIndexingError::FailedToGetFiles(format!("Invalid exclude pattern: {e}"))
+            })?;
+        let (files, excluded_files) = exclude_matcher.exclude(files);
+        if excluded_files > 0 {
+            info!(
+                "Excluded {excluded_files} file(s) matching .gkgignore or the exclude patterns in repository: {}",
+                self.name
+            );
+        }
+
+        Ok((files, excluded_files))
     }
 
-    /// Analyze processed files, write graph data to Parquet files, and load into Kuzu database
+    /// Analyze processed files, write graph data to Parquet files, and load into Kuzu database.
+    /// With `config.write_parquet` set to false, the graph data is loaded from memory instead.
     /// FIXME: SEPARATE THIS INTO A SEPARATE MODULE/EXECUTOR
     pub fn analyze_and_write_graph_data(
         &self,
@@ -388,6 +729,7 @@ impl RepositoryIndexer {
         file_results: Vec<FileProcessingResult>,
         output_directory: &str,
         database_path: &str,
+        config: &IndexingConfig,
     ) -> Result<(GraphData, WriterResult), FatalIndexingError> {
         info!(
             "Starting analysis and writing phase for repository: {}",
@@ -395,10 +737,14 @@ impl RepositoryIndexer {
         );
         let start_time = Instant::now();
 
-        let analysis_service = AnalysisService::new(self.name.clone(), self.path.clone());
+        self.progress.on_phase_changed(IndexingPhase::Analyzing);
+        let analysis_service = AnalysisService::new(self.name.clone(), self.path.clone())
+            .with_resolve_references(config.resolve_references)
+            .with_public_only(config.public_only);
 
-        let mut graph_data = analysis_service
-            .analyze_results(file_results)
+        let analyze_span = info_span!("analyze", files = file_results.len());
+        let mut graph_data = analyze_pool(config.worker_threads)
+            .install(|| analyze_span.in_scope(|| analysis_service.analyze_results(file_results)))
             .map_err(|e| {
                 FatalIndexingError::FailedToAnalyze(AnalyzeAndWriteErrors::FailedToAnalyze(
                     e.to_string(),
@@ -413,14 +759,53 @@ impl RepositoryIndexer {
             graph_data.relationships.len()
         );
 
-        let writer_service = WriterService::new(output_directory).map_err(|e| {
-            FatalIndexingError::FailedToWrite(AnalyzeAndWriteErrors::FailedToWrite(e.to_string()))
-        })?;
-
         let mut node_id_generator = NodeIdGenerator::new();
 
-        let writer_result = writer_service
-            .write_graph_data(&mut graph_data, &mut node_id_generator)
+        self.progress.on_phase_changed(IndexingPhase::Writing);
+        if !config.write_parquet {
+            let (batches, writer_result) = info_span!("convert_graph_data")
+                .in_scope(|| {
+                    WriterService::convert_graph_data(&mut graph_data, &mut node_id_generator)
+                })
+                .map_err(|e| {
+                    FatalIndexingError::FailedToWrite(AnalyzeAndWriteErrors::FailedToWrite(
+                        e.to_string(),
+                    ))
+                })?;
+
+            info!(
+                "✅ Analysis completed in {:?}, skipping Parquet output",
+                start_time.elapsed()
+            );
+
+            info!("Loading graph data into Kuzu database at: {database_path}");
+            self.progress.on_phase_changed(IndexingPhase::Loading);
+            self.load_into_database(
+                database,
+                ImportSource::Memory(&batches),
+                database_path,
+                config.compute_call_betweenness,
+            )
+            .map_err(|e| {
+                FatalIndexingError::FailedToLoadDatabase(
+                    AnalyzeAndWriteErrors::FailedToLoadDatabase(e.to_string()),
+                )
+            })?;
+
+            return Ok((graph_data, writer_result));
+        }
+
+        let writer_service = WriterService::new(output_directory)
+            .map_err(|e| {
+                FatalIndexingError::FailedToWrite(AnalyzeAndWriteErrors::FailedToWrite(
+                    e.to_string(),
+                ))
+            })?
+            .with_compression(config.parquet_compression)
+            .with_batch_size(config.parquet_batch_size);
+
+        let writer_result = info_span!("write_parquet")
+            .in_scope(|| writer_service.write_graph_data(&mut graph_data, &mut node_id_generator))
             .map_err(|e| {
                 FatalIndexingError::FailedToWrite(AnalyzeAndWriteErrors::FailedToWrite(
                     e.to_string(),
@@ -435,12 +820,18 @@ impl RepositoryIndexer {
         );
 
         info!("Loading graph data into Kuzu database at: {database_path}");
-        self.load_into_database(database, output_directory, database_path)
-            .map_err(|e| {
-                FatalIndexingError::FailedToLoadDatabase(
-                    AnalyzeAndWriteErrors::FailedToLoadDatabase(e.to_string()),
-                )
-            })?;
+        self.progress.on_phase_changed(IndexingPhase::Loading);
+        self.load_into_database(
+            database,
+            ImportSource::Parquet(output_directory),
+            database_path,
+            config.compute_call_betweenness,
+        )
+        .map_err(|e| {
+            FatalIndexingError::FailedToLoadDatabase(AnalyzeAndWriteErrors::FailedToLoadDatabase(
+                e.to_string(),
+            ))
+        })?;
 
         Ok((graph_data, writer_result))
     }
@@ -469,7 +860,7 @@ impl RepositoryIndexer {
     pub async fn reindex_repository(
         &mut self,
         database: &KuzuDatabase,
-        file_changes: FileChanges,
+        mut file_changes: FileChanges,
         config: &IndexingConfig,
         database_path: &str,
         output_path: &str,
@@ -478,18 +869,7 @@ impl RepositoryIndexer {
 
         if !file_changes.has_changes() {
             warn!("No files to process in repository: {}", self.name);
-            return Ok(RepositoryReindexingResult {
-                total_processing_time: start_time.elapsed(),
-                repository_name: self.name.clone(),
-                repository_path: self.path.clone(),
-                skipped_files: Vec::new(),
-                errored_files: Vec::new(),
-                errors: Vec::new(),
-                graph_data: None,
-                writer_result: None,
-                database_path: Some(database_path.to_string()),
-                database_loaded: false,
-            });
+            return Ok(self.no_changes_result(start_time, database_path, 0));
         }
 
         let database_instance = database.get_or_create_database(database_path, None);
@@ -504,16 +884,35 @@ impl RepositoryIndexer {
         }
         let database_instance = database_instance.unwrap();
 
+        let unchanged_files = self
+            .skip_unchanged_files(&database_instance, &mut file_changes)
+            .await;
+        if !file_changes.has_changes() {
+            info!(
+                "All {unchanged_files} changed files in repository {} have unchanged content",
+                self.name
+            );
+            return Ok(self.no_changes_result(start_time, database_path, unchanged_files));
+        }
+
         let file_source = ChangesFileSource::new(&file_changes, self.path.clone());
-        let files = self.get_files(file_source, config)?;
+        let (files, _) = self.get_files(file_source, config, false)?;
 
-        let (file_results, skipped_files, errored_files, errors) =
-            self.parse_files(files, config).await?;
+        let parse_span = info_span!("parse", files = files.len());
+        let (file_results, skipped_files, errored_files, errors) = self
+            .parse_files(files, config)
+            .instrument(parse_span)
+            .await?;
+        let file_results_len = file_results.len();
 
-        let analysis_service = AnalysisService::new(self.name.clone(), self.path.clone());
+        self.progress.on_phase_changed(IndexingPhase::Analyzing);
+        let analysis_service = AnalysisService::new(self.name.clone(), self.path.clone())
+            .with_resolve_references(config.resolve_references)
+            .with_public_only(config.public_only);
 
-        let graph_data = analysis_service
-            .analyze_results(file_results)
+        let analyze_span = info_span!("analyze", files = file_results_len);
+        let graph_data = analyze_pool(config.worker_threads)
+            .install(|| analyze_span.in_scope(|| analysis_service.analyze_results(file_results)))
             .map_err(|e| {
                 FatalIndexingError::FailedToAnalyze(AnalyzeAndWriteErrors::FailedToAnalyze(
                     e.to_string(),
@@ -527,53 +926,192 @@ impl RepositoryIndexer {
             graph_data,
             &self.path,
             output_path,
+            config,
         );
 
-        kuzu_syncer
-            .sync_changes()
-            .map(|writer_result| RepositoryReindexingResult {
-                total_processing_time: start_time.elapsed(),
-                repository_name: self.name.clone(),
-                repository_path: self.path.clone(),
-                skipped_files,
-                errored_files,
-                errors,
-                graph_data: None,
-                writer_result: Some(writer_result),
-                database_path: Some(database_path.to_string()),
-                database_loaded: true,
-            })
-            .map_err(|e| FatalIndexingError::FailedToSyncChanges(e.to_string()))
+        self.progress.on_phase_changed(IndexingPhase::Loading);
+        let writer_result = info_span!("sync_changes")
+            .in_scope(|| kuzu_syncer.sync_changes())
+            .map_err(|e| FatalIndexingError::FailedToSyncChanges(e.to_string()))?;
+
+        let total_processing_time = start_time.elapsed();
+        self.progress.on_complete(&IndexingProgressSummary {
+            repository_path: self.path.clone(),
+            processed_files: file_results_len,
+            skipped_files: skipped_files.len(),
+            errored_files: errored_files.len(),
+            elapsed: total_processing_time,
+        });
+
+        Ok(RepositoryReindexingResult {
+            total_processing_time,
+            repository_name: self.name.clone(),
+            repository_path: self.path.clone(),
+            skipped_files,
+            errored_files,
+            errors,
+            graph_data: None,
+            writer_result: Some(writer_result),
+            database_path: Some(database_path.to_string()),
+            database_loaded: true,
+            unchanged_files,
+        })
     }
 
-    /// Load Parquet data into Kuzu database
+    /// Drop the changed files whose content hash equals the one stored with their `FileNode`,
+    /// e.g. files that were only touched, so they are not reparsed. Returns how many were
+    /// dropped.
+    async fn skip_unchanged_files(
+        &self,
+        database: &Database,
+        file_changes: &mut FileChanges,
+    ) -> usize {
+        let relative_paths =
+            file_changes.get_rel_paths(FileChangesPathType::ChangedFiles, &self.path);
+        let file_nodes = NodeDatabaseService::new(database).get_by::<String, FileNodeFromKuzu>(
+            KuzuNodeType::FileNode,
+            "path",
+            &relative_paths,
+        );
+        let stored_hashes: HashMap<String, String> = match file_nodes {
+            Ok(file_nodes) => file_nodes
+                .into_iter()
+                .filter(|file_node| !file_node.content_hash.is_empty())
+                .map(|file_node| (file_node.path, file_node.content_hash))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to get the stored content hashes, reparsing all changed files: {e}");
+                return 0;
+            }
+        };
+        if stored_hashes.is_empty() {
+            return 0;
+        }
+
+        let repository_path = Path::new(&self.path);
+        let changed_files = std::mem::take(&mut file_changes.changed_files);
+        let changed_files_count = changed_files.len();
+        for file_path in changed_files {
+            let relative_path = Path::new(&file_path)
+                .strip_prefix(repository_path)
+                .unwrap_or(Path::new(&file_path))
+                .to_string_lossy()
+                .into_owned();
+            let unchanged = match stored_hashes.get(&relative_path) {
+                Some(stored_hash) => tokio::fs::read(repository_path.join(&file_path))
+                    .await
+                    .is_ok_and(|content| content_hash(&content) == *stored_hash),
+                None => false,
+            };
+            if !unchanged {
+                file_changes.changed_files.insert(file_path);
+            }
+        }
+
+        changed_files_count - file_changes.changed_files.len()
+    }
+
+    fn no_changes_result(
+        &self,
+        start_time: Instant,
+        database_path: &str,
+        unchanged_files: usize,
+    ) -> RepositoryReindexingResult {
+        self.progress.on_complete(&IndexingProgressSummary {
+            repository_path: self.path.clone(),
+            processed_files: 0,
+            skipped_files: 0,
+            errored_files: 0,
+            elapsed: start_time.elapsed(),
+        });
+        RepositoryReindexingResult {
+            total_processing_time: start_time.elapsed(),
+            repository_name: self.name.clone(),
+            repository_path: self.path.clone(),
+            skipped_files: Vec::new(),
+            errored_files: Vec::new(),
+            errors: Vec::new(),
+            graph_data: None,
+            writer_result: None,
+            database_path: Some(database_path.to_string()),
+            database_loaded: false,
+            unchanged_files,
+        }
+    }
+
+    /// Load Parquet or in-memory data into Kuzu database
+    /// The data is loaded into a staging database that only replaces the existing one once
+    /// the import succeeded, so a failed load leaves the previous graph in place.
     /// FIXME: SEPARATE THIS INTO A SEPARATE MODULE/EXECUTOR
     fn load_into_database(
         &self,
         database: &KuzuDatabase,
-        parquet_directory: &str,
+        source: ImportSource,
         database_path: &str,
+        compute_call_betweenness: bool,
     ) -> Result<(), String> {
+        let _span = info_span!("load_database").entered();
         info!("Initializing Kuzu database and loading graph data...");
 
-        let config = DatabaseConfig::new(database_path)
+        let staging_path = format!("{database_path}.staging");
+        if let Err(e) = self.load_into_staging_database(
+            database,
+            source,
+            &staging_path,
+            compute_call_betweenness,
+        ) {
+            if let Err(cleanup_error) = database.remove_database(&staging_path) {
+                warn!("Failed to remove staging database {staging_path}: {cleanup_error}");
+            }
+            return Err(format!(
+                "{e}. The import was rolled back and the previous database was kept."
+            ));
+        }
+
+        database
+            .replace_database(&staging_path, database_path)
+            .map_err(|e| format!("Failed to replace database {database_path}: {e}"))
+    }
+
+    fn load_into_staging_database(
+        &self,
+        database: &KuzuDatabase,
+        source: ImportSource,
+        staging_path: &str,
+        compute_call_betweenness: bool,
+    ) -> Result<(), String> {
+        let config = DatabaseConfig::new(staging_path)
             .with_buffer_size(512 * 1024 * 1024)
             .with_compression(true);
 
         let database_instance = database
-            .force_new_database(database_path, Some(config))
-            .ok_or(format!("Failed to create database: {database_path}."))?;
-
-        let database_instance = database_instance;
+            .force_new_database(staging_path, Some(config))
+            .ok_or(format!("Failed to create database: {staging_path}."))?;
 
         let schema_manager = SchemaManager::new(&database_instance);
         schema_manager
             .initialize_schema()
             .map_err(|e| format!("Failed to initialize database schema: {e:?}"))?;
 
-        schema_manager
-            .import_graph_data(parquet_directory)
-            .map_err(|e| format!("Failed to import graph data: {e:?}"))?;
+        info_span!("import_graph_data")
+            .in_scope(|| match source {
+                ImportSource::Parquet(parquet_directory) => {
+                    schema_manager.import_graph_data(parquet_directory)
+                }
+                ImportSource::Memory(batches) => schema_manager.import_graph_batches(batches),
+            })
+            .map_err(|e| format!("Failed to import graph data: {e}"))?;
+
+        NodeDatabaseService::new(&database_instance)
+            .record_index_time(Utc::now().timestamp_millis())
+            .map_err(|e| format!("Failed to record the index time: {e}"))?;
+
+        info_span!("compute_call_centrality")
+            .in_scope(|| {
+                NodeDatabaseService::new(&database_instance)
+                    .compute_call_centrality(compute_call_betweenness)
+            })
+            .map_err(|e| format!("Failed to compute call centrality: {e}"))?;
 
         match schema_manager.get_schema_stats() {
             Ok(stats) => {
@@ -3,7 +3,9 @@ pub mod execution;
 pub mod indexer;
 pub mod mutation;
 pub mod parsing;
+pub mod progress;
 pub mod project;
+pub mod snapshots;
 pub mod stats;
 pub mod writer;
 
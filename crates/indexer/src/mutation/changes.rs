@@ -1,26 +1,29 @@
 use database::kuzu::service::NodeDatabaseService;
 use database::kuzu::types::{
-    DefinitionNodeFromKuzu, DirectoryNodeFromKuzu, FileNodeFromKuzu, FromKuzuNode,
-    ImportedSymbolNodeFromKuzu, KuzuNodeType,
+    DefinitionNodeFromKuzu, DirectoryNodeFromKuzu, FileNodeFromKuzu, ImportedSymbolNodeFromKuzu,
+    KuzuNodeType,
 };
 use database::schema::manager::SchemaManager;
 use kuzu::Database;
 
 use crate::analysis::types::GraphData;
+use crate::indexer::IndexingConfig;
 use crate::mutation::utils::NodeIdGenerator;
 use crate::parsing::changes::{FileChanges, FileChangesPathType};
-use crate::writer::{WriterResult, WriterService};
+use crate::writer::{ParquetCompression, WriterResult, WriterService};
 use anyhow::Error;
+use chrono::Utc;
 use tracing::error;
 
+/// The paths whose nodes are replaced by an incremental reindex, relative to the repository.
 #[derive(Debug, Clone)]
-pub struct KuzuChangesIds {
-    pub deleted_definition_ids: Vec<u32>,
-    pub deleted_imported_symbol_ids: Vec<u32>,
-    pub deleted_file_ids: Vec<u32>,
-    pub deleted_directory_ids: Vec<u32>,
-    pub changed_file_paths: Vec<String>,
+pub struct KuzuChangeScope {
+    /// Added, modified and deleted files. Everything that originated in them is deleted
+    /// and the nodes of the files that still exist are imported again.
+    pub affected_file_paths: Vec<String>,
     pub changed_dir_paths: Vec<String>,
+    /// Deleted directories, with a trailing `/`. Everything below them is deleted.
+    pub deleted_dir_prefixes: Vec<String>,
 }
 
 pub struct KuzuChanges<'a> {
@@ -30,6 +33,11 @@ pub struct KuzuChanges<'a> {
     pub graph_data: GraphData,
     pub repo_path: String,
     pub output_path: String,
+    /// Go through Parquet files in `output_path` instead of importing from memory
+    pub write_parquet: bool,
+    pub parquet_compression: ParquetCompression,
+    pub parquet_batch_size: usize,
+    pub compute_call_betweenness: bool,
 }
 
 impl<'a> KuzuChanges<'a> {
@@ -39,6 +47,7 @@ impl<'a> KuzuChanges<'a> {
         graph_data: GraphData,
         repo_path: &str,
         output_path: &str,
+        config: &IndexingConfig,
     ) -> Self {
         Self {
             database,
@@ -47,12 +56,16 @@ impl<'a> KuzuChanges<'a> {
             graph_data,
             repo_path: repo_path.to_string(),
             output_path: output_path.to_string(),
+            write_parquet: config.write_parquet,
+            parquet_compression: config.parquet_compression,
+            parquet_batch_size: config.parquet_batch_size,
+            compute_call_betweenness: config.compute_call_betweenness,
         }
     }
 
     pub fn sync_changes(&mut self) -> Result<WriterResult, Error> {
-        // First, get all the changes that need to be applied
-        let changes = self.get_changes();
+        // Only the nodes of the changed paths are touched, the rest of the graph stays as is
+        let scope = self.get_change_scope();
 
         // Get the new node ID heads
         let (max_definition_id, max_imported_symbol_id, max_file_id, max_dir_id) =
@@ -66,83 +79,92 @@ impl<'a> KuzuChanges<'a> {
         // Clear the ID mappings to ensure new IDs are assigned
         node_id_generator.clear();
 
-        // Write new nodes to Parquet files with new IDs
-        let writer_service = WriterService::new(&self.output_path)
-            .map_err(|e| format!("Failed to create writer service: {e}"))
-            .unwrap();
-
-        // Simple validation to make sure the output directory is flushed
-        if !writer_service.flush_output_directory().unwrap() {
-            // To note: this is a holdover that will be removed in a future MR
-            error!("Output directory not flushed");
-            // return Err(anyhow::anyhow!("Output directory not flushed"));
-        }
-
-        let result = writer_service
-            .write_graph_data(&mut self.graph_data, &mut node_id_generator)
-            .map_err(|e| format!("Writing failed: {e}"))
-            .expect("Failed to write graph data");
+        // Write new nodes to Parquet files with new IDs, or keep them in memory
+        let (batches, result) = if self.write_parquet {
+            let writer_service = WriterService::new(&self.output_path)
+                .map_err(|e| anyhow::anyhow!("Failed to create writer service: {e}"))?
+                .with_compression(self.parquet_compression)
+                .with_batch_size(self.parquet_batch_size);
+
+            // Simple validation to make sure the output directory is flushed
+            if !writer_service.flush_output_directory()? {
+                // To note: this is a holdover that will be removed in a future MR
+                error!("Output directory not flushed");
+                // return Err(anyhow::anyhow!("Output directory not flushed"));
+            }
+
+            let result = writer_service
+                .write_graph_data(&mut self.graph_data, &mut node_id_generator)
+                .map_err(|e| anyhow::anyhow!("Writing failed: {e}"))?;
+            (None, result)
+        } else {
+            let (batches, result) =
+                WriterService::convert_graph_data(&mut self.graph_data, &mut node_id_generator)
+                    .map_err(|e| anyhow::anyhow!("Converting graph data failed: {e}"))?;
+            (Some(batches), result)
+        };
 
-        // Import the new nodes from Parquet files
+        // Import the new nodes from Parquet files or memory
         let schema_manager = SchemaManager::new(self.database);
+        let output_path = &self.output_path;
+        let compute_call_betweenness = self.compute_call_betweenness;
 
         // Create a transaction-enabled service for the modification operations
         let mut transaction_service = NodeDatabaseService::new_with_transaction(self.database);
         transaction_service
             .transaction(|service| {
-                // Remove deleted definitions (and their relationships)
-                let _ = service.delete_by(
-                    KuzuNodeType::DefinitionNode,
-                    "id",
-                    &changes.deleted_definition_ids,
-                );
-
-                // Remove deleted imported symbols (and their relationships)
-                let _ = service.delete_by(
-                    KuzuNodeType::ImportedSymbolNode,
-                    "id",
-                    &changes.deleted_imported_symbol_ids,
-                );
-
-                // Remove deleted files (and their relationships)
-                let _ = service.delete_by(KuzuNodeType::FileNode, "id", &changes.deleted_file_ids);
-                // Remove deleted directories (and their relationships)
-                let _ = service.delete_by(
-                    KuzuNodeType::DirectoryNode,
-                    "id",
-                    &changes.deleted_directory_ids,
-                );
-
-                // Delete the nodes for changed files and directories from the database
-                let _ = service.delete_by(
+                // Remove the nodes (and their relationships) that originated in the affected files
+                service.delete_by(
                     KuzuNodeType::DefinitionNode,
                     "primary_file_path",
-                    &changes.changed_file_paths,
-                );
-                let _ = service.delete_by(
+                    &scope.affected_file_paths,
+                )?;
+                service.delete_by(
                     KuzuNodeType::ImportedSymbolNode,
                     "file_path",
-                    &changes.changed_file_paths,
-                );
-                let _ =
-                    service.delete_by(KuzuNodeType::FileNode, "path", &changes.changed_file_paths);
-                let _ = service.delete_by(
+                    &scope.affected_file_paths,
+                )?;
+                service.delete_by(KuzuNodeType::FileNode, "path", &scope.affected_file_paths)?;
+                service.delete_by(
                     KuzuNodeType::DirectoryNode,
                     "path",
-                    &changes.changed_dir_paths,
-                );
+                    &scope.changed_dir_paths,
+                )?;
+
+                // Remove everything below deleted directories
+                let deleted_dir_paths = scope
+                    .deleted_dir_prefixes
+                    .iter()
+                    .map(|prefix| prefix.trim_end_matches('/').to_string())
+                    .collect::<Vec<_>>();
+                service.delete_by(KuzuNodeType::DirectoryNode, "path", &deleted_dir_paths)?;
+                for (node_type, column) in [
+                    (KuzuNodeType::DefinitionNode, "primary_file_path"),
+                    (KuzuNodeType::ImportedSymbolNode, "file_path"),
+                    (KuzuNodeType::FileNode, "path"),
+                    (KuzuNodeType::DirectoryNode, "path"),
+                ] {
+                    service.delete_by_prefix(node_type, column, &scope.deleted_dir_prefixes)?;
+                }
 
                 // Reuse the same connection for the data import
-                schema_manager
-                    .import_graph_data_with_existing_connection(
-                        &self.output_path,
-                        service.transaction_conn.as_mut().unwrap(),
-                    )
-                    .expect("Failed to import graph data");
-
-                Ok(())
+                let connection = service.transaction_conn.as_mut().unwrap();
+                match &batches {
+                    Some(batches) => schema_manager
+                        .import_graph_batches_with_existing_connection(batches, connection),
+                    None => schema_manager
+                        .import_graph_data_with_existing_connection(output_path, connection),
+                }?;
+                service.record_index_time(Utc::now().timestamp_millis())?;
+
+                // Calls into the changed files also change the scores of untouched definitions
+                service.compute_call_centrality(compute_call_betweenness)
             })
-            .expect("Failed to apply destructive changes");
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to apply changes, the database was rolled back to its previous state: {e}"
+                )
+            })?;
 
         Ok(result)
     }
@@ -191,99 +213,31 @@ impl<'a> KuzuChanges<'a> {
         )
     }
 
-    fn find_nodes<R: FromKuzuNode>(
-        &mut self,
-        path_type: FileChangesPathType,
-        node_type: KuzuNodeType,
-    ) -> Vec<R> {
-        let changed_files = self.file_changes.get_rel_paths(path_type, &self.repo_path);
-        match node_type {
-            KuzuNodeType::DefinitionNode => self
-                .node_database_service
-                .get_by::<String, R>(node_type, "primary_file_path", &changed_files)
-                .unwrap(),
-
-            KuzuNodeType::FileNode => self
-                .node_database_service
-                .get_by::<String, R>(node_type, "path", &changed_files)
-                .unwrap(),
-
-            KuzuNodeType::DirectoryNode => self
-                .node_database_service
-                .get_by::<String, R>(node_type, "path", &changed_files)
-                .unwrap(),
-
-            KuzuNodeType::ImportedSymbolNode => self
-                .node_database_service
-                .get_by::<String, R>(node_type, "file_path", &changed_files)
-                .unwrap(),
-        }
-    }
-
-    fn get_changes(&mut self) -> KuzuChangesIds {
-        let changed_def_nodes = self.find_nodes::<DefinitionNodeFromKuzu>(
-            FileChangesPathType::ChangedFiles,
-            KuzuNodeType::DefinitionNode,
-        );
-
-        let deleted_definitions = changed_def_nodes
-            .iter()
-            .filter(|kuzu_def| {
-                !self.graph_data.definition_nodes.iter().any(|def| {
-                    def.fqn == kuzu_def.fqn && def.file_path == kuzu_def.primary_file_path
-                })
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        // Remove deleted definitions (and their relationships)
-        let deleted_def_ids = deleted_definitions
-            .iter()
-            .map(|def| def.id)
-            .collect::<Vec<_>>();
-
-        // Find deleted imported symbols
-        let deleted_symbols = self.find_nodes::<ImportedSymbolNodeFromKuzu>(
-            FileChangesPathType::ChangedFiles,
-            KuzuNodeType::ImportedSymbolNode,
-        );
-        let deleted_import_ids = deleted_symbols
-            .iter()
-            .map(|symbol| symbol.id)
-            .collect::<Vec<_>>();
-
-        // Find removed files (exist in kuzu but not in new)
-        let deleted_files = self.find_nodes::<FileNodeFromKuzu>(
-            FileChangesPathType::DeletedFiles,
-            KuzuNodeType::FileNode,
-        );
-
-        let deleted_file_ids = deleted_files.iter().map(|file| file.id).collect::<Vec<_>>();
-
-        // Find removed directories (exist in kuzu but not in new)
-        let deleted_dirs = self.find_nodes::<DirectoryNodeFromKuzu>(
-            FileChangesPathType::DeletedFiles,
-            KuzuNodeType::DirectoryNode,
-        );
-
-        let deleted_dir_ids = deleted_dirs.iter().map(|dir| dir.id).collect::<Vec<_>>();
-
-        let changed_files = self
+    fn get_change_scope(&self) -> KuzuChangeScope {
+        let mut affected_file_paths = self
             .file_changes
             .get_rel_paths(FileChangesPathType::ChangedFiles, &self.repo_path);
+        affected_file_paths.extend(
+            self.file_changes
+                .get_rel_paths(FileChangesPathType::DeletedFiles, &self.repo_path),
+        );
 
-        // Delete the nodes for deleted files from the database
-        let changed_dirs = self
+        let changed_dir_paths = self
             .file_changes
             .get_rel_paths(FileChangesPathType::ChangedDirs, &self.repo_path);
 
-        KuzuChangesIds {
-            deleted_definition_ids: deleted_def_ids,
-            deleted_imported_symbol_ids: deleted_import_ids,
-            deleted_file_ids,
-            deleted_directory_ids: deleted_dir_ids,
-            changed_file_paths: changed_files,
-            changed_dir_paths: changed_dirs,
+        let deleted_dir_prefixes = self
+            .file_changes
+            .get_rel_paths(FileChangesPathType::DeletedDirs, &self.repo_path)
+            .into_iter()
+            .map(|path| format!("{}/", path.trim_end_matches('/')))
+            .filter(|prefix| prefix != "/")
+            .collect();
+
+        KuzuChangeScope {
+            affected_file_paths,
+            changed_dir_paths,
+            deleted_dir_prefixes,
         }
     }
 }
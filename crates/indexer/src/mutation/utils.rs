@@ -176,8 +176,11 @@ impl<'a> GraphMapper<'a> {
         }
     }
 
-    /// Pre-assign integer IDs to all nodes
+    /// Pre-assign integer IDs to all nodes. Nodes are sorted first so identical source gets
+    /// identical IDs and write order, whatever order the files were parsed in.
     pub fn assign_node_ids(&mut self) {
+        self.sort_nodes();
+
         // Assign directory IDs
         for dir_node in &self.graph_data.directory_nodes {
             self.node_id_generator
@@ -203,6 +206,31 @@ impl<'a> GraphMapper<'a> {
         }
     }
 
+    /// Sort directories and files by path, definitions by FQN and location, and imported
+    /// symbols by location
+    fn sort_nodes(&mut self) {
+        self.graph_data
+            .directory_nodes
+            .sort_by(|a, b| a.path.cmp(&b.path));
+        self.graph_data
+            .file_nodes
+            .sort_by(|a, b| a.path.cmp(&b.path));
+        self.graph_data.definition_nodes.sort_by(|a, b| {
+            a.fqn
+                .cmp(&b.fqn)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.range.byte_offset.cmp(&b.range.byte_offset))
+        });
+        self.graph_data.imported_symbol_nodes.sort_by(|a, b| {
+            let a = &a.location;
+            let b = &b.location;
+            a.file_path
+                .cmp(&b.file_path)
+                .then_with(|| a.start_byte.cmp(&b.start_byte))
+                .then_with(|| a.end_byte.cmp(&b.end_byte))
+        });
+    }
+
     /// Consolidate all relationships into four categories with integer IDs and types
     pub fn assign_relationship_ids(&mut self) -> Result<(), anyhow::Error> {
         let mut dir_not_found = 0;
@@ -538,6 +566,20 @@ impl<'a> GraphMapper<'a> {
             .relationships
             .retain(|rel| rel.source_id.is_some() && rel.target_id.is_some());
 
+        // Order by the assigned IDs so the relationship files are reproducible as well
+        self.graph_data.relationships.sort_by(|a, b| {
+            a.source_id
+                .cmp(&b.source_id)
+                .then_with(|| a.target_id.cmp(&b.target_id))
+                .then_with(|| {
+                    a.relationship_type
+                        .as_str()
+                        .cmp(b.relationship_type.as_str())
+                })
+                .then_with(|| a.source_range.byte_offset.cmp(&b.source_range.byte_offset))
+                .then_with(|| a.target_range.byte_offset.cmp(&b.target_range.byte_offset))
+        });
+
         warn!(
             "Consolidated relationships: dir_not_found: {}, file_not_found: {}, def_not_found: {}, import_not_found: {}",
             dir_not_found, file_not_found, def_not_found, import_not_found
@@ -0,0 +1,275 @@
+//! Finds the object creation expressions of C# files, e.g. `new User(name)`, which the parser
+//! doesn't extract references for. The source is scanned skipping comments and literals.
+
+use parser_core::utils::{Position, Range};
+
+/// A `new` expression naming the type it creates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSharpConstruction {
+    /// The type as written, without type arguments, e.g. `Models.User`
+    pub type_name: String,
+    /// Range of `type_name`
+    pub range: Range,
+}
+
+impl CSharpConstruction {
+    /// The simple name of the created type, e.g. `User` for `Models.User`
+    pub fn simple_name(&self) -> &str {
+        self.type_name.rsplit('.').next().unwrap_or(&self.type_name)
+    }
+}
+
+fn is_identifier_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_' || byte == b'@' || !byte.is_ascii()
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
+}
+
+/// The object creation expressions of `content`. Target-typed `new()`, anonymous objects and
+/// arrays don't name a type to instantiate and are left out.
+pub fn find_csharp_constructions(content: &str) -> Vec<CSharpConstruction> {
+    let bytes = content.as_bytes();
+    let mut constructions = Vec::new();
+    let mut lines = LineIndex::default();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = find_from(bytes, i, b"\n").unwrap_or(bytes.len());
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find_from(bytes, i + 2, b"*/").map_or(bytes.len(), |end| end + 2);
+            }
+            b'"' | b'\'' | b'@' | b'$' if starts_literal(bytes, i) => {
+                i = skip_literal(bytes, i);
+            }
+            byte if is_identifier_start(byte) => {
+                let start = i;
+                i = skip_identifier(bytes, i);
+                let preceded_by_member_access = start > 0 && bytes[start - 1] == b'.';
+                if &bytes[start..i] != b"new" || preceded_by_member_access {
+                    continue;
+                }
+                if let Some((name_start, name_end)) = created_type(bytes, i) {
+                    constructions.push(CSharpConstruction {
+                        type_name: content[name_start..name_end].replace("global::", ""),
+                        range: lines.range(content, name_start, name_end),
+                    });
+                    i = name_end;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    constructions
+}
+
+/// The span of the type created by the `new` keyword ending at `after_new`, when followed by
+/// an argument list or an object initializer
+fn created_type(bytes: &[u8], after_new: usize) -> Option<(usize, usize)> {
+    let name_start = skip_whitespace(bytes, after_new);
+    if name_start == after_new
+        || !bytes
+            .get(name_start)
+            .copied()
+            .is_some_and(is_identifier_start)
+    {
+        return None;
+    }
+
+    let mut name_end = skip_identifier(bytes, name_start);
+    loop {
+        if bytes[name_end..].starts_with(b"::") {
+            name_end = skip_identifier(bytes, name_end + 2);
+        } else if bytes.get(name_end) == Some(&b'.')
+            && bytes
+                .get(name_end + 1)
+                .copied()
+                .is_some_and(is_identifier_start)
+        {
+            name_end = skip_identifier(bytes, name_end + 1);
+        } else {
+            break;
+        }
+    }
+
+    let mut next = skip_whitespace(bytes, name_end);
+    if bytes.get(next) == Some(&b'<') {
+        next = skip_type_arguments(bytes, next)?;
+        next = skip_whitespace(bytes, next);
+    }
+    matches!(bytes.get(next), Some(b'(') | Some(b'{')).then_some((name_start, name_end))
+}
+
+fn skip_identifier(bytes: &[u8], start: usize) -> usize {
+    let mut end = start + 1;
+    while bytes.get(end).copied().is_some_and(is_identifier_byte) {
+        end += 1;
+    }
+    end
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+        i += 1;
+    }
+    i
+}
+
+/// The end of the balanced `<...>` starting at `start`
+fn skip_type_arguments(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, byte) in bytes[start..].iter().enumerate() {
+        match byte {
+            b'<' => depth += 1,
+            b'>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + offset + 1);
+                }
+            }
+            b';' | b'{' | b'}' | b'(' | b')' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_from(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+/// Whether a string or character literal starts at `i`, including the `@` and `$` prefixes
+fn starts_literal(bytes: &[u8], i: usize) -> bool {
+    let mut j = i;
+    while matches!(bytes.get(j), Some(b'@') | Some(b'$')) {
+        j += 1;
+    }
+    matches!(bytes.get(j), Some(b'"')) || (j == i && bytes[i] == b'\'')
+}
+
+/// The end of the literal starting at `i`
+fn skip_literal(bytes: &[u8], i: usize) -> usize {
+    let mut j = i;
+    let mut verbatim = false;
+    while let Some(prefix @ (b'@' | b'$')) = bytes.get(j) {
+        verbatim |= *prefix == b'@';
+        j += 1;
+    }
+    let quote = bytes[j];
+
+    // Raw string literals, closed by as many quotes as they're opened with
+    let quotes = bytes[j..].iter().take_while(|byte| **byte == b'"').count();
+    if quote == b'"' && quotes >= 3 {
+        let delimiter = &bytes[j..j + quotes];
+        return find_from(bytes, j + quotes, delimiter).map_or(bytes.len(), |end| end + quotes);
+    }
+
+    j += 1;
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' if !verbatim => j += 2,
+            b'"' if verbatim && bytes.get(j + 1) == Some(&b'"') => j += 2,
+            byte if byte == quote => return j + 1,
+            b'\n' if !verbatim => return j,
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Turns byte offsets into positions, for offsets visited in increasing order
+#[derive(Default)]
+struct LineIndex {
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl LineIndex {
+    fn position(&mut self, content: &str, offset: usize) -> Position {
+        for (i, byte) in content.as_bytes()[self.offset..offset].iter().enumerate() {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.line_start = self.offset + i + 1;
+            }
+        }
+        self.offset = offset;
+        Position::new(self.line, offset - self.line_start)
+    }
+
+    fn range(&mut self, content: &str, start: usize, end: usize) -> Range {
+        let start_position = self.position(content, start);
+        let end_position = self.position(content, end);
+        Range::new(start_position, end_position, (start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_names(content: &str) -> Vec<String> {
+        find_csharp_constructions(content)
+            .into_iter()
+            .map(|construction| construction.type_name)
+            .collect()
+    }
+
+    #[test]
+    fn test_object_creation_expressions() {
+        assert_eq!(
+            type_names("var user = new User(name);\nvar list = new List<User> { user };"),
+            vec!["User", "List"]
+        );
+        assert_eq!(
+            type_names("var user = new Models.User();"),
+            vec!["Models.User"]
+        );
+        assert_eq!(
+            type_names("var user = new global::Models.User();"),
+            vec!["Models.User"]
+        );
+    }
+
+    #[test]
+    fn test_expressions_without_a_created_type() {
+        assert!(type_names("User user = new();").is_empty());
+        assert!(type_names("var point = new { X = 1 };").is_empty());
+        assert!(type_names("var users = new User[3];").is_empty());
+        assert!(type_names("public new void Run() {}").is_empty());
+    }
+
+    #[test]
+    fn test_comments_and_literals_are_skipped() {
+        let content = r#"
+// new Commented()
+/* new Blocked() */
+var text = "new Quoted()";
+var path = @"C:\new Verbatim()";
+var raw = """new Raw()""";
+var c = '"';
+var user = new User();
+"#;
+        assert_eq!(type_names(content), vec!["User"]);
+    }
+
+    #[test]
+    fn test_construction_range() {
+        let content = "class A {\n  void Run() { var b = new B(); }\n}";
+        let constructions = find_csharp_constructions(content);
+        assert_eq!(constructions.len(), 1);
+        let range = constructions[0].range;
+        assert_eq!(&content[range.byte_offset.0..range.byte_offset.1], "B");
+        assert_eq!(range.start.line, 1);
+        assert_eq!(range.start.column, 27);
+    }
+}
@@ -0,0 +1,339 @@
+//! Reads the parts of C# declarations that override resolution needs and the parser doesn't
+//! extract: the base types of a type, and the modifiers and parameter types of a member.
+
+use parser_core::csharp::types::{CSharpDefinitionInfo, CSharpDefinitionType};
+
+/// Modifiers a parameter can be declared with, before its type
+const PARAMETER_MODIFIERS: &[&str] = &["this", "ref", "out", "in", "params", "scoped", "readonly"];
+
+/// The declaration of a C# type or member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSharpDeclaration {
+    pub fqn: String,
+    /// The base class and interfaces of a type as written, without type arguments
+    pub base_types: Vec<String>,
+    /// Whether a member is declared `override`
+    pub is_override: bool,
+    /// The parameter types of a method as written
+    pub parameter_types: Vec<String>,
+}
+
+/// The declarations of the types, methods and properties among `definitions`
+pub fn find_csharp_declarations(
+    content: &str,
+    definitions: &[CSharpDefinitionInfo],
+) -> Vec<CSharpDeclaration> {
+    definitions
+        .iter()
+        .filter_map(|definition| {
+            let (start, end) = definition.range.byte_offset;
+            let header = header(content.get(start..end)?);
+            let fqn = definition
+                .fqn
+                .iter()
+                .map(|part| part.node_name.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+
+            match definition.definition_type {
+                CSharpDefinitionType::Class
+                | CSharpDefinitionType::Struct
+                | CSharpDefinitionType::Record
+                | CSharpDefinitionType::Interface => Some(CSharpDeclaration {
+                    fqn,
+                    base_types: base_types(header, &definition.name),
+                    is_override: false,
+                    parameter_types: Vec::new(),
+                }),
+                CSharpDefinitionType::InstanceMethod | CSharpDefinitionType::Property => {
+                    let (is_override, parameter_types) = signature(header, &definition.name);
+                    Some(CSharpDeclaration {
+                        fqn,
+                        base_types: Vec::new(),
+                        is_override,
+                        parameter_types,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'@' || !byte.is_ascii()
+}
+
+/// The declaration up to its body, without its attributes
+fn header(declaration: &str) -> &str {
+    let bytes = declaration.as_bytes();
+    let mut start = 0;
+    loop {
+        start += bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+        if bytes.get(start) != Some(&b'[') {
+            break;
+        }
+        start = closing(bytes, start).unwrap_or(bytes.len());
+    }
+
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'"' | b'\'' => i = skip_quoted(bytes, i),
+            b'{' | b';' if depth == 0 => break,
+            b'=' if depth == 0 && bytes.get(i + 1) == Some(&b'>') => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    &declaration[start..i.min(bytes.len())]
+}
+
+/// The end of the bracket or parenthesis opened at `start`
+fn closing(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'<' => depth += 1,
+            b')' | b']' | b'>' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            b'"' | b'\'' => i = skip_quoted(bytes, i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The index of the quote closing the literal opened at `start`
+fn skip_quoted(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() && bytes[i] != quote {
+        i += if bytes[i] == b'\\' { 2 } else { 1 };
+    }
+    i
+}
+
+/// The identifiers of `header` outside of any brackets, with their offsets
+fn top_level_words(header: &str) -> Vec<(usize, &str)> {
+    let bytes = header.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'<' => i = closing(bytes, i).unwrap_or(bytes.len()),
+            byte if is_identifier_byte(byte) => {
+                let start = i;
+                while i < bytes.len() && is_identifier_byte(bytes[i]) {
+                    i += 1;
+                }
+                words.push((start, &header[start..i]));
+            }
+            _ => i += 1,
+        }
+    }
+    words
+}
+
+/// Splits `list` at the commas outside of any brackets
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, byte) in list.bytes().enumerate() {
+        match byte {
+            b'(' | b'[' | b'<' => depth += 1,
+            b')' | b']' | b'>' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                parts.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(list[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// `Models.Repository<User>` as `Models.Repository`, and `Base(Name)` of a record as `Base`
+fn without_arguments(type_name: &str) -> String {
+    let end = type_name.find(['<', '(']).unwrap_or(type_name.len());
+    type_name[..end].split_whitespace().collect()
+}
+
+/// The base types listed after the name of the type declared by `header`
+fn base_types(header: &str, name: &str) -> Vec<String> {
+    let words = top_level_words(header);
+    let Some(name_end) = words
+        .windows(2)
+        .find(|pair| {
+            matches!(pair[0].1, "class" | "struct" | "record" | "interface") && pair[1].1 == name
+        })
+        .map(|pair| pair[1].0 + name.len())
+    else {
+        return Vec::new();
+    };
+
+    // Skips the type parameters and the parameters of a primary constructor
+    let bytes = header.as_bytes();
+    let mut i = name_end;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' | b'(' => i = closing(bytes, i).unwrap_or(bytes.len()),
+            byte if byte.is_ascii_whitespace() => i += 1,
+            _ => break,
+        }
+    }
+    let Some(list) = header[i..].strip_prefix(':') else {
+        return Vec::new();
+    };
+    let list_end = top_level_words(list)
+        .into_iter()
+        .find(|(_, word)| *word == "where")
+        .map_or(list.len(), |(offset, _)| offset);
+
+    split_top_level(&list[..list_end])
+        .into_iter()
+        .map(without_arguments)
+        .collect()
+}
+
+/// Whether the member declared by `header` is an `override`, and its parameter types
+fn signature(header: &str, name: &str) -> (bool, Vec<String>) {
+    let words = top_level_words(header);
+    let Some(&(name_start, _)) = words.iter().rev().find(|(_, word)| *word == name) else {
+        return (false, Vec::new());
+    };
+    let is_override = words
+        .iter()
+        .take_while(|(offset, _)| *offset < name_start)
+        .any(|(_, word)| *word == "override");
+
+    let bytes = header.as_bytes();
+    let Some(open) = (name_start..bytes.len()).find(|i| bytes[*i] == b'(') else {
+        return (is_override, Vec::new());
+    };
+    let close = closing(bytes, open).unwrap_or(bytes.len());
+    let parameters = &header[open + 1..close.saturating_sub(1).max(open + 1)];
+    let parameter_types = split_top_level(parameters)
+        .into_iter()
+        .map(parameter_type)
+        .collect();
+    (is_override, parameter_types)
+}
+
+/// The type of a parameter, e.g. `string[]` of `params string[] names`
+fn parameter_type(parameter: &str) -> String {
+    let parameter = header(parameter);
+    let parameter = parameter
+        .find('=')
+        .map_or(parameter, |default| &parameter[..default]);
+
+    // The words outside of brackets, keeping `Dictionary<string, int>` as one word
+    let mut words: Vec<&str> = Vec::new();
+    let bytes = parameter.as_bytes();
+    let mut start = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'<' => {
+                start.get_or_insert(i);
+                i = closing(bytes, i).unwrap_or(bytes.len());
+            }
+            byte if byte.is_ascii_whitespace() => {
+                if let Some(word_start) = start.take() {
+                    words.push(&parameter[word_start..i]);
+                }
+                i += 1;
+            }
+            _ => {
+                start.get_or_insert(i);
+                i += 1;
+            }
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(&parameter[word_start..]);
+    }
+
+    words.retain(|word| !PARAMETER_MODIFIERS.contains(word));
+    match words.split_last() {
+        Some((_, type_words)) if !type_words.is_empty() => type_words.concat(),
+        _ => words.concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_types() {
+        assert_eq!(
+            base_types("public class Orders : Repository<Order>, IStore", "Orders"),
+            vec!["Repository", "IStore"]
+        );
+        assert_eq!(
+            base_types(
+                "public sealed record Admin(string Name) : User(Name) where T : new()",
+                "Admin"
+            ),
+            vec!["User"]
+        );
+        assert_eq!(
+            base_types("internal interface IStore<T> : Models.IReadable", "IStore"),
+            vec!["Models.IReadable"]
+        );
+        assert!(base_types("public class Order", "Order").is_empty());
+    }
+
+    #[test]
+    fn test_member_signatures() {
+        assert_eq!(
+            signature(
+                "public override Task<User> Find(int id, Dictionary<string, int> counts = null)",
+                "Find"
+            ),
+            (
+                true,
+                vec!["int".to_string(), "Dictionary<string, int>".to_string()]
+            )
+        );
+        assert_eq!(
+            signature(
+                "public static void Log(this ILogger logger, params string[] messages)",
+                "Log"
+            ),
+            (false, vec!["ILogger".to_string(), "string[]".to_string()])
+        );
+        assert_eq!(
+            signature("public override string Name ", "Name"),
+            (true, Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_header_ends_before_the_body() {
+        assert_eq!(
+            header("[Obsolete(\"Use Run()\")]\n    public void Start() => Run();"),
+            "public void Start() "
+        );
+        assert_eq!(
+            header("public class Orders : IStore\n{\n}"),
+            "public class Orders : IStore\n"
+        );
+    }
+}
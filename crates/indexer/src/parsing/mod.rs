@@ -1,2 +1,5 @@
 pub mod changes;
+pub mod csharp_constructions;
+pub mod csharp_declarations;
 pub mod processor;
+pub mod reference_syntax;
@@ -1,4 +1,8 @@
+use crate::parsing::csharp_constructions::{CSharpConstruction, find_csharp_constructions};
+use crate::parsing::csharp_declarations::{CSharpDeclaration, find_csharp_declarations};
+use crate::parsing::reference_syntax::TypeScriptReferenceSyntax;
 use crate::project::file_info::FileInfo;
+use crate::project::io::{TextEncoding, content_hash};
 use log::debug;
 use parser_core::definitions::DefinitionInfo;
 use parser_core::{
@@ -98,12 +102,23 @@ impl ProcessingResult {
     }
 }
 
+/// Default cap on the definitions of a single file, far above hand-written code
+pub const DEFAULT_MAX_DEFINITIONS_PER_FILE: usize = 100_000;
+/// Default cap on the references of a single file, far above hand-written code
+pub const DEFAULT_MAX_REFERENCES_PER_FILE: usize = 500_000;
+
 #[derive(Debug, Clone)]
 pub struct FileProcessor<'a> {
     pub path: String,
     pub content: &'a str,
     /// Pre-computed file extension to avoid duplicate parsing
     pub extension: String,
+    /// Language configured for the extension, used instead of the built-in detection
+    pub language_override: Option<SupportedLanguage>,
+    /// The file is skipped when it has more definitions than this
+    pub max_definitions: usize,
+    /// The file is skipped when it has more references than this
+    pub max_references: usize,
 }
 
 impl<'a> FileProcessor<'a> {
@@ -119,6 +134,9 @@ impl<'a> FileProcessor<'a> {
             path,
             content,
             extension,
+            language_override: None,
+            max_definitions: usize::MAX,
+            max_references: usize::MAX,
         }
     }
 
@@ -133,6 +151,9 @@ impl<'a> FileProcessor<'a> {
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string(),
+            language_override: None,
+            max_definitions: usize::MAX,
+            max_references: usize::MAX,
         }
     }
 
@@ -148,9 +169,26 @@ impl<'a> FileProcessor<'a> {
             path,
             content: "",
             extension,
+            language_override: None,
+            max_definitions: usize::MAX,
+            max_references: usize::MAX,
         }
     }
 
+    /// Parse the file as `language` regardless of its extension
+    pub fn with_language_override(mut self, language: Option<SupportedLanguage>) -> Self {
+        self.language_override = language;
+        self
+    }
+
+    /// Skip the file, rather than adding it to the graph, when it has more definitions or
+    /// references than these caps, e.g. because it is machine-generated
+    pub fn with_max_counts(mut self, max_definitions: usize, max_references: usize) -> Self {
+        self.max_definitions = max_definitions;
+        self.max_references = max_references;
+        self
+    }
+
     /// Get the file path
     pub fn path(&self) -> &str {
         &self.path
@@ -180,7 +218,11 @@ impl<'a> FileProcessor<'a> {
         let start_time = Instant::now();
 
         // 1. Detect language using pre-computed extension (avoids duplicate parsing)
-        let language = match detect_language_from_extension(&self.extension) {
+        let detected_language = match self.language_override {
+            Some(language) => Ok(language),
+            None => detect_language_from_extension(&self.extension),
+        };
+        let language = match detected_language {
             Ok(lang) => lang,
             Err(e) => {
                 return ProcessingResult::Error(ErroredFile {
@@ -263,12 +305,36 @@ impl<'a> FileProcessor<'a> {
 
             let matches_count = matches.len();
             let definitions_count = definitions.count();
+            if definitions_count > self.max_definitions {
+                return ProcessingResult::Skipped(SkippedFile {
+                    file_path: self.path.clone(),
+                    reason: format!(
+                        "Too many definitions: {definitions_count}, the limit is {}",
+                        self.max_definitions
+                    ),
+                    file_size: Some(self.size()),
+                });
+            }
+            let references_count = references.as_ref().map_or(0, References::count);
+            if references_count > self.max_references {
+                return ProcessingResult::Skipped(SkippedFile {
+                    file_path: self.path.clone(),
+                    reason: format!(
+                        "Too many references: {references_count}, the limit is {}",
+                        self.max_references
+                    ),
+                    file_size: Some(self.size()),
+                });
+            }
             let imported_symbols_count = imports.as_ref().map_or(0, |i| i.count());
 
             ProcessingResult::Success(FileProcessingResult {
                 file_path: self.path.clone(),
                 extension: self.extension.clone(),
                 file_size: self.size(),
+                content_hash: content_hash(self.content.as_bytes()),
+                encoding: TextEncoding::Utf8,
+                lossy_decoded: false,
                 language,
                 definitions,
                 imported_symbols: imports,
@@ -371,11 +437,22 @@ impl<'a> FileProcessor<'a> {
             SupportedLanguage::CSharp => {
                 let analyzer = CSharpAnalyzer::new();
                 match analyzer.analyze(parse_result) {
-                    Ok(analysis_result) => Ok((
-                        Definitions::CSharp(analysis_result.definitions),
-                        Some(ImportedSymbols::CSharp(analysis_result.imports)),
-                        None, // CSharp doesn't extract references currently
-                    )),
+                    Ok(analysis_result) => {
+                        // The parser doesn't extract C# references, object creations and the
+                        // declarations overrides are resolved from are read from the source
+                        let references = CSharpReferences {
+                            constructions: find_csharp_constructions(self.content),
+                            declarations: find_csharp_declarations(
+                                self.content,
+                                &analysis_result.definitions,
+                            ),
+                        };
+                        Ok((
+                            Definitions::CSharp(analysis_result.definitions),
+                            Some(ImportedSymbols::CSharp(analysis_result.imports)),
+                            Some(References::CSharp(references)),
+                        ))
+                    }
                     Err(e) => Err(anyhow::anyhow!(
                         "Failed to analyze CSharp file '{}': {}",
                         self.path,
@@ -389,7 +466,19 @@ impl<'a> FileProcessor<'a> {
                     Ok(analysis_result) => Ok((
                         Definitions::TypeScript(analysis_result.definitions),
                         Some(ImportedSymbols::TypeScript(analysis_result.imports)),
-                        Some(References::TypeScript(analysis_result.references)),
+                        Some(References::TypeScript(
+                            analysis_result
+                                .references
+                                .into_iter()
+                                .map(|info| TypeScriptReference {
+                                    syntax: TypeScriptReferenceSyntax::classify(
+                                        self.content,
+                                        &info.range,
+                                    ),
+                                    info,
+                                })
+                                .collect(),
+                        )),
                     )),
                     Err(e) => Err(anyhow::anyhow!(
                         "Failed to analyze TypeScript file '{}': {}",
@@ -642,13 +731,28 @@ pub type RubyReference = ReferenceInfo<
     parser_core::ruby::types::RubyFqn,
 >;
 
+/// A TypeScript reference with the syntax it appears in
+#[derive(Debug, Clone)]
+pub struct TypeScriptReference {
+    pub info: TypeScriptReferenceInfo,
+    pub syntax: TypeScriptReferenceSyntax,
+}
+
+/// The C# object creations, and the declarations of types and members
+#[derive(Debug, Clone, Default)]
+pub struct CSharpReferences {
+    pub constructions: Vec<CSharpConstruction>,
+    pub declarations: Vec<CSharpDeclaration>,
+}
+
 #[derive(Debug, Clone)]
 pub enum References {
     Ruby(Vec<RubyReference>),
     Kotlin(Vec<KotlinReferenceInfo>),
-    TypeScript(Vec<TypeScriptReferenceInfo>),
+    TypeScript(Vec<TypeScriptReference>),
     Java(Vec<JavaReferenceInfo>),
     Python(Vec<PythonReferenceInfo>),
+    CSharp(CSharpReferences),
 }
 
 impl References {
@@ -660,6 +764,7 @@ impl References {
             References::TypeScript(references) => references.len(),
             References::Java(references) => references.len(),
             References::Python(references) => references.len(),
+            References::CSharp(references) => references.constructions.len(),
         }
     }
 
@@ -682,7 +787,7 @@ impl References {
         }
     }
 
-    pub fn iter_typescript(&self) -> Option<impl Iterator<Item = &TypeScriptReferenceInfo>> {
+    pub fn iter_typescript(&self) -> Option<impl Iterator<Item = &TypeScriptReference>> {
         match self {
             References::TypeScript(references) => Some(references.iter()),
             _ => None,
@@ -702,6 +807,20 @@ impl References {
             _ => None,
         }
     }
+
+    pub fn iter_csharp(&self) -> Option<impl Iterator<Item = &CSharpConstruction>> {
+        match self {
+            References::CSharp(references) => Some(references.constructions.iter()),
+            _ => None,
+        }
+    }
+
+    pub fn iter_csharp_declarations(&self) -> Option<impl Iterator<Item = &CSharpDeclaration>> {
+        match self {
+            References::CSharp(references) => Some(references.declarations.iter()),
+            _ => None,
+        }
+    }
 }
 
 /// Result of processing a single file using Ruby analyzer
@@ -713,6 +832,13 @@ pub struct FileProcessingResult {
     pub extension: String,
     /// File size in bytes
     pub file_size: u64,
+    /// Hash of the file content, see [`content_hash`]
+    pub content_hash: String,
+    /// Encoding the file was decoded from before parsing
+    pub encoding: TextEncoding,
+    /// Whether invalid UTF-8 sequences of the file were replaced before parsing, so the
+    /// parsed content differs from the file
+    pub lossy_decoded: bool,
     /// Detected language
     pub language: SupportedLanguage,
     /// Extracted definitions
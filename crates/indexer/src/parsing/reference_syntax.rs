@@ -0,0 +1,123 @@
+//! Tells apart the syntax TypeScript references appear in, which the parser doesn't record.
+//! A reference is classified from the source text right before and at its range.
+
+use parser_core::utils::Range;
+
+/// Keywords after which a reference names a type
+const TYPE_KEYWORDS: &[&str] = &["implements", "extends", "as", "satisfies", "keyof"];
+
+/// Syntax a TypeScript reference appears in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeScriptReferenceSyntax {
+    /// A type annotation, type argument or heritage clause, e.g. `User` in `user: User`
+    Type,
+    /// The class of a `new` expression, e.g. `User` in `new User()`
+    New,
+    /// Any other use, e.g. a call
+    Other,
+}
+
+impl TypeScriptReferenceSyntax {
+    /// Classify the reference at `range` of `content`
+    pub fn classify(content: &str, range: &Range) -> Self {
+        let (start, end) = range.byte_offset;
+        let (Some(before), Some(referenced)) = (content.get(..start), content.get(start..end))
+        else {
+            return TypeScriptReferenceSyntax::Other;
+        };
+        let after = content.get(end..).unwrap_or_default().trim_start();
+        let before = before.trim_end();
+
+        if ends_with_keyword(before, "new") {
+            return TypeScriptReferenceSyntax::New;
+        }
+
+        // A type is never invoked, `{ key: build() }` is an object literal
+        if referenced.trim_end().ends_with(')') || after.starts_with('(') {
+            return TypeScriptReferenceSyntax::Other;
+        }
+
+        if before.ends_with(':') || is_type_argument_list(before) {
+            return TypeScriptReferenceSyntax::Type;
+        }
+        if TYPE_KEYWORDS
+            .iter()
+            .any(|keyword| ends_with_keyword(before, keyword))
+        {
+            return TypeScriptReferenceSyntax::Type;
+        }
+        TypeScriptReferenceSyntax::Other
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether `text` ends with the word `keyword`
+fn ends_with_keyword(text: &str, keyword: &str) -> bool {
+    text.strip_suffix(keyword)
+        .is_some_and(|rest| !rest.ends_with(is_identifier_char))
+}
+
+/// Whether `text` ends with `<` opening type arguments, e.g. `Array<`, rather than a JSX
+/// element or a comparison
+fn is_type_argument_list(text: &str) -> bool {
+    text.strip_suffix('<')
+        .is_some_and(|rest| rest.ends_with(is_identifier_char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser_core::utils::Position;
+
+    fn classify(content: &str, referenced: &str) -> TypeScriptReferenceSyntax {
+        let start = content.find(referenced).unwrap();
+        let range = Range::new(
+            Position::new(0, start),
+            Position::new(0, start + referenced.len()),
+            (start, start + referenced.len()),
+        );
+        TypeScriptReferenceSyntax::classify(content, &range)
+    }
+
+    #[test]
+    fn test_type_positions() {
+        use TypeScriptReferenceSyntax::*;
+
+        assert_eq!(classify("function run(user: User) {}", "User"), Type);
+        assert_eq!(classify("function find(): User | null {}", "User"), Type);
+        assert_eq!(classify("const users: Array<User> = [];", "User"), Type);
+        assert_eq!(classify("class Admin extends User {}", "User"), Type);
+        assert_eq!(classify("class Repo implements Store {}", "Store"), Type);
+        assert_eq!(classify("const user = data as User;", "User"), Type);
+    }
+
+    #[test]
+    fn test_other_uses() {
+        use TypeScriptReferenceSyntax::*;
+
+        assert_eq!(
+            classify("const user = User.create();", "User.create()"),
+            Other
+        );
+        assert_eq!(
+            classify("const options = { user: build() };", "build()"),
+            Other
+        );
+        assert_eq!(classify("render(<User />);", "User"), Other);
+        assert_eq!(classify("if (count < limit) {}", "limit"), Other);
+        assert_eq!(classify("const alias = User;", "User"), Other);
+        assert_eq!(classify("const user = User.create();", "User"), Other);
+    }
+
+    #[test]
+    fn test_new_expressions() {
+        use TypeScriptReferenceSyntax::*;
+
+        assert_eq!(classify("const user = new User();", "User"), New);
+        assert_eq!(classify("const user = new User('name');", "User()"), New);
+        assert_eq!(classify("const user = renew User();", "User"), Other);
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+/// Phases a repository goes through while being indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexingPhase {
+    /// Reading and parsing the source files
+    Parsing,
+    /// Resolving definitions and relationships across the parsed files
+    Analyzing,
+    /// Converting the graph and writing the Parquet files
+    Writing,
+    /// Importing the graph into the database
+    Loading,
+}
+
+/// Outcome of a repository indexing run, passed to [`ProgressSink::on_complete`]
+#[derive(Debug, Clone)]
+pub struct IndexingProgressSummary {
+    pub repository_path: String,
+    pub processed_files: usize,
+    pub skipped_files: usize,
+    pub errored_files: usize,
+    pub elapsed: Duration,
+}
+
+/// Receives progress updates from `RepositoryIndexer` and `IndexingExecutor`.
+///
+/// Methods are called synchronously from the indexing pipeline, so implementations should
+/// return quickly. Every method defaults to a no-op.
+pub trait ProgressSink: Send + Sync {
+    /// The executor started indexing the `number`th of the `total` projects of a workspace
+    /// folder, counting from 1
+    fn on_project_started(&self, _project_path: &str, _number: usize, _total: usize) {}
+
+    /// A file was parsed, skipped or failed; `completed` of `total` files are done
+    fn on_file_parsed(&self, _file_path: &str, _completed: usize, _total: usize) {}
+
+    fn on_phase_changed(&self, _phase: IndexingPhase) {}
+
+    /// Indexing finished successfully. Failures are reported through the returned error.
+    fn on_complete(&self, _summary: &IndexingProgressSummary) {}
+}
+
+/// Sink that ignores every update, used when no sink is configured
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopProgressSink;
+
+impl ProgressSink for NoopProgressSink {}
@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::{info, warn};
+
+use crate::project::file_info::FileInfo;
+
+/// Per-project ignore file, in the gitignore syntax, read from the project root
+pub const GKGIGNORE_FILE_NAME: &str = ".gkgignore";
+
+/// Matches files against exclude patterns written in the gitignore syntax, relative to
+/// the project root. A pattern matching a directory, like `dist/`, excludes every file
+/// below it.
+///
+/// The patterns of the project's `.gkgignore` come first and the explicit patterns after
+/// them, so an explicit pattern overrides a `!` negation of the `.gkgignore`.
+pub struct ExcludeMatcher {
+    project_root: PathBuf,
+    matcher: Gitignore,
+}
+
+impl ExcludeMatcher {
+    pub fn new(project_root: &Path, patterns: &[String]) -> Result<Self, ignore::Error> {
+        let mut builder = GitignoreBuilder::new(project_root);
+
+        let gkgignore_path = project_root.join(GKGIGNORE_FILE_NAME);
+        if gkgignore_path.is_file() {
+            info!("Using ignore file: {}", gkgignore_path.display());
+            // Invalid lines are reported and skipped, the valid ones still apply
+            if let Some(e) = builder.add(&gkgignore_path) {
+                warn!("Invalid pattern in {}: {e}", gkgignore_path.display());
+            }
+        }
+
+        for pattern in patterns {
+            builder.add_line(None, pattern)?;
+        }
+
+        Ok(Self {
+            project_root: project_root.to_path_buf(),
+            matcher: builder.build()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_empty()
+    }
+
+    pub fn is_excluded(&self, file_info: &FileInfo) -> bool {
+        let relative_path = if file_info.path.is_relative() {
+            file_info.path.as_path()
+        } else if let Ok(relative_path) = file_info.path.strip_prefix(&self.project_root) {
+            relative_path
+        } else {
+            // Files outside of the project root can't match a pattern relative to it
+            return false;
+        };
+
+        self.matcher
+            .matched_path_or_any_parents(relative_path, false)
+            .is_ignore()
+    }
+
+    /// Splits off the excluded files, returning the files to index and the excluded count
+    pub fn exclude(&self, files: Vec<FileInfo>) -> (Vec<FileInfo>, usize) {
+        if self.is_empty() {
+            return (files, 0);
+        }
+
+        let total_files = files.len();
+        let files: Vec<FileInfo> = files
+            .into_iter()
+            .filter(|file_info| !self.is_excluded(file_info))
+            .collect();
+        let excluded_files = total_files - files.len();
+
+        (files, excluded_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn file(root: &Path, relative_path: &str) -> FileInfo {
+        FileInfo::from_path(root.join(relative_path))
+    }
+
+    #[test]
+    fn test_exclude_patterns_match_relative_to_project_root() {
+        let root = Path::new("/projects/app");
+        let matcher = ExcludeMatcher::new(
+            root,
+            &[
+                "dist/".to_string(),
+                "**/__generated__/**".to_string(),
+                "vendor/*.rb".to_string(),
+            ],
+        )
+        .unwrap();
+
+        let files = vec![
+            file(root, "dist/bundle.ts"),
+            file(root, "dist/nested/chunk.ts"),
+            file(root, "src/__generated__/types.ts"),
+            file(root, "vendor/gem.rb"),
+            file(root, "src/vendor/gem.rb"),
+            file(root, "src/main.ts"),
+            FileInfo::from_path(PathBuf::from("dist/relative.ts")),
+        ];
+
+        let (files, excluded_files) = matcher.exclude(files);
+
+        assert_eq!(excluded_files, 5);
+        let remaining: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            remaining,
+            vec![root.join("src/vendor/gem.rb"), root.join("src/main.ts")]
+        );
+    }
+
+    #[test]
+    fn test_exclude_without_patterns_keeps_all_files() {
+        let root = Path::new("/projects/app");
+        let matcher = ExcludeMatcher::new(root, &[]).unwrap();
+
+        let (files, excluded_files) = matcher.exclude(vec![file(root, "dist/bundle.ts")]);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(excluded_files, 0);
+    }
+
+    #[test]
+    fn test_gkgignore_is_combined_with_exclude_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(
+            root.join(GKGIGNORE_FILE_NAME),
+            "# generated code\n__generated__/\nvendor/\n!vendor/keep.rb\n",
+        )
+        .unwrap();
+
+        let matcher = ExcludeMatcher::new(root, &["vendor/keep.rb".to_string()]).unwrap();
+        assert!(matcher.is_excluded(&file(root, "src/__generated__/types.ts")));
+        assert!(matcher.is_excluded(&file(root, "vendor/gem.rb")));
+        assert!(matcher.is_excluded(&file(root, "vendor/keep.rb")));
+        assert!(!matcher.is_excluded(&file(root, "src/main.ts")));
+
+        let matcher = ExcludeMatcher::new(root, &[]).unwrap();
+        assert!(!matcher.is_excluded(&file(root, "vendor/keep.rb")));
+    }
+}
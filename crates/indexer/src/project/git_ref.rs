@@ -0,0 +1,308 @@
+//! Reads the files of a git commit straight from the repository's object database, so a
+//! commit can be indexed without checking it out or touching the working tree. Shells out
+//! to `git`.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+use crate::project::io::{DecodedText, InvalidUtf8Policy, ProcessingError, decode_text};
+
+/// A git ref that doesn't name a commit of the repository
+#[derive(Debug)]
+pub struct UnresolvedGitRef {
+    pub git_ref: String,
+    pub repository_path: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for UnresolvedGitRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Cannot resolve git ref '{}' in {}: {}",
+            self.git_ref,
+            self.repository_path.display(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for UnresolvedGitRef {}
+
+/// A commit of a repository whose files are indexed instead of the working tree
+#[derive(Debug, Clone)]
+pub struct GitCommit {
+    repository_path: PathBuf,
+    id: String,
+    /// Started on the first read and shared by the clones, so all files of the commit are
+    /// read through one `git` process
+    cat_file: Arc<Mutex<Option<CatFileBatch>>>,
+}
+
+impl GitCommit {
+    /// Resolve `git_ref`, e.g. a commit SHA, branch or tag, to a commit of the repository
+    pub fn resolve(repository_path: &Path, git_ref: &str) -> Result<Self, UnresolvedGitRef> {
+        let unresolved = |reason: String| UnresolvedGitRef {
+            git_ref: git_ref.to_string(),
+            repository_path: repository_path.to_path_buf(),
+            reason,
+        };
+
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repository_path)
+            .args(["rev-parse", "--verify", "--quiet", "--end-of-options"])
+            .arg(format!("{git_ref}^{{commit}}"))
+            .output()
+            .map_err(|e| unresolved(format!("failed to run git: {e}")))?;
+        if !output.status.success() {
+            return Err(unresolved("no such commit".to_string()));
+        }
+
+        let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Self {
+            repository_path: repository_path.to_path_buf(),
+            id,
+            cat_file: Arc::default(),
+        })
+    }
+
+    /// The full SHA of the commit
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn repository_path(&self) -> &Path {
+        &self.repository_path
+    }
+
+    /// Paths, relative to the repository, of the files in the commit
+    pub fn list_files(&self) -> std::io::Result<Vec<String>> {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.repository_path)
+            .args(["ls-tree", "-r", "-z", "--name-only", &self.id])
+            .output()?;
+        if !output.status.success() {
+            return Err(std::io::Error::other(format!(
+                "Failed to list the files of commit {}: {}",
+                self.id,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).to_string())
+            .collect())
+    }
+
+    /// Read a file of the commit, with the same checks as
+    /// [`read_text_file`](crate::project::io::read_text_file)
+    pub async fn read_text_file(
+        &self,
+        relative_path: &str,
+        max_file_size: usize,
+        invalid_utf8: InvalidUtf8Policy,
+    ) -> Result<DecodedText, ProcessingError> {
+        let object = format!("{}:{relative_path}", self.id);
+        let read_error = |reason: String| {
+            ProcessingError::Error(
+                relative_path.to_string(),
+                format!("Failed to read file at commit {}: {reason}", self.id),
+            )
+        };
+
+        let read = {
+            let mut cat_file = self.cat_file.lock().await;
+            if cat_file.is_none() {
+                *cat_file = Some(
+                    CatFileBatch::spawn(&self.repository_path)
+                        .map_err(|e| read_error(format!("failed to run git: {e}")))?,
+                );
+            }
+            let result = cat_file
+                .as_mut()
+                .expect("cat-file process was just started")
+                .read(&object, max_file_size)
+                .await;
+            // The output can't be trusted to be in step after an error, start over next time
+            if result.is_err() {
+                *cat_file = None;
+            }
+            result.map_err(|e| read_error(e.to_string()))?
+        };
+
+        match read {
+            CatFileRead::Blob(bytes) => decode_text(relative_path.to_string(), bytes, invalid_utf8),
+            CatFileRead::TooLarge(file_len) => Err(ProcessingError::Skipped(
+                relative_path.to_string(),
+                format!("File too large: {file_len} bytes"),
+            )),
+            CatFileRead::Missing => Err(read_error("no such file".to_string())),
+            CatFileRead::NotABlob(object_type) => {
+                Err(read_error(format!("not a file but a {object_type}")))
+            }
+        }
+    }
+}
+
+/// What `git cat-file --batch` returned for an object
+enum CatFileRead {
+    Blob(Vec<u8>),
+    /// A blob larger than the maximum file size, with its size. Its content is not kept.
+    TooLarge(usize),
+    Missing,
+    NotABlob(String),
+}
+
+/// A `git cat-file --batch` process, which reads objects named on its input one after the
+/// other
+#[derive(Debug)]
+struct CatFileBatch {
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CatFileBatch {
+    fn spawn(repository_path: &Path) -> std::io::Result<Self> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repository_path)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        Ok(Self {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    async fn read(&mut self, object: &str, max_size: usize) -> std::io::Result<CatFileRead> {
+        self.stdin
+            .write_all(format!("{object}\n").as_bytes())
+            .await?;
+        self.stdin.flush().await?;
+
+        // `<oid> <type> <size>`, or `<object> missing` for an unknown object
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header).await? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "git cat-file exited",
+            ));
+        }
+        let header = header.trim_end();
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            return Ok(CatFileRead::Missing);
+        }
+        let mut fields = header.rsplitn(3, ' ');
+        let (Some(size), Some(object_type)) = (fields.next(), fields.next()) else {
+            return Err(std::io::Error::other(format!(
+                "unexpected git cat-file output '{header}'"
+            )));
+        };
+        let size = size.parse::<usize>().map_err(|_| {
+            std::io::Error::other(format!("unexpected git cat-file output '{header}'"))
+        })?;
+
+        // The content is followed by a newline
+        let read = if object_type != "blob" || size > max_size {
+            tokio::io::copy(
+                &mut (&mut self.stdout).take(size as u64 + 1),
+                &mut tokio::io::sink(),
+            )
+            .await?;
+            if object_type != "blob" {
+                CatFileRead::NotABlob(object_type.to_string())
+            } else {
+                CatFileRead::TooLarge(size)
+            }
+        } else {
+            let mut bytes = vec![0; size + 1];
+            self.stdout.read_exact(&mut bytes).await?;
+            bytes.truncate(size);
+            CatFileRead::Blob(bytes)
+        };
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    /// A repository with a commit tagged `v1`
+    fn create_repository(temp_dir: &TempDir) -> TestRepository {
+        let repository = TestRepository::with_files(
+            temp_dir.path(),
+            &[("app/billing.rb", "class Billing; end\n")],
+        );
+        repository.git(&["tag", "v1"]);
+        repository
+    }
+
+    #[tokio::test]
+    async fn test_files_are_read_at_the_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let repository = create_repository(&temp_dir);
+        let path = temp_dir.path();
+        std::fs::write(
+            path.join("app/billing.rb"),
+            "class Billing; def refund; end; end\n",
+        )
+        .unwrap();
+        std::fs::write(path.join("refunds.rb"), "class Refunds; end\n").unwrap();
+        repository.git(&["add", "."]);
+        repository.git(&["commit", "--quiet", "-m", "Refunds"]);
+
+        let commit = GitCommit::resolve(path, "v1").unwrap();
+        assert_eq!(commit.id().len(), 40);
+        assert_eq!(commit.list_files().unwrap(), vec!["app/billing.rb"]);
+        assert_eq!(
+            commit
+                .read_text_file("app/billing.rb", 1024, InvalidUtf8Policy::Skip)
+                .await
+                .unwrap()
+                .text,
+            "class Billing; end\n"
+        );
+        assert!(matches!(
+            commit
+                .read_text_file("app/billing.rb", 4, InvalidUtf8Policy::Skip)
+                .await,
+            Err(ProcessingError::Skipped(_, _))
+        ));
+        assert!(matches!(
+            commit
+                .read_text_file("refunds.rb", 1024, InvalidUtf8Policy::Skip)
+                .await,
+            Err(ProcessingError::Error(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_ref_is_not_resolved() {
+        let temp_dir = TempDir::new().unwrap();
+        create_repository(&temp_dir);
+        let error = GitCommit::resolve(temp_dir.path(), "does-not-exist").unwrap_err();
+        assert_eq!(error.git_ref, "does-not-exist");
+        assert!(error.to_string().contains("no such commit"));
+    }
+}
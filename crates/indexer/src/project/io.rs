@@ -1,5 +1,8 @@
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
 
+use sha2::{Digest, Sha256};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 
@@ -9,13 +12,209 @@ pub enum ProcessingError {
     Error(String, String),   // file_path, error_message
 }
 
+/// How files whose content is not valid UTF-8, or not valid UTF-16 for files starting with
+/// a UTF-16 byte order mark, are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidUtf8Policy {
+    /// Skip the file
+    #[default]
+    Skip,
+    /// Replace each invalid byte with `?` and parse the rest of the file. Unlike U+FFFD, which
+    /// takes three bytes, this keeps the byte offsets of the parsed content those of the file.
+    Lossy,
+}
+
+impl fmt::Display for InvalidUtf8Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidUtf8Policy::Skip => write!(f, "skip"),
+            InvalidUtf8Policy::Lossy => write!(f, "lossy"),
+        }
+    }
+}
+
+/// Parses `skip` or `lossy`
+impl FromStr for InvalidUtf8Policy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(InvalidUtf8Policy::Skip),
+            "lossy" => Ok(InvalidUtf8Policy::Lossy),
+            _ => Err(format!(
+                "Unknown invalid UTF-8 policy '{s}', expected skip or lossy"
+            )),
+        }
+    }
+}
+
+/// Encoding of a text file, detected from its byte order mark
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// UTF-8 without a byte order mark
+    #[default]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, which is stripped before parsing
+    Utf8Bom,
+    /// UTF-16 little endian, transcoded to UTF-8 before parsing
+    Utf16Le,
+    /// UTF-16 big endian, transcoded to UTF-8 before parsing
+    Utf16Be,
+}
+
+impl TextEncoding {
+    /// Detect the encoding of `bytes` from its byte order mark, returning it with the
+    /// length of the mark. Content without a mark is taken to be UTF-8.
+    fn detect(bytes: &[u8]) -> (Self, usize) {
+        let encoding = match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => TextEncoding::Utf8Bom,
+            [0xFF, 0xFE, ..] => TextEncoding::Utf16Le,
+            [0xFE, 0xFF, ..] => TextEncoding::Utf16Be,
+            _ => TextEncoding::Utf8,
+        };
+        (encoding, encoding.bom_len())
+    }
+
+    /// Length in bytes of the byte order mark, which the decoded text starts after
+    pub fn bom_len(&self) -> usize {
+        match self {
+            TextEncoding::Utf8 => 0,
+            TextEncoding::Utf8Bom => 3,
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 with BOM",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Content of a text file, decoded to UTF-8
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DecodedText {
+    pub text: String,
+    /// Encoding the file was decoded from
+    pub encoding: TextEncoding,
+    /// Whether invalid sequences were replaced, see [`InvalidUtf8Policy::Lossy`]
+    pub lossy: bool,
+    /// [`content_hash`] of the bytes as read, before decoding, so it can be compared with
+    /// the hash of the file on disk
+    pub content_hash: String,
+}
+
+/// Decode the bytes of the file at `file_path` to UTF-8. A UTF-8 byte order mark is
+/// stripped and UTF-16 content, detected from its byte order mark, is transcoded. Invalid
+/// content is handled according to `invalid_utf8`.
+pub fn decode_text(
+    file_path: String,
+    mut bytes: Vec<u8>,
+    invalid_utf8: InvalidUtf8Policy,
+) -> Result<DecodedText, ProcessingError> {
+    let raw_hash = content_hash(&bytes);
+    let (encoding, bom_len) = TextEncoding::detect(&bytes);
+    if matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be) {
+        return decode_utf16(file_path, &bytes[bom_len..], encoding, invalid_utf8).map(|decoded| {
+            DecodedText {
+                content_hash: raw_hash,
+                ..decoded
+            }
+        });
+    }
+
+    bytes.drain(..bom_len);
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(DecodedText {
+            text,
+            encoding,
+            lossy: false,
+            content_hash: raw_hash,
+        }),
+        Err(e) => match invalid_utf8 {
+            InvalidUtf8Policy::Skip => Err(ProcessingError::Skipped(
+                file_path,
+                "Non-UTF-8 content".to_string(),
+            )),
+            InvalidUtf8Policy::Lossy => Ok(DecodedText {
+                text: replace_invalid_utf8(e.as_bytes()),
+                encoding,
+                lossy: true,
+                content_hash: raw_hash,
+            }),
+        },
+    }
+}
+
+/// `bytes` with each byte of its invalid UTF-8 sequences replaced with `?`, so the text is as
+/// long as `bytes`
+fn replace_invalid_utf8(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        text.extend(std::iter::repeat_n('?', chunk.invalid().len()));
+    }
+    text
+}
+
+/// Transcode UTF-16 `bytes`, without their byte order mark, to UTF-8. Unpaired surrogates
+/// and a trailing odd byte are invalid.
+fn decode_utf16(
+    file_path: String,
+    bytes: &[u8],
+    encoding: TextEncoding,
+    invalid_utf8: InvalidUtf8Policy,
+) -> Result<DecodedText, ProcessingError> {
+    let units = bytes.chunks_exact(2).map(|pair| match encoding {
+        TextEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+        _ => u16::from_le_bytes([pair[0], pair[1]]),
+    });
+
+    let mut text = String::with_capacity(bytes.len() / 2);
+    let mut lossy = false;
+    for decoded in char::decode_utf16(units) {
+        text.push(decoded.unwrap_or_else(|_| {
+            lossy = true;
+            char::REPLACEMENT_CHARACTER
+        }));
+    }
+    if bytes.len() % 2 != 0 {
+        lossy = true;
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+
+    if lossy && invalid_utf8 == InvalidUtf8Policy::Skip {
+        return Err(ProcessingError::Skipped(
+            file_path,
+            format!("Invalid {encoding} content"),
+        ));
+    }
+    Ok(DecodedText {
+        text,
+        encoding,
+        lossy,
+        ..Default::default()
+    })
+}
+
 /// Read a text file efficiently with size checks.
 ///
 /// - Opens the file once and inspects metadata from the handle
+/// - Content that is not valid UTF-8 is handled according to `invalid_utf8`
 pub async fn read_text_file(
     full_path: &Path,
     max_file_size: usize,
-) -> Result<String, ProcessingError> {
+    invalid_utf8: InvalidUtf8Policy,
+) -> Result<DecodedText, ProcessingError> {
     let file_path = full_path.to_string_lossy().to_string();
 
     // Open file and inspect metadata from the handle
@@ -36,7 +235,7 @@ pub async fn read_text_file(
     }
 
     if file_len == 0 {
-        return Ok(String::new());
+        return decode_text(file_path, Vec::new(), invalid_utf8);
     }
 
     // Read the entire file into a buffer
@@ -45,14 +244,13 @@ pub async fn read_text_file(
         ProcessingError::Error(file_path.clone(), format!("Failed to read file: {e}"))
     })?;
 
-    // Validate UTF-8; skip if not valid text
-    match String::from_utf8(bytes) {
-        Ok(s) => Ok(s),
-        Err(_) => Err(ProcessingError::Skipped(
-            file_path,
-            "Non-UTF-8 content".to_string(),
-        )),
-    }
+    decode_text(file_path, bytes, invalid_utf8)
+}
+
+/// Hex-encoded SHA-256 of a file's content, stored with its `FileNode` to detect files
+/// whose content did not change since they were indexed
+pub fn content_hash(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
 }
 
 #[cfg(test)]
@@ -65,17 +263,20 @@ mod tests {
         let file = NamedTempFile::new().unwrap();
         std::fs::write(file.path(), "hello world").unwrap();
 
-        let content = read_text_file(file.path(), 1024)
+        let content = read_text_file(file.path(), 1024, InvalidUtf8Policy::Skip)
             .await
             .expect("should read");
-        assert_eq!(content, "hello world");
+        assert_eq!(content.text, "hello world");
+        assert!(!content.lossy);
     }
 
     #[tokio::test]
     async fn test_read_text_file_empty() {
         let file = NamedTempFile::new().unwrap();
-        let content = read_text_file(file.path(), 1024).await.expect("empty ok");
-        assert!(content.is_empty());
+        let content = read_text_file(file.path(), 1024, InvalidUtf8Policy::Skip)
+            .await
+            .expect("empty ok");
+        assert!(content.text.is_empty());
     }
 
     #[tokio::test]
@@ -84,7 +285,7 @@ mod tests {
         let data = vec![b'a'; 2048];
         std::fs::write(file.path(), &data).unwrap();
 
-        let err = read_text_file(file.path(), 1024)
+        let err = read_text_file(file.path(), 1024, InvalidUtf8Policy::Skip)
             .await
             .expect_err("should error");
         match err {
@@ -94,4 +295,116 @@ mod tests {
             _ => panic!("unexpected error type"),
         }
     }
+
+    #[tokio::test]
+    async fn test_read_text_file_invalid_utf8() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"class Caf\xe9; end\n").unwrap();
+
+        let err = read_text_file(file.path(), 1024, InvalidUtf8Policy::Skip)
+            .await
+            .expect_err("should be skipped");
+        assert!(
+            matches!(err, ProcessingError::Skipped(_, reason) if reason == "Non-UTF-8 content")
+        );
+
+        let content = read_text_file(file.path(), 1024, InvalidUtf8Policy::Lossy)
+            .await
+            .expect("should decode lossily");
+        assert_eq!(content.text, "class Caf?; end\n");
+        assert!(content.lossy);
+    }
+
+    #[test]
+    fn test_lossy_decoding_keeps_byte_offsets() {
+        // A truncated 3-byte sequence and a lone continuation byte
+        let bytes = b"a = \xE2\x82; b = \x80; c = 1\n".to_vec();
+        let decoded =
+            decode_text("a.rb".to_string(), bytes.clone(), InvalidUtf8Policy::Lossy).unwrap();
+        assert_eq!(decoded.text, "a = ??; b = ?; c = 1\n");
+        assert_eq!(decoded.text.len(), bytes.len());
+        assert_eq!(decoded.text.find("c = 1"), Some(15));
+        assert!(decoded.lossy);
+    }
+
+    #[test]
+    fn test_decode_text_strips_utf8_bom() {
+        let decoded = decode_text(
+            "a.rb".to_string(),
+            b"\xEF\xBB\xBFclass A; end\n".to_vec(),
+            InvalidUtf8Policy::Skip,
+        )
+        .unwrap();
+        assert_eq!(decoded.text, "class A; end\n");
+        assert_eq!(decoded.encoding, TextEncoding::Utf8Bom);
+        assert!(!decoded.lossy);
+    }
+
+    #[test]
+    fn test_decode_text_transcodes_utf16() {
+        let source = "class Café; end\n";
+        let little_endian: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(source.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let big_endian: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain(source.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+
+        for (bytes, encoding) in [
+            (little_endian, TextEncoding::Utf16Le),
+            (big_endian, TextEncoding::Utf16Be),
+        ] {
+            let decoded = decode_text("a.rb".to_string(), bytes, InvalidUtf8Policy::Skip).unwrap();
+            assert_eq!(decoded.text, source);
+            assert_eq!(decoded.encoding, encoding);
+            assert!(!decoded.lossy);
+        }
+    }
+
+    #[test]
+    fn test_decode_text_invalid_utf16() {
+        // Unpaired high surrogate followed by `A`
+        let bytes = vec![0xFF, 0xFE, 0x00, 0xD8, 0x41, 0x00];
+
+        let err = decode_text("a.rb".to_string(), bytes.clone(), InvalidUtf8Policy::Skip)
+            .expect_err("should be skipped");
+        assert!(
+            matches!(err, ProcessingError::Skipped(_, reason) if reason == "Invalid UTF-16LE content")
+        );
+
+        let decoded = decode_text("a.rb".to_string(), bytes, InvalidUtf8Policy::Lossy).unwrap();
+        assert_eq!(decoded.text, "\u{FFFD}A");
+        assert!(decoded.lossy);
+    }
+
+    #[test]
+    fn test_invalid_utf8_policy_from_str() {
+        assert_eq!("skip".parse(), Ok(InvalidUtf8Policy::Skip));
+        assert_eq!("Lossy".parse(), Ok(InvalidUtf8Policy::Lossy));
+        assert!("replace".parse::<InvalidUtf8Policy>().is_err());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b"hello world"),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_ne!(content_hash(b"hello world"), content_hash(b"hello world\n"));
+    }
+
+    #[test]
+    fn test_decoded_text_hashes_the_bytes_as_read() {
+        for bytes in [
+            b"\xEF\xBB\xBFclass A; end".to_vec(),
+            vec![0xFF, 0xFE, 0x41, 0x00],
+            b"class \xFF; end".to_vec(),
+        ] {
+            let decoded =
+                decode_text("a.rb".to_string(), bytes.clone(), InvalidUtf8Policy::Lossy).unwrap();
+            assert_eq!(decoded.content_hash, content_hash(&bytes));
+        }
+    }
 }
@@ -1,3 +1,6 @@
+pub mod exclude;
 pub mod file_info;
+pub mod git_ref;
 pub mod io;
 pub mod source;
+pub mod symlinks;
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::indexer::IndexingConfig;
 use crate::parsing::changes::FileChanges;
 use crate::project::file_info::FileInfo;
+use crate::project::git_ref::GitCommit;
 use ignore::WalkBuilder;
 use parser_core::parser::get_supported_extensions;
 use std::sync::{Arc, Mutex};
@@ -29,6 +30,11 @@ pub trait FileSource {
     type Error: std::fmt::Display + Send + Sync + 'static;
 
     fn get_files(&self, config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error>;
+
+    /// The commit the contents of the files are read from, `None` to read them from disk
+    fn commit(&self) -> Option<&GitCommit> {
+        None
+    }
 }
 
 pub struct PathFileSource {
@@ -89,11 +95,14 @@ impl PathFileSource {
 impl FileSource for PathFileSource {
     type Error = &'static str;
 
-    fn get_files(&self, _config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error> {
+    fn get_files(&self, config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error> {
         let filtered_files = self
             .files
             .iter()
-            .filter(|file_info| should_process_file_info(file_info, &self.supported_extensions))
+            .filter(|file_info| {
+                should_process_file_info(file_info, &self.supported_extensions)
+                    || has_extension_override(file_info, config)
+            })
             .cloned()
             .collect();
         Ok(filtered_files)
@@ -104,6 +113,8 @@ impl FileSource for PathFileSource {
 pub struct GitaliskFileSource {
     pub repository: gitalisk_core::repository::gitalisk_repository::CoreGitaliskRepository,
     pub supported_extensions: HashSet<String>,
+    /// Index this commit instead of the working tree
+    pub commit: Option<GitCommit>,
 }
 
 impl GitaliskFileSource {
@@ -118,14 +129,37 @@ impl GitaliskFileSource {
         Self {
             repository,
             supported_extensions,
+            commit: None,
         }
     }
+
+    /// Index the files of `commit`, read from the object database, instead of the working
+    /// tree
+    pub fn at_commit(mut self, commit: GitCommit) -> Self {
+        self.commit = Some(commit);
+        self
+    }
 }
 
 impl FileSource for GitaliskFileSource {
     type Error = std::io::Error;
 
     fn get_files(&self, config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error> {
+        if let Some(commit) = &self.commit {
+            // Hidden files are left out like in the working tree
+            let files = commit
+                .list_files()?
+                .into_iter()
+                .filter(|path| !path.split('/').any(|component| component.starts_with('.')))
+                .map(|path| FileInfo::from_path(commit.repository_path().join(path)))
+                .filter(|file_info| {
+                    should_process_file_info(file_info, &self.supported_extensions)
+                        || has_extension_override(file_info, config)
+                })
+                .collect();
+            return Ok(files);
+        }
+
         let gitalisk_files = self.repository.get_repo_files(
             gitalisk_core::repository::gitalisk_repository::IterFileOptions {
                 include_ignored: !config.respect_gitignore,
@@ -135,11 +169,18 @@ impl FileSource for GitaliskFileSource {
 
         let filtered_files = gitalisk_files
             .into_iter()
-            .filter(|file_info| should_process_file_info(file_info, &self.supported_extensions))
+            .filter(|file_info| {
+                should_process_file_info(file_info, &self.supported_extensions)
+                    || has_extension_override(file_info, config)
+            })
             .collect();
 
         Ok(filtered_files)
     }
+
+    fn commit(&self) -> Option<&GitCommit> {
+        self.commit.as_ref()
+    }
 }
 
 pub struct ChangesFileSource {
@@ -166,14 +207,16 @@ impl ChangesFileSource {
 impl FileSource for ChangesFileSource {
     type Error = std::io::Error;
 
-    fn get_files(&self, _config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error> {
+    fn get_files(&self, config: &IndexingConfig) -> Result<Vec<FileInfo>, Self::Error> {
         let mut files = Vec::new();
 
         // Convert changed files to FileInfo
         for file_path in &self.changes.changed_files {
             let path = PathBuf::from(&self.repository_path).join(file_path);
             let file_info = FileInfo::from_path(path);
-            if should_process_file_info(&file_info, &self.supported_extensions) {
+            if should_process_file_info(&file_info, &self.supported_extensions)
+                || has_extension_override(&file_info, config)
+            {
                 files.push(file_info);
             }
         }
@@ -188,3 +231,9 @@ fn should_process_file_info(file_info: &FileInfo, supported_extensions: &HashSet
     let extension = file_info.extension();
     supported_extensions.contains(extension)
 }
+
+fn has_extension_override(file_info: &FileInfo, config: &IndexingConfig) -> bool {
+    config
+        .extension_overrides
+        .contains_key(file_info.extension())
+}
@@ -0,0 +1,136 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use log::{info, warn};
+
+/// Files below the symlinked directories of `root`, including the ones reached through
+/// nested symlinks, listed under the path of the symlink rather than of its target. Hidden
+/// entries are left out like in the rest of the project.
+///
+/// A directory whose real path was already walked, `root` itself included, is not walked
+/// again, which breaks symlink cycles and keeps files from being listed twice.
+pub fn files_below_symlinked_directories(root: &Path, respect_gitignore: bool) -> Vec<PathBuf> {
+    let mut visited = HashSet::new();
+    let mut files = Vec::new();
+    let mut pending = VecDeque::new();
+
+    let Ok(real_root) = root.canonicalize() else {
+        return files;
+    };
+    visited.insert(real_root.clone());
+    // Files of `root` itself are already listed by the file source
+    walk(
+        root,
+        &real_root,
+        &visited,
+        respect_gitignore,
+        None,
+        &mut pending,
+    );
+
+    while let Some(symlink_path) = pending.pop_front() {
+        let Ok(real_path) = symlink_path.canonicalize() else {
+            warn!("Skipping dangling symlink: {}", symlink_path.display());
+            continue;
+        };
+        if let Some(walked) = visited.iter().find(|walked| real_path.starts_with(walked)) {
+            info!(
+                "Not following symlink {} to {}, already indexed through {}",
+                symlink_path.display(),
+                real_path.display(),
+                walked.display()
+            );
+            continue;
+        }
+
+        visited.insert(real_path.clone());
+        walk(
+            &symlink_path,
+            &real_path,
+            &visited,
+            respect_gitignore,
+            Some(&mut files),
+            &mut pending,
+        );
+    }
+
+    files
+}
+
+/// Walk the directory at `real_path` without following symlinks, adding its files under
+/// `display_path` to `files` and the symlinks to directories to `pending`. Directories
+/// that were already walked, e.g. the project when a symlink points to one of its parents,
+/// are skipped.
+fn walk(
+    display_path: &Path,
+    real_path: &Path,
+    visited: &HashSet<PathBuf>,
+    respect_gitignore: bool,
+    mut files: Option<&mut Vec<PathBuf>>,
+    pending: &mut VecDeque<PathBuf>,
+) {
+    let walked = visited.clone();
+    let root = real_path.to_path_buf();
+    let walker = WalkBuilder::new(real_path)
+        .follow_links(false)
+        .hidden(true)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .filter_entry(move |entry| entry.path() == root || !walked.contains(entry.path()))
+        .build();
+
+    for entry in walker.flatten() {
+        let Ok(relative_path) = entry.path().strip_prefix(real_path) else {
+            continue;
+        };
+        let path = display_path.join(relative_path);
+        if entry.path_is_symlink() {
+            if entry.path().is_dir() {
+                pending.push_back(path);
+            }
+        } else if let Some(files) = files.as_deref_mut()
+            && entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_symlinked_directories_are_followed_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir_all(project.join("app")).unwrap();
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(project.join("app/main.rb"), "class Main; end\n").unwrap();
+        std::fs::write(shared.join("billing.rb"), "class Billing; end\n").unwrap();
+
+        symlink(&shared, project.join("shared")).unwrap();
+        // Cycles back into the shared directory and the project
+        symlink(&shared, shared.join("again")).unwrap();
+        symlink(&project, shared.join("project")).unwrap();
+        // Second link to an already indexed directory
+        symlink(project.join("app"), project.join("app_link")).unwrap();
+
+        let files = files_below_symlinked_directories(&project, true);
+        assert_eq!(files, vec![project.join("shared/billing.rb")]);
+    }
+
+    #[test]
+    fn test_no_files_without_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("main.rb"), "class Main; end\n").unwrap();
+        assert!(files_below_symlinked_directories(temp_dir.path(), true).is_empty());
+    }
+}
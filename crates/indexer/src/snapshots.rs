@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::{DefinitionNodeFromKuzu, KuzuNodeType};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use workspace_manager::{WorkspaceManager, git_state};
+
+use crate::project::io::{InvalidUtf8Policy, decode_text};
+
+const SNAPSHOT_EXTENSION: &str = "json";
+
+/// A definition as recorded in a snapshot. Lines are 0-based, like in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDefinition {
+    pub fqn: String,
+    pub definition_type: String,
+    pub file_path: String,
+    pub start_line: i32,
+    pub end_line: i32,
+    /// Hash of the definition's source text, tells whether its body changed
+    pub range_hash: u64,
+}
+
+/// The definitions of a project after an index run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionSnapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Commit checked out when the snapshot was taken
+    pub commit: Option<String>,
+    pub definitions: Vec<SnapshotDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DefinitionChange {
+    pub before: SnapshotDefinition,
+    pub after: SnapshotDefinition,
+}
+
+/// Differences between the definitions of two snapshots, sorted by FQN
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DefinitionDiff {
+    pub added: Vec<SnapshotDefinition>,
+    pub removed: Vec<SnapshotDefinition>,
+    /// Same source text at another file or line
+    pub moved: Vec<DefinitionChange>,
+    /// Source text changed, wherever the definition now is
+    pub changed: Vec<DefinitionChange>,
+}
+
+impl DefinitionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl DefinitionSnapshot {
+    /// Reads the definitions of the project's database and hashes their source text from
+    /// the files of `project_path`
+    pub fn take(
+        database: &KuzuDatabase,
+        database_path: &Path,
+        project_path: &Path,
+    ) -> Result<Self> {
+        let project_database = database
+            .get_or_create_database(&database_path.to_string_lossy(), None)
+            .ok_or_else(|| anyhow!("Failed to open database {}", database_path.display()))?;
+        let nodes = NodeDatabaseService::new(&project_database)
+            .get_all::<DefinitionNodeFromKuzu>(KuzuNodeType::DefinitionNode)?;
+
+        let mut file_contents: HashMap<String, Option<Vec<u8>>> = HashMap::new();
+        let mut definitions: Vec<SnapshotDefinition> = nodes
+            .into_iter()
+            .map(|node| {
+                let content = file_contents
+                    .entry(node.primary_file_path.clone())
+                    .or_insert_with(|| {
+                        // Byte offsets are in the text as parsed, after decoding
+                        let bytes =
+                            std::fs::read(project_path.join(&node.primary_file_path)).ok()?;
+                        decode_text(
+                            node.primary_file_path.clone(),
+                            bytes,
+                            InvalidUtf8Policy::Lossy,
+                        )
+                        .ok()
+                        .map(|decoded| decoded.text.into_bytes())
+                    });
+                let source = content.as_deref().and_then(|content| {
+                    content.get(node.primary_start_byte as usize..node.primary_end_byte as usize)
+                });
+
+                SnapshotDefinition {
+                    range_hash: fnv1a_hash(source.unwrap_or_default()),
+                    fqn: node.fqn,
+                    definition_type: node.definition_type,
+                    file_path: node.primary_file_path,
+                    start_line: node.start_line,
+                    end_line: node.end_line,
+                }
+            })
+            .collect();
+        definitions.sort_by(|a, b| {
+            (&a.fqn, &a.file_path, a.start_line).cmp(&(&b.fqn, &b.file_path, b.start_line))
+        });
+
+        let created_at = Utc::now();
+        Ok(Self {
+            id: created_at.format("%Y%m%dT%H%M%S%.3fZ").to_string(),
+            created_at,
+            commit: git_state::head_commit(project_path),
+            definitions,
+        })
+    }
+}
+
+/// FNV-1a, stable across runs and toolchains unlike the std hasher
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Snapshots of one project, stored as `<id>.json` files in a directory of the data
+/// directory. Ids are timestamps, so they sort in the order the snapshots were taken.
+pub struct SnapshotStore {
+    directory: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Stores the snapshot and deletes the oldest ones beyond the `history` most recent
+    pub fn save(&self, snapshot: &DefinitionSnapshot, history: usize) -> Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        std::fs::write(
+            self.snapshot_path(&snapshot.id),
+            serde_json::to_string(snapshot)?,
+        )?;
+
+        let ids = self.list()?;
+        for id in ids.iter().take(ids.len().saturating_sub(history)) {
+            if let Err(e) = std::fs::remove_file(self.snapshot_path(id)) {
+                warn!("Failed to prune definition snapshot {id}: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Ids of the stored snapshots, oldest first
+    pub fn list(&self) -> Result<Vec<String>> {
+        if !self.directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut ids: Vec<String> = std::fs::read_dir(&self.directory)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == SNAPSHOT_EXTENSION)
+            })
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Loads a snapshot by id, or the latest one taken at a commit starting with `reference`
+    pub fn load(&self, reference: &str) -> Result<DefinitionSnapshot> {
+        let path = self.snapshot_path(reference);
+        if path.is_file() {
+            return Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?);
+        }
+
+        for id in self.list()?.iter().rev() {
+            let snapshot: DefinitionSnapshot =
+                serde_json::from_str(&std::fs::read_to_string(self.snapshot_path(id))?)?;
+            if !reference.is_empty()
+                && snapshot
+                    .commit
+                    .as_deref()
+                    .is_some_and(|commit| commit.starts_with(reference))
+            {
+                return Ok(snapshot);
+            }
+        }
+        Err(anyhow!("No definition snapshot matches '{reference}'"))
+    }
+
+    fn snapshot_path(&self, id: &str) -> PathBuf {
+        self.directory.join(format!("{id}.{SNAPSHOT_EXTENSION}"))
+    }
+}
+
+/// Compares the definitions of two snapshots. Definitions are matched by FQN and type,
+/// and definitions sharing both, like overloads, in the order of their location.
+pub fn diff_snapshots(before: &DefinitionSnapshot, after: &DefinitionSnapshot) -> DefinitionDiff {
+    fn group(snapshot: &DefinitionSnapshot) -> HashMap<(&str, &str), Vec<&SnapshotDefinition>> {
+        let mut groups: HashMap<(&str, &str), Vec<&SnapshotDefinition>> = HashMap::new();
+        for definition in &snapshot.definitions {
+            groups
+                .entry((definition.fqn.as_str(), definition.definition_type.as_str()))
+                .or_default()
+                .push(definition);
+        }
+        groups
+    }
+
+    let before_groups = group(before);
+    let after_groups = group(after);
+    let mut diff = DefinitionDiff::default();
+
+    for (key, before_definitions) in &before_groups {
+        let after_definitions = after_groups.get(key).map(Vec::as_slice).unwrap_or_default();
+        for (index, before_definition) in before_definitions.iter().enumerate() {
+            let Some(after_definition) = after_definitions.get(index) else {
+                diff.removed.push((*before_definition).clone());
+                continue;
+            };
+
+            let change = || DefinitionChange {
+                before: (*before_definition).clone(),
+                after: (*after_definition).clone(),
+            };
+            if before_definition.range_hash != after_definition.range_hash {
+                diff.changed.push(change());
+            } else if before_definition.file_path != after_definition.file_path
+                || before_definition.start_line != after_definition.start_line
+            {
+                diff.moved.push(change());
+            }
+        }
+    }
+    for (key, after_definitions) in &after_groups {
+        let before_count = before_groups.get(key).map_or(0, Vec::len);
+        diff.added.extend(
+            after_definitions
+                .iter()
+                .skip(before_count)
+                .map(|definition| (*definition).clone()),
+        );
+    }
+
+    diff.added.sort_by(|a, b| a.fqn.cmp(&b.fqn));
+    diff.removed.sort_by(|a, b| a.fqn.cmp(&b.fqn));
+    diff.moved.sort_by(|a, b| a.after.fqn.cmp(&b.after.fqn));
+    diff.changed.sort_by(|a, b| a.after.fqn.cmp(&b.after.fqn));
+    diff
+}
+
+/// Definitions added, removed, moved and changed in a project between two snapshots, given
+/// by id or commit
+pub fn diff_index(
+    workspace_manager: &WorkspaceManager,
+    project_path: &str,
+    snapshot_a: &str,
+    snapshot_b: &str,
+) -> Result<DefinitionDiff> {
+    let project_info = workspace_manager
+        .get_project_for_path(project_path)
+        .ok_or_else(|| anyhow!("Project not found: {project_path}"))?;
+    let directory = workspace_manager
+        .project_snapshots_directory(
+            &project_info.workspace_folder_path,
+            &project_info.project_path,
+        )
+        .ok_or_else(|| anyhow!("Project not found: {project_path}"))?;
+
+    let store = SnapshotStore::new(directory);
+    Ok(diff_snapshots(
+        &store.load(snapshot_a)?,
+        &store.load(snapshot_b)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn definition(
+        fqn: &str,
+        file_path: &str,
+        start_line: i32,
+        range_hash: u64,
+    ) -> SnapshotDefinition {
+        SnapshotDefinition {
+            fqn: fqn.to_string(),
+            definition_type: "Method".to_string(),
+            file_path: file_path.to_string(),
+            start_line,
+            end_line: start_line + 2,
+            range_hash,
+        }
+    }
+
+    fn snapshot(
+        id: &str,
+        commit: &str,
+        definitions: Vec<SnapshotDefinition>,
+    ) -> DefinitionSnapshot {
+        DefinitionSnapshot {
+            id: id.to_string(),
+            created_at: Utc::now(),
+            commit: Some(commit.to_string()),
+            definitions,
+        }
+    }
+
+    #[test]
+    fn test_diff_snapshots_reports_added_removed_moved_and_changed() {
+        let before = snapshot(
+            "1",
+            "aaa",
+            vec![
+                definition("Billing::charge", "billing.rb", 1, 10),
+                definition("Billing::refund", "billing.rb", 5, 20),
+                definition("Billing::void", "billing.rb", 9, 30),
+                definition("Billing::total", "billing.rb", 13, 40),
+            ],
+        );
+        let after = snapshot(
+            "2",
+            "bbb",
+            vec![
+                definition("Billing::charge", "billing.rb", 1, 10),
+                definition("Billing::refund", "refunds.rb", 0, 20),
+                definition("Billing::total", "billing.rb", 9, 41),
+                definition("Billing::tax", "billing.rb", 13, 50),
+            ],
+        );
+
+        let diff = diff_snapshots(&before, &after);
+
+        assert_eq!(
+            diff.added,
+            vec![definition("Billing::tax", "billing.rb", 13, 50)]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![definition("Billing::void", "billing.rb", 9, 30)]
+        );
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].after.file_path, "refunds.rb");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].after.fqn, "Billing::total");
+        assert!(diff_snapshots(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_store_prunes_and_loads_by_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnapshotStore::new(temp_dir.path().join("definition_snapshots"));
+
+        for (id, commit) in [
+            ("20260101T000000.000Z", "aaa111"),
+            ("20260102T000000.000Z", "bbb222"),
+            ("20260103T000000.000Z", "ccc333"),
+        ] {
+            store.save(&snapshot(id, commit, Vec::new()), 2).unwrap();
+        }
+
+        assert_eq!(
+            store.list().unwrap(),
+            vec!["20260102T000000.000Z", "20260103T000000.000Z"]
+        );
+        assert_eq!(store.load("bbb").unwrap().id, "20260102T000000.000Z");
+        assert_eq!(
+            store
+                .load("20260103T000000.000Z")
+                .unwrap()
+                .commit
+                .as_deref(),
+            Some("ccc333")
+        );
+        assert!(store.load("aaa").is_err());
+    }
+}
@@ -30,6 +30,7 @@ pub fn finalize_project_statistics(
     duration: Duration,
     graph_data: &GraphData,
     writer_result: &WriterResult,
+    excluded_files: usize,
 ) -> ProjectStatistics {
     let mut language_map: HashMap<String, (usize, usize, HashMap<String, usize>)> = HashMap::new();
 
@@ -78,6 +79,7 @@ pub fn finalize_project_statistics(
         project_name,
         project_path,
         total_files: writer_result.total_files,
+        excluded_files,
         total_definitions: writer_result.total_definitions,
         total_imported_symbols: writer_result.total_imported_symbols,
         total_definition_relationships: writer_result.total_definition_relationships,
@@ -93,6 +95,13 @@ pub struct StatisticsMetadata {
     pub timestamp: DateTime<Utc>,
     pub workspace_path: String,
     pub indexing_duration_seconds: f64,
+    /// False when indexed without resolving references, so the graph has no call relationships
+    #[serde(default = "default_references_resolved")]
+    pub references_resolved: bool,
+}
+
+fn default_references_resolved() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +117,9 @@ pub struct ProjectStatistics {
     pub project_name: String,
     pub project_path: String,
     pub total_files: usize,
+    /// Files left out because they match an exclude pattern
+    #[serde(default)]
+    pub excluded_files: usize,
     pub total_definitions: usize,
     pub total_imported_symbols: usize,
     pub total_definition_relationships: usize,
@@ -122,13 +134,21 @@ pub struct WorkspaceStatistics {
     pub metadata: StatisticsMetadata,
     pub total_projects: usize,
     pub total_files: usize,
+    #[serde(default)]
+    pub total_excluded_files: usize,
     pub total_definitions: usize,
     pub total_imported_symbols: usize,
     pub total_definition_relationships: usize,
     pub total_imported_symbol_relationships: usize,
+    /// Imports linked to a definition of another project, with cross-project resolution
+    #[serde(default)]
+    pub total_cross_project_links: usize,
 
     pub total_languages: HashMap<String, LanguageSummary>,
     pub projects: Vec<ProjectStatistics>,
+    /// Paths of the projects that failed to index, they aren't counted in the totals
+    #[serde(default)]
+    pub failed_projects: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,21 +166,26 @@ impl WorkspaceStatistics {
                 timestamp: Utc::now(),
                 workspace_path,
                 indexing_duration_seconds,
+                references_resolved: true,
             },
             total_projects: 0,
             total_files: 0,
+            total_excluded_files: 0,
             total_definitions: 0,
             total_imported_symbols: 0,
             total_definition_relationships: 0,
             total_imported_symbol_relationships: 0,
+            total_cross_project_links: 0,
 
             total_languages: HashMap::new(),
             projects: Vec::new(),
+            failed_projects: Vec::new(),
         }
     }
 
     pub fn add_project(&mut self, project_stats: ProjectStatistics) {
         self.total_files += project_stats.total_files;
+        self.total_excluded_files += project_stats.excluded_files;
         self.total_definitions += project_stats.total_definitions;
         self.total_imported_symbols += project_stats.total_imported_symbols;
         self.total_definition_relationships += project_stats.total_definition_relationships;
@@ -197,4 +222,14 @@ impl WorkspaceStatistics {
         fs::write(path, json)?;
         Ok(())
     }
+
+    /// Exports the statistics of several workspace folders as a JSON array
+    pub fn export_all_to_file<P: AsRef<Path>>(
+        workspace_stats: &[&WorkspaceStatistics],
+        path: P,
+    ) -> Result<()> {
+        let json = serde_json::to_string_pretty(workspace_stats)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
 }
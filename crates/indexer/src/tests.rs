@@ -1,11 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
 use crate::analysis::types::{DefinitionType, GraphData, RelationshipKind};
+use crate::execution::config::default_parse_memory_budget;
 use crate::indexer::{IndexingConfig, RepositoryIndexer};
 use crate::parsing::changes::FileChanges;
+use crate::progress::{IndexingPhase, IndexingProgressSummary, ProgressSink};
 use crate::project::file_info::FileInfo;
+use crate::project::io::InvalidUtf8Policy;
 use crate::project::source::{GitaliskFileSource, PathFileSource};
+use crate::writer::ParquetCompression;
 use database::graph::RelationshipType;
 use database::kuzu::connection::KuzuConnection;
 use database::kuzu::database::KuzuDatabase;
@@ -159,9 +164,9 @@ async fn setup_reindexing_pipeline(
 
     // Configure indexing for Ruby files
     let config = IndexingConfig {
-        worker_threads: 1, // Use single thread for deterministic testing
-        max_file_size: 5_000_000,
+        worker_threads: 1,        // Use single thread for deterministic testing
         respect_gitignore: false, // Don't use gitignore in tests
+        ..Default::default()
     };
 
     // Create output directory for this test
@@ -449,6 +454,76 @@ async fn test_full_reindexing_pipeline_git_status_typescript() {
     assert_eq!(imported_symbols.len(), 5);
 }
 
+#[traced_test]
+#[tokio::test]
+async fn test_reindexing_skips_files_with_unchanged_content() {
+    let database = Arc::new(KuzuDatabase::new());
+    let mut setup = setup_reindexing_pipeline(&database, SupportedLanguage::Ruby).await;
+
+    // Rewrite one file with the same content and append a comment to another
+    let touched_file = setup.local_repo.path.join("app/models/user_model.rb");
+    fs::write(&touched_file, fs::read(&touched_file).unwrap()).unwrap();
+    let modified_file = setup.local_repo.path.join("app/models/base_model.rb");
+    let mut content = fs::read_to_string(&modified_file).unwrap();
+    content.push_str("\n# changed\n");
+    fs::write(&modified_file, content).unwrap();
+
+    let file_changes = FileChanges {
+        changed_files: HashSet::from([
+            "app/models/user_model.rb".to_string(),
+            "app/models/base_model.rb".to_string(),
+        ]),
+        deleted_files: HashSet::new(),
+        changed_dirs: HashSet::new(),
+        deleted_dirs: HashSet::new(),
+    };
+    let result = setup
+        .indexer
+        .reindex_repository(
+            &database,
+            file_changes,
+            &setup.config,
+            &setup.database_path,
+            &setup.output_path,
+        )
+        .await
+        .expect("Failed to reindex repository");
+    assert_eq!(result.unchanged_files, 1);
+    let writer_result = result.writer_result.expect("Should have writer result");
+    assert_eq!(writer_result.total_files, 1);
+
+    // Nothing is reparsed when every changed file has unchanged content
+    let file_changes = FileChanges {
+        changed_files: HashSet::from([touched_file.to_string_lossy().to_string()]),
+        deleted_files: HashSet::new(),
+        changed_dirs: HashSet::new(),
+        deleted_dirs: HashSet::new(),
+    };
+    let result = setup
+        .indexer
+        .reindex_repository(
+            &database,
+            file_changes,
+            &setup.config,
+            &setup.database_path,
+            &setup.output_path,
+        )
+        .await
+        .expect("Failed to reindex repository");
+    assert_eq!(result.unchanged_files, 1);
+    assert!(result.writer_result.is_none());
+    assert!(!result.database_loaded);
+
+    let database_instance = database
+        .get_or_create_database(&setup.database_path, None)
+        .expect("Failed to create database");
+    let node_database_service = NodeDatabaseService::new(&database_instance);
+    assert_eq!(
+        node_database_service.count_nodes::<DefinitionNodeFromKuzu>(),
+        96
+    );
+}
+
 #[traced_test]
 #[tokio::test]
 async fn test_typescript_call_relationship_has_location() {
@@ -517,6 +592,72 @@ async fn test_typescript_call_relationship_has_location() {
     assert_eq!(end_line, 21);
 }
 
+/// Types of the relationships from the definitions whose FQNs end with `source_fqn` to the
+/// definitions named `target_name`
+fn relationship_types_between(
+    conn: &KuzuConnection,
+    source_fqn: &str,
+    target_name: &str,
+) -> HashSet<String> {
+    let query = format!(
+        "MATCH (source:DefinitionNode)-[r:DEFINITION_RELATIONSHIPS]->(target:DefinitionNode) \
+         WHERE source.fqn ENDS WITH '{source_fqn}' AND target.name = '{target_name}' \
+         RETURN DISTINCT r.type"
+    );
+    conn.query(&query)
+        .expect("query ok")
+        .into_iter()
+        .filter_map(|row| match row.first() {
+            Some(kuzu::Value::String(relationship_type)) => Some(relationship_type.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_typescript_type_annotations_are_references() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::TypeScript);
+    let database = setup_end_to_end_kuzu(&temp_repo).await;
+    let database_path = temp_repo.workspace_path.join("database.kz");
+    let database_instance = database
+        .get_or_create_database(database_path.to_str().unwrap(), None)
+        .expect("db open");
+    let conn = KuzuConnection::new(&database_instance).expect("conn");
+
+    // `static findByUsername(username: string): User | undefined` only names User as a type
+    let relationship_types = relationship_types_between(&conn, "User::findByUsername", "User");
+    assert!(
+        relationship_types.contains(RelationshipType::References.as_str()),
+        "The return type should be a reference, got {relationship_types:?}"
+    );
+    assert!(!relationship_types.contains(RelationshipType::Calls.as_str()));
+    assert!(!relationship_types.contains(RelationshipType::Instantiates.as_str()));
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_typescript_only_new_expressions_instantiate() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::TypeScript);
+    let database = setup_end_to_end_kuzu(&temp_repo).await;
+    let database_path = temp_repo.workspace_path.join("database.kz");
+    let database_instance = database
+        .get_or_create_database(database_path.to_str().unwrap(), None)
+        .expect("db open");
+    let conn = KuzuConnection::new(&database_instance).expect("conn");
+
+    // `const user = new User({ username, email, password });`
+    let relationship_types = relationship_types_between(&conn, "User::create", "User");
+    assert!(
+        relationship_types.contains(RelationshipType::Instantiates.as_str()),
+        "The new expression should instantiate User, got {relationship_types:?}"
+    );
+
+    // `return User.getUsersDb();` only calls a static method of User
+    let relationship_types = relationship_types_between(&conn, "UserRepository::allUsers", "User");
+    assert!(!relationship_types.contains(RelationshipType::Instantiates.as_str()));
+}
+
 async fn setup_end_to_end_kuzu(temp_repo: &LocalGitRepository) -> Arc<KuzuDatabase> {
     // Create temporary repository with test files
     let repo_path = temp_repo.path.to_str().unwrap();
@@ -531,8 +672,8 @@ async fn setup_end_to_end_kuzu(temp_repo: &LocalGitRepository) -> Arc<KuzuDataba
     // Configure indexing for Ruby files
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     // Run full processing pipeline
@@ -572,8 +713,8 @@ async fn test_new_indexer_with_gitalisk_file_source() {
 
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     let temp_output_dir = temp_repo.workspace_path.join("output");
@@ -600,6 +741,128 @@ async fn test_new_indexer_with_gitalisk_file_source() {
     );
 }
 
+#[traced_test]
+#[tokio::test]
+async fn test_tiny_parse_memory_budget_still_indexes_all_files() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+    let database = Arc::new(KuzuDatabase::new());
+
+    let mut total_files = Vec::new();
+    for (run, parse_memory_budget) in [default_parse_memory_budget(), 1].into_iter().enumerate() {
+        let gitalisk_repo =
+            CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+        let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+        let file_source = GitaliskFileSource::new(gitalisk_repo);
+
+        // A budget smaller than any file forces the files to be parsed one at a time
+        let config = IndexingConfig {
+            worker_threads: 4,
+            respect_gitignore: false,
+            parse_memory_budget,
+            ..Default::default()
+        };
+
+        let output_dir = temp_repo.workspace_path.join(format!("output_{run}"));
+        let db_path = temp_repo.workspace_path.join(format!("database_{run}.kz"));
+        let result = indexer
+            .index_files(
+                &database,
+                output_dir.to_str().unwrap(),
+                db_path.to_str().unwrap(),
+                file_source,
+                &config,
+            )
+            .await
+            .expect("Failed to index files");
+
+        assert_eq!(result.errored_files.len(), 0, "Should have no errors");
+        total_files.push(result.writer_result.unwrap().total_files);
+    }
+
+    assert!(total_files[0] > 0, "Should have processed some files");
+    assert_eq!(
+        total_files[0], total_files[1],
+        "A small memory budget must not drop files"
+    );
+}
+
+#[derive(Default)]
+struct RecordingProgressSink {
+    parsed_files: std::sync::Mutex<Vec<(usize, usize)>>,
+    phases: std::sync::Mutex<Vec<IndexingPhase>>,
+    completed: std::sync::Mutex<Vec<IndexingProgressSummary>>,
+}
+
+impl ProgressSink for RecordingProgressSink {
+    fn on_file_parsed(&self, _file_path: &str, completed: usize, total: usize) {
+        self.parsed_files.lock().unwrap().push((completed, total));
+    }
+
+    fn on_phase_changed(&self, phase: IndexingPhase) {
+        self.phases.lock().unwrap().push(phase);
+    }
+
+    fn on_complete(&self, summary: &IndexingProgressSummary) {
+        self.completed.lock().unwrap().push(summary.clone());
+    }
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_progress_sink_receives_indexing_progress() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let gitalisk_repo = CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+    let sink = Arc::new(RecordingProgressSink::default());
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string())
+        .with_progress_sink(sink.clone());
+    let file_source = GitaliskFileSource::new(gitalisk_repo);
+
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        ..Default::default()
+    };
+
+    let output_dir = temp_repo.workspace_path.join("output");
+    let db_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+    indexer
+        .index_files(
+            &database,
+            output_dir.to_str().unwrap(),
+            db_path.to_str().unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    let completed = sink.completed.lock().unwrap().clone();
+    assert_eq!(completed.len(), 1, "on_complete should be called once");
+    assert_eq!(completed[0].repository_path, repo_path);
+    assert_eq!(completed[0].errored_files, 0);
+    assert!(completed[0].processed_files > 0);
+
+    let total_files =
+        completed[0].processed_files + completed[0].skipped_files + completed[0].errored_files;
+    let parsed_files = sink.parsed_files.lock().unwrap().clone();
+    assert_eq!(parsed_files.len(), total_files);
+    assert_eq!(parsed_files.last(), Some(&(total_files, total_files)));
+
+    assert_eq!(
+        *sink.phases.lock().unwrap(),
+        vec![
+            IndexingPhase::Parsing,
+            IndexingPhase::Analyzing,
+            IndexingPhase::Writing,
+            IndexingPhase::Loading,
+        ]
+    );
+}
+
 #[traced_test]
 #[tokio::test]
 async fn test_new_indexer_with_path_file_source() {
@@ -619,8 +882,8 @@ async fn test_new_indexer_with_path_file_source() {
 
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     let temp_output_dir = temp_repo.workspace_path.join("output");
@@ -647,6 +910,351 @@ async fn test_new_indexer_with_path_file_source() {
     );
 }
 
+#[traced_test]
+#[tokio::test]
+async fn test_extension_overrides_route_custom_extensions() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let custom_file = temp_repo.path.join("custom_model.rbx");
+    std::fs::write(&custom_file, "class CustomModel\n  def save\n  end\nend\n").unwrap();
+    let unknown_file = temp_repo.path.join("notes.xyz");
+    std::fs::write(&unknown_file, "class NotIndexed\nend\n").unwrap();
+
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+    let file_source = PathFileSource::new(vec![
+        FileInfo::from_path(custom_file),
+        FileInfo::from_path(unknown_file),
+    ]);
+
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        write_parquet: false,
+        extension_overrides: HashMap::from([("rbx".to_string(), SupportedLanguage::Ruby)]),
+        ..Default::default()
+    };
+
+    let output_path = temp_repo.workspace_path.join("output");
+    let db_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+
+    let result = indexer
+        .index_files(
+            &database,
+            output_path.to_str().unwrap(),
+            db_path.to_str().unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    assert_eq!(result.successful_files().len(), 1);
+    assert!(
+        result.successful_files()[0]
+            .file_path
+            .ends_with("custom_model.rbx")
+    );
+    assert_eq!(result.successful_files()[0].language, "Ruby");
+
+    let graph_data = result.graph_data.expect("Should have graph data");
+    assert!(
+        graph_data
+            .definition_nodes
+            .iter()
+            .any(|def| def.fqn == "CustomModel")
+    );
+    assert!(
+        !graph_data
+            .definition_nodes
+            .iter()
+            .any(|def| def.fqn == "NotIndexed")
+    );
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_exclude_patterns_skip_matching_files() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let generated_dir = temp_repo.path.join("dist");
+    std::fs::create_dir_all(&generated_dir).unwrap();
+    let generated_file = generated_dir.join("generated.rb");
+    std::fs::write(&generated_file, "class Generated\nend\n").unwrap();
+    let source_file = temp_repo.path.join("source.rb");
+    std::fs::write(&source_file, "class Source\nend\n").unwrap();
+
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+    let file_source = PathFileSource::new(vec![
+        FileInfo::from_path(generated_file),
+        FileInfo::from_path(source_file),
+    ]);
+
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        write_parquet: false,
+        exclude_patterns: vec!["dist/".to_string()],
+        ..Default::default()
+    };
+
+    let output_path = temp_repo.workspace_path.join("output");
+    let db_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+
+    let result = indexer
+        .index_files(
+            &database,
+            output_path.to_str().unwrap(),
+            db_path.to_str().unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    assert_eq!(result.excluded_files, 1);
+    assert_eq!(result.successful_files().len(), 1);
+    assert!(
+        result.successful_files()[0]
+            .file_path
+            .ends_with("source.rb")
+    );
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_files_over_the_definition_cap_are_skipped() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let generated_file = temp_repo.path.join("generated.rb");
+    std::fs::write(
+        &generated_file,
+        "class Generated\n  def one\n  end\n\n  def two\n  end\nend\n",
+    )
+    .unwrap();
+    let source_file = temp_repo.path.join("source.rb");
+    std::fs::write(&source_file, "class Source\nend\n").unwrap();
+
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+    let file_source = PathFileSource::new(vec![
+        FileInfo::from_path(generated_file),
+        FileInfo::from_path(source_file),
+    ]);
+
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        write_parquet: false,
+        max_definitions_per_file: 2,
+        ..Default::default()
+    };
+
+    let output_path = temp_repo.workspace_path.join("output");
+    let db_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+
+    let result = indexer
+        .index_files(
+            &database,
+            output_path.to_str().unwrap(),
+            db_path.to_str().unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    assert_eq!(result.successful_files().len(), 1);
+    assert!(
+        result.successful_files()[0]
+            .file_path
+            .ends_with("source.rb")
+    );
+    let skipped_files: Vec<_> = result.skipped_files().collect();
+    assert_eq!(skipped_files.len(), 1);
+    assert!(skipped_files[0].file_path.ends_with("generated.rb"));
+    assert_eq!(
+        skipped_files[0].reason,
+        "Too many definitions: 3, the limit is 2"
+    );
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_lossy_decoding_indexes_invalid_utf8_files() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let legacy_file = temp_repo.path.join("legacy.rb");
+    std::fs::write(
+        &legacy_file,
+        b"# Caf\xe9 menu\nclass LegacyMenu\n  def items\n  end\nend\n",
+    )
+    .unwrap();
+
+    let index_legacy_file = |invalid_utf8: InvalidUtf8Policy| {
+        let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+        let file_source = PathFileSource::new(vec![FileInfo::from_path(legacy_file.clone())]);
+        let config = IndexingConfig {
+            worker_threads: 1,
+            respect_gitignore: false,
+            write_parquet: false,
+            invalid_utf8,
+            ..Default::default()
+        };
+        let output_path = temp_repo
+            .workspace_path
+            .join(format!("output-{invalid_utf8}"));
+        let db_path = temp_repo
+            .workspace_path
+            .join(format!("database-{invalid_utf8}.kz"));
+        async move {
+            let database = Arc::new(KuzuDatabase::new());
+            indexer
+                .index_files(
+                    &database,
+                    output_path.to_str().unwrap(),
+                    db_path.to_str().unwrap(),
+                    file_source,
+                    &config,
+                )
+                .await
+                .expect("Failed to index files")
+        }
+    };
+
+    let skipped = index_legacy_file(InvalidUtf8Policy::Skip).await;
+    assert!(skipped.successful_files().is_empty());
+    let skipped_files: Vec<_> = skipped.skipped_files().collect();
+    assert_eq!(skipped_files.len(), 1);
+    assert_eq!(skipped_files[0].reason, "Non-UTF-8 content");
+
+    let decoded = index_legacy_file(InvalidUtf8Policy::Lossy).await;
+    assert_eq!(decoded.successful_files().len(), 1);
+    assert!(decoded.successful_files()[0].lossy_decoded);
+    let graph_data = decoded.graph_data.expect("Should have graph data");
+    assert!(
+        graph_data
+            .definition_nodes
+            .iter()
+            .any(|def| def.fqn == "LegacyMenu#items")
+    );
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_byte_order_mark_is_recorded_as_the_content_offset() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let windows_file = temp_repo.path.join("windows.rb");
+    let content = b"\xEF\xBB\xBFclass WindowsMenu\nend\n";
+    std::fs::write(&windows_file, content).unwrap();
+
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+    let file_source = PathFileSource::new(vec![FileInfo::from_path(windows_file)]);
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        write_parquet: false,
+        ..Default::default()
+    };
+    let database = Arc::new(KuzuDatabase::new());
+    let result = indexer
+        .index_files(
+            &database,
+            temp_repo.workspace_path.join("output").to_str().unwrap(),
+            temp_repo
+                .workspace_path
+                .join("database.kz")
+                .to_str()
+                .unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    let graph_data = result.graph_data.expect("Should have graph data");
+    assert_eq!(graph_data.file_nodes.len(), 1);
+    let content_offset = graph_data.file_nodes[0].content_offset as usize;
+    assert_eq!(content_offset, 3);
+
+    let definition = graph_data
+        .definition_nodes
+        .iter()
+        .find(|def| def.fqn == "WindowsMenu")
+        .expect("Should have the class definition");
+    let start = definition.range.byte_offset.0 + content_offset;
+    assert!(content[start..].starts_with(b"class WindowsMenu"));
+}
+
+#[traced_test]
+#[tokio::test]
+async fn test_indexing_result_file_reports() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+
+    let mut ruby_files = Vec::new();
+    for entry in walkdir::WalkDir::new(repo_path) {
+        let entry = entry.unwrap();
+        if entry.path().extension().and_then(|s| s.to_str()) == Some("rb") {
+            ruby_files.push(FileInfo::from_path(entry.path().to_path_buf()));
+        }
+    }
+    let ruby_files_len = ruby_files.len();
+
+    // Non-UTF-8 content is skipped rather than parsed
+    let binary_file = temp_repo.path.join("binary.rb");
+    std::fs::write(&binary_file, [0x00, 0x81, 0xff, 0xfe]).unwrap();
+    ruby_files.push(FileInfo::from_path(binary_file.clone()));
+
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+    let file_source = PathFileSource::new(ruby_files);
+
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        ..Default::default()
+    };
+
+    let temp_output_dir = temp_repo.workspace_path.join("output");
+    let temp_db_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+
+    let result = indexer
+        .index_files(
+            &database,
+            temp_output_dir.to_str().unwrap(),
+            temp_db_path.to_str().unwrap(),
+            file_source,
+            &config,
+        )
+        .await
+        .expect("Failed to index files");
+
+    let successful_files = result.successful_files();
+    assert_eq!(successful_files.len(), ruby_files_len);
+    assert!(successful_files.iter().all(|file| file.language == "Ruby"));
+    assert!(
+        successful_files
+            .iter()
+            .any(|file| file.definition_count > 0),
+        "Some Ruby files should contain definitions"
+    );
+
+    assert_eq!(result.errored_files().count(), 0, "Should have no errors");
+
+    let skipped_files: Vec<_> = result.skipped_files().collect();
+    assert_eq!(skipped_files.len(), 1);
+    assert!(skipped_files[0].file_path.ends_with("binary.rb"));
+    assert_eq!(skipped_files[0].reason, "Non-UTF-8 content");
+}
+
 #[traced_test]
 #[tokio::test]
 async fn test_full_indexing_pipeline() {
@@ -663,9 +1271,9 @@ async fn test_full_indexing_pipeline() {
 
     // Configure indexing for Ruby files
     let config = IndexingConfig {
-        worker_threads: 1, // Use single thread for deterministic testing
-        max_file_size: 5_000_000,
+        worker_threads: 1,        // Use single thread for deterministic testing
         respect_gitignore: false, // Don't use gitignore in tests
+        ..Default::default()
     };
 
     // Create output directory for this test
@@ -815,8 +1423,8 @@ async fn test_inheritance_relationships() {
     // Configure indexing for Ruby files
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     // Run full processing
@@ -1141,8 +1749,8 @@ async fn test_detailed_data_inspection() {
     // Configure indexing for Ruby files
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     // Run full processing pipeline
@@ -1211,8 +1819,8 @@ async fn test_parquet_file_structure() {
     // Configure indexing for Ruby files
     let config = IndexingConfig {
         worker_threads: 1,
-        max_file_size: 5_000_000,
         respect_gitignore: false,
+        ..Default::default()
     };
 
     // Create a known output directory
@@ -1236,6 +1844,7 @@ async fn test_parquet_file_structure() {
         .expect("Failed to process repository");
 
     let writer_result = result.writer_result.expect("Should have writer result");
+    assert_eq!(writer_result.compression, Some(ParquetCompression::Snappy));
 
     println!("\n📁 === CONSOLIDATED PARQUET FILE STRUCTURE VERIFICATION ===");
 
@@ -1438,3 +2047,230 @@ async fn test_parquet_file_structure() {
     println!("\n✅ Consolidated Parquet file structure verification completed!");
     println!("📁 Output directory: {}", output_dir.display());
 }
+
+#[tokio::test]
+async fn test_index_without_parquet_matches_parquet_import() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+    let database = Arc::new(KuzuDatabase::new());
+
+    let mut counts = Vec::new();
+    for write_parquet in [true, false] {
+        let gitalisk_repo =
+            CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+        let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+        let config = IndexingConfig {
+            worker_threads: 1,
+            respect_gitignore: false,
+            write_parquet,
+            ..Default::default()
+        };
+
+        let output_dir = temp_repo
+            .workspace_path
+            .join(format!("output_{write_parquet}"));
+        let database_path = temp_repo
+            .workspace_path
+            .join(format!("database_{write_parquet}.kz"));
+        let result = indexer
+            .process_files_full_with_database(
+                &database,
+                GitaliskFileSource::new(gitalisk_repo),
+                &config,
+                output_dir.to_str().unwrap(),
+                database_path.to_str().unwrap(),
+            )
+            .await
+            .expect("Failed to process repository");
+
+        let writer_result = result.writer_result.expect("Should have writer result");
+        assert_eq!(writer_result.files_written.is_empty(), !write_parquet);
+        assert_eq!(writer_result.compression.is_some(), write_parquet);
+        assert_eq!(output_dir.exists(), write_parquet);
+
+        let database_instance = database
+            .get_or_create_database(database_path.to_str().unwrap(), None)
+            .expect("Failed to open database");
+        let service = NodeDatabaseService::new(&database_instance);
+        let node_counts = service.get_node_counts().unwrap();
+        let relationship_counts = service.get_relationship_counts().unwrap();
+        assert!(node_counts.definition_count > 0);
+        counts.push(format!("{node_counts:?} {relationship_counts:?}"));
+    }
+
+    assert_eq!(counts[0], counts[1]);
+}
+
+#[tokio::test]
+async fn test_indexing_without_references_builds_structural_graph() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+    let database = Arc::new(KuzuDatabase::new());
+
+    let mut graphs = Vec::new();
+    for resolve_references in [true, false] {
+        let gitalisk_repo =
+            CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+        let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+        let config = IndexingConfig {
+            worker_threads: 1,
+            respect_gitignore: false,
+            write_parquet: false,
+            resolve_references,
+            ..Default::default()
+        };
+
+        let database_path = temp_repo
+            .workspace_path
+            .join(format!("database_{resolve_references}.kz"));
+        let result = indexer
+            .process_files_full_with_database(
+                &database,
+                GitaliskFileSource::new(gitalisk_repo),
+                &config,
+                temp_repo.workspace_path.join("output").to_str().unwrap(),
+                database_path.to_str().unwrap(),
+            )
+            .await
+            .expect("Failed to process repository");
+        graphs.push(result.graph_data.expect("Should have graph data"));
+    }
+
+    let calls = |graph: &GraphData| {
+        graph
+            .relationships
+            .iter()
+            .filter(|relationship| relationship.relationship_type == RelationshipType::Calls)
+            .count()
+    };
+    assert!(calls(&graphs[0]) > 0);
+    assert_eq!(calls(&graphs[1]), 0);
+    assert_eq!(
+        graphs[0].definition_nodes.len(),
+        graphs[1].definition_nodes.len()
+    );
+    assert_eq!(graphs[0].file_nodes.len(), graphs[1].file_nodes.len());
+}
+
+#[tokio::test]
+async fn test_parquet_written_in_bounded_batches() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+    let gitalisk_repo = CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+    let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+
+    let batch_size = 10;
+    let config = IndexingConfig {
+        worker_threads: 1,
+        respect_gitignore: false,
+        parquet_batch_size: batch_size,
+        ..Default::default()
+    };
+
+    let output_dir = temp_repo.workspace_path.join("batched_output");
+    let database_path = temp_repo.workspace_path.join("database.kz");
+    let database = Arc::new(KuzuDatabase::new());
+    let result = indexer
+        .process_files_full_with_database(
+            &database,
+            GitaliskFileSource::new(gitalisk_repo),
+            &config,
+            output_dir.to_str().unwrap(),
+            database_path.to_str().unwrap(),
+        )
+        .await
+        .expect("Failed to process repository");
+
+    let writer_result = result.writer_result.expect("Should have writer result");
+    let definitions_file = writer_result
+        .files_written
+        .iter()
+        .find(|file| file.file_type == "definitions")
+        .expect("Should have written definitions");
+    assert_eq!(
+        definitions_file.record_count,
+        writer_result.total_definitions
+    );
+
+    // Every batch is its own row group, and together they hold every record
+    let reader = parquet::file::reader::SerializedFileReader::new(
+        fs::File::open(&definitions_file.file_path).unwrap(),
+    )
+    .unwrap();
+    let metadata = parquet::file::reader::FileReader::metadata(&reader);
+    assert_eq!(
+        metadata.num_row_groups(),
+        writer_result.total_definitions.div_ceil(batch_size)
+    );
+    assert!(
+        metadata
+            .row_groups()
+            .iter()
+            .all(|row_group| row_group.num_rows() as usize <= batch_size)
+    );
+    assert_eq!(
+        metadata.file_metadata().num_rows() as usize,
+        definitions_file.record_count
+    );
+
+    // Writing in batches doesn't change what ends up in the database
+    let database_instance = database
+        .get_or_create_database(database_path.to_str().unwrap(), None)
+        .expect("Failed to open database");
+    let node_counts = NodeDatabaseService::new(&database_instance)
+        .get_node_counts()
+        .unwrap();
+    assert_eq!(
+        node_counts.definition_count as usize,
+        writer_result.total_definitions
+    );
+}
+
+#[tokio::test]
+async fn test_reindexing_identical_source_writes_identical_parquet() {
+    let temp_repo = init_local_git_repository(SupportedLanguage::Ruby);
+    let repo_path = temp_repo.path.to_str().unwrap();
+    let database = Arc::new(KuzuDatabase::new());
+
+    let mut runs = Vec::new();
+    for (run, worker_threads) in [1, 4].into_iter().enumerate() {
+        let gitalisk_repo =
+            CoreGitaliskRepository::new(repo_path.to_string(), repo_path.to_string());
+        let indexer = RepositoryIndexer::new("test-repo".to_string(), repo_path.to_string());
+        let config = IndexingConfig {
+            worker_threads,
+            respect_gitignore: false,
+            ..Default::default()
+        };
+
+        let output_dir = temp_repo.workspace_path.join(format!("output_{run}"));
+        let database_path = temp_repo.workspace_path.join(format!("database_{run}.kz"));
+        let result = indexer
+            .process_files_full_with_database(
+                &database,
+                GitaliskFileSource::new(gitalisk_repo),
+                &config,
+                output_dir.to_str().unwrap(),
+                database_path.to_str().unwrap(),
+            )
+            .await
+            .expect("Failed to process repository");
+
+        let writer_result = result.writer_result.expect("Should have writer result");
+        let mut files: Vec<_> = writer_result
+            .files_written
+            .iter()
+            .map(|file| {
+                (
+                    file.file_path.file_name().unwrap().to_owned(),
+                    fs::read(&file.file_path).unwrap(),
+                )
+            })
+            .collect();
+        files.sort();
+        runs.push(files);
+    }
+
+    assert!(!runs[0].is_empty());
+    assert!(runs[0] == runs[1], "Parquet output differs between runs");
+}
@@ -2,25 +2,103 @@ use crate::analysis::types::{
     ConsolidatedRelationship, DefinitionNode, DirectoryNode, FileNode, GraphData,
     ImportedSymbolNode, RelationshipKind,
 };
-use crate::analysis::types::{get_relationships_for_pair, rels_by_kind};
+use crate::analysis::types::{get_relationships_for_pair, relationship_kind_for_pair};
 use crate::mutation::utils::{GraphMapper, NodeIdGenerator};
 use anyhow::{Context, Error, Result};
 use arrow::{datatypes::Schema, record_batch::RecordBatch};
 use database::schema::init::RELATIONSHIP_TABLES;
 use database::schema::types::{
-    ArrowBatchConverter, RelationshipTable, ToArrowBatch, ToArrowRelationshipBatch,
+    ArrowBatchConverter, GraphRecordBatches, NodeFieldAccess, NodeTable, RelationshipRecordBatch,
+    RelationshipTable, ToArrowBatch, ToArrowRelationshipBatch,
+};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
 };
-use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
 use std::{
+    fmt,
     fs::File,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+/// Compression codec used for the Parquet files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParquetCompression {
+    None,
+    #[default]
+    Snappy,
+    /// Zstandard with the given level (1 to 22)
+    Zstd(i32),
+}
+
+impl ParquetCompression {
+    /// Level used when `zstd` is given without one, matching the zstd command line tool
+    pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+    fn to_parquet_compression(self) -> Result<Compression> {
+        Ok(match self {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd(level) => Compression::ZSTD(
+                ZstdLevel::try_new(level)
+                    .map_err(|e| anyhow::anyhow!("Invalid zstd level {level}: {e}"))?,
+            ),
+        })
+    }
+}
+
+impl fmt::Display for ParquetCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParquetCompression::None => write!(f, "none"),
+            ParquetCompression::Snappy => write!(f, "snappy"),
+            ParquetCompression::Zstd(level) => write!(f, "zstd:{level}"),
+        }
+    }
+}
+
+/// Parses `none`, `snappy`, `zstd` or `zstd:<level>`
+impl FromStr for ParquetCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let compression = match s.to_ascii_lowercase().as_str() {
+            "none" | "uncompressed" => ParquetCompression::None,
+            "snappy" => ParquetCompression::Snappy,
+            "zstd" => ParquetCompression::Zstd(Self::DEFAULT_ZSTD_LEVEL),
+            other => match other.strip_prefix("zstd:") {
+                Some(level) => ParquetCompression::Zstd(
+                    level
+                        .parse()
+                        .map_err(|_| format!("Invalid zstd level: {level}"))?,
+                ),
+                None => {
+                    return Err(format!(
+                        "Unknown compression '{s}', expected none, snappy, zstd or zstd:<level>"
+                    ));
+                }
+            },
+        };
+        // Reject out of range levels up front rather than when the first file is written
+        compression
+            .to_parquet_compression()
+            .map_err(|e| e.to_string())?;
+        Ok(compression)
+    }
+}
+
+/// Rows converted to Arrow and written as one Parquet row group at a time
+pub const DEFAULT_PARQUET_BATCH_SIZE: usize = 50_000;
+
 /// Writer service for creating Parquet files from graph data
 pub struct WriterService {
     output_directory: PathBuf,
+    compression: ParquetCompression,
+    batch_size: usize,
 }
 
 /// Results of writing graph data to Parquet files
@@ -38,6 +116,8 @@ pub struct WriterResult {
     pub total_definition_imported_symbol_relationships: usize,
     pub total_imported_symbol_relationships: usize,
     pub writing_duration: Duration,
+    /// Codec of the written files, `None` when no Parquet file was written
+    pub compression: Option<ParquetCompression>,
 }
 
 /// Information about a written Parquet file
@@ -64,7 +144,24 @@ impl WriterService {
             })?;
         }
 
-        Ok(Self { output_directory })
+        Ok(Self {
+            output_directory,
+            compression: ParquetCompression::default(),
+            batch_size: DEFAULT_PARQUET_BATCH_SIZE,
+        })
+    }
+
+    /// Convert and write at most `batch_size` rows at a time. Smaller batches lower the peak
+    /// memory of the writer at the cost of more, smaller row groups.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Compress the written files with `compression` instead of Snappy
+    pub fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
     }
 
     pub fn flush_output_directory(&self) -> Result<bool, Error> {
@@ -83,27 +180,39 @@ impl WriterService {
         Ok(false)
     }
 
-    pub fn write_batch_to_parquet(
+    fn create_parquet_writer(
         &self,
         file_path: &Path,
         schema: Arc<Schema>,
-        batch: &RecordBatch,
-    ) -> Result<()> {
-        // Write to parquet file
+    ) -> Result<ArrowWriter<File>> {
         let file = File::create(file_path)
             .with_context(|| format!("Failed to create file: {}", file_path.display()))?;
 
+        // Every batch is flushed as its own row group so the writer never buffers more
         let props = WriterProperties::builder()
-            .set_compression(Compression::SNAPPY)
+            .set_compression(self.compression.to_parquet_compression()?)
+            .set_max_row_group_size(self.batch_size)
             .build();
 
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+        Ok(ArrowWriter::try_new(file, schema, Some(props))?)
+    }
+
+    pub fn write_batch_to_parquet(
+        &self,
+        file_path: &Path,
+        schema: Arc<Schema>,
+        batch: &RecordBatch,
+    ) -> Result<()> {
+        // Write to parquet file
+        let mut writer = self.create_parquet_writer(file_path, schema)?;
         writer.write(batch)?;
         writer.close()?;
         Ok(())
     }
 
-    /// Write graph data to Parquet files with consolidated relationship schema
+    /// Write graph data to Parquet files with consolidated relationship schema.
+    /// Records are converted and written `batch_size` rows at a time, so the writer only
+    /// holds one batch in memory on top of the graph data.
     pub fn write_graph_data(
         &self,
         graph_data: &mut GraphData,
@@ -111,12 +220,203 @@ impl WriterService {
     ) -> Result<WriterResult> {
         let start_time = Instant::now();
         log::info!(
-            "Starting to write graph data to Parquet files in directory: {}",
-            self.output_directory.display()
+            "Starting to write graph data to Parquet files in directory: {} (batch size: {})",
+            self.output_directory.display(),
+            self.batch_size
         );
 
+        let mut graph_mapper = GraphMapper::new(graph_data, node_id_generator);
+
+        // Pre-assign IDs to all nodes
+        graph_mapper.assign_node_ids();
+
+        // Consolidate relationships with assigned IDs
+        graph_mapper.assign_relationship_ids()?;
+
         let mut files_written = Vec::new();
 
+        // WRITE ALL NODES to PARQUET
+        files_written.extend(self.write_nodes(
+            &database::schema::init::DIRECTORY_TABLE,
+            &graph_data.directory_nodes,
+            |n: &DirectoryNode| node_id_generator.get_directory_id(&n.path).unwrap_or(0),
+        )?);
+        files_written.extend(self.write_nodes(
+            &database::schema::init::FILE_TABLE,
+            &graph_data.file_nodes,
+            |n: &FileNode| node_id_generator.get_file_id(&n.path).unwrap_or(0),
+        )?);
+        files_written.extend(self.write_nodes(
+            &database::schema::init::DEFINITION_TABLE,
+            &graph_data.definition_nodes,
+            |n: &DefinitionNode| {
+                node_id_generator
+                    .get_definition_id(&n.file_path, n.range.byte_offset.0, n.range.byte_offset.1)
+                    .unwrap_or(0)
+            },
+        )?);
+        files_written.extend(self.write_nodes(
+            &database::schema::init::IMPORTED_SYMBOL_TABLE,
+            &graph_data.imported_symbol_nodes,
+            |n: &ImportedSymbolNode| {
+                node_id_generator
+                    .get_imported_symbol_id(
+                        &n.location.file_path,
+                        n.location.start_byte as usize,
+                        n.location.end_byte as usize,
+                    )
+                    .unwrap_or(0)
+            },
+        )?);
+
+        for table in RELATIONSHIP_TABLES.iter() {
+            for (from, to) in table.from_to_pairs {
+                let Some(kind) = relationship_kind_for_pair(from, to) else {
+                    continue;
+                };
+                let relationships = graph_data
+                    .relationships
+                    .iter()
+                    .filter(|rel| rel.kind == kind);
+                files_written.extend(self.write_relationships(table, from, to, relationships)?);
+            }
+        }
+
+        let writing_duration = start_time.elapsed();
+
+        log::info!(
+            "✅ Parquet writing completed in {:?}. Files written: {}",
+            writing_duration,
+            files_written.len()
+        );
+
+        let mut writer_result = summarize_graph_data(graph_data);
+        writer_result.files_written = files_written;
+        writer_result.writing_duration = writing_duration;
+        writer_result.compression = Some(self.compression);
+        Ok(writer_result)
+    }
+
+    /// Write the nodes of a table, one batch at a time
+    fn write_nodes<T, F>(
+        &self,
+        table: &NodeTable,
+        nodes: &[T],
+        id_callback: F,
+    ) -> Result<Option<WrittenFile>>
+    where
+        T: NodeFieldAccess,
+        F: Fn(&T) -> u32 + Clone,
+    {
+        if nodes.is_empty() {
+            log::warn!("No nodes to write for {}", table.name);
+            return Ok(None);
+        }
+
+        let file_path = self.output_directory.join(table.parquet_filename);
+        log::info!(
+            "Writing {} nodes to Parquet: {}",
+            table.name,
+            file_path.display()
+        );
+
+        let mut writer = self.create_parquet_writer(&file_path, table.to_arrow_schema())?;
+        let mut record_count = 0;
+        for chunk in nodes.chunks(self.batch_size) {
+            let batch = <ArrowBatchConverter as ToArrowBatch<T>>::to_record_batch(
+                chunk,
+                table,
+                id_callback.clone(),
+            )
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Error converting {} nodes to Arrow batch: {}",
+                    table.name,
+                    e
+                )
+            })?;
+            writer.write(&batch)?;
+            record_count += batch.num_rows();
+        }
+        writer.close()?;
+
+        log::info!(
+            "✅ Successfully wrote {} {} nodes to Parquet",
+            record_count,
+            table.name
+        );
+        let file_type = match table.parquet_filename.to_string().strip_suffix(".parquet") {
+            Some(s) => s.to_string(),
+            None => table.parquet_filename.to_string(),
+        };
+        Ok(Some(WrittenFile {
+            file_path: file_path.clone(),
+            file_type,
+            record_count,
+            file_size_bytes: self.get_file_size(&file_path)?,
+        }))
+    }
+
+    /// Write consolidated relationships of one FROM/TO pair, one batch at a time. No file is
+    /// created when there are no relationships.
+    fn write_relationships<'r>(
+        &self,
+        table: &RelationshipTable,
+        from_table: &NodeTable,
+        to_table: &NodeTable,
+        relationships: impl Iterator<Item = &'r ConsolidatedRelationship>,
+    ) -> Result<Option<WrittenFile>> {
+        let filename = from_table.relationship_filename(to_table);
+        let file_path = self.output_directory.join(&filename);
+
+        let mut writer = None;
+        let mut record_count = 0;
+        let mut chunk = Vec::with_capacity(self.batch_size.min(1024));
+        let mut relationships = relationships.peekable();
+        while let Some(relationship) = relationships.next() {
+            chunk.push(relationship.clone());
+            if chunk.len() < self.batch_size && relationships.peek().is_some() {
+                continue;
+            }
+
+            let batch = ArrowBatchConverter::to_relationship_record_batch(chunk.as_slice(), table)
+                .map_err(|e| anyhow::anyhow!("Failed to create Arrow batch: {}", e))?;
+            if writer.is_none() {
+                log::info!(
+                    "Writing consolidated relationships to Parquet: {}",
+                    file_path.display(),
+                );
+                writer = Some(self.create_parquet_writer(&file_path, table.to_arrow_schema())?);
+            }
+            if let Some(writer) = writer.as_mut() {
+                writer.write(&batch)?;
+            }
+            record_count += chunk.len();
+            chunk.clear();
+        }
+
+        let Some(writer) = writer else {
+            return Ok(None);
+        };
+        writer.close()?;
+
+        log::info!("✅ Successfully wrote {record_count} consolidated relationships to Parquet");
+        Ok(Some(WrittenFile {
+            file_path: file_path.clone(),
+            file_type: filename,
+            record_count,
+            file_size_bytes: self.get_file_size(&file_path)?,
+        }))
+    }
+
+    /// Assign node IDs and convert graph data to Arrow batches without writing any file.
+    /// The batches can be imported into Kuzu directly, the result lists no written files.
+    pub fn convert_graph_data(
+        graph_data: &mut GraphData,
+        node_id_generator: &mut NodeIdGenerator,
+    ) -> Result<(GraphRecordBatches, WriterResult)> {
+        let start_time = Instant::now();
+
         let mut graph_mapper = GraphMapper::new(graph_data, node_id_generator);
 
         // Pre-assign IDs to all nodes
@@ -125,8 +425,10 @@ impl WriterService {
         // Consolidate relationships with assigned IDs
         graph_mapper.assign_relationship_ids()?;
 
-        // WRITE ALL NODES to PARQUET
-        let batches = [
+        let mut batches = GraphRecordBatches::default();
+
+        // CONVERT ALL NODES
+        let node_batches = [
             (
                 &database::schema::init::DIRECTORY_TABLE,
                 ArrowBatchConverter::to_record_batch(
@@ -177,36 +479,14 @@ impl WriterService {
             ),
         ];
 
-        for (table, batch) in batches {
-            let file_path = self.output_directory.join(table.parquet_filename);
-            log::info!(
-                "Writing {} nodes to Parquet: {}",
-                table.name,
-                file_path.display()
-            );
+        for (table, batch) in node_batches {
             match batch {
                 Ok(batch) => {
                     if batch.num_rows() == 0 {
                         log::warn!("No nodes to write for {}", table.name);
                         continue;
                     }
-                    self.write_batch_to_parquet(&file_path, table.to_arrow_schema(), &batch)?;
-                    log::info!(
-                        "✅ Successfully wrote {} {} nodes to Parquet",
-                        batch.num_rows(),
-                        table.name
-                    );
-                    let file_type =
-                        match table.parquet_filename.to_string().strip_suffix(".parquet") {
-                            Some(s) => s.to_string(),
-                            None => table.parquet_filename.to_string(),
-                        };
-                    files_written.push(WrittenFile {
-                        file_path: file_path.clone(),
-                        file_type,
-                        record_count: batch.num_rows(),
-                        file_size_bytes: self.get_file_size(&file_path)?,
-                    });
+                    batches.nodes.push((table, batch));
                 }
                 Err(e) => {
                     log::error!(
@@ -222,104 +502,25 @@ impl WriterService {
             for (from, to) in table.from_to_pairs {
                 let (filename, relationships) =
                     get_relationships_for_pair(&graph_data.relationships, from, to);
-                if let Some(filename) = &filename {
-                    let file_path = self.output_directory.join(filename);
-                    if relationships.is_empty() {
-                        continue;
-                    }
-                    self.write_consolidated_relationships(&file_path, &relationships, table)?;
-                    files_written.push(WrittenFile {
-                        file_path: file_path.clone(),
-                        file_type: filename.clone(),
-                        record_count: relationships.len(),
-                        file_size_bytes: self.get_file_size(&file_path)?,
-                    });
+                if filename.is_none() || relationships.is_empty() {
+                    continue;
                 }
+                let batch =
+                    ArrowBatchConverter::to_relationship_record_batch(&relationships, table)
+                        .map_err(|e| anyhow::anyhow!("Failed to create Arrow batch: {}", e))?;
+                batches.relationships.push(RelationshipRecordBatch {
+                    table,
+                    from_table: from,
+                    to_table: to,
+                    batch,
+                });
             }
         }
 
-        let writing_duration = start_time.elapsed();
-
-        log::info!(
-            "✅ Parquet writing completed in {:?}. Files written: {}",
-            writing_duration,
-            files_written.len()
-        );
-
-        Ok(WriterResult {
-            files_written,
-            total_directories: graph_data.directory_nodes.len(),
-            total_files: graph_data.file_nodes.len(),
-            total_definitions: graph_data.definition_nodes.len(),
-            total_imported_symbols: graph_data.imported_symbol_nodes.len(),
-            total_directory_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::DirectoryToDirectory,
-            )
-            .len()
-                + rels_by_kind(&graph_data.relationships, RelationshipKind::DirectoryToFile).len(),
-            total_file_definition_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::FileToDefinition,
-            )
-            .len(),
-            total_file_imported_symbol_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::FileToImportedSymbol,
-            )
-            .len(),
-            total_definition_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::DefinitionToDefinition,
-            )
-            .len(),
-            total_definition_imported_symbol_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::DefinitionToImportedSymbol,
-            )
-            .len(),
-            total_imported_symbol_relationships: rels_by_kind(
-                &graph_data.relationships,
-                RelationshipKind::ImportedSymbolToDefinition,
-            )
-            .len()
-                + rels_by_kind(
-                    &graph_data.relationships,
-                    RelationshipKind::ImportedSymbolToImportedSymbol,
-                )
-                .len()
-                + rels_by_kind(
-                    &graph_data.relationships,
-                    RelationshipKind::ImportedSymbolToFile,
-                )
-                .len(),
-            writing_duration,
-        })
-    }
-
-    /// Write consolidated relationships to a Parquet file
-    fn write_consolidated_relationships(
-        &self,
-        file_path: &Path,
-        relationships: &[ConsolidatedRelationship],
-        table: &RelationshipTable,
-    ) -> Result<()> {
-        log::info!(
-            "Writing {} consolidated relationships to Parquet: {}",
-            relationships.len(),
-            file_path.display(),
-        );
+        let mut writer_result = summarize_graph_data(graph_data);
+        writer_result.writing_duration = start_time.elapsed();
 
-        let batch = ArrowBatchConverter::to_relationship_record_batch(relationships, table)
-            .map_err(|e| anyhow::anyhow!("Failed to create Arrow batch: {}", e))?;
-
-        self.write_batch_to_parquet(file_path, table.to_arrow_schema(), &batch)?;
-
-        log::info!(
-            "✅ Successfully wrote {} consolidated relationships to Parquet",
-            relationships.len()
-        );
-        Ok(())
+        Ok((batches, writer_result))
     }
 
     /// Get file size in bytes
@@ -330,6 +531,42 @@ impl WriterService {
     }
 }
 
+/// Node and relationship totals of the graph data, without any written file
+fn summarize_graph_data(graph_data: &GraphData) -> WriterResult {
+    let count = |kinds: &[RelationshipKind]| {
+        graph_data
+            .relationships
+            .iter()
+            .filter(|rel| kinds.contains(&rel.kind))
+            .count()
+    };
+
+    WriterResult {
+        files_written: Vec::new(),
+        total_directories: graph_data.directory_nodes.len(),
+        total_files: graph_data.file_nodes.len(),
+        total_definitions: graph_data.definition_nodes.len(),
+        total_imported_symbols: graph_data.imported_symbol_nodes.len(),
+        total_directory_relationships: count(&[
+            RelationshipKind::DirectoryToDirectory,
+            RelationshipKind::DirectoryToFile,
+        ]),
+        total_file_definition_relationships: count(&[RelationshipKind::FileToDefinition]),
+        total_file_imported_symbol_relationships: count(&[RelationshipKind::FileToImportedSymbol]),
+        total_definition_relationships: count(&[RelationshipKind::DefinitionToDefinition]),
+        total_definition_imported_symbol_relationships: count(&[
+            RelationshipKind::DefinitionToImportedSymbol,
+        ]),
+        total_imported_symbol_relationships: count(&[
+            RelationshipKind::ImportedSymbolToDefinition,
+            RelationshipKind::ImportedSymbolToImportedSymbol,
+            RelationshipKind::ImportedSymbolToFile,
+        ]),
+        writing_duration: Duration::ZERO,
+        compression: None,
+    }
+}
+
 impl WriterResult {
     /// Format the writer result as a readable string
     pub fn format_summary(&self) -> String {
@@ -342,6 +579,9 @@ impl WriterResult {
             "  • Total files written: {}\n",
             self.files_written.len()
         ));
+        if let Some(compression) = self.compression {
+            result.push_str(&format!("  • Compression: {compression}\n"));
+        }
         result.push_str(&format!(
             "  • Directory nodes: {}\n",
             self.total_directories
@@ -391,3 +631,34 @@ impl WriterResult {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_parquet_compression() {
+        assert_eq!(
+            "none".parse::<ParquetCompression>(),
+            Ok(ParquetCompression::None)
+        );
+        assert_eq!(
+            "Snappy".parse::<ParquetCompression>(),
+            Ok(ParquetCompression::Snappy)
+        );
+        assert_eq!(
+            "zstd".parse::<ParquetCompression>(),
+            Ok(ParquetCompression::Zstd(
+                ParquetCompression::DEFAULT_ZSTD_LEVEL
+            ))
+        );
+        assert_eq!(
+            "zstd:19".parse::<ParquetCompression>(),
+            Ok(ParquetCompression::Zstd(19))
+        );
+        assert!("zstd:99".parse::<ParquetCompression>().is_err());
+        assert!("gzip".parse::<ParquetCompression>().is_err());
+        assert_eq!(ParquetCompression::default(), ParquetCompression::Snappy);
+        assert_eq!(ParquetCompression::Zstd(19).to_string(), "zstd:19");
+    }
+}
@@ -1,17 +1,31 @@
 //! This crate provides logging initialization for the gkg application.
 //!
 //! It supports three modes:
-//! - CLI mode: logs to STDOUT.
+//! - CLI mode: logs to STDOUT, or only warnings and errors to STDERR when quiet.
 //! - ServerForeground mode: logs to STDERR and to a rolling file (keeps STDOUT clean for protocol output).
 //! - ServerBackground mode: logs to a rolling file in the system's data directory.
 //!
 //! The server logs are rolled over when they reach 5 MB. Rotated logs are
 //! compressed. The maximum number of rotated logs is 20.
+//!
+//! In CLI mode, the logs can be written as JSON lines instead of text, and the spans of the run
+//! can also be written to a Chrome trace for profiling.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use file_rotate::{ContentLimit, FileRotate, compression::Compression, suffix::AppendCount};
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::Subscriber;
 use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
-use tracing_subscriber::{EnvFilter, fmt::writer::MakeWriterExt};
+use tracing_chrome::{ChromeLayer, ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, fmt::writer::MakeWriterExt};
 use workspace_manager::data_directory::DataDirectory;
 
 pub enum LogMode {
@@ -22,25 +36,99 @@ pub enum LogMode {
     DataStdout,
 }
 
+/// How much is logged. The default level of `Normal` can be changed with `RUST_LOG`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Only warnings and errors
+    Quiet,
+    #[default]
+    Normal,
+    /// Everything down to debug logs
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn from_verbose(verbose: bool) -> Self {
+        Self::from_flags(verbose, false)
+    }
+
+    /// The verbosity of `--verbose` and `--quiet`, quiet winning when both are set
+    pub fn from_flags(verbose: bool, quiet: bool) -> Self {
+        match (verbose, quiet) {
+            (_, true) => Verbosity::Quiet,
+            (true, false) => Verbosity::Verbose,
+            (false, false) => Verbosity::Normal,
+        }
+    }
+}
+
+/// How the logs of a CLI run are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// One human readable line per event
+    #[default]
+    Text,
+    /// One JSON object per line, for tools collecting the logs
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Parses `text` or `json`
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format '{s}', expected text or json")),
+        }
+    }
+}
+
 /// Guard that keeps background logging workers alive.
 pub struct LoggingGuards {
     _guards: Vec<WorkerGuard>,
+    _trace: Option<FlushGuard>,
 }
 
-pub fn init(mode: LogMode, verbose: bool) -> Result<Option<LoggingGuards>> {
-    let filter = if verbose {
-        EnvFilter::new("debug")
-    } else {
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
-    };
+fn env_filter(verbosity: Verbosity) -> EnvFilter {
+    match verbosity {
+        Verbosity::Quiet => EnvFilter::new("warn"),
+        Verbosity::Normal => {
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+        }
+        Verbosity::Verbose => EnvFilter::new("debug"),
+    }
+}
+
+/// With `profile`, the spans of a CLI run at info level and above are written to that file as
+/// a Chrome trace, see [`chrome_trace_layer`]. `format` and `profile` are ignored in the server
+/// modes.
+pub fn init(
+    mode: LogMode,
+    verbosity: Verbosity,
+    format: LogFormat,
+    profile: Option<&Path>,
+) -> Result<Option<LoggingGuards>> {
+    let filter = env_filter(verbosity);
 
     match mode {
         LogMode::Cli => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .with_target(false)
-                .init();
-            Ok(None)
+            // Quiet runs are scripted, so what's left to log is kept off STDOUT
+            let writer = match verbosity {
+                Verbosity::Quiet => BoxMakeWriter::new(std::io::stderr),
+                _ => BoxMakeWriter::new(std::io::stdout),
+            };
+            init_cli(filter, format, profile, writer)
         }
         LogMode::ServerForeground => {
             let data_dir = DataDirectory::get_system_data_directory()?;
@@ -75,6 +163,7 @@ pub fn init(mode: LogMode, verbose: bool) -> Result<Option<LoggingGuards>> {
 
             Ok(Some(LoggingGuards {
                 _guards: vec![file_guard, stderr_guard],
+                _trace: None,
             }))
         }
         LogMode::ServerBackground => {
@@ -100,6 +189,7 @@ pub fn init(mode: LogMode, verbose: bool) -> Result<Option<LoggingGuards>> {
 
             Ok(Some(LoggingGuards {
                 _guards: vec![guard],
+                _trace: None,
             }))
         }
         LogMode::ServerDeployed => {
@@ -115,3 +205,192 @@ pub fn init(mode: LogMode, verbose: bool) -> Result<Option<LoggingGuards>> {
         LogMode::DataStdout => Ok(None),
     }
 }
+
+/// Like [`init`] in CLI mode, with the logs written to `console` instead of STDOUT, e.g. so
+/// they don't garble a progress bar
+pub fn init_cli_with_writer(
+    verbosity: Verbosity,
+    format: LogFormat,
+    profile: Option<&Path>,
+    console: BoxMakeWriter,
+) -> Result<Option<LoggingGuards>> {
+    init_cli(env_filter(verbosity), format, profile, console)
+}
+
+/// Writes the spans of a run to `path` as a Chrome trace, to see where the time goes in
+/// `chrome://tracing`, [Perfetto](https://ui.perfetto.dev) or speedscope. The file is replaced
+/// if it exists, and the trace is written out once the returned guard is dropped.
+pub fn chrome_trace_layer<S>(path: &Path) -> Result<(ChromeLayer<S>, FlushGuard)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+{
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create the trace file {}", path.display()))?;
+    Ok(ChromeLayerBuilder::new()
+        .writer(file)
+        .include_args(true)
+        .build())
+}
+
+/// The layer writing the logs of a CLI run to `writer`
+fn console_layer<S>(format: LogFormat, writer: BoxMakeWriter) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.with_ansi(false).json().boxed(),
+    }
+}
+
+fn init_cli(
+    filter: EnvFilter,
+    format: LogFormat,
+    profile: Option<&Path>,
+    writer: BoxMakeWriter,
+) -> Result<Option<LoggingGuards>> {
+    let (trace_layer, trace_guard) = match profile {
+        Some(profile) => {
+            let (layer, guard) = chrome_trace_layer(profile)?;
+            (Some(layer.with_filter(LevelFilter::INFO)), Some(guard))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(console_layer(format, writer).with_filter(filter))
+        .with(trace_layer)
+        .init();
+
+    Ok(trace_guard.map(|guard| LoggingGuards {
+        _guards: vec![],
+        _trace: Some(guard),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// A writer keeping what's written in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn log_with(verbosity: Verbosity, format: LogFormat) -> Vec<String> {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            console_layer(format, BoxMakeWriter::new(move || writer.clone()))
+                .with_filter(env_filter(verbosity)),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("parsing");
+            tracing::info!("indexed");
+            tracing::warn!(files = 2, "skipped");
+        });
+        captured.lines()
+    }
+
+    #[test]
+    fn test_verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(false, false), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(true, false), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, true), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(true, true), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_verbose(true), Verbosity::Verbose);
+        assert_eq!(Verbosity::default(), Verbosity::Normal);
+    }
+
+    #[test]
+    fn test_verbosity_filters_levels() {
+        let quiet = log_with(Verbosity::Quiet, LogFormat::Text);
+        assert_eq!(quiet.len(), 1);
+        assert!(quiet[0].contains("skipped"));
+
+        let verbose = log_with(Verbosity::Verbose, LogFormat::Text);
+        assert_eq!(verbose.len(), 3);
+        assert!(verbose[0].contains("parsing"));
+    }
+
+    #[test]
+    fn test_quiet_json_logs() {
+        let lines = log_with(Verbosity::Quiet, LogFormat::Json);
+        assert_eq!(lines.len(), 1);
+        let event: Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(event["level"], "WARN");
+        assert_eq!(event["fields"]["message"], "skipped");
+        assert_eq!(event["fields"]["files"], 2);
+    }
+
+    #[test]
+    fn test_log_format_parsing() {
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("Text".parse::<LogFormat>(), Ok(LogFormat::Text));
+        assert!("yaml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::Json.to_string(), "json");
+    }
+
+    #[test]
+    fn test_spans_are_written_to_the_chrome_trace() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("trace.json");
+        let (layer, guard) = chrome_trace_layer(&path).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _index = tracing::info_span!("index", files = 3).entered();
+            let _parse = tracing::info_span!("parse", language = "ruby").entered();
+        });
+        drop(guard);
+
+        let trace: Vec<Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let phases: Vec<(&str, &str)> = trace
+            .iter()
+            .filter(|event| event["ph"] == "B" || event["ph"] == "E")
+            .map(|event| {
+                (
+                    event["ph"].as_str().unwrap(),
+                    event["name"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                ("B", "index"),
+                ("B", "parse"),
+                ("E", "parse"),
+                ("E", "index")
+            ]
+        );
+        let index = trace.iter().find(|event| event["name"] == "index").unwrap();
+        assert_eq!(index["args"]["files"], "3");
+    }
+}
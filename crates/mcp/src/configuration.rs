@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{collections::HashSet, sync::Arc};
 
 use log::{info, warn};
@@ -12,12 +12,16 @@ const MCP_CONFIGURATION_FILE_NAME: &str = "mcp.settings.json";
 #[derive(Serialize, Deserialize)]
 pub struct McpConfiguration {
     pub disabled_tools: HashSet<String>,
+    /// Workspace folder or project paths the tools may access. Empty allows every registered project.
+    #[serde(default)]
+    pub allowed_projects: HashSet<String>,
 }
 
 impl McpConfiguration {
     pub fn new() -> Self {
         Self {
             disabled_tools: HashSet::new(),
+            allowed_projects: HashSet::new(),
         }
     }
 
@@ -25,6 +29,15 @@ impl McpConfiguration {
         !self.disabled_tools.contains(tool_name)
     }
 
+    /// Whether the project at `project_path` is inside one of the allowed paths
+    pub fn is_project_allowed(&self, project_path: &str) -> bool {
+        self.allowed_projects.is_empty()
+            || self
+                .allowed_projects
+                .iter()
+                .any(|allowed| Path::new(project_path).starts_with(allowed))
+    }
+
     pub fn save(&self, path: &PathBuf) -> Result<()> {
         let json = serde_json::to_string_pretty(self)?;
         fs::write(path, json)?;
@@ -134,6 +147,29 @@ mod tests {
         assert_eq!(config.disabled_tools.len(), new_config.disabled_tools.len());
     }
 
+    #[test]
+    fn test_is_project_allowed() {
+        let mut config = McpConfiguration::new();
+        assert!(config.is_project_allowed("/any/project"));
+
+        config.allowed_projects.insert("/work/allowed".to_string());
+        assert!(config.is_project_allowed("/work/allowed"));
+        assert!(config.is_project_allowed("/work/allowed/project"));
+        assert!(!config.is_project_allowed("/work/allowed-other"));
+        assert!(!config.is_project_allowed("/work/other"));
+    }
+
+    #[test]
+    fn test_read_mcp_configuration_without_allowed_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        fs::write(&config_path, r#"{"disabled_tools": ["tool1"]}"#).unwrap();
+
+        let config = read_mcp_configuration(config_path);
+        assert!(config.disabled_tools.contains("tool1"));
+        assert!(config.allowed_projects.is_empty());
+    }
+
     #[test]
     fn test_configuration_save_success() {
         let temp_dir = TempDir::new().unwrap();
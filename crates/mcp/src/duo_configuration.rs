@@ -1,12 +1,15 @@
 use anyhow::Result;
+use log::warn;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::env;
 use std::env::home_dir;
+use std::ffi::OsString;
 use std::fs;
-use std::path::{MAIN_SEPARATOR, PathBuf};
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
 
-use crate::MCP_NAME;
+use crate::{MCP_LOCAL_FILE, MCP_NAME};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(untagged)]
@@ -24,6 +27,8 @@ pub enum DuoMcpServer {
         url: String,
         #[serde(rename = "approvedTools", skip_serializing_if = "Option::is_none")]
         approved_tools: Option<ApprovedTools>,
+        #[serde(flatten)]
+        extra: Map<String, Value>,
     },
     Command {
         #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
@@ -32,7 +37,11 @@ pub enum DuoMcpServer {
         args: Vec<String>,
         #[serde(rename = "approvedTools", skip_serializing_if = "Option::is_none")]
         approved_tools: Option<ApprovedTools>,
+        #[serde(flatten)]
+        extra: Map<String, Value>,
     },
+    // Servers in a shape gkg doesn't know about are kept as they are
+    Other(Value),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -41,18 +50,25 @@ struct DuoMcpConfig {
     #[serde(skip)]
     path: PathBuf,
 
-    #[serde(rename = "mcpServers")]
-    mcp_servers: HashMap<String, DuoMcpServer>,
+    #[serde(rename = "mcpServers", default)]
+    mcp_servers: BTreeMap<String, DuoMcpServer>,
+
+    // Other top-level settings are written back untouched
+    #[serde(flatten)]
+    extra: Map<String, Value>,
 }
 
 impl DuoMcpConfig {
     fn new(path: PathBuf) -> Self {
         Self {
             path,
-            mcp_servers: HashMap::new(),
+            mcp_servers: BTreeMap::new(),
+            extra: Map::new(),
         }
     }
 
+    // A file that isn't a valid MCP configuration is copied aside before starting over,
+    // so saving never discards the user's settings.
     pub fn get_or_create(path: PathBuf) -> Result<Self> {
         if !path.exists() {
             fs::create_dir_all(path.parent().unwrap())?;
@@ -60,12 +76,18 @@ impl DuoMcpConfig {
         }
 
         let content = fs::read_to_string(path.clone())?;
-        let json: DuoMcpConfig = serde_json::from_str(&content)?;
-
-        Ok(Self {
-            path,
-            mcp_servers: json.mcp_servers,
-        })
+        match serde_json::from_str::<DuoMcpConfig>(&content) {
+            Ok(json) => Ok(Self { path, ..json }),
+            Err(e) => {
+                let backup_path = back_up_file(&path)?;
+                warn!(
+                    "Could not parse MCP configuration {}: {e}. Saved a copy to {} and starting from an empty configuration.",
+                    path.display(),
+                    backup_path.display()
+                );
+                Ok(Self::new(path))
+            }
+        }
     }
 
     pub fn add_server(mut self, name: String, server: DuoMcpServer) -> Self {
@@ -74,6 +96,10 @@ impl DuoMcpConfig {
         self
     }
 
+    pub fn remove_server(&mut self, name: &str) -> bool {
+        self.mcp_servers.remove(name).is_some()
+    }
+
     pub fn save(&self) -> Result<()> {
         let json = serde_json::to_string_pretty(self)?;
         fs::write(self.path.clone(), json)?;
@@ -89,41 +115,223 @@ pub fn add_local_http_server_to_mcp_config(mcp_config_path: PathBuf, port: u16)
     let server_url = format!("http://localhost:{port}/mcp/sse");
 
     // Check if knowledge graph server already exists
-    if let Some(DuoMcpServer::Url {
-        command_type,
-        url,
-        approved_tools,
-    }) = config.mcp_servers.get(MCP_NAME)
-    {
-        // If URL matches and approvedTools already exists (any value), nothing to do
-        if url.as_str() == server_url && approved_tools.is_some() && command_type.is_some() {
-            return Ok(());
-        }
+    let (approved_tools, extra) = match config.mcp_servers.remove(MCP_NAME) {
+        Some(DuoMcpServer::Url {
+            command_type,
+            url,
+            approved_tools,
+            extra,
+        }) => {
+            // If URL matches and approvedTools already exists (any value), nothing to do
+            if url == server_url && approved_tools.is_some() && command_type.is_some() {
+                return Ok(());
+            }
 
-        // If URL matches but approvedTools is missing, add it
-        if url.as_str() == server_url {
-            let server = DuoMcpServer::Url {
-                command_type: Some("sse".to_string()),
-                url: url.clone(),
-                approved_tools: Some(ApprovedTools::Bool(true)),
-            };
-            config.mcp_servers.insert(MCP_NAME.to_string(), server);
-            config.save()?;
-            return Ok(());
+            // Keep the user's tool approvals and any other settings of the entry
+            (approved_tools, extra)
         }
-    }
+        _ => (None, Map::new()),
+    };
 
-    // Server doesn't exist or URL doesn't match, create/update it
+    // Server doesn't exist or is outdated, create/update it
     let server = DuoMcpServer::Url {
         command_type: Some("sse".to_string()),
         url: server_url,
-        approved_tools: Some(ApprovedTools::Bool(true)),
+        approved_tools: approved_tools.or(Some(ApprovedTools::Bool(true))),
+        extra,
     };
     config.add_server(MCP_NAME.to_string(), server).save()?;
 
     Ok(())
 }
 
+// Copies the file to the first free `<name>.bak`, `<name>.bak.1`, ... next to it.
+fn back_up_file(path: &Path) -> Result<PathBuf> {
+    let mut backup_name = OsString::from(path.as_os_str());
+    backup_name.push(".bak");
+    let mut backup_path = PathBuf::from(&backup_name);
+
+    let mut suffix = 1;
+    while backup_path.exists() {
+        let mut numbered_name = backup_name.clone();
+        numbered_name.push(format!(".{suffix}"));
+        backup_path = PathBuf::from(numbered_name);
+        suffix += 1;
+    }
+
+    fs::copy(path, &backup_path)?;
+    Ok(backup_path)
+}
+
+// Helper function that removes the local HTTP server from the MCP configuration.
+// Returns false when the file doesn't exist or has no knowledge graph entry.
+pub fn remove_local_http_server_from_mcp_config(mcp_config_path: PathBuf) -> Result<bool> {
+    let expanded_path = naively_expand_shell_path(mcp_config_path)?;
+    if !expanded_path.exists() {
+        return Ok(false);
+    }
+
+    let mut config = DuoMcpConfig::get_or_create(expanded_path)?;
+    if !config.remove_server(MCP_NAME) {
+        return Ok(false);
+    }
+    config.save()?;
+
+    Ok(true)
+}
+
+// Location of the user-level GitLab Duo MCP configuration (~/.gitlab/duo/mcp.json).
+pub fn default_duo_mcp_config_path() -> Result<PathBuf> {
+    let home = home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory."))?;
+    Ok(home.join(".gitlab").join("duo").join(MCP_LOCAL_FILE))
+}
+
+/// Result of a single validation step of the MCP configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpConfigCheck {
+    pub description: String,
+    pub passed: bool,
+    /// How to fix the problem when the check failed.
+    pub hint: Option<String>,
+}
+
+impl McpConfigCheck {
+    pub fn pass(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            passed: true,
+            hint: None,
+        }
+    }
+
+    pub fn fail(description: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            passed: false,
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct McpConfigDiagnostics {
+    /// Checks in the order they ran. Checks stop at the first failure since the
+    /// remaining ones depend on it.
+    pub checks: Vec<McpConfigCheck>,
+    /// URL of the knowledge graph server found in the configuration.
+    pub server_url: Option<String>,
+}
+
+// Validates the knowledge graph entry of an MCP configuration file without modifying it.
+pub fn diagnose_mcp_config(mcp_config_path: PathBuf) -> McpConfigDiagnostics {
+    let mut diagnostics = McpConfigDiagnostics::default();
+    let register_hint = format!(
+        "Run `gkg mcp register --path {}` to add the gkg server.",
+        mcp_config_path.display()
+    );
+
+    let path = match naively_expand_shell_path(mcp_config_path.clone()) {
+        Ok(path) => path,
+        Err(e) => {
+            diagnostics.checks.push(McpConfigCheck::fail(
+                format!("Resolve {}", mcp_config_path.display()),
+                format!("{e}. Pass an absolute path with --path."),
+            ));
+            return diagnostics;
+        }
+    };
+
+    let description = format!("Configuration file exists at {}", path.display());
+    if !path.is_file() {
+        diagnostics
+            .checks
+            .push(McpConfigCheck::fail(description, register_hint));
+        return diagnostics;
+    }
+    diagnostics.checks.push(McpConfigCheck::pass(description));
+
+    let description = "Configuration file is valid JSON";
+    let json = match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<Value>(&content)?))
+    {
+        Ok(json) => json,
+        Err(e) => {
+            diagnostics.checks.push(McpConfigCheck::fail(
+                description,
+                format!(
+                    "{e}. Fix the file, or run `gkg mcp register` to back it up and write a new one."
+                ),
+            ));
+            return diagnostics;
+        }
+    };
+    diagnostics.checks.push(McpConfigCheck::pass(description));
+
+    let description = "`mcpServers` is an object";
+    let Some(servers) = json.get("mcpServers").and_then(Value::as_object) else {
+        diagnostics
+            .checks
+            .push(McpConfigCheck::fail(description, register_hint));
+        return diagnostics;
+    };
+    diagnostics.checks.push(McpConfigCheck::pass(description));
+
+    let description = format!("`mcpServers` contains a `{MCP_NAME}` entry");
+    let Some(server) = servers.get(MCP_NAME) else {
+        diagnostics
+            .checks
+            .push(McpConfigCheck::fail(description, register_hint));
+        return diagnostics;
+    };
+    diagnostics.checks.push(McpConfigCheck::pass(description));
+
+    let description = format!("`{MCP_NAME}` has a `url`");
+    let Some(url) = server.get("url").and_then(Value::as_str) else {
+        diagnostics
+            .checks
+            .push(McpConfigCheck::fail(description, register_hint));
+        return diagnostics;
+    };
+    diagnostics.checks.push(McpConfigCheck::pass(description));
+    diagnostics.server_url = Some(url.to_string());
+
+    let description = format!("`{MCP_NAME}` uses the `sse` transport");
+    if server.get("type").and_then(Value::as_str) == Some("sse") {
+        diagnostics.checks.push(McpConfigCheck::pass(description));
+    } else {
+        diagnostics.checks.push(McpConfigCheck::fail(
+            description,
+            "Set `\"type\": \"sse\"` or run `gkg mcp register` to update the entry.",
+        ));
+        return diagnostics;
+    }
+
+    let description = format!("`{MCP_NAME}` has `approvedTools`");
+    if serde_json::from_value::<ApprovedTools>(
+        server.get("approvedTools").cloned().unwrap_or_default(),
+    )
+    .is_ok()
+    {
+        diagnostics.checks.push(McpConfigCheck::pass(description));
+    } else {
+        diagnostics.checks.push(McpConfigCheck::fail(
+            description,
+            "Set `approvedTools` to `true` or a list of tool names, or run `gkg mcp register` to add it.",
+        ));
+    }
+
+    diagnostics
+}
+
+// Port of a knowledge graph server URL written by `add_local_http_server_to_mcp_config`.
+pub fn local_http_server_port(url: &str) -> Option<u16> {
+    let rest = url
+        .strip_prefix("http://localhost:")
+        .or_else(|| url.strip_prefix("http://127.0.0.1:"))?;
+    rest.split('/').next()?.parse().ok()
+}
+
 // Helper function that expands the shell variables in the path.
 fn naively_expand_shell_path(path: PathBuf) -> Result<PathBuf> {
     #[cfg(unix)]
@@ -222,6 +430,7 @@ mod tests {
                     command: "gitlab".to_string(),
                     args: vec!["mcp".to_string(), "server".to_string()],
                     approved_tools: None,
+                    extra: Map::new(),
                 },
             )
             .save()
@@ -342,6 +551,44 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[test]
+    fn test_remove_local_http_server_keeps_other_servers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("mcp.json");
+
+        DuoMcpConfig::get_or_create(mcp_config_path.clone())
+            .unwrap()
+            .add_server(
+                "gitlab".to_string(),
+                DuoMcpServer::Command {
+                    command_type: Some("stdio".to_string()),
+                    command: "gitlab".to_string(),
+                    args: vec!["mcp".to_string(), "server".to_string()],
+                    approved_tools: None,
+                    extra: Map::new(),
+                },
+            )
+            .save()
+            .unwrap();
+        add_local_http_server_to_mcp_config(mcp_config_path.clone(), 8080).unwrap();
+
+        assert!(remove_local_http_server_from_mcp_config(mcp_config_path.clone()).unwrap());
+        assert!(!remove_local_http_server_from_mcp_config(mcp_config_path.clone()).unwrap());
+
+        let config = DuoMcpConfig::get_or_create(mcp_config_path.clone()).unwrap();
+        assert_eq!(config.mcp_servers.len(), 1);
+        assert!(config.mcp_servers.contains_key("gitlab"));
+    }
+
+    #[test]
+    fn test_remove_local_http_server_without_config_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("duo").join("mcp.json");
+
+        assert!(!remove_local_http_server_from_mcp_config(mcp_config_path.clone()).unwrap());
+        assert!(!mcp_config_path.exists());
+    }
+
     #[test]
     fn test_expand_tilde_path() {
         let home_dir = home_dir().unwrap();
@@ -546,4 +793,137 @@ mod tests {
 
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn test_preserves_unknown_fields() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("mcp.json");
+
+        let fixture_json = format!(
+            r#"{{
+            "inputs": [{{ "id": "token", "type": "promptString" }}],
+            "mcpServers": {{
+                "gitlab": {{
+                    "command": "gitlab",
+                    "args": ["mcp", "server"],
+                    "env": {{ "GITLAB_TOKEN": "secret" }}
+                }},
+                "remote": {{
+                    "serverUrl": "https://example.com/mcp"
+                }},
+                "{MCP_NAME}": {{
+                    "type": "sse",
+                    "url": "http://localhost:8080/mcp/sse",
+                    "approvedTools": ["search_codebase_definitions"],
+                    "timeout": 30
+                }}
+            }}
+        }}"#
+        );
+        fs::write(mcp_config_path.clone(), &fixture_json).unwrap();
+        let before: serde_json::Value = serde_json::from_str(&fixture_json).unwrap();
+
+        add_local_http_server_to_mcp_config(mcp_config_path.clone(), 9090).unwrap();
+
+        let content = fs::read_to_string(mcp_config_path.clone()).unwrap();
+        let after: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(after["inputs"], before["inputs"]);
+        assert_eq!(
+            after["mcpServers"]["gitlab"],
+            before["mcpServers"]["gitlab"]
+        );
+        assert_eq!(
+            after["mcpServers"]["remote"],
+            before["mcpServers"]["remote"]
+        );
+
+        let server = &after["mcpServers"][MCP_NAME];
+        assert_eq!(server["url"], "http://localhost:9090/mcp/sse");
+        assert_eq!(server["timeout"], 30);
+        assert_eq!(
+            server["approvedTools"],
+            before["mcpServers"][MCP_NAME]["approvedTools"]
+        );
+    }
+
+    #[test]
+    fn test_backs_up_malformed_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("mcp.json");
+        let malformed = r#"{ "mcpServers": { "gitlab": "#;
+        fs::write(mcp_config_path.clone(), malformed).unwrap();
+        fs::write(temp_dir.path().join("mcp.json.bak"), "older backup").unwrap();
+
+        add_local_http_server_to_mcp_config(mcp_config_path.clone(), 8080).unwrap();
+
+        let backup = fs::read_to_string(temp_dir.path().join("mcp.json.bak.1")).unwrap();
+        assert_eq!(backup, malformed);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("mcp.json.bak")).unwrap(),
+            "older backup"
+        );
+
+        let config = DuoMcpConfig::get_or_create(mcp_config_path.clone()).unwrap();
+        assert_eq!(config.mcp_servers.len(), 1);
+        check_http_server_is_added_to_existing_config(
+            config.mcp_servers.get(MCP_NAME).unwrap(),
+            8080,
+        );
+    }
+
+    #[test]
+    fn test_diagnose_registered_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("mcp.json");
+        add_local_http_server_to_mcp_config(mcp_config_path.clone(), 8080).unwrap();
+
+        let diagnostics = diagnose_mcp_config(mcp_config_path);
+
+        assert!(diagnostics.checks.iter().all(|check| check.passed));
+        assert_eq!(
+            diagnostics.server_url.as_deref(),
+            Some("http://localhost:8080/mcp/sse")
+        );
+        assert_eq!(
+            local_http_server_port(diagnostics.server_url.as_deref().unwrap()),
+            Some(8080)
+        );
+    }
+
+    #[test]
+    fn test_diagnose_reports_first_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mcp_config_path = temp_dir.path().join("mcp.json");
+
+        let diagnostics = diagnose_mcp_config(mcp_config_path.clone());
+        assert_eq!(diagnostics.checks.len(), 1);
+        assert!(!diagnostics.checks[0].passed);
+
+        fs::write(
+            &mcp_config_path,
+            r#"{ "mcpServers": { "gitlab": { "command": "gitlab" } } }"#,
+        )
+        .unwrap();
+        let diagnostics = diagnose_mcp_config(mcp_config_path.clone());
+        let failed: Vec<_> = diagnostics
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .collect();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].description.contains(MCP_NAME));
+        assert!(
+            failed[0]
+                .hint
+                .as_ref()
+                .unwrap()
+                .contains("gkg mcp register")
+        );
+        assert!(diagnostics.server_url.is_none());
+
+        fs::write(&mcp_config_path, "{ not json").unwrap();
+        let diagnostics = diagnose_mcp_config(mcp_config_path);
+        assert!(!diagnostics.checks.last().unwrap().passed);
+        assert_eq!(diagnostics.checks.len(), 2);
+    }
 }
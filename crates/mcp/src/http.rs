@@ -1,3 +1,4 @@
+use crate::indexing_jobs::IndexingJobQueue;
 use crate::{configuration::McpConfiguration, service::DefaultMcpService};
 use database::kuzu::database::KuzuDatabase;
 use database::querying::types::QueryingService;
@@ -14,6 +15,7 @@ pub fn mcp_http_service(
     database: Arc<KuzuDatabase>,
     event_bus: Arc<EventBus>,
     configuration: Arc<McpConfiguration>,
+    job_queue: Arc<dyn IndexingJobQueue>,
 ) -> StreamableHttpService<DefaultMcpService> {
     StreamableHttpService::new(
         move || {
@@ -23,6 +25,7 @@ pub fn mcp_http_service(
                 Arc::clone(&database),
                 Arc::clone(&event_bus),
                 Arc::clone(&configuration),
+                Arc::clone(&job_queue),
             ))
         },
         Arc::new(LocalSessionManager::default()),
@@ -0,0 +1,52 @@
+use anyhow::Result;
+
+/// State of a background indexing job, as reported to MCP clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexingJobState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl IndexingJobState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexingJobStatus {
+    pub job_id: String,
+    pub state: IndexingJobState,
+    /// RFC 3339 timestamps of when the job started and finished running.
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Queue that runs indexing jobs in the background, so MCP tools can return a job ID
+/// right away instead of blocking until a large project is indexed.
+#[async_trait::async_trait]
+pub trait IndexingJobQueue: Send + Sync {
+    /// Queues a full indexing of `project_path` and returns the ID of the job.
+    async fn dispatch_project_indexing(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+    ) -> Result<String>;
+
+    /// Status of a job that is queued, running or recently finished.
+    fn indexing_job_status(&self, job_id: &str) -> Option<IndexingJobStatus>;
+}
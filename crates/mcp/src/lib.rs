@@ -1,6 +1,7 @@
 pub mod configuration;
 pub mod duo_configuration;
 pub mod http;
+pub mod indexing_jobs;
 pub mod service;
 pub mod sse;
 pub mod tools;
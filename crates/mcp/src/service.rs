@@ -1,4 +1,5 @@
 use crate::configuration::McpConfiguration;
+use crate::indexing_jobs::IndexingJobQueue;
 use crate::tools::AvailableToolsService;
 use database::kuzu::database::KuzuDatabase;
 use database::querying::types::QueryingService;
@@ -22,6 +23,7 @@ impl DefaultMcpService {
         database: Arc<KuzuDatabase>,
         event_bus: Arc<EventBus>,
         configuration: Arc<McpConfiguration>,
+        job_queue: Arc<dyn IndexingJobQueue>,
     ) -> Self {
         Self {
             available_tools_service: AvailableToolsService::new(
@@ -30,6 +32,7 @@ impl DefaultMcpService {
                 database,
                 event_bus,
                 configuration,
+                job_queue,
             ),
         }
     }
@@ -1,3 +1,4 @@
+use crate::indexing_jobs::IndexingJobQueue;
 use crate::{configuration::McpConfiguration, service::DefaultMcpService};
 use axum::Router;
 use database::kuzu::database::KuzuDatabase;
@@ -15,6 +16,7 @@ pub fn mcp_sse_router(
     database: Arc<KuzuDatabase>,
     event_bus: Arc<EventBus>,
     configuration: Arc<McpConfiguration>,
+    job_queue: Arc<dyn IndexingJobQueue>,
 ) -> (Router, CancellationToken) {
     let (sse_server, router) = SseServer::new(SseServerConfig {
         bind,
@@ -33,6 +35,7 @@ pub fn mcp_sse_router(
             Arc::clone(&database),
             Arc::clone(&event_bus),
             Arc::clone(&configuration),
+            Arc::clone(&job_queue),
         )
     });
 
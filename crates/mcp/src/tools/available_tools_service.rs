@@ -2,11 +2,17 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::configuration::McpConfiguration;
+use crate::indexing_jobs::IndexingJobQueue;
 use crate::tools::INDEX_PROJECT_TOOL_NAME;
 use crate::tools::SEARCH_CODEBASE_DEFINITIONS_TOOL_NAME;
 use crate::tools::SearchCodebaseDefinitionsTool;
+use crate::tools::find_call_cycles::{FIND_CALL_CYCLES_TOOL_NAME, FindCallCyclesTool};
+use crate::tools::find_implementations::{FIND_IMPLEMENTATIONS_TOOL_NAME, FindImplementationsTool};
 use crate::tools::get_definition::GetDefinitionTool;
 use crate::tools::get_definition::constants::GET_DEFINITION_TOOL_NAME;
+use crate::tools::get_definition_source::GET_DEFINITION_SOURCE_TOOL_NAME;
+use crate::tools::get_definition_source::tool::GetDefinitionSourceTool;
+use crate::tools::get_index_status::{GET_INDEX_STATUS_TOOL_NAME, GetIndexStatusTool};
 use crate::tools::get_references::GET_REFERENCES_TOOL_NAME;
 use crate::tools::get_references::tool::GetReferencesTool;
 use crate::tools::import_usage::{IMPORT_USAGE_TOOL_NAME, ImportUsageTool};
@@ -15,7 +21,9 @@ use crate::tools::list_projects::{LIST_PROJECTS_TOOL_NAME, ListProjectsTool};
 use crate::tools::read_definitions::READ_DEFINITIONS_TOOL_NAME;
 use crate::tools::read_definitions::tool::ReadDefinitionsTool;
 use crate::tools::repo_map::{REPO_MAP_TOOL_NAME, RepoMapTool};
+use crate::tools::search_file_contents::{SEARCH_FILE_CONTENTS_TOOL_NAME, SearchFileContentsTool};
 use crate::tools::types::KnowledgeGraphTool;
+use crate::tools::utils::check_project_access;
 use database::kuzu::database::KuzuDatabase;
 use database::querying::QueryingService;
 use event_bus::EventBus;
@@ -26,6 +34,8 @@ use workspace_manager::WorkspaceManager;
 
 pub struct AvailableToolsService {
     tools: HashMap<String, Box<dyn KnowledgeGraphTool>>,
+    workspace_manager: Arc<WorkspaceManager>,
+    configuration: Arc<McpConfiguration>,
 }
 
 impl AvailableToolsService {
@@ -35,13 +45,17 @@ impl AvailableToolsService {
         database: Arc<KuzuDatabase>,
         event_bus: Arc<EventBus>,
         configuration: Arc<McpConfiguration>,
+        job_queue: Arc<dyn IndexingJobQueue>,
     ) -> Self {
         let mut tools: HashMap<String, Box<dyn KnowledgeGraphTool>> = HashMap::new();
 
         if configuration.is_tool_enabled(LIST_PROJECTS_TOOL_NAME) {
             tools.insert(
                 LIST_PROJECTS_TOOL_NAME.to_string(),
-                Box::new(ListProjectsTool::new(workspace_manager.clone())),
+                Box::new(ListProjectsTool::new(
+                    workspace_manager.clone(),
+                    configuration.clone(),
+                )),
             );
         }
 
@@ -58,11 +72,21 @@ impl AvailableToolsService {
         if configuration.is_tool_enabled(INDEX_PROJECT_TOOL_NAME) {
             tools.insert(
                 INDEX_PROJECT_TOOL_NAME.to_string(),
-                Box::new(IndexProjectTool::new(
-                    database.clone(),
-                    workspace_manager.clone(),
-                    event_bus.clone(),
-                )),
+                Box::new(
+                    IndexProjectTool::new(
+                        database.clone(),
+                        workspace_manager.clone(),
+                        event_bus.clone(),
+                    )
+                    .with_job_queue(job_queue.clone()),
+                ),
+            );
+        }
+
+        if configuration.is_tool_enabled(GET_INDEX_STATUS_TOOL_NAME) {
+            tools.insert(
+                GET_INDEX_STATUS_TOOL_NAME.to_string(),
+                Box::new(GetIndexStatusTool::new(job_queue.clone())),
             );
         }
 
@@ -96,6 +120,36 @@ impl AvailableToolsService {
             );
         }
 
+        if configuration.is_tool_enabled(FIND_CALL_CYCLES_TOOL_NAME) {
+            tools.insert(
+                FIND_CALL_CYCLES_TOOL_NAME.to_string(),
+                Box::new(FindCallCyclesTool::new(
+                    database.clone(),
+                    workspace_manager.clone(),
+                )),
+            );
+        }
+
+        if configuration.is_tool_enabled(FIND_IMPLEMENTATIONS_TOOL_NAME) {
+            tools.insert(
+                FIND_IMPLEMENTATIONS_TOOL_NAME.to_string(),
+                Box::new(FindImplementationsTool::new(
+                    database.clone(),
+                    workspace_manager.clone(),
+                )),
+            );
+        }
+
+        if configuration.is_tool_enabled(SEARCH_FILE_CONTENTS_TOOL_NAME) {
+            tools.insert(
+                SEARCH_FILE_CONTENTS_TOOL_NAME.to_string(),
+                Box::new(SearchFileContentsTool::new(
+                    database.clone(),
+                    workspace_manager.clone(),
+                )),
+            );
+        }
+
         if configuration.is_tool_enabled(READ_DEFINITIONS_TOOL_NAME) {
             tools.insert(
                 READ_DEFINITIONS_TOOL_NAME.to_string(),
@@ -106,6 +160,16 @@ impl AvailableToolsService {
             );
         }
 
+        if configuration.is_tool_enabled(GET_DEFINITION_SOURCE_TOOL_NAME) {
+            tools.insert(
+                GET_DEFINITION_SOURCE_TOOL_NAME.to_string(),
+                Box::new(GetDefinitionSourceTool::new(
+                    query_service.clone(),
+                    workspace_manager.clone(),
+                )),
+            );
+        }
+
         if configuration.is_tool_enabled(REPO_MAP_TOOL_NAME) {
             tools.insert(
                 REPO_MAP_TOOL_NAME.to_string(),
@@ -116,7 +180,11 @@ impl AvailableToolsService {
             );
         }
 
-        Self { tools }
+        Self {
+            tools,
+            workspace_manager,
+            configuration,
+        }
     }
 
     pub fn get_available_tools(&self) -> Vec<Tool> {
@@ -128,14 +196,14 @@ impl AvailableToolsService {
         tool_name: &str,
         params: JsonObject,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
-        self.tools
-            .get(tool_name)
-            .ok_or(rmcp::ErrorData::new(
-                rmcp::model::ErrorCode::INVALID_REQUEST,
-                format!("Tool {tool_name} not found."),
-                None,
-            ))?
-            .call(params)
-            .await
+        let tool = self.tools.get(tool_name).ok_or(rmcp::ErrorData::new(
+            rmcp::model::ErrorCode::INVALID_REQUEST,
+            format!("Tool {tool_name} not found."),
+            None,
+        ))?;
+
+        check_project_access(&self.configuration, &self.workspace_manager, &params)?;
+
+        tool.call(params).await
     }
 }
@@ -1,8 +1,38 @@
 use futures::future::join_all;
 use std::io::{self};
+use std::path::{Path, PathBuf};
 use tokio::fs::File as AsyncFile;
 use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
 
+/// Resolves `requested_path`, absolute or relative to `project_root`, to a canonical path and
+/// verifies it stays within the project root.
+///
+/// Symlinks and `..` components are resolved before the check, so neither can be used to
+/// escape the project. Fails with [`io::ErrorKind::PermissionDenied`] when the path is outside
+/// the project, or with the canonicalization error when it does not exist.
+pub fn resolve_within_project(project_root: &Path, requested_path: &str) -> io::Result<PathBuf> {
+    let project_root = project_root.canonicalize()?;
+    let requested = Path::new(requested_path);
+    let candidate = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        project_root.join(requested)
+    };
+
+    let resolved = candidate.canonicalize()?;
+    if !resolved.starts_with(&project_root) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "Path '{requested_path}' is outside of the project root {}",
+                project_root.display()
+            ),
+        ));
+    }
+
+    Ok(resolved)
+}
+
 /// Returns 1-indexed line numbers in the file whose trimmed content equals the given trimmed line.
 /// Leading and trailing whitespace is ignored for comparison.
 pub async fn find_matching_line_numbers(path: &str, line: &str) -> io::Result<Vec<usize>> {
@@ -82,6 +112,196 @@ pub async fn read_file_chunks(
     Ok(results)
 }
 
+/// Maximum number of lines returned by [`read_lines`], to protect the model context size.
+pub const MAX_READ_LINES: usize = 2_000;
+
+/// A line range read from a file by [`read_lines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineSlice {
+    /// The requested lines, each followed by a newline.
+    pub content: String,
+    /// 1-indexed first line of `content`.
+    pub start_line: usize,
+    /// 1-indexed last line of `content`; may be lower than requested at EOF or when capped.
+    pub end_line: usize,
+    /// Number of lines in the whole file.
+    pub total_lines: usize,
+}
+
+/// Reads the 1-indexed inclusive line range `start..=end` of a file.
+///
+/// The file is streamed, so only the requested lines are kept in memory. At most
+/// [`MAX_READ_LINES`] lines are returned and `end` is clamped to the end of the file.
+/// Fails when the range is invalid or `start` is past the end of the file.
+pub async fn read_lines(path: &str, start: usize, end: usize) -> io::Result<LineSlice> {
+    if start == 0 || end < start {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Invalid line range: start must be >= 1 and end must be >= start",
+        ));
+    }
+
+    let end = end.min(start.saturating_add(MAX_READ_LINES - 1));
+    let file = AsyncFile::open(path).await?;
+    let reader = AsyncBufReader::new(file);
+    let mut lines = reader.lines();
+
+    let mut content = String::new();
+    let mut total_lines = 0;
+    while let Some(line) = lines.next_line().await? {
+        total_lines += 1;
+        if (start..=end).contains(&total_lines) {
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+
+    if start > total_lines {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Start line {start} exceeds file length (file has {total_lines} lines)"),
+        ));
+    }
+
+    Ok(LineSlice {
+        content,
+        start_line: start,
+        end_line: end.min(total_lines),
+        total_lines,
+    })
+}
+
+/// Returns the lines `start_line..=end_line` (1-indexed) of `content`, extended by up to
+/// `context_lines` lines on each side, together with the 1-indexed range actually returned.
+///
+/// `end_line` is clamped to the last line of `content`. Returns `None` when the range is
+/// invalid or starts past the end of `content`.
+pub fn slice_lines_with_context(
+    content: &str,
+    start_line: usize,
+    end_line: usize,
+    context_lines: usize,
+) -> Option<(String, usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    if start_line == 0 || end_line < start_line || start_line > lines.len() {
+        return None;
+    }
+
+    let first = start_line.saturating_sub(context_lines).max(1);
+    let last = end_line.saturating_add(context_lines).min(lines.len());
+
+    let mut result = String::new();
+    for line in &lines[first - 1..last] {
+        result.push_str(line);
+        result.push('\n');
+    }
+
+    Some((result, first, last))
+}
+
+/// Location of a definition as stored in the graph, used to extract well-formed snippets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefinitionSpan {
+    /// 1-indexed inclusive line range of the definition.
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Byte range of the definition node, `None` when it was not stored.
+    pub byte_range: Option<(usize, usize)>,
+}
+
+impl DefinitionSpan {
+    /// Builds a span from a 1-indexed line range and the raw byte offsets stored in the graph.
+    /// Empty or negative byte ranges are treated as missing.
+    pub fn new(start_line: usize, end_line: usize, start_byte: i64, end_byte: i64) -> Self {
+        let byte_range = match (usize::try_from(start_byte), usize::try_from(end_byte)) {
+            (Ok(start), Ok(end)) if start < end => Some((start, end)),
+            _ => None,
+        };
+
+        Self {
+            start_line,
+            end_line,
+            byte_range,
+        }
+    }
+}
+
+/// Returns the complete syntactic span of a definition: every line touched by its stored
+/// byte range, so multi-line signatures and closing delimiters are never cut off.
+///
+/// Falls back to the plain line range when the byte range is missing, out of bounds or no
+/// longer starts on the stored line (e.g. the file changed since indexing). Returns `None`
+/// when neither range fits the content.
+pub fn extract_definition_snippet(content: &str, span: &DefinitionSpan) -> Option<String> {
+    if let Some(snippet) = extract_syntactic_span(content, span) {
+        return Some(snippet);
+    }
+
+    slice_lines_with_context(content, span.start_line, span.end_line, 0)
+        .filter(|(_, _, last)| *last == span.end_line)
+        .map(|(snippet, _, _)| snippet)
+}
+
+fn extract_syntactic_span(content: &str, span: &DefinitionSpan) -> Option<String> {
+    let (start, end) = span.byte_range?;
+    content.get(start..end)?;
+
+    if content[..start].matches('\n').count() + 1 != span.start_line {
+        return None;
+    }
+
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[end..]
+        .find('\n')
+        .map_or(content.len(), |i| end + i + 1);
+
+    let mut snippet = content[line_start..line_end].to_string();
+    if !snippet.ends_with('\n') {
+        snippet.push('\n');
+    }
+
+    Some(snippet)
+}
+
+/// Reads the snippet of each `(path, span)` pair concurrently with
+/// [`extract_definition_snippet`], returning per-snippet results in input order.
+pub async fn read_definition_snippets(
+    snippets: Vec<(String, DefinitionSpan)>,
+) -> io::Result<Vec<io::Result<String>>> {
+    let snippets_len = snippets.len();
+    let mut tasks = Vec::with_capacity(snippets_len);
+
+    for (path, span) in snippets {
+        let task = tokio::spawn(async move {
+            let content = tokio::fs::read_to_string(&path).await?;
+            extract_definition_snippet(&content, &span).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Definition range L{}-{} does not fit {path}",
+                        span.start_line, span.end_line
+                    ),
+                )
+            })
+        });
+        tasks.push(task);
+    }
+
+    let task_results = join_all(tasks).await;
+
+    let mut results = Vec::with_capacity(snippets_len);
+    for task_result in task_results {
+        match task_result {
+            Ok(snippet_result) => results.push(snippet_result),
+            Err(join_error) => results.push(Err(io::Error::other(format!(
+                "Task join error: {join_error}"
+            )))),
+        }
+    }
+
+    Ok(results)
+}
+
 async fn read_file_chunk_async(
     path: &str,
     start_line: usize,
@@ -148,6 +368,63 @@ mod tests {
         file
     }
 
+    fn setup_project() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::TempDir::new().expect("create temp dir");
+        let project_root = tmp.path().join("project");
+        std::fs::create_dir_all(project_root.join("src")).unwrap();
+        std::fs::write(project_root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(tmp.path().join("secret.txt"), "secret\n").unwrap();
+        (tmp, project_root)
+    }
+
+    #[test]
+    fn resolve_within_project_accepts_paths_inside_project() {
+        let (_tmp, project_root) = setup_project();
+        let expected = project_root.join("src/main.rs").canonicalize().unwrap();
+
+        assert_eq!(
+            resolve_within_project(&project_root, "src/main.rs").unwrap(),
+            expected
+        );
+        assert_eq!(
+            resolve_within_project(&project_root, "src/../src/main.rs").unwrap(),
+            expected
+        );
+        assert_eq!(
+            resolve_within_project(&project_root, expected.to_str().unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn resolve_within_project_rejects_parent_traversal() {
+        let (_tmp, project_root) = setup_project();
+        let err = resolve_within_project(&project_root, "../secret.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn resolve_within_project_rejects_absolute_paths_outside_project() {
+        let (tmp, project_root) = setup_project();
+        let outside = tmp.path().join("secret.txt");
+        let err = resolve_within_project(&project_root, outside.to_str().unwrap()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_within_project_rejects_symlink_escapes() {
+        let (tmp, project_root) = setup_project();
+        std::os::unix::fs::symlink(tmp.path().join("secret.txt"), project_root.join("link.txt"))
+            .unwrap();
+        std::os::unix::fs::symlink(tmp.path(), project_root.join("linked_dir")).unwrap();
+
+        let err = resolve_within_project(&project_root, "link.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = resolve_within_project(&project_root, "linked_dir/secret.txt").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
     #[tokio::test]
     async fn reads_single_chunk_ok() {
         let file = write_temp_file("line1\nline2\nline3\nline4\n");
@@ -247,6 +524,99 @@ mod tests {
         assert_eq!(result, vec![1, 3]);
     }
 
+    #[tokio::test]
+    async fn read_lines_returns_range_and_total_line_count() {
+        let file = write_temp_file("1\n2\n3\n4\n5\n");
+        let path = file.path().to_string_lossy().to_string();
+
+        let slice = read_lines(&path, 2, 3).await.unwrap();
+        assert_eq!(slice.content, "2\n3\n");
+        assert_eq!((slice.start_line, slice.end_line), (2, 3));
+        assert_eq!(slice.total_lines, 5);
+
+        let slice = read_lines(&path, 4, 10).await.unwrap();
+        assert_eq!(slice.content, "4\n5\n");
+        assert_eq!(slice.end_line, 5);
+
+        let err = read_lines(&path, 6, 7).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(read_lines(&path, 0, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_lines_caps_returned_lines() {
+        let contents: String = (1..=MAX_READ_LINES + 10)
+            .map(|i| format!("{i}\n"))
+            .collect();
+        let file = write_temp_file(&contents);
+        let path = file.path().to_string_lossy().to_string();
+
+        let slice = read_lines(&path, 1, usize::MAX).await.unwrap();
+        assert_eq!(slice.end_line, MAX_READ_LINES);
+        assert_eq!(slice.content.lines().count(), MAX_READ_LINES);
+        assert_eq!(slice.total_lines, MAX_READ_LINES + 10);
+    }
+
+    #[test]
+    fn extracts_full_syntactic_span_of_definition() {
+        let content = "class A {\n    fn foo(\n        a: i32,\n    ) {\n    }\n}\n";
+        let start = content.find("fn foo").unwrap() as i64;
+        let end = (content.find("    }\n}").unwrap() + 5) as i64;
+
+        // The stored line range stops at the signature, the byte range covers the body
+        let span = DefinitionSpan::new(2, 3, start, end);
+        assert_eq!(
+            extract_definition_snippet(content, &span).unwrap(),
+            "    fn foo(\n        a: i32,\n    ) {\n    }\n"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_line_range_when_byte_range_is_incomplete() {
+        let content = "a\nb\nc\n";
+
+        let missing = DefinitionSpan::new(2, 3, 0, 0);
+        assert_eq!(missing.byte_range, None);
+        assert_eq!(
+            extract_definition_snippet(content, &missing).unwrap(),
+            "b\nc\n"
+        );
+
+        let out_of_bounds = DefinitionSpan::new(2, 2, 2, 100);
+        assert_eq!(
+            extract_definition_snippet(content, &out_of_bounds).unwrap(),
+            "b\n"
+        );
+
+        let stale = DefinitionSpan::new(3, 3, 0, 1);
+        assert_eq!(extract_definition_snippet(content, &stale).unwrap(), "c\n");
+
+        assert_eq!(
+            extract_definition_snippet(content, &DefinitionSpan::new(3, 9, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn slices_lines_with_clamped_context() {
+        let content = "1\n2\n3\n4\n5\n";
+
+        assert_eq!(
+            slice_lines_with_context(content, 3, 3, 1),
+            Some(("2\n3\n4\n".to_string(), 2, 4))
+        );
+        assert_eq!(
+            slice_lines_with_context(content, 1, 5, 10),
+            Some(("1\n2\n3\n4\n5\n".to_string(), 1, 5))
+        );
+        assert_eq!(
+            slice_lines_with_context(content, 4, 9, 0),
+            Some(("4\n5\n".to_string(), 4, 5))
+        );
+        assert_eq!(slice_lines_with_context(content, 6, 7, 2), None);
+        assert_eq!(slice_lines_with_context(content, 0, 1, 2), None);
+    }
+
     #[test]
     fn finds_matching_line_numbers_sync_ignoring_whitespace() {}
 }
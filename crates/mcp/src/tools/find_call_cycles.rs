@@ -0,0 +1,286 @@
+use std::{borrow::Cow, path::Path, sync::Arc};
+
+use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::DefinitionNodeFromKuzu;
+use rmcp::model::{CallToolResult, Content, ErrorCode, JsonObject, Tool, object};
+use serde_json::json;
+use workspace_manager::WorkspaceManager;
+
+use crate::tools::types::{KnowledgeGraphTool, KnowledgeGraphToolInput};
+use crate::tools::utils::database_error;
+use crate::tools::xml::{ToXml, XmlBuilder};
+
+pub const FIND_CALL_CYCLES_TOOL_NAME: &str = "find_call_cycles";
+const FIND_CALL_CYCLES_TOOL_DESCRIPTION: &str = r#"Find recursive and cyclic call chains in a project.
+
+Behavior:
+- Returns groups of definitions that call each other in a loop, directly or through other definitions, and definitions that call themselves.
+- The largest cycles are listed first. Only calls resolved by the Knowledge Graph are considered.
+
+When to use:
+- During code review, to spot unintended recursion or tightly coupled definitions.
+- Before refactoring, to find definitions that cannot be changed in isolation.
+
+Example:
+Call:
+{ "project_absolute_path": "/abs/path/to/project", "max_cycles": 10 }
+"#;
+
+const DEFAULT_MAX_CYCLES: usize = 20;
+const MAX_CYCLES_LIMIT: usize = 100;
+
+pub struct FindCallCyclesTool {
+    database: Arc<KuzuDatabase>,
+    workspace_manager: Arc<WorkspaceManager>,
+}
+
+impl FindCallCyclesTool {
+    pub fn new(database: Arc<KuzuDatabase>, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        Self {
+            database,
+            workspace_manager,
+        }
+    }
+}
+
+struct FindCallCyclesToolOutput {
+    project_path: String,
+    cycles: Vec<Vec<DefinitionNodeFromKuzu>>,
+    truncated: bool,
+    system_message: String,
+}
+
+impl ToXml for FindCallCyclesToolOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("cycles")?;
+        for cycle in &self.cycles {
+            builder.start_element("cycle")?;
+            builder.write_numeric_element("size", cycle.len())?;
+            for definition in cycle {
+                let absolute_file_path = Path::new(&self.project_path)
+                    .join(&definition.primary_file_path)
+                    .to_string_lossy()
+                    .to_string();
+
+                builder.start_element("definition")?;
+                builder.write_element("name", &definition.name)?;
+                builder.write_element("fqn", &definition.fqn)?;
+                builder.write_element("definition-type", &definition.definition_type)?;
+                builder.write_element("absolute-file-path", &absolute_file_path)?;
+                builder.write_numeric_element("start-line", definition.start_line + 1)?;
+                builder.write_numeric_element("end-line", definition.end_line + 1)?;
+                builder.end_element("definition")?;
+            }
+            builder.end_element("cycle")?;
+        }
+        builder.end_element("cycles")?;
+
+        builder.write_boolean_element("truncated", self.truncated)?;
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl KnowledgeGraphTool for FindCallCyclesTool {
+    fn name(&self) -> &str {
+        FIND_CALL_CYCLES_TOOL_NAME
+    }
+
+    fn to_mcp_tool(&self) -> Tool {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "project_absolute_path": {
+                    "type": "string",
+                    "description": "Absolute path to the indexed project root.",
+                },
+                "max_cycles": {
+                    "type": "integer",
+                    "description": format!("Maximum number of cycles to return. Defaults to {DEFAULT_MAX_CYCLES}, capped at {MAX_CYCLES_LIMIT}."),
+                    "minimum": 1,
+                }
+            },
+            "required": ["project_absolute_path"]
+        });
+
+        Tool {
+            name: Cow::Borrowed(FIND_CALL_CYCLES_TOOL_NAME),
+            description: Some(Cow::Borrowed(FIND_CALL_CYCLES_TOOL_DESCRIPTION)),
+            input_schema: Arc::new(object(input_schema)),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params };
+        let project_absolute_path = input.get_string("project_absolute_path")?;
+        let max_cycles = input
+            .get_usize_optional("max_cycles")
+            .unwrap_or(DEFAULT_MAX_CYCLES)
+            .clamp(1, MAX_CYCLES_LIMIT);
+
+        let project_info = self
+            .workspace_manager
+            .get_project_for_path(project_absolute_path)
+            .ok_or_else(|| {
+                rmcp::ErrorData::new(
+                    ErrorCode::INVALID_REQUEST,
+                    "Project not found in workspace manager".to_string(),
+                    None,
+                )
+            })?;
+
+        let database = self
+            .database
+            .get_indexed_database(&project_info.database_path.to_string_lossy(), None)
+            .map_err(|e| database_error(e, &project_info.project_path))?;
+
+        // One extra cycle tells whether the result was cut off
+        let mut cycles = NodeDatabaseService::new(&database)
+            .find_call_cycles(max_cycles + 1)
+            .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        let truncated = cycles.len() > max_cycles;
+        cycles.truncate(max_cycles);
+
+        let system_message = if cycles.is_empty() {
+            "No recursive or cyclic call chains were found in the project.".to_string()
+        } else if truncated {
+            format!(
+                "Showing the {max_cycles} largest call cycles, more were found. Increase max_cycles to see them."
+            )
+        } else {
+            format!("Found {} call cycle(s).", cycles.len())
+        };
+
+        let output = FindCallCyclesToolOutput {
+            project_path: project_info.project_path,
+            cycles,
+            truncated,
+            system_message,
+        };
+
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::kuzu::connection::KuzuConnection;
+    use database::schema::manager::SchemaManager;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    fn setup_project_with_calls(
+        database: &KuzuDatabase,
+        calls: &[(u32, u32)],
+    ) -> (TempDir, TempDir, Arc<WorkspaceManager>, String) {
+        let temp_workspace_dir = TempDir::new().unwrap();
+        let workspace_path = temp_workspace_dir.path().join("workspace");
+        TestRepository::new(&workspace_path.join("test-repo"), Some("test-repo"));
+
+        let temp_data_dir = TempDir::new().unwrap();
+        let workspace_manager = Arc::new(
+            WorkspaceManager::new_with_directory(temp_data_dir.path().to_path_buf()).unwrap(),
+        );
+        workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap();
+        let project_info = workspace_manager.list_all_projects()[0].clone();
+
+        std::fs::create_dir_all(project_info.database_path.parent().unwrap()).unwrap();
+        let project_database = database
+            .force_new_database(&project_info.database_path.to_string_lossy(), None)
+            .unwrap();
+        SchemaManager::new(&project_database)
+            .initialize_schema()
+            .unwrap();
+        let connection = KuzuConnection::new(&project_database).unwrap();
+        for (id, name) in [(1, "even"), (2, "odd"), (3, "factorial"), (4, "main")] {
+            connection
+                .execute_ddl(&format!(
+                    "CREATE (:DefinitionNode {{id: {id}, name: '{name}', fqn: 'Math::{name}', definition_type: 'Method', primary_file_path: 'lib/math.rb', start_line: {id}, end_line: {id}}})"
+                ))
+                .unwrap();
+        }
+        for (source, target) in calls {
+            connection
+                .execute_ddl(&format!(
+                    "MATCH (a:DefinitionNode {{id: {source}}}), (b:DefinitionNode {{id: {target}}}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {{type: 'CALLS'}}]->(b)"
+                ))
+                .unwrap();
+        }
+
+        (
+            temp_workspace_dir,
+            temp_data_dir,
+            workspace_manager,
+            project_info.project_path,
+        )
+    }
+
+    async fn call_tool(tool: &FindCallCyclesTool, params: serde_json::Value) -> String {
+        let result = tool.call(object(params)).await.unwrap();
+        result.content.unwrap()[0].as_text().unwrap().text.clone()
+    }
+
+    #[tokio::test]
+    async fn test_find_call_cycles_reports_cycles() {
+        let database = Arc::new(KuzuDatabase::new());
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            setup_project_with_calls(&database, &[(1, 2), (2, 1), (3, 3), (4, 1)]);
+        let tool = FindCallCyclesTool::new(Arc::clone(&database), workspace_manager);
+
+        let xml = call_tool(&tool, json!({ "project_absolute_path": project_path })).await;
+
+        assert!(xml.contains("<size>2</size>"));
+        assert!(xml.contains("<fqn>Math::even</fqn>"));
+        assert!(xml.contains("<fqn>Math::odd</fqn>"));
+        assert!(xml.contains("<fqn>Math::factorial</fqn>"));
+        assert!(!xml.contains("<fqn>Math::main</fqn>"));
+        assert!(xml.contains(&format!(
+            "<absolute-file-path>{project_path}/lib/math.rb</absolute-file-path>"
+        )));
+        assert!(xml.contains("<truncated>false</truncated>"));
+
+        let xml = call_tool(
+            &tool,
+            json!({ "project_absolute_path": project_path, "max_cycles": 1 }),
+        )
+        .await;
+
+        assert!(xml.contains("<fqn>Math::even</fqn>"));
+        assert!(!xml.contains("<fqn>Math::factorial</fqn>"));
+        assert!(xml.contains("<truncated>true</truncated>"));
+    }
+
+    #[tokio::test]
+    async fn test_find_call_cycles_without_cycles() {
+        let database = Arc::new(KuzuDatabase::new());
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            setup_project_with_calls(&database, &[(4, 1), (1, 2)]);
+        let tool = FindCallCyclesTool::new(Arc::clone(&database), workspace_manager);
+
+        let xml = call_tool(&tool, json!({ "project_absolute_path": project_path })).await;
+
+        assert!(!xml.contains("<cycle>"));
+        assert!(xml.contains("No recursive or cyclic call chains were found"));
+    }
+}
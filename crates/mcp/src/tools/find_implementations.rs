@@ -0,0 +1,230 @@
+use std::{borrow::Cow, path::Path, sync::Arc};
+
+use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::DefinitionNodeFromKuzu;
+use rmcp::model::{CallToolResult, Content, ErrorCode, JsonObject, Tool, object};
+use serde_json::json;
+use workspace_manager::WorkspaceManager;
+
+use crate::tools::types::{KnowledgeGraphTool, KnowledgeGraphToolInput};
+use crate::tools::utils::database_error;
+use crate::tools::xml::{ToXml, XmlBuilder};
+
+pub const FIND_IMPLEMENTATIONS_TOOL_NAME: &str = "find_implementations";
+const FIND_IMPLEMENTATIONS_TOOL_DESCRIPTION: &str = r#"Find every implementation of an interface or base class method in a project.
+
+Behavior:
+- Returns the methods that override the given method, directly or through other overrides, nearest first.
+- Overrides are detected for Java and Kotlin, by matching the method name against the methods of the super types.
+
+When to use:
+- To navigate polymorphic code, e.g. to find which classes implement an interface method before changing its contract.
+
+Example:
+Call:
+{ "project_absolute_path": "/abs/path/to/project", "fqn": "com.example.filter.Filter.apply" }
+"#;
+
+pub struct FindImplementationsTool {
+    database: Arc<KuzuDatabase>,
+    workspace_manager: Arc<WorkspaceManager>,
+}
+
+impl FindImplementationsTool {
+    pub fn new(database: Arc<KuzuDatabase>, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        Self {
+            database,
+            workspace_manager,
+        }
+    }
+}
+
+struct FindImplementationsToolOutput {
+    project_path: String,
+    implementations: Vec<DefinitionNodeFromKuzu>,
+    system_message: String,
+}
+
+impl ToXml for FindImplementationsToolOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("implementations")?;
+        for definition in &self.implementations {
+            let absolute_file_path = Path::new(&self.project_path)
+                .join(&definition.primary_file_path)
+                .to_string_lossy()
+                .to_string();
+
+            builder.start_element("definition")?;
+            builder.write_element("name", &definition.name)?;
+            builder.write_element("fqn", &definition.fqn)?;
+            builder.write_element("definition-type", &definition.definition_type)?;
+            builder.write_element("absolute-file-path", &absolute_file_path)?;
+            builder.write_numeric_element("start-line", definition.start_line + 1)?;
+            builder.write_numeric_element("end-line", definition.end_line + 1)?;
+            builder.end_element("definition")?;
+        }
+        builder.end_element("implementations")?;
+
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl KnowledgeGraphTool for FindImplementationsTool {
+    fn name(&self) -> &str {
+        FIND_IMPLEMENTATIONS_TOOL_NAME
+    }
+
+    fn to_mcp_tool(&self) -> Tool {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "project_absolute_path": {
+                    "type": "string",
+                    "description": "Absolute path to the indexed project root.",
+                },
+                "fqn": {
+                    "type": "string",
+                    "description": "Fully qualified name of the interface or base class method.",
+                }
+            },
+            "required": ["project_absolute_path", "fqn"]
+        });
+
+        Tool {
+            name: Cow::Borrowed(FIND_IMPLEMENTATIONS_TOOL_NAME),
+            description: Some(Cow::Borrowed(FIND_IMPLEMENTATIONS_TOOL_DESCRIPTION)),
+            input_schema: Arc::new(object(input_schema)),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params };
+        let project_absolute_path = input.get_string("project_absolute_path")?;
+        let fqn = input.get_string("fqn")?;
+
+        let project_info = self
+            .workspace_manager
+            .get_project_for_path(project_absolute_path)
+            .ok_or_else(|| {
+                rmcp::ErrorData::new(
+                    ErrorCode::INVALID_REQUEST,
+                    "Project not found in workspace manager".to_string(),
+                    None,
+                )
+            })?;
+
+        let database = self
+            .database
+            .get_indexed_database(&project_info.database_path.to_string_lossy(), None)
+            .map_err(|e| database_error(e, &project_info.project_path))?;
+
+        let implementations = NodeDatabaseService::new(&database)
+            .find_implementations(fqn)
+            .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        let system_message = if implementations.is_empty() {
+            format!("No implementations of {fqn} were found in the project.")
+        } else {
+            format!(
+                "Found {} implementation(s) of {fqn}.",
+                implementations.len()
+            )
+        };
+
+        let output = FindImplementationsToolOutput {
+            project_path: project_info.project_path,
+            implementations,
+            system_message,
+        };
+
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::kuzu::connection::KuzuConnection;
+    use database::schema::manager::SchemaManager;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    #[tokio::test]
+    async fn test_find_implementations_follows_overrides() {
+        let database = Arc::new(KuzuDatabase::new());
+        let temp_workspace_dir = TempDir::new().unwrap();
+        let workspace_path = temp_workspace_dir.path().join("workspace");
+        TestRepository::new(&workspace_path.join("test-repo"), Some("test-repo"));
+
+        let temp_data_dir = TempDir::new().unwrap();
+        let workspace_manager = Arc::new(
+            WorkspaceManager::new_with_directory(temp_data_dir.path().to_path_buf()).unwrap(),
+        );
+        workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap();
+        let project_info = workspace_manager.list_all_projects()[0].clone();
+
+        std::fs::create_dir_all(project_info.database_path.parent().unwrap()).unwrap();
+        let project_database = database
+            .force_new_database(&project_info.database_path.to_string_lossy(), None)
+            .unwrap();
+        SchemaManager::new(&project_database)
+            .initialize_schema()
+            .unwrap();
+        let connection = KuzuConnection::new(&project_database).unwrap();
+        for (id, class) in [(1, "Filter"), (2, "ServerFilter"), (3, "CachedFilter")] {
+            connection
+                .execute_ddl(&format!(
+                    "CREATE (:DefinitionNode {{id: {id}, name: 'apply', fqn: 'com.example.{class}.apply', definition_type: 'Method', primary_file_path: 'src/{class}.java', start_line: 3, end_line: 5}})"
+                ))
+                .unwrap();
+        }
+        for (source, target) in [(2, 1), (3, 2)] {
+            connection
+                .execute_ddl(&format!(
+                    "MATCH (a:DefinitionNode {{id: {source}}}), (b:DefinitionNode {{id: {target}}}) CREATE (a)-[:DEFINITION_RELATIONSHIPS {{type: 'OVERRIDES'}}]->(b)"
+                ))
+                .unwrap();
+        }
+
+        let tool = FindImplementationsTool::new(Arc::clone(&database), workspace_manager);
+        let project_path = project_info.project_path;
+        let result = tool
+            .call(object(json!({
+                "project_absolute_path": project_path,
+                "fqn": "com.example.Filter.apply",
+            })))
+            .await
+            .unwrap();
+        let xml = result.content.unwrap()[0].as_text().unwrap().text.clone();
+
+        assert!(!xml.contains("<fqn>com.example.Filter.apply</fqn>"));
+        assert!(xml.contains("<fqn>com.example.ServerFilter.apply</fqn>"));
+        assert!(xml.contains("<fqn>com.example.CachedFilter.apply</fqn>"));
+        assert!(xml.contains(&format!(
+            "<absolute-file-path>{project_path}/src/ServerFilter.java</absolute-file-path>"
+        )));
+        assert!(xml.contains("<start-line>4</start-line>"));
+        assert!(xml.contains("Found 2 implementation(s)"));
+    }
+}
@@ -23,8 +23,7 @@ pub fn find_definitions(
     db_line: i64,
     start_col: i64,
     end_col: i64,
-    calls_type_id: String,
-    ambiguous_calls_type_id: String,
+    reference_type_ids: &[String],
 ) -> Result<Vec<RawHit>, rmcp::ErrorData> {
     let mut base_params = Map::new();
     base_params.insert(
@@ -32,11 +31,14 @@ pub fn find_definitions(
         Value::String(relative_file_path.to_string()),
     );
     base_params.insert(
-        "calls_type_ids".to_string(),
-        Value::Array(vec![
-            Value::from(calls_type_id),
-            Value::from(ambiguous_calls_type_id),
-        ]),
+        "reference_type_ids".to_string(),
+        Value::Array(
+            reference_type_ids
+                .iter()
+                .cloned()
+                .map(Value::from)
+                .collect(),
+        ),
     );
     base_params.insert(
         "source_lines".to_string(),
@@ -47,7 +49,7 @@ pub fn find_definitions(
 
     let q_def_from_def = r#"
         MATCH (source:DefinitionNode {primary_file_path: $primary_file_path})-[r:DEFINITION_RELATIONSHIPS]->(target:DefinitionNode)
-        WHERE r.type IN $calls_type_ids
+        WHERE r.type IN $reference_type_ids
           AND r.source_start_line IN $source_lines
           AND r.source_start_col <= $start_col AND r.source_end_col >= $end_col
         RETURN
@@ -66,7 +68,7 @@ pub fn find_definitions(
 
     let q_imp_from_def = r#"
         MATCH (source:DefinitionNode {primary_file_path: $primary_file_path})-[r:DEFINITION_RELATIONSHIPS]->(target:ImportedSymbolNode)
-        WHERE r.type IN $calls_type_ids
+        WHERE r.type IN $reference_type_ids
           AND r.source_start_line IN $source_lines
           AND r.source_start_col <= $start_col AND r.source_end_col >= $end_col
         RETURN
@@ -85,7 +87,7 @@ pub fn find_definitions(
 
     let q_def_from_file = r#"
         MATCH (file:FileNode {path: $primary_file_path})-[r:DEFINITION_RELATIONSHIPS]->(target:DefinitionNode)
-        WHERE r.type IN $calls_type_ids
+        WHERE r.type IN $reference_type_ids
           AND r.source_start_line IN $source_lines
           AND r.source_start_col <= $start_col AND r.source_end_col >= $end_col
         RETURN
@@ -104,7 +106,7 @@ pub fn find_definitions(
 
     let q_imp_from_file = r#"
         MATCH (file:FileNode {path: $primary_file_path})-[r:DEFINITION_RELATIONSHIPS]->(target:ImportedSymbolNode)
-        WHERE r.type IN $calls_type_ids
+        WHERE r.type IN $reference_type_ids
           AND r.source_start_line IN $source_lines
           AND r.source_start_col <= $start_col AND r.source_end_col >= $end_col
         RETURN
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use database::graph::RelationshipType;
 use database::kuzu::connection::KuzuConnection;
 use database::kuzu::database::KuzuDatabase;
+use indexer::execution::cross_project::{CrossProjectLink, load_cross_project_links};
 use rmcp::model::ErrorCode;
 use workspace_manager::WorkspaceManager;
 
@@ -58,23 +59,18 @@ impl GetDefinitionService {
                 .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
         };
 
-        let (calls_type_id, ambiguous_calls_type_id) = {
-            (
-                RelationshipType::Calls.as_string(),
-                RelationshipType::AmbiguouslyCalls.as_string(),
-            )
-        };
+        let ambiguous_calls_type_id = RelationshipType::AmbiguouslyCalls.as_string();
+        let reference_type_ids = [
+            RelationshipType::Calls.as_string(),
+            ambiguous_calls_type_id.clone(),
+            RelationshipType::References.as_string(),
+            RelationshipType::Instantiates.as_string(),
+        ];
 
         let database = self
             .database
-            .get_or_create_database(&project_info.database_path.to_string_lossy(), None)
-            .ok_or_else(|| {
-                rmcp::ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    "Failed to get database for workspace".to_string(),
-                    None,
-                )
-            })?;
+            .get_indexed_database(&project_info.database_path.to_string_lossy(), None)
+            .map_err(|e| utils::database_error(e, &project_info.project_path))?;
 
         let raw_hits = {
             let conn = KuzuConnection::new(&database).map_err(|e| {
@@ -102,8 +98,7 @@ impl GetDefinitionService {
                         db_line,
                         start_col,
                         end_col,
-                        calls_type_id.clone(),
-                        ambiguous_calls_type_id.clone(),
+                        &reference_type_ids,
                     )?;
                     for hit in hits {
                         if hit.target_type == "Definition" {
@@ -122,6 +117,8 @@ impl GetDefinitionService {
         let (raw_hits, multiple_line_matches, multiple_symbol_occurrences) = raw_hits;
 
         let selected_hits = select_best_hits(raw_hits);
+        let cross_project_links =
+            self.load_cross_project_links(&project_info.workspace_folder_path);
 
         let mut definitions = Vec::new();
         let mut chunks_input = Vec::new();
@@ -152,6 +149,17 @@ impl GetDefinitionService {
                     code_error: None,
                 }));
             } else if hit.target_type == "ImportedSymbol" {
+                // Imports of a sibling project's package also point at its definitions
+                let linked_definitions: Vec<&CrossProjectLink> = cross_project_links
+                    .iter()
+                    .filter(|link| {
+                        link.source_project_path == project_info.project_path
+                            && link.source_file_path == hit.path
+                            && i64::from(link.source_start_line) == hit.start_line_db
+                            && link.imported_name == hit.name
+                    })
+                    .collect();
+
                 definitions.push(Definition::ImportedSymbol(ImportedSymbolInfo {
                     id: hit.id,
                     name: hit.name,
@@ -166,6 +174,32 @@ impl GetDefinitionService {
                     code: None,
                     code_error: None,
                 }));
+
+                for link in &linked_definitions {
+                    let abs_linked_path = Path::new(&link.target_project_path)
+                        .join(&link.target_file_path)
+                        .to_string_lossy()
+                        .to_string();
+                    let start_line_1 = (link.target_start_line + 1).max(1) as usize;
+                    let end_line_1 =
+                        (link.target_end_line + 1).max(link.target_start_line + 1) as usize;
+                    chunks_input.push((abs_linked_path.clone(), start_line_1, end_line_1));
+
+                    definitions.push(Definition::Definition(DefinitionInfo {
+                        id: link.target_definition_id.to_string(),
+                        name: link.imported_name.clone(),
+                        fqn: link.target_fqn.clone(),
+                        primary_file_path: link.target_file_path.clone(),
+                        absolute_file_path: abs_linked_path,
+                        start_line: link.target_start_line.into(),
+                        end_line: link.target_end_line.into(),
+                        rel_start_col: hit.rel_start_col,
+                        rel_end_col: hit.rel_end_col,
+                        is_ambiguous: linked_definitions.len() > 1,
+                        code: None,
+                        code_error: None,
+                    }));
+                }
             }
         }
 
@@ -225,6 +259,15 @@ impl GetDefinitionService {
             system_message,
         })
     }
+
+    /// Links found by the last cross-project resolution of the workspace folder, none when
+    /// it didn't run
+    fn load_cross_project_links(&self, workspace_folder_path: &str) -> Vec<CrossProjectLink> {
+        self.workspace_manager
+            .cross_project_links_path(workspace_folder_path)
+            .and_then(|path| load_cross_project_links(&path).ok())
+            .unwrap_or_default()
+    }
 }
 
 fn find_symbol_col_ranges(line: &str, symbol_name: &str) -> Vec<(i64, i64)> {
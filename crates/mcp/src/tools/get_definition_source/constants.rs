@@ -0,0 +1,29 @@
+pub const GET_DEFINITION_SOURCE_TOOL_NAME: &str = "get_definition_source";
+pub(in crate::tools::get_definition_source) const GET_DEFINITION_SOURCE_TOOL_DESCRIPTION: &str = r#"Return the exact source code of a definition, using the location stored in the knowledge graph.
+
+Behavior:
+- Finds definitions in the given file whose name or fully qualified name matches `name`
+- Returns the source text of each match along with its location, fully qualified name and definition type
+- Adds `context_lines` lines of surrounding code before and after the definition (default: 3)
+- Warns when the file has changed since it was indexed and the stored location no longer matches the code
+
+Requirements:
+- Provide the definition name exactly as it appears in code (case-sensitive), or its fully qualified name
+- Specify the absolute or project-relative path of the file that declares the definition
+
+Example:
+Call:
+{
+  "file_path": "/project/src/services/user.js",
+  "name": "UserService",
+  "context_lines": 2
+}
+
+Tip: Use `search_codebase_definitions` or `get_definition` first to find where a definition lives."#;
+
+pub(in crate::tools::get_definition_source) const FILE_PATH_FIELD: &str = "file_path";
+pub(in crate::tools::get_definition_source) const NAME_FIELD: &str = "name";
+pub(in crate::tools::get_definition_source) const CONTEXT_LINES_FIELD: &str = "context_lines";
+
+pub(in crate::tools::get_definition_source) const DEFAULT_CONTEXT_LINES: usize = 3;
+pub(in crate::tools::get_definition_source) const MAX_CONTEXT_LINES: usize = 50;
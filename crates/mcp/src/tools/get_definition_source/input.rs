@@ -0,0 +1,55 @@
+use rmcp::model::{ErrorCode, JsonObject};
+use std::{path::PathBuf, sync::Arc};
+use workspace_manager::WorkspaceManager;
+
+use crate::tools::{types::KnowledgeGraphToolInput, utils::resolve_paths};
+
+use super::constants::{
+    CONTEXT_LINES_FIELD, DEFAULT_CONTEXT_LINES, FILE_PATH_FIELD, MAX_CONTEXT_LINES, NAME_FIELD,
+};
+
+#[derive(Debug, Clone)]
+pub struct GetDefinitionSourceInput {
+    pub name: String,
+    pub context_lines: usize,
+    pub database_path: PathBuf,
+    pub project_path: PathBuf,
+    pub relative_file_path: String,
+    pub absolute_file_path: PathBuf,
+}
+
+impl GetDefinitionSourceInput {
+    pub fn new(
+        object: JsonObject,
+        workspace_manager: &Arc<WorkspaceManager>,
+    ) -> Result<Self, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params: object };
+
+        let name = input.get_string(NAME_FIELD)?.to_string();
+        if name.is_empty() {
+            return Err(rmcp::ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Definition name cannot be empty.".to_string(),
+                None,
+            ));
+        }
+
+        let input_file_path = input.get_string(FILE_PATH_FIELD)?;
+        let (absolute_file_path, project_info, relative_file_path) =
+            resolve_paths(workspace_manager, input_file_path)?;
+
+        let context_lines = input
+            .get_usize_optional(CONTEXT_LINES_FIELD)
+            .unwrap_or(DEFAULT_CONTEXT_LINES)
+            .min(MAX_CONTEXT_LINES);
+
+        Ok(Self {
+            name,
+            context_lines,
+            database_path: project_info.database_path,
+            project_path: project_info.project_path.into(),
+            relative_file_path,
+            absolute_file_path,
+        })
+    }
+}
@@ -0,0 +1,8 @@
+pub mod constants;
+pub mod input;
+pub mod output;
+pub mod repository;
+pub mod service;
+pub mod tool;
+
+pub use constants::*;
@@ -0,0 +1,55 @@
+use crate::tools::xml::{ToXml, XmlBuilder};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct GetDefinitionSourceOutput {
+    pub definitions: Vec<DefinitionSource>,
+    pub system_message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DefinitionSource {
+    pub name: String,
+    pub fqn: String,
+    pub definition_type: String,
+    pub absolute_file_path: String,
+    /// One-indexed line range of the definition
+    pub start_line: usize,
+    pub end_line: usize,
+    /// One-indexed line range of `source`, including the context lines
+    pub source_start_line: usize,
+    pub source_end_line: usize,
+    pub source: String,
+    /// The file changed since indexing and the stored range no longer matches the definition
+    pub range_mismatch: bool,
+}
+
+impl ToXml for GetDefinitionSourceOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("definitions")?;
+        for definition in &self.definitions {
+            builder.start_element("definition")?;
+            builder.write_element("name", &definition.name)?;
+            builder.write_element("fqn", &definition.fqn)?;
+            builder.write_element("definition-type", &definition.definition_type)?;
+            builder.write_element("absolute-file-path", &definition.absolute_file_path)?;
+            builder.write_numeric_element("start-line", definition.start_line)?;
+            builder.write_numeric_element("end-line", definition.end_line)?;
+            builder.write_numeric_element("source-start-line", definition.source_start_line)?;
+            builder.write_numeric_element("source-end-line", definition.source_end_line)?;
+            builder.write_boolean_element("range-mismatch", definition.range_mismatch)?;
+            builder.write_cdata_element("source", &definition.source)?;
+            builder.end_element("definition")?;
+        }
+        builder.end_element("definitions")?;
+
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
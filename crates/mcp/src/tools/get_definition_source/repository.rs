@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use database::querying::QueryingService;
+use rmcp::model::ErrorCode;
+
+use super::input::GetDefinitionSourceInput;
+
+#[derive(Debug)]
+pub struct DefinitionLocation {
+    pub name: String,
+    pub fqn: String,
+    pub definition_type: String,
+    /// Zero-indexed, as stored in the database
+    pub start_line: i64,
+    pub end_line: i64,
+    pub start_byte: i64,
+    pub end_byte: i64,
+}
+
+pub struct GetDefinitionSourceRepository {
+    querying_service: Arc<dyn QueryingService>,
+}
+
+impl GetDefinitionSourceRepository {
+    pub fn new(querying_service: Arc<dyn QueryingService>) -> Self {
+        Self { querying_service }
+    }
+
+    pub fn find_definitions(
+        &self,
+        input: &GetDefinitionSourceInput,
+    ) -> Result<Vec<DefinitionLocation>, rmcp::ErrorData> {
+        let query = "
+            MATCH (d:DefinitionNode)
+            WHERE
+                d.primary_file_path = $file_path
+                AND (d.name = $name OR d.fqn = $name)
+            RETURN
+                d.name as name,
+                d.fqn as fqn,
+                d.definition_type as definition_type,
+                d.start_line as start_line,
+                d.end_line as end_line,
+                d.primary_start_byte as start_byte,
+                d.primary_end_byte as end_byte
+            ORDER BY d.start_line
+        ";
+
+        let mut params = serde_json::Map::new();
+        params.insert(
+            "file_path".to_string(),
+            serde_json::Value::String(input.relative_file_path.clone()),
+        );
+        params.insert(
+            "name".to_string(),
+            serde_json::Value::String(input.name.clone()),
+        );
+
+        let mut query_result = self
+            .querying_service
+            .execute_query(input.database_path.clone(), query.to_string(), params)
+            .map_err(|e| {
+                rmcp::ErrorData::new(
+                    ErrorCode::INVALID_REQUEST,
+                    format!(
+                        "Could not execute definition query for '{}': {e}.",
+                        input.name
+                    ),
+                    None,
+                )
+            })?;
+
+        let mut results = Vec::new();
+        while let Some(row) = query_result.next() {
+            results.push(DefinitionLocation {
+                name: row.get_string_value(0).unwrap_or_default(),
+                fqn: row.get_string_value(1).unwrap_or_default(),
+                definition_type: row.get_string_value(2).unwrap_or_default(),
+                start_line: row.get_int_value(3).unwrap_or_default(),
+                end_line: row.get_int_value(4).unwrap_or_default(),
+                start_byte: row.get_int_value(5).unwrap_or_default(),
+                end_byte: row.get_int_value(6).unwrap_or_default(),
+            });
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use database::querying::QueryingService;
+use tokio::time::{Duration, timeout};
+
+use crate::tools::file_reader_utils::slice_lines_with_context;
+use crate::tools::get_definition_source::input::GetDefinitionSourceInput;
+use crate::tools::get_definition_source::output::{DefinitionSource, GetDefinitionSourceOutput};
+use crate::tools::get_definition_source::repository::{
+    DefinitionLocation, GetDefinitionSourceRepository,
+};
+
+const FILE_READ_TIMEOUT_SECONDS: u64 = 10;
+
+pub struct GetDefinitionSourceService {
+    repository: GetDefinitionSourceRepository,
+}
+
+impl GetDefinitionSourceService {
+    pub fn new(querying_service: Arc<dyn QueryingService>) -> Self {
+        Self {
+            repository: GetDefinitionSourceRepository::new(querying_service),
+        }
+    }
+
+    pub async fn get_definition_source(
+        &self,
+        input: GetDefinitionSourceInput,
+    ) -> Result<GetDefinitionSourceOutput, rmcp::ErrorData> {
+        let locations = self.repository.find_definitions(&input)?;
+
+        if locations.is_empty() {
+            return Ok(GetDefinitionSourceOutput {
+                definitions: vec![],
+                system_message: self.get_system_message(&input, 0, 0, None),
+            });
+        }
+
+        let content = match self.read_file(&input).await {
+            Ok(content) => content,
+            Err(e) => {
+                return Ok(GetDefinitionSourceOutput {
+                    definitions: vec![],
+                    system_message: self.get_system_message(
+                        &input,
+                        locations.len(),
+                        0,
+                        Some(e.to_string()),
+                    ),
+                });
+            }
+        };
+
+        let absolute_file_path = input.absolute_file_path.to_string_lossy().to_string();
+        let mut definitions = Vec::with_capacity(locations.len());
+        for location in &locations {
+            // Lines are stored zero-indexed
+            let start_line = (location.start_line + 1).max(1) as usize;
+            let end_line = (location.end_line + 1).max(location.start_line + 1) as usize;
+
+            let (source, source_start_line, source_end_line) =
+                slice_lines_with_context(&content, start_line, end_line, input.context_lines)
+                    .unwrap_or_default();
+
+            definitions.push(DefinitionSource {
+                name: location.name.clone(),
+                fqn: location.fqn.clone(),
+                definition_type: location.definition_type.clone(),
+                absolute_file_path: absolute_file_path.clone(),
+                start_line,
+                end_line,
+                source_start_line,
+                source_end_line,
+                source,
+                range_mismatch: !range_matches(&content, location),
+            });
+        }
+
+        let total_mismatched = definitions.iter().filter(|d| d.range_mismatch).count();
+
+        Ok(GetDefinitionSourceOutput {
+            system_message: self.get_system_message(
+                &input,
+                definitions.len(),
+                total_mismatched,
+                None,
+            ),
+            definitions,
+        })
+    }
+
+    async fn read_file(&self, input: &GetDefinitionSourceInput) -> std::io::Result<String> {
+        match timeout(
+            Duration::from_secs(FILE_READ_TIMEOUT_SECONDS),
+            tokio::fs::read_to_string(&input.absolute_file_path),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "File reading operation timed out.",
+            )),
+        }
+    }
+
+    fn get_system_message(
+        &self,
+        input: &GetDefinitionSourceInput,
+        total_found: usize,
+        total_mismatched: usize,
+        file_read_error: Option<String>,
+    ) -> String {
+        let mut message = String::new();
+
+        if let Some(error) = file_read_error {
+            message.push_str(&format!(
+                "Found {total_found} definitions named '{}', but failed to read {}: {error}.\n",
+                input.name, input.relative_file_path
+            ));
+            message.push_str("Perhaps the file was deleted or moved since the last indexing.\n");
+            message.push_str("If the source is important, use the `index_project` tool to re-index the project and try again.\n");
+            return message;
+        }
+
+        if total_found == 0 {
+            message.push_str(&format!(
+                "No definitions named '{}' were found in {}.\n",
+                input.name, input.relative_file_path
+            ));
+
+            message.push_str("\nDecision Framework:\n");
+            message.push_str("  - Verify that the definition name and file path are correct and exact matches.\n");
+            message.push_str("  - Use the `search_codebase_definitions` tool to find definitions with similar names.\n");
+            message.push_str("  - If you know the definition exists, use the `index_project` tool to re-index the project and try again.\n");
+            return message;
+        }
+
+        message.push_str(&format!(
+            "Found {total_found} definitions named '{}' in {}.\n",
+            input.name, input.relative_file_path
+        ));
+
+        if total_mismatched > 0 {
+            message.push_str(&format!(
+                "Warning: the file changed since the last indexing and {total_mismatched} of the returned ranges no longer match their definitions (marked with range-mismatch).\n"
+            ));
+            message.push_str("The returned source for those definitions may be incomplete or wrong. Use the `index_project` tool to re-index the project and try again.\n");
+        }
+
+        message
+    }
+}
+
+/// Checks whether the stored byte range still points at the definition: it must lie within
+/// the file, start on the stored line and contain the definition name.
+fn range_matches(content: &str, location: &DefinitionLocation) -> bool {
+    let (Ok(start), Ok(end)) = (
+        usize::try_from(location.start_byte),
+        usize::try_from(location.end_byte),
+    ) else {
+        return false;
+    };
+
+    let Some(text) = content.get(start..end) else {
+        return false;
+    };
+
+    let line_of_start = content[..start].matches('\n').count() as i64;
+    line_of_start == location.start_line && text.contains(location.name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str, start_line: i64, start_byte: i64, end_byte: i64) -> DefinitionLocation {
+        DefinitionLocation {
+            name: name.to_string(),
+            fqn: name.to_string(),
+            definition_type: "Function".to_string(),
+            start_line,
+            end_line: start_line,
+            start_byte,
+            end_byte,
+        }
+    }
+
+    #[test]
+    fn test_range_matches() {
+        let content = "// header\nfn foo() {}\n";
+
+        assert!(range_matches(content, &location("foo", 1, 10, 21)));
+        assert!(!range_matches(content, &location("foo", 0, 10, 21)));
+        assert!(!range_matches(content, &location("bar", 1, 10, 21)));
+        assert!(!range_matches(content, &location("foo", 1, 10, 200)));
+        assert!(!range_matches(content, &location("foo", 1, -1, 21)));
+    }
+}
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use database::querying::QueryingService;
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool, object};
+use serde_json::json;
+use workspace_manager::WorkspaceManager;
+
+use crate::tools::get_definition_source::constants::{
+    CONTEXT_LINES_FIELD, DEFAULT_CONTEXT_LINES, FILE_PATH_FIELD,
+    GET_DEFINITION_SOURCE_TOOL_DESCRIPTION, GET_DEFINITION_SOURCE_TOOL_NAME, MAX_CONTEXT_LINES,
+    NAME_FIELD,
+};
+use crate::tools::get_definition_source::input::GetDefinitionSourceInput;
+use crate::tools::{
+    get_definition_source::service::GetDefinitionSourceService, types::KnowledgeGraphTool,
+    xml::ToXml,
+};
+
+pub struct GetDefinitionSourceTool {
+    workspace_manager: Arc<WorkspaceManager>,
+    service: GetDefinitionSourceService,
+}
+
+impl GetDefinitionSourceTool {
+    pub fn new(
+        querying_service: Arc<dyn QueryingService>,
+        workspace_manager: Arc<WorkspaceManager>,
+    ) -> Self {
+        Self {
+            workspace_manager: Arc::clone(&workspace_manager),
+            service: GetDefinitionSourceService::new(querying_service),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl KnowledgeGraphTool for GetDefinitionSourceTool {
+    fn name(&self) -> &str {
+        GET_DEFINITION_SOURCE_TOOL_NAME
+    }
+
+    fn to_mcp_tool(&self) -> Tool {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                FILE_PATH_FIELD: {
+                    "type": "string",
+                    "description": "Absolute or project-relative path to the file that contains the definition. Example: src/main/java/com/example/User.java"
+                },
+                NAME_FIELD: {
+                    "type": "string",
+                    "description": "Exact name or fully qualified name of the definition. Example: 'getUser' or 'com.example.User.getUser'."
+                },
+                CONTEXT_LINES_FIELD: {
+                    "type": "integer",
+                    "description": "Number of lines of surrounding code to include before and after the definition.",
+                    "default": DEFAULT_CONTEXT_LINES,
+                    "minimum": 0,
+                    "maximum": MAX_CONTEXT_LINES
+                }
+            },
+            "required": [FILE_PATH_FIELD, NAME_FIELD],
+            "additionalProperties": false
+        });
+
+        Tool {
+            name: Cow::Borrowed(GET_DEFINITION_SOURCE_TOOL_NAME),
+            description: Some(Cow::Borrowed(GET_DEFINITION_SOURCE_TOOL_DESCRIPTION)),
+            input_schema: Arc::new(object(input_schema)),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = GetDefinitionSourceInput::new(params, &self.workspace_manager)?;
+
+        let output = self.service.get_definition_source(input).await?;
+
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                rmcp::model::ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use database::{kuzu::database::KuzuDatabase, querying::DatabaseQueryingService};
+    use indexer::analysis::languages::java::setup_java_reference_pipeline;
+    use rmcp::model::{CallToolResult, object};
+    use serde_json::json;
+
+    use crate::tools::{
+        get_definition_source::tool::GetDefinitionSourceTool, types::KnowledgeGraphTool,
+    };
+
+    fn result_text(result: &CallToolResult) -> String {
+        let content = result.content.as_ref().expect("Expected content in result");
+        match &content[0].raw {
+            rmcp::model::RawContent::Text(text_content) => text_content.text.clone(),
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[tracing_test::traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_returns_definition_source_with_context() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_java_reference_pipeline(&database).await;
+
+        database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+
+        let tool: &dyn KnowledgeGraphTool = &GetDefinitionSourceTool::new(
+            Arc::new(DatabaseQueryingService::new(database)),
+            Arc::new(setup.workspace_manager.clone()),
+        );
+
+        let result = tool
+            .call(object(json!({
+                "file_path": "main/src/com/example/app/Foo.java",
+                "name": "bar",
+                "context_lines": 1
+            })))
+            .await
+            .unwrap();
+        let xml_str = result_text(&result);
+
+        assert!(xml_str.contains("<name>bar</name>"));
+        assert!(xml_str.contains("<fqn>com.example.app.Foo.bar</fqn>"));
+        assert!(xml_str.contains("<range-mismatch>false</range-mismatch>"));
+        assert!(
+            xml_str.contains("return new Bar()"),
+            "Expected method source in output"
+        );
+
+        let start_line = extract_number(&xml_str, "start-line");
+        let source_start_line = extract_number(&xml_str, "source-start-line");
+        assert_eq!(source_start_line, start_line - 1);
+
+        setup.cleanup();
+    }
+
+    #[tracing_test::traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_warns_when_file_changed_since_indexing() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_java_reference_pipeline(&database).await;
+
+        database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+
+        let tool: &dyn KnowledgeGraphTool = &GetDefinitionSourceTool::new(
+            Arc::new(DatabaseQueryingService::new(database)),
+            Arc::new(setup.workspace_manager.clone()),
+        );
+
+        let file_path = setup
+            .local_repo
+            .path
+            .join("main/src/com/example/app/Foo.java");
+        let original = std::fs::read_to_string(&file_path).unwrap();
+        std::fs::write(&file_path, format!("// moved\n// down\n{original}")).unwrap();
+
+        let result = tool
+            .call(object(json!({
+                "file_path": "main/src/com/example/app/Foo.java",
+                "name": "bar"
+            })))
+            .await
+            .unwrap();
+        let xml_str = result_text(&result);
+
+        assert!(xml_str.contains("<range-mismatch>true</range-mismatch>"));
+        assert!(
+            xml_str.contains("changed since the last indexing"),
+            "Expected a stale index warning"
+        );
+
+        setup.cleanup();
+    }
+
+    fn extract_number(xml: &str, tag: &str) -> usize {
+        let open = format!("<{tag}>");
+        let start = xml.find(&open).expect("tag not found") + open.len();
+        let end = start + xml[start..].find('<').unwrap();
+        xml[start..end].parse().unwrap()
+    }
+}
@@ -0,0 +1,200 @@
+use std::{borrow::Cow, sync::Arc};
+
+use rmcp::model::{CallToolResult, Content, ErrorCode, JsonObject, Tool, object};
+use serde_json::json;
+
+use crate::indexing_jobs::{IndexingJobQueue, IndexingJobState, IndexingJobStatus};
+use crate::tools::index_project::INDEX_PROJECT_TOOL_NAME;
+use crate::tools::types::{KnowledgeGraphTool, KnowledgeGraphToolInput};
+use crate::tools::xml::{ToXml, XmlBuilder};
+
+pub const GET_INDEX_STATUS_TOOL_NAME: &str = "get_index_status";
+const GET_INDEX_STATUS_TOOL_DESCRIPTION: &str = r#"Check the progress of an indexing job started by the `index_project` tool.
+
+Behavior:
+- Returns whether the job is pending, running, completed, failed or cancelled.
+- Failed jobs include the error that stopped them.
+
+When to use:
+- After calling `index_project`, to find out when the Knowledge Graph is up to date.
+- Poll sparingly, indexing large projects can take several minutes.
+
+Example:
+Call:
+{ "job_id": "2f1c7a4e-0b8e-4d7e-9a8f-3c5d2b1e6f00" }
+"#;
+
+pub struct GetIndexStatusTool {
+    job_queue: Arc<dyn IndexingJobQueue>,
+}
+
+impl GetIndexStatusTool {
+    pub fn new(job_queue: Arc<dyn IndexingJobQueue>) -> Self {
+        Self { job_queue }
+    }
+
+    fn get_system_message(status: &IndexingJobStatus) -> String {
+        match status.state {
+            IndexingJobState::Pending => {
+                "The indexing job is waiting in the queue. Check again later.".to_string()
+            }
+            IndexingJobState::Running => {
+                "The project is being indexed. Results from the other Knowledge Graph tools may be incomplete until the job has completed.".to_string()
+            }
+            IndexingJobState::Completed => {
+                "The project has been indexed, the Knowledge Graph is up to date.".to_string()
+            }
+            IndexingJobState::Failed => format!(
+                "The indexing job failed. You can call the `{INDEX_PROJECT_TOOL_NAME}` tool again to retry."
+            ),
+            IndexingJobState::Cancelled => format!(
+                "The indexing job was cancelled, usually because a newer job for the same workspace replaced it. Call the `{INDEX_PROJECT_TOOL_NAME}` tool again if the Knowledge Graph is still stale."
+            ),
+        }
+    }
+}
+
+struct GetIndexStatusToolOutput {
+    status: IndexingJobStatus,
+    system_message: String,
+}
+
+impl ToXml for GetIndexStatusToolOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("indexing-job")?;
+        builder.write_element("job-id", &self.status.job_id)?;
+        builder.write_element("status", self.status.state.as_str())?;
+        if let Some(started_at) = &self.status.started_at {
+            builder.write_element("started-at", started_at)?;
+        }
+        if let Some(completed_at) = &self.status.completed_at {
+            builder.write_element("completed-at", completed_at)?;
+        }
+        if let Some(error) = &self.status.error {
+            builder.write_cdata_element("error", error)?;
+        }
+        builder.end_element("indexing-job")?;
+
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl KnowledgeGraphTool for GetIndexStatusTool {
+    fn name(&self) -> &str {
+        GET_INDEX_STATUS_TOOL_NAME
+    }
+
+    fn to_mcp_tool(&self) -> Tool {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "job_id": {
+                    "type": "string",
+                    "description": "The job ID returned by the index_project tool.",
+                }
+            },
+            "required": ["job_id"]
+        });
+
+        Tool {
+            name: Cow::Borrowed(GET_INDEX_STATUS_TOOL_NAME),
+            description: Some(Cow::Borrowed(GET_INDEX_STATUS_TOOL_DESCRIPTION)),
+            input_schema: Arc::new(object(input_schema)),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params };
+        let job_id = input.get_string("job_id")?;
+
+        let status = self.job_queue.indexing_job_status(job_id).ok_or_else(|| {
+            rmcp::ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Indexing job {job_id} not found. Only queued, running and recently finished jobs are tracked."
+                ),
+                None,
+            )
+        })?;
+
+        let output = GetIndexStatusToolOutput {
+            system_message: Self::get_system_message(&status),
+            status,
+        };
+
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    struct FailedJobQueue;
+
+    #[async_trait::async_trait]
+    impl IndexingJobQueue for FailedJobQueue {
+        async fn dispatch_project_indexing(
+            &self,
+            _workspace_folder_path: &str,
+            _project_path: &str,
+        ) -> anyhow::Result<String> {
+            Ok("job-1".to_string())
+        }
+
+        fn indexing_job_status(&self, job_id: &str) -> Option<IndexingJobStatus> {
+            (job_id == "job-1").then(|| IndexingJobStatus {
+                job_id: job_id.to_string(),
+                state: IndexingJobState::Failed,
+                started_at: Some("2025-01-01T00:00:00+00:00".to_string()),
+                completed_at: Some("2025-01-01T00:01:00+00:00".to_string()),
+                error: Some("Project not found".to_string()),
+            })
+        }
+    }
+
+    #[test]
+    fn test_get_index_status_reports_job_state() {
+        let tool = GetIndexStatusTool::new(Arc::new(FailedJobQueue));
+
+        let mut params = JsonObject::new();
+        params.insert("job_id".to_string(), Value::String("job-1".to_string()));
+        let result = futures::executor::block_on(tool.call(params)).unwrap();
+        let text = result.content.unwrap()[0].as_text().unwrap().text.clone();
+
+        assert!(text.contains("<job-id>job-1</job-id>"));
+        assert!(text.contains("<status>failed</status>"));
+        assert!(text.contains("<error>Project not found</error>"));
+        assert!(text.contains("<completed-at>2025-01-01T00:01:00+00:00</completed-at>"));
+    }
+
+    #[test]
+    fn test_get_index_status_unknown_job() {
+        let tool = GetIndexStatusTool::new(Arc::new(FailedJobQueue));
+
+        let mut params = JsonObject::new();
+        params.insert("job_id".to_string(), Value::String("job-2".to_string()));
+        let err = futures::executor::block_on(tool.call(params)).unwrap_err();
+
+        assert!(err.message.contains("job-2 not found"));
+    }
+}
@@ -123,6 +123,8 @@ impl GetReferencesRepository {
             RelationshipType::Calls.as_string(),
             RelationshipType::PropertyReference.as_string(),
             RelationshipType::AmbiguouslyCalls.as_string(),
+            RelationshipType::References.as_string(),
+            RelationshipType::Instantiates.as_string(),
         ]
     }
 }
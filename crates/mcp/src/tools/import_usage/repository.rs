@@ -40,8 +40,15 @@ impl ImportUsageRepository {
         aliases: Vec<String>,
     ) -> Result<(Vec<ImportHit>, Vec<ReferenceHit>), rmcp::ErrorData> {
         use database::graph::RelationshipType;
-        let calls_type_id = RelationshipType::Calls.as_string();
-        let ambiguous_calls_type_id = RelationshipType::AmbiguouslyCalls.as_string();
+        let reference_type_ids = [
+            RelationshipType::Calls,
+            RelationshipType::AmbiguouslyCalls,
+            RelationshipType::References,
+            RelationshipType::Instantiates,
+        ]
+        .iter()
+        .map(|relationship_type| serde_json::Value::String(relationship_type.as_string()))
+        .collect();
 
         let mut params = serde_json::Map::new();
         let lowercased: Vec<serde_json::Value> = import_paths
@@ -50,12 +57,8 @@ impl ImportUsageRepository {
             .collect();
         params.insert("paths_lc".to_string(), serde_json::Value::Array(lowercased));
         params.insert(
-            "calls_type_id".to_string(),
-            serde_json::Value::String(calls_type_id),
-        );
-        params.insert(
-            "ambiguous_calls_type_id".to_string(),
-            serde_json::Value::String(ambiguous_calls_type_id),
+            "reference_type_ids".to_string(),
+            serde_json::Value::Array(reference_type_ids),
         );
         params.insert("limit".to_string(), serde_json::Value::Number(500.into()));
 
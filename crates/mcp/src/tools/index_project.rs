@@ -1,7 +1,10 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::thread;
 use std::{borrow::Cow, collections::HashMap};
 
+use crate::indexing_jobs::IndexingJobQueue;
+use crate::tools::get_index_status::GET_INDEX_STATUS_TOOL_NAME;
 use crate::tools::xml::{ToXml, XmlBuilder};
 use database::kuzu::database::KuzuDatabase;
 use event_bus::EventBus;
@@ -21,6 +24,8 @@ const INDEX_PROJECT_TOOL_DESCRIPTION: &str = r#"Rebuild the Knowledge Graph inde
 Behavior:
 - Scans the entire project and regenerates the Knowledge Graph from scratch.
 - Updates all file relationships, dependencies, and cross-references.
+- Full indexing runs in the background: the tool returns a job ID right away, use the `get_index_status` tool to check when the job has finished.
+- With `incremental: true`, only re-indexes the files git reports as changed and returns them. This is much faster after small edits.
 
 Requirements:
 - Specify the absolute filesystem path to the project root directory.
@@ -29,12 +34,124 @@ Requirements:
 When to use:
 - After substantial file modifications, additions, or deletions.
 - When the Knowledge Graph appears stale or incomplete.
+- Use `incremental: true` to refresh the Knowledge Graph after editing a few files.
 
 Example:
 Call:
-{ "project": "/path/to/project" }
+{ "project_absolute_path": "/path/to/project", "incremental": true }
 "#;
 
+#[derive(Serialize)]
+pub struct IndexProjectToolJobOutput {
+    pub job_id: String,
+    pub project_path: String,
+    pub system_message: String,
+}
+
+impl IndexProjectToolJobOutput {
+    fn new(job_id: String, project_path: String) -> Self {
+        let system_message = format!(
+            "Indexing of the project has been queued and runs in the background. Call the `{GET_INDEX_STATUS_TOOL_NAME}` tool with the job ID to check its progress. Results from the other Knowledge Graph tools may be incomplete until the job has completed."
+        );
+
+        Self {
+            job_id,
+            project_path,
+            system_message,
+        }
+    }
+}
+
+impl ToXml for IndexProjectToolJobOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("indexing-job")?;
+        builder.write_element("job-id", &self.job_id)?;
+        builder.write_element("project-path", &self.project_path)?;
+        builder.write_element("status", "pending")?;
+        builder.end_element("indexing-job")?;
+
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
+#[derive(Serialize)]
+pub struct IndexProjectToolIncrementalOutput {
+    pub project_path: String,
+    pub updated_files: Vec<String>,
+    pub deleted_files: Vec<String>,
+    pub system_message: String,
+}
+
+impl IndexProjectToolIncrementalOutput {
+    fn new(project_path: String, changes: Vec<PathBuf>) -> Self {
+        let mut updated_files = Vec::new();
+        let mut deleted_files = Vec::new();
+        for change in changes {
+            let relative = change
+                .strip_prefix(&project_path)
+                .unwrap_or(&change)
+                .to_string_lossy()
+                .to_string();
+            if change.exists() {
+                updated_files.push(relative);
+            } else {
+                deleted_files.push(relative);
+            }
+        }
+
+        let system_message = if updated_files.is_empty() && deleted_files.is_empty() {
+            "Git reports no changed files, the Knowledge Graph is already up to date.".to_string()
+        } else {
+            format!(
+                "Re-indexed {} updated and {} deleted files. Run a full index (without `incremental`) if the Knowledge Graph still looks stale, e.g. after switching branches.",
+                updated_files.len(),
+                deleted_files.len()
+            )
+        };
+
+        Self {
+            project_path,
+            updated_files,
+            deleted_files,
+            system_message,
+        }
+    }
+}
+
+impl ToXml for IndexProjectToolIncrementalOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("incremental-update")?;
+        builder.write_element("project-path", &self.project_path)?;
+        builder.start_element("updated-files")?;
+        for file in &self.updated_files {
+            builder.write_element("file", file)?;
+        }
+        builder.end_element("updated-files")?;
+        builder.start_element("deleted-files")?;
+        for file in &self.deleted_files {
+            builder.write_element("file", file)?;
+        }
+        builder.end_element("deleted-files")?;
+        builder.end_element("incremental-update")?;
+
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
 #[derive(Serialize)]
 pub struct IndexProjectToolOutput {
     pub stats: IndexProjectToolStatsOutput,
@@ -145,6 +262,7 @@ pub struct IndexProjectTool {
     database: Arc<KuzuDatabase>,
     workspace_manager: Arc<WorkspaceManager>,
     event_bus: Arc<EventBus>,
+    job_queue: Option<Arc<dyn IndexingJobQueue>>,
 }
 
 impl IndexProjectTool {
@@ -157,9 +275,111 @@ impl IndexProjectTool {
             database,
             workspace_manager,
             event_bus,
+            job_queue: None,
         }
     }
 
+    /// Runs full indexing as a background job on `job_queue` instead of blocking the call
+    /// until the project is indexed.
+    pub fn with_job_queue(mut self, job_queue: Arc<dyn IndexingJobQueue>) -> Self {
+        self.job_queue = Some(job_queue);
+        self
+    }
+
+    async fn call_in_background(
+        &self,
+        job_queue: &dyn IndexingJobQueue,
+        workspace_folder_path: String,
+        project_path: String,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let job_id = job_queue
+            .dispatch_project_indexing(&workspace_folder_path, &project_path)
+            .await
+            .map_err(|e| {
+                rmcp::ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to queue indexing job: {e}"),
+                    None,
+                )
+            })?;
+
+        let output = IndexProjectToolJobOutput::new(job_id, project_path);
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+
+    async fn call_incremental(
+        &self,
+        workspace_folder_path: String,
+        project_path: String,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let database = Arc::clone(&self.database);
+        let workspace_manager = Arc::clone(&self.workspace_manager);
+        let event_bus = Arc::clone(&self.event_bus);
+        let reindexed_project_path = project_path.clone();
+
+        let handle = thread::spawn(move || {
+            let runtime = Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| {
+                    rmcp::ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to build tokio runtime: {e}"),
+                        None,
+                    )
+                })?;
+
+            runtime.block_on(async move {
+                let threads = num_cpus::get();
+                let config = IndexingConfigBuilder::build(threads);
+                let mut executor =
+                    IndexingExecutor::new(database, workspace_manager, event_bus, config);
+
+                executor
+                    .execute_project_incremental_reindexing(
+                        &workspace_folder_path,
+                        &reindexed_project_path,
+                        None,
+                    )
+                    .await
+                    .map_err(|e| {
+                        rmcp::ErrorData::new(
+                            ErrorCode::INTERNAL_ERROR,
+                            format!("Incremental re-index failed: {e}"),
+                            None,
+                        )
+                    })
+            })
+        });
+
+        let changes = handle.join().map_err(|_| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                "Indexing thread panicked".to_string(),
+                None,
+            )
+        })??;
+
+        let output = IndexProjectToolIncrementalOutput::new(project_path, changes);
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+
     fn get_system_message(&self, project_stats: &ProjectStatistics) -> Option<String> {
         if project_stats.total_definitions == 0 {
             let mut message = String::new();
@@ -188,6 +408,11 @@ impl KnowledgeGraphTool for IndexProjectTool {
                 "project_absolute_path": {
                     "type": "string",
                     "description": "Absolute filesystem path to the project root directory to re-index. You can use the list_projects tool to get the list of indexed projects.",
+                },
+                "incremental": {
+                    "type": "boolean",
+                    "description": "Only re-index the files git reports as changed instead of the whole project.",
+                    "default": false
                 }
             },
             "required": ["project_absolute_path"]
@@ -206,6 +431,7 @@ impl KnowledgeGraphTool for IndexProjectTool {
         let input = KnowledgeGraphToolInput { params };
 
         let project_absolute_path = input.get_string("project_absolute_path")?;
+        let incremental = input.get_boolean_optional("incremental").unwrap_or(false);
 
         // Resolve workspace for the project
         let project_info = self
@@ -219,11 +445,24 @@ impl KnowledgeGraphTool for IndexProjectTool {
                 )
             })?;
 
+        let workspace_folder_path = project_info.workspace_folder_path.clone();
+        let project_path = project_info.project_path.clone();
+
+        if incremental {
+            return self
+                .call_incremental(workspace_folder_path, project_path)
+                .await;
+        }
+
+        if let Some(job_queue) = &self.job_queue {
+            return self
+                .call_in_background(job_queue.as_ref(), workspace_folder_path, project_path)
+                .await;
+        }
+
         let database = Arc::clone(&self.database);
         let workspace_manager = Arc::clone(&self.workspace_manager);
         let event_bus = Arc::clone(&self.event_bus);
-        let workspace_folder_path = project_info.workspace_folder_path.clone();
-        let project_path = project_info.project_path.clone();
 
         let handle = thread::spawn(move || {
             let runtime = Builder::new_current_thread()
@@ -361,4 +600,111 @@ mod tests {
             "Expected total-definitions element"
         );
     }
+
+    #[test]
+    fn test_index_project_incremental_returns_changed_files() {
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            create_workspace_with_project();
+        let database = Arc::new(KuzuDatabase::new());
+        let event_bus = Arc::new(EventBus::new());
+
+        let tool = IndexProjectTool::new(
+            Arc::clone(&database),
+            Arc::clone(&workspace_manager),
+            Arc::clone(&event_bus),
+        );
+
+        let mut params = JsonObject::new();
+        params.insert(
+            "project_absolute_path".to_string(),
+            Value::String(project_path.clone()),
+        );
+        futures::executor::block_on(tool.call(params.clone())).expect("full index should succeed");
+
+        std::fs::write(
+            std::path::Path::new(&project_path).join("lib/new_helper.rb"),
+            "class NewHelper\n  def help; end\nend\n",
+        )
+        .unwrap();
+
+        params.insert("incremental".to_string(), Value::Bool(true));
+        let result = futures::executor::block_on(tool.call(params))
+            .expect("incremental index should succeed");
+        let text = result.content.unwrap()[0]
+            .raw
+            .as_text()
+            .unwrap()
+            .text
+            .clone();
+
+        assert!(text.contains("<incremental-update>"));
+        assert!(
+            text.contains("<file>lib/new_helper.rb</file>"),
+            "Expected the new file to be re-indexed: {text}"
+        );
+        assert!(!text.contains("<stats>"));
+    }
+
+    struct RecordingJobQueue {
+        dispatched: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl IndexingJobQueue for RecordingJobQueue {
+        async fn dispatch_project_indexing(
+            &self,
+            workspace_folder_path: &str,
+            project_path: &str,
+        ) -> anyhow::Result<String> {
+            self.dispatched
+                .lock()
+                .unwrap()
+                .push((workspace_folder_path.to_string(), project_path.to_string()));
+            Ok("job-1".to_string())
+        }
+
+        fn indexing_job_status(
+            &self,
+            _job_id: &str,
+        ) -> Option<crate::indexing_jobs::IndexingJobStatus> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_index_project_with_job_queue_returns_job_id() {
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            create_workspace_with_project();
+        let job_queue = Arc::new(RecordingJobQueue {
+            dispatched: std::sync::Mutex::new(Vec::new()),
+        });
+
+        let tool = IndexProjectTool::new(
+            Arc::new(KuzuDatabase::new()),
+            Arc::clone(&workspace_manager),
+            Arc::new(EventBus::new()),
+        )
+        .with_job_queue(job_queue.clone());
+
+        let mut params = JsonObject::new();
+        params.insert(
+            "project_absolute_path".to_string(),
+            Value::String(project_path.clone()),
+        );
+        let result = futures::executor::block_on(tool.call(params)).unwrap();
+        let text = result.content.unwrap()[0]
+            .raw
+            .as_text()
+            .unwrap()
+            .text
+            .clone();
+
+        assert!(text.contains("<job-id>job-1</job-id>"));
+        assert!(text.contains("<status>pending</status>"));
+        assert!(!text.contains("<stats>"));
+
+        let dispatched = job_queue.dispatched.lock().unwrap();
+        assert_eq!(dispatched.len(), 1);
+        assert_eq!(dispatched[0].1, project_path);
+    }
 }
@@ -4,7 +4,8 @@ use rmcp::model::{CallToolResult, Content, JsonObject, Tool, object};
 use serde_json::json;
 use workspace_manager::WorkspaceManager;
 
-use crate::tools::types::KnowledgeGraphTool;
+use crate::configuration::McpConfiguration;
+use crate::tools::types::{KnowledgeGraphTool, KnowledgeGraphToolInput};
 use crate::tools::xml::XmlBuilder;
 
 pub const LIST_PROJECTS_TOOL_NAME: &str = "list_projects";
@@ -13,15 +14,23 @@ pub const LIST_PROJECTS_TOOL_DESCRIPTION: &str = r#"Get a list of all projects i
 Useful for:
 - You don't know the absolute filesystem path to the current project root directory.
 - You want to know the indexed projects in the Knowledge Graph.
+- You want the projects of a team or service area, by filtering on the tags given to them.
 "#;
 
 pub struct ListProjectsTool {
     workspace_manager: Arc<WorkspaceManager>,
+    configuration: Arc<McpConfiguration>,
 }
 
 impl ListProjectsTool {
-    pub fn new(workspace_manager: Arc<WorkspaceManager>) -> Self {
-        Self { workspace_manager }
+    pub fn new(
+        workspace_manager: Arc<WorkspaceManager>,
+        configuration: Arc<McpConfiguration>,
+    ) -> Self {
+        Self {
+            workspace_manager,
+            configuration,
+        }
     }
 }
 
@@ -34,7 +43,13 @@ impl KnowledgeGraphTool for ListProjectsTool {
     fn to_mcp_tool(&self) -> Tool {
         let input_schema = json!({
             "type": "object",
-            "properties": {},
+            "properties": {
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only list the projects that have all of these tags.",
+                }
+            },
             "required": []
         });
 
@@ -47,8 +62,16 @@ impl KnowledgeGraphTool for ListProjectsTool {
         }
     }
 
-    async fn call(&self, _params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
-        let projects = self.workspace_manager.list_all_projects();
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params };
+        let tags = input.get_string_array_optional("tags").unwrap_or_default();
+
+        let projects = self
+            .workspace_manager
+            .list_all_projects()
+            .into_iter()
+            .filter(|project| self.configuration.is_project_allowed(&project.project_path))
+            .filter(|project| tags.iter().all(|tag| project.tags.contains(tag)));
 
         let mut builder = XmlBuilder::new();
         builder.start_element("ToolResponse").unwrap();
@@ -75,7 +98,7 @@ mod tests {
     use tempfile::TempDir;
     use testing::repository::TestRepository;
 
-    fn create_test_workspace_manager() -> (Arc<WorkspaceManager>, String) {
+    fn create_test_workspace_manager() -> (TempDir, TempDir, Arc<WorkspaceManager>, String) {
         let temp_workspace_dir = TempDir::new().unwrap();
         let workspace_path = temp_workspace_dir.path().join("test_workspace");
         std::fs::create_dir_all(&workspace_path).unwrap();
@@ -93,14 +116,18 @@ mod tests {
         let projects = manager.list_all_projects();
         let project_path = projects[0].project_path.clone();
 
-        (manager, project_path)
+        (temp_workspace_dir, temp_data_dir, manager, project_path)
     }
 
     #[test]
     fn test_list_projects_tool_functionality() {
-        let (workspace_manager, _project_path) = create_test_workspace_manager();
+        let (_workspace_dir, _data_dir, workspace_manager, _project_path) =
+            create_test_workspace_manager();
 
-        let tool = ListProjectsTool::new(workspace_manager.clone());
+        let tool = ListProjectsTool::new(
+            workspace_manager.clone(),
+            Arc::new(McpConfiguration::default()),
+        );
 
         let empty_params = JsonObject::new();
         let result = futures::executor::block_on(tool.call(empty_params)).unwrap();
@@ -117,4 +144,55 @@ mod tests {
         assert!(xml_data.contains("<project_path>"));
         assert!(xml_data.contains("test_project"));
     }
+
+    #[test]
+    fn test_list_projects_only_lists_allowed_projects() {
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            create_test_workspace_manager();
+
+        let mut configuration = McpConfiguration::default();
+        configuration
+            .allowed_projects
+            .insert("/some/other/workspace".to_string());
+        let tool = ListProjectsTool::new(workspace_manager.clone(), Arc::new(configuration));
+
+        let result = futures::executor::block_on(tool.call(JsonObject::new())).unwrap();
+        let xml_data = result.content.unwrap()[0].as_text().unwrap().text.clone();
+        assert!(!xml_data.contains(&project_path));
+
+        let mut configuration = McpConfiguration::default();
+        configuration.allowed_projects.insert(project_path.clone());
+        let tool = ListProjectsTool::new(workspace_manager, Arc::new(configuration));
+
+        let result = futures::executor::block_on(tool.call(JsonObject::new())).unwrap();
+        let xml_data = result.content.unwrap()[0].as_text().unwrap().text.clone();
+        assert!(xml_data.contains(&project_path));
+    }
+
+    #[test]
+    fn test_list_projects_filters_by_tags() {
+        let (_workspace_dir, _data_dir, workspace_manager, project_path) =
+            create_test_workspace_manager();
+        let project = workspace_manager
+            .get_project_for_path(&project_path)
+            .unwrap();
+        workspace_manager
+            .set_project_tags(
+                &project.workspace_folder_path,
+                &project_path,
+                vec!["payments".to_string(), "team-a".to_string()],
+            )
+            .unwrap();
+        let tool = ListProjectsTool::new(workspace_manager, Arc::new(McpConfiguration::default()));
+
+        let call_with_tags = |tags: serde_json::Value| {
+            let result =
+                futures::executor::block_on(tool.call(object(json!({ "tags": tags })))).unwrap();
+            result.content.unwrap()[0].as_text().unwrap().text.clone()
+        };
+
+        assert!(call_with_tags(json!(["team-a"])).contains(&project_path));
+        assert!(call_with_tags(json!(["team-a", "payments"])).contains(&project_path));
+        assert!(!call_with_tags(json!(["team-a", "search"])).contains(&project_path));
+    }
 }
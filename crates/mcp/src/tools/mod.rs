@@ -1,6 +1,10 @@
 pub mod available_tools_service;
 pub mod file_reader_utils;
+pub mod find_call_cycles;
+pub mod find_implementations;
 pub mod get_definition;
+pub mod get_definition_source;
+pub mod get_index_status;
 pub mod get_references;
 pub mod import_usage;
 pub mod index_project;
@@ -8,6 +12,7 @@ pub mod list_projects;
 pub mod read_definitions;
 pub mod repo_map;
 pub mod search_codebase_definitions;
+pub mod search_file_contents;
 pub mod types;
 pub mod utils;
 pub mod xml;
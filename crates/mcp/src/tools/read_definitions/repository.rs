@@ -13,6 +13,8 @@ pub struct DefinitionQueryResult {
     pub primary_file_path: String,
     pub start_line: i64,
     pub end_line: i64,
+    pub start_byte: i64,
+    pub end_byte: i64,
     pub request_index: usize, // To track which request this result belongs to
 }
 
@@ -55,7 +57,9 @@ impl ReadDefinitionsRepository {
                 d.definition_type as definition_type,
                 d.primary_file_path as primary_file_path,
                 d.start_line as start_line,
-                d.end_line as end_line
+                d.end_line as end_line,
+                d.primary_start_byte as start_byte,
+                d.primary_end_byte as end_byte
             ORDER BY d.start_line
         ";
 
@@ -99,6 +103,8 @@ impl ReadDefinitionsRepository {
                     .to_string(), // primary_file_path (convert to absolute)
                 start_line: row.get_int_value(4).unwrap() + 1,     // start_line, one-indexed
                 end_line: row.get_int_value(5).unwrap() + 1,       // end_line, one-indexed
+                start_byte: row.get_int_value(6).unwrap_or_default(),
+                end_byte: row.get_int_value(7).unwrap_or_default(),
                 request_index,
             });
         }
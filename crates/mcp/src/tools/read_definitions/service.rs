@@ -3,7 +3,7 @@ use std::sync::Arc;
 use database::querying::QueryingService;
 use tokio::time::{Duration, timeout};
 
-use crate::tools::file_reader_utils::read_file_chunks;
+use crate::tools::file_reader_utils::{DefinitionSpan, read_definition_snippets};
 use crate::tools::read_definitions::input::ReadDefinitionsToolInput;
 use crate::tools::read_definitions::output::{
     ReadDefinitionsToolDefinitionOutput, ReadDefinitionsToolOutput,
@@ -39,16 +39,20 @@ impl ReadDefinitionsService {
             )));
         }
 
-        let mut file_chunks = Vec::new();
+        let mut snippets = Vec::new();
         for result in &results {
-            file_chunks.push((
+            snippets.push((
                 result.primary_file_path.clone(),
-                result.start_line as usize,
-                result.end_line as usize,
+                DefinitionSpan::new(
+                    result.start_line as usize,
+                    result.end_line as usize,
+                    result.start_byte,
+                    result.end_byte,
+                ),
             ));
         }
 
-        let file_contents = self.read_definition_chunks(file_chunks).await;
+        let file_contents = self.read_definition_snippets(snippets).await;
 
         let mut file_read_errors = Vec::new();
         let mut definitions = Vec::new();
@@ -97,27 +101,27 @@ impl ReadDefinitionsService {
         })
     }
 
-    async fn read_definition_chunks(
+    async fn read_definition_snippets(
         &self,
-        file_chunks: Vec<(String, usize, usize)>,
+        snippets: Vec<(String, DefinitionSpan)>,
     ) -> Vec<std::io::Result<String>> {
         match timeout(
             Duration::from_secs(FILE_READ_TIMEOUT_SECONDS),
-            read_file_chunks(file_chunks.clone()),
+            read_definition_snippets(snippets.clone()),
         )
         .await
         {
             Ok(Ok(results)) => results,
-            Ok(Err(e)) => file_chunks
+            Ok(Err(e)) => snippets
                 .iter()
                 .map(|_| {
                     Err(std::io::Error::new(
                         e.kind(),
-                        format!("Failed to read file chunks: {e}."),
+                        format!("Failed to read definition snippets: {e}."),
                     ))
                 })
                 .collect(),
-            Err(_) => file_chunks
+            Err(_) => snippets
                 .iter()
                 .map(|_| {
                     Err(std::io::Error::new(
@@ -9,6 +9,7 @@ Useful for:
 Recommendations:
 - Keep depth at 1–2 for large repos to control output size
 - Increase page_size or follow next-page if more results are needed
+- Files are processed in path order; at most max_files files are mapped per call
 "#;
 
 pub const DEFAULT_PAGE: u64 = 1;
@@ -16,4 +17,6 @@ pub const DEFAULT_PAGE_SIZE: u64 = 50;
 pub const MAX_PAGE_SIZE: u64 = 200;
 pub const MIN_PAGE: u64 = 1;
 pub const DEFAULT_DEPTH: u64 = 1;
+pub const DEFAULT_MAX_FILES: u64 = 100;
+pub const MAX_FILES_LIMIT: u64 = 500;
 pub const FILE_READ_TIMEOUT_SECONDS: u64 = 10;
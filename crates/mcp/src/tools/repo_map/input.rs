@@ -1,5 +1,7 @@
 use rmcp::model::{ErrorCode, JsonObject};
 
+use super::constants::DEFAULT_MAX_FILES;
+
 #[derive(Debug, Clone)]
 pub struct RepoMapInput {
     pub project_absolute_path: String,
@@ -9,6 +11,7 @@ pub struct RepoMapInput {
     pub show_definitions: bool,
     pub page: u64,
     pub page_size: u64,
+    pub max_files: u64,
 }
 
 impl TryFrom<JsonObject> for RepoMapInput {
@@ -65,6 +68,11 @@ impl TryFrom<JsonObject> for RepoMapInput {
             .and_then(|v| v.as_u64())
             .unwrap_or(50)
             .max(1);
+        let max_files = params
+            .get("max_files")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_FILES)
+            .max(1);
 
         Ok(Self {
             project_absolute_path,
@@ -74,6 +82,7 @@ impl TryFrom<JsonObject> for RepoMapInput {
             show_definitions,
             page,
             page_size,
+            max_files,
         })
     }
 }
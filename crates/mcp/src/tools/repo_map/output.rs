@@ -108,6 +108,7 @@ pub fn build_repo_map_xml(
     show_definitions: bool,
     next_page: Option<u64>,
     depth: u64,
+    truncated: bool,
     system_message: String,
 ) -> Result<String, Box<dyn std::error::Error>> {
     let grouped = group_items_by_file(items);
@@ -117,6 +118,7 @@ pub fn build_repo_map_xml(
 
     builder.start_element("repo-map")?;
     builder.write_numeric_element("depth", depth)?;
+    builder.write_boolean_element("truncated", truncated)?;
 
     // Directories (ASCII tree)
     if show_directories {
@@ -297,6 +299,7 @@ mod tests {
             true,
             None,
             1,
+            false,
             "msg".to_string(),
         )
         .unwrap();
@@ -312,6 +315,7 @@ mod tests {
             false,
             None,
             1,
+            false,
             "msg".to_string(),
         )
         .unwrap();
@@ -326,6 +330,7 @@ mod tests {
             true,
             None,
             1,
+            false,
             "msg".to_string(),
         )
         .unwrap();
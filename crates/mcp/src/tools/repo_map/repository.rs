@@ -2,6 +2,9 @@ use std::collections::HashSet;
 use std::path::Path;
 
 use ignore::WalkBuilder;
+use rmcp::model::ErrorCode;
+
+use crate::tools::file_reader_utils::resolve_within_project;
 
 // FIXME: this should be a database query
 // In the essence of time, we'll use FS for now
@@ -21,17 +24,20 @@ pub fn collect_paths_ignore(
     let mut has_dir_input = false;
 
     for rel in relative_paths {
-        let abs_path = project_root_canon.join(rel);
-        let canon = match abs_path.canonicalize() {
+        let canon = match resolve_within_project(&project_root_canon, rel) {
             Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                return Err(rmcp::ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("{e}."),
+                    None,
+                ));
+            }
             Err(e) => {
-                log::info!("Skipping path: {} (error: {})", abs_path.display(), e);
+                log::info!("Skipping path: {rel} (error: {e})");
                 continue;
             }
         };
-        if !canon.starts_with(&project_root_canon) {
-            continue;
-        }
 
         if canon.is_file() {
             let s = canon.to_string_lossy().to_string();
@@ -119,6 +125,9 @@ pub fn collect_paths_ignore(
         }
     }
 
+    // Sort so callers capping the number of files truncate predictably
+    files_abs.sort();
+
     let mut directories_rel: Vec<String> = directories_rel_set.into_iter().collect();
     directories_rel.sort();
     Ok((files_abs, directories_rel))
@@ -197,6 +206,31 @@ mod tests {
         assert!(files.iter().any(|f| f.ends_with("app/models/user.ts")));
     }
 
+    #[test]
+    fn test_collect_paths_ignore_returns_sorted_files() {
+        let (_tmp, root) = setup_tree();
+        let (files, _dirs) = collect_paths_ignore(&root, &[".".to_string()], 3).unwrap();
+        let mut sorted = files.clone();
+        sorted.sort();
+        assert_eq!(files, sorted);
+        assert_eq!(files.len(), 4);
+    }
+
+    #[test]
+    fn test_collect_paths_ignore_rejects_paths_outside_project_root() {
+        let (_tmp, root) = setup_tree();
+        let rel = vec!["../".to_string()];
+        let err = collect_paths_ignore(&root, &rel, 1).unwrap_err();
+        assert!(err.message.contains("outside of the project root"));
+    }
+
+    #[test]
+    fn test_collect_paths_ignore_rejects_absolute_paths_outside_project_root() {
+        let (tmp, root) = setup_tree();
+        let outside = tmp.path().parent().unwrap().to_string_lossy().to_string();
+        assert!(collect_paths_ignore(&root, &[outside], 1).is_err());
+    }
+
     #[test]
     fn test_collect_paths_ignore_skips_hidden_git() {
         let (_tmp, root) = setup_tree();
@@ -12,8 +12,8 @@ use crate::tools::types::KnowledgeGraphTool;
 use crate::tools::utils::get_database_path;
 
 use super::constants::{
-    DEFAULT_DEPTH, DEFAULT_PAGE, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, MIN_PAGE,
-    REPO_MAP_TOOL_DESCRIPTION, REPO_MAP_TOOL_NAME,
+    DEFAULT_DEPTH, DEFAULT_MAX_FILES, DEFAULT_PAGE, DEFAULT_PAGE_SIZE, MAX_FILES_LIMIT,
+    MAX_PAGE_SIZE, MIN_PAGE, REPO_MAP_TOOL_DESCRIPTION, REPO_MAP_TOOL_NAME,
 };
 use super::input::RepoMapInput;
 use super::output::{RepoMapItem, build_repo_map_xml};
@@ -48,7 +48,8 @@ impl RepoMapTool {
                 "show_directories": { "type": "boolean", "description": "Whether to include the directories list.", "default": true },
                 "show_definitions": { "type": "boolean", "description": "Whether to include files and their definitions.", "default": true },
                 "page": { "type": "integer", "description": "Page number starting from 1.", "default": DEFAULT_PAGE, "minimum": MIN_PAGE },
-                "page_size": { "type": "integer", "description": "Number of definitions per page (global across all files).", "default": DEFAULT_PAGE_SIZE, "minimum": 1, "maximum": MAX_PAGE_SIZE }
+                "page_size": { "type": "integer", "description": "Number of definitions per page (global across all files).", "default": DEFAULT_PAGE_SIZE, "minimum": 1, "maximum": MAX_PAGE_SIZE },
+                "max_files": { "type": "integer", "description": "Maximum number of files to map, taken in path order. Remaining files are reported as truncated.", "default": DEFAULT_MAX_FILES, "minimum": 1, "maximum": MAX_FILES_LIMIT }
             },
             "required": ["project_absolute_path", "relative_paths"],
             "additionalProperties": false
@@ -85,11 +86,16 @@ impl KnowledgeGraphTool for RepoMapTool {
             .canonicalize()
             .map_err(|e| rmcp::ErrorData::new(ErrorCode::INVALID_REQUEST, e.to_string(), None))?;
 
-        let (expanded_files, collected_directories_rel) =
+        let (mut expanded_files, collected_directories_rel) =
             collect_paths_ignore(&project_root, &input.relative_paths, input.depth)?;
 
+        let total_files = expanded_files.len();
+        let max_files = input.max_files.min(MAX_FILES_LIMIT) as usize;
+        let truncated = total_files > max_files;
+        expanded_files.truncate(max_files);
+
         if expanded_files.is_empty() {
-            let xml = build_repo_map_xml(Vec::new(), collected_directories_rel.clone(), input.show_directories, input.show_definitions, None, input.depth, "No files found within the specified project. Ensure paths are relative to the project root and exist.".to_string())
+            let xml = build_repo_map_xml(Vec::new(), collected_directories_rel.clone(), input.show_directories, input.show_definitions, None, input.depth, false, "No files found within the specified project. Ensure paths are relative to the project root and exist.".to_string())
                 .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
             return Ok(CallToolResult::success(vec![Content::text(xml)]));
         }
@@ -120,6 +126,7 @@ impl KnowledgeGraphTool for RepoMapTool {
                 input.show_definitions,
                 None,
                 input.depth,
+                truncated,
                 msg,
             )
             .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
@@ -198,6 +205,11 @@ impl KnowledgeGraphTool for RepoMapTool {
             None
         };
         let mut message = String::new();
+        if truncated {
+            message.push_str(&format!(
+                "Truncated: mapped the first {max_files} of {total_files} files in path order. Narrow relative_paths or raise max_files (up to {MAX_FILES_LIMIT}) to map the rest."
+            ));
+        }
         let summary = format!(
             "Returned {} definitions from {} input path(s). depth={}.{}",
             items.len(),
@@ -234,6 +246,7 @@ impl KnowledgeGraphTool for RepoMapTool {
             input.show_definitions,
             next_page,
             input.depth,
+            truncated,
             message,
         )
         .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
@@ -449,4 +462,44 @@ mod tests {
             .clone();
         assert!(xml_d2.contains("app/models/user_model.ts"));
     }
+
+    #[tracing_test::traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_repo_map_typescript_e2e_max_files_truncates() {
+        let (_ws_tmp, _data_tmp, workspace_manager, project_path) = setup_ts_workspace();
+        index_project(&workspace_manager, &project_path).await;
+        let database = Arc::new(KuzuDatabase::new());
+        let tool: &dyn KnowledgeGraphTool =
+            &make_tool(Arc::clone(&database), Arc::clone(&workspace_manager));
+
+        let result = tool
+            .call(object(json!({
+                "project_absolute_path": project_path,
+                "relative_paths": ["."],
+                "depth": 2,
+                "max_files": 1,
+            })))
+            .await
+            .unwrap();
+        let xml = result.content.unwrap()[0]
+            .raw
+            .as_text()
+            .unwrap()
+            .text
+            .clone();
+        assert!(xml.contains("<truncated>true</truncated>"));
+        assert!(xml.contains("mapped the first 1 of"));
+        assert_eq!(xml.matches("<file>").count(), 1);
+
+        let result = tool
+            .call(object(json!({
+                "project_absolute_path": project_path,
+                "relative_paths": ["../"],
+            })))
+            .await;
+        assert!(
+            result.is_err(),
+            "Paths outside the project must be rejected"
+        );
+    }
 }
@@ -1,4 +1,9 @@
-use std::{borrow::Cow, cmp::min, path::Path, sync::Arc};
+use std::{
+    borrow::Cow,
+    cmp::{Reverse, min},
+    path::Path,
+    sync::Arc,
+};
 
 use crate::tools::xml::{ToXml, XmlBuilder};
 use database::querying::QueryLibrary;
@@ -22,6 +27,7 @@ Behavior:
 - Supports exact and partial matching.
 - Returns signatures, locations and the definition type of the matching definitions.
 - Large result sets are paginated with the `page` parameter.
+- With `near_file`, definitions in files closer to that file, e.g. in the same directory, are listed first.
 
 Requirements:
 - Provide one or multiple search terms to locate the definitions.
@@ -90,6 +96,9 @@ const MIN_PAGE: u64 = 1;
 
 const CONTEXT_DEFINITION_LINES: usize = 3;
 
+/// Matches fetched per query when all of them are ranked by their proximity to `near_file`
+const PROXIMITY_RANKING_CHUNK_ROWS: u64 = 1000;
+
 const FILE_READ_TIMEOUT_SECONDS: u64 = 10;
 
 #[derive(Serialize)]
@@ -148,6 +157,9 @@ impl ToXml for SearchCodebaseDefinitionsToolOutput {
     }
 }
 
+/// Name, FQN, definition type, file path and one-indexed start and end lines of a match
+type DefinitionMatch = (String, String, String, String, usize, usize);
+
 pub struct SearchCodebaseDefinitionsTool {
     pub query_service: Arc<dyn database::querying::QueryingService>,
     pub workspace_manager: Arc<WorkspaceManager>,
@@ -164,15 +176,14 @@ impl SearchCodebaseDefinitionsTool {
         }
     }
 
-    /// Executes database queries and populates file content in one clean step
-    async fn search_and_populate_content(
+    /// Matches of `search_terms` in the order of the query, `limit` of them from `skip`
+    fn fetch_matches(
         &self,
-        project_absolute_path: &str,
         database_path: &Path,
         search_terms: &[String],
-        page: u64,
-    ) -> Result<SearchCodebaseDefinitionsToolOutput, SearchError> {
-        // Execute a single database query for all search terms
+        skip: u64,
+        limit: u64,
+    ) -> Result<Vec<DefinitionMatch>, SearchError> {
         let query = QueryLibrary::get_search_definitions_query();
         let mut query_params = Map::new();
 
@@ -183,11 +194,8 @@ impl SearchCodebaseDefinitionsTool {
             .collect();
 
         query_params.insert("search_terms".to_string(), Value::Array(lowercase_terms));
-        query_params.insert("limit".to_string(), Value::Number(PAGE_SIZE.into()));
-        query_params.insert(
-            "skip".to_string(),
-            Value::Number(((page - 1) * PAGE_SIZE).into()),
-        );
+        query_params.insert("limit".to_string(), Value::Number(limit.into()));
+        query_params.insert("skip".to_string(), Value::Number(skip.into()));
 
         let mut query_result = self
             .query_service
@@ -197,7 +205,7 @@ impl SearchCodebaseDefinitionsTool {
                 source: None,
             })?;
 
-        let mut query_results = Vec::new();
+        let mut matches = Vec::new();
         while let Some(row) = query_result.next() {
             let name = row.get_string_value(0).unwrap_or_default();
             let fqn = row.get_string_value(1).unwrap_or_default();
@@ -206,18 +214,92 @@ impl SearchCodebaseDefinitionsTool {
             let start_line = row.get_int_value(4).unwrap_or(0) as usize;
             let end_line = row.get_int_value(5).unwrap_or(0) as usize;
 
-            query_results.push((
+            matches.push((
                 name,
                 fqn,
                 definition_type,
-                Path::new(project_absolute_path)
-                    .join(primary_file_path)
-                    .to_string_lossy()
-                    .to_string(),
+                primary_file_path,
                 start_line + 1, // one-indexed
                 end_line + 1,   // one-indexed
             ));
         }
+        Ok(matches)
+    }
+
+    /// All the matches of `search_terms`, fetched `chunk_rows` at a time
+    fn fetch_all_matches(
+        &self,
+        database_path: &Path,
+        search_terms: &[String],
+        chunk_rows: u64,
+    ) -> Result<Vec<DefinitionMatch>, SearchError> {
+        let mut matches = Vec::new();
+        loop {
+            let chunk = self.fetch_matches(
+                database_path,
+                search_terms,
+                matches.len() as u64,
+                chunk_rows,
+            )?;
+            let is_last = (chunk.len() as u64) < chunk_rows;
+            matches.extend(chunk);
+            if is_last {
+                return Ok(matches);
+            }
+        }
+    }
+
+    /// Executes database queries and populates file content in one clean step
+    async fn search_and_populate_content(
+        &self,
+        project_absolute_path: &str,
+        database_path: &Path,
+        search_terms: &[String],
+        page: u64,
+        near_file: Option<&str>,
+    ) -> Result<SearchCodebaseDefinitionsToolOutput, SearchError> {
+        // Ranking by proximity needs all the matches, the page is taken once they're ranked
+        let mut query_results = match near_file {
+            Some(_) => {
+                self.fetch_all_matches(database_path, search_terms, PROXIMITY_RANKING_CHUNK_ROWS)?
+            }
+            None => self.fetch_matches(
+                database_path,
+                search_terms,
+                (page - 1) * PAGE_SIZE,
+                PAGE_SIZE,
+            )?,
+        };
+
+        let has_more = match near_file {
+            Some(near_file) => {
+                let near_file = Path::new(near_file)
+                    .strip_prefix(project_absolute_path)
+                    .map_or(near_file.to_string(), |path| {
+                        path.to_string_lossy().to_string()
+                    });
+                // The stable sort keeps the order of the query among equally close matches
+                query_results.sort_by_key(|(_, _, _, file_path, _, _)| {
+                    Reverse(shared_path_depth(file_path, &near_file))
+                });
+
+                let page_start = ((page - 1) * PAGE_SIZE) as usize;
+                let has_more = query_results.len() > page_start + PAGE_SIZE as usize;
+                query_results = query_results
+                    .into_iter()
+                    .skip(page_start)
+                    .take(PAGE_SIZE as usize)
+                    .collect();
+                has_more
+            }
+            None => query_results.len() == PAGE_SIZE as usize,
+        };
+        for (_, _, _, file_path, _, _) in &mut query_results {
+            *file_path = Path::new(project_absolute_path)
+                .join(file_path.as_str())
+                .to_string_lossy()
+                .to_string();
+        }
 
         if query_results.is_empty() {
             let system_message = self.get_system_message(
@@ -283,11 +365,7 @@ impl SearchCodebaseDefinitionsTool {
             )
             .collect();
 
-        let next_page = if results.len() == PAGE_SIZE as usize {
-            Some(page + 1)
-        } else {
-            None
-        };
+        let next_page = if has_more { Some(page + 1) } else { None };
         let system_message = self.get_system_message(
             search_terms,
             project_absolute_path,
@@ -387,6 +465,10 @@ impl KnowledgeGraphTool for SearchCodebaseDefinitionsTool {
                     "description": "Page number starting from 1. If the response's next_page field is greater than 1, more results are available at that page. You can use this to retrieve more results if more context is needed.",
                     "default": DEFAULT_PAGE,
                     "minimum": MIN_PAGE,
+                },
+                "near_file": {
+                    "type": "string",
+                    "description": "Absolute or project relative path of the file being worked on. Definitions in files sharing more leading directories with it, e.g. in the same directory, are listed first.",
                 }
             },
             "required": ["search_terms", "project_absolute_path"],
@@ -411,11 +493,18 @@ impl KnowledgeGraphTool for SearchCodebaseDefinitionsTool {
         let search_terms = input.get_string_array("search_terms")?;
         let project_absolute_path = input.get_string("project_absolute_path")?;
         let page = input.get_u64("page").unwrap_or(DEFAULT_PAGE).max(MIN_PAGE);
+        let near_file = input.get_string_optional("near_file");
 
         let database_path = get_database_path(&self.workspace_manager, project_absolute_path)?;
 
         let output = self
-            .search_and_populate_content(project_absolute_path, &database_path, &search_terms, page)
+            .search_and_populate_content(
+                project_absolute_path,
+                &database_path,
+                &search_terms,
+                page,
+                near_file,
+            )
             .await
             .map_err(rmcp::ErrorData::from)?;
 
@@ -431,6 +520,15 @@ impl KnowledgeGraphTool for SearchCodebaseDefinitionsTool {
     }
 }
 
+/// Number of leading path components, directories and file name, shared by two paths
+fn shared_path_depth(path: &str, other: &str) -> usize {
+    Path::new(path)
+        .components()
+        .zip(Path::new(other).components())
+        .take_while(|(component, other_component)| component == other_component)
+        .count()
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -440,8 +538,29 @@ mod tests {
     use rmcp::model::object;
     use serde_json::json;
 
+    use super::shared_path_depth;
     use crate::tools::{SearchCodebaseDefinitionsTool, types::KnowledgeGraphTool};
 
+    #[test]
+    fn test_shared_path_depth() {
+        assert_eq!(
+            shared_path_depth("src/auth/login.rs", "src/auth/login.rs"),
+            3
+        );
+        assert_eq!(
+            shared_path_depth("src/auth/session.rs", "src/auth/login.rs"),
+            2
+        );
+        assert_eq!(
+            shared_path_depth("src/billing/invoice.rs", "src/auth/login.rs"),
+            1
+        );
+        assert_eq!(
+            shared_path_depth("lib/auth/login.rs", "src/auth/login.rs"),
+            0
+        );
+    }
+
     #[tracing_test::traced_test]
     #[tokio::test(flavor = "multi_thread")]
     async fn test_search_codebase_definitions_context_lines() {
@@ -659,4 +778,77 @@ mod tests {
 
         setup.cleanup();
     }
+
+    #[tracing_test::traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_search_codebase_definitions_near_file_ranks_all_matches() {
+        let database = Arc::new(KuzuDatabase::new());
+        let setup = setup_java_reference_pipeline(&database).await;
+
+        database
+            .get_or_create_database(&setup.database_path, None)
+            .expect("Failed to create database");
+
+        let search_tool = SearchCodebaseDefinitionsTool::new(
+            Arc::new(DatabaseQueryingService::new(database)),
+            Arc::new(setup.workspace_manager.clone()),
+        );
+        let project = &setup.workspace_manager.clone().list_all_projects()[0];
+        let search_terms = ["matches".to_string(), "repeatedMethod".to_string()];
+
+        // Fetching in chunks smaller than the matches still finds all of them
+        let database_path = std::path::Path::new(&setup.database_path);
+        let all_matches = search_tool
+            .fetch_all_matches(database_path, &search_terms, 1000)
+            .unwrap();
+        assert!(all_matches.len() > 60);
+        assert_eq!(
+            search_tool
+                .fetch_all_matches(database_path, &search_terms, 7)
+                .unwrap(),
+            all_matches
+        );
+
+        // `matches` sorts first by name, the 60 repeated methods of the near file are ranked
+        // before it and push it to the second page
+        let tool: &dyn KnowledgeGraphTool = &search_tool;
+        let near_file = format!(
+            "{}/src/com/example/edgecases/Edgecases.java",
+            project.project_path
+        );
+        let page_xml = |page: u64| {
+            let search_terms = search_terms.clone();
+            let near_file = near_file.clone();
+            async move {
+                let result = tool
+                    .call(object(json!({
+                        "project_absolute_path": project.project_path.clone(),
+                        "search_terms": search_terms,
+                        "near_file": near_file,
+                        "page": page,
+                    })))
+                    .await
+                    .unwrap();
+                match &result.content.expect("Expected content in result")[0].raw {
+                    rmcp::model::RawContent::Text(text_content) => text_content.text.clone(),
+                    _ => panic!("Expected text content"),
+                }
+            }
+        };
+
+        let first_page = page_xml(1).await;
+        assert_eq!(first_page.matches("<definition>").count(), 50);
+        assert!(!first_page.contains("<name>matches</name>"));
+        assert!(first_page.contains("<next-page>2</next-page>"));
+
+        let second_page = page_xml(2).await;
+        assert_eq!(
+            second_page.matches("<definition>").count(),
+            all_matches.len() - 50
+        );
+        assert!(second_page.contains("<name>matches</name>"));
+        assert!(!second_page.contains("<next-page>"));
+
+        setup.cleanup();
+    }
 }
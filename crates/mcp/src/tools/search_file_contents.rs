@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::{borrow::Cow, path::Path, sync::Arc};
+
+use database::kuzu::database::KuzuDatabase;
+use database::kuzu::service::NodeDatabaseService;
+use database::kuzu::types::{DefinitionNodeFromKuzu, FileNodeFromKuzu, KuzuNodeType};
+use regex::{Regex, RegexBuilder};
+use rmcp::model::{CallToolResult, Content, ErrorCode, JsonObject, Tool, object};
+use serde_json::json;
+use workspace_manager::WorkspaceManager;
+
+use crate::tools::types::{KnowledgeGraphTool, KnowledgeGraphToolInput};
+use crate::tools::utils::database_error;
+use crate::tools::xml::{ToXml, XmlBuilder};
+
+pub const SEARCH_FILE_CONTENTS_TOOL_NAME: &str = "search_file_contents";
+const SEARCH_FILE_CONTENTS_TOOL_DESCRIPTION: &str = r#"Search the contents of the indexed files of a project, like grep, and tell which definition encloses each match.
+
+Behavior:
+- Matches `pattern` against each line of the files in the Knowledge Graph, as a literal string unless `regex` is true.
+- Returns the file, line and text of each match along with the innermost definition, e.g. the method, whose lines contain it.
+- The number of files scanned and matches returned is bounded, `truncated` tells whether the search stopped early.
+
+When to use:
+- To find where a string literal, error message or configuration key is used, and in which function.
+
+Example:
+Call:
+{ "project_absolute_path": "/abs/path/to/project", "pattern": "Payment declined" }
+"#;
+
+const DEFAULT_MAX_RESULTS: usize = 50;
+const MAX_RESULTS_LIMIT: usize = 200;
+/// Files scanned per search, in path order, the ones beyond are left out
+const MAX_FILES_SCANNED: usize = 10_000;
+/// Larger files, e.g. generated or minified code, are skipped
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Upper bound, in bytes, of the compiled `regex` pattern
+const MAX_REGEX_SIZE: usize = 1 << 20;
+/// Matched lines are cut to this number of characters
+const MAX_MATCH_TEXT_LENGTH: usize = 200;
+
+pub struct SearchFileContentsTool {
+    database: Arc<KuzuDatabase>,
+    workspace_manager: Arc<WorkspaceManager>,
+}
+
+impl SearchFileContentsTool {
+    pub fn new(database: Arc<KuzuDatabase>, workspace_manager: Arc<WorkspaceManager>) -> Self {
+        Self {
+            database,
+            workspace_manager,
+        }
+    }
+}
+
+struct ContentMatch {
+    file_path: String,
+    /// Zero-based, like the lines of the definitions
+    line: usize,
+    text: String,
+    enclosing_definition: Option<DefinitionNodeFromKuzu>,
+}
+
+struct SearchFileContentsToolOutput {
+    project_path: String,
+    matches: Vec<ContentMatch>,
+    files_scanned: usize,
+    truncated: bool,
+    system_message: String,
+}
+
+impl ToXml for SearchFileContentsToolOutput {
+    fn to_xml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut builder = XmlBuilder::new();
+
+        builder.start_element("ToolResponse")?;
+
+        builder.start_element("matches")?;
+        for content_match in &self.matches {
+            let absolute_file_path = Path::new(&self.project_path)
+                .join(&content_match.file_path)
+                .to_string_lossy()
+                .to_string();
+
+            builder.start_element("match")?;
+            builder.write_element("absolute-file-path", &absolute_file_path)?;
+            builder.write_numeric_element("line", content_match.line + 1)?;
+            builder.write_cdata_element("text", &content_match.text)?;
+            if let Some(definition) = &content_match.enclosing_definition {
+                builder.start_element("enclosing-definition")?;
+                builder.write_element("name", &definition.name)?;
+                builder.write_element("fqn", &definition.fqn)?;
+                builder.write_element("definition-type", &definition.definition_type)?;
+                builder.write_numeric_element("start-line", definition.start_line + 1)?;
+                builder.write_numeric_element("end-line", definition.end_line + 1)?;
+                builder.end_element("enclosing-definition")?;
+            }
+            builder.end_element("match")?;
+        }
+        builder.end_element("matches")?;
+
+        builder.write_numeric_element("files-scanned", self.files_scanned)?;
+        builder.write_boolean_element("truncated", self.truncated)?;
+        builder.write_cdata_element("system-message", &self.system_message)?;
+
+        builder.end_element("ToolResponse")?;
+        builder.finish()
+    }
+}
+
+/// Matches of `regex` in the files at `file_paths`, relative to `project_path`, in order.
+/// Unreadable and oversized files are skipped. Returns at most `max_results` matches and
+/// whether more were found.
+fn scan_files(
+    project_path: &Path,
+    file_paths: &[String],
+    regex: &Regex,
+    max_results: usize,
+) -> (Vec<ContentMatch>, bool) {
+    let mut matches = Vec::new();
+
+    for file_path in file_paths {
+        let absolute_file_path = project_path.join(file_path);
+        let is_too_large = std::fs::metadata(&absolute_file_path)
+            .is_ok_and(|metadata| metadata.len() > MAX_FILE_SIZE);
+        if is_too_large {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&absolute_file_path) else {
+            continue;
+        };
+
+        for (line, text) in content.lines().enumerate() {
+            if !regex.is_match(text) {
+                continue;
+            }
+            if matches.len() == max_results {
+                return (matches, true);
+            }
+            matches.push(ContentMatch {
+                file_path: file_path.clone(),
+                line,
+                text: text.trim().chars().take(MAX_MATCH_TEXT_LENGTH).collect(),
+                enclosing_definition: None,
+            });
+        }
+    }
+
+    (matches, false)
+}
+
+/// The innermost of `definitions` whose lines contain `line`
+fn find_enclosing_definition(
+    definitions: &[DefinitionNodeFromKuzu],
+    line: usize,
+) -> Option<&DefinitionNodeFromKuzu> {
+    definitions
+        .iter()
+        .filter(|definition| {
+            definition.start_line as usize <= line && line <= definition.end_line as usize
+        })
+        .min_by_key(|definition| definition.end_line - definition.start_line)
+}
+
+#[async_trait::async_trait]
+impl KnowledgeGraphTool for SearchFileContentsTool {
+    fn name(&self) -> &str {
+        SEARCH_FILE_CONTENTS_TOOL_NAME
+    }
+
+    fn to_mcp_tool(&self) -> Tool {
+        let input_schema = json!({
+            "type": "object",
+            "properties": {
+                "project_absolute_path": {
+                    "type": "string",
+                    "description": "Absolute path to the indexed project root.",
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Text to search for in each line of the files.",
+                },
+                "regex": {
+                    "type": "boolean",
+                    "description": "Whether `pattern` is a regular expression rather than a literal string.",
+                    "default": false,
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matches to return.",
+                    "default": DEFAULT_MAX_RESULTS,
+                    "minimum": 1,
+                    "maximum": MAX_RESULTS_LIMIT,
+                }
+            },
+            "required": ["project_absolute_path", "pattern"]
+        });
+
+        Tool {
+            name: Cow::Borrowed(SEARCH_FILE_CONTENTS_TOOL_NAME),
+            description: Some(Cow::Borrowed(SEARCH_FILE_CONTENTS_TOOL_DESCRIPTION)),
+            input_schema: Arc::new(object(input_schema)),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    async fn call(&self, params: JsonObject) -> Result<CallToolResult, rmcp::ErrorData> {
+        let input = KnowledgeGraphToolInput { params };
+        let project_absolute_path = input.get_string("project_absolute_path")?;
+        let pattern = input.get_string("pattern")?;
+        let is_regex = input.get_boolean_optional("regex").unwrap_or(false);
+        let max_results = input
+            .get_usize_optional("max_results")
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT);
+
+        let regex = RegexBuilder::new(&if is_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        })
+        .size_limit(MAX_REGEX_SIZE)
+        .dfa_size_limit(MAX_REGEX_SIZE)
+        .build()
+        .map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!("Invalid regular expression: {e}"),
+                None,
+            )
+        })?;
+
+        let project_info = self
+            .workspace_manager
+            .get_project_for_path(project_absolute_path)
+            .ok_or_else(|| {
+                rmcp::ErrorData::new(
+                    ErrorCode::INVALID_REQUEST,
+                    "Project not found in workspace manager".to_string(),
+                    None,
+                )
+            })?;
+
+        let database = self
+            .database
+            .get_indexed_database(&project_info.database_path.to_string_lossy(), None)
+            .map_err(|e| database_error(e, &project_info.project_path))?;
+        let service = NodeDatabaseService::new(&database);
+
+        let mut file_paths: Vec<String> = service
+            .get_all::<FileNodeFromKuzu>(KuzuNodeType::FileNode)
+            .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        file_paths.sort();
+        let files_truncated = file_paths.len() > MAX_FILES_SCANNED;
+        file_paths.truncate(MAX_FILES_SCANNED);
+
+        let project_path = project_info.project_path.clone();
+        let files_scanned = file_paths.len();
+        let (mut matches, results_truncated) = tokio::task::spawn_blocking(move || {
+            scan_files(Path::new(&project_path), &file_paths, &regex, max_results)
+        })
+        .await
+        .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        let mut matched_file_paths: Vec<String> = matches
+            .iter()
+            .map(|content_match| content_match.file_path.clone())
+            .collect();
+        matched_file_paths.dedup();
+        let mut definitions_by_file: HashMap<String, Vec<DefinitionNodeFromKuzu>> = HashMap::new();
+        for definition in service
+            .get_by::<String, DefinitionNodeFromKuzu>(
+                KuzuNodeType::DefinitionNode,
+                "primary_file_path",
+                &matched_file_paths,
+            )
+            .map_err(|e| rmcp::ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
+        {
+            definitions_by_file
+                .entry(definition.primary_file_path.clone())
+                .or_default()
+                .push(definition);
+        }
+        for content_match in &mut matches {
+            content_match.enclosing_definition = definitions_by_file
+                .get(&content_match.file_path)
+                .and_then(|definitions| find_enclosing_definition(definitions, content_match.line))
+                .cloned();
+        }
+
+        let truncated = files_truncated || results_truncated;
+        let system_message = if matches.is_empty() {
+            format!("No matches of {pattern} were found in {files_scanned} file(s).")
+        } else if results_truncated {
+            format!(
+                "Showing the first {max_results} matches of {pattern}, more were found. Increase max_results or narrow the pattern to see them."
+            )
+        } else if files_truncated {
+            format!(
+                "Found {} match(es) of {pattern} in the first {MAX_FILES_SCANNED} files, the other files were not scanned.",
+                matches.len()
+            )
+        } else {
+            format!(
+                "Found {} match(es) of {pattern} in {files_scanned} file(s).",
+                matches.len()
+            )
+        };
+
+        let output = SearchFileContentsToolOutput {
+            project_path: project_info.project_path,
+            matches,
+            files_scanned,
+            truncated,
+            system_message,
+        };
+
+        let xml_output = output.to_xml_without_cdata().map_err(|e| {
+            rmcp::ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to convert output to XML: {e}"),
+                None,
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(xml_output)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::kuzu::connection::KuzuConnection;
+    use database::schema::manager::SchemaManager;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    #[tokio::test]
+    async fn test_search_file_contents_finds_enclosing_definitions() {
+        let database = Arc::new(KuzuDatabase::new());
+        let temp_workspace_dir = TempDir::new().unwrap();
+        let workspace_path = temp_workspace_dir.path().join("workspace");
+        TestRepository::new(&workspace_path.join("test-repo"), Some("test-repo"));
+
+        let temp_data_dir = TempDir::new().unwrap();
+        let workspace_manager = Arc::new(
+            WorkspaceManager::new_with_directory(temp_data_dir.path().to_path_buf()).unwrap(),
+        );
+        workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap();
+        let project_info = workspace_manager.list_all_projects()[0].clone();
+        let project_path = project_info.project_path.clone();
+
+        std::fs::write(
+            Path::new(&project_path).join("billing.rb"),
+            "# Payment declined is logged below\nclass Billing\n  def charge\n    raise \"Payment declined\"\n  end\nend\n",
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(project_info.database_path.parent().unwrap()).unwrap();
+        let project_database = database
+            .force_new_database(&project_info.database_path.to_string_lossy(), None)
+            .unwrap();
+        SchemaManager::new(&project_database)
+            .initialize_schema()
+            .unwrap();
+        let connection = KuzuConnection::new(&project_database).unwrap();
+        connection
+            .execute_ddl("CREATE (:FileNode {id: 1, path: 'billing.rb', name: 'billing.rb'})")
+            .unwrap();
+        for (id, name, fqn, definition_type, start_line, end_line) in [
+            (1, "Billing", "Billing", "Class", 1, 5),
+            (2, "charge", "Billing#charge", "InstanceMethod", 2, 4),
+        ] {
+            connection
+                .execute_ddl(&format!(
+                    "CREATE (:DefinitionNode {{id: {id}, name: '{name}', fqn: '{fqn}', definition_type: '{definition_type}', primary_file_path: 'billing.rb', start_line: {start_line}, end_line: {end_line}}})"
+                ))
+                .unwrap();
+        }
+
+        let tool = SearchFileContentsTool::new(Arc::clone(&database), workspace_manager);
+        let result = tool
+            .call(object(json!({
+                "project_absolute_path": project_path,
+                "pattern": "Payment declined",
+            })))
+            .await
+            .unwrap();
+        let xml = result.content.unwrap()[0].as_text().unwrap().text.clone();
+
+        assert_eq!(xml.matches("<match>").count(), 2);
+        assert!(xml.contains("<line>4</line>"));
+        assert!(xml.contains("raise \"Payment declined\""));
+        assert!(xml.contains("<fqn>Billing#charge</fqn>"));
+        assert_eq!(xml.matches("<enclosing-definition>").count(), 1);
+        assert!(xml.contains("<files-scanned>1</files-scanned>"));
+        assert!(xml.contains("<truncated>false</truncated>"));
+
+        let result = tool
+            .call(object(json!({
+                "project_absolute_path": project_path,
+                "pattern": "Payment (",
+                "regex": true,
+            })))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_find_enclosing_definition_prefers_innermost() {
+        let definition = |fqn: &str, start_line, end_line| DefinitionNodeFromKuzu {
+            fqn: fqn.to_string(),
+            start_line,
+            end_line,
+            ..DefinitionNodeFromKuzu::empty()
+        };
+        let definitions = [
+            definition("Billing", 0, 20),
+            definition("Billing#charge", 2, 6),
+            definition("Billing#refund", 8, 12),
+        ];
+
+        let fqn_at = |line| find_enclosing_definition(&definitions, line).map(|d| d.fqn.as_str());
+        assert_eq!(fqn_at(4), Some("Billing#charge"));
+        assert_eq!(fqn_at(7), Some("Billing"));
+        assert_eq!(fqn_at(30), None);
+    }
+}
@@ -93,6 +93,10 @@ impl KnowledgeGraphToolInput {
         self.params.get(key).and_then(|v| v.as_bool())
     }
 
+    pub fn get_string_optional(&self, key: &str) -> Option<&str> {
+        self.params.get(key).and_then(|v| v.as_str())
+    }
+
     pub fn get_string_array_optional(&self, key: &str) -> Option<Vec<String>> {
         self.params
             .get(key)
@@ -3,9 +3,14 @@ use std::{
     sync::Arc,
 };
 
-use rmcp::model::ErrorCode;
+use database::kuzu::types::DatabaseError;
+use rmcp::model::{ErrorCode, JsonObject};
 use workspace_manager::WorkspaceManager;
 
+use crate::configuration::McpConfiguration;
+use crate::tools::file_reader_utils::resolve_within_project;
+use crate::tools::index_project::INDEX_PROJECT_TOOL_NAME;
+
 // File management utils
 
 pub fn resolve_paths(
@@ -19,8 +24,9 @@ pub fn resolve_paths(
         // Try to find a project containing this relative path
         let mut found: Option<PathBuf> = None;
         for project in workspace_manager.list_all_projects() {
-            let candidate = Path::new(&project.project_path).join(input_file_path);
-            if candidate.exists() {
+            if let Ok(candidate) =
+                resolve_within_project(Path::new(&project.project_path), input_file_path)
+            {
                 found = Some(candidate);
                 break;
             }
@@ -49,8 +55,14 @@ pub fn resolve_paths(
             )
         })?;
 
-    let relative = Path::new(&abs_path_str)
-        .strip_prefix(&project_info.project_path)
+    let abs_path = resolve_within_project(Path::new(&project_info.project_path), &abs_path_str)
+        .map_err(|e| rmcp::ErrorData::new(ErrorCode::INVALID_REQUEST, e.to_string(), None))?;
+    let project_root = Path::new(&project_info.project_path)
+        .canonicalize()
+        .map_err(|e| rmcp::ErrorData::new(ErrorCode::INVALID_REQUEST, e.to_string(), None))?;
+
+    let relative = abs_path
+        .strip_prefix(&project_root)
         .map_err(|_| {
             rmcp::ErrorData::new(
                 ErrorCode::INVALID_REQUEST,
@@ -61,7 +73,73 @@ pub fn resolve_paths(
         .to_string_lossy()
         .to_string();
 
-    Ok((PathBuf::from(abs_path_str), project_info, relative))
+    Ok((abs_path, project_info, relative))
+}
+
+// Project access utils
+
+const PROJECT_PATH_PARAMS: [&str; 1] = ["project_absolute_path"];
+const FILE_PATH_PARAMS: [&str; 2] = ["file_path", "absolute_file_path"];
+const NESTED_FILE_PATH_PARAMS: [&str; 1] = ["definitions"];
+
+/// Rejects tool calls whose project or file path params point outside the configured
+/// project allowlist. Paths that don't resolve to a registered project are left to the tool.
+pub fn check_project_access(
+    configuration: &McpConfiguration,
+    workspace_manager: &WorkspaceManager,
+    params: &JsonObject,
+) -> Result<(), rmcp::ErrorData> {
+    if configuration.allowed_projects.is_empty() {
+        return Ok(());
+    }
+
+    let mut project_paths = Vec::new();
+    for key in PROJECT_PATH_PARAMS {
+        if let Some(path) = params.get(key).and_then(|v| v.as_str()) {
+            let path = Path::new(path)
+                .canonicalize()
+                .unwrap_or_else(|_| PathBuf::from(path));
+            project_paths.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    let mut file_paths: Vec<&str> = FILE_PATH_PARAMS
+        .iter()
+        .filter_map(|key| params.get(*key).and_then(|v| v.as_str()))
+        .collect();
+    for key in NESTED_FILE_PATH_PARAMS {
+        for item in params
+            .get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            file_paths.extend(
+                FILE_PATH_PARAMS
+                    .iter()
+                    .filter_map(|key| item.get(*key).and_then(|v| v.as_str())),
+            );
+        }
+    }
+    for file_path in file_paths {
+        if let Ok((_, project_info, _)) = resolve_paths(workspace_manager, file_path) {
+            project_paths.push(project_info.project_path);
+        }
+    }
+
+    match project_paths
+        .into_iter()
+        .find(|path| !configuration.is_project_allowed(path))
+    {
+        Some(path) => Err(rmcp::ErrorData::new(
+            ErrorCode::INVALID_REQUEST,
+            format!(
+                "Project {path} is not in the MCP server's allowed projects. Use the `list_projects` tool to see the projects that can be accessed."
+            ),
+            None,
+        )),
+        None => Ok(()),
+    }
 }
 
 // Database management utils
@@ -84,3 +162,91 @@ pub fn get_database_path(
 
     Ok(database_path.unwrap())
 }
+
+/// Turns a failure to open the database of a project into a tool error. A project that was
+/// registered but never indexed, or whose database was deleted, gets a `RESOURCE_NOT_FOUND`
+/// error asking for it to be indexed, so it isn't mistaken for a server failure.
+pub fn database_error(error: DatabaseError, project_path: &str) -> rmcp::ErrorData {
+    match error {
+        DatabaseError::NotIndexed { .. } => rmcp::ErrorData::new(
+            ErrorCode::RESOURCE_NOT_FOUND,
+            format!(
+                "Project {project_path} has not been indexed yet. Index it with the `{INDEX_PROJECT_TOOL_NAME}` tool first."
+            ),
+            None,
+        ),
+        e => rmcp::ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to get database for project: {e}"),
+            None,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::kuzu::database::KuzuDatabase;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    fn params(value: serde_json::Value) -> JsonObject {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_check_project_access_rejects_projects_outside_allowlist() {
+        let temp_workspace_dir = TempDir::new().unwrap();
+        let workspace_path = temp_workspace_dir.path().join("workspace");
+        TestRepository::new(&workspace_path.join("project"), Some("test-repo"));
+
+        let temp_data_dir = TempDir::new().unwrap();
+        let workspace_manager =
+            WorkspaceManager::new_with_directory(temp_data_dir.path().to_path_buf()).unwrap();
+        workspace_manager
+            .register_workspace_folder(&workspace_path)
+            .unwrap();
+        let project_path = workspace_manager.list_all_projects()[0]
+            .project_path
+            .clone();
+
+        let request = params(json!({ "project_absolute_path": project_path }));
+        let nested_request = params(json!({
+            "definitions": [{ "names": ["main"], "file_path": "main.rb" }]
+        }));
+
+        let allow_all = McpConfiguration::default();
+        assert!(check_project_access(&allow_all, &workspace_manager, &request).is_ok());
+
+        let mut allow_other = McpConfiguration::default();
+        allow_other
+            .allowed_projects
+            .insert("/some/other/workspace".to_string());
+        assert!(check_project_access(&allow_other, &workspace_manager, &request).is_err());
+        assert!(check_project_access(&allow_other, &workspace_manager, &nested_request).is_err());
+
+        let mut allow_workspace = McpConfiguration::default();
+        allow_workspace.allowed_projects.insert(
+            workspace_path
+                .canonicalize()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+        );
+        assert!(check_project_access(&allow_workspace, &workspace_manager, &request).is_ok());
+        assert!(
+            check_project_access(&allow_workspace, &workspace_manager, &nested_request).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_database_error_asks_to_index_the_project() {
+        let error = KuzuDatabase::new()
+            .get_indexed_database("/no/such/project/database.kz", None)
+            .unwrap_err();
+        let error = database_error(error, "/no/such/project");
+        assert_eq!(error.code, ErrorCode::RESOURCE_NOT_FOUND);
+        assert!(error.message.contains(INDEX_PROJECT_TOOL_NAME));
+    }
+}
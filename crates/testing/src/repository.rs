@@ -32,6 +32,37 @@ impl TestRepository {
         }
     }
 
+    /// Creates a Git repository at `dir` with an initial commit of `files`, given as
+    /// paths relative to `dir` and their contents.
+    pub fn with_files(dir: &Path, files: &[(&str, &str)]) -> Self {
+        for (path, content) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create file directory");
+            fs::write(path, content).expect("Failed to write file");
+        }
+        create_git_repo_structure(dir);
+        initialize_git_repo(dir);
+
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Runs `git` with `args` in the repository, e.g. to commit further changes or add a tag
+    pub fn git(&self, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.dir)
+            .output()
+            .expect("Failed to run git");
+        assert!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     /// Creates a minimal Git repository without adding or committing files.
     /// This is useful for large repositories where the initial commit would be slow.
     pub fn new_minimal(dir: &Path) -> Self {
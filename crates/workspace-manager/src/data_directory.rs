@@ -19,7 +19,12 @@
 //! │   │   │   ├── database.kz
 //! │   │   │   ├── parquet_files/
 //! ├── gkg_manifest.json
+//! ├── gkg_job_queue.json
 //! ```
+//!
+//! The Parquet files can be kept apart from the databases by setting a parquet root with
+//! [`DataDirectory::with_parquet_root`]. They are then written to
+//! `<parquet_root>/gkg_workspace_folders/<workspace_folder_hash>/<project_hash>/` instead.
 
 use crate::errors::{Result, WorkspaceManagerError};
 use std::path::{Path, PathBuf};
@@ -27,8 +32,11 @@ use std::path::{Path, PathBuf};
 const GKG_DATA_DIR_NAME: &str = ".gkg";
 const GKG_WORKSPACE_FOLDERS_NAME: &str = "gkg_workspace_folders";
 const GKG_MANIFEST_FILE_NAME: &str = "gkg_manifest.json";
+const GKG_JOB_QUEUE_FILE_NAME: &str = "gkg_job_queue.json";
 const GKG_KUZU_DB_NAME: &str = "database.kz";
 const GKG_PARQUET_FILES_NAME: &str = "parquet_files";
+const GKG_CROSS_PROJECT_LINKS_FILE_NAME: &str = "cross_project_links.json";
+const GKG_DEFINITION_SNAPSHOTS_NAME: &str = "definition_snapshots";
 
 /// Manages the centralized data directory for the Knowledge Graph framework
 #[derive(Debug, Clone)]
@@ -36,6 +44,8 @@ pub struct DataDirectory {
     pub root_path: PathBuf,
     pub workspace_folders_dir: PathBuf,
     pub manifest_path: PathBuf,
+    /// Where the Parquet files are written, next to the databases when `None`
+    pub parquet_root: Option<PathBuf>,
 }
 
 impl DataDirectory {
@@ -51,26 +61,55 @@ impl DataDirectory {
             root_path,
             workspace_folders_dir,
             manifest_path,
+            parquet_root: None,
         };
         data_dir.ensure_directory_structure()?;
         Ok(data_dir)
     }
 
+    /// Writes the Parquet files below `parquet_root` instead of next to the databases.
+    pub fn with_parquet_root(mut self, parquet_root: PathBuf) -> Self {
+        self.parquet_root = Some(parquet_root);
+        self
+    }
+
     pub fn get_system_data_directory() -> Result<PathBuf> {
         dirs::home_dir()
             .map(|data_dir| data_dir.join(GKG_DATA_DIR_NAME))
             .ok_or(WorkspaceManagerError::SystemDataDirectoryNotFound)
     }
 
+    /// File where the server keeps jobs that haven't finished yet, so they survive restarts
+    pub fn job_queue_path(&self) -> PathBuf {
+        self.root_path.join(GKG_JOB_QUEUE_FILE_NAME)
+    }
+
     pub fn workspace_folder_data_directory(&self, workspace_folder_name: &str) -> PathBuf {
         self.workspace_folders_dir.join(workspace_folder_name)
     }
 
+    /// File holding the imports that resolve to definitions of another project of the
+    /// workspace folder
+    pub fn cross_project_links_path(&self, workspace_folder_name: &str) -> PathBuf {
+        self.workspace_folder_data_directory(workspace_folder_name)
+            .join(GKG_CROSS_PROJECT_LINKS_FILE_NAME)
+    }
+
     pub fn project_directory(&self, workspace_folder_name: &str, project_name: &str) -> PathBuf {
         self.workspace_folder_data_directory(workspace_folder_name)
             .join(project_name)
     }
 
+    /// Directory holding the snapshots of a project's definitions, one per index run
+    pub fn project_snapshots_directory(
+        &self,
+        workspace_folder_name: &str,
+        project_name: &str,
+    ) -> PathBuf {
+        self.project_directory(workspace_folder_name, project_name)
+            .join(GKG_DEFINITION_SNAPSHOTS_NAME)
+    }
+
     pub fn project_database_path(
         &self,
         workspace_folder_name: &str,
@@ -85,8 +124,22 @@ impl DataDirectory {
         workspace_folder_name: &str,
         project_name: &str,
     ) -> PathBuf {
-        self.project_directory(workspace_folder_name, project_name)
-            .join(GKG_PARQUET_FILES_NAME)
+        match self.parquet_workspace_folders_dir() {
+            Some(parquet_workspace_folders_dir) => parquet_workspace_folders_dir
+                .join(workspace_folder_name)
+                .join(project_name),
+            None => self
+                .project_directory(workspace_folder_name, project_name)
+                .join(GKG_PARQUET_FILES_NAME),
+        }
+    }
+
+    /// The directory holding the Parquet files of all workspace folders when they are kept
+    /// outside of the data directory.
+    pub fn parquet_workspace_folders_dir(&self) -> Option<PathBuf> {
+        self.parquet_root
+            .as_ref()
+            .map(|parquet_root| parquet_root.join(GKG_WORKSPACE_FOLDERS_NAME))
     }
 
     pub fn ensure_directory_structure(&self) -> Result<()> {
@@ -172,6 +225,17 @@ impl DataDirectory {
             );
         }
 
+        if let Some(parquet_workspace_folders_dir) = self.parquet_workspace_folders_dir() {
+            let parquet_dir = parquet_workspace_folders_dir.join(data_directory_name);
+            if parquet_dir.exists() {
+                std::fs::remove_dir_all(&parquet_dir)?;
+                log::info!(
+                    "Removed workspace folder parquet directory: {}",
+                    parquet_dir.display()
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -187,6 +251,17 @@ impl DataDirectory {
             log::info!("Removed project directory: {}", project_dir.display());
         }
 
+        if self.parquet_root.is_some() {
+            let parquet_dir = self.project_parquet_directory(workspace_folder_name, project_name);
+            if parquet_dir.exists() {
+                std::fs::remove_dir_all(&parquet_dir)?;
+                log::info!(
+                    "Removed project parquet directory: {}",
+                    parquet_dir.display()
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -201,7 +276,23 @@ impl DataDirectory {
         project_name: &str,
     ) -> Result<u64> {
         let project_dir = self.project_directory(workspace_folder_name, project_name);
-        Self::calculate_directory_size(&project_dir)
+        let mut size = Self::calculate_directory_size(&project_dir)?;
+        if self.parquet_root.is_some() {
+            let parquet_dir = self.project_parquet_directory(workspace_folder_name, project_name);
+            size += Self::calculate_directory_size(&parquet_dir)?;
+        }
+        Ok(size)
+    }
+
+    /// Bytes taken by the indexed data, i.e. everything `WorkspaceManager::clean` removes: the
+    /// workspace folders, their Parquet files when kept apart, and the manifest
+    pub fn total_disk_usage(&self) -> Result<u64> {
+        let mut size = Self::calculate_directory_size(&self.workspace_folders_dir)?;
+        if let Some(parquet_workspace_folders_dir) = self.parquet_workspace_folders_dir() {
+            size += Self::calculate_directory_size(&parquet_workspace_folders_dir)?;
+        }
+        size += std::fs::metadata(&self.manifest_path).map_or(0, |metadata| metadata.len());
+        Ok(size)
     }
 
     // Note: kuzu_db typically saves its "database" as a directory with the same name as the database.
@@ -238,6 +329,22 @@ impl DataDirectory {
         Ok(total_size.load(Ordering::Relaxed))
     }
 
+    /// Size on disk of a project database, including its write-ahead log, or `None` when the
+    /// project has no database yet
+    pub fn database_size(database_path: &Path) -> Option<u64> {
+        let metadata = std::fs::metadata(database_path).ok()?;
+        let size = if metadata.is_dir() {
+            Self::calculate_directory_size(database_path).ok()?
+        } else {
+            metadata.len()
+        };
+
+        let mut wal_path = database_path.as_os_str().to_owned();
+        wal_path.push(".wal");
+        let wal_size = std::fs::metadata(wal_path).map_or(0, |metadata| metadata.len());
+        Some(size + wal_size)
+    }
+
     pub fn list_workspace_folder_directories(&self) -> Result<Vec<String>> {
         let workspace_folder_dir = &self.workspace_folders_dir;
 
@@ -478,6 +585,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_separate_parquet_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let parquet_root = TempDir::new().unwrap();
+        let data_dir = DataDirectory::new(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_parquet_root(parquet_root.path().to_path_buf());
+
+        let workspace_name = "test-workspace";
+        let project_name = "test-project";
+        data_dir
+            .ensure_project_directory(workspace_name, project_name)
+            .unwrap();
+
+        let parquet_dir = data_dir.project_parquet_directory(workspace_name, project_name);
+        assert_eq!(
+            parquet_dir,
+            parquet_root
+                .path()
+                .join(GKG_WORKSPACE_FOLDERS_NAME)
+                .join(workspace_name)
+                .join(project_name)
+        );
+        assert!(parquet_dir.exists());
+        assert_eq!(
+            data_dir.project_database_path(workspace_name, project_name),
+            data_dir
+                .project_directory(workspace_name, project_name)
+                .join(GKG_KUZU_DB_NAME)
+        );
+        assert!(
+            !data_dir
+                .project_directory(workspace_name, project_name)
+                .join(GKG_PARQUET_FILES_NAME)
+                .exists()
+        );
+
+        data_dir
+            .remove_project_directory(workspace_name, project_name)
+            .unwrap();
+        assert!(!parquet_dir.exists());
+    }
+
     #[test]
     fn test_remove_workspace_folder_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -555,6 +705,39 @@ mod tests {
         assert_eq!(non_existent_size, 0);
     }
 
+    #[test]
+    fn test_total_disk_usage_counts_separate_parquet_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let parquet_root = TempDir::new().unwrap();
+        let data_dir = DataDirectory::new(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_parquet_root(parquet_root.path().to_path_buf());
+        assert_eq!(data_dir.total_disk_usage().unwrap(), 0);
+
+        data_dir
+            .ensure_project_directory("workspace", "project")
+            .unwrap();
+        fs::write(
+            data_dir
+                .project_directory("workspace", "project")
+                .join("database.kz"),
+            "0123456789",
+        )
+        .unwrap();
+        fs::write(
+            data_dir
+                .project_parquet_directory("workspace", "project")
+                .join("definitions.parquet"),
+            "01234",
+        )
+        .unwrap();
+        fs::write(&data_dir.manifest_path, "{}").unwrap();
+        // Files outside of the indexed data aren't counted
+        fs::write(temp_dir.path().join("logs.log"), "not counted").unwrap();
+
+        assert_eq!(data_dir.total_disk_usage().unwrap(), 17);
+    }
+
     #[test]
     fn test_nested_directory_size_calculation() {
         let temp_dir = TempDir::new().unwrap();
@@ -640,6 +823,19 @@ mod tests {
         assert_eq!(project_size, 0);
     }
 
+    #[test]
+    fn test_database_size_includes_the_write_ahead_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let database_path = temp_dir.path().join("database.kz");
+        assert_eq!(DataDirectory::database_size(&database_path), None);
+
+        fs::write(&database_path, vec![0; 4096]).unwrap();
+        assert_eq!(DataDirectory::database_size(&database_path), Some(4096));
+
+        fs::write(temp_dir.path().join("database.kz.wal"), vec![0; 100]).unwrap();
+        assert_eq!(DataDirectory::database_size(&database_path), Some(4196));
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(0), "0 B");
@@ -1,5 +1,6 @@
 //! Error types for the workspace-manager crate
 
+use crate::manifest::Status;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -24,4 +25,16 @@ pub enum WorkspaceManagerError {
     /// Failed to determine system data directory
     #[error("Failed to determine system data directory")]
     SystemDataDirectoryNotFound,
+
+    /// No project is registered at the path
+    #[error("Project not found: {path}, it must be indexed before it can be queried")]
+    ProjectNotFound { path: String },
+
+    /// The project has no complete graph to query
+    #[error("Project {path} is {status}, it must be indexed before it can be queried")]
+    ProjectNotQueryable { path: String, status: Status },
+
+    /// None of the projects of a workspace folder has a complete graph to query
+    #[error("No indexed project in workspace {path}, it must be indexed before it can be queried")]
+    NoQueryableProjects { path: String },
 }
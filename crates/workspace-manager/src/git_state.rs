@@ -0,0 +1,127 @@
+//! Reads the state of a project's git checkout, used to tell whether a project changed since it
+//! was indexed. Shells out to `git`; every function answers `None`/`false` when git or the
+//! repository isn't available.
+
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::process::Command;
+
+fn git(project_path: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(args)
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// The commit checked out in the project, `None` for a repository without commits
+pub fn head_commit(project_path: &Path) -> Option<String> {
+    let stdout = git(project_path, &["rev-parse", "--verify", "--quiet", "HEAD"])?;
+    let commit = String::from_utf8(stdout).ok()?.trim().to_string();
+    (!commit.is_empty()).then_some(commit)
+}
+
+/// Whether a modified or untracked file of the working tree was written after `since`.
+/// Deleted files have no modification time and aren't taken into account.
+pub fn has_changes_since(project_path: &Path, since: DateTime<Utc>) -> bool {
+    let Some(stdout) = git(
+        project_path,
+        &["status", "--porcelain=v1", "-z", "--untracked-files=all"],
+    ) else {
+        return false;
+    };
+
+    // Entries are `XY path`, renames and copies are followed by the original path
+    let mut entries = stdout.split(|byte| *byte == 0);
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        if matches!(entry[0], b'R' | b'C') {
+            entries.next();
+        }
+
+        let relative_path = String::from_utf8_lossy(&entry[3..]);
+        let modified_at = std::fs::metadata(project_path.join(relative_path.as_ref()))
+            .and_then(|metadata| metadata.modified());
+        if let Ok(modified_at) = modified_at
+            && DateTime::<Utc>::from(modified_at) > since
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether the project's checkout differs from the one that was indexed: another commit is
+/// checked out, or files were changed after `indexed_at`
+pub fn has_changed_since_indexing(
+    project_path: &Path,
+    indexed_commit: Option<&str>,
+    indexed_at: DateTime<Utc>,
+) -> bool {
+    if let Some(indexed_commit) = indexed_commit
+        && head_commit(project_path).is_some_and(|head| head != indexed_commit)
+    {
+        return true;
+    }
+    has_changes_since(project_path, indexed_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use testing::repository::TestRepository;
+
+    fn create_repository(temp_dir: &TempDir) -> TestRepository {
+        TestRepository::with_files(temp_dir.path(), &[("main.rb", "class Billing; end\n")])
+    }
+
+    #[test]
+    fn test_head_change_is_detected() {
+        let temp_dir = TempDir::new().unwrap();
+        let repository = create_repository(&temp_dir);
+        let path = temp_dir.path();
+        let indexed_commit = head_commit(path).unwrap();
+        let indexed_at = Utc::now();
+        assert!(!has_changed_since_indexing(
+            path,
+            Some(&indexed_commit),
+            indexed_at
+        ));
+
+        repository.git(&["commit", "--quiet", "--allow-empty", "-m", "Next"]);
+        assert!(has_changed_since_indexing(
+            path,
+            Some(&indexed_commit),
+            indexed_at
+        ));
+    }
+
+    #[test]
+    fn test_working_tree_changes_after_indexing_are_detected() {
+        let temp_dir = TempDir::new().unwrap();
+        create_repository(&temp_dir);
+        let path = temp_dir.path();
+        std::fs::write(
+            path.join("main.rb"),
+            "class Billing; def refund; end; end\n",
+        )
+        .unwrap();
+        std::fs::write(path.join("refunds.rb"), "class Refunds; end\n").unwrap();
+
+        assert!(has_changes_since(
+            path,
+            Utc::now() - chrono::Duration::minutes(1)
+        ));
+        // Changed before the project was indexed
+        assert!(!has_changes_since(
+            path,
+            Utc::now() + chrono::Duration::minutes(1)
+        ));
+        assert!(!has_changes_since(Path::new("/nonexistent"), Utc::now()));
+    }
+}
@@ -71,6 +71,7 @@
 
 pub mod data_directory;
 pub mod errors;
+pub mod git_state;
 pub mod manifest;
 pub mod state_service;
 pub mod workspace_manager;
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Status of a workspace folder or project
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -13,6 +14,10 @@ pub enum Status {
     Reindexing,
     Error,
     Pending,
+    /// Indexed with an older graph schema, the project needs a full reindex
+    Outdated,
+    /// Indexed, but the project's checkout changed since, the graph may miss recent changes
+    Stale,
 }
 
 impl fmt::Display for Status {
@@ -23,6 +28,27 @@ impl fmt::Display for Status {
             Status::Reindexing => write!(f, "reindexing"),
             Status::Error => write!(f, "error"),
             Status::Pending => write!(f, "pending"),
+            Status::Outdated => write!(f, "outdated"),
+            Status::Stale => write!(f, "stale"),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "indexed" => Ok(Status::Indexed),
+            "indexing" => Ok(Status::Indexing),
+            "reindexing" => Ok(Status::Reindexing),
+            "error" => Ok(Status::Error),
+            "pending" => Ok(Status::Pending),
+            "outdated" => Ok(Status::Outdated),
+            "stale" => Ok(Status::Stale),
+            _ => Err(format!(
+                "unknown status `{s}`, expected one of: indexed, indexing, reindexing, error, pending, outdated, stale"
+            )),
         }
     }
 }
@@ -44,6 +70,15 @@ pub struct ProjectMetadata {
     pub status: Status,
     /// Error message if status is Error
     pub error_message: Option<String>,
+    /// Version of the graph schema the project's database was built with
+    #[serde(default)]
+    pub schema_version: Option<u32>,
+    /// Labels used to group projects, e.g. by team or service area
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Commit checked out when the project was last indexed
+    #[serde(default)]
+    pub indexed_commit: Option<String>,
 }
 
 impl ProjectMetadata {
@@ -53,6 +88,9 @@ impl ProjectMetadata {
             last_indexed_at: None,
             status: Status::default(),
             error_message: None,
+            schema_version: None,
+            tags: Vec::new(),
+            indexed_commit: None,
         }
     }
 
@@ -70,13 +108,38 @@ impl ProjectMetadata {
     pub fn mark_status(mut self, status: Status, error_message: Option<String>) -> Self {
         self.status = status.clone();
         self.error_message = error_message;
-        if status == Status::Indexed {
-            self.last_indexed_at = Some(Utc::now());
-        } else {
-            self.last_indexed_at = None;
+        match status {
+            Status::Indexed => self.last_indexed_at = Some(Utc::now()),
+            // The database is still there and can be queried until it's rebuilt
+            Status::Outdated | Status::Stale => {}
+            _ => self.last_indexed_at = None,
         }
         self
     }
+
+    /// Replaces the tags of the project. Tags are trimmed, and empty and duplicate tags are
+    /// dropped.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = String>) -> Self {
+        let mut tags: Vec<String> = tags
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        self.tags = tags;
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|project_tag| project_tag == tag)
+    }
+
+    /// Whether the project's database was built with a schema other than `schema_version`.
+    /// Projects indexed before the schema version was recorded count as outdated.
+    pub fn is_schema_outdated(&self, schema_version: u32) -> bool {
+        self.schema_version != Some(schema_version)
+    }
 }
 
 /// Metadata for a workspace folder containing multiple projects
@@ -138,6 +201,8 @@ impl WorkspaceFolderMetadata {
         let mut has_error = false;
         let mut has_indexing = false;
         let mut has_reindexing = false;
+        let mut has_outdated = false;
+        let mut has_stale = false;
         let mut all_indexed = true;
 
         for project in self.projects.values() {
@@ -157,6 +222,14 @@ impl WorkspaceFolderMetadata {
                 Status::Pending => {
                     all_indexed = false;
                 }
+                Status::Outdated => {
+                    has_outdated = true;
+                    all_indexed = false;
+                }
+                Status::Stale => {
+                    has_stale = true;
+                    all_indexed = false;
+                }
                 Status::Indexed => {} // keep all_indexed as is
             }
 
@@ -181,6 +254,10 @@ impl WorkspaceFolderMetadata {
             Status::Reindexing
         } else if all_indexed {
             Status::Indexed
+        } else if has_outdated {
+            Status::Outdated
+        } else if has_stale {
+            Status::Stale
         } else {
             Status::Pending
         };
@@ -196,6 +273,9 @@ pub struct Manifest {
     workspace_folders: HashMap<String, WorkspaceFolderMetadata>,
     /// Framework version used for migrations / updating gkg / etc.
     pub framework_version: String,
+    /// Where the Parquet files are written when they are kept outside of the data directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parquet_root: Option<PathBuf>,
 }
 
 impl Manifest {
@@ -203,6 +283,7 @@ impl Manifest {
         Self {
             workspace_folders: HashMap::with_capacity(16),
             framework_version,
+            parquet_root: None,
         }
     }
 
@@ -325,6 +406,55 @@ mod tests {
         assert_eq!(project.error_message, Some("Test error".to_string()));
     }
 
+    #[test]
+    fn test_status_round_trips_through_its_name() {
+        for status in [
+            Status::Indexed,
+            Status::Indexing,
+            Status::Reindexing,
+            Status::Error,
+            Status::Pending,
+            Status::Outdated,
+            Status::Stale,
+        ] {
+            assert_eq!(status.to_string().parse::<Status>(), Ok(status));
+        }
+        assert_eq!("Error".parse::<Status>(), Ok(Status::Error));
+        assert!("failed".parse::<Status>().is_err());
+    }
+
+    #[test]
+    fn test_project_metadata_tags() {
+        let project = ProjectMetadata::new("test_hash".to_string()).with_tags([
+            "payments".to_string(),
+            " team-a ".to_string(),
+            "".to_string(),
+            "payments".to_string(),
+        ]);
+
+        assert_eq!(project.tags, vec!["payments", "team-a"]);
+        assert!(project.has_tag("team-a"));
+        assert!(!project.has_tag("team-b"));
+
+        let project = project.with_tags(Vec::new());
+        assert!(project.tags.is_empty());
+    }
+
+    #[test]
+    fn test_project_metadata_outdated_schema() {
+        let mut project = ProjectMetadata::new("test_hash".to_string());
+        assert!(project.is_schema_outdated(2));
+
+        project = project.mark_status(Status::Indexed, None);
+        project.schema_version = Some(1);
+        assert!(project.is_schema_outdated(2));
+        assert!(!project.is_schema_outdated(1));
+
+        project = project.mark_status(Status::Outdated, None);
+        assert_eq!(project.status, Status::Outdated);
+        assert!(project.last_indexed_at.is_some());
+    }
+
     #[test]
     fn test_workspace_status() {
         let mut workspace = WorkspaceFolderMetadata::new("workspace_hash".to_string());
@@ -421,6 +551,36 @@ mod tests {
         // Should be Indexing, not Indexed
         assert_eq!(workspace.status, Status::Indexing);
         assert_eq!(workspace.last_indexed_at, Some(now));
+
+        // Test 6: Outdated projects make the workspace outdated once nothing is in progress
+        workspace.projects.clear();
+
+        let mut outdated_project = ProjectMetadata::new("outdated_project_hash".to_string());
+        outdated_project.status = Status::Outdated;
+        outdated_project.last_indexed_at = Some(earlier);
+
+        let mut indexed_project3 = ProjectMetadata::new("indexed_project3_hash".to_string());
+        indexed_project3.status = Status::Indexed;
+        indexed_project3.last_indexed_at = Some(now);
+
+        workspace.add_project("/path/to/outdated_project".to_string(), outdated_project);
+        workspace.add_project("/path/to/indexed_project3".to_string(), indexed_project3);
+
+        workspace.update_status_from_projects();
+
+        assert_eq!(workspace.status, Status::Outdated);
+        assert_eq!(workspace.last_indexed_at, Some(now));
+
+        // Test 7: Stale projects keep their timestamp and make an indexed workspace stale
+        workspace.remove_project("/path/to/outdated_project");
+        let stale_project = workspace
+            .get_project_mut("/path/to/indexed_project3")
+            .unwrap();
+        *stale_project = stale_project.clone().mark_status(Status::Stale, None);
+        workspace.update_status_from_projects();
+
+        assert_eq!(workspace.status, Status::Stale);
+        assert_eq!(workspace.last_indexed_at, Some(now));
     }
 
     #[test]
@@ -1,5 +1,6 @@
 use crate::data_directory::DataDirectory;
 use crate::errors::{Result, WorkspaceManagerError};
+use crate::git_state;
 use crate::manifest::{ProjectMetadata, Status, WorkspaceFolderMetadata, generate_path_hash};
 use crate::state_service::LocalStateService;
 use dunce;
@@ -61,11 +62,41 @@ pub struct ProjectInfo {
     pub status: Status,
     pub last_indexed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub error_message: Option<String>,
+    pub schema_version: Option<u32>,
+    pub tags: Vec<String>,
+    pub indexed_commit: Option<String>,
     pub database_path: PathBuf,
     pub parquet_directory: PathBuf,
     pub repository: CoreGitaliskRepository,
 }
 
+impl ProjectInfo {
+    /// Registered, but without a database to query: never indexed, i.e. [`Status::Pending`],
+    /// or its database was deleted since. Projects being indexed don't count.
+    pub fn is_not_indexed(&self) -> bool {
+        is_not_indexed(&self.status, &self.database_path)
+    }
+
+    /// Size on disk of the database, `None` until the project is indexed. Walks the database
+    /// directory, so it's computed on demand rather than whenever a project is looked up.
+    pub fn database_size_bytes(&self) -> Option<u64> {
+        DataDirectory::database_size(&self.database_path)
+    }
+}
+
+fn is_not_indexed(status: &Status, database_path: &Path) -> bool {
+    match status {
+        Status::Pending => true,
+        Status::Indexing | Status::Reindexing => false,
+        _ => !database_path.exists(),
+    }
+}
+
+/// A stale project still has a complete graph, only missing the latest changes
+fn is_queryable(status: &Status) -> bool {
+    matches!(status, Status::Indexed | Status::Stale)
+}
+
 impl WorkspaceManager {
     /// Create a new WorkspaceManager with the provided dependencies
     ///
@@ -85,10 +116,7 @@ impl WorkspaceManager {
     /// the dependencies using system defaults.
     pub fn new_system_default() -> Result<Self> {
         let data_directory = DataDirectory::new_system_default()?;
-        let state_service =
-            LocalStateService::new(&data_directory.manifest_path, FRAMEWORK_VERSION.to_string())?;
-
-        Ok(Self::new(data_directory, state_service))
+        Self::new_with_data_directory(data_directory)
     }
 
     /// Create a new WorkspaceManager with custom data directory
@@ -97,12 +125,40 @@ impl WorkspaceManager {
     /// the dependencies using the provided data directory path.
     pub fn new_with_directory(data_directory_path: PathBuf) -> Result<Self> {
         let data_directory = DataDirectory::new(data_directory_path)?;
+        Self::new_with_data_directory(data_directory)
+    }
+
+    /// Create a new WorkspaceManager with an already configured data directory
+    ///
+    /// Use this when the data directory needs more than a root path, for example
+    /// a separate parquet root. A parquet root is recorded in the manifest, and a data
+    /// directory without one uses the recorded root, so every command finds the Parquet
+    /// files wherever they were written.
+    pub fn new_with_data_directory(mut data_directory: DataDirectory) -> Result<Self> {
         let state_service =
             LocalStateService::new(&data_directory.manifest_path, FRAMEWORK_VERSION.to_string())?;
 
+        let recorded_parquet_root =
+            state_service.with_manifest(|manifest| manifest.parquet_root.clone());
+        match &data_directory.parquet_root {
+            Some(parquet_root) if recorded_parquet_root.as_ref() != Some(parquet_root) => {
+                let parquet_root = parquet_root.clone();
+                state_service.with_manifest_mut(|manifest| {
+                    manifest.parquet_root = Some(parquet_root);
+                })?;
+            }
+            Some(_) => {}
+            None => data_directory.parquet_root = recorded_parquet_root,
+        }
+
         Ok(Self::new(data_directory, state_service))
     }
 
+    /// Where the Parquet files are written when they are kept apart from the databases
+    pub fn parquet_root(&self) -> Option<&Path> {
+        self.data_directory.parquet_root.as_deref()
+    }
+
     fn register_project_internal(
         &self,
         workspace_folder_path: &str,
@@ -129,6 +185,9 @@ impl WorkspaceManager {
             status: project_metadata.status.clone(),
             last_indexed_at: project_metadata.last_indexed_at,
             error_message: project_metadata.error_message.clone(),
+            schema_version: project_metadata.schema_version,
+            tags: project_metadata.tags.clone(),
+            indexed_commit: project_metadata.indexed_commit.clone(),
             database_path,
             parquet_directory,
             repository,
@@ -456,6 +515,43 @@ impl WorkspaceManager {
         project_infos
     }
 
+    /// Calls `f` with the path and manifest entry of every registered project, for callers
+    /// that only scan for a match or compute an aggregate. Unlike [`Self::list_all_projects`]
+    /// it doesn't open the projects' repositories or build a [`ProjectInfo`] for each.
+    ///
+    /// The manifest stays locked while `f` runs, so `f` must not call back into the
+    /// workspace manager.
+    pub fn for_each_project(&self, mut f: impl FnMut(&str, &ProjectMetadata)) {
+        self.state_service.with_manifest(|manifest| {
+            for workspace_metadata in manifest.workspace_folders().values() {
+                for (project_path, project_metadata) in &workspace_metadata.projects {
+                    f(project_path, project_metadata);
+                }
+            }
+        });
+    }
+
+    /// The workspace folder and project paths of the projects that aren't indexed, see
+    /// [`ProjectInfo::is_not_indexed`]. Only the manifest is read and the databases checked for
+    /// existence, the projects' repositories aren't opened.
+    pub fn list_not_indexed_projects(&self) -> Vec<(String, String)> {
+        self.state_service.with_manifest(|manifest| {
+            let mut not_indexed = Vec::new();
+            for (workspace_folder_path, workspace_metadata) in manifest.workspace_folders() {
+                for (project_path, project_metadata) in &workspace_metadata.projects {
+                    let database_path = self.data_directory.project_database_path(
+                        &workspace_metadata.data_directory_name,
+                        &project_metadata.project_hash,
+                    );
+                    if is_not_indexed(&project_metadata.status, &database_path) {
+                        not_indexed.push((workspace_folder_path.clone(), project_path.clone()));
+                    }
+                }
+            }
+            not_indexed
+        })
+    }
+
     pub fn list_projects_in_workspace(&self, workspace_folder_path: &str) -> Vec<ProjectInfo> {
         let workspace_metadata = match self
             .state_service
@@ -487,6 +583,43 @@ impl WorkspaceManager {
         project_infos
     }
 
+    /// The project at `project_path`, when it has a graph that can be queried. A project that
+    /// is pending, being indexed or failed to index is reported as such.
+    pub fn get_queryable_project(&self, project_path: &str) -> Result<ProjectInfo> {
+        let project = self.get_project_for_path(project_path).ok_or_else(|| {
+            WorkspaceManagerError::ProjectNotFound {
+                path: project_path.to_string(),
+            }
+        })?;
+        if !is_queryable(&project.status) {
+            return Err(WorkspaceManagerError::ProjectNotQueryable {
+                path: project.project_path,
+                status: project.status,
+            });
+        }
+        Ok(project)
+    }
+
+    /// The project at `path`, or every project of the workspace folder at `path`, that has a
+    /// graph that can be queried. Fails when none of them has.
+    pub fn get_queryable_projects(&self, path: &str) -> Result<Vec<ProjectInfo>> {
+        if self.get_workspace_folder_info(path).is_none() {
+            return Ok(vec![self.get_queryable_project(path)?]);
+        }
+
+        let projects: Vec<_> = self
+            .list_projects_in_workspace(path)
+            .into_iter()
+            .filter(|project| is_queryable(&project.status))
+            .collect();
+        if projects.is_empty() {
+            return Err(WorkspaceManagerError::NoQueryableProjects {
+                path: path.to_string(),
+            });
+        }
+        Ok(projects)
+    }
+
     pub fn update_project_indexing_status(
         &self,
         workspace_folder_path: &str,
@@ -494,9 +627,38 @@ impl WorkspaceManager {
         status: Status,
         status_error_message: Option<String>,
     ) -> Result<ProjectInfo> {
+        let indexed_commit = match status {
+            Status::Indexed => Some(git_state::head_commit(Path::new(project_path))),
+            _ => None,
+        };
         self.state_service
             .update_project(workspace_folder_path, project_path, |project| {
                 *project = project.clone().mark_status(status, status_error_message);
+                if let Some(indexed_commit) = indexed_commit {
+                    project.indexed_commit = indexed_commit;
+                }
+            })?;
+
+        self.get_project_info(workspace_folder_path, project_path)
+            .ok_or_else(|| {
+                WorkspaceManagerError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Project not found",
+                ))
+            })
+    }
+
+    /// Records `indexed_commit` as the commit the project was indexed at, for a project
+    /// indexed from a commit other than its `HEAD`
+    pub fn set_project_indexed_commit(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        indexed_commit: String,
+    ) -> Result<ProjectInfo> {
+        self.state_service
+            .update_project(workspace_folder_path, project_path, |project| {
+                project.indexed_commit = Some(indexed_commit);
             })?;
 
         self.get_project_info(workspace_folder_path, project_path)
@@ -508,6 +670,135 @@ impl WorkspaceManager {
             })
     }
 
+    /// Replaces the tags of the project, see [`ProjectMetadata::with_tags`]
+    pub fn set_project_tags(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        tags: Vec<String>,
+    ) -> Result<ProjectInfo> {
+        self.state_service
+            .update_project(workspace_folder_path, project_path, |project| {
+                *project = project.clone().with_tags(tags);
+            })?;
+
+        self.get_project_info(workspace_folder_path, project_path)
+            .ok_or_else(|| {
+                WorkspaceManagerError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Project not found",
+                ))
+            })
+    }
+
+    /// Records the graph schema version the project's database was built with
+    pub fn update_project_schema_version(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+        schema_version: u32,
+    ) -> Result<()> {
+        self.state_service
+            .update_project(workspace_folder_path, project_path, |project| {
+                project.schema_version = Some(schema_version);
+            })?;
+        Ok(())
+    }
+
+    /// Marks the indexed projects whose database was built with a graph schema other than
+    /// `schema_version` as [`Status::Outdated`], and returns their `(workspace folder path,
+    /// project path)` pairs. Projects that are already outdated are returned as well.
+    pub fn flag_outdated_projects(&self, schema_version: u32) -> Result<Vec<(String, String)>> {
+        let mut outdated_projects = Vec::new();
+        for (workspace_folder_path, project_path, project_metadata) in
+            self.state_service.get_all_projects()
+        {
+            match project_metadata.status {
+                Status::Outdated => {}
+                Status::Indexed if project_metadata.is_schema_outdated(schema_version) => {
+                    self.state_service.update_project(
+                        &workspace_folder_path,
+                        &project_path,
+                        |project| {
+                            *project = project.clone().mark_status(Status::Outdated, None);
+                        },
+                    )?;
+                }
+                _ => continue,
+            }
+            outdated_projects.push((workspace_folder_path, project_path));
+        }
+
+        outdated_projects.sort();
+        Ok(outdated_projects)
+    }
+
+    /// Marks the project as [`Status::Outdated`] if it's indexed or stale, e.g. when its
+    /// database can no longer be opened, and returns whether it's outdated
+    pub fn mark_project_outdated(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+    ) -> Result<bool> {
+        let mut outdated = false;
+        self.state_service
+            .update_project(workspace_folder_path, project_path, |project| {
+                if matches!(project.status, Status::Indexed | Status::Stale) {
+                    *project = project.clone().mark_status(Status::Outdated, None);
+                }
+                outdated = project.status == Status::Outdated;
+            })?;
+        Ok(outdated)
+    }
+
+    /// Marks the project as [`Status::Stale`] if it's indexed, and returns whether it's stale
+    pub fn mark_project_stale(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+    ) -> Result<bool> {
+        let mut stale = false;
+        self.state_service
+            .update_project(workspace_folder_path, project_path, |project| {
+                if project.status == Status::Indexed {
+                    *project = project.clone().mark_status(Status::Stale, None);
+                }
+                stale = project.status == Status::Stale;
+            })?;
+        Ok(stale)
+    }
+
+    /// Marks the indexed projects whose checkout changed since they were indexed as
+    /// [`Status::Stale`]: another commit is checked out, or files were modified after the
+    /// project was indexed. Returns the `(workspace folder path, project path)` pairs of the
+    /// stale projects, including the ones that were already stale.
+    pub fn flag_stale_projects(&self) -> Result<Vec<(String, String)>> {
+        let mut stale_projects = Vec::new();
+        for (workspace_folder_path, project_path, project_metadata) in
+            self.state_service.get_all_projects()
+        {
+            match (&project_metadata.status, project_metadata.last_indexed_at) {
+                (Status::Stale, _) => {}
+                (Status::Indexed, Some(indexed_at))
+                    if git_state::has_changed_since_indexing(
+                        Path::new(&project_path),
+                        project_metadata.indexed_commit.as_deref(),
+                        indexed_at,
+                    ) =>
+                {
+                    if !self.mark_project_stale(&workspace_folder_path, &project_path)? {
+                        continue;
+                    }
+                }
+                _ => continue,
+            }
+            stale_projects.push((workspace_folder_path, project_path));
+        }
+
+        stale_projects.sort();
+        Ok(stale_projects)
+    }
+
     pub fn remove_workspace_folder(&self, workspace_folder_path: &str) -> Result<bool> {
         let workspace_metadata = match self
             .state_service
@@ -634,12 +925,49 @@ impl WorkspaceManager {
         )
     }
 
+    /// Where the cross-project links of a workspace folder are stored, `None` when the
+    /// workspace folder isn't registered
+    pub fn cross_project_links_path(&self, workspace_folder_path: &str) -> Option<PathBuf> {
+        self.state_service
+            .get_workspace_folder(workspace_folder_path)
+            .map(|workspace_metadata| {
+                self.data_directory
+                    .cross_project_links_path(&workspace_metadata.data_directory_name)
+            })
+    }
+
+    /// Where the definition snapshots of a project are stored, `None` when the project
+    /// isn't registered
+    pub fn project_snapshots_directory(
+        &self,
+        workspace_folder_path: &str,
+        project_path: &str,
+    ) -> Option<PathBuf> {
+        let workspace_metadata = self
+            .state_service
+            .get_workspace_folder(workspace_folder_path)?;
+        let project_metadata = workspace_metadata.get_project(project_path)?;
+        Some(self.data_directory.project_snapshots_directory(
+            &workspace_metadata.data_directory_name,
+            &project_metadata.project_hash,
+        ))
+    }
+
     pub fn get_data_directory_info(
         &self,
     ) -> Result<crate::data_directory::WorkspaceFolderDataDirectoryInfo> {
         self.data_directory.get_info()
     }
 
+    pub fn job_queue_path(&self) -> PathBuf {
+        self.data_directory.job_queue_path()
+    }
+
+    /// Bytes freed by [`WorkspaceManager::clean`]
+    pub fn total_disk_usage(&self) -> Result<u64> {
+        self.data_directory.total_disk_usage()
+    }
+
     pub fn get_framework_version(&self) -> Result<String> {
         Ok(self
             .state_service
@@ -672,6 +1000,17 @@ impl WorkspaceManager {
             );
         }
 
+        if let Some(parquet_workspace_folders_dir) =
+            self.data_directory.parquet_workspace_folders_dir()
+            && parquet_workspace_folders_dir.exists()
+        {
+            fs::remove_dir_all(&parquet_workspace_folders_dir)?;
+            info!(
+                "Removed parquet workspace folders directory: {}",
+                parquet_workspace_folders_dir.display()
+            );
+        }
+
         let manifest_path = self.state_service.manifest_path().to_path_buf();
         if manifest_path.exists() {
             fs::remove_file(&manifest_path)?;
@@ -755,6 +1094,28 @@ mod tests {
         assert_eq!(info.workspace_folder_count, 0);
     }
 
+    #[test]
+    fn test_parquet_root_is_recorded_in_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let parquet_root = TempDir::new().unwrap();
+
+        let data_directory = DataDirectory::new(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_parquet_root(parquet_root.path().to_path_buf());
+        let manager = WorkspaceManager::new_with_data_directory(data_directory).unwrap();
+        assert_eq!(manager.parquet_root(), Some(parquet_root.path()));
+
+        // Later commands without a parquet root still find the Parquet files
+        let manager = WorkspaceManager::new_with_directory(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(manager.parquet_root(), Some(parquet_root.path()));
+        assert!(
+            manager
+                .data_directory
+                .project_parquet_directory("workspace", "project")
+                .starts_with(parquet_root.path())
+        );
+    }
+
     #[test]
     fn test_discover_and_register_workspace() {
         let temp_dir = TempDir::new().unwrap();
@@ -886,6 +1247,32 @@ mod tests {
         let all_projects = manager.list_all_projects();
         assert_eq!(all_projects.len(), 1);
 
+        let tagged_project = manager
+            .set_project_tags(
+                &result.workspace_folder_path,
+                &all_projects[0].project_path,
+                vec!["team-a".to_string(), "payments".to_string()],
+            )
+            .unwrap();
+        assert_eq!(tagged_project.tags, vec!["payments", "team-a"]);
+        assert_eq!(
+            manager
+                .get_project_for_path(&all_projects[0].project_path)
+                .unwrap()
+                .tags,
+            vec!["payments", "team-a"]
+        );
+
+        let mut visited_project_paths = Vec::new();
+        manager.for_each_project(|project_path, project_metadata| {
+            visited_project_paths.push(project_path.to_string());
+            assert_eq!(project_metadata.project_hash, all_projects[0].project_hash);
+        });
+        assert_eq!(
+            visited_project_paths,
+            vec![all_projects[0].project_path.clone()]
+        );
+
         let workspace_projects = manager.list_projects_in_workspace(&result.workspace_folder_path);
         assert_eq!(workspace_projects.len(), 1);
     }
@@ -1265,4 +1652,275 @@ mod tests {
         );
         assert_eq!(info_two.unwrap().project_path, project_path);
     }
+
+    #[test]
+    fn test_flag_outdated_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder_path = temp_dir.path().join("test_workspace");
+        fs::create_dir_all(&workspace_folder_path).unwrap();
+        create_test_git_repo(&workspace_folder_path.join("current_project"));
+        create_test_git_repo(&workspace_folder_path.join("old_project"));
+        create_test_git_repo(&workspace_folder_path.join("pending_project"));
+
+        let data_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new_with_directory(data_dir.path().to_path_buf()).unwrap();
+        let workspace_path = manager
+            .register_workspace_folder(&workspace_folder_path)
+            .unwrap()
+            .workspace_folder_path;
+        let project_path = |name: &str| {
+            manager
+                .list_projects_in_workspace(&workspace_path)
+                .into_iter()
+                .find(|project| project.project_path.ends_with(name))
+                .unwrap()
+                .project_path
+        };
+        let current_project = project_path("current_project");
+        let old_project = project_path("old_project");
+
+        for project in [&current_project, &old_project] {
+            manager
+                .update_project_indexing_status(&workspace_path, project, Status::Indexed, None)
+                .unwrap();
+        }
+        manager
+            .update_project_schema_version(&workspace_path, &current_project, 2)
+            .unwrap();
+        manager
+            .update_project_schema_version(&workspace_path, &old_project, 1)
+            .unwrap();
+
+        let outdated_projects = manager.flag_outdated_projects(2).unwrap();
+        assert_eq!(
+            outdated_projects,
+            vec![(workspace_path.clone(), old_project.clone())]
+        );
+
+        let old_project_info = manager
+            .get_project_info(&workspace_path, &old_project)
+            .unwrap();
+        assert_eq!(old_project_info.status, Status::Outdated);
+        assert_eq!(old_project_info.schema_version, Some(1));
+        assert!(old_project_info.last_indexed_at.is_some());
+        let current_project_info = manager
+            .get_project_info(&workspace_path, &current_project)
+            .unwrap();
+        assert_eq!(current_project_info.status, Status::Indexed);
+
+        // Flagging again still reports the outdated project
+        assert_eq!(manager.flag_outdated_projects(2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_mark_project_outdated() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder_path = temp_dir.path().join("test_workspace");
+        fs::create_dir_all(&workspace_folder_path).unwrap();
+        create_test_git_repo(&workspace_folder_path.join("project"));
+
+        let data_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new_with_directory(data_dir.path().to_path_buf()).unwrap();
+        let workspace_path = manager
+            .register_workspace_folder(&workspace_folder_path)
+            .unwrap()
+            .workspace_folder_path;
+        let project_path = manager.list_projects_in_workspace(&workspace_path)[0]
+            .project_path
+            .clone();
+
+        // A pending project has nothing to reindex
+        assert!(
+            !manager
+                .mark_project_outdated(&workspace_path, &project_path)
+                .unwrap()
+        );
+
+        manager
+            .update_project_indexing_status(&workspace_path, &project_path, Status::Indexed, None)
+            .unwrap();
+        assert!(
+            manager
+                .mark_project_outdated(&workspace_path, &project_path)
+                .unwrap()
+        );
+        let project_info = manager
+            .get_project_info(&workspace_path, &project_path)
+            .unwrap();
+        assert_eq!(project_info.status, Status::Outdated);
+        assert!(project_info.last_indexed_at.is_some());
+    }
+
+    #[test]
+    fn test_project_without_database_is_not_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder_path = temp_dir.path().join("test_workspace");
+        fs::create_dir_all(&workspace_folder_path).unwrap();
+        create_test_git_repo(&workspace_folder_path.join("project"));
+
+        let data_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new_with_directory(data_dir.path().to_path_buf()).unwrap();
+        let workspace_path = manager
+            .register_workspace_folder(&workspace_folder_path)
+            .unwrap()
+            .workspace_folder_path;
+        let project = manager
+            .list_projects_in_workspace(&workspace_path)
+            .remove(0);
+        assert!(project.is_not_indexed());
+
+        fs::write(&project.database_path, b"graph").unwrap();
+        manager
+            .update_project_indexing_status(
+                &workspace_path,
+                &project.project_path,
+                Status::Indexed,
+                None,
+            )
+            .unwrap();
+        let project = manager
+            .list_projects_in_workspace(&workspace_path)
+            .remove(0);
+        assert!(!project.is_not_indexed());
+        assert!(manager.list_not_indexed_projects().is_empty());
+
+        fs::remove_file(&project.database_path).unwrap();
+        let project = manager
+            .list_projects_in_workspace(&workspace_path)
+            .remove(0);
+        assert!(project.is_not_indexed());
+        assert_eq!(
+            manager.list_not_indexed_projects(),
+            vec![(workspace_path.clone(), project.project_path.clone())]
+        );
+    }
+
+    #[test]
+    fn test_flag_stale_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder_path = temp_dir.path().join("test_workspace");
+        fs::create_dir_all(&workspace_folder_path).unwrap();
+        create_test_git_repo(&workspace_folder_path.join("clean_project"));
+        create_test_git_repo(&workspace_folder_path.join("edited_project"));
+        create_test_git_repo(&workspace_folder_path.join("committed_project"));
+
+        let data_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new_with_directory(data_dir.path().to_path_buf()).unwrap();
+        let workspace_path = manager
+            .register_workspace_folder(&workspace_folder_path)
+            .unwrap()
+            .workspace_folder_path;
+        let projects = manager.list_projects_in_workspace(&workspace_path);
+        for project in &projects {
+            let project_info = manager
+                .update_project_indexing_status(
+                    &workspace_path,
+                    &project.project_path,
+                    Status::Indexed,
+                    None,
+                )
+                .unwrap();
+            assert!(project_info.indexed_commit.is_some());
+        }
+        let project_path = |name: &str| {
+            projects
+                .iter()
+                .find(|project| project.project_path.ends_with(name))
+                .unwrap()
+                .project_path
+                .clone()
+        };
+        let edited_project = project_path("edited_project");
+        let committed_project = project_path("committed_project");
+        assert!(manager.flag_stale_projects().unwrap().is_empty());
+
+        fs::write(
+            Path::new(&edited_project).join("main.rb"),
+            "puts 'Hello, stale World!'",
+        )
+        .unwrap();
+        std::process::Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "Next commit"])
+            .current_dir(&committed_project)
+            .output()
+            .unwrap();
+
+        let mut expected = vec![
+            (workspace_path.clone(), committed_project.clone()),
+            (workspace_path.clone(), edited_project.clone()),
+        ];
+        expected.sort();
+        assert_eq!(manager.flag_stale_projects().unwrap(), expected);
+
+        let edited_project_info = manager
+            .get_project_info(&workspace_path, &edited_project)
+            .unwrap();
+        assert_eq!(edited_project_info.status, Status::Stale);
+        assert!(edited_project_info.last_indexed_at.is_some());
+        assert_eq!(
+            manager
+                .get_workspace_folder_info(&workspace_path)
+                .unwrap()
+                .status,
+            Status::Stale
+        );
+
+        // Reindexing the project clears the stale status
+        manager
+            .update_project_indexing_status(&workspace_path, &edited_project, Status::Indexed, None)
+            .unwrap();
+        assert_eq!(manager.flag_stale_projects().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_queryable_projects_must_be_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_folder_path = temp_dir.path().join("test_workspace");
+        fs::create_dir_all(&workspace_folder_path).unwrap();
+        create_test_git_repo(&workspace_folder_path.join("billing"));
+        create_test_git_repo(&workspace_folder_path.join("shipping"));
+
+        let data_dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new_with_directory(data_dir.path().to_path_buf()).unwrap();
+        let workspace_path = manager
+            .register_workspace_folder(&workspace_folder_path)
+            .unwrap()
+            .workspace_folder_path;
+        let projects = manager.list_projects_in_workspace(&workspace_path);
+
+        assert!(matches!(
+            manager.get_queryable_project(&projects[0].project_path),
+            Err(WorkspaceManagerError::ProjectNotQueryable {
+                status: Status::Pending,
+                ..
+            })
+        ));
+        assert!(matches!(
+            manager.get_queryable_project("/no/such/project"),
+            Err(WorkspaceManagerError::ProjectNotFound { .. })
+        ));
+        assert!(matches!(
+            manager.get_queryable_projects(&workspace_path),
+            Err(WorkspaceManagerError::NoQueryableProjects { .. })
+        ));
+
+        manager
+            .update_project_indexing_status(
+                &workspace_path,
+                &projects[0].project_path,
+                Status::Stale,
+                None,
+            )
+            .unwrap();
+        let queryable = manager.get_queryable_projects(&workspace_path).unwrap();
+        assert_eq!(queryable.len(), 1);
+        assert_eq!(queryable[0].project_path, projects[0].project_path);
+        assert_eq!(
+            manager
+                .get_queryable_projects(&projects[0].project_path)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
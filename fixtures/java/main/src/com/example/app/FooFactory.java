@@ -4,4 +4,7 @@ public class FooFactory {
     public Foo createFoo() {
         return new Foo();
     }
+
+    public void configure(Executor executor) {
+    }
 }
@@ -0,0 +1,8 @@
+package com.example.filter;
+
+public abstract class LengthMatcher implements Matcher {
+    // Overloads Matcher.matches, its subclasses still have to override it
+    public boolean matches(String input, int maxLength) {
+        return input.length() <= maxLength;
+    }
+}
@@ -0,0 +1,5 @@
+package com.example.filter;
+
+public interface Matcher {
+    boolean matches(String input);
+}
@@ -2,6 +2,8 @@ package com.example.entites
 
 interface Person {
     fun getName(): String
+
+    fun greet(greeting: String): String = greeting
 }
 
 class User : Person {
@@ -14,4 +16,7 @@ class Admin : Person {
     override fun getName(): String {
         return "Admin"
     }
+
+    // Overloads Person.greet
+    fun greet(greeting: String, times: Int): String = greeting
 }
\ No newline at end of file
@@ -212,6 +212,10 @@ export class ApiClient extends HttpClient {
     await this.#sseConnection.connect(endpointPaths.events, {
       ...callbacks,
       onEvent: (event: EventSourceMessage) => {
+        if (event.event === 'gkg-resync') {
+          callbacks.onResync?.();
+          return;
+        }
         if (event.data) {
           const gkgEvent = JSON.parse(event.data) as GkgEvent;
           callbacks.onEvent?.(gkgEvent);
@@ -14,6 +14,8 @@ export interface BaseEventCallbacks {
 
 export interface EventBusCallbacks extends BaseEventCallbacks {
   onEvent?: (event: GkgEvent) => void;
+  // Called when the server dropped events for this client, its state must be refetched
+  onResync?: () => void;
 }
 
 export interface WorkspaceIndexCallbacks extends BaseEventCallbacks {
@@ -130,6 +130,11 @@ export const useWorkspaceStream = () => {
             void queryClient.invalidateQueries({ queryKey: ['workspaces'] });
           }
         },
+        onResync: () => {
+          // Some events were missed, so nothing fetched so far can be trusted
+          // eslint-disable-next-line no-void
+          void queryClient.invalidateQueries();
+        },
         onError: () => {
           isConnected.value = false;
           // Don't re-throw the error to prevent it from reaching the error boundary
@@ -2,9 +2,28 @@
 import type { TSProjectInfo } from "./project_info";
 import type { TSWorkspaceFolderInfo } from "./workspace_folder";
 
-export type ApiContract = { info: InfoEndpointDef, workspace_index: WorkspaceIndexEndpointDef, workspace_list: WorkspaceListEndpointDef, workspace_delete: WorkspaceDeleteEndpointDef, index: WorkspaceIndexEndpointDef, events: EventsEndpointDef, graph_initial: GraphInitialEndpointDef, graph_neighbors: GraphNeighborsEndpointDef, graph_search: GraphSearchEndpointDef, graph_stats: GraphStatsEndpointDef, };
+export type ApiContract = { info: InfoEndpointDef, workspace_index: WorkspaceIndexEndpointDef, workspace_list: WorkspaceListEndpointDef, workspace_delete: WorkspaceDeleteEndpointDef, index: WorkspaceIndexEndpointDef, events: EventsEndpointDef, job_list: JobListEndpointDef, graph_initial: GraphInitialEndpointDef, graph_neighbors: GraphNeighborsEndpointDef, graph_search: GraphSearchEndpointDef, graph_stats: GraphStatsEndpointDef, graph_definitions: GraphDefinitionsEndpointDef, graph_directory_counts: GraphDirectoryCountsEndpointDef, };
 
-export type DefinitionNodeProperties = { path: string, fqn: string, definition_type: string, start_line: number, primary_start_byte: bigint, primary_end_byte: bigint, total_locations: number, };
+export type ApiError = { code: string, message: string, details: unknown, };
+
+export type DefinitionNodeProperties = { path: string, fqn: string, 
+/**
+ * Parts of `fqn` split by the separators of the definition's language, e.g.
+ * `["Billing", "Invoice", "total"]` for the Ruby `Billing::Invoice#total`
+ */
+fqn_parts: Array<string>, definition_type: string, 
+/**
+ * Language independent category of `definition_type`, e.g. `class` or `method`
+ */
+definition_category: string, start_line: number, primary_start_byte: bigint, primary_end_byte: bigint, total_locations: number, 
+/**
+ * Number of calls into and out of the definition
+ */
+call_degree: number, 
+/**
+ * Betweenness centrality in the call graph, 0 unless computed with `--call-betweenness`
+ */
+call_betweenness: number, };
 
 export type DirectoryNodeProperties = { path: string, absolute_path: string, repository_name: string, };
 
@@ -16,25 +35,110 @@ export type EventsResponses = Record<string, never>;
 
 export type FileNodeProperties = { path: string, absolute_path: string, repository_name: string, language: string, extension: string, };
 
+export type GraphDefinitionSummary = { id: string, name: string, fqn: string, 
+/**
+ * Parts of `fqn` split by the separators of the definition's language
+ */
+fqn_parts: Array<string>, definition_type: string, definition_category: string, start_line: number, end_line: number, };
+
+export type GraphDefinitionsEndpointDef = { method: HttpMethod, path: "/api/graph/definitions/{workspace_folder_path}/{project_path}", path_request: GraphDefinitionsPathRequest, body_request: EmptyRequest, query_request: GraphDefinitionsQueryRequest, responses: GraphDefinitionsSuccessResponse, };
+
+export type GraphDefinitionsPathRequest = { workspace_folder_path: string, project_path: string, };
+
+export type GraphDefinitionsQueryRequest = { 
+/**
+ * Directory relative to the project root, e.g. `src/auth`. Empty for the whole project.
+ */
+path_prefix: string | null, 
+/**
+ * Page number, starting from 1
+ */
+page: number | null, page_size: number | null, };
+
+export type GraphDefinitionsResponses = { "200": GraphDefinitionsSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
+
+export type GraphDefinitionsSuccessResponse = { files: Array<GraphFileDefinitions>, page: number, page_size: number, 
+/**
+ * Whether a next page has more definitions under the prefix
+ */
+has_more: boolean, project_info: TSProjectInfo, };
+
+export type GraphDirectoryCountsEndpointDef = { method: HttpMethod, path: "/api/graph/directory-counts/{workspace_folder_path}/{project_path}", path_request: GraphDirectoryCountsPathRequest, body_request: EmptyRequest, query_request: EmptyRequest, responses: GraphDirectoryCountsSuccessResponse, };
+
+export type GraphDirectoryCountsPathRequest = { workspace_folder_path: string, project_path: string, };
+
+export type GraphDirectoryCountsResponses = { "200": GraphDirectoryCountsSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
+
+export type GraphDirectoryCountsSuccessResponse = { 
+/**
+ * Directory path relative to the project root -> number of definitions in the files
+ * under it, nested directories included
+ */
+definition_counts: { [key in string]?: number }, project_info: TSProjectInfo, };
+
+export type GraphFileDefinitions = { path: string, definitions: Array<GraphDefinitionSummary>, };
+
 export type GraphInitialEndpointDef = { method: HttpMethod, path: "/api/graph/initial/{workspace_folder_path}/{project_path}", path_request: GraphInitialPathRequest, body_request: EmptyRequest, query_request: GraphInitialQueryRequest, responses: GraphInitialSuccessResponse, };
 
 export type GraphInitialPathRequest = { workspace_folder_path: string, project_path: string, };
 
-export type GraphInitialQueryRequest = { directory_limit: number | null, file_limit: number | null, definition_limit: number | null, imported_symbol_limit: number | null, };
+export type GraphInitialQueryRequest = { directory_limit: number | null, file_limit: number | null, definition_limit: number | null, imported_symbol_limit: number | null, 
+/**
+ * FQN of a definition to center the initial graph on
+ */
+focus_fqn: string | null, 
+/**
+ * File to center the initial graph on, relative to the project root or absolute.
+ * Combined with `focus_fqn` it disambiguates definitions sharing the same FQN.
+ */
+focus_file: string | null, 
+/**
+ * Maximum number of nodes in the response, defaults to 1000
+ */
+max_nodes: number | null, 
+/**
+ * Maximum number of relationships in the response, defaults to 2000
+ */
+max_edges: number | null, };
 
-export type GraphInitialResponses = { "200": GraphInitialSuccessResponse | null, "404": StatusResponse | null, "400": StatusResponse | null, "500": StatusResponse | null, };
+export type GraphInitialResponses = { "200": GraphInitialSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
 
-export type GraphInitialSuccessResponse = { nodes: Array<TypedGraphNode>, relationships: Array<GraphRelationship>, project_info: TSProjectInfo, };
+export type GraphInitialSuccessResponse = { nodes: Array<TypedGraphNode>, relationships: Array<GraphRelationship>, 
+/**
+ * Id of the node the graph is centered on, when a focus was requested and found
+ */
+focus_node_id: string | null, 
+/**
+ * Whether the requested focus node was not found and the default view was returned
+ */
+focus_fallback: boolean, 
+/**
+ * Whether nodes or relationships were left out to stay within `max_nodes` and `max_edges`
+ */
+truncated: boolean, project_info: TSProjectInfo, };
 
 export type GraphNeighborsEndpointDef = { method: HttpMethod, path: "/api/graph/neighbors/{workspace_folder_path}/{project_path}/{node_type}/{node_id}", path_request: GraphNeighborsPathRequest, body_request: EmptyRequest, query_request: GraphNeighborsQueryRequest, responses: GraphNeighborsSuccessResponse, };
 
 export type GraphNeighborsPathRequest = { workspace_folder_path: string, project_path: string, node_type: string, node_id: string, };
 
-export type GraphNeighborsQueryRequest = { limit: number | null, };
+export type GraphNeighborsQueryRequest = { limit: number | null, 
+/**
+ * Only follows the relationships of this type, e.g. `INSTANTIATES`
+ */
+relationship_type: string | null, 
+/**
+ * `true` returns the query and its plan alongside the neighbors, `only` returns them
+ * without fetching the neighbors
+ */
+explain: string | null, };
 
-export type GraphNeighborsResponses = { "200": GraphNeighborsSuccessResponse | null, "404": StatusResponse | null, "400": StatusResponse | null, "500": StatusResponse | null, };
+export type GraphNeighborsResponses = { "200": GraphNeighborsSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
 
-export type GraphNeighborsSuccessResponse = { nodes: Array<TypedGraphNode>, relationships: Array<GraphRelationship>, project_info: TSProjectInfo, };
+export type GraphNeighborsSuccessResponse = { nodes: Array<TypedGraphNode>, relationships: Array<GraphRelationship>, project_info: TSProjectInfo, 
+/**
+ * The query and its plan, only filled when `explain` is requested
+ */
+explain: QueryExplanation | null, };
 
 export type GraphNodeCountsResponse = { directory_count: number, file_count: number, definition_count: number, imported_symbol_count: number, };
 
@@ -44,19 +148,61 @@ export type GraphRelationshipCountsResponse = { directory_relationships: number,
 
 export type GraphSearchEndpointDef = { method: HttpMethod, path: "/api/graph/search/{workspace_folder_path}/{project_path}", path_request: GraphSearchPathRequest, body_request: EmptyRequest, query_request: GraphSearchQueryRequest, responses: GraphSearchSuccessResponse, };
 
+export type GraphSearchHit = { project_path: string, node: TypedGraphNode, };
+
 export type GraphSearchPathRequest = { workspace_folder_path: string, project_path: string, };
 
-export type GraphSearchQueryRequest = { search_term: string, limit: number | null, };
+export type GraphSearchQueryRequest = { search_term: string, limit: number | null, 
+/**
+ * `call_degree` lists the most called definitions first. Defaults to sorting by name.
+ */
+sort_by: string | null, 
+/**
+ * `true` matches `search_term` as a case sensitive regular expression, e.g.
+ * `^Foo::.*_test$`, instead of a case insensitive substring
+ */
+regex: boolean | null, 
+/**
+ * `project` searches the requested project only, `workspace` every project of its
+ * workspace folder and `all` every registered project. Defaults to `project`.
+ */
+scope: string | null, 
+/**
+ * Only matches the definitions with a relationship of this type to another definition,
+ * e.g. `INSTANTIATES` for the classes that are constructed somewhere
+ */
+relationship_type: string | null, 
+/**
+ * `true` returns the query and its plan alongside the matches, `only` returns them
+ * without searching. The plan is the one of the requested project.
+ */
+explain: string | null, };
 
-export type GraphSearchResponses = { "200": GraphSearchSuccessResponse | null, "404": StatusResponse | null, "400": StatusResponse | null, "500": StatusResponse | null, };
+export type GraphSearchResponses = { "200": GraphSearchSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
 
-export type GraphSearchSuccessResponse = { nodes: Array<TypedGraphNode>, project_info: TSProjectInfo, };
+export type GraphSearchSuccessResponse = { 
+/**
+ * Matches in the requested project
+ */
+nodes: Array<TypedGraphNode>, project_info: TSProjectInfo, 
+/**
+ * Matches in every searched project, only filled for the `workspace` and `all` scopes
+ */
+hits: Array<GraphSearchHit>, 
+/**
+ * Whether more matches were found than `limit` across the searched projects
+ */
+truncated: boolean, 
+/**
+ * The query and its plan, only filled when `explain` is requested
+ */
+explain: QueryExplanation | null, };
 
 export type GraphStatsEndpointDef = { method: HttpMethod, path: "/api/graph/stats/{workspace_folder_path}/{project_path}", path_request: GraphStatsPathRequest, body_request: EmptyRequest, query_request: EmptyRequest, responses: GraphStatsSuccessResponse, };
 
 export type GraphStatsPathRequest = { workspace_folder_path: string, project_path: string, };
 
-export type GraphStatsResponses = { "200": GraphStatsSuccessResponse | null, "404": StatusResponse | null, "400": StatusResponse | null, "500": StatusResponse | null, };
+export type GraphStatsResponses = { "200": GraphStatsSuccessResponse | null, "404": ApiError | null, "400": ApiError | null, "500": ApiError | null, };
 
 export type GraphStatsSuccessResponse = { total_nodes: number, total_relationships: number, node_counts: GraphNodeCountsResponse, relationship_counts: GraphRelationshipCountsResponse, project_info: TSProjectInfo, };
 
@@ -74,10 +220,16 @@ export type InfoResponses = { "200": ServerInfoResponse, };
  * Each job variant represents a different type of work that can be performed.
  * Jobs are routed to workspace-specific queues for sequential processing.
  */
-export type Job = { "type": "IndexWorkspaceFolder", "data": { workspace_folder_path: string, priority: JobPriority, } } | { "type": "ReindexWorkspaceFolderWithWatchedFiles", "data": { workspace_folder_path: string, workspace_changes: Array<string>, priority: JobPriority, } } | { "type": "ReindexProjectFolderWithWatchedFiles", "data": { workspace_folder_path: string, project_folder_path: string, project_changes: Array<string>, priority: JobPriority, } };
+export type Job = { "type": "IndexWorkspaceFolder", "data": { workspace_folder_path: string, priority: JobPriority, } } | { "type": "ReindexWorkspaceFolderWithWatchedFiles", "data": { workspace_folder_path: string, workspace_changes: Array<string>, priority: JobPriority, } } | { "type": "ReindexProjectFolderWithWatchedFiles", "data": { workspace_folder_path: string, project_folder_path: string, project_changes: Array<string>, priority: JobPriority, } } | { "type": "IndexProjectFolder", "data": { workspace_folder_path: string, project_folder_path: string, priority: JobPriority, } };
 
 export type JobInfo = { id: string, job: Job, created_at: string, started_at: string | null, completed_at: string | null, status: JobStatus, error: string | null, };
 
+export type JobListEndpointDef = { method: HttpMethod, path: "/api/jobs", path_request: EmptyRequest, body_request: EmptyRequest, query_request: EmptyRequest, responses: JobListResponses, };
+
+export type JobListResponses = { "200": JobListSuccessResponse, };
+
+export type JobListSuccessResponse = { jobs: Array<JobInfo>, };
+
 /**
  * Priority levels for job processing.
  *
@@ -88,9 +240,20 @@ export type JobPriority = "Low" | "Normal" | "High";
 
 export type JobStatus = "Pending" | "Running" | "Completed" | "Failed" | "Cancelled";
 
-export type ServerInfoResponse = { port: number, version: string, };
+/**
+ * The query run by an endpoint, returned when it's called with `explain`
+ */
+export type QueryExplanation = { query: string, 
+/**
+ * Parameters bound to the query, as a JSON object
+ */
+params: string, 
+/**
+ * Kuzu's physical plan of the query
+ */
+plan: string, };
 
-export type StatusResponse = { status: string, };
+export type ServerInfoResponse = { port: number, version: string, };
 
 export type TypedGraphNode = { "node_type": "DirectoryNode", id: string, node_id: string, label: string, properties: DirectoryNodeProperties, } | { "node_type": "FileNode", id: string, node_id: string, label: string, properties: FileNodeProperties, } | { "node_type": "DefinitionNode", id: string, node_id: string, label: string, properties: DefinitionNodeProperties, } | { "node_type": "ImportedSymbolNode", id: string, node_id: string, label: string, properties: ImportedSymbolNodeProperties, };
 
@@ -98,7 +261,7 @@ export type WorkspaceDeleteBodyRequest = { workspace_folder_path: string, };
 
 export type WorkspaceDeleteEndpointDef = { method: HttpMethod, path: "/api/workspace/delete", path_request: EmptyRequest, body_request: WorkspaceDeleteBodyRequest, query_request: EmptyRequest, responses: WorkspaceDeleteResponses, };
 
-export type WorkspaceDeleteResponses = { "200": WorkspaceDeleteSuccessResponse, "400": StatusResponse, "404": StatusResponse, "500": StatusResponse, };
+export type WorkspaceDeleteResponses = { "200": WorkspaceDeleteSuccessResponse, "400": ApiError, "404": ApiError, "500": ApiError, };
 
 export type WorkspaceDeleteSuccessResponse = { workspace_folder_path: string, removed: boolean, };
 
@@ -106,11 +269,11 @@ export type WorkspaceIndexBodyRequest = { workspace_folder_path: string, };
 
 export type WorkspaceIndexEndpointDef = { method: HttpMethod, path: "/api/workspace/index", path_request: EmptyRequest, body_request: WorkspaceIndexBodyRequest, query_request: EmptyRequest, responses: WorkspaceIndexResponses, };
 
-export type WorkspaceIndexResponses = { "200": TSWorkspaceFolderInfo, "400": StatusResponse, "500": StatusResponse, };
+export type WorkspaceIndexResponses = { "200": TSWorkspaceFolderInfo, "400": ApiError, "500": ApiError, };
 
 export type WorkspaceListEndpointDef = { method: HttpMethod, path: "/api/workspace/list", path_request: EmptyRequest, body_request: EmptyRequest, query_request: EmptyRequest, responses: WorkspaceListResponses, };
 
-export type WorkspaceListResponses = { "200": WorkspaceListSuccessResponse, "500": StatusResponse, };
+export type WorkspaceListResponses = { "200": WorkspaceListSuccessResponse, "500": ApiError, };
 
 export type WorkspaceListSuccessResponse = { workspaces: Array<WorkspaceWithProjects>, };
 
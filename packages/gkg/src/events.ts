@@ -2,7 +2,15 @@
 import type { TSProjectInfo } from "./project_info";
 import type { TSWorkspaceFolderInfo } from "./workspace_folder";
 
-export type GkgEvent = { "type": "WorkspaceIndexing", "payload": WorkspaceIndexingEvent } | { "type": "ProjectIndexing", "payload": ProjectIndexingEvent } | { "type": "ProjectReindexing", "payload": ProjectReindexingEvent } | { "type": "WorkspaceReindexing", "payload": WorkspaceReindexingEvent };
+export type GkgEvent = { "type": "WorkspaceIndexing", "payload": WorkspaceIndexingEvent } | { "type": "ProjectIndexing", "payload": ProjectIndexingEvent } | { "type": "ProjectReindexing", "payload": ProjectReindexingEvent } | { "type": "WorkspaceReindexing", "payload": WorkspaceReindexingEvent } | { "type": "Watcher", "payload": WatcherEvent } | { "type": "Graph", "payload": GraphEvent };
+
+export type GraphEvent = { "status": "Updated" } & GraphUpdated;
+
+/**
+ * Sent once an index run wrote to a project's database, whether or not it succeeded.
+ * Anything derived from the database before, such as cached query results, is out of date.
+ */
+export type GraphUpdated = { workspace_folder_path: string, project_path: string, database_path: string, updated_at: string, };
 
 export type ProjectIndexingCompleted = { project_info: TSProjectInfo, completed_at: string, };
 
@@ -20,6 +28,12 @@ export type ProjectReindexingFailed = { project_info: TSProjectInfo, error: stri
 
 export type ProjectReindexingStarted = { project_info: TSProjectInfo, started_at: string, };
 
+export type WatcherEvent = { "status": "Lost" } & WatcherLost | { "status": "Recovered" } & WatcherRecovered;
+
+export type WatcherLost = { workspace_folder_path: string, project_path: string, error: string, lost_at: string, };
+
+export type WatcherRecovered = { workspace_folder_path: string, project_path: string, recovered_at: string, };
+
 export type WorkspaceIndexingCompleted = { workspace_folder_info: TSWorkspaceFolderInfo, projects_indexed: Array<string>, completed_at: string, };
 
 export type WorkspaceIndexingEvent = { "status": "Started" } & WorkspaceIndexingStarted | { "status": "Completed" } & WorkspaceIndexingCompleted | { "status": "Failed" } & WorkspaceIndexingFailed;